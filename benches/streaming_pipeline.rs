@@ -0,0 +1,186 @@
+//! Throughput of the OpenCode streaming pipeline, in events per second:
+//! SSE bytes parsed into events, parts accumulated into a transcript, and
+//! the transcript and chat output rendered.
+//!
+//! Run with `cargo bench --bench streaming_pipeline`. Each workload is
+//! measured per stage and end to end (`pipeline`), so a change can be traced
+//! to the stage it affects: `parse` turns bytes into events, `accumulate`
+//! does what the worker and channel do per event (transcript assembly, busy
+//! status line, todo checklist), and `render` turns the result into
+//! transcript and chat text. Compare against a saved baseline with
+//! `--save-baseline main` and `--baseline main`.
+
+use spacebot::opencode::fixtures::{FixtureConfig, SseFixture, generate};
+use spacebot::testkit::{Replay, parse_sse_chunks, replay};
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use serde_json::{Value, json};
+
+use std::hint::black_box;
+
+/// Bytes per read, roughly what one network read of `/event` delivers.
+const CHUNK_BYTES: usize = 4096;
+
+const SESSION_ID: &str = "ses_bench";
+
+/// A streamed workload: its SSE body and the session it belongs to.
+struct Workload {
+    name: &'static str,
+    body: Vec<u8>,
+    session_id: String,
+    events: usize,
+}
+
+impl Workload {
+    fn new(name: &'static str, fixture: SseFixture, session_id: String) -> Self {
+        Self {
+            name,
+            body: fixture.to_sse_string().into_bytes(),
+            session_id,
+            events: fixture.events.len(),
+        }
+    }
+}
+
+fn part_event(part: Value) -> Value {
+    json!({ "type": "message.part.updated", "properties": { "part": part } })
+}
+
+fn tool_part(id: &str, tool: &str, state: Value) -> Value {
+    part_event(json!({
+        "id": id, "sessionID": SESSION_ID, "messageID": "msg_bench",
+        "type": "tool", "callID": format!("call_{id}"), "tool": tool, "state": state
+    }))
+}
+
+fn text_part(id: &str, text: &str) -> Value {
+    part_event(json!({
+        "id": id, "sessionID": SESSION_ID, "messageID": "msg_bench",
+        "type": "text", "text": text
+    }))
+}
+
+/// Assistant text streamed a few words at a time, with no tools: many small
+/// updates that each re-send the whole text so far.
+fn text_deltas() -> Workload {
+    let fixture = generate(
+        1,
+        &FixtureConfig {
+            messages_per_session: 20,
+            max_tools_per_message: 0,
+            text_deltas: 50,
+            ..FixtureConfig::default()
+        },
+    );
+    let session_id = fixture.events[0]["properties"]["sessionID"]
+        .as_str()
+        .expect("generated sessions start with their status")
+        .to_string();
+    Workload::new("text_deltas", fixture, session_id)
+}
+
+/// Tool calls whose completed output is 64 KiB, as from reading a large file
+/// or a verbose build.
+fn large_tool_outputs() -> Workload {
+    let output: String = (0..1024)
+        .map(|line| format!("{line:>6} | {}\n", "x".repeat(54)))
+        .collect();
+    let mut events = Vec::new();
+    for call in 0..50 {
+        let id = format!("prt_{call}");
+        let input = json!({ "command": "cargo build --verbose" });
+        events.push(tool_part(
+            &id,
+            "bash",
+            json!({ "status": "running", "input": input, "title": "bash" }),
+        ));
+        events.push(tool_part(
+            &id,
+            "bash",
+            json!({
+                "status": "completed", "input": input, "output": output, "title": "bash",
+                "metadata": { "exit": 0 }
+            }),
+        ));
+    }
+    Workload::new(
+        "large_tool_outputs",
+        SseFixture { events },
+        SESSION_ID.to_string(),
+    )
+}
+
+/// One session with 1,000 distinct parts, alternating text and short tool
+/// calls, each updated twice.
+fn thousand_parts() -> Workload {
+    let mut events = Vec::new();
+    for index in 0..1000 {
+        let id = format!("prt_{index}");
+        if index % 2 == 0 {
+            events.push(text_part(&id, "checking the parser"));
+            events.push(text_part(&id, "checking the parser and its tests"));
+        } else {
+            let input = json!({ "filePath": format!("src/module_{index}.rs") });
+            events.push(tool_part(
+                &id,
+                "read",
+                json!({ "status": "running", "input": input, "title": "read" }),
+            ));
+            events.push(tool_part(
+                &id,
+                "read",
+                json!({
+                    "status": "completed", "input": input, "title": "read",
+                    "output": "fn main() {}\n"
+                }),
+            ));
+        }
+    }
+    Workload::new(
+        "thousand_parts",
+        SseFixture { events },
+        SESSION_ID.to_string(),
+    )
+}
+
+fn render(replayed: &Replay) -> usize {
+    replayed.transcript_text().len() + replayed.chat_text("discord").len()
+}
+
+fn streaming_pipeline(criterion: &mut Criterion) {
+    let workloads = [text_deltas(), large_tool_outputs(), thousand_parts()];
+
+    for workload in &workloads {
+        let chunks = || workload.body.chunks(CHUNK_BYTES);
+        let events = parse_sse_chunks(chunks()).events;
+        assert_eq!(events.len(), workload.events, "{} parses", workload.name);
+        let replayed = replay(&events, &workload.session_id);
+        assert!(
+            !replayed.transcript.is_empty(),
+            "{} has a transcript",
+            workload.name
+        );
+
+        let mut group = criterion.benchmark_group(workload.name);
+        group.throughput(Throughput::Elements(workload.events as u64));
+        group.bench_function(BenchmarkId::from_parameter("parse"), |bencher| {
+            bencher.iter(|| parse_sse_chunks(black_box(chunks())))
+        });
+        group.bench_function(BenchmarkId::from_parameter("accumulate"), |bencher| {
+            bencher.iter(|| replay(black_box(&events), &workload.session_id))
+        });
+        group.bench_function(BenchmarkId::from_parameter("render"), |bencher| {
+            bencher.iter(|| render(black_box(&replayed)))
+        });
+        group.bench_function(BenchmarkId::from_parameter("pipeline"), |bencher| {
+            bencher.iter(|| {
+                let events = parse_sse_chunks(black_box(chunks())).events;
+                render(&replay(&events, &workload.session_id))
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, streaming_pipeline);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+//! One `data:` payload through both envelope parsers and into a replay.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use spacebot::opencode::types::{SseEvent, SseEventEnvelope, SseEventEnvelopeRef};
+use spacebot::testkit::replay;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(raw) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(envelope) = serde_json::from_str::<SseEventEnvelopeRef>(raw) {
+        let _ = SseEvent::from_envelope_ref(envelope);
+    }
+    if let Ok(envelope) = serde_json::from_str::<SseEventEnvelope>(raw) {
+        let event = SseEvent::from_envelope(envelope);
+        let _ = replay(&[event], "ses_fuzz").chat_text("slack");
+    }
+});
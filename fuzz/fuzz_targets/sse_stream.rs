@@ -0,0 +1,24 @@
+//! Raw bytes through the worker's SSE parser, cut into chunks, then replayed
+//! through the transcript assembler and chat renderers.
+//!
+//! The first byte sets the chunk size. The parser must not panic, and an
+//! unterminated block must not grow its buffer past the block limit.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use spacebot::opencode::worker::MAX_SSE_BLOCK_BYTES;
+use spacebot::testkit::{parse_sse_chunks, replay};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&chunk_size, body)) = data.split_first() else {
+        return;
+    };
+    let chunk_size = usize::from(chunk_size).max(1);
+    let parsed = parse_sse_chunks(body.chunks(chunk_size));
+    assert!(parsed.peak_buffered_bytes <= MAX_SSE_BLOCK_BYTES + chunk_size * 4);
+
+    let replayed = replay(&parsed.events, "ses_fuzz");
+    let _ = replayed.transcript_text();
+    let _ = replayed.chat_text("discord");
+});
@@ -247,11 +247,34 @@ export type OpenCodePart =
 	| { type: "step_start"; id: string }
 	| { type: "step_finish"; id: string; reason?: string };
 
+/** A `task` tool sub-agent session spawned by an OpenCode worker. */
+export interface OpenCodeSubagent {
+	session_id: string;
+	parent_part_id?: string | null;
+	title?: string | null;
+}
+
+/** A part as held in the live transcript, tagged when it came from a sub-agent. */
+export type LiveOpenCodePart = OpenCodePart & { subagent?: OpenCodeSubagent };
+
 export interface OpenCodePartUpdatedEvent {
 	type: "opencode_part_updated";
 	agent_id: string;
 	worker_id: string;
 	part: OpenCodePart;
+	/** Set when the part belongs to a sub-agent session rather than the worker's own. */
+	subagent?: OpenCodeSubagent;
+}
+
+export interface OpenCodeServerLogEvent {
+	type: "opencode_server_log";
+	agent_id: string;
+	worker_id: string;
+	entry: {
+		level: "debug" | "info" | "warn" | "error";
+		service?: string | null;
+		message: string;
+	};
 }
 
 export interface WorkerTextEvent {
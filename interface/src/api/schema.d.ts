@@ -2590,6 +2590,8 @@ export interface components {
             original_filename: string;
             /** Format: int64 */
             size_bytes: number;
+            /** @description Absolute URL serving the file, based on the external base URL. */
+            url: string;
         };
         AttachmentListResponse: {
             attachments: components["schemas"]["AttachmentInfo"][];
@@ -2600,6 +2602,8 @@ export interface components {
             original_filename: string;
             /** Format: int64 */
             size_bytes: number;
+            /** @description Absolute URL serving the file, based on the external base URL. */
+            url: string;
         };
         AuthorizedKeyRequest: {
             public_key: string;
@@ -2740,6 +2744,11 @@ export interface components {
         ConversationSettings: {
             /** @description How tools work in this conversation. */
             delegation?: components["schemas"]["DelegationMode"];
+            /**
+             * @description Custom instructions appended to the channel's system prompt and
+             *     passed to OpenCode sessions spawned from it.
+             */
+            instructions?: string | null;
             /** @description How memory is used in this conversation. */
             memory?: components["schemas"]["MemoryMode"];
             /**
@@ -2749,6 +2758,11 @@ export interface components {
             model?: string | null;
             /** @description Per-process model overrides. Takes priority over `model`. */
             model_overrides?: components["schemas"]["ModelOverrides"];
+            /**
+             * @description Repository directory on the OpenCode host that OpenCode workers from
+             *     this channel use when no directory is given.
+             */
+            project_directory?: string | null;
             /** @description How the channel handles incoming messages. */
             response_mode?: components["schemas"]["ResponseMode"];
             /** @description Whether file attachments are saved to workspace. */
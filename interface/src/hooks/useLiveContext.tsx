@@ -1,6 +1,6 @@
 import { createContext, useContext, useCallback, useEffect, useRef, useState, useMemo, type ReactNode } from "react";
 import { useQuery, useQueryClient } from "@tanstack/react-query";
-import { api, type AgentMessageEvent, type ChannelInfo, type ToolStartedEvent, type ToolCompletedEvent, type ToolOutputEvent, type OpenCodePart, type OpenCodePartUpdatedEvent, type WorkerTextEvent } from "@/api/client";
+import { api, type AgentMessageEvent, type ChannelInfo, type ToolStartedEvent, type ToolCompletedEvent, type ToolOutputEvent, type LiveOpenCodePart, type OpenCodePartUpdatedEvent, type WorkerTextEvent } from "@/api/client";
 import type { TranscriptStep as SchemaTranscriptStep } from "@/api/types";
 
 type ToolResultStatus = "pending" | "final" | "waiting_for_input";
@@ -32,7 +32,7 @@ interface LiveContextValue {
 	/** Live transcript steps for running workers, keyed by worker_id. Built from SSE tool events. */
 	liveTranscripts: Record<string, TranscriptStep[]>;
 	/** Live OpenCode parts for running workers, keyed by worker_id. Parts are insertion-ordered Maps keyed by part ID. */
-	liveOpenCodeParts: Record<string, Map<string, OpenCodePart>>;
+	liveOpenCodeParts: Record<string, Map<string, LiveOpenCodePart>>;
 }
 
 const LiveContext = createContext<LiveContextValue>({
@@ -99,7 +99,7 @@ export function LiveContextProvider({ children, onBootstrapped }: { children: Re
 
 	// Live OpenCode parts: per-worker insertion-ordered Map keyed by part ID.
 	// Updated via opencode_part_updated SSE events. Cleared when worker completes.
-	const [liveOpenCodeParts, setLiveOpenCodeParts] = useState<Record<string, Map<string, OpenCodePart>>>({});
+	const [liveOpenCodeParts, setLiveOpenCodeParts] = useState<Record<string, Map<string, LiveOpenCodePart>>>({});
 
 	// Derive flat active workers from channel live states
 	const activeWorkers = useMemo(() => {
@@ -310,9 +310,13 @@ export function LiveContextProvider({ children, onBootstrapped }: { children: Re
 	const handleOpenCodePartUpdated = useCallback((data: unknown) => {
 		const event = data as OpenCodePartUpdatedEvent;
 		setLiveOpenCodeParts((prev) => {
-			const existing = prev[event.worker_id] ?? new Map<string, OpenCodePart>();
+			const existing = prev[event.worker_id] ?? new Map<string, LiveOpenCodePart>();
 			const next = new Map(existing);
-			next.set(event.part.id, event.part);
+			if (event.subagent) {
+				next.set(`${event.subagent.session_id}:${event.part.id}`, { ...event.part, subagent: event.subagent });
+			} else {
+				next.set(event.part.id, event.part);
+			}
 			return { ...prev, [event.worker_id]: next };
 		});
 		bumpWorkerVersion();
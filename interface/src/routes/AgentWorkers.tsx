@@ -8,6 +8,7 @@ import {
 	type WorkerRunInfo,
 	type WorkerDetailResponse,
 	type TranscriptStep,
+	type LiveOpenCodePart,
 	type OpenCodePart,
 } from "@/api/client";
 import {
@@ -387,7 +388,7 @@ export function WorkerDetail({
 	detail: WorkerDetailResponse;
 	liveWorker?: LiveWorker;
 	liveTranscript?: TranscriptStep[];
-	liveOpenCodeParts?: Map<string, OpenCodePart>;
+	liveOpenCodeParts?: Map<string, LiveOpenCodePart>;
 }) {
 	const isLive = detail.status === "running" || !!liveWorker;
 	const isIdle = liveWorker?.isIdle ?? detail.status === "idle";
@@ -406,10 +407,21 @@ export function WorkerDetail({
 		detail.opencode_session_id != null;
 
 	// Convert the insertion-ordered Map to an array for rendering
-	const openCodeParts: OpenCodePart[] = useMemo(
-		() => (liveOpenCodeParts ? Array.from(liveOpenCodeParts.values()) : []),
-		[liveOpenCodeParts],
-	);
+	// Convert the insertion-ordered Map to an array for rendering. Sub-agent
+	// parts are split out and grouped by the `task` part that spawned them.
+	const {openCodeParts, subagentParts} = useMemo(() => {
+		const own: OpenCodePart[] = [];
+		const nested = new Map<string, LiveOpenCodePart[]>();
+		for (const part of liveOpenCodeParts?.values() ?? []) {
+			if (!part.subagent) {
+				own.push(part);
+				continue;
+			}
+			const key = part.subagent.parent_part_id ?? part.subagent.session_id;
+			nested.set(key, [...(nested.get(key) ?? []), part]);
+		}
+		return {openCodeParts: own, subagentParts: nested};
+	}, [liveOpenCodeParts]);
 
 	const [activeTab, setActiveTab] = useState<DetailTab>(
 		hasOpenCodeEmbed ? "opencode" : "transcript",
@@ -570,6 +582,9 @@ export function WorkerDetail({
 										transition={{duration: 0.2, ease: "easeOut"}}
 									>
 										<OpenCodePartView part={part} />
+										{subagentParts.has(part.id) && (
+											<SubagentSection parts={subagentParts.get(part.id) ?? []} />
+										)}
 									</motion.div>
 								))}
 								{isRunning && currentTool && (
@@ -745,6 +760,22 @@ function CancelWorkerButton({
 
 // -- OpenCode-native part renderers --
 
+function SubagentSection({parts}: {parts: LiveOpenCodePart[]}) {
+	const title = parts[0]?.subagent?.title ?? "Sub-agent";
+	return (
+		<details className="ml-4 mt-2 border-l border-app-line/30 pl-3">
+			<summary className="cursor-pointer text-tiny text-ink-faint">
+				{title} · {parts.length} {parts.length === 1 ? "step" : "steps"}
+			</summary>
+			<div className="mt-2 flex flex-col gap-2">
+				{parts.map((part) => (
+					<OpenCodePartView key={`${part.subagent?.session_id}:${part.id}`} part={part} />
+				))}
+			</div>
+		</details>
+	);
+}
+
 function OpenCodePartView({part}: {part: OpenCodePart}) {
 	switch (part.type) {
 		case "text":
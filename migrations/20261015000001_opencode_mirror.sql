@@ -0,0 +1,18 @@
+-- Compliance mirror of OpenCode sessions observed on mirrored backends.
+-- One row per finished part, permission reply, or session error.
+CREATE TABLE IF NOT EXISTS opencode_mirror_parts (
+    id          TEXT PRIMARY KEY,
+    agent_id    TEXT NOT NULL,
+    backend     TEXT NOT NULL,
+    session_id  TEXT NOT NULL,
+    message_id  TEXT,
+    role        TEXT,
+    kind        TEXT NOT NULL,
+    tool        TEXT,
+    input       TEXT,
+    content     TEXT NOT NULL DEFAULT '',
+    recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_opencode_mirror_session ON opencode_mirror_parts(agent_id, session_id, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_opencode_mirror_recorded ON opencode_mirror_parts(agent_id, recorded_at DESC);
@@ -0,0 +1,15 @@
+-- Append-only audit trail: prompts, tool calls, permission decisions, and
+-- privileged chat commands. Rows are never updated or deleted by spacebot.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id    TEXT NOT NULL,
+    kind        TEXT NOT NULL,
+    actor       TEXT NOT NULL,
+    channel_id  TEXT,
+    summary     TEXT NOT NULL,
+    detail      TEXT,
+    recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_agent ON audit_log(agent_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_kind ON audit_log(agent_id, kind, id DESC);
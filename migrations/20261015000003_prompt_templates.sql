@@ -0,0 +1,11 @@
+-- Prompt templates saved from chat with `/template add`, per agent.
+-- Templates from `[[agents.templates]]` in config are not stored here.
+CREATE TABLE IF NOT EXISTS prompt_templates (
+    agent_id    TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    prompt      TEXT NOT NULL,
+    description TEXT,
+    created_at  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (agent_id, name)
+);
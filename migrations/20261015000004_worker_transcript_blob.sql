@@ -0,0 +1,3 @@
+-- Blob ID (`transcripts/<sha256>`) of a transcript exported to the blob
+-- store when an idle OpenCode session is archived.
+ALTER TABLE worker_runs ADD COLUMN transcript_blob_id TEXT;
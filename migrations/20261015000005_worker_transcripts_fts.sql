@@ -0,0 +1,22 @@
+-- Full-text index over worker transcripts, for `/search`.
+--
+-- Transcripts are stored gzip-compressed, so the index can't read them from
+-- worker_runs directly. The application decompresses each transcript into
+-- worker_transcripts_fts and records the indexed transcript's size in
+-- worker_transcript_index; a size change means the transcript has grown
+-- and is re-indexed on the next search.
+
+CREATE TABLE IF NOT EXISTS worker_transcript_index (
+    id INTEGER PRIMARY KEY,                -- rowid of the worker_transcripts_fts row
+    worker_id TEXT NOT NULL UNIQUE,
+    transcript_size INTEGER NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS worker_transcripts_fts USING fts5(content);
+
+CREATE TRIGGER IF NOT EXISTS worker_transcript_index_delete AFTER DELETE ON worker_runs BEGIN
+    DELETE FROM worker_transcripts_fts
+    WHERE rowid IN (SELECT id FROM worker_transcript_index WHERE worker_id = old.id);
+    DELETE FROM worker_transcript_index WHERE worker_id = old.id;
+END;
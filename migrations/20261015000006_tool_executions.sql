@@ -0,0 +1,14 @@
+-- One row per finished tool call, for `/stats tools`. Recorded from the
+-- agent's event bus: builtin tool calls and OpenCode worker tool parts.
+CREATE TABLE IF NOT EXISTS tool_executions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id    TEXT NOT NULL,
+    channel_id  TEXT,
+    tool_name   TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    success     INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_executions_agent ON tool_executions(agent_id, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_tool_executions_channel ON tool_executions(channel_id, recorded_at);
@@ -0,0 +1,5 @@
+-- Attribute token usage to the user whose prompt started the turn, for
+-- `/stats cost`. NULL for background work (cortex, cron) and older rows.
+ALTER TABLE token_usage ADD COLUMN user_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_token_usage_user ON token_usage(agent_id, user_id, recorded_at DESC);
@@ -0,0 +1,3 @@
+-- Mark usage recorded in direct-message sessions so shared spend reports
+-- (`/stats cost`, the weekly report) can leave it out.
+ALTER TABLE token_usage ADD COLUMN private INTEGER NOT NULL DEFAULT 0;
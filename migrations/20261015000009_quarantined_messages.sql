@@ -0,0 +1,15 @@
+-- Outbound messages caught by a tenant content filter. `block` rows were
+-- never posted; `flag` rows were posted unchanged. Admins review them at
+-- /api/messaging/quarantine.
+CREATE TABLE IF NOT EXISTS quarantined_messages (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id       TEXT NOT NULL,
+    conversation_id TEXT NOT NULL,
+    rule            TEXT NOT NULL,
+    action          TEXT NOT NULL,
+    content         TEXT NOT NULL,
+    reviewed        INTEGER NOT NULL DEFAULT 0,
+    created_at      TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_messages_reviewed ON quarantined_messages(reviewed, created_at);
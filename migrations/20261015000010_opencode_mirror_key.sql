@@ -0,0 +1,28 @@
+-- Part IDs are only unique within one OpenCode server, so two mirrored
+-- backends (or agents) could overwrite each other's rows. Key the table by
+-- (agent_id, backend, id) instead.
+CREATE TABLE opencode_mirror_parts_new (
+    id          TEXT NOT NULL,
+    agent_id    TEXT NOT NULL,
+    backend     TEXT NOT NULL,
+    session_id  TEXT NOT NULL,
+    message_id  TEXT,
+    role        TEXT,
+    kind        TEXT NOT NULL,
+    tool        TEXT,
+    input       TEXT,
+    content     TEXT NOT NULL DEFAULT '',
+    recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (agent_id, backend, id)
+);
+
+INSERT INTO opencode_mirror_parts_new
+    (id, agent_id, backend, session_id, message_id, role, kind, tool, input, content, recorded_at)
+SELECT id, agent_id, backend, session_id, message_id, role, kind, tool, input, content, recorded_at
+FROM opencode_mirror_parts;
+
+DROP TABLE opencode_mirror_parts;
+ALTER TABLE opencode_mirror_parts_new RENAME TO opencode_mirror_parts;
+
+CREATE INDEX IF NOT EXISTS idx_opencode_mirror_session ON opencode_mirror_parts(agent_id, session_id, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_opencode_mirror_recorded ON opencode_mirror_parts(agent_id, recorded_at DESC);
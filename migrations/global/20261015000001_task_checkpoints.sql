@@ -0,0 +1,12 @@
+-- Stage checkpoints for multi-stage tasks. Each row records the result of a
+-- completed subtask so a task interrupted by a restart resumes after the last
+-- completed stage instead of starting over.
+CREATE TABLE IF NOT EXISTS task_checkpoints (
+    task_id     TEXT NOT NULL,
+    stage_index INTEGER NOT NULL,
+    stage_title TEXT NOT NULL,
+    result      TEXT NOT NULL DEFAULT '',
+    worker_id   TEXT,
+    recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (task_id, stage_index)
+);
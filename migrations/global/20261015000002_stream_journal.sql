@@ -0,0 +1,12 @@
+-- In-flight streamed messages. Adapters record the platform message a stream
+-- edits and checkpoint its text, so after a crash a restarted stream edits the
+-- same message instead of posting a duplicate. Rows are removed when the
+-- stream ends.
+CREATE TABLE IF NOT EXISTS stream_journal (
+    adapter             TEXT NOT NULL,
+    stream_key          TEXT NOT NULL,
+    platform_message_id TEXT NOT NULL,
+    text                TEXT NOT NULL DEFAULT '',
+    updated_at          TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (adapter, stream_key)
+);
@@ -0,0 +1,35 @@
+-- Notifications store for deployments sharing a Postgres database.
+-- Mirrors the SQLite `notifications` table in migrations/global, including
+-- its ISO-8601 text timestamps.
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    severity TEXT NOT NULL DEFAULT 'info',
+    title TEXT NOT NULL,
+    body TEXT,
+    agent_id TEXT,
+    related_entity_type TEXT,
+    related_entity_id TEXT,
+    action_url TEXT,
+    metadata TEXT,
+    created_at TEXT NOT NULL
+        DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+    read_at TEXT,
+    dismissed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_inbox
+    ON notifications(dismissed_at, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_agent
+    ON notifications(agent_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_entity
+    ON notifications(related_entity_type, related_entity_id);
+
+-- One active notification per entity; inserts of a duplicate are skipped
+-- with ON CONFLICT DO NOTHING.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_entity_active
+    ON notifications(kind, related_entity_type, related_entity_id)
+    WHERE dismissed_at IS NULL;
@@ -0,0 +1,14 @@
+-- Which replica handles each session when several share one bot token.
+-- A lease is taken by the first replica to see a message for a session and
+-- kept by renewing `expires_at`; an expired lease can be taken by any replica.
+
+CREATE TABLE IF NOT EXISTS session_leases (
+    -- "{agent_id}:{conversation_id}"
+    session_key TEXT PRIMARY KEY,
+    -- `[cluster] replica_id` of the owner
+    owner TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_leases_owner
+    ON session_leases(owner);
@@ -0,0 +1,276 @@
+//! Per-user access tiers for chat commands and privileged actions.
+
+use crate::InboundMessage;
+use crate::config::HumanDef;
+
+use serde::{Deserialize, Serialize};
+
+/// Authorization tier assigned to a configured human.
+///
+/// Tiers are ordered: an admin can do everything a developer can, and a
+/// developer can do everything a read-only user can.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessTier {
+    /// Can talk to the agent and inspect status, nothing else.
+    ReadOnly,
+    /// Can change how the agent behaves in a conversation.
+    Developer,
+    /// Can switch models, change budgets, and approve dangerous tool calls.
+    Admin,
+}
+
+impl AccessTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::Developer => "developer",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Parse a tier name from config. Accepts `read-only` as an alias.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "read_only" | "read-only" | "readonly" => Some(Self::ReadOnly),
+            "developer" => Some(Self::Developer),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AccessTier {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// A privileged action gated by [`AccessTier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ViewStatus,
+    ChangeResponseMode,
+    SwitchModel,
+    ChangeBudget,
+    ApproveToolCall,
+    ViewAudit,
+    ViewPipelines,
+    SetInstructions,
+    BindProject,
+    ManageSchedules,
+    ApprovePlan,
+    CompactSession,
+    RunGit,
+    RunOpenCodeCommand,
+    AnswerQuestion,
+    ManageDriverLock,
+    ManageTemplates,
+    AttachSession,
+    BypassRateLimit,
+    ManageMaintenance,
+    MirrorEvents,
+}
+
+impl Action {
+    /// Minimum tier required to perform this action.
+    pub fn required_tier(self) -> AccessTier {
+        match self {
+            Self::ViewStatus => AccessTier::ReadOnly,
+            Self::ChangeResponseMode
+            | Self::ApprovePlan
+            | Self::CompactSession
+            | Self::RunGit
+            | Self::RunOpenCodeCommand
+            | Self::AnswerQuestion
+            | Self::ManageDriverLock
+            | Self::ManageTemplates
+            | Self::AttachSession => AccessTier::Developer,
+            Self::SwitchModel
+            | Self::ChangeBudget
+            | Self::ApproveToolCall
+            | Self::ViewAudit
+            | Self::ViewPipelines
+            | Self::SetInstructions
+            | Self::BindProject
+            | Self::ManageSchedules
+            | Self::BypassRateLimit
+            | Self::ManageMaintenance
+            | Self::MirrorEvents => AccessTier::Admin,
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::ViewStatus => "view status",
+            Self::ChangeResponseMode => "change the response mode",
+            Self::SwitchModel => "switch models",
+            Self::ChangeBudget => "change budgets",
+            Self::ApproveToolCall => "approve tool calls",
+            Self::ViewAudit => "read the audit log",
+            Self::ViewPipelines => "view pipeline status",
+            Self::SetInstructions => "change channel instructions",
+            Self::BindProject => "bind the channel to a project",
+            Self::ManageSchedules => "manage scheduled prompts",
+            Self::ApprovePlan => "approve or reject plans",
+            Self::CompactSession => "compact worker sessions",
+            Self::RunGit => "run git commands in worker sessions",
+            Self::RunOpenCodeCommand => "run opencode commands in worker sessions",
+            Self::AnswerQuestion => "answer worker questions",
+            Self::ManageDriverLock => "take or release the driver lock",
+            Self::ManageTemplates => "manage prompt templates",
+            Self::AttachSession => "attach or detach opencode sessions",
+            Self::BypassRateLimit => "bypass prompt rate limits",
+            Self::ManageMaintenance => "manage maintenance mode, broadcasts, and abort-all",
+            Self::MirrorEvents => "mirror raw worker events",
+        }
+    }
+}
+
+/// Whether any human has an explicit tier. Enforcement is opt-in: until at
+/// least one tier is configured, every sender is treated as an admin so
+/// existing single-user installs keep working unchanged.
+pub fn enforcement_enabled(humans: &[HumanDef]) -> bool {
+    humans.iter().any(|human| human.access.is_some())
+}
+
+/// Resolve the tier for the sender of an inbound message.
+///
+/// Only platform-verified identifiers are matched. The human `id` is not
+/// compared against the sender, since on some platforms (Twitch, webchat)
+/// sender IDs are user-chosen names that could collide with a configured id.
+/// Sources without an identity field (Mattermost, IRC, Twitch, webchat)
+/// never match and resolve to read-only.
+pub fn resolve_tier(humans: &[HumanDef], message: &InboundMessage) -> AccessTier {
+    resolve_sender_tier(humans, &message.source, &message.sender_id)
+}
+
+/// Resolve the tier for `sender_id` on `source`, as [`resolve_tier`] does
+/// for a message.
+pub fn resolve_sender_tier(humans: &[HumanDef], source: &str, sender_id: &str) -> AccessTier {
+    if !enforcement_enabled(humans) {
+        return AccessTier::Admin;
+    }
+    if source == "system" {
+        return AccessTier::Admin;
+    }
+
+    humans
+        .iter()
+        .find(|human| match source {
+            "discord" => human.discord_id.as_deref() == Some(sender_id),
+            "telegram" => human.telegram_id.as_deref() == Some(sender_id),
+            "slack" => human.slack_id.as_deref() == Some(sender_id),
+            "email" => human.email.as_deref() == Some(sender_id),
+            _ => false,
+        })
+        .map(|human| human.access.unwrap_or(AccessTier::ReadOnly))
+        .unwrap_or(AccessTier::ReadOnly)
+}
+
+/// Check whether the sender of `message` may perform `action`.
+pub fn authorize(humans: &[HumanDef], message: &InboundMessage, action: Action) -> bool {
+    resolve_tier(humans, message) >= action.required_tier()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn human(id: &str, discord_id: &str, access: Option<AccessTier>) -> HumanDef {
+        HumanDef {
+            id: id.to_string(),
+            display_name: None,
+            role: None,
+            bio: None,
+            description: None,
+            discord_id: Some(discord_id.to_string()),
+            telegram_id: None,
+            slack_id: None,
+            email: None,
+            access,
+        }
+    }
+
+    fn message(source: &str, sender_id: &str) -> InboundMessage {
+        InboundMessage {
+            source: source.to_string(),
+            adapter: Some(source.to_string()),
+            conversation_id: format!("{source}:1"),
+            sender_id: sender_id.to_string(),
+            content: crate::MessageContent::Text("/status".to_string()),
+            ..InboundMessage::empty()
+        }
+    }
+
+    #[test]
+    fn everyone_is_admin_without_configured_tiers() {
+        let humans = vec![human("victor", "1", None)];
+        let tier = resolve_tier(&humans, &message("discord", "999"));
+        assert_eq!(tier, AccessTier::Admin);
+    }
+
+    #[test]
+    fn unknown_senders_are_read_only_once_enforced() {
+        let humans = vec![
+            human("victor", "1", Some(AccessTier::Admin)),
+            human("jamie", "2", Some(AccessTier::Developer)),
+        ];
+        assert_eq!(
+            resolve_tier(&humans, &message("discord", "1")),
+            AccessTier::Admin
+        );
+        assert_eq!(
+            resolve_tier(&humans, &message("discord", "2")),
+            AccessTier::Developer
+        );
+        assert_eq!(
+            resolve_tier(&humans, &message("discord", "3")),
+            AccessTier::ReadOnly
+        );
+        // Platform IDs are scoped to their platform.
+        assert_eq!(
+            resolve_tier(&humans, &message("telegram", "1")),
+            AccessTier::ReadOnly
+        );
+    }
+
+    #[test]
+    fn human_id_does_not_grant_access() {
+        let humans = vec![human("admin", "1", Some(AccessTier::Admin))];
+        assert_eq!(
+            resolve_tier(&humans, &message("twitch", "admin")),
+            AccessTier::ReadOnly
+        );
+    }
+
+    #[test]
+    fn actions_require_their_tier() {
+        let humans = vec![human("jamie", "2", Some(AccessTier::Developer))];
+        let sender = message("discord", "2");
+        assert!(authorize(&humans, &sender, Action::ViewStatus));
+        assert!(authorize(&humans, &sender, Action::ChangeResponseMode));
+        assert!(!authorize(&humans, &sender, Action::SwitchModel));
+        assert!(!authorize(&humans, &sender, Action::ManageMaintenance));
+        assert!(!authorize(&humans, &sender, Action::ApproveToolCall));
+    }
+
+    #[test]
+    fn parses_tier_aliases() {
+        assert_eq!(AccessTier::parse("read-only"), Some(AccessTier::ReadOnly));
+        assert_eq!(AccessTier::parse("Admin"), Some(AccessTier::Admin));
+        assert_eq!(AccessTier::parse("owner"), None);
+    }
+}
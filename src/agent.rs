@@ -2,10 +2,21 @@
 
 pub mod branch;
 pub mod channel;
+pub mod channel_abort;
 pub mod channel_attachments;
+pub mod channel_context;
 pub mod channel_dispatch;
+pub mod channel_driver;
+pub mod channel_git;
+pub mod channel_heartbeat;
 pub mod channel_history;
+pub mod channel_permission;
+pub mod channel_plan;
 pub mod channel_prompt;
+pub mod channel_question;
+pub mod channel_status;
+pub mod channel_tool_render;
+pub mod channel_tool_summary;
 pub mod compactor;
 pub mod cortex;
 pub mod cortex_chat;
@@ -14,6 +25,7 @@ pub mod ingestion;
 mod invariant_harness;
 pub mod maintenance;
 pub mod process_control;
+pub mod prompt_guard;
 pub mod prompt_snapshot;
 pub mod status;
 pub mod wake;
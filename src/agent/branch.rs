@@ -37,6 +37,9 @@ pub struct Branch {
     pub memory_persistence_contract: Option<Arc<MemoryPersistenceContractState>>,
     /// Model override from conversation settings (per-process or blanket).
     pub model_override: Option<String>,
+    /// `source:sender_id` of the user whose turn forked this branch. Its
+    /// token usage is attributed to them.
+    pub requested_by: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -83,9 +86,16 @@ impl Branch {
             max_turns: execution_config.max_turns,
             memory_persistence_contract: execution_config.memory_persistence_contract,
             model_override,
+            requested_by: None,
         }
     }
 
+    /// Attribute this branch's token usage to `requested_by`.
+    pub fn with_requested_by(mut self, requested_by: Option<String>) -> Self {
+        self.requested_by = requested_by;
+        self
+    }
+
     /// Run the branch's LLM agent loop and return a conclusion.
     ///
     /// Each branch has its own isolated ToolServer with `memory_save` and
@@ -261,18 +271,14 @@ impl Branch {
             conclusion: conclusion.clone(),
         });
 
-        // Flush accumulated token usage.
-        let acc = usage_accumulator.lock().await;
-        if let Err(error) = acc
-            .flush(
-                &self.deps.sqlite_pool,
-                &self.deps.agent_id,
-                "branch",
-                Some(&*self.channel_id),
-            )
+        // Queue accumulated token usage.
+        let usage = usage_accumulator
+            .lock()
             .await
-        {
-            tracing::warn!(%error, "failed to flush branch token usage");
+            .to_record(&self.deps.agent_id, "branch", Some(&*self.channel_id))
+            .map(|usage| usage.with_user(self.requested_by.clone()));
+        if let Some(usage) = usage {
+            self.deps.write_behind.write_usage(usage).await;
         }
 
         tracing::info!(branch_id = %self.id, "branch completed");
@@ -1,22 +1,37 @@
 //! Channel: User-facing conversation process.
 
+use crate::agent::channel_abort::{self, PendingAbortAll};
 use crate::agent::channel_attachments;
 use crate::agent::channel_attachments::download_attachments;
+use crate::agent::channel_context;
 use crate::agent::channel_dispatch::spawn_memory_persistence_branch;
+use crate::agent::channel_driver::{self, DriverCommand, DriverLock};
+use crate::agent::channel_git::{self, GitCommand};
+use crate::agent::channel_heartbeat::{Heartbeat, HeartbeatStyle};
 use crate::agent::channel_history::{
     apply_history_after_turn, event_is_for_channel, extract_message_id,
     extract_reply_from_tool_syntax, format_batched_user_message, format_user_message,
-    message_display_name, pop_retrigger_bridge_message,
+    message_display_name, pop_retrigger_bridge_message, turn_requester,
 };
+use crate::agent::channel_permission::{self, PermissionDecision};
+use crate::agent::channel_plan::{self, PlanDecision};
 use crate::agent::channel_prompt::{
     MAX_RETRIGGERS_PER_TURN, RETRIGGER_DEBOUNCE_MS, RETRIGGER_MAX_TURNS, TemporalContext,
 };
+use crate::agent::channel_question;
+use crate::agent::channel_tool_render::{
+    SubagentSection, ToolTally, merge_turn_replies, render_collapsed_tool_call,
+    render_files_changed, render_raw_event, render_summarized_tool_call, render_todo_checklist,
+    render_tool_call, tool_output_lines,
+};
+use crate::agent::channel_tool_summary::summarize_tool_output;
 use crate::agent::compactor::Compactor;
 use crate::agent::process_control::ControlActionResult;
 use crate::agent::status::{StatusBlock, SystemInfo};
 use crate::agent::worker::Worker;
 use crate::conversation::settings::{
-    DelegationMode, MemoryMode, ResolvedConversationSettings, ResponseMode,
+    DelegationMode, DeliveryMode, MemoryMode, ResolvedConversationSettings, ResponseMode,
+    ToolDetail,
 };
 use crate::conversation::{
     ActiveParticipant, ChannelStore, ConversationLogger, ProcessRunLogger,
@@ -26,6 +41,9 @@ use crate::conversation::{
 use crate::error::{AgentError, Result};
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::messaging::collapse::collapsed;
+use crate::messaging::links::link_file_paths;
+use crate::messaging::timestamp::{TimestampStyle, Timestamps};
 use crate::{
     AgentDeps, BranchId, ChannelId, InboundMessage, OutboundResponse, ProcessEvent, ProcessId,
     ProcessType, RoutedResponse, RoutedSender, WorkerId,
@@ -64,6 +82,64 @@ struct PendingResult {
 }
 
 const EVENT_LAG_WARNING_INTERVAL_SECS: u64 = 30;
+/// Upper bound on `/instructions set`, which is added to every prompt.
+const MAX_CHANNEL_INSTRUCTIONS_CHARS: usize = 4_000;
+
+/// Transcripts listed by `/search`.
+const SEARCH_RESULT_LIMIT: i64 = 5;
+
+/// Window `/stats` covers when no number of days is given, and its cap.
+const STATS_DEFAULT_DAYS: i64 = 7;
+const STATS_MAX_DAYS: i64 = 365;
+
+/// Tools listed per section of `/stats tools`.
+const STATS_TOOL_LIMIT: i64 = 10;
+
+/// Longest worker budget `/budget` accepts, a day.
+const MAX_WORKER_BUDGET_MINUTES: u64 = 24 * 60;
+
+/// Message catalog keys of the `/help` lines, in order.
+const HELP_KEYS: &[&str] = &[
+    "help.header",
+    "help.status",
+    "help.today",
+    "help.tasks",
+    "help.digest",
+    "help.observe",
+    "help.mention_only",
+    "help.active",
+    "help.model",
+    "help.budget",
+    "help.audit",
+    "help.pipeline",
+    "help.project",
+    "help.instructions",
+    "help.schedule",
+    "help.plan",
+    "help.approve",
+    "help.allow",
+    "help.dryrun",
+    "help.delivery",
+    "help.collapse",
+    "help.debug",
+    "help.driver",
+    "help.compact",
+    "help.attach",
+    "help.detach",
+    "help.rebind",
+    "help.oc",
+    "help.git",
+    "help.templates",
+    "help.run",
+    "help.template",
+    "help.locale",
+    "help.timezone",
+    "help.search",
+    "help.stats",
+    "help.admin",
+    "help.context",
+    "help.agent_id",
+];
 const DECISION_MARKERS: &[&str] = &[
     "we decided to ",
     "i decided to ",
@@ -310,6 +386,9 @@ struct AgentTurnResult {
     replied_flag: crate::tools::RepliedFlag,
     retrigger_reply_preserved: bool,
     reply_text: Option<String>,
+    /// Replies held for one message at the end of the turn, in
+    /// `DeliveryMode::Summary`.
+    deferred_replies: Option<crate::tools::DeferredReplies>,
 }
 
 /// Shared state that channel tools need to act on the channel.
@@ -331,6 +410,12 @@ pub struct ChannelState {
     /// Used by the route tool to deliver addendum context to running workers
     /// without requiring the worker to be interactive.
     pub worker_injections: Arc<RwLock<HashMap<WorkerId, tokio::sync::mpsc::Sender<String>>>>,
+    /// Idle OpenCode workers closed by the session TTL. Their completion is
+    /// announced with an "archived" note instead of a retrigger.
+    pub archived_workers: Arc<RwLock<HashSet<WorkerId>>>,
+    /// Idle OpenCode workers unbound with `/detach` or `/rebind`. Their
+    /// sessions stay on the server; completion is silent.
+    pub detached_workers: Arc<RwLock<HashSet<WorkerId>>>,
     /// Task descriptions reserved for spawn. Prevents the TOCTOU race where
     /// two concurrent `spawn_worker` calls both pass `check_duplicate_task`
     /// before either registers in the status block. Reservations are
@@ -343,6 +428,20 @@ pub struct ChannelState {
     pub process_run_logger: ProcessRunLogger,
     /// Discord message ID to reply to for work spawned in the current turn.
     pub reply_target_message_id: Arc<RwLock<Option<String>>>,
+    /// `source:sender_id` of the user who prompted the current turn. Usage
+    /// recorded by the turn and the work it spawns is attributed to them.
+    /// Retriggers keep the previous requester.
+    pub turn_requester: Arc<RwLock<Option<String>>>,
+    /// Display name of [`Self::turn_requester`].
+    pub turn_author: Arc<RwLock<Option<String>>>,
+    /// Author slots of OpenCode workers, keyed by worker ID. The route tool
+    /// fills a worker's slot before queueing a follow-up so the prompt is
+    /// attributed to the user who asked for it.
+    pub worker_contributors:
+        Arc<RwLock<HashMap<WorkerId, crate::opencode::worker::ContributorSlot>>>,
+    /// Shared with every OpenCode worker the channel spawns; set while
+    /// `/debug` is on so they send their raw events.
+    pub mirror_worker_events: Arc<std::sync::atomic::AtomicBool>,
     pub channel_store: ChannelStore,
     pub screenshot_dir: std::path::PathBuf,
     pub logs_dir: std::path::PathBuf,
@@ -372,6 +471,39 @@ pub struct ChannelState {
 }
 
 impl ChannelState {
+    /// The user who prompted the current turn, for attributing prompts that
+    /// reach OpenCode sessions.
+    pub async fn turn_contributor(&self) -> Option<crate::opencode::worker::Contributor> {
+        let user_id = self.turn_requester.read().await.clone()?;
+        let display_name = self
+            .turn_author
+            .read()
+            .await
+            .clone()
+            .unwrap_or_else(|| user_id.clone());
+        Some(crate::opencode::worker::Contributor {
+            user_id,
+            display_name,
+        })
+    }
+
+    /// Attribute the next prompt queued for `worker_id` to the current
+    /// turn's user. A no-op for workers without an author slot.
+    pub async fn attribute_next_prompt(&self, worker_id: WorkerId) {
+        let slot = self
+            .worker_contributors
+            .read()
+            .await
+            .get(&worker_id)
+            .cloned();
+        if let Some(slot) = slot {
+            let contributor = self.turn_contributor().await;
+            *slot
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = contributor;
+        }
+    }
+
     /// Cancel a running worker by aborting its tokio task and cleaning up state.
     /// Returns an error message if the worker is not found.
     pub async fn cancel_worker(&self, worker_id: WorkerId) -> std::result::Result<(), String> {
@@ -505,6 +637,57 @@ impl ChannelState {
         Ok(())
     }
 
+    /// Close an idle interactive worker's session. Dropping its input
+    /// sender ends the follow-up loop, so the worker fetches and persists
+    /// its full transcript and completes normally.
+    pub async fn archive_worker(&self, worker_id: WorkerId) -> std::result::Result<(), String> {
+        if self
+            .worker_inputs
+            .write()
+            .await
+            .remove(&worker_id)
+            .is_none()
+        {
+            return Err(format!("Worker {worker_id} is not interactive"));
+        }
+        self.worker_injections.write().await.remove(&worker_id);
+        self.archived_workers.write().await.insert(worker_id);
+        Ok(())
+    }
+
+    /// Unbind an idle interactive worker from the channel without touching
+    /// its OpenCode session. The run is marked detached in the store first,
+    /// so a failed write leaves the binding as it was; then the input sender
+    /// is dropped and the worker completes like an archived one.
+    pub async fn detach_worker(&self, worker_id: WorkerId) -> std::result::Result<(), String> {
+        let mut inputs = self.worker_inputs.write().await;
+        if !inputs.contains_key(&worker_id) {
+            return Err(format!("Worker {worker_id} is not interactive"));
+        }
+        self.process_run_logger
+            .log_worker_detached(worker_id)
+            .await
+            .map_err(|error| format!("couldn't record the detach: {error}"))?;
+        inputs.remove(&worker_id);
+        drop(inputs);
+        self.worker_injections.write().await.remove(&worker_id);
+        self.detached_workers.write().await.insert(worker_id);
+        Ok(())
+    }
+
+    /// Cancel all active workers and branches, emitting WorkerComplete/BranchResult
+    /// for each so the channel can retrigger and synthesize partial results.
+    pub async fn cancel_all_workers_and_branches(&self, reason: &str) {
+        let worker_ids: Vec<WorkerId> = self.worker_handles.read().await.keys().cloned().collect();
+        for worker_id in worker_ids {
+            let _ = self.cancel_worker_with_reason(worker_id, reason).await;
+        }
+        let branch_ids: Vec<BranchId> = self.active_branches.read().await.keys().cloned().collect();
+        for branch_id in branch_ids {
+            let _ = self.cancel_branch_with_reason(branch_id, reason).await;
+        }
+    }
+
     /// Cancel a running branch by aborting its tokio task.
     /// Returns an error message if the branch is not found.
     pub async fn cancel_branch(&self, branch_id: BranchId) -> std::result::Result<(), String> {
@@ -625,41 +808,20 @@ impl ChannelControlHandle {
         }
     }
 
+    pub async fn archive_worker(&self, worker_id: WorkerId) -> ControlActionResult {
+        match self.inner.state.archive_worker(worker_id).await {
+            Ok(()) => ControlActionResult::Cancelled,
+            Err(_) => ControlActionResult::NotFound,
+        }
+    }
+
     /// Cancel all active workers and branches, emitting WorkerComplete/BranchResult
     /// for each so the channel can retrigger and synthesize partial results.
     pub async fn cancel_all_workers_and_branches(&self, reason: &str) {
-        let worker_ids: Vec<WorkerId> = self
-            .inner
-            .state
-            .worker_handles
-            .read()
-            .await
-            .keys()
-            .cloned()
-            .collect();
-        for worker_id in worker_ids {
-            let _ = self
-                .inner
-                .state
-                .cancel_worker_with_reason(worker_id, reason)
-                .await;
-        }
-        let branch_ids: Vec<BranchId> = self
-            .inner
+        self.inner
             .state
-            .active_branches
-            .read()
-            .await
-            .keys()
-            .cloned()
-            .collect();
-        for branch_id in branch_ids {
-            let _ = self
-                .inner
-                .state
-                .cancel_branch_with_reason(branch_id, reason)
-                .await;
-        }
+            .cancel_all_workers_and_branches(reason)
+            .await;
     }
 }
 
@@ -683,6 +845,15 @@ impl std::fmt::Debug for ChannelState {
     }
 }
 
+/// Where `/debug on` mirrors raw worker events.
+#[derive(Debug, Clone)]
+enum DebugMirror {
+    /// The conversation or thread `/debug on` was sent from.
+    Here(InboundMessage),
+    /// Another channel, given as an `adapter:target` delivery target.
+    Target(crate::messaging::target::BroadcastTarget),
+}
+
 /// User-facing conversation process.
 pub struct Channel {
     pub id: ChannelId,
@@ -703,10 +874,16 @@ pub struct Channel {
     /// The inbound message currently being processed. Used to pair outbound
     /// responses with the correct platform routing metadata (e.g. Slack thread_ts).
     current_inbound: Option<InboundMessage>,
+    /// Last name given to this channel's thread from an OpenCode session
+    /// title, to skip renames that wouldn't change anything.
+    thread_title: Option<String>,
     /// Conversation ID from the first message (for synthetic re-trigger messages).
     pub conversation_id: Option<String>,
     /// Adapter source captured from the first non-system message.
     pub source_adapter: Option<String>,
+    /// Set once an email in this thread was sent to a prompt address. The
+    /// thread is then answered by email instead of treated as intake.
+    email_prompt: bool,
     /// Conversation context (platform, channel name, server) captured from the first message.
     pub conversation_context: Option<String>,
     /// Context monitor that triggers background compaction.
@@ -734,6 +911,39 @@ pub struct Channel {
     /// Background process results waiting to be embedded in the next retrigger.
     /// Accumulated during the debounce window and drained when the retrigger fires.
     pending_results: Vec<PendingResult>,
+    /// `!context` additions held until a busy worker goes idle.
+    pending_context: channel_context::PendingContext,
+    /// Arguments of in-flight worker tool calls keyed by call id, kept until
+    /// the matching `ToolCompleted` renders them.
+    pending_tool_args: HashMap<String, String>,
+    /// Secondary live messages (todo checklists, sub-agent sections) keyed
+    /// by `<kind>:<id>`, with the worker that owns each and its stream target.
+    side_streams: HashMap<String, (WorkerId, InboundMessage)>,
+    /// Render state of `task` sub-agent sessions, keyed by session id.
+    subagent_sections: HashMap<String, (WorkerId, SubagentSection)>,
+    /// OpenCode session id and server port of each running OpenCode worker.
+    opencode_sessions: HashMap<WorkerId, (String, u16)>,
+    /// Typing heartbeats for worker sessions that are currently busy.
+    heartbeats: HashMap<WorkerId, Heartbeat>,
+    /// Worker tool calls not yet posted, in `DeliveryMode::Summary`.
+    tool_tally: ToolTally,
+    /// Workers whose plan is posted and waiting for `/approve` or `/reject`,
+    /// oldest first.
+    pending_plans: Vec<WorkerId>,
+    /// Who is steering the conversation, when `/driver take` is in effect.
+    driver_lock: Option<DriverLock>,
+    /// Where raw worker events go while `/debug` is on. Not persisted.
+    debug_mirror: Option<DebugMirror>,
+    /// Worker questions posted to chat and waiting for a reply, oldest first.
+    pending_questions: Vec<channel_question::PendingQuestion>,
+    /// Worker permission requests posted to chat for an admin, oldest first.
+    pending_permissions: Vec<channel_permission::PendingPermission>,
+    /// `/git` commands sent to a worker and waiting for its reply.
+    pending_git: HashMap<WorkerId, GitCommand>,
+    /// `/admin abort-all` armed here and waiting for confirmation.
+    pending_abort_all: Option<PendingAbortAll>,
+    /// Last user message or process event for this channel, for `/status`.
+    last_activity_at: Option<chrono::DateTime<chrono::Utc>>,
     /// Optional send_agent_message tool (only when agent has active links).
     send_agent_message_tool: Option<crate::tools::SendAgentMessageTool>,
     /// Backfilled conversation history rendered as a system-prompt fragment.
@@ -841,12 +1051,18 @@ impl Channel {
             worker_handles: Arc::new(RwLock::new(HashMap::new())),
             worker_inputs: Arc::new(RwLock::new(HashMap::new())),
             worker_injections: Arc::new(RwLock::new(HashMap::new())),
+            archived_workers: Arc::new(RwLock::new(HashSet::new())),
+            detached_workers: Arc::new(RwLock::new(HashSet::new())),
             reserved_tasks: Arc::new(RwLock::new(HashSet::new())),
             status_block: status_block.clone(),
             deps: deps.clone(),
             conversation_logger,
             process_run_logger,
             reply_target_message_id: Arc::new(RwLock::new(None)),
+            turn_requester: Arc::new(RwLock::new(None)),
+            turn_author: Arc::new(RwLock::new(None)),
+            worker_contributors: Arc::new(RwLock::new(HashMap::new())),
+            mirror_worker_events: Arc::default(),
             channel_store: channel_store.clone(),
             screenshot_dir,
             logs_dir,
@@ -900,8 +1116,10 @@ impl Channel {
             response_tx,
             self_tx,
             current_inbound: None,
+            thread_title: None,
             conversation_id: None,
             source_adapter: None,
+            email_prompt: false,
             conversation_context: None,
             compactor,
             message_count: 0,
@@ -915,6 +1133,21 @@ impl Channel {
             pending_retrigger_metadata: HashMap::new(),
             retrigger_deadline: None,
             pending_results: Vec::new(),
+            pending_context: channel_context::PendingContext::default(),
+            pending_tool_args: HashMap::new(),
+            side_streams: HashMap::new(),
+            subagent_sections: HashMap::new(),
+            opencode_sessions: HashMap::new(),
+            heartbeats: HashMap::new(),
+            tool_tally: ToolTally::default(),
+            pending_plans: Vec::new(),
+            driver_lock: None,
+            debug_mirror: None,
+            pending_questions: Vec::new(),
+            pending_permissions: Vec::new(),
+            pending_git: HashMap::new(),
+            pending_abort_all: None,
+            last_activity_at: None,
             send_agent_message_tool,
             backfill_transcript: None,
             control_handle,
@@ -1046,112 +1279,2844 @@ impl Channel {
         });
     }
 
-    fn persist_inbound_user_message(
-        &self,
-        message: &InboundMessage,
-        raw_text: &str,
-        saved_attachments: Option<&[channel_attachments::SavedAttachmentMeta]>,
-    ) {
-        if message.source == "system" {
-            return;
-        }
-        let sender_name = participant_display_name(message);
+    /// Persist a blanket model override for this channel. `None` clears it.
+    async fn set_channel_model(&mut self, model: Option<String>) {
+        self.resolved_settings.model = model.clone();
 
-        // If attachments were saved, enrich the metadata with their info
-        let metadata = if let Some(saved) = saved_attachments {
-            let mut enriched = message.metadata.clone();
-            if let Ok(attachments_json) = serde_json::to_value(saved) {
-                enriched.insert("attachments".to_string(), attachments_json);
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting model override"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.model = model;
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist model override to channel_settings"
+                );
             }
-            enriched
-        } else {
-            message.metadata.clone()
-        };
-
-        self.state.conversation_logger.log_user_message(
-            &self.state.channel_id,
-            &sender_name,
-            &message.sender_id,
-            raw_text,
-            &metadata,
-        );
-        self.state
-            .channel_store
-            .upsert(&message.conversation_id, &metadata);
+        });
     }
 
-    fn suppress_plaintext_fallback(&self) -> bool {
-        matches!(self.current_adapter(), Some("email"))
+    /// Persist custom instructions for this channel. `None` clears them.
+    /// Workers already running keep the instructions they started with.
+    async fn set_channel_instructions(&mut self, instructions: Option<String>) {
+        self.resolved_settings.instructions = instructions.clone();
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting instructions"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.instructions = instructions;
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist instructions to channel_settings"
+                );
+            }
+        });
     }
 
-    async fn track_participant_from_message(&self, message: &InboundMessage) {
-        if message.source == "system" {
-            return;
-        }
+    /// Persist the channel's bound project directory. `None` unbinds it.
+    async fn set_channel_project(&mut self, directory: Option<String>) {
+        self.resolved_settings.project_directory = directory.clone();
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
 
-        let humans = self.deps.humans.load();
-        let mut participants = self.state.active_participants.write().await;
-        track_active_participant(&mut participants, humans.as_ref(), message);
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting project directory"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.project_directory = directory;
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist project directory to channel_settings"
+                );
+            }
+        });
     }
 
-    /// Return a handle that allows external supervision to cancel this channel's
-    /// workers and branches without direct access to Channel internals.
-    pub fn control_handle(&self) -> ChannelControlHandle {
-        self.control_handle.clone()
+    /// Persist the channel's plan-approval mode.
+    async fn set_plan_approval(&mut self, enabled: bool) {
+        self.resolved_settings.plan_approval = enabled;
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting plan approval"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.plan_approval = Some(enabled);
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist plan approval to channel_settings"
+                );
+            }
+        });
     }
 
-    fn rewrite_tool_routed_command_prompt(&self, raw_text: &str) -> Option<String> {
-        match raw_text.trim() {
-            "/tasks" => Some(
-                "use channel tools to fetch my ready tasks (limit 10) and reply exactly with:\n\
-                 - header: tasks (ready):\n\
-                 - each line: - #<task_number> [<priority>] <title>\n\
-                 if no tasks are ready, reply exactly: tasks (ready): none"
-                    .to_string(),
-            ),
-            "/today" => Some(
-                "use channel tools to build a local tasks snapshot and reply exactly in this format:\n\
-                 - first line: today (local tasks snapshot):\n\
-                 - section 1: in-progress tasks (up to 5), each line:   #<task_number> [<priority>] <title>\n\
-                 - section 2: up next ready tasks (up to 5), each line:   #<task_number> [<priority>] <title>\n\
-                 if a section is empty use:\n\
-                 - in progress: none\n\
-                 - up next (ready): none"
-                    .to_string(),
-            ),
-            "/digest" => Some(
-                "using available tools and channel context, generate a concise day digest from local 00:00 to now with exactly this order:\n\
-                 1) top decisions\n\
-                 2) key convo themes\n\
-                 3) open loops\n\
-                 keep it practical and concise; if there are no meaningful updates, reply exactly: no material updates today."
-                    .to_string(),
-            ),
-            _ => None,
+    /// Handle `/plan [on|off]`.
+    async fn handle_plan_command(&mut self, argument: &str) -> String {
+        match argument {
+            "" => {
+                let state = self.text(if self.resolved_settings.plan_approval {
+                    "plan.state_on"
+                } else {
+                    "plan.state_off"
+                });
+                self.text_with(
+                    "plan.show",
+                    &[
+                        ("state", &state),
+                        ("count", &self.pending_plans.len().to_string()),
+                    ],
+                )
+            }
+            "on" => {
+                self.set_plan_approval(true).await;
+                self.text("plan.on")
+            }
+            "off" => {
+                self.set_plan_approval(false).await;
+                self.text("plan.off")
+            }
+            other => self.text_with("plan.unknown", &[("command", other)]),
         }
     }
 
-    fn compute_listen_mode_invocation(
-        &self,
-        message: &InboundMessage,
-        raw_text: &str,
-    ) -> (bool, bool, bool) {
-        compute_listen_mode_invocation(message, raw_text)
-    }
+    /// Persist the channel's dry-run override; `None` follows the global flag.
+    async fn set_dry_run(&mut self, enabled: Option<bool>) {
+        self.resolved_settings.dry_run = enabled;
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
 
-    /// Send a routed response paired with the current inbound message.
-    ///
-    /// Falls back to a bare response with a placeholder target if no inbound
-    /// message is set (should not happen during normal turn processing).
-    async fn send_routed(
-        &self,
-        response: OutboundResponse,
-    ) -> std::result::Result<(), mpsc::error::SendError<RoutedResponse>> {
-        let routed = match &self.current_inbound {
-            Some(target) => RoutedResponse {
-                response,
-                target: target.clone(),
-            },
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting dry run"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.dry_run = enabled;
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist dry run to channel_settings"
+                );
+            }
+        });
+    }
+
+    /// Persist how this channel's output reaches chat.
+    async fn set_delivery(&mut self, delivery: DeliveryMode) {
+        self.resolved_settings.delivery = delivery;
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+        if delivery == DeliveryMode::Live {
+            // Tool calls from here on post live; don't tack old ones on later.
+            self.tool_tally.take();
+        }
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting delivery mode"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.delivery = Some(delivery);
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist delivery mode to channel_settings"
+                );
+            }
+        });
+    }
+
+    /// Persist whether this channel collapses reasoning and long tool output.
+    async fn set_collapse(&mut self, collapse: bool) {
+        self.resolved_settings.collapse = collapse;
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting collapse"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.collapse = Some(collapse);
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist collapse to channel_settings"
+                );
+            }
+        });
+    }
+
+    /// Handle `/collapse [on|off]`.
+    async fn handle_collapse_command(&mut self, argument: &str) -> String {
+        match argument {
+            "" => self.text(if self.resolved_settings.collapse {
+                "collapse.show_on"
+            } else {
+                "collapse.show_off"
+            }),
+            "on" => {
+                self.set_collapse(true).await;
+                self.text("collapse.on")
+            }
+            "off" => {
+                self.set_collapse(false).await;
+                self.text("collapse.off")
+            }
+            other => self.text_with("collapse.unknown", &[("command", other)]),
+        }
+    }
+
+    /// Post a worker's reasoning collapsed, in channels with `collapse` on.
+    /// Other channels don't show reasoning at all.
+    async fn post_worker_reasoning(&self, worker_id: WorkerId, text: &str) {
+        if !self.resolved_settings.collapse
+            || self.resolved_settings.delivery == DeliveryMode::Summary
+            || text.trim().is_empty()
+        {
+            return;
+        }
+        let Some(inbound) = &self.current_inbound else {
+            return;
+        };
+        let summary = self.text_with(
+            "collapse.reasoning",
+            &[("worker", &channel_plan::short_id(worker_id))],
+        );
+        let response = collapsed(&inbound.source, &summary, text);
+        if let Err(error) = self.send_routed(response).await {
+            tracing::warn!(%error, channel_id = %self.id, %worker_id, "failed to send worker reasoning");
+        }
+    }
+
+    /// Handle `/delivery [live|summary]`.
+    async fn handle_delivery_command(&mut self, argument: &str) -> String {
+        if argument.is_empty() {
+            return self.text(match self.resolved_settings.delivery {
+                DeliveryMode::Live => "delivery.show_live",
+                DeliveryMode::Summary => "delivery.show_summary",
+            });
+        }
+        match DeliveryMode::parse(argument) {
+            Some(DeliveryMode::Live) => {
+                self.set_delivery(DeliveryMode::Live).await;
+                self.text("delivery.live")
+            }
+            Some(DeliveryMode::Summary) => {
+                self.set_delivery(DeliveryMode::Summary).await;
+                self.text("delivery.summary")
+            }
+            None => self.text_with("delivery.unknown", &[("command", argument)]),
+        }
+    }
+
+    /// Handle `/debug [on [adapter:target]|off]`.
+    fn handle_debug_command(&mut self, argument: &str, message: &InboundMessage) -> String {
+        let (command, target) = argument
+            .split_once(char::is_whitespace)
+            .map_or((argument, ""), |(command, target)| (command, target.trim()));
+        let mirror = match (command, target) {
+            ("", _) => {
+                return match &self.debug_mirror {
+                    None => self.text("debug.show_off"),
+                    Some(DebugMirror::Here(_)) => self.text("debug.show_here"),
+                    Some(DebugMirror::Target(target)) => {
+                        self.text_with("debug.show_target", &[("target", &target.to_string())])
+                    }
+                };
+            }
+            ("on", "") => Some(DebugMirror::Here(message.clone())),
+            ("on", raw) => match crate::messaging::target::parse_delivery_target(raw) {
+                Some(target) => Some(DebugMirror::Target(target)),
+                None => return self.text_with("debug.bad_target", &[("target", raw)]),
+            },
+            ("off", "") => None,
+            _ => return self.text_with("debug.unknown", &[("command", argument)]),
+        };
+
+        self.state
+            .mirror_worker_events
+            .store(mirror.is_some(), std::sync::atomic::Ordering::Relaxed);
+        tracing::info!(
+            channel_id = %self.id,
+            enabled = mirror.is_some(),
+            "debug mirroring changed"
+        );
+        let body = match &mirror {
+            None => self.text("debug.off"),
+            Some(DebugMirror::Here(_)) => self.text("debug.on_here"),
+            Some(DebugMirror::Target(target)) => {
+                self.text_with("debug.on_target", &[("target", &target.to_string())])
+            }
+        };
+        self.debug_mirror = mirror;
+        body
+    }
+
+    /// Post a mirrored raw worker event. It isn't logged or added to
+    /// history: the mirror is for whoever is debugging, not for the model.
+    async fn post_debug_event(&self, worker_id: WorkerId, data: &str) {
+        let Some(mirror) = &self.debug_mirror else {
+            return;
+        };
+        let response =
+            OutboundResponse::Text(render_raw_event(&channel_plan::short_id(worker_id), data));
+        let result = match mirror {
+            DebugMirror::Here(target) => self
+                .response_tx
+                .send(RoutedResponse {
+                    response,
+                    target: target.clone(),
+                })
+                .await
+                .map_err(anyhow::Error::from),
+            DebugMirror::Target(target) => match &self.deps.messaging_manager {
+                Some(manager) => manager
+                    .broadcast(&target.adapter, &target.target, response)
+                    .await
+                    .map_err(anyhow::Error::from),
+                None => return,
+            },
+        };
+        if let Err(error) = result {
+            tracing::warn!(%error, channel_id = %self.id, %worker_id, "failed to mirror raw worker event");
+        }
+    }
+
+    /// Handle `/dryrun [on|off|default]`.
+    async fn handle_dry_run_command(&mut self, argument: &str) -> String {
+        let global = self.deps.runtime_config.opencode.load().dry_run;
+        let state_text = |channel: &Self, enabled: bool| {
+            channel.text(if enabled {
+                "dry_run.state_on"
+            } else {
+                "dry_run.state_off"
+            })
+        };
+        match argument {
+            "" => match self.resolved_settings.dry_run {
+                Some(enabled) => {
+                    self.text_with("dry_run.show", &[("state", &state_text(self, enabled))])
+                }
+                None => self.text_with(
+                    "dry_run.show_default",
+                    &[("state", &state_text(self, global))],
+                ),
+            },
+            "on" => {
+                self.set_dry_run(Some(true)).await;
+                self.text("dry_run.on")
+            }
+            "off" => {
+                self.set_dry_run(Some(false)).await;
+                self.text("dry_run.off")
+            }
+            "default" => {
+                self.set_dry_run(None).await;
+                self.text_with("dry_run.default", &[("state", &state_text(self, global))])
+            }
+            other => self.text_with("dry_run.unknown", &[("command", other)]),
+        }
+    }
+
+    /// Show, take, or release the driver lock.
+    fn handle_driver_command(
+        &mut self,
+        command: DriverCommand,
+        message: &InboundMessage,
+        access_tier: crate::access::AccessTier,
+    ) -> String {
+        let user_id = turn_requester(message).unwrap_or_default();
+        let is_admin = access_tier >= crate::access::AccessTier::Admin;
+        let current = self
+            .driver_lock
+            .as_ref()
+            .map(|lock| (lock.display_name.clone(), lock.is_driver(&user_id)));
+        match (command, current) {
+            (DriverCommand::Show, None) => self.text("driver.none"),
+            (DriverCommand::Show, Some((name, _))) => {
+                self.text_with("driver.show", &[("name", &name)])
+            }
+            (DriverCommand::Take, Some((name, false))) if !is_admin => {
+                self.text_with("driver.held_by_other", &[("name", &name)])
+            }
+            (DriverCommand::Take, _) => {
+                tracing::info!(channel_id = %self.id, driver = %user_id, "driver lock taken");
+                self.driver_lock = Some(DriverLock::new(
+                    user_id,
+                    message_display_name(message).to_string(),
+                ));
+                self.text("driver.taken")
+            }
+            (DriverCommand::Release, None) => self.text("driver.none"),
+            (DriverCommand::Release, Some((name, false))) if !is_admin => {
+                self.text_with("driver.not_driver", &[("name", &name)])
+            }
+            (DriverCommand::Release, Some(_)) => {
+                tracing::info!(channel_id = %self.id, released_by = %user_id, "driver lock released");
+                self.driver_lock = None;
+                self.text("driver.released")
+            }
+        }
+    }
+
+    /// Whether the driver lock keeps `message` from starting a turn. Builtin
+    /// and plugin commands, button clicks, system messages, the driver, and
+    /// admins pass through. `/run` and unhandled slash text are held, since
+    /// both would reach the model.
+    fn held_by_driver_lock(&self, message: &InboundMessage) -> bool {
+        let Some(lock) = &self.driver_lock else {
+            return false;
+        };
+        let text = match &message.content {
+            crate::MessageContent::Text(text) => text.as_str(),
+            crate::MessageContent::Media { text, .. } => text.as_deref().unwrap_or_default(),
+            crate::MessageContent::Interaction { .. } | crate::MessageContent::Deleted { .. } => {
+                return false;
+            }
+        };
+        let Some(user_id) = turn_requester(message) else {
+            return false;
+        };
+        !channel_driver::is_handled_command(text)
+            && !lock.is_driver(&user_id)
+            && crate::access::resolve_tier(self.deps.humans.load().as_ref(), message)
+                < crate::access::AccessTier::Admin
+    }
+
+    /// Keep a message held by the driver lock as context, telling its
+    /// author once who is driving.
+    async fn hold_for_driver(&mut self, message: InboundMessage) {
+        let raw_text = match &message.content {
+            crate::MessageContent::Text(text) => text.clone(),
+            crate::MessageContent::Media { text, .. } => text.clone().unwrap_or_default(),
+            _ => message.content.to_string(),
+        };
+        self.last_activity_at = Some(chrono::Utc::now());
+        self.persist_inbound_user_message(&message, &raw_text, None);
+        self.track_participant_from_message(&message).await;
+
+        let temporal_context = self.temporal_context();
+        let message_timestamp = temporal_context.format_timestamp(message.timestamp);
+        let user_text = format_user_message(&raw_text, &message, &message_timestamp);
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::User {
+                content: OneOrMany::one(UserContent::text(&user_text)),
+            });
+
+        let user_id = turn_requester(&message).unwrap_or_default();
+        tracing::debug!(channel_id = %self.id, %user_id, "message held by driver lock");
+        let notice = self.driver_lock.as_mut().and_then(|lock| {
+            lock.should_notify(&user_id)
+                .then(|| lock.display_name.clone())
+        });
+        if let Some(name) = notice {
+            self.send_private_notice(
+                &message,
+                self.text_with("driver.held", &[("name", &name)]),
+                "driver",
+            )
+            .await;
+        }
+    }
+
+    /// Persist the language of bot-authored messages for this channel.
+    async fn set_channel_locale(&mut self, locale: String) {
+        self.resolved_settings.locale = locale.clone();
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting locale"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.locale = Some(locale);
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist locale to channel_settings"
+                );
+            }
+        });
+    }
+
+    /// `/locale` shows the channel's language; `/locale <code>` sets it.
+    async fn handle_locale_command(&mut self, argument: &str) -> String {
+        let available = crate::prompts::messages::LOCALES.join(", ");
+        match argument {
+            "" => self.text_with(
+                "locale.show",
+                &[
+                    ("locale", &self.resolved_settings.locale),
+                    ("available", &available),
+                ],
+            ),
+            locale if crate::prompts::messages::is_supported(locale) => {
+                self.set_channel_locale(locale.to_string()).await;
+                self.text_with("locale.set", &[("locale", locale)])
+            }
+            other => self.text_with(
+                "locale.unknown",
+                &[("locale", other), ("available", &available)],
+            ),
+        }
+    }
+
+    /// Time context for prompts and chat, in the channel's timezone when
+    /// one is set.
+    fn temporal_context(&self) -> TemporalContext {
+        TemporalContext::for_channel(
+            self.deps.runtime_config.as_ref(),
+            self.resolved_settings.timezone.as_deref(),
+        )
+    }
+
+    /// Timestamp formatting for this channel's platform and timezone.
+    fn timestamps(&self) -> Timestamps {
+        let platform = self
+            .current_adapter()
+            .and_then(|adapter| adapter.split(':').next())
+            .unwrap_or_default();
+        Timestamps::new(platform, self.temporal_context().named_timezone())
+    }
+
+    /// Persist the channel's timezone; `None` goes back to the agent's.
+    async fn set_channel_timezone(&mut self, timezone: Option<String>) {
+        self.resolved_settings.timezone = timezone.clone();
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting timezone"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.timezone = timezone;
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist timezone to channel_settings"
+                );
+            }
+        });
+    }
+
+    /// Persist the channel's worker budget; `None` goes back to the agent's.
+    /// Workers already running keep the budget they started with.
+    async fn set_channel_worker_budget(&mut self, budget_secs: Option<u64>) {
+        self.resolved_settings.worker_budget_secs = budget_secs;
+        self.state.model_overrides = Arc::new(self.resolved_settings.clone());
+
+        let pool = self.deps.sqlite_pool.clone();
+        let agent_id = self.deps.agent_id.clone();
+        let channel_id: String = self.id.as_ref().to_owned();
+        tokio::spawn(async move {
+            let store = crate::conversation::ChannelSettingsStore::new(pool);
+            let mut settings = match store.get(&agent_id, &channel_id).await {
+                Ok(Some(existing)) => existing,
+                Ok(None) => crate::conversation::ConversationSettings::default(),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        "failed to load existing settings before persisting worker budget"
+                    );
+                    crate::conversation::ConversationSettings::default()
+                }
+            };
+            settings.worker_budget_secs = budget_secs;
+            if let Err(error) = store.upsert(&agent_id, &channel_id, &settings).await {
+                tracing::warn!(
+                    %error,
+                    %channel_id,
+                    "failed to persist worker budget to channel_settings"
+                );
+            }
+        });
+    }
+
+    /// `/budget` shows how long a worker from this channel may run,
+    /// `/budget <minutes>` sets it, and `/budget reset` goes back to the
+    /// agent's.
+    async fn handle_budget_command(&mut self, argument: &str) -> String {
+        let agent_minutes = (self
+            .deps
+            .runtime_config
+            .cortex
+            .load()
+            .worker_wall_clock_timeout_secs
+            / 60)
+            .to_string();
+        match argument {
+            "" => match self.resolved_settings.worker_budget_secs {
+                Some(budget_secs) => self.text_with(
+                    "budget.show",
+                    &[("minutes", &(budget_secs / 60).to_string())],
+                ),
+                None => self.text_with("budget.default", &[("minutes", &agent_minutes)]),
+            },
+            "reset" => {
+                self.set_channel_worker_budget(None).await;
+                self.text_with("budget.reset", &[("minutes", &agent_minutes)])
+            }
+            minutes => match minutes.parse::<u64>() {
+                Ok(minutes) if (1..=MAX_WORKER_BUDGET_MINUTES).contains(&minutes) => {
+                    self.set_channel_worker_budget(Some(minutes * 60)).await;
+                    self.text_with("budget.set", &[("minutes", &minutes.to_string())])
+                }
+                _ => self.text_with(
+                    "budget.invalid",
+                    &[("max", &MAX_WORKER_BUDGET_MINUTES.to_string())],
+                ),
+            },
+        }
+    }
+
+    /// `/timezone` shows the channel's timezone, `/timezone <zone>` sets it,
+    /// and `/timezone reset` goes back to the agent's.
+    async fn handle_timezone_command(&mut self, argument: &str) -> String {
+        match argument {
+            "" => {
+                let context = self.temporal_context();
+                self.text_with("timezone.show", &[("timezone", context.timezone_label())])
+            }
+            "reset" => {
+                self.set_channel_timezone(None).await;
+                let context = self.temporal_context();
+                self.text_with("timezone.reset", &[("timezone", context.timezone_label())])
+            }
+            timezone => match timezone.parse::<chrono_tz::Tz>() {
+                Ok(parsed) => {
+                    let name = parsed.name().to_string();
+                    self.set_channel_timezone(Some(name.clone())).await;
+                    self.text_with("timezone.set", &[("timezone", &name)])
+                }
+                Err(_) => self.text_with("timezone.unknown", &[("timezone", timezone)]),
+            },
+        }
+    }
+
+    /// `/stats tools [days]`: most-run tools with their average duration
+    /// and failure rate, in this channel and across the agent.
+    /// `/stats cost [day|week|month]`: the agent's spend per user and model.
+    async fn handle_stats_command(&self, argument: &str) -> String {
+        let mut words = argument.split_whitespace();
+        match words.next() {
+            Some("tools") => {
+                let days = match words.next().map(str::parse::<i64>) {
+                    None => STATS_DEFAULT_DAYS,
+                    Some(Ok(days)) if days > 0 => days.min(STATS_MAX_DAYS),
+                    Some(_) => return self.text("stats.usage"),
+                };
+                self.render_tool_stats(days).await
+            }
+            Some("cost") => {
+                let period = match words.next() {
+                    None => crate::cost_report::CostPeriod::Week,
+                    Some(raw) => match crate::cost_report::CostPeriod::parse(raw) {
+                        Some(period) => period,
+                        None => return self.text("stats.usage"),
+                    },
+                };
+                match crate::cost_report::cost_breakdown(
+                    &self.deps.sqlite_pool,
+                    &self.deps.agent_id,
+                    period.hours(),
+                )
+                .await
+                {
+                    Ok(breakdown) => crate::cost_report::render(
+                        &breakdown,
+                        period,
+                        &self.resolved_settings.locale,
+                    ),
+                    Err(error) => {
+                        tracing::warn!(%error, channel_id = %self.id, "failed to read cost report");
+                        self.text("stats.failed")
+                    }
+                }
+            }
+            Some(other) => self.text_with("stats.unknown", &[("command", other)]),
+            None => self.text("stats.usage"),
+        }
+    }
+
+    async fn render_tool_stats(&self, days: i64) -> String {
+        let stats = crate::tool_stats::ToolStats::new(self.deps.sqlite_pool.clone());
+        let channel_id: &str = self.id.as_ref();
+        let (channel, global) = match tokio::try_join!(
+            stats.summary(
+                &self.deps.agent_id,
+                Some(channel_id),
+                days,
+                STATS_TOOL_LIMIT
+            ),
+            stats.summary(&self.deps.agent_id, None, days, STATS_TOOL_LIMIT),
+        ) {
+            Ok(summaries) => summaries,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to read tool stats");
+                return self.text("stats.failed");
+            }
+        };
+
+        let days = days.to_string();
+        let mut lines = vec![self.text_with("stats.tools_header", &[("days", &days)])];
+        for (heading, summaries) in [
+            ("stats.tools_channel", &channel),
+            ("stats.tools_global", &global),
+        ] {
+            lines.push(self.text(heading));
+            if summaries.is_empty() {
+                lines.push(self.text("stats.tools_none"));
+            }
+            for summary in summaries {
+                lines.push(self.text_with(
+                    "stats.tool_line",
+                    &[
+                        ("tool", &summary.tool_name),
+                        ("runs", &summary.runs.to_string()),
+                        (
+                            "average",
+                            &crate::tool_stats::format_duration_ms(summary.average_duration_ms),
+                        ),
+                        ("failure_rate", &format!("{:.0}", summary.failure_rate())),
+                        ("failures", &summary.failures.to_string()),
+                    ],
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// `/search <query>`: past transcripts from this channel matching `query`.
+    async fn handle_search_command(&self, query: &str) -> String {
+        if query.is_empty() {
+            return self.text("search.usage");
+        }
+        let search = crate::conversation::TranscriptSearch::new(self.deps.sqlite_pool.clone());
+        let hits = match search
+            .search(self.id.as_ref(), query, SEARCH_RESULT_LIMIT)
+            .await
+        {
+            Ok(hits) => hits,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to search transcripts");
+                return self.text("search.failed");
+            }
+        };
+        if hits.is_empty() {
+            return self.text_with("search.none", &[("query", query)]);
+        }
+
+        let timestamps = self.timestamps();
+        let mut lines = vec![self.text_with("search.header", &[("query", query)])];
+        for hit in &hits {
+            let mut line = format!(
+                "- {} worker `{}`",
+                hit.started_at
+                    .map(|at| timestamps.format(at, TimestampStyle::DateTime))
+                    .unwrap_or_default(),
+                hit.worker_id.chars().take(8).collect::<String>()
+            );
+            if let Some(session_id) = &hit.opencode_session_id {
+                line.push_str(&format!(" session `{session_id}`"));
+            }
+            line.push_str(&format!(
+                ": {}",
+                crate::summarize_first_non_empty_line(&hit.task, crate::EVENT_SUMMARY_MAX_CHARS)
+            ));
+            lines.push(line);
+            lines.push(format!("  > {}", hit.snippet.replace('\n', " ")));
+            match (&hit.message_link, &hit.message_id) {
+                (Some(link), _) => lines.push(format!("  {link}")),
+                (None, Some(message_id)) => lines.push(format!("  message {message_id}")),
+                (None, None) => {}
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// `/admin maintenance [on [notice]|off]` and `/admin broadcast <text>`.
+    fn handle_admin_command(&self, argument: &str) -> String {
+        let controls = crate::maintenance::AdminControls::global();
+        let (command, rest) = argument.split_once(' ').unwrap_or((argument, ""));
+        match command {
+            "maintenance" => {
+                let (toggle, notice) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+                match toggle {
+                    "" => match controls.status() {
+                        Some(notice) => self.text_with(
+                            "admin.maintenance_status_on",
+                            &[(
+                                "notice",
+                                &crate::maintenance::paused_message(
+                                    notice,
+                                    &self.resolved_settings.locale,
+                                ),
+                            )],
+                        ),
+                        None => self.text("admin.maintenance_status_off"),
+                    },
+                    "on" => {
+                        controls.enable(Some(notice.trim().to_string()));
+                        tracing::info!(channel_id = %self.id, "maintenance mode enabled");
+                        self.text("admin.maintenance_on")
+                    }
+                    "off" => {
+                        if controls.disable() {
+                            tracing::info!(channel_id = %self.id, "maintenance mode disabled");
+                        }
+                        self.text("admin.maintenance_off")
+                    }
+                    other => self.text_with("admin.unknown", &[("command", other)]),
+                }
+            }
+            "broadcast" if !rest.trim().is_empty() => {
+                if controls.broadcast(rest.trim().to_string()) {
+                    self.text("admin.broadcast_sent")
+                } else {
+                    self.text("admin.broadcast_failed")
+                }
+            }
+            other => self.text_with("admin.unknown", &[("command", other)]),
+        }
+    }
+
+    /// `/admin abort-all [backend]` arms an abort of every busy session;
+    /// `/admin abort-all confirm` or `cancel` settles it.
+    async fn handle_abort_all_command(&mut self, message: &InboundMessage, argument: &str) {
+        let requester = format!("{}:{}", message.source, message.sender_id);
+        let body = match argument {
+            "confirm" => self.confirm_abort_all(&requester),
+            "cancel" => match self.pending_abort_all.take() {
+                Some(_) => self.text("admin.abort_all_cancelled"),
+                None => self.text("admin.abort_all_none"),
+            },
+            backend => {
+                let backend = (!backend.is_empty()).then(|| backend.to_string());
+                if let Some(backend) = &backend
+                    && !self
+                        .deps
+                        .runtime_config
+                        .opencode_server_pool
+                        .load()
+                        .has_backend(backend)
+                {
+                    let body =
+                        self.text_with("admin.abort_all_unknown_backend", &[("backend", backend)]);
+                    self.send_builtin_text(body, "admin").await;
+                    return;
+                }
+                let seconds = channel_abort::CONFIRM_WINDOW.as_secs().to_string();
+                let text = match &backend {
+                    Some(backend) => self.text_with(
+                        "admin.abort_all_confirm_backend",
+                        &[("backend", backend), ("seconds", &seconds)],
+                    ),
+                    None => self.text_with("admin.abort_all_confirm", &[("seconds", &seconds)]),
+                };
+                self.pending_abort_all = Some(PendingAbortAll::new(backend, requester));
+                match self
+                    .send_routed(channel_abort::confirmation_message(text.clone()))
+                    .await
+                {
+                    Ok(()) => self.state.conversation_logger.log_bot_message_with_name(
+                        &self.state.channel_id,
+                        &text,
+                        Some(self.agent_display_name()),
+                    ),
+                    Err(error) => {
+                        tracing::error!(%error, channel_id = %self.id, "failed to post abort-all confirmation");
+                    }
+                }
+                return;
+            }
+        };
+        self.send_builtin_text(body, "admin").await;
+    }
+
+    /// Send the armed abort-all to every channel, if `requester` armed it
+    /// and it hasn't expired.
+    fn confirm_abort_all(&mut self, requester: &str) -> String {
+        let pending = match self.pending_abort_all.take() {
+            None => return self.text("admin.abort_all_none"),
+            Some(pending) if pending.is_expired(std::time::Instant::now()) => {
+                return self.text("admin.abort_all_expired");
+            }
+            Some(pending) if pending.requested_by != requester => {
+                self.pending_abort_all = Some(pending);
+                return self.text("admin.abort_all_not_requester");
+            }
+            Some(pending) => pending,
+        };
+        tracing::warn!(
+            channel_id = %self.id,
+            backend = ?pending.backend,
+            requested_by = %pending.requested_by,
+            "admin abort-all confirmed"
+        );
+        if !crate::maintenance::AdminControls::global().abort_all(pending.backend.clone()) {
+            return self.text("admin.abort_all_failed");
+        }
+        match &pending.backend {
+            Some(backend) => {
+                self.text_with("admin.abort_all_sent_backend", &[("backend", backend)])
+            }
+            None => self.text("admin.abort_all_sent"),
+        }
+    }
+
+    /// Abort this channel's busy worker sessions, or only those on
+    /// `backend`, for a confirmed `/admin abort-all`. OpenCode sessions are
+    /// aborted on the server as well, so nothing keeps editing files after
+    /// the worker is gone. Idle sessions are left alone.
+    async fn abort_busy_sessions(&mut self, backend: Option<&str>) {
+        let busy: Vec<WorkerId> = {
+            let status = self.state.status_block.read().await;
+            status
+                .active_workers
+                .iter()
+                .filter(|worker| worker.status != "idle")
+                .map(|worker| worker.id)
+                .collect()
+        };
+        let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+        let mut aborted = 0;
+        for worker_id in busy {
+            let session = self.opencode_sessions.get(&worker_id).cloned();
+            if let Some(backend) = backend {
+                let label = match &session {
+                    Some((_, port)) => server_pool.backend_label(*port).await,
+                    None => None,
+                };
+                if label.as_deref() != Some(backend) {
+                    continue;
+                }
+            }
+            if let Some((session_id, port)) = &session
+                && let Some(server) = server_pool.server_for_port(*port).await
+                && let Err(error) = server.lock().await.abort_session(session_id).await
+            {
+                tracing::warn!(%error, channel_id = %self.id, %worker_id, "failed to abort OpenCode session");
+            }
+            if self
+                .state
+                .cancel_worker_with_reason(worker_id, "aborted by an admin (/admin abort-all)")
+                .await
+                .is_ok()
+            {
+                aborted += 1;
+            }
+        }
+        if aborted == 0 {
+            return;
+        }
+        tracing::info!(channel_id = %self.id, aborted, ?backend, "aborted busy sessions");
+        if self.current_inbound.is_some() {
+            let body = self.text_with("admin.abort_all_notice", &[("count", &aborted.to_string())]);
+            self.send_builtin_text(body, "admin").await;
+        }
+    }
+
+    /// Approve or reject a pending plan, from a button click or `/approve`
+    /// and `/reject`. Approval sends the build prompt to the worker's
+    /// session; rejection cancels the worker.
+    async fn handle_plan_decision(&mut self, decision: PlanDecision, requested: Option<&str>) {
+        let worker_id = match channel_plan::resolve_pending_plan(&self.pending_plans, requested) {
+            Ok(worker_id) => worker_id,
+            Err(body) => {
+                self.send_builtin_text(body, "plan").await;
+                return;
+            }
+        };
+        self.pending_plans.retain(|pending| *pending != worker_id);
+        tracing::info!(
+            channel_id = %self.id,
+            %worker_id,
+            decision = decision.as_str(),
+            "plan decision received"
+        );
+
+        let body = match decision {
+            PlanDecision::Approve => {
+                let input_tx = self
+                    .state
+                    .worker_inputs
+                    .read()
+                    .await
+                    .get(&worker_id)
+                    .cloned();
+                match input_tx {
+                    Some(input_tx)
+                        if input_tx
+                            .send(crate::opencode::worker::PLAN_APPROVAL_PROMPT.to_string())
+                            .await
+                            .is_ok() =>
+                    {
+                        format!("plan approved. worker {worker_id} is building it.")
+                    }
+                    _ => format!("worker {worker_id} is no longer running."),
+                }
+            }
+            PlanDecision::Reject => {
+                match self
+                    .state
+                    .cancel_worker_with_reason(worker_id, "plan rejected")
+                    .await
+                {
+                    Ok(()) => format!("plan rejected. worker {worker_id} cancelled."),
+                    Err(error) => format!("couldn't cancel worker {worker_id}: {error}"),
+                }
+            }
+        };
+        self.send_builtin_text(body, "plan").await;
+    }
+
+    /// Post a worker's question and wait for the next reply to answer it.
+    /// Posted as-is so the options the user picks from are the ones
+    /// OpenCode offered.
+    async fn post_worker_question(
+        &mut self,
+        worker_id: WorkerId,
+        question_id: &str,
+        questions: &[crate::opencode::QuestionInfo],
+    ) {
+        let timeout = self
+            .deps
+            .runtime_config
+            .opencode
+            .load()
+            .question_timeout_secs;
+        // With no time to wait, the worker has already picked the first option.
+        if timeout == 0 {
+            return;
+        }
+        self.pending_questions
+            .retain(|pending| pending.worker_id != worker_id);
+        self.pending_questions
+            .push(channel_question::PendingQuestion {
+                worker_id,
+                question_id: question_id.to_string(),
+                questions: questions.to_vec(),
+                expires_at: std::time::Instant::now() + std::time::Duration::from_secs(timeout),
+            });
+
+        let text = channel_question::question_text(worker_id, questions);
+        match self.send_routed(OutboundResponse::Text(text.clone())).await {
+            Ok(()) => {
+                self.state.conversation_logger.log_bot_message_with_name(
+                    &self.state.channel_id,
+                    &text,
+                    Some(self.agent_display_name()),
+                );
+                self.state
+                    .history
+                    .write()
+                    .await
+                    .push(rig::message::Message::Assistant {
+                        id: None,
+                        content: OneOrMany::one(rig::message::AssistantContent::text(format!(
+                            "[Background worker {worker_id} question]: {text}"
+                        ))),
+                    });
+            }
+            Err(error) => {
+                tracing::error!(%error, channel_id = %self.id, %worker_id, "failed to post worker question");
+            }
+        }
+    }
+
+    /// Post a worker's permission request for an admin to allow or deny.
+    async fn post_permission_request(
+        &mut self,
+        worker_id: WorkerId,
+        session_id: &str,
+        permission_id: &str,
+        description: &str,
+    ) {
+        let timeout = self
+            .deps
+            .runtime_config
+            .opencode
+            .load()
+            .question_timeout_secs;
+        self.pending_permissions
+            .push(channel_permission::PendingPermission {
+                worker_id,
+                session_id: session_id.to_string(),
+                permission_id: permission_id.to_string(),
+                description: description.to_string(),
+                expires_at: std::time::Instant::now() + std::time::Duration::from_secs(timeout),
+            });
+
+        let response =
+            channel_permission::permission_message(worker_id, permission_id, description);
+        match self.send_routed(response).await {
+            Ok(()) => {
+                self.state.conversation_logger.log_bot_message_with_name(
+                    &self.state.channel_id,
+                    &channel_permission::permission_text(worker_id, description),
+                    Some(self.agent_display_name()),
+                );
+            }
+            Err(error) => {
+                tracing::error!(%error, channel_id = %self.id, %worker_id, "failed to post permission request");
+            }
+        }
+    }
+
+    /// Send an admin's decision on a pending permission request to the
+    /// worker's session.
+    async fn decide_permission(
+        &mut self,
+        message: &InboundMessage,
+        index: usize,
+        decision: PermissionDecision,
+    ) {
+        let pending = self.pending_permissions.remove(index);
+        let worker_id = pending.worker_id;
+        let server = match self.opencode_sessions.get(&worker_id) {
+            Some((_, port)) => {
+                let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+                server_pool.server_for_port(*port).await
+            }
+            None => None,
+        };
+        let body = match server {
+            Some(server) => match server
+                .lock()
+                .await
+                .reply_permission(
+                    &pending.session_id,
+                    &pending.permission_id,
+                    decision.reply(),
+                )
+                .await
+            {
+                Ok(()) => {
+                    tracing::info!(
+                        channel_id = %self.id,
+                        %worker_id,
+                        decision = decision.as_str(),
+                        "worker permission answered from chat"
+                    );
+                    let summary = match decision {
+                        PermissionDecision::Allow => "allowed once",
+                        PermissionDecision::Deny => "denied",
+                    };
+                    crate::audit::AuditLog::new(self.deps.sqlite_pool.clone()).log(
+                        &self.deps.agent_id,
+                        crate::audit::AuditEntry::new(
+                            crate::audit::AuditKind::Permission,
+                            format!("{}:{}", message.source, message.sender_id),
+                            format!("worker {worker_id} {summary}: {}", pending.description),
+                        )
+                        .with_channel(self.id.to_string()),
+                    );
+                    format!("worker {worker_id} {summary}: {}", pending.description)
+                }
+                Err(error) => {
+                    tracing::warn!(%error, channel_id = %self.id, %worker_id, "failed to answer worker permission");
+                    format!("couldn't answer worker {worker_id}'s permission request: {error}")
+                }
+            },
+            None => format!("worker {worker_id} is no longer running."),
+        };
+        self.send_builtin_text(body, "permission").await;
+    }
+
+    /// Send `reply` as the answer to the oldest pending worker question.
+    async fn answer_pending_question(&mut self, reply: &str) {
+        let pending = &self.pending_questions[0];
+        let answers = match channel_question::parse_answers(&pending.questions, reply) {
+            Ok(answers) => answers,
+            Err(body) => {
+                self.send_builtin_text(body, "question").await;
+                return;
+            }
+        };
+        let pending = self.pending_questions.remove(0);
+        let worker_id = pending.worker_id;
+        let summary = answers
+            .iter()
+            .map(|answer| answer.label.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let server = match self.opencode_sessions.get(&worker_id) {
+            Some((_, port)) => {
+                let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+                server_pool.server_for_port(*port).await
+            }
+            None => None,
+        };
+        let body = match server {
+            Some(server) => match server
+                .lock()
+                .await
+                .reply_question(&pending.question_id, answers)
+                .await
+            {
+                Ok(()) => {
+                    tracing::info!(channel_id = %self.id, %worker_id, "worker question answered from chat");
+                    self.state
+                        .history
+                        .write()
+                        .await
+                        .push(rig::message::Message::User {
+                            content: OneOrMany::one(UserContent::text(format!(
+                                "[Answer to background worker {worker_id} question]: {summary}"
+                            ))),
+                        });
+                    format!("answered worker {worker_id}: {summary}")
+                }
+                Err(error) => {
+                    tracing::warn!(%error, channel_id = %self.id, %worker_id, "failed to answer worker question");
+                    format!("couldn't answer worker {worker_id}'s question: {error}")
+                }
+            },
+            None => format!("worker {worker_id} is no longer running."),
+        };
+        self.send_builtin_text(body, "question").await;
+    }
+
+    /// Idle OpenCode sessions in this channel whose worker id starts with
+    /// `prefix`, as (worker, session id, server port).
+    async fn idle_opencode_sessions(&self, prefix: &str) -> Vec<(WorkerId, String, u16)> {
+        let status = self.state.status_block.read().await;
+        status
+            .active_workers
+            .iter()
+            .filter(|worker| worker.status == "idle" && worker.id.to_string().starts_with(prefix))
+            .filter_map(|worker| {
+                self.opencode_sessions
+                    .get(&worker.id)
+                    .map(|(session_id, port)| (worker.id, session_id.clone(), *port))
+            })
+            .collect()
+    }
+
+    /// Run `task` in the background and post the text it resolves to, for
+    /// builtin commands whose result takes longer than a reply should.
+    fn spawn_background_reply(
+        &self,
+        task: impl std::future::Future<Output = String> + Send + 'static,
+    ) {
+        let response_tx = self.response_tx.clone();
+        let target = self
+            .current_inbound
+            .clone()
+            .unwrap_or_else(InboundMessage::empty);
+        let conversation_logger = self.state.conversation_logger.clone();
+        let channel_id = self.state.channel_id.clone();
+        let sender_name = self.agent_display_name().to_string();
+        tokio::spawn(async move {
+            let text = task.await;
+            let routed = RoutedResponse {
+                response: OutboundResponse::Text(text.clone()),
+                target,
+            };
+            if response_tx.send(routed).await.is_ok() {
+                conversation_logger.log_bot_message_with_name(
+                    &channel_id,
+                    &text,
+                    Some(&sender_name),
+                );
+            }
+        });
+    }
+
+    /// Handle `/compact [worker]`: summarize an idle OpenCode worker's
+    /// session in the background, then post the summary and refresh the
+    /// worker's stored transcript.
+    async fn handle_compact_command(&self, argument: &str) -> String {
+        let idle_sessions = self.idle_opencode_sessions(argument).await;
+        let (worker_id, session_id, port) = match idle_sessions.as_slice() {
+            [session] => session.clone(),
+            [] if argument.is_empty() => {
+                return "no idle opencode session to compact. sessions can be compacted while they wait for input."
+                    .to_string();
+            }
+            [] => return format!("no idle opencode session for worker '{argument}'."),
+            _ if argument.is_empty() => {
+                return format!(
+                    "{} idle sessions. name one with /compact <worker>.",
+                    idle_sessions.len()
+                );
+            }
+            _ => return format!("'{argument}' matches more than one worker."),
+        };
+
+        let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+        let Some(server) = server_pool.server_for_port(port).await else {
+            return format!("the opencode server for worker {worker_id} is no longer running.");
+        };
+
+        let pool = self.deps.sqlite_pool.clone();
+        self.spawn_background_reply(async move {
+            match crate::opencode::compact::compact_session(&server, &session_id).await {
+                Ok(compaction) => {
+                    let blob = crate::conversation::worker_transcript::serialize_steps(
+                        &compaction.transcript,
+                    );
+                    if let Err(error) =
+                        sqlx::query("UPDATE worker_runs SET transcript = ? WHERE id = ?")
+                            .bind(&blob)
+                            .bind(worker_id.to_string())
+                            .execute(&pool)
+                            .await
+                    {
+                        tracing::warn!(
+                            %error,
+                            %worker_id,
+                            "failed to persist compacted transcript"
+                        );
+                    }
+                    format!(
+                        "compacted worker {worker_id}'s session. summary:\n\n{}",
+                        compaction.summary
+                    )
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %worker_id,
+                        %session_id,
+                        "failed to compact OpenCode session"
+                    );
+                    format!("couldn't compact worker {worker_id}'s session: {error}")
+                }
+            }
+        });
+
+        format!("compacting worker {worker_id}'s opencode session. the summary will follow.")
+    }
+
+    /// Handle `/attach <session_id> [directory]`: take over an OpenCode
+    /// session started elsewhere as an idle worker, and post a summary of its
+    /// recent messages. The directory defaults to the channel's project.
+    async fn handle_attach_command(&self, argument: &str) -> String {
+        let mut words = argument.split_whitespace();
+        let Some(session_id) = words.next() else {
+            return self.text("attach.usage");
+        };
+        let directory = words.collect::<Vec<_>>().join(" ");
+        let directory = if directory.is_empty() {
+            match &self.resolved_settings.project_directory {
+                Some(directory) => directory.clone(),
+                None => return self.text("attach.no_directory"),
+            }
+        } else {
+            directory
+        };
+        if let Some(worker_id) = self
+            .opencode_sessions
+            .iter()
+            .find(|(_, (attached, _))| attached == session_id)
+            .map(|(worker_id, _)| worker_id)
+        {
+            return self.text_with(
+                "attach.already",
+                &[
+                    ("session", session_id),
+                    ("worker", &channel_plan::short_id(*worker_id)),
+                ],
+            );
+        }
+
+        let (worker_id, messages) = match crate::agent::channel_dispatch::attach_opencode_session(
+            &self.state,
+            session_id,
+            &directory,
+        )
+        .await
+        {
+            Ok(attached) => attached,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, %session_id, "failed to attach OpenCode session");
+                return self.text_with(
+                    "attach.failed",
+                    &[("session", session_id), ("error", &error)],
+                );
+            }
+        };
+        let worker = channel_plan::short_id(worker_id);
+        let placeholders = [("session", session_id), ("worker", worker.as_str())];
+        match crate::opencode::attach::summarize_recent(
+            &messages,
+            crate::opencode::attach::SUMMARY_MESSAGES,
+        ) {
+            Some(summary) => {
+                self.push_context_into_history(&format!(
+                    "[OpenCode session {session_id} attached as worker {worker_id}. Recent messages]:\n{summary}"
+                ))
+                .await;
+                format!(
+                    "{}\n{summary}",
+                    self.text_with("attach.attached", &placeholders)
+                )
+            }
+            None => self.text_with("attach.attached_empty", &placeholders),
+        }
+    }
+
+    /// Handle `/detach [worker]`: unbind the channel's idle OpenCode session
+    /// without deleting it, so it can be attached again later.
+    async fn handle_detach_command(&mut self, argument: &str) -> String {
+        let idle_sessions = self.idle_opencode_sessions(argument).await;
+        let (worker_id, session_id, _) = match idle_sessions.as_slice() {
+            [session] => session.clone(),
+            [] if argument.is_empty() => return self.text("detach.no_session"),
+            [] => return self.text_with("detach.no_match", &[("worker", argument)]),
+            _ => {
+                return self.text_with(
+                    "detach.many_sessions",
+                    &[("count", &idle_sessions.len().to_string())],
+                );
+            }
+        };
+        let worker = channel_plan::short_id(worker_id);
+        if let Err(error) = self.state.detach_worker(worker_id).await {
+            tracing::warn!(%error, channel_id = %self.id, %worker_id, "failed to detach OpenCode session");
+            return self.text_with(
+                "detach.failed",
+                &[("session", &session_id), ("error", &error)],
+            );
+        }
+        self.opencode_sessions.remove(&worker_id);
+        self.push_context_into_history(&format!(
+            "[OpenCode session {session_id} detached from worker {worker_id}.]"
+        ))
+        .await;
+        self.text_with(
+            "detach.detached",
+            &[("session", &session_id), ("worker", &worker)],
+        )
+    }
+
+    /// Handle `/rebind <session_id> [directory]`: swap the channel's idle
+    /// OpenCode session for another one. With no session bound it attaches.
+    async fn handle_rebind_command(&mut self, argument: &str) -> String {
+        let mut words = argument.split_whitespace();
+        let Some(session_id) = words.next() else {
+            return self.text("rebind.usage");
+        };
+        let directory = words.collect::<Vec<_>>().join(" ");
+        let directory = if directory.is_empty() {
+            match &self.resolved_settings.project_directory {
+                Some(directory) => directory.clone(),
+                None => return self.text("attach.no_directory"),
+            }
+        } else {
+            directory
+        };
+        if let Some(worker_id) = self
+            .opencode_sessions
+            .iter()
+            .find(|(_, (bound, _))| bound == session_id)
+            .map(|(worker_id, _)| worker_id)
+        {
+            return self.text_with(
+                "attach.already",
+                &[
+                    ("session", session_id),
+                    ("worker", &channel_plan::short_id(worker_id)),
+                ],
+            );
+        }
+
+        let idle_sessions = self.idle_opencode_sessions("").await;
+        let (previous_worker, previous_session) = match idle_sessions.as_slice() {
+            [] => return self.handle_attach_command(argument).await,
+            [(worker_id, session_id, _)] => (*worker_id, session_id.clone()),
+            _ => {
+                return self.text_with(
+                    "rebind.many_sessions",
+                    &[("count", &idle_sessions.len().to_string())],
+                );
+            }
+        };
+
+        let (worker_id, messages) = match crate::agent::channel_dispatch::rebind_opencode_session(
+            &self.state,
+            previous_worker,
+            session_id,
+            &directory,
+        )
+        .await
+        {
+            Ok(rebound) => rebound,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, %session_id, "failed to rebind OpenCode session");
+                return self.text_with(
+                    "rebind.failed",
+                    &[
+                        ("session", session_id),
+                        ("previous", &previous_session),
+                        ("error", &error),
+                    ],
+                );
+            }
+        };
+        self.opencode_sessions.remove(&previous_worker);
+
+        let worker = channel_plan::short_id(worker_id);
+        let placeholders = [
+            ("previous", previous_session.as_str()),
+            ("session", session_id),
+            ("worker", worker.as_str()),
+        ];
+        match crate::opencode::attach::summarize_recent(
+            &messages,
+            crate::opencode::attach::SUMMARY_MESSAGES,
+        ) {
+            Some(summary) => {
+                self.push_context_into_history(&format!(
+                    "[OpenCode session {previous_session} detached. Session {session_id} attached as worker {worker_id}. Recent messages]:\n{summary}"
+                ))
+                .await;
+                format!(
+                    "{}\n{summary}",
+                    self.text_with("rebind.rebound", &placeholders)
+                )
+            }
+            None => {
+                self.push_context_into_history(&format!(
+                    "[OpenCode session {previous_session} detached. Session {session_id} attached as worker {worker_id}.]"
+                ))
+                .await;
+                self.text_with("rebind.rebound_empty", &placeholders)
+            }
+        }
+    }
+
+    /// Handle `/oc [list | <command> [args]]`: list the OpenCode server's
+    /// named commands, or run one in the channel's idle session and post its
+    /// reply once it finishes.
+    async fn handle_oc_command(&self, command: crate::opencode::command::OcCommand) -> String {
+        let idle_sessions = self.idle_opencode_sessions("").await;
+        let (worker_id, session_id, port) = match idle_sessions.as_slice() {
+            [session] => session.clone(),
+            [] => return self.text("oc.no_session"),
+            _ => {
+                return self.text_with(
+                    "oc.many_sessions",
+                    &[("count", &idle_sessions.len().to_string())],
+                );
+            }
+        };
+        let worker = channel_plan::short_id(worker_id);
+
+        let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+        let Some(server) = server_pool.server_for_port(port).await else {
+            return self.text_with("oc.server_gone", &[("worker", &worker)]);
+        };
+        let commands = match server.lock().await.list_commands().await {
+            Ok(commands) => commands,
+            Err(error) => {
+                tracing::warn!(%error, %worker_id, "failed to list OpenCode commands");
+                return self.text_with("oc.list_failed", &[("error", &error.to_string())]);
+            }
+        };
+
+        let (name, arguments) = match command {
+            crate::opencode::command::OcCommand::List if commands.is_empty() => {
+                return self.text_with("oc.list_empty", &[("worker", &worker)]);
+            }
+            crate::opencode::command::OcCommand::List => {
+                return format!(
+                    "{}\n{}",
+                    self.text_with("oc.list_header", &[("worker", &worker)]),
+                    crate::opencode::command::render_commands(&commands)
+                );
+            }
+            crate::opencode::command::OcCommand::Run { name, arguments } => (name, arguments),
+        };
+        if !commands.iter().any(|command| command.name == name) {
+            let available = commands
+                .iter()
+                .map(|command| format!("/{}", command.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return self.text_with(
+                "oc.unknown",
+                &[("command", &name), ("available", &available)],
+            );
+        }
+
+        let locale = self.resolved_settings.locale.clone();
+        let running = self.text_with("oc.running", &[("command", &name), ("worker", &worker)]);
+        self.spawn_background_reply(async move {
+            let placeholders = [("command", name.as_str()), ("worker", worker.as_str())];
+            match crate::opencode::command::run_command(&server, &session_id, &name, &arguments)
+                .await
+            {
+                Ok(Some(reply)) => format!(
+                    "{}\n\n{reply}",
+                    crate::prompts::messages::get_with(&locale, "oc.finished", &placeholders)
+                ),
+                Ok(None) => {
+                    crate::prompts::messages::get_with(&locale, "oc.no_output", &placeholders)
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %worker_id,
+                        %session_id,
+                        command = %name,
+                        "failed to run OpenCode command"
+                    );
+                    let error = error.to_string();
+                    let mut placeholders = placeholders.to_vec();
+                    placeholders.push(("error", &error));
+                    crate::prompts::messages::get_with(&locale, "oc.failed", &placeholders)
+                }
+            }
+        });
+        running
+    }
+
+    /// Transcribe a Telegram or Discord voice note with `[agents.transcribe]`
+    /// and return the prompt to submit in its place. Returns `None` when the
+    /// message has no audio, transcription isn't configured, or any clip
+    /// fails, leaving the audio to the `routing.voice` path.
+    #[cfg(feature = "transcribe")]
+    async fn transcribe_voice_notes(
+        &mut self,
+        message: &InboundMessage,
+        caption: &str,
+        attachments: &[crate::Attachment],
+    ) -> Option<String> {
+        if !crate::transcribe::transcribes_source(&message.source) {
+            return None;
+        }
+        let transcribe_config = self.deps.runtime_config.transcribe.load_full();
+        let config = transcribe_config.as_ref().as_ref()?;
+        let audio: Vec<&crate::Attachment> = attachments
+            .iter()
+            .filter(|attachment| attachment.mime_type.starts_with("audio/"))
+            .collect();
+        if audio.is_empty() {
+            return None;
+        }
+
+        let http = self.deps.llm_manager.http_client();
+        let mut transcriptions = Vec::with_capacity(audio.len());
+        for attachment in audio {
+            let result =
+                match channel_attachments::download_attachment_bytes(http, attachment).await {
+                    Ok(bytes) => {
+                        crate::transcribe::transcribe(
+                            http,
+                            config,
+                            bytes,
+                            &attachment.filename,
+                            &attachment.mime_type,
+                        )
+                        .await
+                    }
+                    Err(error) => Err(anyhow::anyhow!("download failed: {error}")),
+                };
+            match result {
+                Ok(transcription) => transcriptions.push(transcription),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        channel_id = %self.id,
+                        filename = %attachment.filename,
+                        "failed to transcribe voice note, falling back to routing.voice"
+                    );
+                    return None;
+                }
+            }
+        }
+
+        if config.echo {
+            self.send_builtin_text(crate::transcribe::echo_text(&transcriptions), "transcribe")
+                .await;
+        }
+        Some(crate::transcribe::prompt_text(&transcriptions, caption))
+    }
+
+    /// All prompt templates available to `/run`: saved ones first, then
+    /// those from config that no saved template replaces.
+    async fn prompt_templates(&self) -> Vec<crate::templates::PromptTemplate> {
+        let saved = crate::templates::TemplateStore::new(self.deps.sqlite_pool.clone())
+            .list(&self.deps.agent_id)
+            .await
+            .unwrap_or_else(|error| {
+                tracing::warn!(%error, "failed to load saved prompt templates");
+                Vec::new()
+            });
+        crate::templates::merge(&self.deps.runtime_config.templates.load(), saved)
+    }
+
+    /// Handle `/templates`: list templates with their `/run` usage.
+    async fn handle_templates_command(&self) -> String {
+        let templates = self.prompt_templates().await;
+        if templates.is_empty() {
+            return "no prompt templates. add one with /template add <name> <prompt>.".to_string();
+        }
+        let mut lines = vec!["templates:".to_string()];
+        for template in &templates {
+            let placeholders = crate::templates::placeholders(&template.prompt).unwrap_or_default();
+            let usage = crate::templates::usage(template, &placeholders);
+            lines.push(match &template.description {
+                Some(description) => format!("- {usage}: {description}"),
+                None => format!("- {usage}"),
+            });
+        }
+        lines.join("\n")
+    }
+
+    /// Handle `/template add <name> <prompt>` and `/template remove <name>`.
+    async fn handle_template_command(&self, argument: &str) -> String {
+        let store = crate::templates::TemplateStore::new(self.deps.sqlite_pool.clone());
+        let (operation, rest) = argument
+            .split_once(char::is_whitespace)
+            .map(|(operation, rest)| (operation, rest.trim()))
+            .unwrap_or((argument, ""));
+        match operation {
+            "add" => {
+                let Some((name, prompt)) = rest
+                    .split_once(char::is_whitespace)
+                    .map(|(name, prompt)| (name, prompt.trim()))
+                    .filter(|(_, prompt)| !prompt.is_empty())
+                else {
+                    return "usage: /template add <name> <prompt with {{placeholders}}>"
+                        .to_string();
+                };
+                if let Err(error) = crate::templates::validate_name(name) {
+                    return error;
+                }
+                let placeholders = match crate::templates::placeholders(prompt) {
+                    Ok(placeholders) => placeholders,
+                    Err(error) => return error,
+                };
+                let template = crate::templates::PromptTemplate {
+                    name: name.to_string(),
+                    prompt: prompt.to_string(),
+                    description: None,
+                };
+                match store.upsert(&self.deps.agent_id, &template).await {
+                    Ok(()) => format!(
+                        "saved template {name}. run it with {}",
+                        crate::templates::usage(&template, &placeholders)
+                    ),
+                    Err(error) => {
+                        tracing::warn!(%error, template = name, "failed to save prompt template");
+                        format!("couldn't save template {name}.")
+                    }
+                }
+            }
+            "remove" if !rest.is_empty() => match store.delete(&self.deps.agent_id, rest).await {
+                Ok(true) => format!("removed template {rest}."),
+                Ok(false)
+                    if self
+                        .deps
+                        .runtime_config
+                        .templates
+                        .load()
+                        .iter()
+                        .any(|template| template.name == rest) =>
+                {
+                    format!("{rest} is defined in config and can only be removed there.")
+                }
+                Ok(false) => format!("no saved template named {rest}."),
+                Err(error) => {
+                    tracing::warn!(%error, template = rest, "failed to remove prompt template");
+                    format!("couldn't remove template {rest}.")
+                }
+            },
+            _ => "usage: /template add <name> <prompt> | /template remove <name>".to_string(),
+        }
+    }
+
+    /// Render a `/run` invocation into the prompt submitted for this turn.
+    async fn render_template_invocation(
+        &self,
+        invocation: Result<crate::templates::TemplateInvocation, String>,
+    ) -> std::result::Result<String, String> {
+        let invocation = invocation?;
+        let templates = self.prompt_templates().await;
+        let template = templates
+            .iter()
+            .find(|template| template.name == invocation.name)
+            .ok_or_else(|| {
+                format!(
+                    "no template named {}. /templates lists them.",
+                    invocation.name
+                )
+            })?;
+        crate::templates::render(template, &invocation.arguments)
+    }
+
+    /// Handle `/git branch|commit|pr`: send the operation to the channel's
+    /// idle OpenCode worker. The outcome is posted when the worker replies.
+    async fn handle_git_command(&mut self, arguments: &str) -> String {
+        let via_api = self.deps.runtime_config.github.load().token.is_some();
+        let command = match channel_git::parse_git_command(arguments, via_api) {
+            Ok(command) => command,
+            Err(usage) => return usage,
+        };
+        let idle_sessions = self.idle_opencode_sessions("").await;
+        let worker_id = match idle_sessions.as_slice() {
+            [(worker_id, ..)] => *worker_id,
+            [] => {
+                return "no idle opencode session. /git runs in a session that's waiting for input."
+                    .to_string();
+            }
+            _ => {
+                return format!(
+                    "{} idle opencode sessions. /git needs exactly one; cancel the others or wait for them to finish.",
+                    idle_sessions.len()
+                );
+            }
+        };
+        if self.pending_git.contains_key(&worker_id) {
+            return format!("worker {worker_id} is still running a git command.");
+        }
+
+        let input_tx = self
+            .state
+            .worker_inputs
+            .read()
+            .await
+            .get(&worker_id)
+            .cloned();
+        let Some(input_tx) = input_tx else {
+            return format!("worker {worker_id} is no longer running.");
+        };
+        if input_tx.send(command.prompt()).await.is_err() {
+            return format!("worker {worker_id} is no longer running.");
+        }
+        let name = command.name();
+        self.pending_git.insert(worker_id, command);
+        format!("running git {name} in worker {worker_id}…")
+    }
+
+    /// Post the outcome of a `/git` command from the worker's reply. Pull
+    /// requests opened through the GitHub API are posted once created.
+    async fn finish_git_command(&mut self, worker_id: WorkerId, command: GitCommand, reply: &str) {
+        let branch = channel_git::marker(reply, "BRANCH");
+        let unreported =
+            |what: &str| format!("worker {worker_id} didn't report {what}. its reply:\n\n{reply}");
+        let body = match command {
+            GitCommand::Branch(_) => match branch {
+                Some(branch) => format!("switched to branch `{branch}`."),
+                None => unreported("a branch"),
+            },
+            GitCommand::Commit(_) => match channel_git::marker(reply, "COMMIT") {
+                Some(commit) => format!(
+                    "committed `{commit}` on `{}`.",
+                    branch.unwrap_or("the current branch")
+                ),
+                None => unreported("a commit"),
+            },
+            GitCommand::Pr { via_api: false, .. } => match channel_git::marker(reply, "PR") {
+                Some(url) => format!("pull request: {url}"),
+                None => unreported("a pull request URL"),
+            },
+            GitCommand::Pr {
+                title,
+                via_api: true,
+            } => {
+                let repository =
+                    channel_git::marker(reply, "REMOTE").and_then(channel_git::github_repository);
+                let (Some(branch), Some(repository)) = (branch, repository) else {
+                    return self
+                        .send_builtin_text(unreported("a pushed branch on a GitHub remote"), "git")
+                        .await;
+                };
+                let token = self.deps.runtime_config.github.load().token.clone();
+                let Some(token) = token else {
+                    return self
+                        .send_builtin_text(
+                            "the github token was removed before the pull request could be opened."
+                                .to_string(),
+                            "git",
+                        )
+                        .await;
+                };
+                let request = channel_git::PullRequest {
+                    title: title
+                        .or_else(|| channel_git::marker(reply, "TITLE").map(str::to_string))
+                        .unwrap_or_else(|| branch.to_string()),
+                    body: channel_git::without_markers(reply),
+                    repository,
+                    head: branch.to_string(),
+                };
+                self.spawn_background_reply(async move {
+                    match channel_git::create_pull_request(&token, &request).await {
+                        Ok(url) => format!("pull request: {url}"),
+                        Err(error) => {
+                            tracing::warn!(
+                                %error,
+                                %worker_id,
+                                repository = %request.repository,
+                                "failed to open pull request"
+                            );
+                            format!("branch `{}` is pushed, but opening the pull request failed: {error}", request.head)
+                        }
+                    }
+                });
+                format!("branch `{branch}` pushed. opening the pull request…")
+            }
+        };
+        self.send_builtin_text(body, "git").await;
+    }
+
+    /// Handle `/project [<path>|clear]`. Binding asks the OpenCode server
+    /// for the directory's project so typos and non-repositories surface
+    /// immediately.
+    async fn handle_project_command(&mut self, argument: &str) -> String {
+        match argument {
+            "" => match &self.resolved_settings.project_directory {
+                Some(directory) => format!("project: {directory}"),
+                None => "no project bound to this channel. use /project <path>.".to_string(),
+            },
+            "clear" => {
+                self.set_channel_project(None).await;
+                "project unbound. opencode workers need an explicit directory again.".to_string()
+            }
+            path => {
+                let runtime_config = &self.deps.runtime_config;
+                if !runtime_config.opencode.load().enabled {
+                    return "opencode workers are not enabled, so there's nothing to bind a project for."
+                        .to_string();
+                }
+                let directory = crate::agent::channel_dispatch::expand_tilde(path);
+                let server_pool = runtime_config.opencode_server_pool.load().clone();
+                let project = match server_pool.get_or_create(&directory).await {
+                    Ok(server) => server.lock().await.current_project().await,
+                    Err(error) => Err(error),
+                };
+                match project {
+                    Ok(project) => {
+                        let bound = directory.display().to_string();
+                        self.set_channel_project(Some(bound.clone())).await;
+                        let vcs = project.vcs.as_deref().unwrap_or("no vcs");
+                        format!(
+                            "project bound: {bound}\n- worktree: {} ({vcs})\nopencode workers from this channel will run here unless given another directory.",
+                            project.worktree
+                        )
+                    }
+                    Err(error) => format!("couldn't open {}: {error:#}", directory.display()),
+                }
+            }
+        }
+    }
+
+    /// Handle `/instructions [show|set <text>|clear]`.
+    async fn handle_instructions_command(&mut self, argument: &str) -> String {
+        let (subcommand, rest) = argument
+            .split_once(char::is_whitespace)
+            .map(|(subcommand, rest)| (subcommand, rest.trim()))
+            .unwrap_or((argument, ""));
+        match subcommand {
+            "" | "show" => match &self.resolved_settings.instructions {
+                Some(instructions) => format!("channel instructions:\n{instructions}"),
+                None => "no custom instructions for this channel.".to_string(),
+            },
+            "set" if rest.is_empty() => "usage: /instructions set <text>".to_string(),
+            "set" if rest.chars().count() > MAX_CHANNEL_INSTRUCTIONS_CHARS => format!(
+                "instructions are too long ({} characters, limit {MAX_CHANNEL_INSTRUCTIONS_CHARS}).",
+                rest.chars().count()
+            ),
+            "set" => {
+                self.set_channel_instructions(Some(rest.to_string())).await;
+                "instructions set for this channel. new workers will pick them up.".to_string()
+            }
+            "clear" => {
+                self.set_channel_instructions(None).await;
+                "channel instructions cleared.".to_string()
+            }
+            other => format!(
+                "unknown instructions command '{other}'. use /instructions show, set <text>, or clear."
+            ),
+        }
+    }
+
+    /// Handle `/schedule [list|add <id> <when> run <prompt> [in <channel>]|remove <id>]`.
+    /// Jobs go through the cron tool, so they share its validation and run
+    /// in a fresh cron session each time.
+    async fn handle_schedule_command(&self, argument: &str, message: &InboundMessage) -> String {
+        use rig::tool::Tool as _;
+
+        const USAGE: &str = "usage: /schedule add <id> <when> run \"<prompt>\" [in #channel], /schedule list, /schedule remove <id>";
+
+        let Some(cron_tool) = self.deps.cron_tool.clone() else {
+            return "scheduled prompts aren't available for this agent.".to_string();
+        };
+        let (subcommand, rest) = argument
+            .split_once(char::is_whitespace)
+            .map(|(subcommand, rest)| (subcommand, rest.trim()))
+            .unwrap_or((argument, ""));
+        let args = |action: &str| crate::tools::CronArgs {
+            action: action.to_string(),
+            id: None,
+            prompt: None,
+            cron_expr: None,
+            interval_secs: None,
+            delivery_target: None,
+            active_start_hour: None,
+            active_end_hour: None,
+            delete_id: None,
+            timeout_secs: None,
+            run_once: None,
+        };
+
+        let result = match subcommand {
+            "" | "list" => cron_tool.call(args("list")).await.map(|output| {
+                let jobs = output.jobs.unwrap_or_default();
+                if jobs.is_empty() {
+                    return "no scheduled prompts.".to_string();
+                }
+                let timestamps = self.timestamps();
+                let mut lines = vec![output.message];
+                for job in jobs {
+                    let schedule = job
+                        .cron_expr
+                        .unwrap_or_else(|| format!("every {}s", job.interval_secs));
+                    let next_run = job
+                        .next_run_at
+                        .map(|at| {
+                            format!(", next {}", timestamps.format(at, TimestampStyle::DateTime))
+                        })
+                        .unwrap_or_default();
+                    lines.push(format!(
+                        "- {} `{schedule}`{next_run} → {}: {}",
+                        job.id, job.delivery_target, job.prompt
+                    ));
+                }
+                lines.join("\n")
+            }),
+            "add" => {
+                let request = match crate::cron::phrase::parse_schedule_request(rest) {
+                    Ok(request) => request,
+                    Err(error) => return format!("{error}.\n{USAGE}"),
+                };
+                let slack_thread_ts = message
+                    .metadata
+                    .get("slack_thread_ts")
+                    .and_then(|value| value.as_str());
+                let (cron_tool, delivery_target) = match request.target.as_deref() {
+                    None => (
+                        cron_tool
+                            .with_default_delivery_target(
+                                crate::tools::default_delivery_target_for_conversation(
+                                    &message.conversation_id,
+                                    slack_thread_ts,
+                                ),
+                            )
+                            .with_current_adapter(self.current_adapter().map(str::to_string)),
+                        None,
+                    ),
+                    Some(name) if name.starts_with('#') => {
+                        let channel_store =
+                            crate::conversation::ChannelStore::new(self.deps.sqlite_pool.clone());
+                        match channel_store.find_by_name(&name[1..]).await {
+                            Ok(Some(channel)) => (
+                                cron_tool.with_default_delivery_target(
+                                    crate::tools::default_delivery_target_for_conversation(
+                                        &channel.id,
+                                        None,
+                                    ),
+                                ),
+                                None,
+                            ),
+                            Ok(None) => return format!("no active channel named {name}."),
+                            Err(error) => return format!("couldn't look up {name}: {error}"),
+                        }
+                    }
+                    Some(target) => (cron_tool, Some(target.to_string())),
+                };
+                cron_tool
+                    .call(crate::tools::CronArgs {
+                        id: Some(request.id),
+                        prompt: Some(request.prompt),
+                        cron_expr: Some(request.cron_expr),
+                        delivery_target,
+                        ..args("create")
+                    })
+                    .await
+                    .map(|output| output.message)
+            }
+            "remove" | "delete" if !rest.is_empty() => cron_tool
+                .call(crate::tools::CronArgs {
+                    delete_id: Some(rest.to_string()),
+                    ..args("delete")
+                })
+                .await
+                .map(|output| output.message),
+            _ => return USAGE.to_string(),
+        };
+        result.unwrap_or_else(|error| error.to_string())
+    }
+
+    /// Render recent audit entries for `/audit [kind] [count]`.
+    async fn render_audit(&self, arguments: &str) -> String {
+        let mut kind = None;
+        let mut limit = 10;
+        for argument in arguments.split_whitespace() {
+            if let Ok(count) = argument.parse::<i64>() {
+                limit = count.clamp(1, 50);
+            } else if let Some(parsed) = crate::audit::AuditKind::parse(argument) {
+                kind = Some(parsed);
+            } else {
+                return format!(
+                    "unknown audit filter '{argument}'. use prompt, tool_call, permission, command, deletion, or file_change."
+                );
+            }
+        }
+
+        let log = crate::audit::AuditLog::new(self.deps.sqlite_pool.clone());
+        let entries = match log.recent(&self.deps.agent_id, kind, limit).await {
+            Ok(entries) => entries,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to read audit log");
+                return "failed to read the audit log.".to_string();
+            }
+        };
+        let cost = match log.cost_since_hours(&self.deps.agent_id, 24).await {
+            Ok(cost) => format!("${cost:.4}"),
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to read usage costs");
+                "unknown".to_string()
+            }
+        };
+
+        let mut lines = vec![format!(
+            "audit (last {} entries, 24h cost: {cost})",
+            entries.len()
+        )];
+        for entry in &entries {
+            lines.push(format!(
+                "- {} [{}] {}: {}",
+                entry.recorded_at,
+                entry.kind.as_str(),
+                entry.actor,
+                crate::summarize_first_non_empty_line(
+                    &entry.summary,
+                    crate::EVENT_SUMMARY_MAX_CHARS
+                )
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Render stage progress for this agent's multi-stage tasks for
+    /// `/pipeline status`.
+    async fn render_pipeline_status(&self) -> String {
+        let mut pipelines = Vec::new();
+        for status in [
+            crate::tasks::TaskStatus::InProgress,
+            crate::tasks::TaskStatus::Ready,
+        ] {
+            let filter = crate::tasks::TaskListFilter {
+                assigned_agent_id: Some(self.deps.agent_id.to_string()),
+                status: Some(status),
+                limit: Some(20),
+                ..Default::default()
+            };
+            match self.deps.task_store.list(filter).await {
+                Ok(tasks) => {
+                    pipelines.extend(tasks.into_iter().filter(|task| !task.subtasks.is_empty()))
+                }
+                Err(error) => {
+                    tracing::warn!(%error, channel_id = %self.id, "failed to list pipeline tasks");
+                    return "failed to read pipeline status.".to_string();
+                }
+            }
+        }
+        if pipelines.is_empty() {
+            return "no pipelines in progress or queued.".to_string();
+        }
+
+        let mut lines = vec!["pipelines".to_string()];
+        for task in &pipelines {
+            let completed = task
+                .subtasks
+                .iter()
+                .filter(|subtask| subtask.completed)
+                .count();
+            let next_stage = task
+                .subtasks
+                .iter()
+                .find(|subtask| !subtask.completed)
+                .map(|subtask| subtask.title.as_str())
+                .unwrap_or("finishing");
+            let checkpoint = match self.deps.task_store.list_checkpoints(&task.id).await {
+                Ok(checkpoints) => checkpoints
+                    .last()
+                    .map(|checkpoint| format!(", last checkpoint {}", checkpoint.recorded_at))
+                    .unwrap_or_default(),
+                Err(error) => {
+                    tracing::warn!(%error, task_number = task.task_number, "failed to read task checkpoints");
+                    String::new()
+                }
+            };
+            lines.push(format!(
+                "- #{} {} [{}]: {completed}/{} stages done, next: {next_stage}{checkpoint}",
+                task.task_number,
+                task.title,
+                task.status,
+                task.subtasks.len(),
+            ));
+        }
+        if let Some(link) = self.deps.runtime_config.dashboard_link(&format!(
+            "/agents/{}/tasks",
+            urlencoding::encode(&self.deps.agent_id)
+        )) {
+            lines.push(format!("dashboard: {link}"));
+        }
+        lines.join("\n")
+    }
+
+    /// Dashboard links for this channel and its running workers, empty when
+    /// no `public_url` is configured.
+    /// Collect session diagnostics for `/status`.
+    async fn status_diagnostics(&self) -> crate::agent::channel_status::ChannelDiagnostics {
+        let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+        let worker_statuses = {
+            let status = self.state.status_block.read().await;
+            status
+                .active_workers
+                .iter()
+                .map(|worker| (worker.id, worker.status.clone(), worker.started_at))
+                .collect::<Vec<_>>()
+        };
+        let mut workers = Vec::with_capacity(worker_statuses.len());
+        for (worker_id, status, started_at) in worker_statuses {
+            let session = self.opencode_sessions.get(&worker_id);
+            let backend = match session {
+                Some((_, port)) => server_pool.backend_label(*port).await,
+                None => None,
+            };
+            workers.push(crate::agent::channel_status::WorkerDiagnostic {
+                worker_id,
+                status,
+                session_id: session.map(|(session_id, _)| session_id.clone()),
+                backend,
+                started_at,
+            });
+        }
+
+        // Usage from recent turns may still be queued.
+        self.deps.write_behind.flush().await;
+        let usage = match crate::llm::usage::conversation_usage(&self.deps.sqlite_pool, &self.id)
+            .await
+        {
+            Ok(usage) => Some(usage),
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to load channel usage for /status");
+                None
+            }
+        };
+
+        crate::agent::channel_status::ChannelDiagnostics {
+            workers,
+            active_branches: self.state.active_branches.read().await.len(),
+            queued_messages: self.coalesce_buffer.len() + self.message_rx.len(),
+            pending_results: self.pending_results.len(),
+            last_activity_at: self.last_activity_at,
+            usage,
+        }
+    }
+
+    /// MCP servers Spacebot connects to, plus the ones configured in each
+    /// OpenCode server this channel's workers use.
+    async fn mcp_status_report(&self) -> String {
+        use crate::agent::channel_status::{McpEntry, McpReport};
+
+        let mut reports = vec![McpReport {
+            source: "spacebot".to_string(),
+            servers: Ok(self
+                .deps
+                .mcp_manager
+                .statuses()
+                .await
+                .iter()
+                .map(McpEntry::from_spacebot)
+                .collect()),
+        }];
+
+        let mut workers_by_port: std::collections::BTreeMap<u16, Vec<WorkerId>> =
+            std::collections::BTreeMap::new();
+        for (worker_id, (_, port)) in &self.opencode_sessions {
+            workers_by_port.entry(*port).or_default().push(*worker_id);
+        }
+        let server_pool = self.deps.runtime_config.opencode_server_pool.load().clone();
+        for (port, worker_ids) in workers_by_port {
+            let backend = server_pool
+                .backend_label(port)
+                .await
+                .unwrap_or_else(|| format!("port {port}"));
+            let worker_list = worker_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let servers = match server_pool.server_for_port(port).await {
+                Some(server) => match server.lock().await.mcp_status().await {
+                    Ok(statuses) => Ok(statuses
+                        .iter()
+                        .map(|(name, status)| McpEntry::from_opencode(name, status))
+                        .collect()),
+                    Err(error) => {
+                        tracing::warn!(%error, port, "failed to fetch OpenCode MCP status");
+                        Err(error.to_string())
+                    }
+                },
+                None => Err("the opencode server is no longer running".to_string()),
+            };
+            reports.push(McpReport {
+                source: format!("opencode on {backend} (worker {worker_list})"),
+                servers,
+            });
+        }
+
+        crate::agent::channel_status::render_mcp_status(&reports)
+    }
+
+    async fn dashboard_status_lines(&self) -> Vec<String> {
+        let agent_id = urlencoding::encode(&self.deps.agent_id).into_owned();
+        let runtime_config = &self.deps.runtime_config;
+        let Some(channel_link) = runtime_config.dashboard_link(&format!(
+            "/agents/{agent_id}/channels/{}",
+            urlencoding::encode(&self.id)
+        )) else {
+            return Vec::new();
+        };
+        let mut lines = vec![format!("- dashboard: {channel_link}")];
+        for worker_id in self.state.worker_handles.read().await.keys() {
+            if let Some(link) = runtime_config
+                .dashboard_link(&format!("/agents/{agent_id}/workers?worker={worker_id}"))
+            {
+                lines.push(format!("- worker {worker_id}: {link}"));
+            }
+        }
+        lines
+    }
+
+    /// Check the sender's tier for a built-in command, replying with a denial
+    /// when it is insufficient. Returns `true` when the command may proceed.
+    async fn ensure_builtin_access(
+        &mut self,
+        tier: crate::access::AccessTier,
+        action: crate::access::Action,
+    ) -> bool {
+        let required = action.required_tier();
+        if tier >= required {
+            return true;
+        }
+        tracing::info!(
+            channel_id = %self.id,
+            %tier,
+            %required,
+            ?action,
+            "built-in command denied by access tier"
+        );
+        let text = self.text_with(
+            "access.denied",
+            &[
+                ("required", &required.to_string()),
+                ("action", action.describe()),
+                ("tier", &tier.to_string()),
+            ],
+        );
+        match self.current_inbound.clone() {
+            Some(target) => {
+                self.send_private_notice(&target, text, "access-denied")
+                    .await
+            }
+            None => self.send_builtin_text(text, "access-denied").await,
+        }
+        false
+    }
+
+    /// Reply to the sender of `target` alone where the platform has
+    /// ephemeral messages, and publicly elsewhere. Notices like access
+    /// denials concern one person, so they aren't logged to the conversation.
+    async fn send_private_notice(&self, target: &InboundMessage, text: String, log_label: &str) {
+        let routed = RoutedResponse {
+            response: OutboundResponse::Ephemeral {
+                text,
+                user_id: target.sender_id.clone(),
+            },
+            target: target.clone(),
+        };
+        if let Err(error) = self.response_tx.send(routed).await {
+            tracing::error!(%error, channel_id = %self.id, %log_label, "failed to send private notice");
+        }
+    }
+
+    /// A bot-authored string in this channel's locale.
+    fn text(&self, key: &str) -> String {
+        crate::prompts::messages::get(&self.resolved_settings.locale, key)
+    }
+
+    /// Like [`Self::text`], with its placeholders filled in.
+    fn text_with(&self, key: &str, arguments: &[(&str, &str)]) -> String {
+        crate::prompts::messages::get_with(&self.resolved_settings.locale, key, arguments)
+    }
+
+    fn persist_inbound_user_message(
+        &self,
+        message: &InboundMessage,
+        raw_text: &str,
+        saved_attachments: Option<&[channel_attachments::SavedAttachmentMeta]>,
+    ) {
+        if message.source == "system" {
+            return;
+        }
+        let sender_name = participant_display_name(message);
+
+        // If attachments were saved, enrich the metadata with their info
+        let metadata = if let Some(saved) = saved_attachments {
+            let mut enriched = message.metadata.clone();
+            if let Ok(attachments_json) = serde_json::to_value(saved) {
+                enriched.insert("attachments".to_string(), attachments_json);
+            }
+            enriched
+        } else {
+            message.metadata.clone()
+        };
+
+        self.state.conversation_logger.log_user_message(
+            &self.state.channel_id,
+            &sender_name,
+            &message.sender_id,
+            raw_text,
+            &metadata,
+        );
+        self.state
+            .channel_store
+            .upsert(&message.conversation_id, &metadata);
+
+        crate::audit::AuditLog::new(self.deps.sqlite_pool.clone()).log(
+            &self.deps.agent_id,
+            crate::audit::AuditEntry::new(
+                crate::audit::AuditKind::Prompt,
+                format!("{}:{}", message.source, message.sender_id),
+                raw_text,
+            )
+            .with_channel(self.id.to_string())
+            .with_detail(format!("sender: {sender_name}")),
+        );
+    }
+
+    /// Handle `!context`: inject into a running worker when possible, hold it
+    /// for the worker's next idle boundary otherwise, or add it to the
+    /// conversation when no worker is active.
+    async fn handle_context_addition(
+        &mut self,
+        message: &InboundMessage,
+        text: &str,
+        attachments: &[crate::Attachment],
+    ) {
+        if text.is_empty() && attachments.is_empty() {
+            self.send_builtin_text(
+                "usage: !context <text or attachment>".to_string(),
+                "context-usage",
+            )
+            .await;
+            return;
+        }
+
+        let addition = channel_context::format_context_addition(
+            &participant_display_name(message),
+            text,
+            attachments,
+        );
+
+        // Target the most recently started worker in this channel.
+        let target = {
+            let status = self.state.status_block.read().await;
+            status
+                .active_workers
+                .iter()
+                .max_by_key(|worker| worker.started_at)
+                .map(|worker| (worker.id, worker.status == "idle"))
+        };
+
+        let acknowledgement = match target {
+            None => {
+                self.push_context_into_history(&addition).await;
+                "context added to the conversation.".to_string()
+            }
+            Some((worker_id, true)) => {
+                let input_tx = self
+                    .state
+                    .worker_inputs
+                    .read()
+                    .await
+                    .get(&worker_id)
+                    .cloned();
+                match input_tx {
+                    Some(input_tx) if input_tx.send(addition.clone()).await.is_ok() => {
+                        format!("context delivered to worker {worker_id}.")
+                    }
+                    _ => {
+                        self.push_context_into_history(&addition).await;
+                        "context added to the conversation.".to_string()
+                    }
+                }
+            }
+            Some((worker_id, false)) => {
+                let inject_tx = self
+                    .state
+                    .worker_injections
+                    .read()
+                    .await
+                    .get(&worker_id)
+                    .cloned();
+                match inject_tx {
+                    Some(inject_tx) if inject_tx.send(addition.clone()).await.is_ok() => {
+                        format!(
+                            "context injected into worker {worker_id}; it will see it at its next step."
+                        )
+                    }
+                    _ => {
+                        self.pending_context.push(worker_id, addition);
+                        format!(
+                            "worker {worker_id} is busy. holding context ({} queued) until it's idle.",
+                            self.pending_context.len_for(&worker_id)
+                        )
+                    }
+                }
+            }
+        };
+
+        tracing::info!(channel_id = %self.id, ?target, "context addition received");
+        self.send_builtin_text(acknowledgement, "context").await;
+    }
+
+    /// Deliver held `!context` additions once a worker reaches an idle boundary.
+    async fn deliver_pending_context(&mut self, worker_id: WorkerId) {
+        let Some(addition) = self.pending_context.take(&worker_id) else {
+            return;
+        };
+        let input_tx = self
+            .state
+            .worker_inputs
+            .read()
+            .await
+            .get(&worker_id)
+            .cloned();
+        match input_tx {
+            Some(input_tx) if input_tx.send(addition.clone()).await.is_ok() => {
+                tracing::info!(channel_id = %self.id, %worker_id, "delivered held context to idle worker");
+                self.send_builtin_text(
+                    format!("delivered held context to worker {worker_id}."),
+                    "context-delivered",
+                )
+                .await;
+            }
+            _ => {
+                tracing::warn!(
+                    channel_id = %self.id,
+                    %worker_id,
+                    "worker has no input channel, adding held context to the conversation"
+                );
+                self.push_context_into_history(&addition).await;
+            }
+        }
+    }
+
+    async fn push_context_into_history(&self, addition: &str) {
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::User {
+                content: OneOrMany::one(UserContent::text(addition)),
+            });
+    }
+
+    /// Record a privileged built-in command in the audit log.
+    fn audit_builtin_command(&self, message: &InboundMessage, command: &str) {
+        crate::audit::AuditLog::new(self.deps.sqlite_pool.clone()).log(
+            &self.deps.agent_id,
+            crate::audit::AuditEntry::new(
+                crate::audit::AuditKind::Command,
+                format!("{}:{}", message.source, message.sender_id),
+                command,
+            )
+            .with_channel(self.id.to_string()),
+        );
+    }
+
+    /// React to a platform deletion event. Always audited; when the deleted
+    /// message started the current turn and `abort_on_prompt_deleted` is set,
+    /// the work it spawned is cancelled. The adapter has already stopped
+    /// editing deleted bot messages by the time this runs.
+    async fn handle_message_deleted(&mut self, message: &InboundMessage) {
+        let crate::MessageContent::Deleted {
+            message_id,
+            bot_message,
+        } = &message.content
+        else {
+            return;
+        };
+
+        // A deleted message still waiting in the coalesce buffer is dropped
+        // before the LLM ever sees it.
+        if !bot_message {
+            self.coalesce_buffer
+                .retain(|buffered| &buffered.id != message_id);
+        }
+
+        let is_current_prompt = !bot_message
+            && self
+                .current_inbound
+                .as_ref()
+                .is_some_and(|inbound| &inbound.id == message_id);
+        let abort = is_current_prompt
+            && self
+                .deps
+                .runtime_config
+                .channel_config
+                .load()
+                .abort_on_prompt_deleted;
+
+        tracing::info!(
+            channel_id = %self.id,
+            %message_id,
+            bot_message,
+            is_current_prompt,
+            abort,
+            "platform message deleted"
+        );
+
+        let summary = if *bot_message {
+            format!("bot message {message_id} deleted")
+        } else if is_current_prompt {
+            format!("prompt {message_id} deleted")
+        } else {
+            format!("message {message_id} deleted")
+        };
+        crate::audit::AuditLog::new(self.deps.sqlite_pool.clone()).log(
+            &self.deps.agent_id,
+            crate::audit::AuditEntry::new(
+                crate::audit::AuditKind::Deletion,
+                format!("{}:{}", message.source, message.sender_id),
+                summary,
+            )
+            .with_channel(self.id.to_string())
+            .with_detail(if abort {
+                "policy: abort_on_prompt_deleted, cancelled active work"
+            } else {
+                "policy: continue"
+            }),
+        );
+
+        if abort {
+            self.state
+                .cancel_all_workers_and_branches(
+                    "the user deleted the message that requested this work",
+                )
+                .await;
+        }
+    }
+
+    fn suppress_plaintext_fallback(&self) -> bool {
+        matches!(self.current_adapter(), Some("email")) && !self.email_prompt
+    }
+
+    async fn track_participant_from_message(&self, message: &InboundMessage) {
+        if message.source == "system" {
+            return;
+        }
+
+        let humans = self.deps.humans.load();
+        let mut participants = self.state.active_participants.write().await;
+        track_active_participant(&mut participants, humans.as_ref(), message);
+    }
+
+    /// Return a handle that allows external supervision to cancel this channel's
+    /// workers and branches without direct access to Channel internals.
+    pub fn control_handle(&self) -> ChannelControlHandle {
+        self.control_handle.clone()
+    }
+
+    fn rewrite_tool_routed_command_prompt(&self, raw_text: &str) -> Option<String> {
+        match raw_text.trim() {
+            "/tasks" => Some(
+                "use channel tools to fetch my ready tasks (limit 10) and reply exactly with:\n\
+                 - header: tasks (ready):\n\
+                 - each line: - #<task_number> [<priority>] <title>\n\
+                 if no tasks are ready, reply exactly: tasks (ready): none"
+                    .to_string(),
+            ),
+            "/today" => Some(
+                "use channel tools to build a local tasks snapshot and reply exactly in this format:\n\
+                 - first line: today (local tasks snapshot):\n\
+                 - section 1: in-progress tasks (up to 5), each line:   #<task_number> [<priority>] <title>\n\
+                 - section 2: up next ready tasks (up to 5), each line:   #<task_number> [<priority>] <title>\n\
+                 if a section is empty use:\n\
+                 - in progress: none\n\
+                 - up next (ready): none"
+                    .to_string(),
+            ),
+            "/digest" => Some(
+                "using available tools and channel context, generate a concise day digest from local 00:00 to now with exactly this order:\n\
+                 1) top decisions\n\
+                 2) key convo themes\n\
+                 3) open loops\n\
+                 keep it practical and concise; if there are no meaningful updates, reply exactly: no material updates today."
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn compute_listen_mode_invocation(
+        &self,
+        message: &InboundMessage,
+        raw_text: &str,
+    ) -> (bool, bool, bool) {
+        compute_listen_mode_invocation(message, raw_text)
+    }
+
+    /// Send a routed response paired with the current inbound message.
+    ///
+    /// Falls back to a bare response with a placeholder target if no inbound
+    /// message is set (should not happen during normal turn processing).
+    async fn send_routed(
+        &self,
+        response: OutboundResponse,
+    ) -> std::result::Result<(), mpsc::error::SendError<RoutedResponse>> {
+        let routed = match &self.current_inbound {
+            Some(target) => RoutedResponse {
+                response,
+                target: target.clone(),
+            },
             None => {
                 tracing::warn!(
                     channel_id = %self.id,
@@ -1163,7 +4128,292 @@ impl Channel {
                 }
             }
         };
-        self.response_tx.send(routed).await
+        self.response_tx.send(routed).await
+    }
+
+    /// Post a completed worker tool call at its configured detail level.
+    /// Channel-level settings overlay the agent's `tool_rendering`. In
+    /// `DeliveryMode::Summary` the call is tallied for the turn's reply
+    /// instead.
+    async fn render_worker_tool_call(&mut self, tool_name: &str, args: Option<&str>, result: &str) {
+        let tool_rendering = self
+            .deps
+            .runtime_config
+            .tool_rendering
+            .load()
+            .overlay(&self.resolved_settings.tool_rendering);
+        let detail = tool_rendering.resolve(tool_name);
+        if self.resolved_settings.delivery == DeliveryMode::Summary {
+            if detail != ToolDetail::Hidden {
+                self.tool_tally.record(tool_name, result);
+            }
+            return;
+        }
+        if detail >= ToolDetail::Summary
+            && tool_rendering
+                .summary_threshold()
+                .is_some_and(|threshold| tool_output_lines(result) > threshold)
+        {
+            self.spawn_tool_summary(
+                tool_name,
+                args,
+                result,
+                detail,
+                tool_rendering.summarize.model,
+            );
+            return;
+        }
+        let collapsed = match &self.current_inbound {
+            Some(inbound) if self.resolved_settings.collapse && detail == ToolDetail::Full => {
+                render_collapsed_tool_call(tool_name, args, result, &inbound.source)
+            }
+            _ => None,
+        };
+        let response = match collapsed {
+            Some(response) => response,
+            None => match render_tool_call(tool_name, args, result, detail) {
+                Some(text) => OutboundResponse::Text(self.link_paths(text)),
+                None => return,
+            },
+        };
+        if let Err(error) = self.send_routed(response).await {
+            tracing::warn!(%error, channel_id = %self.id, tool_name, "failed to send tool rendering");
+        }
+    }
+
+    /// Link file paths in tool output to the channel's repository, when one
+    /// is configured.
+    fn link_paths(&self, text: String) -> String {
+        match &self.resolved_settings.repository_url {
+            Some(repository_url) => link_file_paths(
+                &text,
+                repository_url,
+                self.resolved_settings.project_directory.as_deref(),
+            ),
+            None => text,
+        }
+    }
+
+    /// Summarize a long tool output off the event loop, then post the
+    /// summary with the full output. Falls back to the plain rendering when
+    /// the summary model fails.
+    fn spawn_tool_summary(
+        &self,
+        tool_name: &str,
+        args: Option<&str>,
+        result: &str,
+        detail: ToolDetail,
+        model: Option<String>,
+    ) {
+        let target = self
+            .current_inbound
+            .clone()
+            .unwrap_or_else(InboundMessage::empty);
+        let deps = self.deps.clone();
+        let response_tx = self.response_tx.clone();
+        let channel_id = self.id.clone();
+        let tool_name = tool_name.to_string();
+        let args = args.map(str::to_string);
+        let result = result.to_string();
+        let repository_url = self.resolved_settings.repository_url.clone();
+        let project_directory = self.resolved_settings.project_directory.clone();
+
+        tokio::spawn(async move {
+            let output = tool_output_lines(&result);
+            let text = match summarize_tool_output(&deps, model.as_deref(), &tool_name, &result)
+                .await
+            {
+                Ok(summary) => {
+                    render_summarized_tool_call(&tool_name, args.as_deref(), &result, &summary)
+                }
+                Err(error) => {
+                    tracing::warn!(%error, %channel_id, %tool_name, output_lines = output, "tool output summary failed");
+                    match render_tool_call(&tool_name, args.as_deref(), &result, detail) {
+                        Some(text) => text,
+                        None => return,
+                    }
+                }
+            };
+            let text = match &repository_url {
+                Some(repository_url) => {
+                    link_file_paths(&text, repository_url, project_directory.as_deref())
+                }
+                None => text,
+            };
+            let routed = RoutedResponse {
+                response: OutboundResponse::Text(text),
+                target,
+            };
+            if let Err(error) = response_tx.send(routed).await {
+                tracing::warn!(%error, %channel_id, %tool_name, "failed to send tool rendering");
+            }
+        });
+    }
+
+    /// Name this channel's thread after an OpenCode session title. Runs off
+    /// the event loop: platforms rate-limit renames heavily, and a queued
+    /// rename shouldn't hold up the conversation.
+    fn rename_thread(&mut self, title: &str) {
+        if !self.deps.runtime_config.opencode.load().rename_threads
+            || self.thread_title.as_deref() == Some(title)
+        {
+            return;
+        }
+        let (Some(messaging_manager), Some(inbound)) =
+            (&self.deps.messaging_manager, &self.current_inbound)
+        else {
+            return;
+        };
+        self.thread_title = Some(title.to_string());
+
+        let messaging_manager = messaging_manager.clone();
+        let inbound = inbound.clone();
+        let channel_id = self.id.clone();
+        let title = title.to_string();
+        tokio::spawn(async move {
+            if let Err(error) = messaging_manager.rename_thread(&inbound, &title).await {
+                tracing::warn!(%error, %channel_id, "failed to rename thread");
+            }
+        });
+    }
+
+    /// Post or refresh a worker's todo checklist.
+    async fn update_todo_checklist(
+        &mut self,
+        worker_id: WorkerId,
+        todos: &[crate::opencode::types::TodoItem],
+    ) {
+        let key = format!("todos:{worker_id}");
+        if todos.is_empty() && !self.side_streams.contains_key(&key) {
+            return;
+        }
+        self.update_side_stream(key, worker_id, render_todo_checklist(todos))
+            .await;
+    }
+
+    /// Render a part from one of a worker's `task` sub-agents as a nested
+    /// section. Shown only when the `task` tool itself renders, with each
+    /// step at its own tool's detail level.
+    async fn update_subagent_section(
+        &mut self,
+        worker_id: WorkerId,
+        subagent: &crate::opencode::types::OpenCodeSubagent,
+        part: &crate::opencode::types::OpenCodePart,
+    ) {
+        let crate::opencode::types::OpenCodePart::Tool { tool, .. } = part else {
+            return;
+        };
+        let tool_rendering = self
+            .deps
+            .runtime_config
+            .tool_rendering
+            .load()
+            .overlay(&self.resolved_settings.tool_rendering);
+        if tool_rendering.resolve("task") == ToolDetail::Hidden {
+            return;
+        }
+
+        let (_, section) = self
+            .subagent_sections
+            .entry(subagent.session_id.clone())
+            .or_insert_with(|| (worker_id, SubagentSection::new(subagent.title.clone())));
+        if !section.apply(part, tool_rendering.resolve(tool)) {
+            return;
+        }
+        let text = section.render();
+        self.update_side_stream(format!("subagent:{}", subagent.session_id), worker_id, text)
+            .await;
+    }
+
+    /// Post or edit a secondary live message tied to a worker. Each side
+    /// stream targets a synthetic message id so adapters edit it
+    /// independently of the channel's reply stream.
+    async fn update_side_stream(&mut self, key: String, worker_id: WorkerId, text: String) {
+        if self.resolved_settings.delivery == DeliveryMode::Summary {
+            return;
+        }
+        if !self.side_streams.contains_key(&key) {
+            let Some(inbound) = &self.current_inbound else {
+                return;
+            };
+            let mut target = inbound.clone();
+            target.id = format!("{}:{key}", inbound.id);
+            // Keyed by the worker, not the message, so a resumed worker
+            // picks up the same platform message after a restart.
+            target.metadata.insert(
+                crate::messaging::journal::STREAM_KEY_METADATA.into(),
+                key.clone().into(),
+            );
+            self.side_streams.insert(key.clone(), (worker_id, target));
+            self.send_side_stream(&key, OutboundResponse::StreamStart)
+                .await;
+        }
+        self.send_side_stream(&key, OutboundResponse::StreamChunk(text))
+            .await;
+    }
+
+    /// Stop editing a worker's side streams, leaving their last state in chat.
+    /// Keep the platform's typing indicator, or a status line, alive while
+    /// `worker_id`'s session is busy.
+    fn start_heartbeat(&mut self, worker_id: WorkerId) {
+        if self.heartbeats.contains_key(&worker_id) {
+            return;
+        }
+        let Some(inbound) = &self.current_inbound else {
+            return;
+        };
+        let Some(style) = HeartbeatStyle::for_source(&inbound.source) else {
+            return;
+        };
+        // A status line is edited every few seconds; typing isn't an edit.
+        if style == HeartbeatStyle::StatusLine
+            && self.resolved_settings.delivery == DeliveryMode::Summary
+        {
+            return;
+        }
+        let heartbeat = Heartbeat::start(
+            style,
+            self.response_tx.clone(),
+            inbound,
+            &format!("heartbeat:{worker_id}"),
+            &self.resolved_settings.locale,
+        );
+        self.heartbeats.insert(worker_id, heartbeat);
+    }
+
+    async fn stop_heartbeat(&mut self, worker_id: WorkerId) {
+        if let Some(heartbeat) = self.heartbeats.remove(&worker_id) {
+            heartbeat.stop().await;
+        }
+    }
+
+    async fn finish_side_streams(&mut self, worker_id: WorkerId) {
+        let keys: Vec<String> = self
+            .side_streams
+            .iter()
+            .filter(|(_, (owner, _))| *owner == worker_id)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            self.send_side_stream(&key, OutboundResponse::StreamEnd)
+                .await;
+            self.side_streams.remove(&key);
+        }
+        self.subagent_sections
+            .retain(|_, (owner, _)| *owner != worker_id);
+    }
+
+    async fn send_side_stream(&self, key: &str, response: OutboundResponse) {
+        let Some((_, target)) = self.side_streams.get(key) else {
+            return;
+        };
+        let routed = RoutedResponse {
+            response,
+            target: target.clone(),
+        };
+        if let Err(error) = self.response_tx.send(routed).await {
+            tracing::warn!(%error, channel_id = %self.id, key, "failed to send side stream update");
+        }
     }
 
     /// Drain accumulated channel tool calls from ApiState and serialize as JSON.
@@ -1222,7 +4472,7 @@ impl Channel {
         }
         let supported_source = matches!(
             message.source.as_str(),
-            "telegram" | "discord" | "slack" | "twitch" | "signal"
+            "telegram" | "discord" | "slack" | "twitch" | "signal" | "irc" | "mattermost"
         );
         if !supported_source {
             return Ok(false);
@@ -1233,10 +4483,564 @@ impl Channel {
             return Ok(false);
         }
 
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context();
         let now_line = temporal_context.current_time_line();
+        let access_tier = crate::access::resolve_tier(self.deps.humans.load().as_ref(), message);
+
+        if let Some(argument) = text
+            .strip_prefix("/model")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::SwitchModel)
+                .await
+            {
+                return Ok(true);
+            }
+            let argument = argument.trim();
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = match argument {
+                "" => {
+                    let model = match self.resolved_settings.model.clone() {
+                        Some(model) => model,
+                        None => self.text("model.none"),
+                    };
+                    self.text_with("model.show", &[("model", &model)])
+                }
+                "reset" => {
+                    self.set_channel_model(None).await;
+                    self.text("model.reset")
+                }
+                model => {
+                    self.set_channel_model(Some(model.to_string())).await;
+                    self.text_with("model.set", &[("model", model)])
+                }
+            };
+            self.send_builtin_text(body, "model").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/audit")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ViewAudit)
+                .await
+            {
+                return Ok(true);
+            }
+            let body = self.render_audit(argument.trim()).await;
+            self.send_builtin_text(body, "audit").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/project")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::BindProject
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_project_command(argument).await;
+            self.send_builtin_text(body, "project").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/instructions")
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            let argument = argument.trim();
+            let changes_instructions = !matches!(argument, "" | "show");
+            let action = if changes_instructions {
+                crate::access::Action::SetInstructions
+            } else {
+                crate::access::Action::ViewStatus
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if changes_instructions {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_instructions_command(argument).await;
+            self.send_builtin_text(body, "instructions").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/schedule")
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            let argument = argument.trim();
+            let changes_schedules = !matches!(argument, "" | "list");
+            let action = if changes_schedules {
+                crate::access::Action::ManageSchedules
+            } else {
+                crate::access::Action::ViewStatus
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if changes_schedules {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_schedule_command(argument, message).await;
+            self.send_builtin_text(body, "schedule").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/stats")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            // Per-user spend is admin-only.
+            let action = if argument.split_whitespace().next() == Some("cost") {
+                crate::access::Action::ViewAudit
+            } else {
+                crate::access::Action::ViewStatus
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            let body = self.handle_stats_command(argument.trim()).await;
+            self.send_builtin_text(body, "stats").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/search")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ViewStatus)
+                .await
+            {
+                return Ok(true);
+            }
+            let body = self.handle_search_command(argument.trim()).await;
+            self.send_builtin_text(body, "search").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/admin")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ManageMaintenance)
+                .await
+            {
+                return Ok(true);
+            }
+            let argument = argument.trim();
+            if !argument.is_empty() && argument != "maintenance" {
+                self.audit_builtin_command(message, text);
+            }
+            if let Some(rest) = argument
+                .strip_prefix("abort-all")
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+            {
+                self.handle_abort_all_command(message, rest.trim()).await;
+                return Ok(true);
+            }
+            let body = self.handle_admin_command(argument);
+            self.send_builtin_text(body, "admin").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/locale")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeResponseMode
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_locale_command(argument).await;
+            self.send_builtin_text(body, "locale").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/budget")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeBudget
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_budget_command(argument).await;
+            self.send_builtin_text(body, "budget").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/timezone")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeResponseMode
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_timezone_command(argument).await;
+            self.send_builtin_text(body, "timezone").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/plan")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeResponseMode
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_plan_command(argument).await;
+            self.send_builtin_text(body, "plan").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/driver")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let Some(command) = channel_driver::parse_driver_command(argument) else {
+                let body = self.text_with("driver.unknown", &[("command", argument.trim())]);
+                self.send_builtin_text(body, "driver").await;
+                return Ok(true);
+            };
+            let action = if command == DriverCommand::Show {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ManageDriverLock
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if command != DriverCommand::Show {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_driver_command(command, message, access_tier);
+            self.send_builtin_text(body, "driver").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/delivery")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeResponseMode
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_delivery_command(argument).await;
+            self.send_builtin_text(body, "delivery").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/collapse")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeResponseMode
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_collapse_command(argument).await;
+            self.send_builtin_text(body, "collapse").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/debug")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::MirrorEvents
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_debug_command(argument, message);
+            self.send_builtin_text(body, "debug").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/dryrun")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let argument = argument.trim();
+            let action = if argument.is_empty() {
+                crate::access::Action::ViewStatus
+            } else {
+                crate::access::Action::ChangeResponseMode
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if !argument.is_empty() {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_dry_run_command(argument).await;
+            self.send_builtin_text(body, "dryrun").await;
+            return Ok(true);
+        }
+
+        if text == "/templates" {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ViewStatus)
+                .await
+            {
+                return Ok(true);
+            }
+            let body = self.handle_templates_command().await;
+            self.send_builtin_text(body, "templates").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/template")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ManageTemplates)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let body = self.handle_template_command(argument.trim()).await;
+            self.send_builtin_text(body, "template").await;
+            return Ok(true);
+        }
+
+        if let Some(arguments) = text
+            .strip_prefix("/git")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::RunGit)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let body = self.handle_git_command(arguments).await;
+            self.send_builtin_text(body, "git").await;
+            return Ok(true);
+        }
+
+        if let Some(arguments) = text
+            .strip_prefix("/oc")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            let command = crate::opencode::command::parse_oc_command(arguments);
+            let action = match command {
+                crate::opencode::command::OcCommand::List => crate::access::Action::ViewStatus,
+                crate::opencode::command::OcCommand::Run { .. } => {
+                    crate::access::Action::RunOpenCodeCommand
+                }
+            };
+            if !self.ensure_builtin_access(access_tier, action).await {
+                return Ok(true);
+            }
+            if action == crate::access::Action::RunOpenCodeCommand {
+                self.audit_builtin_command(message, text);
+            }
+            let body = self.handle_oc_command(command).await;
+            self.send_builtin_text(body, "oc").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/compact")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::CompactSession)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let body = self.handle_compact_command(argument.trim()).await;
+            self.send_builtin_text(body, "compact").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/attach")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::AttachSession)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let body = self.handle_attach_command(argument.trim()).await;
+            self.send_builtin_text(body, "attach").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/detach")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::AttachSession)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let body = self.handle_detach_command(argument.trim()).await;
+            self.send_builtin_text(body, "detach").await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/rebind")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::AttachSession)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let body = self.handle_rebind_command(argument.trim()).await;
+            self.send_builtin_text(body, "rebind").await;
+            return Ok(true);
+        }
+
+        if let Some((decision, requested)) = channel_permission::parse_permission_command(text) {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ApproveToolCall)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            let now = std::time::Instant::now();
+            self.pending_permissions
+                .retain(|pending| pending.expires_at > now);
+            match channel_permission::resolve_pending_permission(
+                &self.pending_permissions,
+                requested,
+            ) {
+                Ok(index) => self.decide_permission(message, index, decision).await,
+                Err(body) => self.send_builtin_text(body, "permission").await,
+            }
+            return Ok(true);
+        }
+
+        if let Some((decision, requested)) = channel_plan::parse_plan_command(text) {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ApprovePlan)
+                .await
+            {
+                return Ok(true);
+            }
+            self.audit_builtin_command(message, text);
+            self.handle_plan_decision(decision, requested).await;
+            return Ok(true);
+        }
+
+        if let Some(argument) = text
+            .strip_prefix("/pipeline")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            if !self
+                .ensure_builtin_access(access_tier, crate::access::Action::ViewPipelines)
+                .await
+            {
+                return Ok(true);
+            }
+            let body = match argument.trim() {
+                "" | "status" => self.render_pipeline_status().await,
+                other => self.text_with("pipeline.unknown", &[("command", other)]),
+            };
+            self.send_builtin_text(body, "pipeline").await;
+            return Ok(true);
+        }
 
         match text {
+            "/status mcp" => {
+                let body = self.mcp_status_report().await;
+                self.send_builtin_text(body, "status").await;
+                return Ok(true);
+            }
             "/status" => {
                 let routing = self.deps.runtime_config.routing.load();
                 let channel_model = self
@@ -1261,6 +5065,10 @@ impl Channel {
                      - mode: {}\n\
                      - channel model: {}\n\
                      - branch model: {}\n\
+                     - project: {}\n\
+                     - plan approval: {}\n\
+                     - language: {}\n\
+                     - your access: {}\n\
                      - time: {}",
                     self.deps.agent_id,
                     self.id,
@@ -1268,53 +5076,79 @@ impl Channel {
                     mode,
                     channel_model,
                     branch_model,
+                    self.resolved_settings
+                        .project_directory
+                        .as_deref()
+                        .unwrap_or("none"),
+                    if self.resolved_settings.plan_approval {
+                        "on"
+                    } else {
+                        "off"
+                    },
+                    self.resolved_settings.locale,
+                    access_tier,
                     now_line
                 );
+                let diagnostics = self.status_diagnostics().await;
+                let body = format!(
+                    "{body}\n{}",
+                    diagnostics
+                        .render(chrono::Utc::now(), &self.timestamps())
+                        .join("\n")
+                );
+                let links = self.dashboard_status_lines().await;
+                let body = if links.is_empty() {
+                    body
+                } else {
+                    format!("{body}\n{}", links.join("\n"))
+                };
                 self.send_builtin_text(body, "status").await;
                 return Ok(true);
             }
             "/quiet" | "/observe" => {
+                if !self
+                    .ensure_builtin_access(access_tier, crate::access::Action::ChangeResponseMode)
+                    .await
+                {
+                    return Ok(true);
+                }
+                self.audit_builtin_command(message, text);
                 self.set_response_mode(ResponseMode::Observe).await;
-                self.send_builtin_text(
-                    "observe mode enabled. i'll learn from this conversation but won't respond."
-                        .to_string(),
-                    "observe",
-                )
-                .await;
+                self.send_builtin_text(self.text("mode.observe"), "observe")
+                    .await;
                 return Ok(true);
             }
             "/active" => {
+                if !self
+                    .ensure_builtin_access(access_tier, crate::access::Action::ChangeResponseMode)
+                    .await
+                {
+                    return Ok(true);
+                }
+                self.audit_builtin_command(message, text);
                 self.set_response_mode(ResponseMode::Active).await;
-                self.send_builtin_text(
-                    "active mode enabled. i'll respond normally in this chat.".to_string(),
-                    "active",
-                )
-                .await;
+                self.send_builtin_text(self.text("mode.active"), "active")
+                    .await;
                 return Ok(true);
             }
             "/mention-only" => {
+                if !self
+                    .ensure_builtin_access(access_tier, crate::access::Action::ChangeResponseMode)
+                    .await
+                {
+                    return Ok(true);
+                }
+                self.audit_builtin_command(message, text);
                 self.set_response_mode(ResponseMode::MentionOnly).await;
-                self.send_builtin_text(
-                    "mention-only mode enabled. i'll only respond when @mentioned or replied to."
-                        .to_string(),
-                    "mention-only",
-                )
-                .await;
+                self.send_builtin_text(self.text("mode.mention_only"), "mention-only")
+                    .await;
                 return Ok(true);
             }
             "/help" => {
-                let lines = [
-                    "commands:".to_string(),
-                    "- /status: current mode, models, binding snapshot".to_string(),
-                    "- /today: in-progress + ready task snapshot".to_string(),
-                    "- /tasks: ready task list".to_string(),
-                    "- /digest: one-shot day digest (00:00 -> now)".to_string(),
-                    "- /observe: learn from conversation, never respond".to_string(),
-                    "- /mention-only: only respond when @mentioned, replied to, or given a command"
-                        .to_string(),
-                    "- /active: normal reply mode".to_string(),
-                    "- /agent-id: runtime agent id".to_string(),
-                ];
+                let mut lines: Vec<String> = HELP_KEYS.iter().map(|key| self.text(key)).collect();
+                for command in crate::plugins::PluginRegistry::global().commands() {
+                    lines.push(format!("- /{}: {}", command.name, command.description));
+                }
                 let body = lines.join("\n");
                 self.send_builtin_text(body, "help").await;
                 return Ok(true);
@@ -1322,6 +5156,26 @@ impl Channel {
             _ => {}
         }
 
+        if let Some((command, arguments)) = crate::plugins::split_command(text) {
+            let invocation = crate::plugins::CommandInvocation {
+                agent_id: self.deps.agent_id.clone(),
+                conversation_id: message.conversation_id.clone(),
+                source: message.source.clone(),
+                sender_id: message.sender_id.clone(),
+                access_tier,
+                command: command.to_string(),
+                arguments: arguments.to_string(),
+            };
+            if let Some(reply) = crate::plugins::PluginRegistry::global()
+                .handle_command(invocation)
+                .await
+            {
+                self.audit_builtin_command(message, text);
+                self.send_builtin_text(reply, "plugin").await;
+                return Ok(true);
+            }
+        }
+
         Ok(false)
     }
 
@@ -1369,7 +5223,11 @@ impl Channel {
             tokio::select! {
                 Some(message) = self.message_rx.recv() => {
                     let config = self.deps.runtime_config.coalesce.load();
-                    if self.should_coalesce(&message, &config) {
+                    if matches!(message.content, crate::MessageContent::Deleted { .. }) {
+                        self.handle_message_deleted(&message).await;
+                    } else if self.held_by_driver_lock(&message) {
+                        self.hold_for_driver(message).await;
+                    } else if self.should_coalesce(&message, &config) {
                         self.coalesce_buffer.push(message);
                         self.update_coalesce_deadline(&config).await;
                     } else {
@@ -1476,13 +5334,18 @@ impl Channel {
         if config.multi_user_only && self.is_dm() {
             return false;
         }
-        // Built-in slash commands should execute immediately and never be batched.
+        // Built-in slash commands and `!context` additions should execute
+        // immediately and never be batched.
+        let is_command = |value: &str| {
+            value.trim_start().starts_with('/')
+                || channel_context::parse_context_command(value).is_some()
+        };
         let looks_like_command = match &message.content {
-            crate::MessageContent::Text(text) => text.trim_start().starts_with('/'),
-            crate::MessageContent::Media { text, .. } => text
-                .as_deref()
-                .is_some_and(|value| value.trim_start().starts_with('/')),
-            crate::MessageContent::Interaction { .. } => false,
+            crate::MessageContent::Text(text) => is_command(text),
+            crate::MessageContent::Media { text, .. } => text.as_deref().is_some_and(is_command),
+            crate::MessageContent::Interaction { .. } | crate::MessageContent::Deleted { .. } => {
+                false
+            }
         };
         if looks_like_command {
             return false;
@@ -1494,7 +5357,7 @@ impl Channel {
     fn is_dm(&self) -> bool {
         self.conversation_id
             .as_deref()
-            .is_some_and(is_dm_conversation_id)
+            .is_some_and(crate::conversation::private::is_dm_conversation_id)
     }
 
     /// Update the coalesce deadline based on buffer size and config.
@@ -1567,6 +5430,7 @@ impl Channel {
     async fn handle_message_batch(&mut self, messages: Vec<InboundMessage>) -> Result<()> {
         // Apply runtime-config updates immediately without requiring a restart.
 
+        self.last_activity_at = Some(chrono::Utc::now());
         let message_count = messages.len();
         let batch_start_timestamp = messages
             .iter()
@@ -1634,6 +5498,9 @@ impl Channel {
         {
             self.source_adapter = first.adapter.clone().or_else(|| Some(first.source.clone()));
         }
+        if messages.iter().any(is_email_prompt) {
+            self.email_prompt = true;
+        }
 
         // Capture conversation context from the first message
         if self.conversation_context.is_none()
@@ -1672,7 +5539,7 @@ impl Channel {
             Option<Vec<channel_attachments::SavedAttachmentWithBytes>>,
         )> = Vec::new();
         let mut conversation_id = String::new();
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context();
         let mut batch_has_invoke = false;
 
         for message in &messages {
@@ -1685,7 +5552,8 @@ impl Channel {
                         (text.clone().unwrap_or_default(), attachments.clone())
                     }
                     // Render interactions as their Display form so the LLM sees plain text.
-                    crate::MessageContent::Interaction { .. } => {
+                    crate::MessageContent::Interaction { .. }
+                    | crate::MessageContent::Deleted { .. } => {
                         (message.content.to_string(), Vec::new())
                     }
                 };
@@ -1866,6 +5734,14 @@ impl Channel {
             let mut reply_target = self.state.reply_target_message_id.write().await;
             *reply_target = messages.iter().rev().find_map(extract_message_id);
         }
+        if let Some(message) = messages
+            .iter()
+            .rev()
+            .find(|message| message.source != "system")
+        {
+            *self.state.turn_requester.write().await = turn_requester(message);
+            *self.state.turn_author.write().await = Some(message_display_name(message).to_string());
+        }
 
         // Pin the inbound routing target from the last non-system message in the
         // batch so the RoutedSender (and send_routed) carry the correct platform
@@ -1886,6 +5762,8 @@ impl Channel {
             )
             .await?;
 
+        self.post_deferred_replies(turn_result.deferred_replies.as_ref())
+            .await;
         self.handle_agent_result(
             turn_result.result,
             &turn_result.skip_flag,
@@ -1937,7 +5815,7 @@ impl Channel {
             &mcp_tool_names,
         )?;
 
-        let temporal_context = TemporalContext::from_runtime(rc.as_ref());
+        let temporal_context = self.temporal_context();
         let current_time_line = temporal_context.current_time_line();
         let system_info = self.build_system_info().await;
         let status_text = {
@@ -1958,8 +5836,12 @@ impl Channel {
         let adapter_prompt = if self.state.cron_outcome.is_some() {
             prompt_engine.render_channel_adapter_prompt("cron")
         } else {
-            self.current_adapter()
-                .and_then(|adapter| prompt_engine.render_channel_adapter_prompt(adapter))
+            let adapter = if self.email_prompt {
+                Some("email_prompt")
+            } else {
+                self.current_adapter()
+            };
+            adapter.and_then(|adapter| prompt_engine.render_channel_adapter_prompt(adapter))
         };
 
         let empty_to_none = |s: String| if s.is_empty() { None } else { Some(s) };
@@ -2003,7 +5885,7 @@ impl Channel {
         )?;
 
         prompt_engine.maybe_append_tool_use_enforcement(
-            system_prompt,
+            self.append_channel_instructions(system_prompt),
             tool_use_enforcement.as_ref(),
             &model_name,
         )
@@ -2067,6 +5949,9 @@ impl Channel {
                 .clone()
                 .or_else(|| Some(message.source.clone()));
         }
+        if is_email_prompt(&message) {
+            self.email_prompt = true;
+        }
 
         let (raw_text, attachments) = match &message.content {
             crate::MessageContent::Text(text) => (text.clone(), Vec::new()),
@@ -2074,7 +5959,25 @@ impl Channel {
                 (text.clone().unwrap_or_default(), attachments.clone())
             }
             // Render interactions as their Display form so the LLM sees plain text.
-            crate::MessageContent::Interaction { .. } => (message.content.to_string(), Vec::new()),
+            crate::MessageContent::Interaction { .. } | crate::MessageContent::Deleted { .. } => {
+                (message.content.to_string(), Vec::new())
+            }
+        };
+
+        // Voice notes transcribed here are submitted as text; their audio is
+        // still saved below but not sent to the model again.
+        #[cfg(feature = "transcribe")]
+        let (raw_text, audio_transcribed) = match self
+            .transcribe_voice_notes(&message, &raw_text, &attachments)
+            .await
+        {
+            Some(prompt) => (prompt, true),
+            None => (raw_text, false),
+        };
+        #[cfg(not(feature = "transcribe"))]
+        let audio_transcribed = false;
+        let needs_model_content = |attachment: &crate::Attachment| {
+            !(audio_transcribed && attachment.mime_type.starts_with("audio/"))
         };
 
         // Save attachments to disk when enabled, capturing bytes for LLM reuse
@@ -2155,6 +6058,81 @@ impl Channel {
             )?);
         }
 
+        if message.source != "system"
+            && let Some(addition) = channel_context::parse_context_command(&raw_text)
+        {
+            self.handle_context_addition(&message, addition, &attachments)
+                .await;
+            return Ok(());
+        }
+
+        if let crate::MessageContent::Interaction { action_id, .. } = &message.content
+            && let Some((decision, worker_id)) = channel_plan::parse_plan_interaction(action_id)
+        {
+            let access_tier =
+                crate::access::resolve_tier(self.deps.humans.load().as_ref(), &message);
+            if self
+                .ensure_builtin_access(access_tier, crate::access::Action::ApprovePlan)
+                .await
+            {
+                self.audit_builtin_command(&message, &format!("/{}", decision.as_str()));
+                let worker_id = worker_id.to_string();
+                self.handle_plan_decision(decision, Some(&worker_id)).await;
+            }
+            return Ok(());
+        }
+
+        if let crate::MessageContent::Interaction { action_id, .. } = &message.content
+            && let Some((decision, permission_id)) =
+                channel_permission::parse_permission_interaction(action_id)
+        {
+            let access_tier =
+                crate::access::resolve_tier(self.deps.humans.load().as_ref(), &message);
+            if self
+                .ensure_builtin_access(access_tier, crate::access::Action::ApproveToolCall)
+                .await
+            {
+                self.audit_builtin_command(&message, &format!("/{}", decision.as_str()));
+                let now = std::time::Instant::now();
+                self.pending_permissions
+                    .retain(|pending| pending.expires_at > now);
+                match self
+                    .pending_permissions
+                    .iter()
+                    .position(|pending| pending.permission_id == permission_id)
+                {
+                    Some(index) => {
+                        self.decide_permission(&message, index, decision).await;
+                    }
+                    None => {
+                        self.send_builtin_text(
+                            "that permission request was already answered or has expired."
+                                .to_string(),
+                            "permission",
+                        )
+                        .await;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        if let crate::MessageContent::Interaction { action_id, .. } = &message.content
+            && let Some(confirmed) = channel_abort::parse_interaction(action_id)
+        {
+            let access_tier =
+                crate::access::resolve_tier(self.deps.humans.load().as_ref(), &message);
+            if self
+                .ensure_builtin_access(access_tier, crate::access::Action::ManageMaintenance)
+                .await
+            {
+                let argument = if confirmed { "confirm" } else { "cancel" };
+                self.audit_builtin_command(&message, &format!("/admin abort-all {argument}"));
+                self.handle_abort_all_command(&message, argument).await;
+            }
+            return Ok(());
+        }
+
         if self
             .try_handle_builtin_ops_commands(&raw_text, &message)
             .await?
@@ -2162,14 +6140,50 @@ impl Channel {
             return Ok(());
         }
 
+        // A worker waiting on a question takes the next reply as its answer.
+        if message.source != "system" && !raw_text.trim_start().starts_with('/') {
+            let now = std::time::Instant::now();
+            self.pending_questions
+                .retain(|pending| pending.expires_at > now);
+            if !self.pending_questions.is_empty()
+                && crate::access::authorize(
+                    self.deps.humans.load().as_ref(),
+                    &message,
+                    crate::access::Action::AnswerQuestion,
+                )
+            {
+                self.answer_pending_question(&raw_text).await;
+                return Ok(());
+            }
+        }
+        self.last_activity_at = Some(chrono::Utc::now());
+
         let rewritten_text = if message.source == "system" {
             raw_text.clone()
+        } else if let Some(invocation) = crate::templates::parse_run_command(&raw_text) {
+            match self.render_template_invocation(invocation).await {
+                Ok(prompt) => prompt,
+                Err(error) => {
+                    self.send_builtin_text(error, "run").await;
+                    return Ok(());
+                }
+            }
         } else {
             self.rewrite_tool_routed_command_prompt(&raw_text)
                 .unwrap_or_else(|| raw_text.clone())
         };
+        let rewritten_text = match message.source.as_str() {
+            "system" => rewritten_text,
+            _ => crate::scripting::ScriptHooks::global()
+                .pre_prompt(
+                    crate::scripting::HookKind::Message,
+                    &rewritten_text,
+                    &crate::scripting::ScriptContext::from_message(&message),
+                )
+                .unwrap_or(rewritten_text),
+        };
 
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context();
         let message_timestamp = temporal_context.format_timestamp(message.timestamp);
         let user_text = format_user_message(&rewritten_text, &message, &message_timestamp);
 
@@ -2225,9 +6239,14 @@ impl Channel {
             let mut reply_target = self.state.reply_target_message_id.write().await;
             *reply_target = extract_message_id(&message);
         }
+        if let Some(requester) = turn_requester(&message) {
+            *self.state.turn_requester.write().await = Some(requester);
+            *self.state.turn_author.write().await =
+                Some(message_display_name(&message).to_string());
+        }
 
         let is_retrigger = message.source == "system";
-        let attachment_content = if !attachments.is_empty() {
+        let attachment_content = if attachments.iter().any(needs_model_content) {
             if let Some(ref saved_data) = saved_attachment_data {
                 // Reuse already-downloaded bytes for images/text; audio still
                 // needs transcription via the normal path so we fall through.
@@ -2235,6 +6254,9 @@ impl Channel {
                 let mut unsaved_attachments = Vec::new();
 
                 for (index, attachment) in attachments.iter().enumerate() {
+                    if !needs_model_content(attachment) {
+                        continue;
+                    }
                     if let Some((_, bytes)) = saved_data.get(index) {
                         // Audio attachments need transcription, not just bytes
                         if attachment.mime_type.starts_with("audio/") {
@@ -2255,6 +6277,11 @@ impl Channel {
                 }
                 content
             } else {
+                let attachments: Vec<_> = attachments
+                    .iter()
+                    .filter(|attachment| needs_model_content(attachment))
+                    .cloned()
+                    .collect();
                 download_attachments(&self.deps, &attachments).await
             }
         } else {
@@ -2276,6 +6303,8 @@ impl Channel {
             )
             .await?;
 
+        self.post_deferred_replies(turn_result.deferred_replies.as_ref())
+            .await;
         self.handle_agent_result(
             turn_result.result,
             &turn_result.skip_flag,
@@ -2631,6 +6660,20 @@ impl Channel {
     }
 
     /// Assemble the full system prompt using the PromptEngine.
+    /// Append the channel's `/project` binding and `/instructions` to a
+    /// rendered system prompt.
+    fn append_channel_instructions(&self, mut system_prompt: String) -> String {
+        if let Some(directory) = &self.resolved_settings.project_directory {
+            system_prompt.push_str(&format!(
+                "\n\n## Channel Project\n\nThis channel is bound to `{directory}`. OpenCode workers run there when you don't pass a directory, project, or worktree."
+            ));
+        }
+        if let Some(instructions) = &self.resolved_settings.instructions {
+            system_prompt.push_str(&format!("\n\n## Channel Instructions\n\n{instructions}"));
+        }
+        system_prompt
+    }
+
     async fn build_system_prompt(&self) -> crate::error::Result<String> {
         let rc = &self.deps.runtime_config;
         let prompt_engine = rc.prompts.load();
@@ -2651,7 +6694,7 @@ impl Channel {
             &mcp_tool_names,
         )?;
 
-        let temporal_context = TemporalContext::from_runtime(rc.as_ref());
+        let temporal_context = self.temporal_context();
         let current_time_line = temporal_context.current_time_line();
         let system_info = self.build_system_info().await;
         let status_text = {
@@ -2666,8 +6709,12 @@ impl Channel {
         let adapter_prompt = if self.state.cron_outcome.is_some() {
             prompt_engine.render_channel_adapter_prompt("cron")
         } else {
-            self.current_adapter()
-                .and_then(|adapter| prompt_engine.render_channel_adapter_prompt(adapter))
+            let adapter = if self.email_prompt {
+                Some("email_prompt")
+            } else {
+                self.current_adapter()
+            };
+            adapter.and_then(|adapter| prompt_engine.render_channel_adapter_prompt(adapter))
         };
 
         let project_context = self.build_project_context(&prompt_engine).await;
@@ -2708,12 +6755,32 @@ impl Channel {
         )?;
 
         prompt_engine.maybe_append_tool_use_enforcement(
-            system_prompt,
+            self.append_channel_instructions(system_prompt),
             tool_use_enforcement.as_ref(),
             &model_name,
         )
     }
 
+    /// Context window of the model this turn runs on: the model catalog's
+    /// figure when it knows the model, otherwise the configured
+    /// `context_window`.
+    async fn turn_context_window(&self) -> usize {
+        let model_name = match self.resolved_settings.resolve_model("channel") {
+            Some(override_model) => override_model.to_string(),
+            None => self
+                .deps
+                .runtime_config
+                .routing
+                .load()
+                .resolve(ProcessType::Channel, None)
+                .to_string(),
+        };
+        match crate::api::models::cached_context_window(&model_name).await {
+            Some(window) => window,
+            None => **self.deps.runtime_config.context_window.load(),
+        }
+    }
+
     /// Register per-turn tools, run the LLM agentic loop, and clean up.
     ///
     /// Returns the prompt result and per-turn flags for the caller to dispatch.
@@ -2731,6 +6798,44 @@ impl Channel {
         let replied_flag = crate::tools::new_replied_flag();
         let allow_direct_reply = !self.suppress_plaintext_fallback();
 
+        // A message that can't fit even with no history would be rejected by
+        // the provider; tell the sender instead of running the turn.
+        let context_window = self.turn_context_window().await;
+        if !is_retrigger
+            && let Err(oversized) =
+                crate::agent::prompt_guard::check_message(context_window, system_prompt, user_text)
+        {
+            tracing::warn!(
+                channel_id = %self.id,
+                message_tokens = oversized.message_tokens,
+                available_tokens = oversized.available_tokens,
+                "message too large for the context window, skipping turn"
+            );
+            let warning = self.text_with(
+                "prompt_guard.too_large",
+                &[
+                    ("tokens", &oversized.message_tokens.to_string()),
+                    ("limit", &oversized.available_tokens.to_string()),
+                ],
+            );
+            self.send_outbound_text(warning.clone(), "failed to send prompt size warning")
+                .await;
+            self.state.conversation_logger.log_bot_message_with_name(
+                &self.state.channel_id,
+                &warning,
+                Some(self.agent_display_name()),
+            );
+            skip_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+            return Ok(AgentTurnResult {
+                result: Ok(String::new()),
+                skip_flag,
+                replied_flag,
+                retrigger_reply_preserved: false,
+                reply_text: None,
+                deferred_replies: None,
+            });
+        }
+
         // Set the originating channel on the delegation tool so task completion
         // notifications route back to this conversation.
         let send_agent_message_tool = self
@@ -2752,8 +6857,14 @@ impl Channel {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
-        // reply() always sends live — cron channels use set_outcome() for delivery.
-        let reply_target = crate::tools::ReplyTarget::Live(Box::new(routed_sender.clone()));
+        // reply() sends live unless the channel posts one message per turn —
+        // cron channels use set_outcome() for delivery.
+        let deferred_replies = (self.resolved_settings.delivery == DeliveryMode::Summary)
+            .then(crate::tools::DeferredReplies::default);
+        let reply_target = match &deferred_replies {
+            Some(replies) => crate::tools::ReplyTarget::Deferred(replies.clone()),
+            None => crate::tools::ReplyTarget::Live(Box::new(routed_sender.clone())),
+        };
 
         match self.resolved_settings.delegation {
             DelegationMode::Standard => {
@@ -2879,6 +6990,22 @@ impl Channel {
             let guard = self.state.history.read().await;
             guard.clone()
         };
+        // Trim the copy sent to the model; the channel's own history is left
+        // for the compactor.
+        let trimmed = crate::agent::prompt_guard::trim_history(
+            context_window,
+            system_prompt,
+            &mut history,
+            user_text,
+        );
+        if trimmed > 0 {
+            tracing::info!(
+                channel_id = %self.id,
+                trimmed,
+                remaining = history.len(),
+                "trimmed oldest history to fit the context window"
+            );
+        }
         let history_len_before = history.len();
 
         // ── Prompt snapshot capture (fire-and-forget) ──
@@ -2940,18 +7067,15 @@ impl Channel {
             tracing::warn!(%error, "failed to remove channel tools");
         }
 
-        // Flush accumulated token usage to the database.
-        let acc = usage_accumulator.lock().await;
-        if let Err(error) = acc
-            .flush(
-                &self.deps.sqlite_pool,
-                &self.deps.agent_id,
-                "channel",
-                Some(conversation_id),
-            )
+        // Queue accumulated token usage for the database.
+        let requester = self.state.turn_requester.read().await.clone();
+        let usage = usage_accumulator
+            .lock()
             .await
-        {
-            tracing::warn!(%error, "failed to flush token usage");
+            .to_record(&self.deps.agent_id, "channel", Some(conversation_id))
+            .map(|usage| usage.with_user(requester));
+        if let Some(usage) = usage {
+            self.deps.write_behind.write_usage(usage).await;
         }
 
         Ok(AgentTurnResult {
@@ -2960,9 +7084,34 @@ impl Channel {
             replied_flag,
             retrigger_reply_preserved: applied_history.retrigger_reply_preserved,
             reply_text: applied_history.reply_text,
+            deferred_replies,
         })
     }
 
+    /// Post the replies a `DeliveryMode::Summary` turn held back as one
+    /// message, closed by the tally of worker tools run since the last one.
+    /// When the turn didn't reply, the tally waits for the next turn that does.
+    async fn post_deferred_replies(&mut self, replies: Option<&crate::tools::DeferredReplies>) {
+        let Some(replies) = replies else {
+            return;
+        };
+        let replies = std::mem::take(
+            &mut *replies
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        );
+        if replies.is_empty() {
+            return;
+        }
+        let tool_tally = self.tool_tally.take();
+        let Some(response) = merge_turn_replies(replies, tool_tally.as_deref()) else {
+            return;
+        };
+        if let Err(error) = self.send_routed(response).await {
+            tracing::warn!(%error, channel_id = %self.id, "failed to send turn reply");
+        }
+    }
+
     /// Send outbound text and record send metrics.
     async fn send_outbound_text(&self, text: String, error_context: &str) {
         match self.send_routed(OutboundResponse::Text(text)).await {
@@ -3249,6 +7398,7 @@ impl Channel {
         if !event_is_for_channel(&event, &self.id) {
             return Ok(());
         }
+        self.last_activity_at = Some(chrono::Utc::now());
         // Update status block
         {
             let mut status = self.state.status_block.write().await;
@@ -3368,8 +7518,65 @@ impl Channel {
             } => {
                 run_logger.log_worker_status(*worker_id, status);
             }
+            ProcessEvent::ToolStarted {
+                process_id: ProcessId::Worker(_),
+                call_id,
+                args,
+                ..
+            } => {
+                self.pending_tool_args.insert(call_id.clone(), args.clone());
+            }
+            ProcessEvent::ToolCompleted {
+                process_id: ProcessId::Worker(_),
+                call_id,
+                tool_name,
+                result,
+                ..
+            } => {
+                let args = self.pending_tool_args.remove(call_id);
+                self.render_worker_tool_call(tool_name, args.as_deref(), result)
+                    .await;
+            }
+            ProcessEvent::WorkerTodos {
+                worker_id, todos, ..
+            } => {
+                self.update_todo_checklist(*worker_id, todos).await;
+            }
+            ProcessEvent::WorkerSessionTitle { title, .. } => {
+                self.rename_thread(title);
+            }
+            ProcessEvent::OpenCodePartUpdated {
+                worker_id,
+                part,
+                subagent: Some(subagent),
+                ..
+            } => {
+                self.update_subagent_section(*worker_id, subagent, part)
+                    .await;
+            }
+            ProcessEvent::OpenCodePartUpdated {
+                worker_id,
+                part,
+                subagent: None,
+                ..
+            } => {
+                if let Some(heartbeat) = self.heartbeats.get(worker_id) {
+                    heartbeat.record(part);
+                }
+            }
+            ProcessEvent::WorkerBusy {
+                worker_id, busy, ..
+            } => {
+                if *busy {
+                    self.start_heartbeat(*worker_id);
+                } else {
+                    self.stop_heartbeat(*worker_id).await;
+                }
+            }
             ProcessEvent::WorkerIdle { worker_id, .. } => {
                 run_logger.log_worker_idle(*worker_id);
+                self.stop_heartbeat(*worker_id).await;
+                self.deliver_pending_context(*worker_id).await;
             }
             ProcessEvent::WorkerComplete {
                 worker_id,
@@ -3378,6 +7585,8 @@ impl Channel {
                 success,
                 ..
             } => {
+                self.stop_heartbeat(*worker_id).await;
+
                 // Use worker_handles as the source of truth for active workers.
                 // (active_workers is never populated because Worker is consumed by .run())
                 if self
@@ -3392,10 +7601,29 @@ impl Channel {
                 }
 
                 run_logger.log_worker_completed(*worker_id, result, *success);
+                self.finish_side_streams(*worker_id).await;
+                self.opencode_sessions.remove(worker_id);
+                self.pending_plans.retain(|pending| pending != worker_id);
+                self.pending_questions
+                    .retain(|pending| pending.worker_id != *worker_id);
+                self.pending_permissions
+                    .retain(|pending| pending.worker_id != *worker_id);
+                self.pending_git.remove(worker_id);
 
                 self.state.active_workers.write().await.remove(worker_id);
                 self.state.worker_inputs.write().await.remove(worker_id);
                 self.state.worker_injections.write().await.remove(worker_id);
+                self.state
+                    .worker_contributors
+                    .write()
+                    .await
+                    .remove(worker_id);
+
+                // Context held for a worker that finished before going idle
+                // still belongs to the conversation.
+                if let Some(addition) = self.pending_context.take(worker_id) {
+                    self.push_context_into_history(&addition).await;
+                }
 
                 // Record worker completion in working memory.
                 let worker_summary = if result.len() > 200 {
@@ -3420,7 +7648,38 @@ impl Channel {
                     .importance(if *success { 0.6 } else { 0.8 })
                     .record();
 
-                if *notify {
+                if self.state.archived_workers.write().await.remove(worker_id) {
+                    // The session TTL closed this worker; its result was
+                    // already relayed when it went idle.
+                    let note = self.text("session.archived");
+                    self.send_builtin_text(note, "session_archived").await;
+                    if let Some(store) = crate::db::blob::BlobStore::global() {
+                        let write_behind = self.deps.write_behind.clone();
+                        let run_logger = self.state.process_run_logger.clone();
+                        let worker_id = worker_id.to_string();
+                        tokio::spawn(async move {
+                            // The worker queued its transcript just before completing.
+                            write_behind.flush().await;
+                            match run_logger.export_transcript(&worker_id, &store).await {
+                                Ok(Some(blob_id)) => tracing::info!(
+                                    %worker_id,
+                                    %blob_id,
+                                    backend = store.backend(),
+                                    "archived worker transcript"
+                                ),
+                                Ok(None) => {}
+                                Err(error) => tracing::warn!(
+                                    %error,
+                                    %worker_id,
+                                    "failed to archive worker transcript"
+                                ),
+                            }
+                        });
+                    }
+                } else if self.state.detached_workers.write().await.remove(worker_id) {
+                    // Unbound by /detach or /rebind, which already confirmed
+                    // it in chat.
+                } else if *notify {
                     // Accumulate result for the next retrigger instead of
                     // injecting into history as a fake user message.
                     self.pending_results.push(PendingResult {
@@ -3441,6 +7700,17 @@ impl Channel {
                 ..
             } => {
                 run_logger.log_opencode_metadata(*worker_id, session_id, *port);
+                self.opencode_sessions
+                    .insert(*worker_id, (session_id.clone(), *port));
+            }
+            ProcessEvent::WorkerInitialResult {
+                worker_id, result, ..
+            } if self.pending_git.contains_key(worker_id) => {
+                // The reply to a `/git` command is summarized directly
+                // rather than relayed through the channel LLM.
+                if let Some(command) = self.pending_git.remove(worker_id) {
+                    self.finish_git_command(*worker_id, command, result).await;
+                }
             }
             ProcessEvent::WorkerInitialResult {
                 worker_id, result, ..
@@ -3460,9 +7730,170 @@ impl Channel {
                     "interactive worker result queued for retrigger"
                 );
             }
+            ProcessEvent::WorkerQuestion {
+                worker_id,
+                question_id,
+                questions,
+                ..
+            } => {
+                self.post_worker_question(*worker_id, question_id, questions)
+                    .await;
+            }
+            ProcessEvent::WorkerPermission {
+                worker_id,
+                session_id,
+                permission_id,
+                description,
+                awaiting_approval: true,
+                ..
+            } => {
+                self.post_permission_request(*worker_id, session_id, permission_id, description)
+                    .await;
+            }
+            ProcessEvent::WorkerPlanReady {
+                worker_id, plan, ..
+            } => {
+                // Posted as-is rather than relayed through the channel LLM so
+                // what gets approved is exactly what the build agent receives.
+                if !self.pending_plans.contains(worker_id) {
+                    self.pending_plans.push(*worker_id);
+                }
+                let response = channel_plan::plan_message(*worker_id, plan);
+                match self.send_routed(response).await {
+                    Ok(()) => {
+                        self.state.conversation_logger.log_bot_message_with_name(
+                            &self.state.channel_id,
+                            &channel_plan::plan_text(*worker_id, plan),
+                            Some(self.agent_display_name()),
+                        );
+                        self.state
+                            .history
+                            .write()
+                            .await
+                            .push(rig::message::Message::Assistant {
+                                id: None,
+                                content: OneOrMany::one(rig::message::AssistantContent::text(
+                                    format!("[Background worker {worker_id} plan]: {plan}"),
+                                )),
+                            });
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, channel_id = %self.id, %worker_id, "failed to post plan");
+                    }
+                }
+            }
+            ProcessEvent::WorkerFilesChanged {
+                worker_id,
+                files,
+                session_total,
+                ..
+            } => {
+                let tool_rendering = self
+                    .deps
+                    .runtime_config
+                    .tool_rendering
+                    .load()
+                    .overlay(&self.resolved_settings.tool_rendering);
+                // Follows the `edit` tool's detail level, which is where
+                // most of these changes come from.
+                if tool_rendering.resolve("edit") != ToolDetail::Hidden
+                    && self.resolved_settings.delivery == DeliveryMode::Live
+                {
+                    let summary = self.link_paths(render_files_changed(files, *session_total));
+                    if let Err(error) = self.send_routed(OutboundResponse::Text(summary)).await {
+                        tracing::warn!(
+                            %error,
+                            channel_id = %self.id,
+                            %worker_id,
+                            "failed to post files-changed summary"
+                        );
+                    }
+                }
+            }
+            ProcessEvent::WorkerContextCompacted { worker_id, .. } => {
+                let notice = format!("worker {worker_id}: context compacted, retrying…");
+                match self
+                    .send_routed(OutboundResponse::Text(notice.clone()))
+                    .await
+                {
+                    Ok(()) => {
+                        self.state.conversation_logger.log_bot_message_with_name(
+                            &self.state.channel_id,
+                            &notice,
+                            Some(self.agent_display_name()),
+                        );
+                    }
+                    Err(error) => {
+                        tracing::warn!(
+                            %error,
+                            channel_id = %self.id,
+                            %worker_id,
+                            "failed to post compaction notice"
+                        );
+                    }
+                }
+            }
+            ProcessEvent::WorkerText {
+                worker_id, text, ..
+            } => {
+                self.post_worker_reasoning(worker_id, &text).await;
+            }
+            ProcessEvent::WorkerRawEvent {
+                worker_id, data, ..
+            } => {
+                self.post_debug_event(worker_id, &data).await;
+            }
+            ProcessEvent::WorkerDryRun {
+                worker_id, payload, ..
+            } => {
+                // Posted as-is: the point is to see the exact request body,
+                // not the channel LLM's paraphrase of it.
+                let header = self.text_with(
+                    "dry_run.payload",
+                    &[("worker", &channel_plan::short_id(worker_id))],
+                );
+                let text = format!("{header}\n```json\n{payload}\n```");
+                match self.send_routed(OutboundResponse::Text(text.clone())).await {
+                    Ok(()) => {
+                        self.state.conversation_logger.log_bot_message_with_name(
+                            &self.state.channel_id,
+                            &text,
+                            Some(self.agent_display_name()),
+                        );
+                        self.state
+                            .history
+                            .write()
+                            .await
+                            .push(rig::message::Message::Assistant {
+                                id: None,
+                                content: OneOrMany::one(rig::message::AssistantContent::text(
+                                    format!(
+                                        "[Background worker {worker_id} dry run]: prompt posted to the channel, not sent to OpenCode"
+                                    ),
+                                )),
+                            });
+                    }
+                    Err(error) => {
+                        tracing::warn!(
+                            %error,
+                            channel_id = %self.id,
+                            %worker_id,
+                            "failed to post dry-run payload"
+                        );
+                    }
+                }
+            }
             ProcessEvent::SettingsUpdated { channel_id, .. } if *channel_id == self.id => {
                 self.reload_settings().await;
             }
+            // Pre-created channels that haven't heard from anyone yet have
+            // nowhere to send it.
+            ProcessEvent::Broadcast { text, .. } if self.current_inbound.is_some() => {
+                self.send_builtin_text(text.clone(), "broadcast").await;
+            }
+            ProcessEvent::AbortAll { backend, .. } => {
+                self.abort_busy_sessions(backend.as_deref()).await;
+            }
             _ => {}
         }
 
@@ -3686,7 +8117,7 @@ impl Channel {
 
     /// Get the current status block as a string.
     pub async fn get_status(&self) -> String {
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context();
         let current_time_line = temporal_context.current_time_line();
         let system_info = self.build_system_info().await;
         let status = self.state.status_block.read().await;
@@ -3886,6 +8317,16 @@ fn compute_listen_mode_invocation(message: &InboundMessage, raw_text: &str) -> (
             .get("twitch_mentions_or_replies_to_bot")
             .and_then(|v| v.as_bool())
             .unwrap_or(false),
+        "irc" => message
+            .metadata
+            .get("irc_mentions_bot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        "mattermost" => message
+            .metadata
+            .get("mattermost_mentions_or_replies_to_bot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
         _ => false,
     };
     let invoked_by_reply = match message.source.as_str() {
@@ -3974,21 +8415,17 @@ fn should_send_quiet_mode_fallback(
         && !state.replied_flag
         && matches!(
             message.source.as_str(),
-            "discord" | "telegram" | "slack" | "twitch" | "signal"
+            "discord" | "telegram" | "slack" | "twitch" | "signal" | "irc" | "mattermost"
         )
 }
 
-/// Check if a conversation ID represents a DM (direct message).
-///
-/// Discord and Mattermost embed a `:dm:` segment in the conversation ID.
-/// Slack uses `slack:TEAM:DCHANNEL` where the channel ID starts with `D`.
-fn is_dm_conversation_id(conv_id: &str) -> bool {
-    conv_id.contains(":dm:")
-        || conv_id.starts_with("slack:")
-            && conv_id
-                .rsplit(':')
-                .next()
-                .is_some_and(|last| last.starts_with('D'))
+/// Whether an inbound email was sent to one of the adapter's prompt addresses.
+fn is_email_prompt(message: &InboundMessage) -> bool {
+    message
+        .metadata
+        .get("email_prompt")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -3997,7 +8434,7 @@ mod tests {
         ObserveModeFallbackState, branch_working_memory_event_summary,
         classify_conversational_event_summary, compute_listen_mode_invocation, decision_user_id,
         extract_decision_summary_from_reply, format_conversational_event_summary,
-        is_dm_conversation_id, recv_channel_event, should_process_event_for_channel,
+        recv_channel_event, should_process_event_for_channel,
         should_send_discord_quiet_mode_ping_ack, should_send_quiet_mode_fallback,
     };
     use crate::memory::{MemoryType, WorkingMemoryEventType};
@@ -4126,6 +8563,7 @@ mod tests {
             telegram_id: None,
             slack_id: None,
             email: None,
+            access: None,
         }];
         let message = InboundMessage {
             id: "message-1".to_string(),
@@ -4452,28 +8890,4 @@ mod tests {
             }
         ));
     }
-
-    #[test]
-    fn is_dm_conversation_id_detects_dm_patterns() {
-        // Slack DMs — channel ID starts with 'D'
-        assert!(is_dm_conversation_id("slack:T07GZRRFRRT:D0AHN0BM8D8"));
-        assert!(is_dm_conversation_id(
-            "slack:adapter:T07GZRRFRRT:D0AHN0BM8D8"
-        ));
-
-        // Discord DMs
-        assert!(is_dm_conversation_id("discord:dm:123456789"));
-
-        // Mattermost DMs
-        assert!(is_dm_conversation_id("mattermost:team1:dm:user1"));
-
-        // Generic :dm: pattern
-        assert!(is_dm_conversation_id("platform:dm:some-id"));
-
-        // Non-DM patterns
-        assert!(!is_dm_conversation_id("slack:T07GZRRFRRT:C12345"));
-        assert!(!is_dm_conversation_id("discord:guild:123:channel:456"));
-        assert!(!is_dm_conversation_id("discord:conversation"));
-        assert!(!is_dm_conversation_id(""));
-    }
 }
@@ -0,0 +1,120 @@
+//! Confirmation step for `/admin abort-all [backend]`.
+//!
+//! Aborting every busy session is instance-wide and can't be undone, so the
+//! command only arms a request. It is carried out when the same admin
+//! presses Confirm (or replies `/admin abort-all confirm`) within
+//! [`CONFIRM_WINDOW`]; Cancel, or letting it expire, drops it.
+
+use crate::{Button, ButtonStyle, InteractiveElements, OutboundResponse};
+
+use std::time::{Duration, Instant};
+
+/// `custom_id` / `action_id` prefix shared by both buttons, so the router
+/// can let clicks through during maintenance mode.
+pub const ACTION_PREFIX: &str = "admin_abort_all:";
+
+const CONFIRM_ACTION: &str = "admin_abort_all:confirm";
+
+const CANCEL_ACTION: &str = "admin_abort_all:cancel";
+
+/// How long a request waits for confirmation.
+pub const CONFIRM_WINDOW: Duration = Duration::from_secs(60);
+
+/// An armed abort-all waiting for confirmation.
+#[derive(Debug, Clone)]
+pub struct PendingAbortAll {
+    /// Only sessions on this OpenCode backend; every session when `None`.
+    pub backend: Option<String>,
+    /// `{source}:{sender_id}` of the admin who asked.
+    pub requested_by: String,
+    pub requested_at: Instant,
+}
+
+impl PendingAbortAll {
+    pub fn new(backend: Option<String>, requested_by: String) -> Self {
+        Self {
+            backend,
+            requested_by,
+            requested_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.requested_at) > CONFIRM_WINDOW
+    }
+}
+
+/// Decode a Confirm (`true`) or Cancel (`false`) button click.
+pub fn parse_interaction(action_id: &str) -> Option<bool> {
+    match action_id {
+        CONFIRM_ACTION => Some(true),
+        CANCEL_ACTION => Some(false),
+        _ => None,
+    }
+}
+
+/// The confirmation prompt with Confirm / Cancel controls. `text` is also
+/// the fallback for adapters without buttons.
+pub fn confirmation_message(text: String) -> OutboundResponse {
+    let slack_button = |label: &str, action_id: &str, style: &str| {
+        serde_json::json!({
+            "type": "button",
+            "text": { "type": "plain_text", "text": label },
+            "action_id": action_id,
+            "style": style,
+        })
+    };
+    let blocks = vec![
+        serde_json::json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": text },
+        }),
+        serde_json::json!({
+            "type": "actions",
+            "elements": [
+                slack_button("Confirm", CONFIRM_ACTION, "danger"),
+                slack_button("Cancel", CANCEL_ACTION, "primary"),
+            ],
+        }),
+    ];
+
+    OutboundResponse::RichMessage {
+        text,
+        blocks,
+        cards: Vec::new(),
+        interactive_elements: vec![InteractiveElements::Buttons {
+            buttons: vec![
+                Button {
+                    label: "Confirm".into(),
+                    custom_id: Some(CONFIRM_ACTION.to_string()),
+                    style: ButtonStyle::Danger,
+                    url: None,
+                },
+                Button {
+                    label: "Cancel".into(),
+                    custom_id: Some(CANCEL_ACTION.to_string()),
+                    style: ButtonStyle::Secondary,
+                    url: None,
+                },
+            ],
+        }],
+        poll: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_buttons_and_expires() {
+        assert_eq!(parse_interaction(CONFIRM_ACTION), Some(true));
+        assert_eq!(parse_interaction(CANCEL_ACTION), Some(false));
+        assert_eq!(parse_interaction("plan_approve:1"), None);
+        assert!(CONFIRM_ACTION.starts_with(ACTION_PREFIX));
+
+        let pending = PendingAbortAll::new(None, "discord:42".to_string());
+        assert!(!pending.is_expired(pending.requested_at + CONFIRM_WINDOW));
+        assert!(pending.is_expired(pending.requested_at + CONFIRM_WINDOW * 2));
+    }
+}
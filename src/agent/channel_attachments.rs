@@ -75,7 +75,7 @@ pub(crate) async fn download_attachments(
 /// When `auth_header` is set (Slack), uses a no-redirect client and manually
 /// follows redirects so the `Authorization` header isn't silently stripped on
 /// cross-origin redirects. For public URLs (Discord/Telegram), uses a plain GET.
-async fn download_attachment_bytes(
+pub(crate) async fn download_attachment_bytes(
     http: &reqwest::Client,
     attachment: &crate::Attachment,
 ) -> std::result::Result<Vec<u8>, String> {
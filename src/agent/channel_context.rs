@@ -0,0 +1,105 @@
+//! User-initiated `!context` additions for busy workers.
+//!
+//! Users can post `!context <text>` (optionally with attachments) while a
+//! worker is running. The addition is injected immediately when the worker
+//! supports mid-run injection, otherwise it is held and delivered as a
+//! follow-up message at the worker's next idle boundary.
+
+use crate::{Attachment, WorkerId};
+
+use std::collections::HashMap;
+
+/// Prefix that marks a message as a context addition.
+pub const CONTEXT_COMMAND: &str = "!context";
+
+/// Return the addition text if `text` is a `!context` command. Returns an
+/// empty string when the command carries only attachments.
+pub fn parse_context_command(text: &str) -> Option<&str> {
+    let rest = text.trim_start().strip_prefix(CONTEXT_COMMAND)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// Render an addition as the message delivered to a worker or the channel.
+pub fn format_context_addition(sender: &str, text: &str, attachments: &[Attachment]) -> String {
+    let mut lines = vec![format!("[additional context from {sender}]")];
+    if !text.is_empty() {
+        lines.push(text.to_string());
+    }
+    for attachment in attachments {
+        lines.push(format!(
+            "[attachment: {} ({}) {}]",
+            attachment.filename, attachment.mime_type, attachment.url
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Additions waiting for a busy worker to go idle, in arrival order.
+#[derive(Debug, Default)]
+pub struct PendingContext {
+    by_worker: HashMap<WorkerId, Vec<String>>,
+}
+
+impl PendingContext {
+    pub fn push(&mut self, worker_id: WorkerId, addition: String) {
+        self.by_worker.entry(worker_id).or_default().push(addition);
+    }
+
+    /// Remove and return everything held for a worker, joined into one message.
+    pub fn take(&mut self, worker_id: &WorkerId) -> Option<String> {
+        self.by_worker
+            .remove(worker_id)
+            .filter(|additions| !additions.is_empty())
+            .map(|additions| additions.join("\n\n"))
+    }
+
+    pub fn len_for(&self, worker_id: &WorkerId) -> usize {
+        self.by_worker.get(worker_id).map_or(0, Vec::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_context_command() {
+        assert_eq!(
+            parse_context_command("!context use the staging db"),
+            Some("use the staging db")
+        );
+        assert_eq!(parse_context_command("  !context  "), Some(""));
+        assert_eq!(parse_context_command("!contextual"), None);
+        assert_eq!(parse_context_command("context please"), None);
+    }
+
+    #[test]
+    fn formats_attachments() {
+        let attachment = Attachment {
+            filename: "trace.log".into(),
+            mime_type: "text/plain".into(),
+            url: "https://files.example.com/trace.log".into(),
+            size_bytes: Some(10),
+            auth_header: None,
+            pre_saved_id: None,
+        };
+        let rendered = format_context_addition("Jamie", "see log", &[attachment]);
+        assert!(rendered.starts_with("[additional context from Jamie]"));
+        assert!(rendered.contains("see log"));
+        assert!(rendered.contains("trace.log (text/plain)"));
+    }
+
+    #[test]
+    fn pending_context_joins_in_order() {
+        let worker_id = WorkerId::new_v4();
+        let mut pending = PendingContext::default();
+        pending.push(worker_id, "first".into());
+        pending.push(worker_id, "second".into());
+        assert_eq!(pending.len_for(&worker_id), 2);
+        assert_eq!(pending.take(&worker_id).as_deref(), Some("first\n\nsecond"));
+        assert!(pending.take(&worker_id).is_none());
+    }
+}
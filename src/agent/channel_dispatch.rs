@@ -352,7 +352,8 @@ async fn spawn_branch(
             .model_overrides
             .resolve_model("branch")
             .map(String::from),
-    );
+    )
+    .with_requested_by(state.turn_requester.read().await.clone());
 
     let branch_id = branch.id;
     let prompt = prompt.to_owned();
@@ -602,7 +603,8 @@ pub async fn spawn_worker_from_state(
     worker_context: &WorkerContextMode,
 ) -> std::result::Result<WorkerId, AgentError> {
     check_worker_limit(state).await?;
-    let task = task.into();
+    check_private_session_policy(state, false).await?;
+    let task = scripted_worker_task(state, task.into());
     reserve_task_if_unique(state, &task).await?;
     ensure_dispatch_readiness(state, "worker");
 
@@ -616,6 +618,38 @@ pub async fn spawn_worker_from_state(
     result
 }
 
+/// Apply the private-session tool policy when the channel is a DM. The tier
+/// is the turn requester's; turns without one were started by the system.
+async fn check_private_session_policy(
+    state: &ChannelState,
+    opencode: bool,
+) -> std::result::Result<(), AgentError> {
+    let config = state.deps.runtime_config.private_sessions.load();
+    if !config.enabled || !crate::conversation::private::is_dm_conversation_id(&state.channel_id) {
+        return Ok(());
+    }
+    let requester = state.turn_requester.read().await.clone();
+    let (source, sender_id) = requester
+        .as_deref()
+        .and_then(|requester| requester.split_once(':'))
+        .unwrap_or(("system", ""));
+    let tier = crate::access::resolve_sender_tier(&state.deps.humans.load(), source, sender_id);
+    crate::conversation::private::check_worker_spawn(&config, tier, opencode)
+        .map_err(|reason| AgentError::Other(anyhow::anyhow!(reason)))
+}
+
+/// Run operator `pre_prompt` scripts over a worker task.
+fn scripted_worker_task(state: &ChannelState, task: String) -> String {
+    let context = crate::scripting::ScriptContext {
+        agent_id: state.deps.agent_id.to_string(),
+        conversation_id: state.channel_id.to_string(),
+        ..Default::default()
+    };
+    crate::scripting::ScriptHooks::global()
+        .pre_prompt(crate::scripting::HookKind::WorkerTask, &task, &context)
+        .unwrap_or(task)
+}
+
 /// Inner implementation of worker spawning, separated so the caller can
 /// handle task reservation cleanup in a single place.
 async fn spawn_worker_inner(
@@ -713,7 +747,7 @@ async fn spawn_worker_inner(
     }
 
     // Inject conversation history if needed
-    let initial_history: Vec<rig::message::Message> = match worker_context.history {
+    let mut initial_history: Vec<rig::message::Message> = match worker_context.history {
         WorkerHistoryMode::None => Vec::new(),
         WorkerHistoryMode::Summary => {
             // TODO: Generate an LLM-based summary of conversation history.
@@ -737,6 +771,20 @@ async fn spawn_worker_inner(
             history.clone()
         }
     };
+    let trimmed = crate::agent::prompt_guard::trim_history(
+        **rc.context_window.load(),
+        &system_prompt,
+        &mut initial_history,
+        task,
+    );
+    if trimmed > 0 {
+        tracing::info!(
+            channel_id = %state.channel_id,
+            trimmed,
+            remaining = initial_history.len(),
+            "trimmed injected worker history to fit the context window"
+        );
+    }
 
     let worker_model_override = state
         .model_overrides
@@ -792,6 +840,9 @@ async fn spawn_worker_inner(
             .insert(worker.id, inject_tx);
         worker
     };
+    let worker = worker
+        .with_requested_by(state.turn_requester.read().await.clone())
+        .with_wall_clock_timeout(state.model_overrides.worker_budget_secs);
 
     let worker_id = worker.id;
 
@@ -866,7 +917,8 @@ pub async fn spawn_opencode_worker_from_state(
     }
 
     check_worker_limit(state).await?;
-    let task = task.into();
+    check_private_session_policy(state, true).await?;
+    let task = scripted_worker_task(state, task.into());
     reserve_task_if_unique(state, &task).await?;
     ensure_dispatch_readiness(state, "opencode_worker");
 
@@ -878,6 +930,21 @@ pub async fn spawn_opencode_worker_from_state(
     result
 }
 
+/// Append the channel's `/instructions` to an OpenCode worker's system prompt.
+fn with_channel_instructions(
+    system_prompt: Option<String>,
+    instructions: Option<&str>,
+) -> Option<String> {
+    let Some(instructions) = instructions else {
+        return system_prompt;
+    };
+    let section = format!("## Channel Instructions\n\n{instructions}");
+    Some(match system_prompt {
+        Some(system_prompt) => format!("{system_prompt}\n\n{section}"),
+        None => section,
+    })
+}
+
 /// Inner implementation of OpenCode worker spawning, separated so the
 /// caller can handle task reservation cleanup in a single place.
 async fn spawn_opencode_worker_inner(
@@ -913,8 +980,12 @@ async fn spawn_opencode_worker_inner(
     let oc_secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
 
     // Build temporal/status context so OpenCode workers get the same system
-    // info (time, model, context window) as builtin workers.
-    let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
+    // info (time, model, context window) as builtin workers, followed by the
+    // channel's custom instructions.
+    let worker_status_text = with_channel_instructions(
+        build_worker_status_text(rc.as_ref(), &state.deps.sandbox),
+        state.model_overrides.instructions.as_deref(),
+    );
 
     let worker = if interactive {
         let (worker, input_tx) = crate::opencode::OpenCodeWorker::new_interactive(
@@ -939,7 +1010,14 @@ async fn spawn_opencode_worker_inner(
             Some(store) => worker.with_secrets_store(store.clone()),
             None => worker,
         };
-        worker.with_sqlite_pool(state.deps.sqlite_pool.clone())
+        let worker = if state.model_overrides.plan_approval {
+            worker.with_plan_approval()
+        } else {
+            worker
+        };
+        worker
+            .with_fallback_models(state.model_overrides.fallback_models.clone())
+            .with_write_behind(state.deps.write_behind.clone())
     } else {
         let worker = crate::opencode::OpenCodeWorker::new(
             Some(state.channel_id.clone()),
@@ -957,8 +1035,33 @@ async fn spawn_opencode_worker_inner(
             Some(store) => worker.with_secrets_store(store.clone()),
             None => worker,
         };
-        worker.with_sqlite_pool(state.deps.sqlite_pool.clone())
+        worker
+            .with_fallback_models(state.model_overrides.fallback_models.clone())
+            .with_write_behind(state.deps.write_behind.clone())
+    };
+    let dry_run = state
+        .model_overrides
+        .dry_run
+        .unwrap_or(opencode_config.dry_run);
+    let worker = if dry_run {
+        worker.with_dry_run()
+    } else {
+        worker
     };
+    let mut worker = worker.with_question_timeout(std::time::Duration::from_secs(
+        opencode_config.question_timeout_secs,
+    ));
+    let contributor = state.turn_contributor().await;
+    *worker
+        .contributor
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = contributor;
+    state
+        .worker_contributors
+        .write()
+        .await
+        .insert(worker.id, worker.contributor.clone());
+    worker.mirror_events = state.mirror_worker_events.clone();
 
     let worker_id = worker.id;
 
@@ -968,7 +1071,7 @@ async fn spawn_opencode_worker_inner(
         channel_id = %state.channel_id,
         worker_type = "opencode",
     );
-    let sqlite_pool = state.deps.sqlite_pool.clone();
+    let write_behind = state.deps.write_behind.clone();
     let handle = spawn_worker_task(
         worker_id,
         state.deps.event_tx.clone(),
@@ -987,25 +1090,11 @@ async fn spawn_opencode_worker_inner(
             // Persist the transcript built from SSE events so the worker detail
             // view can show the full conversation (text + tool calls + results).
             if !result.transcript.is_empty() {
-                let blob = crate::conversation::worker_transcript::serialize_steps(
-                    &result.transcript,
-                );
-                let tool_calls = result.tool_calls;
-                let wid = worker_id.to_string();
-                let pool = sqlite_pool.clone();
-                tokio::spawn(async move {
-                    if let Err(error) = sqlx::query(
-                        "UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?",
-                    )
-                    .bind(&blob)
-                    .bind(tool_calls)
-                    .bind(&wid)
-                    .execute(&pool)
-                    .await
-                    {
-                        tracing::warn!(%error, worker_id = wid, "failed to persist OpenCode transcript");
-                    }
-                });
+                let blob =
+                    crate::conversation::worker_transcript::serialize_steps(&result.transcript);
+                write_behind
+                    .write_transcript(worker_id.to_string(), blob, result.tool_calls)
+                    .await;
             }
 
             Ok::<WorkerOutcome, SpacebotError>(WorkerOutcome::Success {
@@ -1208,6 +1297,205 @@ where
     }
 }
 
+/// Attach an OpenCode session started outside spacebot (`/attach`) to the
+/// channel as an idle interactive worker. Returns the worker ID and the
+/// session's messages, for the summary posted to the channel.
+pub async fn attach_opencode_session(
+    state: &ChannelState,
+    session_id: &str,
+    directory: &str,
+) -> std::result::Result<(WorkerId, Vec<serde_json::Value>), String> {
+    check_worker_limit(state)
+        .await
+        .map_err(|error| error.to_string())?;
+    let session = load_opencode_session(state, session_id, directory).await?;
+    let messages = session.messages.clone();
+    let worker_id = start_attached_session(state, session).await;
+
+    tracing::info!(worker_id = %worker_id, %session_id, "OpenCode session attached");
+    Ok((worker_id, messages))
+}
+
+/// Swap the channel's idle OpenCode worker `previous` for a worker on
+/// another session (`/rebind`). The new session is loaded first, so a bad
+/// session ID leaves the channel bound to the old one. The old session is
+/// detached, not deleted.
+pub async fn rebind_opencode_session(
+    state: &ChannelState,
+    previous: WorkerId,
+    session_id: &str,
+    directory: &str,
+) -> std::result::Result<(WorkerId, Vec<serde_json::Value>), String> {
+    let session = load_opencode_session(state, session_id, directory).await?;
+    state.detach_worker(previous).await?;
+    let messages = session.messages.clone();
+    let worker_id = start_attached_session(state, session).await;
+
+    tracing::info!(
+        worker_id = %worker_id,
+        previous_worker_id = %previous,
+        %session_id,
+        "OpenCode session rebound"
+    );
+    Ok((worker_id, messages))
+}
+
+/// An existing OpenCode session, loaded and ready to attach.
+struct LoadedSession {
+    session_id: String,
+    directory: std::path::PathBuf,
+    server_pool: Arc<crate::opencode::OpenCodeServerPool>,
+    messages: Vec<serde_json::Value>,
+}
+
+/// Check that the channel may attach `session_id` and fetch its messages.
+async fn load_opencode_session(
+    state: &ChannelState,
+    session_id: &str,
+    directory: &str,
+) -> std::result::Result<LoadedSession, String> {
+    let rc = &state.deps.runtime_config;
+    if !rc.opencode.load().enabled {
+        return Err("OpenCode workers are not enabled".into());
+    }
+    check_private_session_policy(state, true)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let directory = expand_tilde(directory);
+    let server_pool = rc.opencode_server_pool.load().clone();
+    let server = server_pool
+        .get_or_create(&directory)
+        .await
+        .map_err(|error| format!("couldn't open {}: {error:#}", directory.display()))?;
+    let messages = server
+        .lock()
+        .await
+        .get_messages(session_id)
+        .await
+        .map_err(|error| format!("couldn't load session {session_id}: {error:#}"))?;
+
+    Ok(LoadedSession {
+        session_id: session_id.to_string(),
+        directory,
+        server_pool,
+        messages,
+    })
+}
+
+/// Run a loaded session as an idle interactive worker in the channel.
+async fn start_attached_session(state: &ChannelState, session: LoadedSession) -> WorkerId {
+    let task = format!("attached session {}", session.session_id);
+    let directory_str = session.directory.to_string_lossy().to_string();
+    let (worker, input_tx) = crate::opencode::OpenCodeWorker::attach_interactive(
+        Some(state.channel_id.clone()),
+        state.deps.agent_id.clone(),
+        &task,
+        session.directory,
+        session.server_pool,
+        state.deps.event_tx.clone(),
+        session.session_id,
+        &session.messages,
+    );
+    let worker_id = worker.id;
+    run_reconnected_opencode_worker(state, worker, input_tx, &task, directory_str).await;
+    worker_id
+}
+
+/// Register a worker that picks up an existing OpenCode session into the
+/// channel's state and spawn its follow-up loop.
+async fn run_reconnected_opencode_worker(
+    state: &ChannelState,
+    mut worker: crate::opencode::OpenCodeWorker,
+    input_tx: tokio::sync::mpsc::Sender<String>,
+    task: &str,
+    directory: String,
+) {
+    let worker_id = worker.id;
+    let opencode_config = state.deps.runtime_config.opencode.load();
+
+    // Apply builder chain (same as spawn_opencode_worker_from_state).
+    if let Some(instructions) =
+        with_channel_instructions(None, state.model_overrides.instructions.as_deref())
+    {
+        worker = worker.with_system_prompt(instructions);
+    }
+    let oc_secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
+    if let Some(store) = &oc_secrets_store {
+        worker = worker.with_secrets_store(store.clone());
+    }
+    worker = worker
+        .with_write_behind(state.deps.write_behind.clone())
+        .with_question_timeout(std::time::Duration::from_secs(
+            opencode_config.question_timeout_secs,
+        ));
+    state
+        .worker_contributors
+        .write()
+        .await
+        .insert(worker_id, worker.contributor.clone());
+    worker.mirror_events = state.mirror_worker_events.clone();
+
+    state
+        .worker_inputs
+        .write()
+        .await
+        .insert(worker_id, input_tx);
+
+    let worker_span = tracing::info_span!(
+        "worker.resume",
+        worker_id = %worker_id,
+        channel_id = %state.channel_id,
+        worker_type = "opencode",
+    );
+    let write_behind = state.deps.write_behind.clone();
+    let handle = spawn_worker_task(
+        worker_id,
+        state.deps.event_tx.clone(),
+        state.deps.agent_id.clone(),
+        Some(state.channel_id.clone()),
+        oc_secrets_store,
+        "opencode",
+        async move {
+            let result = worker.run().await.map_err(SpacebotError::from)?;
+            // Persist final transcript.
+            if !result.transcript.is_empty() {
+                let blob =
+                    crate::conversation::worker_transcript::serialize_steps(&result.transcript);
+                write_behind
+                    .write_transcript(worker_id.to_string(), blob, result.tool_calls)
+                    .await;
+            }
+            Ok::<WorkerOutcome, SpacebotError>(WorkerOutcome::Success {
+                result: result.result_text,
+            })
+        }
+        .instrument(worker_span),
+    );
+
+    state.worker_handles.write().await.insert(worker_id, handle);
+
+    let opencode_task = format!("[opencode] {task}");
+    {
+        let mut status = state.status_block.write().await;
+        status.add_worker(worker_id, &opencode_task, false, true);
+    }
+
+    state
+        .deps
+        .event_tx
+        .send(ProcessEvent::WorkerStarted {
+            agent_id: state.deps.agent_id.clone(),
+            worker_id,
+            channel_id: Some(state.channel_id.clone()),
+            task: opencode_task,
+            worker_type: "opencode".into(),
+            interactive: true,
+            directory: Some(directory),
+        })
+        .ok();
+}
+
 /// Resume an idle interactive worker into a channel's state after restart.
 ///
 /// Loads the prior transcript, creates a resumed worker (builtin or opencode),
@@ -1257,90 +1545,19 @@ pub async fn resume_idle_worker_into_state(
             )
             .await;
 
-            let (mut worker, input_tx) = result.ok_or_else(|| {
+            let (worker, input_tx) = result.ok_or_else(|| {
                 "failed to reconnect to OpenCode session (server dead or session expired)"
                     .to_string()
             })?;
 
-            // Apply builder chain (same as spawn_opencode_worker_from_state).
-            let oc_secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
-            if let Some(store) = &oc_secrets_store {
-                worker = worker.with_secrets_store(store.clone());
-            }
-            worker = worker.with_sqlite_pool(state.deps.sqlite_pool.clone());
-
-            state
-                .worker_inputs
-                .write()
-                .await
-                .insert(worker_id, input_tx);
-
-            let worker_span = tracing::info_span!(
-                "worker.resume",
-                worker_id = %worker_id,
-                channel_id = %state.channel_id,
-                worker_type = "opencode",
-            );
-            let sqlite_pool = state.deps.sqlite_pool.clone();
-            let handle = spawn_worker_task(
-                worker_id,
-                state.deps.event_tx.clone(),
-                state.deps.agent_id.clone(),
-                Some(state.channel_id.clone()),
-                oc_secrets_store,
-                "opencode",
-                async move {
-                    let result = worker.run().await.map_err(SpacebotError::from)?;
-                    // Persist final transcript.
-                    if !result.transcript.is_empty() {
-                        let blob = crate::conversation::worker_transcript::serialize_steps(
-                            &result.transcript,
-                        );
-                        let tool_calls = result.tool_calls;
-                        let wid = worker_id.to_string();
-                        let pool = sqlite_pool.clone();
-                        tokio::spawn(async move {
-                            if let Err(error) = sqlx::query(
-                                "UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?",
-                            )
-                            .bind(&blob)
-                            .bind(tool_calls)
-                            .bind(&wid)
-                            .execute(&pool)
-                            .await
-                            {
-                                tracing::warn!(%error, worker_id = wid, "failed to persist OpenCode transcript");
-                            }
-                        });
-                    }
-                    Ok::<WorkerOutcome, SpacebotError>(WorkerOutcome::Success {
-                result: result.result_text,
-            })
-                }
-                .instrument(worker_span),
-            );
-
-            state.worker_handles.write().await.insert(worker_id, handle);
-
-            let opencode_task = format!("[opencode] {}", idle_worker.task);
-            {
-                let mut status = state.status_block.write().await;
-                status.add_worker(worker_id, &opencode_task, false, true);
-            }
-
-            state
-                .deps
-                .event_tx
-                .send(ProcessEvent::WorkerStarted {
-                    agent_id: state.deps.agent_id.clone(),
-                    worker_id,
-                    channel_id: Some(state.channel_id.clone()),
-                    task: opencode_task,
-                    worker_type: "opencode".into(),
-                    interactive: true,
-                    directory: Some(directory_str.clone()),
-                })
-                .ok();
+            run_reconnected_opencode_worker(
+                state,
+                worker,
+                input_tx,
+                &idle_worker.task,
+                directory_str,
+            )
+            .await;
 
             tracing::info!(worker_id = %worker_id, task = %idle_worker.task, "OpenCode worker resumed");
             Ok(worker_id)
@@ -1411,6 +1628,7 @@ pub async fn resume_idle_worker_into_state(
                 state.logs_dir.clone(),
                 prior_history,
             );
+            let worker = worker.with_wall_clock_timeout(state.model_overrides.worker_budget_secs);
 
             state
                 .worker_inputs
@@ -1472,7 +1690,7 @@ pub async fn resume_idle_worker_into_state(
 /// in conversation context. `std::path::Path::canonicalize()` doesn't expand
 /// tildes (that's a shell feature), so paths like `~/Projects/foo` fail with
 /// "directory does not exist". This handles the common cases.
-fn expand_tilde(path: &str) -> std::path::PathBuf {
+pub(crate) fn expand_tilde(path: &str) -> std::path::PathBuf {
     if path == "~" {
         dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("/"))
     } else if let Some(rest) = path.strip_prefix("~/") {
@@ -0,0 +1,102 @@
+//! Driver lock (`/driver`): one person steers a shared conversation.
+//!
+//! Anyone in a channel or thread can normally prompt the agent and the
+//! OpenCode sessions it runs there. While a driver lock is held, only the
+//! driver's messages start turns. Messages from everyone else are kept in
+//! history as context, the way observe mode keeps them, and each person is
+//! told once who is driving. Builtin and plugin commands keep their own
+//! access checks, and admins are never held back. `/run` and slash text no
+//! command handles become prompts for the model, so they are held too.
+
+use std::collections::HashSet;
+
+/// The user holding a channel's driver lock.
+#[derive(Debug, Clone)]
+pub struct DriverLock {
+    /// `source:sender_id` of the driver.
+    pub user_id: String,
+    pub display_name: String,
+    /// Users already told that their messages are held.
+    notified: HashSet<String>,
+}
+
+impl DriverLock {
+    pub fn new(user_id: impl Into<String>, display_name: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            display_name: display_name.into(),
+            notified: HashSet::new(),
+        }
+    }
+
+    pub fn is_driver(&self, user_id: &str) -> bool {
+        self.user_id == user_id
+    }
+
+    /// Whether to tell `user_id` their message was held. True only the first
+    /// time for each user.
+    pub fn should_notify(&mut self, user_id: &str) -> bool {
+        self.notified.insert(user_id.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriverCommand {
+    Show,
+    Take,
+    Release,
+}
+
+/// Parse the argument of `/driver`: nothing, `take`, or `release`.
+pub fn parse_driver_command(argument: &str) -> Option<DriverCommand> {
+    match argument.trim() {
+        "" => Some(DriverCommand::Show),
+        "take" | "me" | "on" => Some(DriverCommand::Take),
+        "release" | "off" => Some(DriverCommand::Release),
+        _ => None,
+    }
+}
+
+/// Whether `text` is a builtin or plugin command the channel answers itself,
+/// and so passes the lock. `/run` expands into a prompt for the model, so it
+/// doesn't count.
+pub fn is_handled_command(text: &str) -> bool {
+    let Some((name, _)) = crate::plugins::split_command(text) else {
+        return false;
+    };
+    (name != "run" && crate::plugins::BUILTIN_COMMANDS.contains(&name))
+        || crate::plugins::PluginRegistry::global().handles_command(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_driver_commands() {
+        assert_eq!(parse_driver_command(""), Some(DriverCommand::Show));
+        assert_eq!(parse_driver_command(" take"), Some(DriverCommand::Take));
+        assert_eq!(parse_driver_command("off"), Some(DriverCommand::Release));
+        assert_eq!(parse_driver_command("everyone"), None);
+    }
+
+    #[test]
+    fn only_handled_commands_pass_the_lock() {
+        assert!(is_handled_command("/status"));
+        assert!(is_handled_command(" /model gpt-4.1"));
+        assert!(!is_handled_command("/run review-pr url=x"));
+        assert!(!is_handled_command("/summarize the thread"));
+        assert!(!is_handled_command("fix the build"));
+    }
+
+    #[test]
+    fn each_held_user_is_notified_once() {
+        let mut lock = DriverLock::new("discord:1", "alice");
+        assert!(lock.is_driver("discord:1"));
+        assert!(!lock.is_driver("discord:2"));
+
+        assert!(lock.should_notify("discord:2"));
+        assert!(!lock.should_notify("discord:2"));
+        assert!(lock.should_notify("discord:3"));
+    }
+}
@@ -0,0 +1,342 @@
+//! Busy indicators for worker sessions.
+//!
+//! While an OpenCode session reports `busy`, the channel keeps one heartbeat
+//! running for it. On platforms with a typing indicator the heartbeat keeps
+//! re-triggering it, since most platforms expire it after a few seconds. On
+//! Slack, whose typing status only works inside assistant threads, it edits
+//! a status line instead, showing a spinner, the elapsed time, the steps
+//! completed so far, and the tool running now. Platforms with neither get no
+//! heartbeat.
+
+use crate::opencode::types::{OpenCodePart, OpenCodeToolState};
+use crate::prompts::messages;
+use crate::{InboundMessage, OutboundResponse, RoutedSender, StatusUpdate};
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often the typing indicator is re-triggered. Discord's lasts 10s and
+/// Telegram's 5s.
+pub const TYPING_INTERVAL: Duration = Duration::from_secs(4);
+
+/// How often the status line is edited.
+pub const STATUS_LINE_INTERVAL: Duration = Duration::from_secs(3);
+
+const SPINNER_FRAMES: [&str; 4] = ["◐", "◓", "◑", "◒"];
+
+/// How a platform shows that a session is busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeartbeatStyle {
+    Typing,
+    StatusLine,
+}
+
+impl HeartbeatStyle {
+    /// The style for messages from `source`, or `None` when the platform can
+    /// neither show typing nor edit a message.
+    pub fn for_source(source: &str) -> Option<Self> {
+        match source {
+            "discord" | "telegram" | "signal" | "mattermost" | "portal" => Some(Self::Typing),
+            "slack" => Some(Self::StatusLine),
+            _ => None,
+        }
+    }
+}
+
+/// What a busy session has done so far, from its own parts.
+#[derive(Debug, Default)]
+pub struct Progress {
+    finished_steps: HashSet<String>,
+    current_tool: Option<String>,
+}
+
+impl Progress {
+    /// Count finished steps and track the running tool. Parts are re-sent on
+    /// every update, so steps are counted by id.
+    pub fn apply(&mut self, part: &OpenCodePart) {
+        match part {
+            OpenCodePart::StepFinish { id, .. } => {
+                self.finished_steps.insert(id.clone());
+            }
+            OpenCodePart::Tool {
+                tool,
+                state: OpenCodeToolState::Pending | OpenCodeToolState::Running { .. },
+                ..
+            } => self.current_tool = Some(tool.clone()),
+            OpenCodePart::Tool { tool, .. } => {
+                if self.current_tool.as_ref() == Some(tool) {
+                    self.current_tool = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn steps(&self) -> usize {
+        self.finished_steps.len()
+    }
+
+    fn describe_steps(&self, locale: &str) -> String {
+        match self.steps() {
+            0 => String::new(),
+            1 => format!(" · {}", messages::get(locale, "status_line.one_step")),
+            steps => format!(
+                " · {}",
+                messages::get_with(
+                    locale,
+                    "status_line.steps",
+                    &[("count", &steps.to_string())]
+                )
+            ),
+        }
+    }
+
+    fn describe(&self, locale: &str) -> String {
+        let mut text = self.describe_steps(locale);
+        if let Some(tool) = &self.current_tool {
+            text.push_str(&format!(" · `{tool}`"));
+        }
+        text
+    }
+}
+
+/// A running heartbeat for one worker session.
+pub struct Heartbeat {
+    style: HeartbeatStyle,
+    sender: RoutedSender,
+    started_at: Instant,
+    progress: Arc<Mutex<Progress>>,
+    locale: String,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl Heartbeat {
+    /// Start a heartbeat for a session working on `target`'s conversation.
+    /// Status lines go to a message of their own, keyed by `key`, and are
+    /// written in `locale`.
+    pub fn start(
+        style: HeartbeatStyle,
+        response_tx: tokio::sync::mpsc::Sender<crate::RoutedResponse>,
+        target: &InboundMessage,
+        key: &str,
+        locale: &str,
+    ) -> Self {
+        let mut target = target.clone();
+        if style == HeartbeatStyle::StatusLine {
+            target.id = format!("{}:{key}", target.id);
+            target.metadata.insert(
+                crate::messaging::journal::STREAM_KEY_METADATA.into(),
+                key.into(),
+            );
+        }
+        let sender = RoutedSender::new(response_tx, target);
+        let started_at = Instant::now();
+        let progress = Arc::new(Mutex::new(Progress::default()));
+        let task_progress = progress.clone();
+        let task_sender = sender.clone();
+        let task_locale = locale.to_string();
+        let handle = tokio::spawn(async move {
+            match style {
+                HeartbeatStyle::Typing => loop {
+                    let thinking = OutboundResponse::Status(StatusUpdate::Thinking);
+                    if task_sender.send(thinking).await.is_err() {
+                        return;
+                    }
+                    tokio::time::sleep(TYPING_INTERVAL).await;
+                },
+                HeartbeatStyle::StatusLine => {
+                    if task_sender
+                        .send(OutboundResponse::StreamStart)
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                    for frame in 0.. {
+                        let line = status_line(
+                            frame,
+                            started_at.elapsed(),
+                            &task_progress
+                                .lock()
+                                .unwrap_or_else(std::sync::PoisonError::into_inner),
+                            &task_locale,
+                        );
+                        if task_sender
+                            .send(OutboundResponse::StreamChunk(line))
+                            .await
+                            .is_err()
+                        {
+                            return;
+                        }
+                        tokio::time::sleep(STATUS_LINE_INTERVAL).await;
+                    }
+                }
+            }
+        });
+        Self {
+            style,
+            sender,
+            started_at,
+            progress,
+            locale: locale.to_string(),
+            handle,
+        }
+    }
+
+    /// Fold one of the session's own parts into the status line.
+    pub fn record(&self, part: &OpenCodePart) {
+        self.progress
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .apply(part);
+    }
+
+    /// Stop the heartbeat, clearing the typing indicator or leaving the
+    /// status line with the total time worked.
+    pub async fn stop(self) {
+        self.handle.abort();
+        let responses = match self.style {
+            HeartbeatStyle::Typing => vec![OutboundResponse::Status(StatusUpdate::StopTyping)],
+            HeartbeatStyle::StatusLine => vec![
+                OutboundResponse::StreamChunk(format!(
+                    "{}{}",
+                    messages::get_with(
+                        &self.locale,
+                        "status_line.worked_for",
+                        &[("elapsed", &format_elapsed(self.started_at.elapsed()))],
+                    ),
+                    self.progress
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .describe_steps(&self.locale)
+                )),
+                OutboundResponse::StreamEnd,
+            ],
+        };
+        for response in responses {
+            if let Err(error) = self.sender.send(response).await {
+                tracing::debug!(%error, "failed to stop heartbeat");
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// One frame of the status line, e.g. `◐ Working… 1m 05s · 3 steps · `bash``.
+pub fn status_line(frame: usize, elapsed: Duration, progress: &Progress, locale: &str) -> String {
+    let spinner = SPINNER_FRAMES[frame % SPINNER_FRAMES.len()];
+    format!(
+        "{spinner} {} {}{}",
+        messages::get(locale, "status_line.working"),
+        format_elapsed(elapsed),
+        progress.describe(locale)
+    )
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(tool: &str, state: OpenCodeToolState) -> OpenCodePart {
+        OpenCodePart::Tool {
+            id: format!("part-{tool}"),
+            tool: tool.to_string(),
+            state,
+        }
+    }
+
+    #[test]
+    fn status_line_spins_and_counts() {
+        let mut progress = Progress::default();
+        assert_eq!(
+            status_line(0, Duration::from_secs(3), &progress, "en"),
+            "◐ Working… 3s"
+        );
+
+        for id in ["step-1", "step-1", "step-2"] {
+            progress.apply(&OpenCodePart::StepFinish {
+                id: id.to_string(),
+                reason: None,
+            });
+        }
+        progress.apply(&tool(
+            "bash",
+            OpenCodeToolState::Running {
+                title: None,
+                input: None,
+            },
+        ));
+        assert_eq!(
+            status_line(5, Duration::from_secs(65), &progress, "en"),
+            "◓ Working… 1m 05s · 2 steps · `bash`"
+        );
+
+        progress.apply(&tool(
+            "bash",
+            OpenCodeToolState::Completed {
+                title: None,
+                input: None,
+                output: None,
+            },
+        ));
+        assert_eq!(
+            status_line(1, Duration::from_secs(70), &progress, "en"),
+            "◓ Working… 1m 10s · 2 steps"
+        );
+        assert_eq!(
+            status_line(1, Duration::from_secs(70), &progress, "es"),
+            "◓ Trabajando… 1m 10s · 2 pasos"
+        );
+        assert_eq!(
+            HeartbeatStyle::for_source("slack"),
+            Some(HeartbeatStyle::StatusLine)
+        );
+        assert_eq!(HeartbeatStyle::for_source("irc"), None);
+    }
+
+    #[tokio::test]
+    async fn status_line_gets_its_own_message_and_a_final_line() {
+        let (response_tx, mut response_rx) = tokio::sync::mpsc::channel(16);
+        let mut target = InboundMessage::empty();
+        target.id = "1712.5".into();
+        let heartbeat = Heartbeat::start(
+            HeartbeatStyle::StatusLine,
+            response_tx,
+            &target,
+            "busy",
+            "en",
+        );
+
+        let start = response_rx.recv().await.unwrap();
+        assert!(matches!(start.response, OutboundResponse::StreamStart));
+        assert_eq!(start.target.id, "1712.5:busy");
+        let first = response_rx.recv().await.unwrap();
+        assert!(
+            matches!(first.response, OutboundResponse::StreamChunk(ref line) if line.starts_with("◐ Working…"))
+        );
+
+        heartbeat.stop().await;
+        let last = response_rx.recv().await.unwrap();
+        assert!(
+            matches!(last.response, OutboundResponse::StreamChunk(ref line) if line == "Worked for 0s")
+        );
+        let end = response_rx.recv().await.unwrap();
+        assert!(matches!(end.response, OutboundResponse::StreamEnd));
+    }
+}
@@ -406,6 +406,12 @@ pub(crate) fn extract_message_id(message: &InboundMessage) -> Option<String> {
         })
 }
 
+/// The `source:sender_id` usage is attributed to for a turn prompted by
+/// `message`. `None` for system messages such as retriggers.
+pub(crate) fn turn_requester(message: &InboundMessage) -> Option<String> {
+    (message.source != "system").then(|| format!("{}:{}", message.source, message.sender_id))
+}
+
 /// Check if a ProcessEvent is targeted at a specific channel.
 ///
 /// Events from branches and workers carry a channel_id. We only process events
@@ -473,22 +479,75 @@ pub(crate) fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId)
             channel_id: event_channel,
             ..
         }
+        | ProcessEvent::WorkerBusy {
+            channel_id: event_channel,
+            ..
+        }
         | ProcessEvent::WorkerInitialResult {
             channel_id: event_channel,
             ..
+        }
+        | ProcessEvent::WorkerPlanReady {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::WorkerDryRun {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::WorkerRawEvent {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::WorkerContextCompacted {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::WorkerFilesChanged {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::WorkerText {
+            channel_id: event_channel,
+            ..
         } => event_channel.as_ref() == Some(channel_id),
         ProcessEvent::OpenCodeSessionCreated {
             channel_id: event_channel,
             ..
+        }
+        | ProcessEvent::WorkerTodos {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::WorkerSessionTitle {
+            channel_id: event_channel,
+            ..
         } => event_channel.as_ref() == Some(channel_id),
         ProcessEvent::SettingsUpdated {
             channel_id: event_channel,
             ..
         } => event_channel == channel_id,
+        ProcessEvent::Broadcast { .. } | ProcessEvent::AbortAll { .. } => true,
+        // Sub-agent parts are rendered in chat. The worker's own parts are
+        // high-volume and mostly matter to the live transcript UI; only tool
+        // and step parts come through, for the busy status line.
+        ProcessEvent::OpenCodePartUpdated {
+            channel_id: event_channel,
+            subagent: Some(_),
+            ..
+        }
+        | ProcessEvent::OpenCodePartUpdated {
+            channel_id: event_channel,
+            part:
+                crate::opencode::types::OpenCodePart::Tool { .. }
+                | crate::opencode::types::OpenCodePart::StepStart { .. }
+                | crate::opencode::types::OpenCodePart::StepFinish { .. },
+            ..
+        } => event_channel.as_ref() == Some(channel_id),
         ProcessEvent::OpenCodePartUpdated { .. }
+        | ProcessEvent::OpenCodeServerLog { .. }
         | ProcessEvent::StatusUpdate { .. }
         | ProcessEvent::TaskUpdated { .. }
-        | ProcessEvent::WorkerText { .. }
         | ProcessEvent::CortexChatUpdate { .. }
         | ProcessEvent::ToolOutput { .. } => false,
     }
@@ -0,0 +1,254 @@
+//! Permission requests from OpenCode workers, approved in chat.
+//!
+//! A permission OpenCode is configured to `ask` for (a shell command, an
+//! edit, a fetch) is posted to the channel with Allow / Deny buttons (or
+//! `/allow` and `/deny` on adapters without buttons). Only admins may answer,
+//! and the answer goes straight to the session's pending request. A request
+//! nobody answers within `opencode.question_timeout_secs` is rejected by the
+//! worker.
+
+use crate::agent::channel_plan::short_id;
+use crate::opencode::PermissionReply;
+use crate::{Button, ButtonStyle, InteractiveElements, OutboundResponse, WorkerId};
+
+use std::time::Instant;
+
+/// `custom_id` / `action_id` prefix of the allow button.
+pub const ALLOW_ACTION_PREFIX: &str = "permission_allow:";
+
+/// `custom_id` / `action_id` prefix of the deny button.
+pub const DENY_ACTION_PREFIX: &str = "permission_deny:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionDecision {
+    Allow,
+    Deny,
+}
+
+impl PermissionDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PermissionDecision::Allow => "allow",
+            PermissionDecision::Deny => "deny",
+        }
+    }
+
+    /// The reply sent to OpenCode. Allowing covers this request only.
+    pub fn reply(self) -> PermissionReply {
+        match self {
+            PermissionDecision::Allow => PermissionReply::Once,
+            PermissionDecision::Deny => PermissionReply::Reject,
+        }
+    }
+}
+
+/// A permission request posted to the channel and waiting for an admin.
+#[derive(Debug, Clone)]
+pub struct PendingPermission {
+    pub worker_id: WorkerId,
+    pub session_id: String,
+    pub permission_id: String,
+    pub description: String,
+    /// When the worker stops waiting and rejects it itself.
+    pub expires_at: Instant,
+}
+
+/// Decode an allow/deny button click into the decision and permission id.
+pub fn parse_permission_interaction(action_id: &str) -> Option<(PermissionDecision, &str)> {
+    if let Some(permission_id) = action_id.strip_prefix(ALLOW_ACTION_PREFIX) {
+        Some((PermissionDecision::Allow, permission_id))
+    } else {
+        action_id
+            .strip_prefix(DENY_ACTION_PREFIX)
+            .map(|permission_id| (PermissionDecision::Deny, permission_id))
+    }
+}
+
+/// Parse `/allow [worker]` or `/deny [worker]`.
+pub fn parse_permission_command(text: &str) -> Option<(PermissionDecision, Option<&str>)> {
+    let (decision, rest) = if let Some(rest) = text.strip_prefix("/allow") {
+        (PermissionDecision::Allow, rest)
+    } else {
+        (PermissionDecision::Deny, text.strip_prefix("/deny")?)
+    };
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let worker = rest.trim();
+    Some((decision, (!worker.is_empty()).then_some(worker)))
+}
+
+/// Pick the pending request a `/allow` or `/deny` applies to. `requested` may
+/// be a full worker id or a prefix, and picks that worker's oldest request.
+/// Without it, every pending request must come from one worker.
+pub fn resolve_pending_permission(
+    pending: &[PendingPermission],
+    requested: Option<&str>,
+) -> Result<usize, String> {
+    let candidates: Vec<usize> = pending
+        .iter()
+        .enumerate()
+        .filter(|(_, permission)| {
+            requested
+                .is_none_or(|requested| permission.worker_id.to_string().starts_with(requested))
+        })
+        .map(|(index, _)| index)
+        .collect();
+    let Some(&first) = candidates.first() else {
+        return Err(match requested {
+            Some(requested) => {
+                format!("no permission request from worker '{requested}' is waiting.")
+            }
+            None => "no permission request is waiting for approval.".to_string(),
+        });
+    };
+    let worker_id = pending[first].worker_id;
+    if candidates
+        .iter()
+        .any(|&index| pending[index].worker_id != worker_id)
+    {
+        let mut workers: Vec<String> = candidates
+            .iter()
+            .map(|&index| short_id(pending[index].worker_id))
+            .collect();
+        workers.sort();
+        workers.dedup();
+        return Err(match requested {
+            Some(requested) => format!("'{requested}' matches more than one worker."),
+            None => format!(
+                "permission requests from {} workers are waiting. name one: {}",
+                workers.len(),
+                workers.join(", ")
+            ),
+        });
+    }
+    Ok(first)
+}
+
+/// Plain-text form of a permission request, also the fallback for adapters
+/// without buttons.
+pub fn permission_text(worker_id: WorkerId, description: &str) -> String {
+    let short = short_id(worker_id);
+    format!(
+        "worker {short} asks for permission: {description}\n\nan admin can reply /allow {short} to allow it once or /deny {short} to refuse."
+    )
+}
+
+/// The chat message carrying a permission request and its allow/deny
+/// controls.
+pub fn permission_message(
+    worker_id: WorkerId,
+    permission_id: &str,
+    description: &str,
+) -> OutboundResponse {
+    let allow_id = format!("{ALLOW_ACTION_PREFIX}{permission_id}");
+    let deny_id = format!("{DENY_ACTION_PREFIX}{permission_id}");
+    let text = permission_text(worker_id, description);
+
+    let slack_button = |label: &str, action_id: &str, style: &str| {
+        serde_json::json!({
+            "type": "button",
+            "text": { "type": "plain_text", "text": label },
+            "action_id": action_id,
+            "value": permission_id,
+            "style": style,
+        })
+    };
+    let blocks = vec![
+        serde_json::json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!("*worker {} asks for permission*\n`{description}`", short_id(worker_id)),
+            },
+        }),
+        serde_json::json!({
+            "type": "actions",
+            "elements": [
+                slack_button("Allow once", &allow_id, "primary"),
+                slack_button("Deny", &deny_id, "danger"),
+            ],
+        }),
+    ];
+
+    OutboundResponse::RichMessage {
+        text,
+        blocks,
+        cards: Vec::new(),
+        interactive_elements: vec![InteractiveElements::Buttons {
+            buttons: vec![
+                Button {
+                    label: "Allow once".into(),
+                    custom_id: Some(allow_id),
+                    style: ButtonStyle::Success,
+                    url: None,
+                },
+                Button {
+                    label: "Deny".into(),
+                    custom_id: Some(deny_id),
+                    style: ButtonStyle::Danger,
+                    url: None,
+                },
+            ],
+        }],
+        poll: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(worker_id: &str, permission_id: &str) -> PendingPermission {
+        PendingPermission {
+            worker_id: uuid::Uuid::parse_str(worker_id).unwrap(),
+            session_id: "ses-1".to_string(),
+            permission_id: permission_id.to_string(),
+            description: "bash: rm -rf target".to_string(),
+            expires_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn parses_buttons_and_commands() {
+        assert_eq!(
+            parse_permission_interaction("permission_allow:per_1"),
+            Some((PermissionDecision::Allow, "per_1"))
+        );
+        assert_eq!(
+            parse_permission_interaction("permission_deny:per_1"),
+            Some((PermissionDecision::Deny, "per_1"))
+        );
+        assert_eq!(parse_permission_interaction("plan_approve:1"), None);
+
+        assert_eq!(
+            parse_permission_command("/allow"),
+            Some((PermissionDecision::Allow, None))
+        );
+        assert_eq!(
+            parse_permission_command("/deny 1f0c"),
+            Some((PermissionDecision::Deny, Some("1f0c")))
+        );
+        assert_eq!(parse_permission_command("/allowed"), None);
+        assert_eq!(PermissionDecision::Deny.reply(), PermissionReply::Reject);
+    }
+
+    #[test]
+    fn resolves_the_pending_permission() {
+        let first = "11111111-0000-0000-0000-000000000000";
+        let second = "22222222-0000-0000-0000-000000000000";
+
+        assert!(resolve_pending_permission(&[], None).is_err());
+
+        let one_worker = [pending(first, "per_1"), pending(first, "per_2")];
+        assert_eq!(resolve_pending_permission(&one_worker, None), Ok(0));
+
+        let two_workers = [pending(first, "per_1"), pending(second, "per_2")];
+        assert!(resolve_pending_permission(&two_workers, None).is_err());
+        assert_eq!(
+            resolve_pending_permission(&two_workers, Some("2222")),
+            Ok(1)
+        );
+        assert!(resolve_pending_permission(&two_workers, Some("3333")).is_err());
+    }
+}
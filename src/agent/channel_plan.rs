@@ -0,0 +1,209 @@
+//! Plan-then-build approval for OpenCode workers (`/plan on`).
+//!
+//! With plan approval on, OpenCode workers spawned from the channel start
+//! their session under OpenCode's read-only `plan` agent. The plan is posted
+//! to chat with Approve / Reject buttons (or `/approve` and `/reject` on
+//! adapters without buttons). Approving sends
+//! [`PLAN_APPROVAL_PROMPT`](crate::opencode::worker::PLAN_APPROVAL_PROMPT) to
+//! the same session under the `build` agent; rejecting cancels the worker.
+//! Any other follow-up while the plan is pending asks for a revised plan.
+
+use crate::{Button, ButtonStyle, InteractiveElements, OutboundResponse, WorkerId};
+
+/// `custom_id` / `action_id` prefix of the approve button.
+pub const APPROVE_ACTION_PREFIX: &str = "plan_approve:";
+
+/// `custom_id` / `action_id` prefix of the reject button.
+pub const REJECT_ACTION_PREFIX: &str = "plan_reject:";
+
+/// Slack section blocks hold at most 3000 characters.
+const SLACK_SECTION_MAX_CHARS: usize = 2_900;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanDecision {
+    Approve,
+    Reject,
+}
+
+impl PlanDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlanDecision::Approve => "approve",
+            PlanDecision::Reject => "reject",
+        }
+    }
+}
+
+/// Decode an approve/reject button click into the decision and worker id.
+pub fn parse_plan_interaction(action_id: &str) -> Option<(PlanDecision, &str)> {
+    if let Some(worker_id) = action_id.strip_prefix(APPROVE_ACTION_PREFIX) {
+        Some((PlanDecision::Approve, worker_id))
+    } else {
+        action_id
+            .strip_prefix(REJECT_ACTION_PREFIX)
+            .map(|worker_id| (PlanDecision::Reject, worker_id))
+    }
+}
+
+/// Parse `/approve [worker]` or `/reject [worker]`.
+pub fn parse_plan_command(text: &str) -> Option<(PlanDecision, Option<&str>)> {
+    let (decision, rest) = if let Some(rest) = text.strip_prefix("/approve") {
+        (PlanDecision::Approve, rest)
+    } else {
+        (PlanDecision::Reject, text.strip_prefix("/reject")?)
+    };
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let worker = rest.trim();
+    Some((decision, (!worker.is_empty()).then_some(worker)))
+}
+
+/// Pick the pending plan a decision applies to. `requested` may be a full
+/// worker id or a prefix; without it there must be exactly one pending plan.
+pub fn resolve_pending_plan(
+    pending: &[WorkerId],
+    requested: Option<&str>,
+) -> Result<WorkerId, String> {
+    match requested {
+        None => match pending {
+            [] => Err("no plan is waiting for approval.".to_string()),
+            [worker_id] => Ok(*worker_id),
+            _ => Err(format!(
+                "{} plans are waiting. name one: {}",
+                pending.len(),
+                pending
+                    .iter()
+                    .map(|worker_id| short_id(*worker_id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        },
+        Some(requested) => {
+            let matches: Vec<WorkerId> = pending
+                .iter()
+                .copied()
+                .filter(|worker_id| worker_id.to_string().starts_with(requested))
+                .collect();
+            match matches.as_slice() {
+                [worker_id] => Ok(*worker_id),
+                [] => Err(format!("no plan from worker '{requested}' is waiting.")),
+                _ => Err(format!("'{requested}' matches more than one worker.")),
+            }
+        }
+    }
+}
+
+/// Plain-text form of a plan message, also the fallback for adapters
+/// without buttons.
+pub fn plan_text(worker_id: WorkerId, plan: &str) -> String {
+    let short = short_id(worker_id);
+    format!(
+        "plan from worker {short}:\n\n{}\n\nreply /approve {short} to build it or /reject {short} to cancel. any other follow-up revises the plan.",
+        plan.trim()
+    )
+}
+
+/// The chat message carrying a plan and its approve/reject controls.
+pub fn plan_message(worker_id: WorkerId, plan: &str) -> OutboundResponse {
+    let short = short_id(worker_id);
+    let approve_id = format!("{APPROVE_ACTION_PREFIX}{worker_id}");
+    let reject_id = format!("{REJECT_ACTION_PREFIX}{worker_id}");
+    let text = plan_text(worker_id, plan);
+
+    let section = match plan.trim().char_indices().nth(SLACK_SECTION_MAX_CHARS) {
+        Some((index, _)) => format!("{}…", &plan.trim()[..index]),
+        None => plan.trim().to_string(),
+    };
+    let slack_button = |label: &str, action_id: &str, style: &str| {
+        serde_json::json!({
+            "type": "button",
+            "text": { "type": "plain_text", "text": label },
+            "action_id": action_id,
+            "value": worker_id.to_string(),
+            "style": style,
+        })
+    };
+    let blocks = vec![
+        serde_json::json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("*plan from worker {short}*\n{section}") },
+        }),
+        serde_json::json!({
+            "type": "actions",
+            "elements": [
+                slack_button("Approve", &approve_id, "primary"),
+                slack_button("Reject", &reject_id, "danger"),
+            ],
+        }),
+    ];
+
+    OutboundResponse::RichMessage {
+        text,
+        blocks,
+        cards: Vec::new(),
+        interactive_elements: vec![InteractiveElements::Buttons {
+            buttons: vec![
+                Button {
+                    label: "Approve".into(),
+                    custom_id: Some(approve_id),
+                    style: ButtonStyle::Success,
+                    url: None,
+                },
+                Button {
+                    label: "Reject".into(),
+                    custom_id: Some(reject_id),
+                    style: ButtonStyle::Danger,
+                    url: None,
+                },
+            ],
+        }],
+        poll: None,
+    }
+}
+
+/// The first eight characters of a worker ID, as shown in chat.
+pub(crate) fn short_id(worker_id: WorkerId) -> String {
+    worker_id.simple().to_string().chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_buttons_and_commands() {
+        let worker_id = uuid::Uuid::new_v4();
+        let action_id = format!("{APPROVE_ACTION_PREFIX}{worker_id}");
+        assert_eq!(
+            parse_plan_interaction(&action_id),
+            Some((PlanDecision::Approve, worker_id.to_string().as_str()))
+        );
+        assert_eq!(parse_plan_interaction("poll_vote:1"), None);
+
+        assert_eq!(
+            parse_plan_command("/approve"),
+            Some((PlanDecision::Approve, None))
+        );
+        assert_eq!(
+            parse_plan_command("/reject 1f0c"),
+            Some((PlanDecision::Reject, Some("1f0c")))
+        );
+        assert_eq!(parse_plan_command("/approved"), None);
+    }
+
+    #[test]
+    fn resolves_the_pending_plan() {
+        let first = uuid::Uuid::parse_str("11111111-0000-0000-0000-000000000000").unwrap();
+        let second = uuid::Uuid::parse_str("22222222-0000-0000-0000-000000000000").unwrap();
+
+        assert!(resolve_pending_plan(&[], None).is_err());
+        assert_eq!(resolve_pending_plan(&[first], None), Ok(first));
+        assert!(resolve_pending_plan(&[first, second], None).is_err());
+        assert_eq!(
+            resolve_pending_plan(&[first, second], Some("2222")),
+            Ok(second)
+        );
+        assert!(resolve_pending_plan(&[first, second], Some("3333")).is_err());
+    }
+}
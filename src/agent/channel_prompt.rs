@@ -44,6 +44,47 @@ impl TemporalContext {
         }
     }
 
+    /// Like [`from_runtime`](Self::from_runtime), with a channel's own
+    /// timezone taking precedence over the agent's.
+    pub(crate) fn for_channel(
+        runtime_config: &crate::config::RuntimeConfig,
+        channel_timezone: Option<&str>,
+    ) -> Self {
+        let mut context = Self::from_runtime(runtime_config);
+        if let Some(timezone_name) = channel_timezone {
+            match timezone_name.parse::<Tz>() {
+                Ok(timezone) => {
+                    context.timezone = TemporalTimezone::Named {
+                        timezone_name: timezone_name.to_string(),
+                        timezone,
+                    };
+                }
+                Err(error) => tracing::warn!(
+                    timezone = %timezone_name,
+                    error = %error,
+                    "invalid channel timezone, using the agent's"
+                ),
+            }
+        }
+        context
+    }
+
+    /// The configured timezone, or `None` when falling back to system local.
+    pub(crate) fn named_timezone(&self) -> Option<Tz> {
+        match &self.timezone {
+            TemporalTimezone::Named { timezone, .. } => Some(*timezone),
+            TemporalTimezone::SystemLocal => None,
+        }
+    }
+
+    /// Name of the timezone times are shown in.
+    pub(crate) fn timezone_label(&self) -> &str {
+        match &self.timezone {
+            TemporalTimezone::Named { timezone_name, .. } => timezone_name,
+            TemporalTimezone::SystemLocal => "system local",
+        }
+    }
+
     pub(crate) fn resolve_timezone_from_names(
         user_timezone: Option<String>,
         cron_timezone: Option<String>,
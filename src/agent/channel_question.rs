@@ -0,0 +1,189 @@
+//! Questions from OpenCode workers, answered in chat.
+//!
+//! When an OpenCode session asks a question, the channel posts it with its
+//! options and takes the next message from someone allowed to steer workers
+//! as the answer instead of as a new prompt. The answer goes straight to the
+//! session's pending question. A question nobody answers within
+//! `opencode.question_timeout_secs` gets its first option, picked by the
+//! worker.
+
+use crate::WorkerId;
+use crate::agent::channel_plan::short_id;
+use crate::opencode::{QuestionAnswer, QuestionInfo};
+
+use std::time::Instant;
+
+/// A question posted to the channel and waiting for a reply.
+#[derive(Debug, Clone)]
+pub struct PendingQuestion {
+    pub worker_id: WorkerId,
+    pub question_id: String,
+    pub questions: Vec<QuestionInfo>,
+    /// When the worker stops waiting and picks the first option itself.
+    pub expires_at: Instant,
+}
+
+/// The chat message asking a worker's question.
+pub fn question_text(worker_id: WorkerId, questions: &[QuestionInfo]) -> String {
+    let mut lines = vec![format!("**question from worker {}**", short_id(worker_id))];
+    for (index, info) in questions.iter().enumerate() {
+        let prompt = match (&info.header, &info.question) {
+            (Some(header), Some(question)) => format!("{header}: {question}"),
+            (None, Some(question)) => question.clone(),
+            (Some(header), None) => header.clone(),
+            (None, None) => "(no question text)".to_string(),
+        };
+        if questions.len() > 1 {
+            lines.push(format!("> {}. {prompt}", index + 1));
+        } else {
+            lines.push(format!("> {prompt}"));
+        }
+        for (number, option) in info.options.iter().enumerate() {
+            match &option.description {
+                Some(description) => lines.push(format!(
+                    ">   {}) {} - {description}",
+                    number + 1,
+                    option.label
+                )),
+                None => lines.push(format!(">   {}) {}", number + 1, option.label)),
+            }
+        }
+    }
+    let hint = if questions.len() > 1 {
+        "reply with one answer per line, in order: an option number, an option, or your own words."
+    } else {
+        "reply with an option number, an option, or your own words."
+    };
+    lines.push(hint.to_string());
+    lines.join("\n")
+}
+
+/// Turn a chat reply into one answer per question. A reply line may be an
+/// option number, an option label, or free text.
+pub fn parse_answers(
+    questions: &[QuestionInfo],
+    reply: &str,
+) -> Result<Vec<QuestionAnswer>, String> {
+    if questions.len() <= 1 {
+        let reply = reply.trim();
+        if reply.is_empty() {
+            return Err("the answer is empty.".to_string());
+        }
+        return Ok(questions
+            .iter()
+            .map(|info| match_option(info, reply))
+            .collect());
+    }
+
+    let lines: Vec<&str> = reply
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.len() != questions.len() {
+        return Err(format!(
+            "the worker asked {} questions. answer each on its own line, in order.",
+            questions.len()
+        ));
+    }
+    Ok(questions
+        .iter()
+        .zip(lines)
+        .map(|(info, line)| match_option(info, line))
+        .collect())
+}
+
+fn match_option(info: &QuestionInfo, answer: &str) -> QuestionAnswer {
+    let by_number = answer
+        .trim_end_matches([')', '.'])
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_sub(1))
+        .and_then(|index| info.options.get(index));
+    let option = by_number.or_else(|| {
+        info.options
+            .iter()
+            .find(|option| option.label.eq_ignore_ascii_case(answer))
+    });
+    match option {
+        Some(option) => QuestionAnswer {
+            label: option.label.clone(),
+            description: option.description.clone(),
+        },
+        None => QuestionAnswer {
+            label: answer.to_string(),
+            description: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opencode::QuestionOption;
+
+    fn database_question() -> QuestionInfo {
+        QuestionInfo {
+            question: Some("Which database should the tests use?".into()),
+            header: Some("Database".into()),
+            options: vec![
+                QuestionOption {
+                    label: "Postgres".into(),
+                    description: Some("matches production".into()),
+                },
+                QuestionOption {
+                    label: "SQLite".into(),
+                    description: None,
+                },
+            ],
+        }
+    }
+
+    fn labels(answers: &[QuestionAnswer]) -> Vec<&str> {
+        answers.iter().map(|answer| answer.label.as_str()).collect()
+    }
+
+    #[test]
+    fn renders_the_question_with_numbered_options() {
+        let worker_id = uuid::Uuid::parse_str("5f0c1a2b-0000-0000-0000-000000000000").unwrap();
+        assert_eq!(
+            question_text(worker_id, &[database_question()]),
+            "**question from worker 5f0c1a2b**\n\
+             > Database: Which database should the tests use?\n\
+             >   1) Postgres - matches production\n\
+             >   2) SQLite\n\
+             reply with an option number, an option, or your own words."
+        );
+    }
+
+    #[test]
+    fn replies_match_numbers_labels_or_free_text() {
+        let questions = [database_question()];
+        assert_eq!(labels(&parse_answers(&questions, "2").unwrap()), ["SQLite"]);
+        assert_eq!(
+            labels(&parse_answers(&questions, "postgres").unwrap()),
+            ["Postgres"]
+        );
+        assert_eq!(
+            labels(&parse_answers(&questions, "use an in-memory fake").unwrap()),
+            ["use an in-memory fake"]
+        );
+        assert!(parse_answers(&questions, "  ").is_err());
+    }
+
+    #[test]
+    fn multiple_questions_take_one_line_each() {
+        let second = QuestionInfo {
+            question: Some("Run migrations first?".into()),
+            header: None,
+            options: vec![],
+        };
+        let questions = [database_question(), second];
+
+        assert_eq!(
+            labels(&parse_answers(&questions, "1\nyes, always").unwrap()),
+            ["Postgres", "yes, always"]
+        );
+        assert!(parse_answers(&questions, "1").is_err());
+    }
+}
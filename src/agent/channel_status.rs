@@ -0,0 +1,310 @@
+//! Session diagnostics for the `/status` command.
+//!
+//! The channel gathers what it knows about its own state (queued messages,
+//! last activity, running workers and their OpenCode sessions) plus the
+//! conversation's recorded spend, and renders it below the mode and model
+//! lines `/status` always shows.
+
+use crate::WorkerId;
+use crate::llm::usage::ConversationUsage;
+use crate::mcp::{McpConnectionState, McpServerStatus};
+use crate::messaging::timestamp::{TimestampStyle, Timestamps};
+use crate::opencode::types::McpStatus;
+
+use chrono::{DateTime, Utc};
+
+/// One running worker as `/status` reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerDiagnostic {
+    pub worker_id: WorkerId,
+    /// Last status text from the worker (`idle` when waiting for input).
+    pub status: String,
+    /// OpenCode session id, for OpenCode workers.
+    pub session_id: Option<String>,
+    /// `local` or the remote backend name serving the session.
+    pub backend: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl WorkerDiagnostic {
+    fn is_idle(&self) -> bool {
+        self.status == "idle"
+    }
+}
+
+/// Everything `/status` adds beyond mode and models.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelDiagnostics {
+    pub workers: Vec<WorkerDiagnostic>,
+    pub active_branches: usize,
+    /// Messages received but not yet handled (coalescing or queued).
+    pub queued_messages: usize,
+    /// Background results waiting for the next retrigger.
+    pub pending_results: usize,
+    pub last_activity_at: Option<DateTime<Utc>>,
+    /// `None` when the usage query failed.
+    pub usage: Option<ConversationUsage>,
+}
+
+impl ChannelDiagnostics {
+    /// Busy while a branch or a non-idle worker is running.
+    pub fn is_busy(&self) -> bool {
+        self.active_branches > 0 || self.workers.iter().any(|worker| !worker.is_idle())
+    }
+
+    /// Render as `/status` bullet lines, with times in the channel's format.
+    pub fn render(&self, now: DateTime<Utc>, timestamps: &Timestamps) -> Vec<String> {
+        let mut lines = Vec::new();
+        let state = if self.is_busy() { "busy" } else { "idle" };
+        lines.push(format!(
+            "- state: {state} ({} worker(s), {} branch(es))",
+            self.workers.len(),
+            self.active_branches
+        ));
+        lines.push(format!(
+            "- queue: {} message(s), {} pending result(s)",
+            self.queued_messages, self.pending_results
+        ));
+        lines.push(format!(
+            "- last activity: {}",
+            match self.last_activity_at {
+                Some(at) => format_ago(now, at, timestamps),
+                None => "none since start".to_string(),
+            }
+        ));
+        lines.push(match &self.usage {
+            Some(usage) => format!(
+                "- cost: ${:.4} over {} request(s) ({} in / {} out tokens)",
+                usage.estimated_cost_usd,
+                usage.request_count,
+                usage.input_tokens,
+                usage.output_tokens
+            ),
+            None => "- cost: unavailable".to_string(),
+        });
+        for worker in &self.workers {
+            let mut line = format!(
+                "- worker {}: {}, started {}",
+                worker.worker_id,
+                worker.status,
+                timestamps.format(worker.started_at, TimestampStyle::Time)
+            );
+            if let Some(session_id) = &worker.session_id {
+                line.push_str(&format!(", opencode session {session_id}"));
+            }
+            if let Some(backend) = &worker.backend {
+                line.push_str(&format!(" on {backend}"));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// One MCP server's state, for `/status mcp`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpEntry {
+    pub name: String,
+    pub state: String,
+    /// Failed, disconnected, or waiting on auth: its tools won't work.
+    pub down: bool,
+}
+
+impl McpEntry {
+    /// A server Spacebot connects to itself.
+    pub fn from_spacebot(status: &McpServerStatus) -> Self {
+        let (state, down) = match (&status.state, status.enabled) {
+            (_, false) => ("disabled".to_string(), false),
+            (McpConnectionState::Connected, true) => ("connected".to_string(), false),
+            (McpConnectionState::Connecting, true) => ("connecting".to_string(), false),
+            (McpConnectionState::Failed(error), true) => (format!("failed: {error}"), true),
+            (McpConnectionState::Disconnected, true) => ("disconnected".to_string(), true),
+        };
+        Self {
+            name: format!("{} ({})", status.name, status.transport),
+            state,
+            down,
+        }
+    }
+
+    /// A server configured in OpenCode.
+    pub fn from_opencode(name: &str, status: &McpStatus) -> Self {
+        let (state, down) = match status {
+            McpStatus::Connected => ("connected".to_string(), false),
+            McpStatus::Disabled => ("disabled".to_string(), false),
+            McpStatus::Failed { error } if error.is_empty() => ("failed".to_string(), true),
+            McpStatus::Failed { error } => (format!("failed: {error}"), true),
+            McpStatus::NeedsAuth => ("needs authentication".to_string(), true),
+            McpStatus::NeedsClientRegistration { .. } => {
+                ("needs client registration".to_string(), true)
+            }
+            McpStatus::Unknown => ("unknown".to_string(), false),
+        };
+        Self {
+            name: name.to_string(),
+            state,
+            down,
+        }
+    }
+}
+
+/// The MCP servers one source reports, for `/status mcp`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpReport {
+    /// `spacebot`, or the OpenCode server and the workers using it.
+    pub source: String,
+    /// The servers, or why the source couldn't be asked.
+    pub servers: Result<Vec<McpEntry>, String>,
+}
+
+/// Render `/status mcp`: each source's servers, then which are down.
+pub fn render_mcp_status(reports: &[McpReport]) -> String {
+    let mut lines = vec!["mcp servers".to_string()];
+    let mut total = 0;
+    let mut down = Vec::new();
+    for report in reports {
+        lines.push(format!("{}:", report.source));
+        match &report.servers {
+            Ok(servers) if servers.is_empty() => lines.push("- none configured".to_string()),
+            Ok(servers) => {
+                for server in servers {
+                    lines.push(format!("- {}: {}", server.name, server.state));
+                    total += 1;
+                    if server.down {
+                        down.push(server.name.as_str());
+                    }
+                }
+            }
+            Err(error) => lines.push(format!("- unreachable: {error}")),
+        }
+    }
+    if !down.is_empty() {
+        lines.push(format!(
+            "{} of {total} mcp server(s) down: {}. tool calls to them will fail.",
+            down.len(),
+            down.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_ago(now: DateTime<Utc>, at: DateTime<Utc>, timestamps: &Timestamps) -> String {
+    let seconds = (now - at).num_seconds().max(0);
+    let ago = match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        _ => format!("{}h {}m ago", seconds / 3600, (seconds % 3600) / 60),
+    };
+    format!(
+        "{} ({ago})",
+        timestamps.format(at, TimestampStyle::DateTime)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_mcp_status_and_flags_dead_servers() {
+        let spacebot = McpServerStatus {
+            name: "github".into(),
+            enabled: true,
+            transport: "stdio".into(),
+            state: McpConnectionState::Connected,
+        };
+        let statuses: std::collections::BTreeMap<String, McpStatus> =
+            serde_json::from_value(serde_json::json!({
+                "context7": { "status": "connected" },
+                "sentry": { "status": "failed", "error": "401 Unauthorized" },
+                "linear": { "status": "needs_auth" },
+            }))
+            .unwrap();
+        let reports = vec![
+            McpReport {
+                source: "spacebot".into(),
+                servers: Ok(vec![McpEntry::from_spacebot(&spacebot)]),
+            },
+            McpReport {
+                source: "opencode on local (worker 5f0c1a2b)".into(),
+                servers: Ok(statuses
+                    .iter()
+                    .map(|(name, status)| McpEntry::from_opencode(name, status))
+                    .collect()),
+            },
+            McpReport {
+                source: "opencode on build-box (worker 9a8b7c6d)".into(),
+                servers: Err("connection refused".into()),
+            },
+        ];
+
+        assert_eq!(
+            render_mcp_status(&reports),
+            "mcp servers\n\
+             spacebot:\n\
+             - github (stdio): connected\n\
+             opencode on local (worker 5f0c1a2b):\n\
+             - context7: connected\n\
+             - linear: needs authentication\n\
+             - sentry: failed: 401 Unauthorized\n\
+             opencode on build-box (worker 9a8b7c6d):\n\
+             - unreachable: connection refused\n\
+             2 of 4 mcp server(s) down: linear, sentry. tool calls to them will fail."
+        );
+    }
+
+    #[test]
+    fn renders_busy_channel_with_opencode_worker() {
+        let now = DateTime::parse_from_rfc3339("2026-05-01T12:10:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let worker_id = uuid::Uuid::nil();
+        let diagnostics = ChannelDiagnostics {
+            workers: vec![WorkerDiagnostic {
+                worker_id,
+                status: "running: bash".into(),
+                session_id: Some("ses_123".into()),
+                backend: Some("build-box".into()),
+                started_at: now - chrono::Duration::minutes(8),
+            }],
+            active_branches: 0,
+            queued_messages: 2,
+            pending_results: 0,
+            last_activity_at: Some(now - chrono::Duration::seconds(150)),
+            usage: Some(ConversationUsage {
+                request_count: 12,
+                input_tokens: 4_000,
+                output_tokens: 900,
+                estimated_cost_usd: 0.0421,
+            }),
+        };
+
+        assert_eq!(
+            diagnostics.render(now, &Timestamps::new("webhook", None)),
+            vec![
+                "- state: busy (1 worker(s), 0 branch(es))".to_string(),
+                "- queue: 2 message(s), 0 pending result(s)".to_string(),
+                "- last activity: 2026-05-01 12:07 UTC (2m ago)".to_string(),
+                "- cost: $0.0421 over 12 request(s) (4000 in / 900 out tokens)".to_string(),
+                format!(
+                    "- worker {worker_id}: running: bash, started 12:02 UTC, opencode session ses_123 on build-box"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn idle_workers_leave_channel_idle() {
+        let diagnostics = ChannelDiagnostics {
+            workers: vec![WorkerDiagnostic {
+                worker_id: uuid::Uuid::nil(),
+                status: "idle".into(),
+                session_id: None,
+                backend: None,
+                started_at: Utc::now(),
+            }],
+            ..ChannelDiagnostics::default()
+        };
+        assert!(!diagnostics.is_busy());
+    }
+}
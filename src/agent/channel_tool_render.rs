@@ -0,0 +1,733 @@
+//! Chat rendering of worker tool calls at a configured detail level.
+//!
+//! The channel posts one line per completed worker tool call, shaped by the
+//! `ToolDetail` resolved from `[defaults.tool_rendering]`, the agent's
+//! `tool_rendering`, and the binding's conversation settings. OpenCode
+//! todo lists and `task` sub-agent activity render as messages the channel
+//! edits in place; the files an OpenCode turn changed are summarized with
+//! their line counts once the turn ends.
+//!
+//! Channels in `DeliveryMode::Summary` post none of that live. Their tool
+//! calls are tallied and the tally closes the turn's single reply.
+//!
+//! While `/debug` is on, raw OpenCode events are mirrored one message each,
+//! pretty-printed and cut to [`RAW_EVENT_CHARS`].
+
+use crate::OutboundResponse;
+use crate::conversation::settings::ToolDetail;
+use crate::messaging::code_language::{infer_language, language_for_path};
+use crate::messaging::collapse::collapsed;
+use crate::opencode::render::fence_for;
+use crate::opencode::types::{
+    FileChange, FileChangeKind, OpenCodePart, OpenCodeToolState, TodoItem,
+};
+use crate::tools::truncate_chars_ellipsis;
+
+/// Maximum characters of tool output shown at `ToolDetail::Full`. Longer
+/// output is cut and marked; the overflow policy handles the message limit.
+const FULL_OUTPUT_CHARS: usize = 1_500;
+
+/// Output longer than this many lines is collapsed at `ToolDetail::Full`
+/// in channels with `collapse` on.
+const COLLAPSE_MIN_LINES: usize = 10;
+
+/// Maximum characters of a mirrored raw event's JSON.
+const RAW_EVENT_CHARS: usize = 1_500;
+
+/// Maximum characters of the argument summary shown next to the tool name.
+const ARGS_SUMMARY_CHARS: usize = 120;
+
+/// Argument fields naming the file a call worked on, used to pick the
+/// output's highlighting language.
+const PATH_ARG_FIELDS: &[&str] = &["file_path", "filePath", "path"];
+
+/// Argument fields worth showing, in preference order.
+const SUMMARY_ARG_FIELDS: &[&str] = &["command", "url", "query", "path", "pattern", "file_path"];
+
+/// Render a completed tool call, or `None` when the detail level hides it.
+pub fn render_tool_call(
+    tool_name: &str,
+    args: Option<&str>,
+    result: &str,
+    detail: ToolDetail,
+) -> Option<String> {
+    match detail {
+        ToolDetail::Hidden => None,
+        ToolDetail::Name => Some(format!("🔧 `{tool_name}`")),
+        ToolDetail::Summary => Some(summary_line(tool_name, args, result)),
+        ToolDetail::Full => {
+            let mut rendered = summary_line(tool_name, args, result);
+            let output = tool_output(result);
+            if !output.trim().is_empty() {
+                let shown = truncate_chars_ellipsis(output.trim_end(), FULL_OUTPUT_CHARS);
+                let fence = fence_for(&shown);
+                let language = output_language(args, &output);
+                rendered.push_str(&format!("\n{fence}{language}\n{shown}\n{fence}"));
+            }
+            Some(rendered)
+        }
+    }
+}
+
+/// Render a completed tool call at `ToolDetail::Full` with its output
+/// collapsed the way `platform` supports. `None` when the output is short
+/// enough to show inline.
+pub fn render_collapsed_tool_call(
+    tool_name: &str,
+    args: Option<&str>,
+    result: &str,
+    platform: &str,
+) -> Option<OutboundResponse> {
+    if tool_output_lines(result) <= COLLAPSE_MIN_LINES {
+        return None;
+    }
+    let output = tool_output(result);
+    let language = output_language(args, &output);
+    let output = truncate_chars_ellipsis(output.trim_end(), FULL_OUTPUT_CHARS);
+    let fence = fence_for(&output);
+    Some(collapsed(
+        platform,
+        &summary_line(tool_name, args, result),
+        &format!("{fence}{language}\n{output}\n{fence}"),
+    ))
+}
+
+/// Number of lines in the human-readable part of a tool result.
+pub fn tool_output_lines(result: &str) -> usize {
+    tool_output(result).trim_end().lines().count()
+}
+
+/// Render a tool call with a model-written summary ahead of its complete,
+/// untruncated output. Oversized messages are left to the overflow policy.
+pub fn render_summarized_tool_call(
+    tool_name: &str,
+    args: Option<&str>,
+    result: &str,
+    summary: &str,
+) -> String {
+    let mut rendered = summary_line(tool_name, args, result);
+    for line in summary.lines().filter(|line| !line.trim().is_empty()) {
+        rendered.push_str("\n> ");
+        rendered.push_str(line.trim());
+    }
+    let output = tool_output(result);
+    let fence = fence_for(&output);
+    let language = output_language(args, &output);
+    rendered.push_str(&format!(
+        "\n{fence}{language}\n{}\n{fence}",
+        output.trim_end()
+    ));
+    rendered
+}
+
+/// The highlighting language for a call's output: the language of the file
+/// it worked on, or a guess from the output itself. Empty when neither is
+/// known.
+fn output_language(args: Option<&str>, output: &str) -> &'static str {
+    let path_language = args
+        .and_then(|args| serde_json::from_str::<serde_json::Value>(args).ok())
+        .and_then(|args| {
+            PATH_ARG_FIELDS
+                .iter()
+                .find_map(|field| args.get(*field)?.as_str().and_then(language_for_path))
+        });
+    path_language
+        .or_else(|| infer_language(output))
+        .unwrap_or_default()
+}
+
+fn summary_line(tool_name: &str, args: Option<&str>, result: &str) -> String {
+    let mut line = format!("🔧 `{tool_name}`");
+    if let Some(summary) = args.and_then(summarize_args) {
+        line.push_str(&format!(" `{summary}`"));
+    }
+    line.push_str(" — ");
+    line.push_str(&outcome(result));
+    line
+}
+
+/// Pick the most descriptive string argument from the call's JSON args.
+fn summarize_args(args: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(args).ok()?;
+    let object = value.as_object()?;
+    let text = SUMMARY_ARG_FIELDS
+        .iter()
+        .find_map(|field| object.get(*field).and_then(|value| value.as_str()))?;
+    let first_line = text.lines().next().unwrap_or_default().trim();
+    if first_line.is_empty() {
+        return None;
+    }
+    // Backticks would break out of the inline code span.
+    Some(truncate_chars_ellipsis(
+        &first_line.replace('`', "'"),
+        ARGS_SUMMARY_CHARS,
+    ))
+}
+
+/// Describe how the call ended: the exit code when the result carries one,
+/// otherwise done/failed.
+fn outcome(result: &str) -> String {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(result) {
+        if let Some(exit_code) = value.get("exit_code").and_then(|code| code.as_i64()) {
+            return format!("exit {exit_code}");
+        }
+        if value.get("success").and_then(|success| success.as_bool()) == Some(false) {
+            return "failed".into();
+        }
+        return "done".into();
+    }
+    if result.starts_with("Toolset error:") || result.starts_with("Error:") {
+        "failed".into()
+    } else {
+        "done".into()
+    }
+}
+
+/// The human-readable part of a tool result: combined stdout/stderr for
+/// shell-style results, the raw text otherwise.
+fn tool_output(result: &str) -> String {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(result) else {
+        return result.to_string();
+    };
+    let stdout = value.get("stdout").and_then(|text| text.as_str());
+    let stderr = value.get("stderr").and_then(|text| text.as_str());
+    match (stdout, stderr) {
+        (None, None) => result.to_string(),
+        (stdout, stderr) => [stdout.unwrap_or_default(), stderr.unwrap_or_default()]
+            .into_iter()
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Render a worker's todo list as a checklist with a progress header.
+pub fn render_todo_checklist(todos: &[TodoItem]) -> String {
+    let completed = todos
+        .iter()
+        .filter(|todo| todo.status == "completed")
+        .count();
+    let mut rendered = format!("**Tasks** ({completed}/{})", todos.len());
+    for todo in todos {
+        let content = todo.content.trim();
+        let line = match todo.status.as_str() {
+            "completed" => format!("✅ {content}"),
+            "in_progress" => format!("🔄 **{content}**"),
+            "cancelled" => format!("✖️ ~~{content}~~"),
+            _ => format!("⬜ {content}"),
+        };
+        rendered.push('\n');
+        rendered.push_str(&line);
+    }
+    rendered
+}
+
+/// File names listed in a files-changed summary before the rest are counted.
+const FILES_CHANGED_VISIBLE: usize = 10;
+
+/// Summarize the files an OpenCode worker changed during one turn, e.g.
+/// ``modified `src/lib.rs` (+42 −7), created `tests/foo.rs` (+120)``.
+pub fn render_files_changed(files: &[FileChange], session_total: usize) -> String {
+    let mut rendered = format!("📝 files changed: {}", files.len());
+    if session_total > files.len() {
+        rendered.push_str(&format!(" ({session_total} this session)"));
+    }
+    let names = files
+        .iter()
+        .take(FILES_CHANGED_VISIBLE)
+        .map(render_file_change)
+        .collect::<Vec<_>>()
+        .join(", ");
+    rendered.push_str(" — ");
+    rendered.push_str(&names);
+    if files.len() > FILES_CHANGED_VISIBLE {
+        rendered.push_str(&format!(
+            " and {} more",
+            files.len() - FILES_CHANGED_VISIBLE
+        ));
+    }
+    rendered
+}
+
+fn render_file_change(change: &FileChange) -> String {
+    let counts = match (change.kind, change.additions, change.deletions) {
+        (FileChangeKind::Deleted, ..) => String::new(),
+        (FileChangeKind::Created, Some(additions), _) => format!(" (+{additions})"),
+        (FileChangeKind::Modified, Some(additions), Some(deletions)) => {
+            format!(" (+{additions} −{deletions})")
+        }
+        _ => String::new(),
+    };
+    format!("{} `{}`{counts}", change.kind.as_str(), change.path)
+}
+
+/// Steps shown in a sub-agent section before older ones are folded.
+const SUBAGENT_VISIBLE_STEPS: usize = 8;
+
+/// Live rendering state for one `task` tool sub-agent session.
+#[derive(Debug, Default)]
+pub struct SubagentSection {
+    title: Option<String>,
+    /// Rendered step lines keyed by part id, in first-seen order.
+    steps: Vec<(String, String)>,
+}
+
+impl SubagentSection {
+    pub fn new(title: Option<String>) -> Self {
+        Self {
+            title,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Upsert a tool part from the sub-agent session. Returns `true` when the
+    /// rendered section changed.
+    pub fn apply(&mut self, part: &OpenCodePart, detail: ToolDetail) -> bool {
+        let OpenCodePart::Tool { id, tool, state } = part else {
+            return false;
+        };
+        let (marker, title) = match state {
+            OpenCodeToolState::Pending => ("⏳", None),
+            OpenCodeToolState::Running { title, .. } => ("⏳", title.as_deref()),
+            OpenCodeToolState::Completed { title, .. } => ("✓", title.as_deref()),
+            OpenCodeToolState::Error { error } => ("✗", error.as_deref()),
+        };
+        let line = match (detail, title.filter(|title| !title.trim().is_empty())) {
+            (ToolDetail::Hidden, _) => return false,
+            (ToolDetail::Name, _) | (_, None) => format!("{marker} `{tool}`"),
+            (_, Some(title)) => format!(
+                "{marker} `{tool}` — {}",
+                truncate_chars_ellipsis(
+                    title.lines().next().unwrap_or_default(),
+                    ARGS_SUMMARY_CHARS
+                )
+            ),
+        };
+
+        match self.steps.iter_mut().find(|(step_id, _)| step_id == id) {
+            Some((_, existing)) if *existing == line => false,
+            Some((_, existing)) => {
+                *existing = line;
+                true
+            }
+            None => {
+                self.steps.push((id.clone(), line));
+                true
+            }
+        }
+    }
+
+    /// Render as a header plus an indented (quoted) list of recent steps.
+    pub fn render(&self) -> String {
+        let title = self.title.as_deref().unwrap_or("sub-agent");
+        let mut rendered = format!("🧩 **Sub-agent:** {title}");
+        let hidden = self.steps.len().saturating_sub(SUBAGENT_VISIBLE_STEPS);
+        if hidden > 0 {
+            rendered.push_str(&format!("\n> … {hidden} earlier step(s)"));
+        }
+        for (_, line) in &self.steps[hidden..] {
+            rendered.push_str("\n> ");
+            rendered.push_str(line);
+        }
+        rendered
+    }
+}
+
+/// Worker tool calls held back from chat until the turn's reply.
+#[derive(Debug, Default)]
+pub struct ToolTally {
+    /// Tool name, calls, and failed calls, in order of first use.
+    tools: Vec<(String, usize, usize)>,
+}
+
+impl ToolTally {
+    pub fn record(&mut self, tool_name: &str, result: &str) {
+        let outcome = outcome(result);
+        let failed = outcome == "failed" || (outcome.starts_with("exit ") && outcome != "exit 0");
+        let index = match self.tools.iter().position(|(name, ..)| name == tool_name) {
+            Some(index) => index,
+            None => {
+                self.tools.push((tool_name.to_string(), 0, 0));
+                self.tools.len() - 1
+            }
+        };
+        let (_, calls, failures) = &mut self.tools[index];
+        *calls += 1;
+        *failures += usize::from(failed);
+    }
+
+    /// Render the tally as one line and start a new one. `None` when no
+    /// tool ran.
+    pub fn take(&mut self) -> Option<String> {
+        if self.tools.is_empty() {
+            return None;
+        }
+        let entries: Vec<String> = std::mem::take(&mut self.tools)
+            .into_iter()
+            .map(|(name, calls, failures)| {
+                let mut entry = format!("`{name}`");
+                if calls > 1 {
+                    entry.push_str(&format!(" ×{calls}"));
+                }
+                if failures > 0 {
+                    entry.push_str(&format!(" ({failures} failed)"));
+                }
+                entry
+            })
+            .collect();
+        Some(format!("🔧 {}", entries.join(" · ")))
+    }
+}
+
+/// Fold the replies of one turn into a single message, ending with
+/// `tool_tally` when given. The first thread name is kept; any cards,
+/// buttons, or poll make the result a rich message, which can't open a
+/// thread.
+pub fn merge_turn_replies(
+    replies: Vec<OutboundResponse>,
+    tool_tally: Option<&str>,
+) -> Option<OutboundResponse> {
+    let mut texts = Vec::new();
+    let mut thread_name = None;
+    let mut rich = false;
+    let mut cards = Vec::new();
+    let mut interactive_elements = Vec::new();
+    let mut poll = None;
+    for reply in replies {
+        match reply {
+            OutboundResponse::Text(text) => texts.push(text),
+            OutboundResponse::ThreadReply {
+                thread_name: name,
+                text,
+            } => {
+                thread_name.get_or_insert(name);
+                texts.push(text);
+            }
+            OutboundResponse::RichMessage {
+                text,
+                cards: more_cards,
+                interactive_elements: more_elements,
+                poll: more_poll,
+                ..
+            } => {
+                rich = true;
+                texts.push(text);
+                cards.extend(more_cards);
+                interactive_elements.extend(more_elements);
+                poll = poll.or(more_poll);
+            }
+            _ => {}
+        }
+    }
+    if texts.is_empty() {
+        return None;
+    }
+
+    let mut text = texts
+        .into_iter()
+        .filter(|text| !text.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if let Some(tool_tally) = tool_tally {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(tool_tally);
+    }
+    Some(if rich {
+        OutboundResponse::RichMessage {
+            text,
+            blocks: Vec::new(),
+            cards,
+            interactive_elements,
+            poll,
+        }
+    } else if let Some(thread_name) = thread_name {
+        OutboundResponse::ThreadReply { thread_name, text }
+    } else {
+        OutboundResponse::Text(text)
+    })
+}
+
+/// Render a raw OpenCode event for the debug mirror, labelled with its
+/// worker and event type.
+pub fn render_raw_event(worker_label: &str, data: &str) -> String {
+    let (event_type, body) = match serde_json::from_str::<serde_json::Value>(data) {
+        Ok(value) => (
+            value
+                .get("type")
+                .and_then(|event_type| event_type.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            serde_json::to_string_pretty(&value).unwrap_or_else(|_| data.to_string()),
+        ),
+        Err(_) => ("unparsed".to_string(), data.to_string()),
+    };
+    let body = truncate_chars_ellipsis(&body, RAW_EVENT_CHARS);
+    let fence = fence_for(&body);
+    format!("🐞 `{worker_label}` `{event_type}`\n{fence}json\n{body}\n{fence}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHELL_ARGS: &str = r#"{"command":"cargo test","timeout_seconds":60}"#;
+    const SHELL_RESULT: &str = r#"{"success":false,"exit_code":101,"stdout":"running 3 tests","stderr":"1 failed","summary":""}"#;
+
+    #[test]
+    fn tallies_collapse_a_turns_tool_calls() {
+        let mut tally = ToolTally::default();
+        assert_eq!(tally.take(), None);
+        tally.record("shell", SHELL_RESULT);
+        tally.record("file_read", "contents");
+        tally.record("shell", r#"{"exit_code":0,"stdout":"ok"}"#);
+        tally.record("shell", r#"{"exit_code":0,"stdout":"ok"}"#);
+        assert_eq!(
+            tally.take().as_deref(),
+            Some("🔧 `shell` ×3 (1 failed) · `file_read`")
+        );
+        assert_eq!(tally.take(), None);
+    }
+
+    #[test]
+    fn raw_events_are_pretty_printed_and_cut() {
+        assert_eq!(
+            render_raw_event(
+                "1a2b3c4d",
+                r#"{"properties":{"sessionID":"ses-1"},"type":"session.idle"}"#
+            ),
+            "🐞 `1a2b3c4d` `session.idle`\n```json\n{\n  \"properties\": {\n    \"sessionID\": \"ses-1\"\n  },\n  \"type\": \"session.idle\"\n}\n```"
+        );
+
+        let long = format!(
+            r#"{{"type":"message.part.updated","delta":"{}"}}"#,
+            "x".repeat(4_000)
+        );
+        let rendered = render_raw_event("1a2b3c4d", &long);
+        assert!(rendered.starts_with("🐞 `1a2b3c4d` `message.part.updated`\n"));
+        assert!(rendered.ends_with("…\n```"));
+        assert!(rendered.chars().count() < RAW_EVENT_CHARS + 100);
+
+        assert!(render_raw_event("1a2b3c4d", "not json").contains("`unparsed`"));
+    }
+
+    #[test]
+    fn turn_replies_merge_into_one_message() {
+        let merged = merge_turn_replies(
+            vec![
+                OutboundResponse::ThreadReply {
+                    thread_name: "Build".into(),
+                    text: "Looking into it.".into(),
+                },
+                OutboundResponse::Text("Fixed, tests pass.".into()),
+            ],
+            Some("🔧 `shell` ×2"),
+        );
+        let Some(OutboundResponse::ThreadReply { thread_name, text }) = merged else {
+            panic!("expected a thread reply, got {merged:?}");
+        };
+        assert_eq!(thread_name, "Build");
+        assert_eq!(
+            text,
+            "Looking into it.\n\nFixed, tests pass.\n\n🔧 `shell` ×2"
+        );
+
+        assert!(merge_turn_replies(Vec::new(), Some("🔧 `shell`")).is_none());
+        assert!(matches!(
+            merge_turn_replies(vec![OutboundResponse::Text("hi".into())], None),
+            Some(OutboundResponse::Text(text)) if text == "hi"
+        ));
+    }
+
+    #[test]
+    fn hidden_renders_nothing() {
+        assert_eq!(
+            render_tool_call("shell", Some(SHELL_ARGS), SHELL_RESULT, ToolDetail::Hidden),
+            None
+        );
+    }
+
+    #[test]
+    fn summary_shows_args_and_exit_code() {
+        let rendered =
+            render_tool_call("shell", Some(SHELL_ARGS), SHELL_RESULT, ToolDetail::Summary);
+        assert_eq!(
+            rendered.as_deref(),
+            Some("🔧 `shell` `cargo test` — exit 101")
+        );
+    }
+
+    #[test]
+    fn full_includes_shell_output() {
+        let rendered =
+            render_tool_call("shell", Some(SHELL_ARGS), SHELL_RESULT, ToolDetail::Full).unwrap();
+        assert!(rendered.contains("```\nrunning 3 tests\n1 failed\n```"));
+    }
+
+    #[test]
+    fn output_fences_name_the_files_language() {
+        let rendered = render_tool_call(
+            "read",
+            Some(r#"{"filePath":"src/lib.rs"}"#),
+            "pub mod agent;",
+            ToolDetail::Full,
+        )
+        .unwrap();
+        assert!(rendered.ends_with("```rust\npub mod agent;\n```"));
+
+        let rendered = render_tool_call(
+            "bash",
+            None,
+            r#"{"stdout":"{\"ok\": true}"}"#,
+            ToolDetail::Full,
+        )
+        .unwrap();
+        assert!(rendered.ends_with("```json\n{\"ok\": true}\n```"));
+    }
+
+    #[test]
+    fn output_containing_fences_gets_a_longer_fence() {
+        let rendered = render_tool_call(
+            "read",
+            Some(r#"{"filePath":"README.md"}"#),
+            "Run:\n```sh\ncargo test\n```",
+            ToolDetail::Full,
+        )
+        .unwrap();
+        let (_, fenced) = rendered.split_once('\n').unwrap();
+        assert!(fenced.starts_with("````"), "{rendered}");
+        assert!(
+            fenced.ends_with("```sh\ncargo test\n```\n````"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn long_output_collapses_and_short_output_stays_inline() {
+        assert!(
+            render_collapsed_tool_call("shell", Some(SHELL_ARGS), SHELL_RESULT, "discord")
+                .is_none()
+        );
+
+        let result = (1..=20)
+            .map(|line| format!("line {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(OutboundResponse::Text(text)) =
+            render_collapsed_tool_call("bash", None, &result, "discord")
+        else {
+            panic!("long output collapses");
+        };
+        assert!(text.starts_with("🔧 `bash` — done\n||```\nline 1\n"));
+        assert!(text.ends_with("line 20\n```||"));
+    }
+
+    #[test]
+    fn checklist_marks_each_status() {
+        let todo = |content: &str, status: &str| TodoItem {
+            id: None,
+            content: content.into(),
+            status: status.into(),
+            priority: None,
+        };
+        let rendered = render_todo_checklist(&[
+            todo("write parser", "completed"),
+            todo("add tests", "in_progress"),
+            todo("update docs", "pending"),
+            todo("benchmark", "cancelled"),
+        ]);
+        assert_eq!(
+            rendered,
+            "**Tasks** (1/4)\n✅ write parser\n🔄 **add tests**\n⬜ update docs\n✖️ ~~benchmark~~"
+        );
+    }
+
+    #[test]
+    fn files_changed_lists_changes_with_line_counts() {
+        let change = |path: &str, kind, counts: Option<(u32, u32)>| FileChange {
+            path: path.into(),
+            kind,
+            additions: counts.map(|(additions, _)| additions),
+            deletions: counts.map(|(_, deletions)| deletions),
+        };
+        let files = vec![
+            change("src/lib.rs", FileChangeKind::Modified, Some((42, 7))),
+            change("tests/foo.rs", FileChangeKind::Created, Some((120, 0))),
+            change("old.rs", FileChangeKind::Deleted, None),
+            change("README.md", FileChangeKind::Modified, None),
+        ];
+        assert_eq!(
+            render_files_changed(&files, 4),
+            "📝 files changed: 4 — modified `src/lib.rs` (+42 −7), created `tests/foo.rs` (+120), deleted `old.rs`, modified `README.md`"
+        );
+        assert!(
+            render_files_changed(&files[..1], 5)
+                .starts_with("📝 files changed: 1 (5 this session) — ")
+        );
+
+        let many: Vec<FileChange> = (0..12)
+            .map(|index| change(&format!("file{index}.rs"), FileChangeKind::Modified, None))
+            .collect();
+        assert!(render_files_changed(&many, 12).ends_with("modified `file9.rs` and 2 more"));
+    }
+
+    #[test]
+    fn subagent_section_upserts_steps_by_part_id() {
+        let tool_part = |state: OpenCodeToolState| OpenCodePart::Tool {
+            id: "part-1".into(),
+            tool: "bash".into(),
+            state,
+        };
+        let mut section = SubagentSection::new(Some("Run the test suite".into()));
+        assert!(section.apply(
+            &tool_part(OpenCodeToolState::Running {
+                title: Some("cargo test".into()),
+                input: None,
+            }),
+            ToolDetail::Summary,
+        ));
+        assert!(section.apply(
+            &tool_part(OpenCodeToolState::Completed {
+                title: Some("cargo test".into()),
+                input: None,
+                output: None,
+            }),
+            ToolDetail::Summary,
+        ));
+        assert_eq!(
+            section.render(),
+            "🧩 **Sub-agent:** Run the test suite\n> ✓ `bash` — cargo test"
+        );
+        assert!(!section.apply(&tool_part(OpenCodeToolState::Pending), ToolDetail::Hidden));
+    }
+
+    #[test]
+    fn summarized_call_keeps_full_output() {
+        let stdout = (1..=200)
+            .map(|line| format!("test case_{line} ... ok"))
+            .collect::<Vec<_>>()
+            .join("\\n");
+        let result = format!(r#"{{"exit_code":0,"stdout":"{stdout}","stderr":""}}"#);
+        assert_eq!(tool_output_lines(&result), 200);
+
+        let rendered = render_summarized_tool_call(
+            "shell",
+            Some(SHELL_ARGS),
+            &result,
+            "All 200 tests passed.\nNo warnings.",
+        );
+        assert!(rendered.starts_with(
+            "🔧 `shell` `cargo test` — exit 0\n> All 200 tests passed.\n> No warnings.\n```\n"
+        ));
+        assert!(rendered.contains("test case_200 ... ok\n```"));
+    }
+
+    #[test]
+    fn full_truncates_long_output() {
+        let result = "x".repeat(FULL_OUTPUT_CHARS * 2);
+        let rendered = render_tool_call("webfetch", None, &result, ToolDetail::Full).unwrap();
+        assert!(rendered.contains('…'));
+        assert!(rendered.chars().count() < FULL_OUTPUT_CHARS + 100);
+    }
+}
@@ -0,0 +1,79 @@
+//! Cheap-model summaries of long worker tool output.
+//!
+//! When a rendered tool call's output runs past
+//! `tool_rendering.summarize.min_lines`, the channel asks a small model for a
+//! two-to-three line summary and posts it ahead of the full output, which the
+//! messaging overflow policy can move into a file or paste link.
+
+use crate::llm::SpacebotModel;
+use crate::{AgentDeps, ProcessType};
+
+use anyhow::Context as _;
+use rig::agent::AgentBuilder;
+use rig::completion::Prompt as _;
+
+/// Output characters sent to the summary model. Longer output keeps its head
+/// and tail, where commands usually report what happened.
+const SUMMARY_INPUT_CHARS: usize = 24_000;
+
+/// Upper bound on a summary call so a slow model can't hold the post back.
+const SUMMARY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Ask `model_override` (or the compactor's routed model) to summarize a
+/// tool's output in a few lines.
+pub async fn summarize_tool_output(
+    deps: &AgentDeps,
+    model_override: Option<&str>,
+    tool_name: &str,
+    output: &str,
+) -> anyhow::Result<String> {
+    let preamble = deps
+        .runtime_config
+        .prompts
+        .load()
+        .render_static("tool_output_summary")
+        .context("failed to render tool output summary prompt")?;
+
+    let routing = deps.runtime_config.routing.load();
+    let model_name = match model_override {
+        Some(model) => model.to_string(),
+        None => routing.resolve(ProcessType::Compactor, None).to_string(),
+    };
+    let model = SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_context(&*deps.agent_id, "tool_summary")
+        .with_routing((**routing).clone());
+    let agent = AgentBuilder::new(model)
+        .preamble(&preamble)
+        .default_max_turns(1)
+        .build();
+
+    let prompt = format!(
+        "Tool: {tool_name}\n\nOutput:\n{}",
+        head_and_tail(output, SUMMARY_INPUT_CHARS)
+    );
+    let summary = tokio::time::timeout(SUMMARY_TIMEOUT, agent.prompt(&prompt))
+        .await
+        .context("tool output summary timed out")?
+        .context("tool output summary failed")?;
+
+    let summary = summary.trim();
+    if summary.is_empty() {
+        anyhow::bail!("summary model returned an empty response");
+    }
+    Ok(summary.to_string())
+}
+
+/// Keep the first and last halves of `text` when it exceeds `max_chars`.
+fn head_and_tail(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let half = max_chars / 2;
+    let head: String = text.chars().take(half).collect();
+    let tail: String = text.chars().skip(total - half).collect();
+    format!(
+        "{head}\n… [{} characters omitted] …\n{tail}",
+        total - 2 * half
+    )
+}
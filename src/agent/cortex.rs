@@ -21,6 +21,7 @@ use crate::llm::SpacebotModel;
 use crate::memory::maintenance as memory_maintenance;
 use crate::memory::search::{SearchConfig, SearchMode, SearchSort};
 use crate::memory::types::{Association, MemoryType, RelationType};
+use crate::opencode::server_log::ServerLogForwarder;
 use crate::tasks::{TaskStatus, TaskStore, UpdateTaskInput};
 use crate::{
     AgentDeps, AgentId, BranchId, ChannelId, ProcessEvent, ProcessId, ProcessType, WorkerId,
@@ -598,6 +599,8 @@ impl HealthRuntimeState {
     fn track_worker_idle(&mut self, worker_id: WorkerId) {
         if let Some(tracker) = self.worker_trackers.get_mut(&worker_id) {
             tracker.is_idle = true;
+            // The session TTL counts from when the worker went idle.
+            tracker.last_activity_at = Instant::now();
         }
     }
 
@@ -680,7 +683,7 @@ impl HealthRuntimeState {
     }
 }
 
-fn parse_structured_success_flag(result: &str) -> Option<bool> {
+pub(crate) fn parse_structured_success_flag(result: &str) -> Option<bool> {
     let trimmed = result.trim();
     if !trimmed.starts_with('{') || trimmed.len() > 16_384 {
         return None;
@@ -839,6 +842,8 @@ pub struct Cortex {
     health_runtime_state: Arc<RwLock<HealthRuntimeState>>,
     /// System prompt loaded from prompts/CORTEX.md.
     pub system_prompt: String,
+    /// Rate limiter for OpenCode server warnings forwarded to chat.
+    server_log_forwarder: Arc<tokio::sync::Mutex<ServerLogForwarder>>,
 }
 
 /// A high-level activity signal (not raw conversation).
@@ -1135,6 +1140,7 @@ impl Cortex {
             signal_buffer: Arc::new(RwLock::new(VecDeque::with_capacity(SIGNAL_BUFFER_CAPACITY))),
             health_runtime_state: Arc::new(RwLock::new(HealthRuntimeState::default())),
             system_prompt: system_prompt.into(),
+            server_log_forwarder: Arc::new(tokio::sync::Mutex::new(ServerLogForwarder::new())),
         }
     }
 
@@ -1147,6 +1153,10 @@ impl Cortex {
             self.deps.runtime_config.bump_knowledge_synthesis_version();
         }
 
+        if let ProcessEvent::OpenCodeServerLog { entry, .. } = &event {
+            self.forward_server_log(entry).await;
+        }
+
         let Some(signal) = signal_from_event(event) else {
             return;
         };
@@ -1159,6 +1169,45 @@ impl Cortex {
         tracing::trace!(buffer_len, "cortex received signal");
     }
 
+    /// Post an OpenCode server warning to `opencode.server_logs.forward_to`,
+    /// subject to its level filter and rate limit.
+    async fn forward_server_log(&self, entry: &crate::opencode::types::ServerLogEntry) {
+        let opencode_config = self.deps.runtime_config.opencode.load();
+        let config = &opencode_config.server_logs;
+        let (Some(target), Some(messaging_manager)) =
+            (&config.forward_to, self.deps.messaging_manager.as_ref())
+        else {
+            return;
+        };
+        let Some(text) = self.server_log_forwarder.lock().await.admit(
+            &self.deps.agent_id,
+            entry,
+            config,
+            Instant::now(),
+        ) else {
+            return;
+        };
+
+        let messaging_manager = messaging_manager.clone();
+        let target = target.clone();
+        tokio::spawn(async move {
+            if let Err(error) = messaging_manager
+                .broadcast_proactive(
+                    &target.adapter,
+                    &target.target,
+                    crate::OutboundResponse::Text(text),
+                )
+                .await
+            {
+                tracing::warn!(
+                    %error,
+                    adapter = %target.adapter,
+                    "failed to forward OpenCode server log entry"
+                );
+            }
+        });
+    }
+
     async fn observe_health_event(&self, event: &ProcessEvent) {
         let threshold = self
             .deps
@@ -1305,6 +1354,47 @@ impl Cortex {
         let targets = build_kill_targets(overdue_workers, overdue_branches);
 
         let mut terminal_worker_ids = Vec::new();
+        let session_ttl = self.deps.runtime_config.opencode.load().session_ttl_secs;
+        if session_ttl > 0 {
+            let expired = {
+                let state = self.health_runtime_state.read().await;
+                expired_sessions(
+                    state.worker_trackers.values(),
+                    Duration::from_secs(session_ttl),
+                    now,
+                )
+            };
+            for tracker in expired {
+                let Some(channel_id) = &tracker.channel_id else {
+                    continue;
+                };
+                let result = self
+                    .deps
+                    .process_control_registry
+                    .archive_channel_worker(channel_id, tracker.worker_id)
+                    .await;
+                if !is_terminal_control_result(result) {
+                    continue;
+                }
+                terminal_worker_ids.push(tracker.worker_id);
+                if is_cancelled_control_result(result) {
+                    logger.log(
+                        "worker_archived",
+                        &format!(
+                            "Idle OpenCode session for worker {} archived",
+                            tracker.worker_id
+                        ),
+                        Some(serde_json::json!({
+                            "worker_id": tracker.worker_id.to_string(),
+                            "channel_id": tracker.channel_id.as_deref(),
+                            "idle_secs": now.duration_since(tracker.last_activity_at).as_secs(),
+                            "session_ttl_secs": session_ttl,
+                        })),
+                    );
+                }
+            }
+        }
+
         let mut terminal_branch_ids = Vec::new();
         let mut kill_attempts = 0_usize;
         let mut kill_actions = 0_usize;
@@ -1418,6 +1508,23 @@ impl Cortex {
     }
 }
 
+/// Idle OpenCode workers whose session has outlived `ttl`.
+fn expired_sessions<'a>(
+    trackers: impl Iterator<Item = &'a WorkerTracker>,
+    ttl: Duration,
+    now: Instant,
+) -> Vec<WorkerTracker> {
+    trackers
+        .filter(|tracker| {
+            tracker.is_idle
+                && tracker.worker_type == "opencode"
+                && tracker.channel_id.is_some()
+                && now.duration_since(tracker.last_activity_at) >= ttl
+        })
+        .cloned()
+        .collect()
+}
+
 fn summarize_signal_text(value: &str) -> String {
     crate::summarize_first_non_empty_line(value, crate::EVENT_SUMMARY_MAX_CHARS)
 }
@@ -1604,10 +1711,21 @@ fn signal_from_event(event: ProcessEvent) -> Option<Signal> {
         // UI-only events — no cortex signal needed.
         ProcessEvent::OpenCodeSessionCreated { .. }
         | ProcessEvent::OpenCodePartUpdated { .. }
+        | ProcessEvent::OpenCodeServerLog { .. }
+        | ProcessEvent::WorkerTodos { .. }
+        | ProcessEvent::WorkerSessionTitle { .. }
         | ProcessEvent::WorkerInitialResult { .. }
+        | ProcessEvent::WorkerPlanReady { .. }
+        | ProcessEvent::WorkerDryRun { .. }
+        | ProcessEvent::WorkerRawEvent { .. }
+        | ProcessEvent::WorkerContextCompacted { .. }
+        | ProcessEvent::WorkerFilesChanged { .. }
+        | ProcessEvent::WorkerBusy { .. }
         | ProcessEvent::WorkerText { .. }
         | ProcessEvent::CortexChatUpdate { .. }
         | ProcessEvent::SettingsUpdated { .. }
+        | ProcessEvent::Broadcast { .. }
+        | ProcessEvent::AbortAll { .. }
         | ProcessEvent::ToolOutput { .. } => return None,
     })
 }
@@ -2777,14 +2895,13 @@ pub async fn generate_bulletin(deps: &AgentDeps, logger: &CortexLogger) -> bool
 
     let result = agent.prompt(&synthesis_prompt).await;
     // Flush cortex token usage.
-    let acc = usage_accumulator.lock().await;
-    if let Err(error) = acc
-        .flush(&deps.sqlite_pool, &deps.agent_id, "cortex", None)
+    let usage = usage_accumulator
+        .lock()
         .await
-    {
-        tracing::warn!(%error, "failed to flush cortex token usage");
+        .to_record(&deps.agent_id, "cortex", None);
+    if let Some(usage) = usage {
+        deps.write_behind.write_usage(usage).await;
     }
-    drop(acc);
 
     match result {
         Ok(bulletin) => {
@@ -3019,14 +3136,13 @@ pub async fn generate_knowledge_synthesis(deps: &AgentDeps, logger: &CortexLogge
     };
 
     let result = agent.prompt(&user_prompt).await;
-    let acc = usage_accumulator.lock().await;
-    if let Err(error) = acc
-        .flush(&deps.sqlite_pool, &deps.agent_id, "cortex", None)
+    let usage = usage_accumulator
+        .lock()
         .await
-    {
-        tracing::warn!(%error, "failed to flush cortex token usage");
+        .to_record(&deps.agent_id, "cortex", None);
+    if let Some(usage) = usage {
+        deps.write_behind.write_usage(usage).await;
     }
-    drop(acc);
 
     match result {
         Ok(synthesis) => {
@@ -3276,14 +3392,13 @@ pub async fn maybe_synthesize_intraday_batch(
         .build();
 
     let synthesis = agent.prompt(&prompt).await;
-    let acc = usage_accumulator.lock().await;
-    if let Err(e) = acc
-        .flush(&deps.sqlite_pool, &deps.agent_id, "cortex", None)
+    let usage = usage_accumulator
+        .lock()
         .await
-    {
-        tracing::warn!(error = %e, "failed to flush cortex token usage");
+        .to_record(&deps.agent_id, "cortex", None);
+    if let Some(usage) = usage {
+        deps.write_behind.write_usage(usage).await;
     }
-    drop(acc);
     let synthesis = synthesis?;
 
     // Store the synthesis.
@@ -3431,14 +3546,13 @@ pub async fn maybe_synthesize_daily_summary(
         .build();
 
     let summary = agent.prompt(&prompt).await;
-    let acc = usage_accumulator.lock().await;
-    if let Err(e) = acc
-        .flush(&deps.sqlite_pool, &deps.agent_id, "cortex", None)
+    let usage = usage_accumulator
+        .lock()
         .await
-    {
-        tracing::warn!(error = %e, "failed to flush cortex token usage");
+        .to_record(&deps.agent_id, "cortex", None);
+    if let Some(usage) = usage {
+        deps.write_behind.write_usage(usage).await;
     }
-    drop(acc);
     let summary = summary?;
 
     wm.save_daily_summary(&yesterday, &summary, total_events)
@@ -3597,14 +3711,13 @@ async fn generate_profile(deps: &AgentDeps, logger: &CortexLogger) {
     let result = agent
         .prompt_typed::<ProfileLlmResponse>(&synthesis_prompt)
         .await;
-    let acc = usage_accumulator.lock().await;
-    if let Err(e) = acc
-        .flush(&deps.sqlite_pool, &deps.agent_id, "cortex", None)
+    let usage = usage_accumulator
+        .lock()
         .await
-    {
-        tracing::warn!(error = %e, "failed to flush cortex token usage");
+        .to_record(&deps.agent_id, "cortex", None);
+    if let Some(usage) = usage {
+        deps.write_behind.write_usage(usage).await;
     }
-    drop(acc);
 
     match result {
         Ok(profile_data) => {
@@ -3862,6 +3975,28 @@ async fn handle_detached_completion(
     });
 }
 
+/// Prompt section listing checkpointed stages from an earlier run, so the
+/// worker continues with the first incomplete subtask instead of redoing work.
+fn render_resume_checkpoints(checkpoints: &[crate::tasks::TaskCheckpoint]) -> String {
+    let mut section = String::from(
+        "\nThis task was interrupted and is resuming. These subtasks already completed; \
+         do not redo them. Continue with the first unchecked subtask.\n",
+    );
+    for checkpoint in checkpoints {
+        section.push_str(&format!(
+            "\n{}. {}",
+            checkpoint.stage_index + 1,
+            checkpoint.stage_title
+        ));
+        let result = checkpoint.result.trim();
+        if !result.is_empty() {
+            section.push_str(&format!(" — result: {result}"));
+        }
+    }
+    section.push('\n');
+    section
+}
+
 /// One-shot wake for dormant agents.
 ///
 /// Triggered by `agent::wake::WakeManager` when an external event delivers
@@ -3994,6 +4129,30 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
             let marker = if subtask.completed { "[x]" } else { "[ ]" };
             task_prompt.push_str(&format!("{}. {} {}\n", index + 1, marker, subtask.title));
         }
+
+        // Resume after the last checkpointed stage if a previous run was
+        // interrupted.
+        match deps.task_store.list_checkpoints(&task.id).await {
+            Ok(checkpoints) if !checkpoints.is_empty() => {
+                task_prompt.push_str(&render_resume_checkpoints(&checkpoints));
+                logger.log(
+                    "task_pickup_resumed",
+                    &format!(
+                        "Resuming task #{} after {} completed stage(s)",
+                        task.task_number,
+                        checkpoints.len()
+                    ),
+                    Some(serde_json::json!({
+                        "task_number": task.task_number,
+                        "completed_stages": checkpoints.len(),
+                    })),
+                );
+            }
+            Ok(_) => {}
+            Err(error) => {
+                tracing::warn!(%error, task_number = task.task_number, "failed to load task checkpoints");
+            }
+        }
     }
 
     let screenshot_dir = deps
@@ -4651,9 +4810,9 @@ mod tests {
         MAINTENANCE_TASK_CANCEL_GRACE_SECS, MaintenanceTimeoutAction, ReceiverClosedBehavior,
         Signal, SynthesisTaskBackoff, WorkerTracker, apply_cancelled_warmup_status,
         build_kill_targets, claim_detached_completion, collect_synthesis_task,
-        detached_timeout_transition, generate_if_dirty_under_lock, handle_cortex_receiver_result,
-        has_completed_initial_warmup, is_cancelled_control_result, is_terminal_control_result,
-        maintenance_task_timeout, maintenance_timeout_action,
+        detached_timeout_transition, expired_sessions, generate_if_dirty_under_lock,
+        handle_cortex_receiver_result, has_completed_initial_warmup, is_cancelled_control_result,
+        is_terminal_control_result, maintenance_task_timeout, maintenance_timeout_action,
         mark_knowledge_synthesis_version_complete, maybe_close_bulletin_refresh_circuit,
         maybe_generate_bulletin_under_lock, maybe_spawn_synthesis_task,
         parse_structured_success_flag, push_signal_into_buffer, record_bulletin_refresh_failure,
@@ -5196,9 +5355,11 @@ mod tests {
                 agent_id: agent_id.clone(),
                 worker_id,
                 channel_id: Some(channel_id.clone()),
+                session_id: "ses-1".to_string(),
                 permission_id: "perm-1".to_string(),
                 description: "allow network".to_string(),
                 patterns: vec!["https://example.com".to_string()],
+                awaiting_approval: false,
             },
             ProcessEvent::WorkerQuestion {
                 agent_id: agent_id.clone(),
@@ -5247,10 +5408,21 @@ mod tests {
             ProcessEvent::OpenCodePartUpdated {
                 agent_id: Arc::from("agent"),
                 worker_id,
+                channel_id: Some(channel_id.clone()),
                 part: crate::opencode::types::OpenCodePart::Text {
                     id: "part-1".to_string(),
                     text: "hello".to_string(),
                 },
+                subagent: None,
+            },
+            ProcessEvent::OpenCodeServerLog {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                entry: crate::opencode::types::ServerLogEntry {
+                    level: crate::opencode::types::ServerLogLevel::Warn,
+                    service: Some("provider".to_string()),
+                    message: "missing API key".to_string(),
+                },
             },
             ProcessEvent::WorkerInitialResult {
                 agent_id: Arc::from("agent"),
@@ -5258,6 +5430,59 @@ mod tests {
                 channel_id: Some(channel_id.clone()),
                 result: "initial result".to_string(),
             },
+            ProcessEvent::WorkerPlanReady {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                plan: "1. read the code".to_string(),
+            },
+            ProcessEvent::WorkerDryRun {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                payload: "{}".to_string(),
+            },
+            ProcessEvent::WorkerRawEvent {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                data: "{}".to_string(),
+            },
+            ProcessEvent::WorkerContextCompacted {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+            },
+            ProcessEvent::WorkerFilesChanged {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                files: vec![crate::opencode::types::FileChange::new(
+                    "src/main.rs",
+                    crate::opencode::types::FileChangeKind::Modified,
+                )],
+                session_total: 1,
+            },
+            ProcessEvent::WorkerBusy {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                busy: true,
+            },
+            ProcessEvent::Broadcast {
+                agent_id: Arc::from("agent"),
+                text: "upgrading opencode".to_string(),
+            },
+            ProcessEvent::AbortAll {
+                agent_id: Arc::from("agent"),
+                backend: Some("gpu-box".to_string()),
+            },
+            ProcessEvent::WorkerSessionTitle {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                title: "Fix flaky retry test".to_string(),
+            },
         ];
 
         for event in events {
@@ -5814,6 +6039,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn only_idle_opencode_sessions_past_the_ttl_expire() {
+        let mut state = HealthRuntimeState::default();
+        let idle_opencode = uuid::Uuid::new_v4();
+        let busy_opencode = uuid::Uuid::new_v4();
+        let idle_builtin = uuid::Uuid::new_v4();
+        for (worker_id, worker_type) in [
+            (idle_opencode, "opencode"),
+            (busy_opencode, "opencode"),
+            (idle_builtin, "builtin"),
+        ] {
+            state.track_worker_start(worker_id, Some(Arc::from("ch")), worker_type.to_string());
+        }
+        state.track_worker_idle(idle_opencode);
+        state.track_worker_idle(idle_builtin);
+
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+        assert!(expired_sessions(state.worker_trackers.values(), ttl, now).is_empty());
+
+        let expired: Vec<crate::WorkerId> =
+            expired_sessions(state.worker_trackers.values(), ttl, now + ttl)
+                .into_iter()
+                .map(|tracker| tracker.worker_id)
+                .collect();
+        assert_eq!(expired, vec![idle_opencode]);
+    }
+
     #[test]
     fn worker_activity_noop_for_unknown_worker() {
         let mut state = HealthRuntimeState::default();
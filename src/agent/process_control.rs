@@ -186,6 +186,28 @@ impl ProcessControlRegistry {
         ControlActionResult::NotFound
     }
 
+    /// Close an idle interactive worker's session so it completes and
+    /// archives its transcript.
+    pub async fn archive_channel_worker(
+        &self,
+        channel_id: &ChannelId,
+        worker_id: WorkerId,
+    ) -> ControlActionResult {
+        for _ in 0..2 {
+            match self.lookup_channel_handle(channel_id).await {
+                ChannelLookupResult::Found(handle) => {
+                    return handle.archive_worker(worker_id).await;
+                }
+                ChannelLookupResult::Stale(registration_id) => {
+                    self.remove_stale_channel_if_matches(channel_id, registration_id)
+                        .await;
+                }
+                ChannelLookupResult::Missing => return ControlActionResult::NotFound,
+            }
+        }
+        ControlActionResult::NotFound
+    }
+
     pub async fn cancel_channel_branch(
         &self,
         channel_id: &ChannelId,
@@ -0,0 +1,141 @@
+//! Prompt size guard: keeps a prompt inside the model's context window
+//! before it is submitted.
+//!
+//! The system prompt and the new message are fixed; injected chat history is
+//! what gives. History is trimmed oldest-first until the estimate fits. A
+//! message that can't fit even with no history is reported so the user can
+//! be told, instead of the provider rejecting the request.
+
+use crate::llm::tokens::{estimate_message_tokens, estimate_tokens};
+use rig::message::{Message, UserContent};
+
+/// Share of the context window held back for tool definitions and the
+/// response.
+const RESERVED_FRACTION: f32 = 0.15;
+
+/// A message too large to send even with no history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Oversized {
+    /// Estimated tokens in the message.
+    pub message_tokens: usize,
+    /// Tokens left for the message after the system prompt.
+    pub available_tokens: usize,
+}
+
+/// Tokens a prompt may use in a model with `context_window` tokens.
+pub fn prompt_budget(context_window: usize) -> usize {
+    (context_window as f32 * (1.0 - RESERVED_FRACTION)) as usize
+}
+
+/// Check that `message` fits next to `system_prompt` with no history.
+pub fn check_message(
+    context_window: usize,
+    system_prompt: &str,
+    message: &str,
+) -> Result<(), Oversized> {
+    let available_tokens =
+        prompt_budget(context_window).saturating_sub(estimate_tokens(system_prompt));
+    let message_tokens = estimate_message_tokens(&Message::from(message));
+    if message_tokens > available_tokens {
+        return Err(Oversized {
+            message_tokens,
+            available_tokens,
+        });
+    }
+    Ok(())
+}
+
+/// Drop the oldest history messages until the whole prompt fits. Returns how
+/// many were removed.
+///
+/// A cut never leaves the history starting on an assistant turn or a tool
+/// result, since the tool call it answers would be gone.
+pub fn trim_history(
+    context_window: usize,
+    system_prompt: &str,
+    history: &mut Vec<Message>,
+    message: &str,
+) -> usize {
+    let budget = prompt_budget(context_window);
+    let fixed = estimate_tokens(system_prompt) + estimate_message_tokens(&Message::from(message));
+    let sizes: Vec<usize> = history.iter().map(estimate_message_tokens).collect();
+    let mut total = fixed + sizes.iter().sum::<usize>();
+
+    let mut removed = 0;
+    while total > budget && removed < sizes.len() {
+        total -= sizes[removed];
+        removed += 1;
+    }
+    if removed == 0 {
+        return 0;
+    }
+    while removed < history.len() && !starts_a_turn(&history[removed]) {
+        removed += 1;
+    }
+    history.drain(..removed);
+    removed
+}
+
+/// Whether a history can start at `message`.
+fn starts_a_turn(message: &Message) -> bool {
+    match message {
+        Message::User { content } => !content
+            .iter()
+            .any(|item| matches!(item, UserContent::ToolResult(_))),
+        Message::Assistant { .. } => false,
+        Message::System { .. } => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assistant(text: &str) -> Message {
+        Message::Assistant {
+            id: None,
+            content: rig::OneOrMany::one(rig::message::AssistantContent::text(text)),
+        }
+    }
+
+    #[test]
+    fn oversized_messages_are_reported() {
+        assert_eq!(check_message(1_000, "be brief", "hello"), Ok(()));
+
+        let message = "word ".repeat(1_000);
+        let oversized = check_message(1_000, "be brief", &message).unwrap_err();
+        assert_eq!(
+            oversized.available_tokens,
+            prompt_budget(1_000) - estimate_tokens("be brief")
+        );
+        assert!(oversized.message_tokens > oversized.available_tokens);
+    }
+
+    #[test]
+    fn history_that_fits_is_left_alone() {
+        let mut history = vec![Message::from("hi"), assistant("hello")];
+        assert_eq!(trim_history(1_000, "be brief", &mut history, "next"), 0);
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn history_is_trimmed_oldest_first_to_a_user_turn() {
+        let long = "word ".repeat(200);
+        let mut history = vec![
+            Message::from(long.as_str()),
+            assistant(&long),
+            Message::from("second question"),
+            assistant("second answer"),
+        ];
+
+        let removed = trim_history(400, "be brief", &mut history, "third question");
+
+        assert_eq!(removed, 2);
+        assert_eq!(history.len(), 2);
+        assert!(matches!(&history[0], Message::User { .. }));
+        assert_eq!(
+            estimate_message_tokens(&history[0]),
+            estimate_message_tokens(&Message::from("second question"))
+        );
+    }
+}
@@ -259,11 +259,15 @@ pub struct Worker {
     pub wiki_write: bool,
     /// Model override from conversation settings (per-process or blanket).
     pub model_override: Option<String>,
+    /// `source:sender_id` of the user whose turn spawned this worker. Its
+    /// token usage is attributed to them.
+    pub requested_by: Option<String>,
     /// Wall-clock budget for the entire `run()` invocation. Distinct from
     /// the supervisor's `CortexConfig.worker_timeout_secs` (which is an
-    /// idle-kill bound measured from `last_activity_at`). Resolution chain
-    /// at construction: agent `CortexConfig.worker_wall_clock_timeout_secs`
-    /// → `DEFAULT_WORKER_WALL_CLOCK_TIMEOUT_SECS`.
+    /// idle-kill bound measured from `last_activity_at`). Resolution chain:
+    /// the channel's `/budget` (see [`Self::with_wall_clock_timeout`]) →
+    /// agent `CortexConfig.worker_wall_clock_timeout_secs` →
+    /// `DEFAULT_WORKER_WALL_CLOCK_TIMEOUT_SECS`.
     pub worker_wall_clock_timeout_secs: u64,
     /// Shared segment counter so the outer timeout wrapper can report
     /// progress in `WorkerOutcome::Timeout` after the inner future is
@@ -336,6 +340,7 @@ impl Worker {
                 worker_memory_mode,
                 wiki_write,
                 model_override,
+                requested_by: None,
                 worker_wall_clock_timeout_secs,
                 segments_run: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
                 blocked_signal: new_block_signal(),
@@ -474,6 +479,21 @@ impl Worker {
         (worker, input_tx, inject_tx)
     }
 
+    /// Attribute this worker's token usage to `requested_by`.
+    pub fn with_requested_by(mut self, requested_by: Option<String>) -> Self {
+        self.requested_by = requested_by;
+        self
+    }
+
+    /// Replace the agent's wall-clock budget with a channel's `/budget`.
+    /// `None` keeps the agent's.
+    pub fn with_wall_clock_timeout(mut self, timeout_secs: Option<u64>) -> Self {
+        if let Some(timeout_secs) = timeout_secs {
+            self.worker_wall_clock_timeout_secs = timeout_secs;
+        }
+        self
+    }
+
     /// Check if the worker can transition to a new state.
     pub fn can_transition_to(&self, target: WorkerState) -> bool {
         use WorkerState::*;
@@ -705,7 +725,7 @@ impl Worker {
                                 });
                         }
 
-                        self.persist_transcript(&compacted_history, &history).await;
+                        self.queue_transcript(&compacted_history, &history).await;
                         dedup_tool_results(&mut history);
                         self.maybe_compact_history(&mut compacted_history, &mut history)
                             .await;
@@ -1051,18 +1071,14 @@ impl Worker {
         // Persist transcript blob
         self.persist_transcript(&compacted_history, &history).await;
 
-        // Flush accumulated token usage.
-        let acc = usage_accumulator.lock().await;
-        if let Err(error) = acc
-            .flush(
-                &self.deps.sqlite_pool,
-                &self.deps.agent_id,
-                "worker",
-                self.channel_id.as_deref(),
-            )
+        // Queue accumulated token usage.
+        let usage = usage_accumulator
+            .lock()
             .await
-        {
-            tracing::warn!(%error, "failed to flush worker token usage");
+            .to_record(&self.deps.agent_id, "worker", self.channel_id.as_deref())
+            .map(|usage| usage.with_user(self.requested_by.clone()));
+        if let Some(usage) = usage {
+            self.deps.write_behind.write_usage(usage).await;
         }
 
         tracing::info!(worker_id = %self.id, "worker completed");
@@ -1162,12 +1178,24 @@ impl Worker {
 
     /// Persist the compressed transcript blob to worker_runs.
     ///
-    /// Awaited directly so that at idle boundaries "idle implies persisted"
-    /// and concurrent snapshots cannot land out of order.
+    /// Flushes the write-behind buffer before returning so that at idle and
+    /// terminal boundaries "idle implies persisted".
     async fn persist_transcript(
         &self,
         compacted_history: &[rig::message::Message],
         history: &[rig::message::Message],
+    ) {
+        self.queue_transcript(compacted_history, history).await;
+        self.deps.write_behind.flush().await;
+    }
+
+    /// Queue a transcript snapshot on the write-behind buffer. Snapshots
+    /// queued before the next commit coalesce, so mid-run checkpoints cost
+    /// at most one write per flush interval.
+    async fn queue_transcript(
+        &self,
+        compacted_history: &[rig::message::Message],
+        history: &[rig::message::Message],
     ) {
         let mut full_history = compacted_history.to_vec();
         full_history.extend(history.iter().cloned());
@@ -1189,16 +1217,10 @@ impl Worker {
             })
             .sum();
 
-        if let Err(error) =
-            sqlx::query("UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?")
-                .bind(&transcript_blob)
-                .bind(tool_calls)
-                .bind(&worker_id)
-                .execute(&self.deps.sqlite_pool)
-                .await
-        {
-            tracing::warn!(%error, worker_id, "failed to persist worker transcript");
-        }
+        self.deps
+            .write_behind
+            .write_transcript(worker_id, transcript_blob, tool_calls)
+            .await;
     }
 
     /// Check if worker is in a terminal state.
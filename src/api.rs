@@ -12,18 +12,23 @@ mod channels;
 mod config;
 mod cortex;
 mod cron;
+pub mod event_bus;
 mod factory;
 mod ingest;
 mod links;
 mod mcp;
 mod memories;
 mod messaging;
-mod models;
+pub(crate) mod models;
 mod notifications;
+mod oncall;
+mod opencode_mirror;
 mod opencode_proxy;
 mod portal;
 mod projects;
+mod prompts;
 mod providers;
+mod public_url;
 mod secrets;
 mod server;
 mod settings;
@@ -34,8 +39,14 @@ mod system;
 mod tasks;
 mod tools;
 mod usage;
+mod webhooks;
 mod wiki;
 mod workers;
 
+pub use event_bus::EventBus;
+pub use prompts::{
+    ActiveSession, ActiveSessionsResponse, SessionMessage, SessionResponse, SessionStatus,
+    SessionWorker, StreamEvent, SubmitPromptResponse,
+};
 pub use server::{api_router, start_http_server};
-pub use state::{AgentInfo, ApiEvent, ApiState, ChannelToolCallEntry};
+pub use state::{AgentInfo, ApiEvent, ApiState, ChannelToolCallEntry, LastToolCall};
@@ -547,6 +547,7 @@ pub(super) async fn trigger_warmup(
                 memory_event_tx,
                 tool_output_tx,
                 sqlite_pool: sqlite_pool.clone(),
+                write_behind: crate::conversation::WriteBehind::spawn(sqlite_pool.clone()),
                 messaging_manager: None,
                 sandbox,
                 task_store,
@@ -781,6 +782,7 @@ pub async fn create_agent_internal(
         warmup: None,
         browser: None,
         channel: None,
+        tool_rendering: None,
         mcp: None,
         brave_search_key: None,
         cron_timezone: None,
@@ -788,6 +790,10 @@ pub async fn create_agent_internal(
         sandbox: None,
         projects: None,
         cron: Vec::new(),
+        github: None,
+        notify_webhooks: Vec::new(),
+        templates: Vec::new(),
+        transcribe: None,
     };
     let agent_config = raw_config.resolve(&instance_dir, defaults);
 
@@ -804,7 +810,7 @@ pub async fn create_agent_internal(
         })?;
     }
 
-    let db = crate::db::Db::connect(&agent_config.data_dir)
+    let db = crate::db::Db::connect(&agent_config.data_dir, crate::db::Migrations::Run)
         .await
         .map_err(|error| {
             tracing::error!(%error, agent_id = %agent_id, "failed to connect agent databases");
@@ -905,6 +911,9 @@ pub async fn create_agent_internal(
         skills,
     ));
     runtime_config.set_settings(settings_store.clone());
+    runtime_config
+        .public_url
+        .store(std::sync::Arc::new(state.public_url.clone()));
 
     let llm_manager = {
         let guard = state.llm_manager.read().await;
@@ -954,6 +963,7 @@ pub async fn create_agent_internal(
         memory_event_tx: memory_event_tx.clone(),
         tool_output_tx: tool_output_tx.clone(),
         sqlite_pool: db.sqlite.clone(),
+        write_behind: crate::conversation::WriteBehind::spawn(db.sqlite.clone()),
         messaging_manager: Some(messaging_manager.clone()),
         sandbox: sandbox.clone(),
         links: Arc::new(arc_swap::ArcSwap::from_pointee(
@@ -1088,6 +1098,20 @@ pub async fn create_agent_internal(
         deps.clone(),
         make_cortex_logger(db.sqlite.clone()),
     );
+    let _audit_recorder = crate::audit::spawn_audit_recorder(
+        deps.agent_id.clone(),
+        deps.event_tx.subscribe(),
+        db.sqlite.clone(),
+    );
+    let _tool_stats_recorder = crate::tool_stats::spawn_tool_stats_recorder(
+        deps.agent_id.clone(),
+        deps.event_tx.subscribe(),
+        db.sqlite.clone(),
+    );
+    let _ops_alert_recorder = crate::notifications::ops::spawn_ops_alert_recorder(
+        deps.agent_id.clone(),
+        deps.event_tx.subscribe(),
+    );
 
     let ingestion_config = **runtime_config.ingestion.load();
     if ingestion_config.enabled {
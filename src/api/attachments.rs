@@ -6,7 +6,7 @@ use crate::agent::channel_attachments::persist_attachment_bytes;
 use axum::Json;
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
-use axum::http::{StatusCode, header};
+use axum::http::{HeaderMap, StatusCode, header};
 use axum::response::Response;
 use serde::{Deserialize, Serialize};
 use sqlx::Row as _;
@@ -22,6 +22,8 @@ pub(super) struct AttachmentUploadResponse {
     original_filename: String,
     mime_type: String,
     size_bytes: u64,
+    /// Absolute URL serving the file, based on the external base URL.
+    url: String,
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -31,6 +33,8 @@ pub(super) struct AttachmentInfo {
     mime_type: String,
     size_bytes: u64,
     created_at: String,
+    /// Absolute URL serving the file, based on the external base URL.
+    url: String,
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -83,6 +87,7 @@ pub(super) struct AttachmentListQuery {
 )]
 pub(super) async fn upload_attachment(
     State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
     Path((agent_id, channel_id)): Path<(String, String)>,
     mut multipart: axum::extract::Multipart,
 ) -> Result<Json<AttachmentUploadResponse>, StatusCode> {
@@ -151,14 +156,30 @@ pub(super) async fn upload_attachment(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    let url = attachment_url(&state, &headers, &agent_id, &meta.id);
     Ok(Json(AttachmentUploadResponse {
         id: meta.id,
         original_filename: meta.filename,
         mime_type: meta.mime_type,
         size_bytes: meta.size_bytes,
+        url,
     }))
 }
 
+/// Public URL of the serve endpoint for an attachment.
+fn attachment_url(
+    state: &ApiState,
+    headers: &HeaderMap,
+    agent_id: &str,
+    attachment_id: &str,
+) -> String {
+    state.external_base(headers).url(&format!(
+        "/api/agents/{}/attachments/{}",
+        urlencoding::encode(agent_id),
+        urlencoding::encode(attachment_id)
+    ))
+}
+
 // ---------------------------------------------------------------------------
 // Serve
 // ---------------------------------------------------------------------------
@@ -283,6 +304,7 @@ pub(super) async fn serve_attachment(
 )]
 pub(super) async fn list_attachments(
     State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
     Path((agent_id, channel_id)): Path<(String, String)>,
     Query(query): Query<AttachmentListQuery>,
 ) -> Result<Json<AttachmentListResponse>, StatusCode> {
@@ -329,6 +351,7 @@ pub(super) async fn list_attachments(
             let size_bytes: i64 = row.try_get("size_bytes")?;
             let created_at: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
             Ok::<_, sqlx::Error>(AttachmentInfo {
+                url: attachment_url(&state, &headers, &agent_id, &id),
                 id,
                 original_filename,
                 mime_type,
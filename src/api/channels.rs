@@ -5,7 +5,8 @@ use crate::conversation::history::ProcessRunLogger;
 
 use axum::Json;
 use axum::extract::{Query, State};
-use axum::http::StatusCode;
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -90,6 +91,19 @@ pub(super) struct CancelProcessResponse {
     message: String,
 }
 
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(super) struct KillChannelRequest {
+    channel_id: String,
+}
+
+#[derive(Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub(super) struct ExportChannelQuery {
+    channel_id: String,
+}
+
+/// Most timeline items one export returns, newest kept.
+const MAX_EXPORT_ITEMS: i64 = 10_000;
+
 /// List channels across agents, with optional activity and agent filters.
 #[utoipa::path(
     get,
@@ -475,6 +489,94 @@ pub(super) async fn cancel_process(
     }
 }
 
+/// Cancel every running worker and branch in a channel. The channel itself
+/// keeps running and answers the next message as usual.
+#[utoipa::path(
+    post,
+    path = "/channels/kill",
+    request_body = KillChannelRequest,
+    responses(
+        (status = 200, body = CancelProcessResponse),
+        (status = 404, description = "Channel not running"),
+    ),
+    tag = "channels",
+)]
+pub(super) async fn kill_channel(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<KillChannelRequest>,
+) -> Result<Json<CancelProcessResponse>, StatusCode> {
+    let channel_state = {
+        let states = state.channel_states.read().await;
+        states.get(&request.channel_id).cloned()
+    }
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    let workers = channel_state.worker_handles.read().await.len();
+    let branches = channel_state.active_branches.read().await.len();
+    channel_state
+        .cancel_all_workers_and_branches("killed via API")
+        .await;
+
+    tracing::info!(
+        channel_id = %request.channel_id,
+        workers,
+        branches,
+        "channel processes killed via API"
+    );
+    Ok(Json(CancelProcessResponse {
+        success: true,
+        message: format!("Cancelled {workers} worker(s) and {branches} branch(es)"),
+    }))
+}
+
+/// Export a channel's full timeline as JSON Lines, oldest first.
+#[utoipa::path(
+    get,
+    path = "/channels/export",
+    params(
+        ("channel_id" = String, Query, description = "Channel ID"),
+    ),
+    responses(
+        (status = 200, description = "JSON Lines export", content_type = "application/x-ndjson"),
+        (status = 404, description = "Channel not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "channels",
+)]
+pub(super) async fn export_channel(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ExportChannelQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let pools = state.agent_pools.load();
+    for (_agent_id, pool) in pools.iter() {
+        let logger = ProcessRunLogger::new(pool.clone());
+        let items = match logger
+            .load_channel_timeline(&query.channel_id, MAX_EXPORT_ITEMS, None)
+            .await
+        {
+            Ok(items) if !items.is_empty() => items,
+            Ok(_) => continue,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %query.channel_id, "failed to export timeline");
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+        let mut body = String::new();
+        for item in &items {
+            let line = serde_json::to_string(item).map_err(|error| {
+                tracing::warn!(%error, "failed to serialize timeline item");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            body.push_str(&line);
+            body.push('\n');
+        }
+        return Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body));
+    }
+
+    Err(StatusCode::NOT_FOUND)
+}
+
 // ── Prompt Inspect ──────────────────────────────────────────────────
 
 #[derive(Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
@@ -0,0 +1,274 @@
+//! The bus behind the dashboard's SSE stream (`[event_bus]`).
+//!
+//! Every [`ApiEvent`] goes to an in-process broadcast channel that this
+//! replica's SSE clients read. With `backend = "redis"` each event is also
+//! published to a Redis pub/sub channel, and events other replicas publish
+//! there are fed into the local broadcast, so a dashboard connected to any
+//! replica sees the whole deployment. Other services can subscribe to the
+//! same channel. Messages are JSON:
+//!
+//! ```json
+//! {"origin": "<process id>", "replica": "<replica id>", "event": {"type": "outbound_message", ...}}
+//! ```
+//!
+//! Publishing never blocks the sender. While Redis is unreachable, events
+//! still reach local SSE clients; the ones that don't fit in the publish
+//! queue are dropped, and both connections reconnect with backoff.
+
+pub mod redis;
+
+use super::ApiEvent;
+use crate::config::RedisConfig;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Events waiting to be published before new ones are dropped.
+const PUBLISH_QUEUE: usize = 1024;
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// What goes over the Redis channel.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope<S, E> {
+    /// The publishing process, so it can skip its own messages.
+    origin: S,
+    /// The publishing replica's `[cluster] replica_id`.
+    replica: S,
+    event: E,
+}
+
+/// Sender side of the event bus. Cloning it is cheap and every clone feeds
+/// the same subscribers.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    local: broadcast::Sender<ApiEvent>,
+    remote: Arc<OnceLock<mpsc::Sender<ApiEvent>>>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (local, _) = broadcast::channel(capacity);
+        Self {
+            local,
+            remote: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Publish an event. Like [`broadcast::Sender::send`], this errors only
+    /// when no local receiver is listening; the event is still published to
+    /// Redis when configured.
+    pub fn send(&self, event: ApiEvent) -> Result<usize, broadcast::error::SendError<ApiEvent>> {
+        if let Some(remote) = self.remote.get()
+            && let Err(mpsc::error::TrySendError::Full(_)) = remote.try_send(event.clone())
+        {
+            tracing::debug!("event bus publish queue full, dropping event");
+        }
+        self.local.send(event)
+    }
+
+    /// Receive every event sent from now on, local and remote.
+    pub fn subscribe(&self) -> broadcast::Receiver<ApiEvent> {
+        self.local.subscribe()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.local.receiver_count()
+    }
+
+    /// Start publishing to and subscribing from Redis. Has no effect after
+    /// the first call. The tasks run until the process exits.
+    pub fn connect_redis(&self, config: &RedisConfig, replica_id: &str) {
+        let (publish_tx, publish_rx) = mpsc::channel(PUBLISH_QUEUE);
+        if self.remote.set(publish_tx).is_err() {
+            return;
+        }
+        let origin = uuid::Uuid::new_v4().to_string();
+        tokio::spawn(publish(
+            config.clone(),
+            origin.clone(),
+            replica_id.to_string(),
+            publish_rx,
+        ));
+        tokio::spawn(subscribe(config.clone(), origin, self.local.clone()));
+        tracing::info!(channel = %config.channel, "event bus publishing to redis");
+    }
+}
+
+async fn publish(
+    config: RedisConfig,
+    origin: String,
+    replica: String,
+    mut events: mpsc::Receiver<ApiEvent>,
+) {
+    let mut backoff = Backoff::default();
+    let mut pending: Option<Vec<u8>> = None;
+    loop {
+        let mut connection = match redis::Connection::open(&config.url).await {
+            Ok(connection) => connection,
+            Err(error) => {
+                tracing::warn!(%error, "event bus failed to connect to redis for publishing");
+                backoff.wait().await;
+                continue;
+            }
+        };
+        backoff.reset();
+
+        loop {
+            let payload = match pending.take() {
+                Some(payload) => payload,
+                None => {
+                    let Some(event) = events.recv().await else {
+                        return;
+                    };
+                    let envelope = Envelope {
+                        origin: origin.as_str(),
+                        replica: replica.as_str(),
+                        event: &event,
+                    };
+                    match serde_json::to_vec(&envelope) {
+                        Ok(payload) => payload,
+                        Err(error) => {
+                            tracing::warn!(%error, "failed to serialize event for redis");
+                            continue;
+                        }
+                    }
+                }
+            };
+            match connection
+                .command(&[b"PUBLISH", config.channel.as_bytes(), &payload])
+                .await
+            {
+                Ok(redis::Reply::Error(message)) => {
+                    tracing::warn!(%message, "redis rejected published event");
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!(%error, "event bus lost its redis publishing connection");
+                    pending = Some(payload);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn subscribe(config: RedisConfig, origin: String, local: broadcast::Sender<ApiEvent>) {
+    let mut backoff = Backoff::default();
+    loop {
+        let result: anyhow::Result<()> = async {
+            let mut connection = redis::Connection::open(&config.url).await?;
+            connection
+                .send(&[b"SUBSCRIBE", config.channel.as_bytes()])
+                .await?;
+            loop {
+                let reply = connection.read().await?;
+                if let redis::Reply::Error(message) = &reply {
+                    anyhow::bail!("redis rejected SUBSCRIBE: {message}");
+                }
+                backoff.reset();
+                if let Some(payload) = message_payload(reply)
+                    && let Some(event) = remote_event(&payload, &origin)
+                {
+                    local.send(event).ok();
+                }
+            }
+        }
+        .await;
+        if let Err(error) = result {
+            tracing::warn!(%error, "event bus redis subscription failed");
+        }
+        backoff.wait().await;
+    }
+}
+
+/// The payload of a `message` push, ignoring subscription confirmations.
+fn message_payload(reply: redis::Reply) -> Option<Vec<u8>> {
+    let redis::Reply::Array(Some(items)) = reply else {
+        return None;
+    };
+    match <[redis::Reply; 3]>::try_from(items) {
+        Ok(
+            [
+                redis::Reply::Bulk(Some(kind)),
+                _,
+                redis::Reply::Bulk(Some(payload)),
+            ],
+        ) if kind == b"message" => Some(payload),
+        _ => None,
+    }
+}
+
+/// Decode a message another process published. Own messages, and ones that
+/// don't decode (for example from a newer version), are skipped.
+fn remote_event(payload: &[u8], origin: &str) -> Option<ApiEvent> {
+    match serde_json::from_slice::<Envelope<String, ApiEvent>>(payload) {
+        Ok(envelope) if envelope.origin == origin => None,
+        Ok(envelope) => Some(envelope.event),
+        Err(error) => {
+            tracing::debug!(%error, "skipping undecodable event bus message");
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct Backoff {
+    current: Option<Duration>,
+}
+
+impl Backoff {
+    async fn wait(&mut self) {
+        let delay = self
+            .current
+            .map_or(Duration::from_secs(1), |delay| (delay * 2).min(MAX_BACKOFF));
+        self.current = Some(delay);
+        tokio::time::sleep(delay).await;
+    }
+
+    fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_other_processes_messages_are_delivered() {
+        let event = ApiEvent::OutboundMessage {
+            agent_id: "main".into(),
+            channel_id: "discord:1:2".into(),
+            text: "hello".into(),
+        };
+        let payload = serde_json::to_vec(&Envelope {
+            origin: "replica-a-process",
+            replica: "replica-a",
+            event: &event,
+        })
+        .unwrap();
+        let push = redis::Reply::Array(Some(vec![
+            redis::Reply::Bulk(Some(b"message".to_vec())),
+            redis::Reply::Bulk(Some(b"spacebot:events".to_vec())),
+            redis::Reply::Bulk(Some(payload)),
+        ]));
+        let payload = message_payload(push).expect("message push has a payload");
+
+        assert!(remote_event(&payload, "replica-a-process").is_none());
+        let Some(ApiEvent::OutboundMessage { text, .. }) = remote_event(&payload, "other") else {
+            panic!("expected the outbound message");
+        };
+        assert_eq!(text, "hello");
+
+        let confirmation = redis::Reply::Array(Some(vec![
+            redis::Reply::Bulk(Some(b"subscribe".to_vec())),
+            redis::Reply::Bulk(Some(b"spacebot:events".to_vec())),
+            redis::Reply::Integer(1),
+        ]));
+        assert!(message_payload(confirmation).is_none());
+        assert!(remote_event(b"{\"origin\":\"x\"}", "other").is_none());
+    }
+}
@@ -0,0 +1,311 @@
+//! The part of the Redis protocol (RESP2) the event bus needs: connecting
+//! over TCP or TLS (`rediss://`), `AUTH`, `SELECT`, `PUBLISH`, and reading
+//! `SUBSCRIBE` pushes.
+//!
+//! Replies come from the network, so the parser bounds how deeply arrays may
+//! nest and how long a bulk string may claim to be, and never trusts a
+//! length in arithmetic without checking it.
+
+use anyhow::Context as _;
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+use tokio::net::TcpStream;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Deepest array nesting accepted. Pub/sub pushes are one level deep.
+const MAX_DEPTH: usize = 8;
+
+/// Longest bulk string accepted, matching Redis's own `proto-max-bulk-len`
+/// default.
+const MAX_BULK_LENGTH: usize = 512 * 1024 * 1024;
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// One RESP2 reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    /// `None` for the null bulk string.
+    Bulk(Option<Vec<u8>>),
+    /// `None` for the null array.
+    Array(Option<Vec<Reply>>),
+}
+
+/// Encode a command as an array of bulk strings.
+pub fn encode_command(arguments: &[&[u8]]) -> Vec<u8> {
+    let mut encoded = format!("*{}\r\n", arguments.len()).into_bytes();
+    for argument in arguments {
+        encoded.extend_from_slice(format!("${}\r\n", argument.len()).as_bytes());
+        encoded.extend_from_slice(argument);
+        encoded.extend_from_slice(b"\r\n");
+    }
+    encoded
+}
+
+/// Parse one reply from the front of `buffer`. Returns the reply and how
+/// many bytes it took, or `None` when the buffer holds only part of one.
+pub fn parse_reply(buffer: &[u8]) -> anyhow::Result<Option<(Reply, usize)>> {
+    parse_nested(buffer, 0)
+}
+
+fn parse_nested(buffer: &[u8], depth: usize) -> anyhow::Result<Option<(Reply, usize)>> {
+    if depth > MAX_DEPTH {
+        anyhow::bail!("RESP arrays nested deeper than {MAX_DEPTH}");
+    }
+    let Some(line_end) = find_crlf(buffer) else {
+        return Ok(None);
+    };
+    let Some((&marker, line)) = buffer[..line_end].split_first() else {
+        anyhow::bail!("empty RESP line");
+    };
+    let line = std::str::from_utf8(line).context("RESP header is not UTF-8")?;
+    let mut consumed = line_end + 2;
+
+    let reply = match marker {
+        b'+' => Reply::Simple(line.to_string()),
+        b'-' => Reply::Error(line.to_string()),
+        b':' => Reply::Integer(line.parse().context("invalid RESP integer")?),
+        b'$' => {
+            let length: i64 = line.parse().context("invalid RESP bulk length")?;
+            if length < 0 {
+                Reply::Bulk(None)
+            } else {
+                let length = usize::try_from(length)
+                    .ok()
+                    .filter(|length| *length <= MAX_BULK_LENGTH)
+                    .with_context(|| format!("RESP bulk length {length} is too large"))?;
+                let data_end = consumed
+                    .checked_add(length)
+                    .context("RESP bulk length overflows")?;
+                let end = data_end
+                    .checked_add(2)
+                    .context("RESP bulk length overflows")?;
+                if buffer.len() < end {
+                    return Ok(None);
+                }
+                if &buffer[data_end..end] != b"\r\n" {
+                    anyhow::bail!("RESP bulk string is not terminated by CRLF");
+                }
+                let data = buffer[consumed..data_end].to_vec();
+                consumed = end;
+                Reply::Bulk(Some(data))
+            }
+        }
+        b'*' => {
+            let count: i64 = line.parse().context("invalid RESP array length")?;
+            if count < 0 {
+                Reply::Array(None)
+            } else {
+                let mut items = Vec::with_capacity(count.min(64) as usize);
+                for _ in 0..count {
+                    let Some((item, used)) = parse_nested(&buffer[consumed..], depth + 1)? else {
+                        return Ok(None);
+                    };
+                    items.push(item);
+                    consumed += used;
+                }
+                Reply::Array(Some(items))
+            }
+        }
+        other => anyhow::bail!("unknown RESP type byte {other:#04x}"),
+    };
+    Ok(Some((reply, consumed)))
+}
+
+fn find_crlf(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(2).position(|window| window == b"\r\n")
+}
+
+/// The database index in a redis URL's path (`redis://host/2`), if any.
+pub fn database_index(url: &url::Url) -> anyhow::Result<Option<u32>> {
+    let path = url.path().trim_start_matches('/');
+    if path.is_empty() {
+        return Ok(None);
+    }
+    path.parse()
+        .map(Some)
+        .with_context(|| format!("invalid redis database index '{path}'"))
+}
+
+/// A single connection to a Redis server.
+pub struct Connection {
+    stream: Box<dyn Stream>,
+    buffer: Vec<u8>,
+}
+
+impl Connection {
+    /// Connect to a `redis://` or `rediss://` URL, authenticate if it carries
+    /// a password, and select its database index.
+    pub async fn open(url: &str) -> anyhow::Result<Self> {
+        let parsed = url::Url::parse(url).context("invalid redis URL")?;
+        let host = parsed.host_str().context("redis URL has no host")?;
+        let port = parsed.port().unwrap_or(6379);
+        let database = database_index(&parsed)?;
+        let tcp = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .context("timed out connecting to redis")?
+            .with_context(|| format!("failed to connect to redis at {host}:{port}"))?;
+        let stream: Box<dyn Stream> = if parsed.scheme() == "rediss" {
+            let roots =
+                rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            let tls_config = rustls::ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+                .context("invalid redis server name")?;
+            let tls = tokio::time::timeout(
+                CONNECT_TIMEOUT,
+                tokio_rustls::TlsConnector::from(Arc::new(tls_config)).connect(server_name, tcp),
+            )
+            .await
+            .context("timed out during TLS handshake with redis")?
+            .context("TLS handshake with redis failed")?;
+            Box::new(tls)
+        } else {
+            Box::new(tcp)
+        };
+        let mut connection = Self {
+            stream,
+            buffer: Vec::new(),
+        };
+
+        if let Some(password) = parsed.password() {
+            let password = urlencoding::decode(password)
+                .map(|password| password.into_owned())
+                .unwrap_or_else(|_| password.to_string());
+            let username = parsed.username();
+            let reply = if username.is_empty() {
+                connection.command(&[b"AUTH", password.as_bytes()]).await?
+            } else {
+                connection
+                    .command(&[b"AUTH", username.as_bytes(), password.as_bytes()])
+                    .await?
+            };
+            if let Reply::Error(message) = reply {
+                anyhow::bail!("redis AUTH failed: {message}");
+            }
+        }
+
+        if let Some(database) = database.filter(|database| *database != 0) {
+            let database = database.to_string();
+            let reply = connection
+                .command(&[b"SELECT", database.as_bytes()])
+                .await?;
+            if let Reply::Error(message) = reply {
+                anyhow::bail!("redis SELECT {database} failed: {message}");
+            }
+        }
+        Ok(connection)
+    }
+
+    /// Send a command and wait for its reply.
+    pub async fn command(&mut self, arguments: &[&[u8]]) -> anyhow::Result<Reply> {
+        self.send(arguments).await?;
+        self.read().await
+    }
+
+    /// Send a command without waiting for a reply.
+    pub async fn send(&mut self, arguments: &[&[u8]]) -> anyhow::Result<()> {
+        self.stream
+            .write_all(&encode_command(arguments))
+            .await
+            .context("failed to write to redis")
+    }
+
+    /// Read the next reply, or the next push on a subscribed connection.
+    pub async fn read(&mut self) -> anyhow::Result<Reply> {
+        loop {
+            if let Some((reply, consumed)) = parse_reply(&self.buffer)? {
+                self.buffer.drain(..consumed);
+                return Ok(reply);
+            }
+            let mut chunk = [0u8; 8192];
+            let read = self
+                .stream
+                .read(&mut chunk)
+                .await
+                .context("failed to read from redis")?;
+            if read == 0 {
+                anyhow::bail!("redis closed the connection");
+            }
+            self.buffer.extend_from_slice(&chunk[..read]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_encode_as_bulk_string_arrays() {
+        assert_eq!(
+            encode_command(&[b"PUBLISH", b"spacebot:events", b"{}"]),
+            b"*3\r\n$7\r\nPUBLISH\r\n$15\r\nspacebot:events\r\n$2\r\n{}\r\n"
+        );
+    }
+
+    #[test]
+    fn replies_parse_and_wait_for_complete_input() {
+        let push = b"*3\r\n$7\r\nmessage\r\n$1\r\nc\r\n$5\r\nhello\r\n:1\r\n";
+        let (reply, used) = parse_reply(push).unwrap().unwrap();
+        assert_eq!(
+            reply,
+            Reply::Array(Some(vec![
+                Reply::Bulk(Some(b"message".to_vec())),
+                Reply::Bulk(Some(b"c".to_vec())),
+                Reply::Bulk(Some(b"hello".to_vec())),
+            ]))
+        );
+        assert_eq!(
+            parse_reply(&push[used..]).unwrap().unwrap().0,
+            Reply::Integer(1)
+        );
+
+        for end in 0..used {
+            assert!(parse_reply(&push[..end]).unwrap().is_none(), "prefix {end}");
+        }
+        assert_eq!(
+            parse_reply(b"-NOAUTH Authentication required.\r\n")
+                .unwrap()
+                .unwrap()
+                .0,
+            Reply::Error("NOAUTH Authentication required.".into())
+        );
+        assert_eq!(
+            parse_reply(b"$-1\r\n").unwrap().unwrap().0,
+            Reply::Bulk(None)
+        );
+        assert!(parse_reply(b"?\r\n").is_err());
+    }
+
+    #[test]
+    fn hostile_replies_are_rejected() {
+        let nested = format!("{}:1\r\n", "*1\r\n".repeat(MAX_DEPTH + 2));
+        assert!(parse_reply(nested.as_bytes()).is_err());
+        let allowed = format!("{}:1\r\n", "*1\r\n".repeat(MAX_DEPTH));
+        assert!(parse_reply(allowed.as_bytes()).unwrap().is_some());
+
+        assert!(parse_reply(b"$9223372036854775807\r\n").is_err());
+        assert!(parse_reply(b"$3\r\nabcXY").is_err());
+        assert!(parse_reply(b"$3\r\nab").unwrap().is_none());
+    }
+
+    #[test]
+    fn database_index_comes_from_the_path() {
+        let index = |url: &str| database_index(&url::Url::parse(url).unwrap());
+        assert_eq!(index("redis://localhost").unwrap(), None);
+        assert_eq!(index("redis://localhost/").unwrap(), None);
+        assert_eq!(
+            index("rediss://:pw@cache.internal:6380/3").unwrap(),
+            Some(3)
+        );
+        assert!(index("redis://localhost/events").is_err());
+    }
+}
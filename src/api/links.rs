@@ -73,6 +73,8 @@ struct TopologyHuman {
     slack_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access: Option<crate::access::AccessTier>,
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
@@ -159,6 +161,7 @@ pub async fn topology(State(state): State<Arc<ApiState>>) -> impl IntoResponse {
             telegram_id: h.telegram_id.clone(),
             slack_id: h.slack_id.clone(),
             email: h.email.clone(),
+            access: h.access,
         })
         .collect();
 
@@ -775,6 +778,8 @@ pub struct CreateHumanRequest {
     pub telegram_id: Option<String>,
     pub slack_id: Option<String>,
     pub email: Option<String>,
+    /// Access tier: `admin`, `developer`, or `read_only`. Empty clears it.
+    pub access: Option<String>,
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
@@ -787,6 +792,18 @@ pub struct UpdateHumanRequest {
     pub telegram_id: Option<String>,
     pub slack_id: Option<String>,
     pub email: Option<String>,
+    /// Access tier: `admin`, `developer`, or `read_only`. Empty clears it.
+    pub access: Option<String>,
+}
+
+/// Parse an optional access tier from an API request. Empty strings clear the tier.
+fn parse_access_tier(value: Option<&str>) -> Result<Option<crate::access::AccessTier>, StatusCode> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => crate::access::AccessTier::parse(value)
+            .map(Some)
+            .ok_or(StatusCode::BAD_REQUEST),
+    }
 }
 
 /// List all humans.
@@ -844,6 +861,9 @@ fn ensure_humans_in_doc(doc: &mut toml_edit::DocumentMut, humans: &[crate::confi
         if let Some(ref email) = human.email {
             table["email"] = toml_edit::value(email.as_str());
         }
+        if let Some(access) = human.access {
+            table["access"] = toml_edit::value(access.as_str());
+        }
         humans_array.push(table);
     }
     doc["humans"] = toml_edit::Item::ArrayOfTables(humans_array);
@@ -869,6 +889,7 @@ pub async fn create_human(
     if id.is_empty() {
         return Err(StatusCode::BAD_REQUEST);
     }
+    let access = parse_access_tier(request.access.as_deref())?;
 
     let existing = state.agent_humans.load();
     if existing.iter().any(|h| h.id == id) {
@@ -939,6 +960,9 @@ pub async fn create_human(
     {
         table["email"] = toml_edit::value(email.as_str());
     }
+    if let Some(access) = access {
+        table["access"] = toml_edit::value(access.as_str());
+    }
     humans_array.push(table);
 
     tokio::fs::write(&config_path, doc.to_string())
@@ -978,6 +1002,7 @@ pub async fn create_human(
         telegram_id: request.telegram_id.clone().filter(|s| !s.is_empty()),
         slack_id: request.slack_id.clone().filter(|s| !s.is_empty()),
         email: request.email.clone().filter(|s| !s.is_empty()),
+        access,
     };
     let mut humans = (**existing).clone();
     humans.push(new_human.clone());
@@ -1073,6 +1098,9 @@ pub async fn update_human(
             Some(email.clone())
         };
     }
+    if request.access.is_some() {
+        updated.access = parse_access_tier(request.access.as_deref())?;
+    }
 
     let config_path = state.config_path.read().await.clone();
     let content = tokio::fs::read_to_string(&config_path)
@@ -1130,6 +1158,11 @@ pub async fn update_human(
                 } else if request.email.is_some() {
                     table.remove("email");
                 }
+                if let Some(access) = updated.access {
+                    table["access"] = toml_edit::value(access.as_str());
+                } else if request.access.is_some() {
+                    table.remove("access");
+                }
                 break;
             }
         }
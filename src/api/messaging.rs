@@ -1,7 +1,7 @@
 use super::state::ApiState;
 
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -557,8 +557,11 @@ pub(super) async fn messaging_status(
                         .and_then(|v| v.as_str())
                         .is_some_and(|s| !s.is_empty());
 
-                    let configured =
-                        has_imap_host && has_imap_username && has_imap_password && has_smtp_host;
+                    let has_webhook = email.get("webhook").is_some();
+
+                    let configured = ((has_imap_host && has_imap_username && has_imap_password)
+                        || has_webhook)
+                        && has_smtp_host;
 
                     let enabled = email
                         .get("enabled")
@@ -912,6 +915,132 @@ pub(super) async fn messaging_status(
     }))
 }
 
+/// Outbound usage for every messaging adapter.
+#[derive(Serialize, utoipa::ToSchema)]
+pub(super) struct MessagingQuotaResponse {
+    adapters: Vec<crate::messaging::quota::AdapterQuotaSnapshot>,
+}
+
+/// Per-adapter outbound request counts, latency, and rate-limit quota.
+#[utoipa::path(
+    get,
+    path = "/messaging/quota",
+    responses(
+        (status = 200, body = MessagingQuotaResponse),
+    ),
+    tag = "messaging",
+)]
+pub(super) async fn messaging_quota() -> Json<MessagingQuotaResponse> {
+    Json(MessagingQuotaResponse {
+        adapters: crate::messaging::quota::OutboundTracker::global().snapshot(),
+    })
+}
+
+/// Most quarantined messages returned per agent.
+const QUARANTINE_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub(super) struct QuarantineQuery {
+    /// Filter to one agent.
+    #[serde(default)]
+    agent_id: Option<String>,
+    /// Include messages already marked reviewed.
+    #[serde(default)]
+    include_reviewed: bool,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub(super) struct QuarantineEntry {
+    agent_id: String,
+    #[serde(flatten)]
+    message: crate::messaging::content_filter::QuarantinedMessage,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub(super) struct QuarantineResponse {
+    messages: Vec<QuarantineEntry>,
+}
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(super) struct ReviewQuarantineRequest {
+    agent_id: String,
+    id: i64,
+}
+
+/// Outbound messages blocked or flagged by tenant content filters.
+#[utoipa::path(
+    get,
+    path = "/messaging/quarantine",
+    params(QuarantineQuery),
+    responses(
+        (status = 200, body = QuarantineResponse),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "messaging",
+)]
+pub(super) async fn list_quarantine(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<QuarantineQuery>,
+) -> Result<Json<QuarantineResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let mut messages = Vec::new();
+    for (agent_id, pool) in pools.iter() {
+        if query
+            .agent_id
+            .as_ref()
+            .is_some_and(|wanted| wanted != agent_id)
+        {
+            continue;
+        }
+        let quarantine = crate::messaging::content_filter::Quarantine::new(pool.clone());
+        let agent_messages = quarantine
+            .list(query.include_reviewed, QUARANTINE_LIST_LIMIT)
+            .await
+            .map_err(|error| {
+                tracing::error!(%error, agent_id, "failed to list quarantined messages");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        messages.extend(agent_messages.into_iter().map(|message| QuarantineEntry {
+            agent_id: agent_id.clone(),
+            message,
+        }));
+    }
+    messages.sort_by(|left, right| right.message.created_at.cmp(&left.message.created_at));
+    Ok(Json(QuarantineResponse { messages }))
+}
+
+/// Mark a quarantined message reviewed.
+#[utoipa::path(
+    post,
+    path = "/messaging/quarantine/review",
+    request_body = ReviewQuarantineRequest,
+    responses(
+        (status = 200, body = serde_json::Value),
+        (status = 404, description = "Agent or message not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "messaging",
+)]
+pub(super) async fn review_quarantine(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ReviewQuarantineRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let quarantine = crate::messaging::content_filter::Quarantine::new(pool.clone());
+    let found = quarantine
+        .mark_reviewed(request.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, id = request.id, "failed to review quarantined message");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !found {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
 /// Disconnect a messaging platform: remove credentials from config, remove all
 /// bindings for that platform, and shut down the adapter.
 ///
@@ -264,6 +264,32 @@ pub(super) async fn ensure_models_cache() -> Vec<ModelInfo> {
     }
 }
 
+/// Context window of `model_id` from the cached catalog, if known.
+///
+/// Never waits on the network: a missing or stale cache is refreshed in the
+/// background and this call answers from what is cached now, so the caller
+/// falls back to its configured window until the catalog arrives.
+pub(crate) async fn cached_context_window(model_id: &str) -> Option<usize> {
+    static REFRESHING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+    let cache = MODELS_CACHE.read().await;
+    if (cache.0.is_empty() || cache.1.elapsed() >= MODELS_CACHE_TTL)
+        && !REFRESHING.swap(true, std::sync::atomic::Ordering::AcqRel)
+    {
+        tokio::spawn(async {
+            ensure_models_cache().await;
+            REFRESHING.store(false, std::sync::atomic::Ordering::Release);
+        });
+    }
+    let window = cache
+        .0
+        .iter()
+        .chain(extra_models().iter())
+        .find(|model| model.id == model_id)
+        .and_then(|model| model.context_window)?;
+    usize::try_from(window).ok().filter(|window| *window > 0)
+}
+
 /// Helper: which providers have keys configured.
 pub(super) async fn configured_providers(config_path: &std::path::Path) -> Vec<&'static str> {
     let mut providers = Vec::new();
@@ -0,0 +1,57 @@
+//! Read-only on-call page listing active sessions.
+//!
+//! The page is static and holds no data itself. It reads `/v1/sessions` and
+//! follows each session's WebSocket stream, passing the token taken from
+//! `?token=` in its own URL, so it is served outside the auth middleware.
+//! That token is normally `[api] oncall_token`, which the auth middleware
+//! accepts only on the routes in [`is_oncall_route`].
+
+use axum::http::Method;
+use axum::response::Html;
+
+const ONCALL_PAGE: &str = include_str!("oncall.html");
+
+pub(super) async fn oncall_page() -> Html<&'static str> {
+    Html(ONCALL_PAGE)
+}
+
+/// The routes the on-call page reads: `GET /api/v1/sessions` and
+/// `GET /api/v1/sessions/{id}/stream`.
+pub(super) fn is_oncall_route(method: &Method, path: &str) -> bool {
+    if method != Method::GET {
+        return false;
+    }
+    let Some(rest) = path.strip_prefix("/api/v1/sessions") else {
+        return false;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+    rest.strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix("/stream"))
+        .is_some_and(|session_id| !session_id.is_empty() && !session_id.contains('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_session_reads_are_oncall_routes() {
+        assert!(is_oncall_route(&Method::GET, "/api/v1/sessions"));
+        assert!(is_oncall_route(
+            &Method::GET,
+            "/api/v1/sessions/portal:chat:main:1/stream"
+        ));
+        assert!(!is_oncall_route(&Method::POST, "/api/v1/sessions"));
+        assert!(!is_oncall_route(&Method::POST, "/api/v1/prompts"));
+        assert!(!is_oncall_route(&Method::GET, "/api/v1/sessions/abc"));
+        assert!(!is_oncall_route(
+            &Method::GET,
+            "/api/v1/sessions/abc/events"
+        ));
+        assert!(!is_oncall_route(&Method::GET, "/api/v1/sessions//stream"));
+        assert!(!is_oncall_route(&Method::GET, "/api/v1/sessionsx"));
+        assert!(!is_oncall_route(&Method::GET, "/api/agents"));
+    }
+}
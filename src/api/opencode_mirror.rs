@@ -0,0 +1,127 @@
+//! OpenCode mirror API: search and export sessions recorded by read-only mirrors.
+
+use super::state::ApiState;
+
+use crate::opencode::OpenCodeMirrorStore;
+use crate::opencode::mirror::MirrorSessionSummary;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub(super) struct MirrorSessionsQuery {
+    agent_id: String,
+    query: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub(super) struct MirrorSessionsResponse {
+    sessions: Vec<MirrorSessionSummary>,
+}
+
+#[derive(Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub(super) struct MirrorExportQuery {
+    agent_id: String,
+    session_id: String,
+}
+
+/// List or search sessions recorded from mirrored OpenCode backends.
+#[utoipa::path(
+    get,
+    path = "/agents/opencode-mirror/sessions",
+    params(
+        ("agent_id" = String, Query, description = "Agent ID"),
+        ("query" = Option<String>, Query, description = "Only sessions containing this text"),
+        ("limit" = i64, Query, description = "Maximum number of sessions to return"),
+    ),
+    responses(
+        (status = 200, body = MirrorSessionsResponse),
+        (status = 404, description = "Agent not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "workers",
+)]
+pub(super) async fn list_mirror_sessions(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<MirrorSessionsQuery>,
+) -> Result<Json<MirrorSessionsResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = OpenCodeMirrorStore::new(pool.clone());
+
+    let sessions = store
+        .search_sessions(
+            &query.agent_id,
+            query.query.as_deref(),
+            query.limit.clamp(1, 500),
+        )
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id = %query.agent_id, "failed to search opencode mirror");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(MirrorSessionsResponse { sessions }))
+}
+
+/// Export a mirrored session as JSON Lines, one record per line.
+#[utoipa::path(
+    get,
+    path = "/agents/opencode-mirror/export",
+    params(
+        ("agent_id" = String, Query, description = "Agent ID"),
+        ("session_id" = String, Query, description = "OpenCode session ID"),
+    ),
+    responses(
+        (status = 200, description = "JSON Lines export", content_type = "application/x-ndjson"),
+        (status = 404, description = "Agent or session not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "workers",
+)]
+pub(super) async fn export_mirror_session(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<MirrorExportQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = OpenCodeMirrorStore::new(pool.clone());
+
+    let records = store
+        .session_records(&query.agent_id, &query.session_id)
+        .await
+        .map_err(|error| {
+            tracing::warn!(
+                %error,
+                agent_id = %query.agent_id,
+                session_id = %query.session_id,
+                "failed to export opencode mirror session"
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if records.is_empty() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let mut body = String::new();
+    for record in &records {
+        let line = serde_json::to_string(record).map_err(|error| {
+            tracing::warn!(%error, "failed to serialize opencode mirror record");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        body.push_str(&line);
+        body.push('\n');
+    }
+
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body))
+}
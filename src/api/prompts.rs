@@ -0,0 +1,764 @@
+//! Versioned endpoints for submitting prompts from scripts and CI jobs.
+//!
+//! A prompt is delivered as a portal message, so it goes through the same
+//! binding resolution, model routing, usage accounting, and conversation
+//! settings as a message typed in the dashboard. Each session is a portal
+//! conversation that callers poll or stream until the agent goes idle.
+//! Streams come as SSE with the raw event payloads, or as a WebSocket with
+//! the normalized [`StreamEvent`] shape meant for external dashboards.
+
+use super::state::{ApiEvent, ApiState, LastToolCall};
+use crate::conversation::channels::ChannelStore;
+use crate::conversation::{ConversationLogger, PortalConversationStore};
+use crate::{InboundMessage, MessageContent};
+
+use axum::Json;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{Response, Sse};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(super) struct SubmitPromptRequest {
+    agent_id: String,
+    prompt: String,
+    /// Continue an existing session instead of starting a new one.
+    session_id: Option<String>,
+    #[serde(default = "default_sender_name")]
+    sender_name: String,
+}
+
+fn default_sender_name() -> String {
+    "api".into()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SubmitPromptResponse {
+    pub session_id: String,
+    pub message_id: String,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(super) struct SessionQuery {
+    /// Agent that owns the session. Looked up across all agents if omitted.
+    agent_id: Option<String>,
+    #[serde(default = "default_message_limit")]
+    limit: i64,
+}
+
+fn default_message_limit() -> i64 {
+    100
+}
+
+/// Where a session is in handling its latest prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    /// The latest prompt has not been answered yet.
+    Pending,
+    /// Workers or branches spawned for the session are still running.
+    Working,
+    /// Everything has been answered and nothing is running.
+    Idle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SessionMessage {
+    pub id: String,
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SessionWorker {
+    pub id: String,
+    pub task: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SessionResponse {
+    pub session_id: String,
+    pub agent_id: String,
+    pub status: SessionStatus,
+    pub messages: Vec<SessionMessage>,
+    pub workers: Vec<SessionWorker>,
+}
+
+/// Submit a prompt to an agent. The reply is produced asynchronously; poll
+/// `/v1/sessions/{session_id}` or stream its events to collect it.
+#[utoipa::path(
+    post,
+    path = "/v1/prompts",
+    request_body = SubmitPromptRequest,
+    responses(
+        (status = 202, body = SubmitPromptResponse),
+        (status = 400, description = "Empty prompt"),
+        (status = 404, description = "Agent not found"),
+        (status = 503, description = "Messaging manager not available"),
+    ),
+    tag = "prompts",
+)]
+pub(super) async fn submit_prompt(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SubmitPromptRequest>,
+) -> Result<(StatusCode, Json<SubmitPromptResponse>), StatusCode> {
+    if request.prompt.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let manager = state
+        .messaging_manager
+        .read()
+        .await
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let pool = state
+        .agent_pools
+        .load()
+        .get(&request.agent_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let session_id = request
+        .session_id
+        .unwrap_or_else(|| new_session_id(&request.agent_id));
+    let store = PortalConversationStore::new(pool);
+    store
+        .ensure(&request.agent_id, &session_id)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %session_id, "failed to ensure prompt session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    store
+        .maybe_set_generated_title(&request.agent_id, &session_id, &request.prompt)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %session_id, "failed to update generated session title");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let message_id = uuid::Uuid::new_v4().to_string();
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "display_name".into(),
+        serde_json::Value::String(request.sender_name.clone()),
+    );
+    let inbound = InboundMessage {
+        id: message_id.clone(),
+        source: "portal".into(),
+        adapter: Some("portal".into()),
+        conversation_id: session_id.clone(),
+        sender_id: request.sender_name.clone(),
+        agent_id: Some(request.agent_id.into()),
+        content: MessageContent::Text(request.prompt),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(request.sender_name),
+    };
+    manager.inject_message(inbound).await.map_err(|error| {
+        tracing::warn!(%error, "failed to inject api prompt");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SubmitPromptResponse {
+            session_id,
+            message_id,
+        }),
+    ))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ActiveSession {
+    pub session_id: String,
+    pub agent_id: String,
+    pub platform: String,
+    pub display_name: Option<String>,
+    /// `working` while workers or branches run, otherwise `idle`.
+    pub status: SessionStatus,
+    pub active_workers: usize,
+    pub active_branches: usize,
+    /// Estimated spend for the session so far.
+    pub cost_usd: f64,
+    pub last_tool_call: Option<LastToolCall>,
+    pub last_activity_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ActiveSessionsResponse {
+    pub sessions: Vec<ActiveSession>,
+}
+
+/// List every session with a running channel on any platform, most recently
+/// active first.
+#[utoipa::path(
+    get,
+    path = "/v1/sessions",
+    responses(
+        (status = 200, body = ActiveSessionsResponse),
+    ),
+    tag = "prompts",
+)]
+pub(super) async fn list_sessions(
+    State(state): State<Arc<ApiState>>,
+) -> Json<ActiveSessionsResponse> {
+    let running: Vec<(String, String)> = state
+        .channel_states
+        .read()
+        .await
+        .iter()
+        .map(|(channel_id, channel_state)| {
+            (channel_id.clone(), channel_state.deps.agent_id.to_string())
+        })
+        .collect();
+    let pools = state.agent_pools.load();
+    let last_tool_calls = state.last_tool_calls.read().await.clone();
+
+    let mut sessions = Vec::with_capacity(running.len());
+    for (session_id, agent_id) in running {
+        let (active_workers, active_branches) = active_work(&state, &session_id).await;
+        let mut session = ActiveSession {
+            platform: platform_of(&session_id).to_string(),
+            display_name: None,
+            status: if active_workers + active_branches > 0 {
+                SessionStatus::Working
+            } else {
+                SessionStatus::Idle
+            },
+            active_workers,
+            active_branches,
+            cost_usd: 0.0,
+            last_tool_call: last_tool_calls.get(&session_id).cloned(),
+            last_activity_at: None,
+            session_id,
+            agent_id,
+        };
+
+        if let Some(pool) = pools.get(&session.agent_id) {
+            match ChannelStore::new(pool.clone())
+                .get(&session.session_id)
+                .await
+            {
+                Ok(Some(channel)) => {
+                    session.platform = channel.platform;
+                    session.display_name = channel.display_name;
+                    session.last_activity_at = Some(channel.last_activity_at.to_rfc3339());
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    tracing::warn!(%error, session_id = %session.session_id, "failed to load session channel");
+                }
+            }
+            match sqlx::query_scalar::<_, f64>(
+                "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) FROM token_usage WHERE conversation_id = ?",
+            )
+            .bind(&session.session_id)
+            .fetch_one(pool)
+            .await
+            {
+                Ok(cost_usd) => session.cost_usd = cost_usd,
+                Err(error) => {
+                    tracing::warn!(%error, session_id = %session.session_id, "failed to query session cost");
+                }
+            }
+        }
+        sessions.push(session);
+    }
+
+    sessions.sort_by(|left, right| right.last_activity_at.cmp(&left.last_activity_at));
+    Json(ActiveSessionsResponse { sessions })
+}
+
+/// Get a session's transcript and whether the agent is still working on it.
+#[utoipa::path(
+    get,
+    path = "/v1/sessions/{session_id}",
+    params(
+        ("session_id" = String, Path, description = "Session ID returned by /v1/prompts"),
+        SessionQuery,
+    ),
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "prompts",
+)]
+pub(super) async fn get_session(
+    State(state): State<Arc<ApiState>>,
+    Path(session_id): Path<String>,
+    Query(query): Query<SessionQuery>,
+) -> Result<Json<SessionResponse>, StatusCode> {
+    let (agent_id, pool) = find_session(&state, &session_id, query.agent_id.as_deref())
+        .await?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let channel_id: crate::ChannelId = Arc::from(session_id.as_str());
+    let messages = ConversationLogger::new(pool)
+        .load_recent(&channel_id, query.limit.clamp(1, 500))
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %session_id, "failed to load session messages");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let status_block = state
+        .channel_status_blocks
+        .read()
+        .await
+        .get(&session_id)
+        .cloned();
+    let (workers, has_active_branches) = match status_block {
+        Some(status_block) => {
+            let block = status_block.read().await;
+            let workers = block
+                .active_workers
+                .iter()
+                .map(|worker| SessionWorker {
+                    id: worker.id.to_string(),
+                    task: worker.task.clone(),
+                    status: worker.status.clone(),
+                })
+                .collect();
+            (workers, !block.active_branches.is_empty())
+        }
+        None => (Vec::new(), false),
+    };
+
+    let last_role = messages.last().map(|message| message.role.as_str());
+    let status = session_status(last_role, !workers.is_empty() || has_active_branches);
+
+    Ok(Json(SessionResponse {
+        session_id,
+        agent_id,
+        status,
+        messages: messages
+            .into_iter()
+            .map(|message| SessionMessage {
+                id: message.id,
+                role: message.role,
+                content: message.content,
+                created_at: message.created_at.to_rfc3339(),
+            })
+            .collect(),
+        workers,
+    }))
+}
+
+/// Stream a session's events (reply deltas, replies, worker and tool
+/// progress) as they happen. Events use the same names and payloads as
+/// `/events`.
+#[utoipa::path(
+    get,
+    path = "/v1/sessions/{session_id}/events",
+    params(
+        ("session_id" = String, Path, description = "Session ID returned by /v1/prompts"),
+    ),
+    responses(
+        (status = 200, description = "SSE event stream", content_type = "text/event-stream"),
+    ),
+    tag = "prompts",
+)]
+pub(super) async fn session_events(
+    State(state): State<Arc<ApiState>>,
+    Path(session_id): Path<String>,
+) -> Sse<impl Stream<Item = Result<axum::response::sse::Event, Infallible>>> {
+    let mut rx = state.event_tx.subscribe();
+
+    let stream = async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if event.channel_id() != Some(session_id.as_str()) {
+                        continue;
+                    }
+                    if let Ok(json) = serde_json::to_string(&event) {
+                        yield Ok(axum::response::sse::Event::default()
+                            .event(event.event_type())
+                            .data(json));
+                    }
+                }
+                Err(error) => {
+                    match crate::classify_broadcast_recv_result::<ApiEvent>(Err(error)) {
+                        crate::BroadcastRecvResult::Lagged(count) => {
+                            yield Ok(axum::response::sse::Event::default()
+                                .event("lagged")
+                                .data(format!("{{\"skipped\":{count}}}")));
+                        }
+                        crate::BroadcastRecvResult::Closed => break,
+                        crate::BroadcastRecvResult::Event(_) => unreachable!(
+                            "classifying an Err recv result should never produce Event"
+                        ),
+                    }
+                }
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(std::time::Duration::from_secs(15))
+            .text("ping"),
+    )
+}
+
+/// A session event as sent over the WebSocket stream. Only the fields a
+/// dashboard needs to render a reply and its tool timeline are kept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// The sender's prompt was received.
+    Prompt {
+        sender: String,
+        text: String,
+    },
+    /// Reply text as it is generated. `text` is everything so far.
+    TextDelta {
+        delta: String,
+        text: String,
+    },
+    /// A finished reply.
+    Message {
+        text: String,
+    },
+    /// The agent started or stopped working on a reply.
+    Typing {
+        active: bool,
+    },
+    ToolStarted {
+        process_type: String,
+        process_id: String,
+        call_id: String,
+        tool_name: String,
+        args: String,
+    },
+    ToolCompleted {
+        process_type: String,
+        process_id: String,
+        call_id: String,
+        tool_name: String,
+        result: String,
+    },
+    WorkerStarted {
+        worker_id: String,
+        task: String,
+    },
+    WorkerStatus {
+        worker_id: String,
+        status: String,
+    },
+    WorkerCompleted {
+        worker_id: String,
+        result: String,
+        success: bool,
+    },
+    BranchStarted {
+        branch_id: String,
+        description: String,
+    },
+    BranchCompleted {
+        branch_id: String,
+        conclusion: String,
+    },
+    /// Everything is answered and no workers or branches are running.
+    Completed,
+    /// The stream fell behind and dropped events.
+    Lagged {
+        skipped: u64,
+    },
+}
+
+impl StreamEvent {
+    fn from_api_event(event: &ApiEvent) -> Option<Self> {
+        let event = match event {
+            ApiEvent::InboundMessage {
+                sender_name,
+                sender_id,
+                text,
+                ..
+            } => Self::Prompt {
+                sender: sender_name.clone().unwrap_or_else(|| sender_id.clone()),
+                text: text.clone(),
+            },
+            ApiEvent::OutboundMessageDelta {
+                text_delta,
+                aggregated_text,
+                ..
+            } => Self::TextDelta {
+                delta: text_delta.clone(),
+                text: aggregated_text.clone(),
+            },
+            ApiEvent::OutboundMessage { text, .. } => Self::Message { text: text.clone() },
+            ApiEvent::TypingState { is_typing, .. } => Self::Typing { active: *is_typing },
+            ApiEvent::ToolStarted {
+                process_type,
+                process_id,
+                call_id,
+                tool_name,
+                args,
+                ..
+            } => Self::ToolStarted {
+                process_type: process_type.clone(),
+                process_id: process_id.clone(),
+                call_id: call_id.clone(),
+                tool_name: tool_name.clone(),
+                args: args.clone(),
+            },
+            ApiEvent::ToolCompleted {
+                process_type,
+                process_id,
+                call_id,
+                tool_name,
+                result,
+                ..
+            } => Self::ToolCompleted {
+                process_type: process_type.clone(),
+                process_id: process_id.clone(),
+                call_id: call_id.clone(),
+                tool_name: tool_name.clone(),
+                result: result.clone(),
+            },
+            ApiEvent::WorkerStarted {
+                worker_id, task, ..
+            } => Self::WorkerStarted {
+                worker_id: worker_id.clone(),
+                task: task.clone(),
+            },
+            ApiEvent::WorkerStatusUpdate {
+                worker_id, status, ..
+            } => Self::WorkerStatus {
+                worker_id: worker_id.clone(),
+                status: status.clone(),
+            },
+            ApiEvent::WorkerCompleted {
+                worker_id,
+                result,
+                success,
+                ..
+            } => Self::WorkerCompleted {
+                worker_id: worker_id.clone(),
+                result: result.clone(),
+                success: *success,
+            },
+            ApiEvent::BranchStarted {
+                branch_id,
+                description,
+                ..
+            } => Self::BranchStarted {
+                branch_id: branch_id.clone(),
+                description: description.clone(),
+            },
+            ApiEvent::BranchCompleted {
+                branch_id,
+                conclusion,
+                ..
+            } => Self::BranchCompleted {
+                branch_id: branch_id.clone(),
+                conclusion: conclusion.clone(),
+            },
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// Stream a session over a WebSocket as normalized [`StreamEvent`]s,
+/// including a `completed` event each time the agent finishes all work for
+/// the session. Browsers can't set headers on WebSocket requests, so the
+/// API token may also be passed as `?token=`.
+#[utoipa::path(
+    get,
+    path = "/v1/sessions/{session_id}/stream",
+    params(
+        ("session_id" = String, Path, description = "Session ID returned by /v1/prompts"),
+    ),
+    responses(
+        (status = 101, description = "WebSocket of StreamEvent JSON messages", body = StreamEvent),
+    ),
+    tag = "prompts",
+)]
+pub(super) async fn session_stream(
+    State(state): State<Arc<ApiState>>,
+    Path(session_id): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade.on_upgrade(move |socket| relay_session_events(state, session_id, socket))
+}
+
+async fn relay_session_events(state: Arc<ApiState>, session_id: String, mut socket: WebSocket) {
+    let mut rx = state.event_tx.subscribe();
+
+    loop {
+        let stream_event = tokio::select! {
+            received = rx.recv() => match crate::classify_broadcast_recv_result(received) {
+                crate::BroadcastRecvResult::Event(event) => {
+                    if event.channel_id() != Some(session_id.as_str()) {
+                        continue;
+                    }
+                    let Some(stream_event) = StreamEvent::from_api_event(&event) else {
+                        continue;
+                    };
+                    stream_event
+                }
+                crate::BroadcastRecvResult::Lagged(skipped) => StreamEvent::Lagged { skipped },
+                crate::BroadcastRecvResult::Closed => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        let finished_turn = stream_event == StreamEvent::Typing { active: false };
+        if !send_stream_event(&mut socket, &stream_event).await {
+            break;
+        }
+        if finished_turn
+            && active_work(&state, &session_id).await == (0, 0)
+            && !send_stream_event(&mut socket, &StreamEvent::Completed).await
+        {
+            break;
+        }
+    }
+
+    socket.close().await.ok();
+}
+
+/// Send one event as a JSON text frame. Returns false once the client is gone.
+async fn send_stream_event(socket: &mut WebSocket, event: &StreamEvent) -> bool {
+    let Ok(json) = serde_json::to_string(event) else {
+        return true;
+    };
+    socket.send(Message::Text(json.into())).await.is_ok()
+}
+
+/// Number of workers and branches still running for a session.
+async fn active_work(state: &ApiState, session_id: &str) -> (usize, usize) {
+    let status_block = state
+        .channel_status_blocks
+        .read()
+        .await
+        .get(session_id)
+        .cloned();
+    match status_block {
+        Some(status_block) => {
+            let block = status_block.read().await;
+            (block.active_workers.len(), block.active_branches.len())
+        }
+        None => (0, 0),
+    }
+}
+
+/// Platform prefix of a conversation ID, e.g. `discord` for `discord:123:456`.
+fn platform_of(session_id: &str) -> &str {
+    session_id.split(':').next().unwrap_or(session_id)
+}
+
+fn new_session_id(agent_id: &str) -> String {
+    format!("portal:api:{agent_id}:{}", uuid::Uuid::new_v4())
+}
+
+/// Find the agent that owns a session, checking only `agent_id` when given.
+async fn find_session(
+    state: &ApiState,
+    session_id: &str,
+    agent_id: Option<&str>,
+) -> Result<Option<(String, sqlx::SqlitePool)>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let candidates: Vec<(&String, &sqlx::SqlitePool)> = match agent_id {
+        Some(agent_id) => pools.get_key_value(agent_id).into_iter().collect(),
+        None => pools.iter().collect(),
+    };
+
+    for (agent_id, pool) in candidates {
+        let session = PortalConversationStore::new(pool.clone())
+            .get(agent_id, session_id)
+            .await
+            .map_err(|error| {
+                tracing::warn!(%error, %session_id, "failed to look up session");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        if session.is_some() {
+            return Ok(Some((agent_id.clone(), pool.clone())));
+        }
+    }
+    Ok(None)
+}
+
+fn session_status(last_role: Option<&str>, has_active_work: bool) -> SessionStatus {
+    if has_active_work {
+        SessionStatus::Working
+    } else if last_role == Some("user") {
+        SessionStatus::Pending
+    } else {
+        SessionStatus::Idle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_reflects_running_work_and_unanswered_prompts() {
+        assert_eq!(session_status(None, false), SessionStatus::Idle);
+        assert_eq!(session_status(Some("user"), false), SessionStatus::Pending);
+        assert_eq!(
+            session_status(Some("assistant"), false),
+            SessionStatus::Idle
+        );
+        assert_eq!(
+            session_status(Some("assistant"), true),
+            SessionStatus::Working
+        );
+        assert_eq!(session_status(Some("user"), true), SessionStatus::Working);
+    }
+
+    #[test]
+    fn platform_is_conversation_id_prefix() {
+        assert_eq!(platform_of("discord:123:456"), "discord");
+        assert_eq!(platform_of("portal:api:main:1"), "portal");
+        assert_eq!(platform_of("standalone"), "standalone");
+    }
+
+    #[test]
+    fn event_channel_scopes_session_streams() {
+        let event = ApiEvent::OutboundMessage {
+            agent_id: "main".into(),
+            channel_id: "portal:api:main:1".into(),
+            text: "done".into(),
+        };
+        assert_eq!(event.channel_id(), Some("portal:api:main:1"));
+        assert_eq!(event.event_type(), "outbound_message");
+        assert_eq!(ApiEvent::ConfigReloaded.channel_id(), None);
+    }
+
+    #[test]
+    fn stream_events_are_normalized_with_type_tags() {
+        let delta = ApiEvent::OutboundMessageDelta {
+            agent_id: "main".into(),
+            channel_id: "portal:api:main:1".into(),
+            text_delta: "lo".into(),
+            aggregated_text: "hello".into(),
+        };
+        let normalized = StreamEvent::from_api_event(&delta).unwrap();
+        assert_eq!(
+            serde_json::to_value(&normalized).unwrap(),
+            serde_json::json!({ "type": "text_delta", "delta": "lo", "text": "hello" })
+        );
+
+        assert_eq!(
+            serde_json::to_value(StreamEvent::Completed).unwrap(),
+            serde_json::json!({ "type": "completed" })
+        );
+        assert!(StreamEvent::from_api_event(&ApiEvent::ConfigReloaded).is_none());
+    }
+}
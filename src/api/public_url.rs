@@ -0,0 +1,202 @@
+//! External URL resolution for deployments behind a reverse proxy.
+//!
+//! Links the API hands out (attachment downloads, the dashboard entry point)
+//! must use the address clients see rather than the bind address. The base
+//! comes from `[api] public_url` when set, otherwise from `Forwarded` /
+//! `X-Forwarded-*` headers when `trust_forwarded_headers` is enabled, and
+//! otherwise from the request's `Host` header.
+
+use axum::http::{HeaderMap, header};
+
+/// Scheme, host, and mount path clients use to reach this server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalBase {
+    /// `scheme://host[:port]`, or empty when no host is known.
+    origin: String,
+    /// Subpath the server is mounted under (`/spacebot`), or empty.
+    base_path: String,
+}
+
+impl ExternalBase {
+    /// Resolve the external base for a request.
+    pub fn resolve(
+        public_url: Option<&str>,
+        trust_forwarded_headers: bool,
+        headers: &HeaderMap,
+    ) -> Self {
+        if let Some(public_url) = public_url {
+            return Self::from_public_url(public_url);
+        }
+
+        let host_header = headers
+            .get(header::HOST)
+            .and_then(|value| value.to_str().ok());
+        let mut scheme = None;
+        let mut host = None;
+        let mut prefix = None;
+
+        if trust_forwarded_headers {
+            if let Some(forwarded) = header_value(headers, "forwarded") {
+                // Only the first (client-facing) element matters.
+                let first = forwarded.split(',').next().unwrap_or_default();
+                for pair in first.split(';') {
+                    let Some((key, value)) = pair.trim().split_once('=') else {
+                        continue;
+                    };
+                    let value = value.trim_matches('"');
+                    match key.to_ascii_lowercase().as_str() {
+                        "proto" => scheme = Some(value.to_string()),
+                        "host" => host = Some(value.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            scheme = scheme.or_else(|| first_value(headers, "x-forwarded-proto"));
+            host = host.or_else(|| first_value(headers, "x-forwarded-host"));
+            prefix = first_value(headers, "x-forwarded-prefix");
+        }
+
+        let host = host.or_else(|| host_header.map(str::to_string));
+        let origin = match host {
+            Some(host) => format!("{}://{host}", scheme.as_deref().unwrap_or("http")),
+            None => String::new(),
+        };
+        Self {
+            origin,
+            base_path: normalize_base_path(prefix.as_deref().unwrap_or_default()),
+        }
+    }
+
+    /// Split a validated `public_url` into origin and mount path.
+    pub fn from_public_url(public_url: &str) -> Self {
+        let (origin, path) = match url::Url::parse(public_url) {
+            Ok(parsed) => (
+                parsed.origin().ascii_serialization(),
+                parsed.path().to_string(),
+            ),
+            Err(_) => (public_url.trim_end_matches('/').to_string(), String::new()),
+        };
+        Self {
+            origin,
+            base_path: normalize_base_path(&path),
+        }
+    }
+
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// Absolute URL for a server path such as `/api/health`. Falls back to a
+    /// root-relative URL when no host is known.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}{path}", self.origin, self.base_path)
+    }
+}
+
+/// `/spacebot/` → `/spacebot`, `/` → ``.
+pub fn normalize_base_path(path: &str) -> String {
+    let trimmed = path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{trimmed}")
+    }
+}
+
+/// Point the SPA at its mount path: set `window.__SPACEBOT_BASE_PATH` and
+/// rewrite root-relative asset references in `index.html`.
+pub fn inject_base_path(index_html: &str, base_path: &str) -> String {
+    if base_path.is_empty() {
+        return index_html.to_string();
+    }
+    let literal = serde_json::to_string(base_path)
+        .unwrap_or_else(|_| "\"\"".into())
+        .replace("</", "<\\/");
+    let script = format!("<head><script>window.__SPACEBOT_BASE_PATH={literal};</script>");
+    index_html
+        .replacen("<head>", &script, 1)
+        .replace("src=\"/", &format!("src=\"{base_path}/"))
+        .replace("href=\"/", &format!("href=\"{base_path}/"))
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn first_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    header_value(headers, name)
+        .and_then(|value| value.split(',').next())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn public_url_wins_over_headers() {
+        let base = ExternalBase::resolve(
+            Some("https://bots.example.com/spacebot"),
+            true,
+            &headers(&[
+                ("host", "127.0.0.1:19898"),
+                ("x-forwarded-host", "evil.test"),
+            ]),
+        );
+        assert_eq!(base.base_path(), "/spacebot");
+        assert_eq!(
+            base.url("/api/health"),
+            "https://bots.example.com/spacebot/api/health"
+        );
+    }
+
+    #[test]
+    fn forwarded_headers_only_when_trusted() {
+        let request = headers(&[
+            ("host", "127.0.0.1:19898"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "bots.example.com, proxy.internal"),
+            ("x-forwarded-prefix", "/sb/"),
+        ]);
+        assert_eq!(
+            ExternalBase::resolve(None, true, &request).url("/"),
+            "https://bots.example.com/sb/"
+        );
+        assert_eq!(
+            ExternalBase::resolve(None, false, &request).url("/"),
+            "http://127.0.0.1:19898/"
+        );
+    }
+
+    #[test]
+    fn rfc7239_forwarded_header_is_parsed() {
+        let request = headers(&[(
+            "forwarded",
+            "for=10.0.0.1;proto=https;host=\"bots.example.com\", for=10.0.0.2",
+        )]);
+        assert_eq!(
+            ExternalBase::resolve(None, true, &request).url("/x"),
+            "https://bots.example.com/x"
+        );
+    }
+
+    #[test]
+    fn index_html_gets_base_path() {
+        let html = r#"<html><head><link href="/favicon.ico"></head><script src="/assets/app.js"></script></html>"#;
+        let injected = inject_base_path(html, "/sb");
+        assert!(injected.contains(r#"window.__SPACEBOT_BASE_PATH="/sb""#));
+        assert!(injected.contains(r#"href="/sb/favicon.ico""#));
+        assert!(injected.contains(r#"src="/sb/assets/app.js""#));
+        assert_eq!(inject_base_path(html, ""), html);
+    }
+}
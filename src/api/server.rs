@@ -1,19 +1,21 @@
 //! HTTP server setup: router, static file serving, and API route wiring.
 
+use super::public_url::{ExternalBase, inject_base_path};
 use super::state::ApiState;
 use super::{
     activity, agents, attachments, bindings, channels, config, cortex, cron, factory, ingest,
-    links, mcp, memories, messaging, models, notifications, opencode_proxy, portal, projects,
-    providers, secrets, settings, skills, ssh, system, tasks, tools, usage, wiki, workers,
+    links, mcp, memories, messaging, models, notifications, oncall, opencode_mirror,
+    opencode_proxy, portal, projects, prompts, providers, secrets, settings, skills, ssh, system,
+    tasks, tools, usage, webhooks, wiki, workers,
 };
 
 use axum::Json;
 use axum::Router;
 use axum::extract::{DefaultBodyLimit, Request, State};
-use axum::http::{StatusCode, Uri, header};
+use axum::http::{HeaderMap, StatusCode, Uri, header};
 use axum::middleware::{self, Next};
 use axum::response::{Html, IntoResponse, Response};
-use axum::routing::any;
+use axum::routing::{any, get};
 use rust_embed::Embed;
 use serde_json::json;
 use tower_http::cors::CorsLayer;
@@ -55,6 +57,7 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
         .routes(routes!(system::backup_export))
         .routes(routes!(system::backup_restore))
         .routes(routes!(system::events_sse))
+        .routes(routes!(system::dead_letters))
         // Agent routes
         .routes(routes!(agents::instance_overview))
         .routes(routes!(agents::list_agents))
@@ -93,6 +96,8 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
         .routes(routes!(channels::list_prompt_snapshots))
         .routes(routes!(channels::get_prompt_snapshot))
         .routes(routes!(channels::cancel_process))
+        .routes(routes!(channels::kill_channel))
+        .routes(routes!(channels::export_channel))
         .routes(routes!(
             channels::get_channel_settings,
             channels::update_channel_settings
@@ -100,6 +105,8 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
         // Worker routes
         .routes(routes!(workers::list_workers))
         .routes(routes!(workers::worker_detail))
+        .routes(routes!(opencode_mirror::list_mirror_sessions))
+        .routes(routes!(opencode_mirror::export_mirror_session))
         // Memory routes
         .routes(routes!(memories::list_memories))
         .routes(routes!(memories::search_memories))
@@ -208,6 +215,9 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
         .routes(routes!(models::refresh_models))
         // Messaging routes
         .routes(routes!(messaging::messaging_status))
+        .routes(routes!(messaging::messaging_quota))
+        .routes(routes!(messaging::list_quarantine))
+        .routes(routes!(messaging::review_quarantine))
         .routes(routes!(messaging::disconnect_platform))
         .routes(routes!(messaging::toggle_platform))
         .routes(routes!(
@@ -230,6 +240,7 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
             settings::get_raw_config,
             settings::update_raw_config
         ))
+        .routes(routes!(settings::validate_raw_config))
         .routes(routes!(settings::update_check, settings::update_check_now))
         .routes(routes!(settings::update_apply))
         .routes(routes!(settings::changelog))
@@ -244,6 +255,12 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
         .routes(routes!(portal::update_portal_conversation))
         .routes(routes!(portal::delete_portal_conversation))
         .routes(routes!(portal::conversation_defaults))
+        // Prompt routes (versioned, for scripts and CI)
+        .routes(routes!(prompts::submit_prompt))
+        .routes(routes!(prompts::list_sessions))
+        .routes(routes!(prompts::get_session))
+        .routes(routes!(prompts::session_events))
+        .routes(routes!(prompts::session_stream))
         // Attachment routes
         .routes(routes!(attachments::upload_attachment))
         .routes(routes!(attachments::serve_attachment))
@@ -262,6 +279,8 @@ pub fn api_router() -> OpenApiRouter<Arc<ApiState>> {
         .routes(routes!(usage::get_conversation_usage))
         // Activity routes
         .routes(routes!(activity::get_activity))
+        // Webhook routes (signature-verified instead of bearer auth)
+        .routes(routes!(webhooks::github_webhook))
         // Factory routes
         .routes(routes!(factory::list_presets))
         .routes(routes!(factory::get_preset))
@@ -316,16 +335,36 @@ pub async fn start_http_server(
     #[cfg(feature = "metrics")]
     let protected_routes = protected_routes.layer(middleware::from_fn(metrics_middleware));
 
+    let base_path = state
+        .public_url
+        .as_deref()
+        .map(|url| ExternalBase::from_public_url(url).base_path().to_string())
+        .unwrap_or_default();
+
     // Build the main application router
     let app = Router::new()
         // Mount all protected routes
         .merge(protected_routes)
+        // Read-only on-call page (static; its data calls carry the token)
+        .route("/oncall", get(oncall::oncall_page))
         // Static file handler for frontend (unprotected)
         .fallback(static_handler)
-        .layer(cors)
-        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)) // 10 MiB
         .with_state(state);
 
+    // With a subpath in `public_url`, serve under it as well as at the root
+    // so proxies that strip the prefix and ones that forward it both work.
+    let app = if base_path.is_empty() {
+        app
+    } else {
+        tracing::info!(%base_path, "HTTP API mounted under subpath");
+        Router::new()
+            .nest(&base_path, app.clone())
+            .fallback_service(app)
+    };
+    let app = app
+        .layer(cors)
+        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)); // 10 MiB
+
     let listener = tokio::net::TcpListener::bind(bind).await?;
     tracing::info!(%bind, "HTTP server listening");
 
@@ -357,13 +396,20 @@ async fn api_auth_middleware(
     if path == "/api/health" || path == "/health" {
         return next.run(request).await;
     }
+    // GitHub can't send the bearer token; the handler verifies the
+    // delivery's HMAC signature instead.
+    if path.starts_with("/api/webhooks/github/") {
+        return next.run(request).await;
+    }
 
     let is_authorized = request
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|value| value.to_str().ok())
         .and_then(|value| value.strip_prefix("Bearer "))
-        .is_some_and(|token| token == expected_token);
+        .is_some_and(|token| token == expected_token)
+        || is_websocket_query_token(&request, expected_token)
+        || is_oncall_request(&state, &request);
 
     if is_authorized {
         next.run(request).await
@@ -376,6 +422,39 @@ async fn api_auth_middleware(
     }
 }
 
+/// Whether the request carries the read-only on-call token and is limited to
+/// what the `/oncall` page reads: the session list and session streams.
+fn is_oncall_request(state: &ApiState, request: &Request) -> bool {
+    let Some(oncall_token) = state.oncall_token.as_deref() else {
+        return false;
+    };
+    if !oncall::is_oncall_route(request.method(), request.uri().path()) {
+        return false;
+    }
+    request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token == oncall_token)
+        || is_websocket_query_token(request, oncall_token)
+}
+
+/// Browsers can't set headers on WebSocket requests, so upgrades may carry
+/// the token as `?token=` instead.
+fn is_websocket_query_token(request: &Request, expected_token: &str) -> bool {
+    let is_upgrade = request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    is_upgrade
+        && request.uri().query().is_some_and(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .any(|(key, value)| key == "token" && value == expected_token)
+        })
+}
+
 #[cfg(feature = "metrics")]
 async fn metrics_middleware(request: Request, next: Next) -> Response {
     let method = request.method().to_string();
@@ -424,6 +503,7 @@ fn normalize_api_path(path: &str) -> String {
             let parent = parts.get(i - 1).copied().unwrap_or("");
             match parent {
                 "secrets" | "groups" | "humans" | "links" => normalized.push("{name}"),
+                "sessions" => normalized.push("{id}"),
                 "servers" | "providers" => normalized.push("{name}"),
                 "opencode" => normalized.push("{port}"),
                 "agents"
@@ -466,7 +546,11 @@ fn normalize_api_path(path: &str) -> String {
     normalized.join("/")
 }
 
-async fn static_handler(uri: Uri) -> Response {
+async fn static_handler(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
     let path = uri.path().trim_start_matches('/');
 
     if let Some(content) = InterfaceAssets::get(path) {
@@ -480,7 +564,9 @@ async fn static_handler(uri: Uri) -> Response {
     }
 
     if let Some(content) = InterfaceAssets::get("index.html") {
-        return Html(std::str::from_utf8(&content.data).unwrap_or("").to_string()).into_response();
+        let index_html = std::str::from_utf8(&content.data).unwrap_or("");
+        let base = state.external_base(&headers);
+        return Html(inject_base_path(index_html, base.base_path())).into_response();
     }
 
     (StatusCode::NOT_FOUND, "not found").into_response()
@@ -660,3 +660,53 @@ pub(super) async fn update_raw_config(
         message: "Config saved and reloaded.".to_string(),
     }))
 }
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(super) struct RawConfigValidateRequest {
+    /// TOML to check. Defaults to the instance's config file on disk.
+    content: Option<String>,
+}
+
+/// Check a config without saving or applying it.
+#[utoipa::path(
+    post,
+    path = "/settings/raw/validate",
+    request_body = RawConfigValidateRequest,
+    responses(
+        (status = 200, body = RawConfigUpdateResponse),
+        (status = 500, description = "Internal server error"),
+    ),
+    tag = "settings",
+)]
+pub(super) async fn validate_raw_config(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<RawConfigValidateRequest>,
+) -> Result<Json<RawConfigUpdateResponse>, StatusCode> {
+    let content = match request.content {
+        Some(content) => content,
+        None => {
+            let config_path = state.config_path.read().await.clone();
+            if config_path.as_os_str().is_empty() {
+                tracing::error!("config_path not set in ApiState");
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+            tokio::fs::read_to_string(&config_path)
+                .await
+                .map_err(|error| {
+                    tracing::warn!(%error, "failed to read config.toml");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+        }
+    };
+
+    Ok(Json(match crate::config::Config::validate_toml(&content) {
+        Ok(()) => RawConfigUpdateResponse {
+            success: true,
+            message: "Config is valid.".to_string(),
+        },
+        Err(error) => RawConfigUpdateResponse {
+            success: false,
+            message: format!("Validation error: {error:#}"),
+        },
+    }))
+}
@@ -3,6 +3,7 @@
 use crate::agent::channel::ChannelState;
 use crate::agent::cortex_chat::CortexChatSession;
 use crate::agent::status::StatusBlock;
+use crate::api::EventBus;
 use crate::config::{
     Binding, DefaultsConfig, DiscordPermissions, RuntimeConfig, SignalPermissions, SlackPermissions,
 };
@@ -21,7 +22,7 @@ use crate::update::SharedUpdateStatus;
 use crate::{ProcessEvent, ProcessId};
 
 use arc_swap::ArcSwap;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -224,8 +225,14 @@ pub struct AgentInfo {
 pub struct ApiState {
     pub started_at: Instant,
     pub auth_token: Option<String>,
+    /// `[api] oncall_token`, accepted only on the routes the on-call page reads.
+    pub oncall_token: Option<String>,
+    /// `[api] public_url`, fixed at startup since it decides the mount path.
+    pub public_url: Option<String>,
+    /// Whether `Forwarded` / `X-Forwarded-*` headers describe the external URL.
+    pub trust_forwarded_headers: bool,
     /// Aggregated event stream from all agents. SSE clients subscribe here.
-    pub event_tx: broadcast::Sender<ApiEvent>,
+    pub event_tx: EventBus,
     /// Per-agent SQLite pools for querying channel/conversation data.
     pub agent_pools: arc_swap::ArcSwap<HashMap<String, sqlx::SqlitePool>>,
     /// Per-agent config summaries for the agents list endpoint.
@@ -332,6 +339,10 @@ pub struct ApiState {
     /// In-memory cache of tool calls for running channel turns (direct mode).
     /// Keyed by channel_id, drained when the bot message is persisted.
     pub live_channel_tool_calls: Arc<RwLock<HashMap<String, Vec<ChannelToolCallEntry>>>>,
+    /// Most recent tool call started by any process in each channel, for the
+    /// active sessions overview. Keyed by channel_id, cleared when the
+    /// channel is dropped.
+    pub last_tool_calls: Arc<RwLock<HashMap<String, LastToolCall>>>,
     /// Serializes SSH daemon enable/disable transitions to prevent races
     /// between overlapping toggle requests.
     pub ssh_mutex: tokio::sync::Mutex<()>,
@@ -349,8 +360,17 @@ pub struct ChannelToolCallEntry {
     pub completed_at: Option<String>,
 }
 
+/// The latest tool call started in a channel by the channel itself or one of
+/// its workers or branches.
+#[derive(Debug, Clone, Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct LastToolCall {
+    pub tool_name: String,
+    pub process_type: String,
+    pub started_at: String,
+}
+
 /// Events sent to SSE clients. Wraps ProcessEvents with agent context.
-#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ApiEvent {
     /// An inbound message from a user.
@@ -475,6 +495,15 @@ pub enum ApiEvent {
         agent_id: String,
         worker_id: String,
         part: crate::opencode::types::OpenCodePart,
+        /// Present for parts from a `task` tool sub-agent session.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subagent: Option<crate::opencode::types::OpenCodeSubagent>,
+    },
+    /// An OpenCode server emitted a log entry or notification.
+    OpenCodeServerLog {
+        agent_id: String,
+        worker_id: String,
+        entry: crate::opencode::types::ServerLogEntry,
     },
     /// A worker emitted text content (model reasoning between tool calls).
     WorkerText {
@@ -513,6 +542,59 @@ pub enum ApiEvent {
     },
 }
 
+impl ApiEvent {
+    /// SSE event name for this event.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            ApiEvent::InboundMessage { .. } => "inbound_message",
+            ApiEvent::OutboundMessage { .. } => "outbound_message",
+            ApiEvent::OutboundMessageDelta { .. } => "outbound_message_delta",
+            ApiEvent::TypingState { .. } => "typing_state",
+            ApiEvent::WorkerStarted { .. } => "worker_started",
+            ApiEvent::WorkerStatusUpdate { .. } => "worker_status",
+            ApiEvent::WorkerIdle { .. } => "worker_idle",
+            ApiEvent::WorkerCompleted { .. } => "worker_completed",
+            ApiEvent::BranchStarted { .. } => "branch_started",
+            ApiEvent::BranchCompleted { .. } => "branch_completed",
+            ApiEvent::ToolStarted { .. } => "tool_started",
+            ApiEvent::ToolCompleted { .. } => "tool_completed",
+            ApiEvent::ConfigReloaded => "config_reloaded",
+            ApiEvent::AgentMessageSent { .. } => "agent_message_sent",
+            ApiEvent::AgentMessageReceived { .. } => "agent_message_received",
+            ApiEvent::TaskUpdated { .. } => "task_updated",
+            ApiEvent::OpenCodePartUpdated { .. } => "opencode_part_updated",
+            ApiEvent::OpenCodeServerLog { .. } => "opencode_server_log",
+            ApiEvent::WorkerText { .. } => "worker_text",
+            ApiEvent::CortexChatMessage { .. } => "cortex_chat_message",
+            ApiEvent::NotificationCreated { .. } => "notification_created",
+            ApiEvent::NotificationUpdated { .. } => "notification_updated",
+            ApiEvent::ToolOutput { .. } => "tool_output",
+        }
+    }
+
+    /// The channel this event belongs to, if it is scoped to one.
+    pub fn channel_id(&self) -> Option<&str> {
+        match self {
+            ApiEvent::InboundMessage { channel_id, .. }
+            | ApiEvent::OutboundMessage { channel_id, .. }
+            | ApiEvent::OutboundMessageDelta { channel_id, .. }
+            | ApiEvent::TypingState { channel_id, .. }
+            | ApiEvent::BranchStarted { channel_id, .. }
+            | ApiEvent::BranchCompleted { channel_id, .. }
+            | ApiEvent::AgentMessageSent { channel_id, .. }
+            | ApiEvent::AgentMessageReceived { channel_id, .. } => Some(channel_id),
+            ApiEvent::WorkerStarted { channel_id, .. }
+            | ApiEvent::WorkerStatusUpdate { channel_id, .. }
+            | ApiEvent::WorkerIdle { channel_id, .. }
+            | ApiEvent::WorkerCompleted { channel_id, .. }
+            | ApiEvent::ToolStarted { channel_id, .. }
+            | ApiEvent::ToolCompleted { channel_id, .. }
+            | ApiEvent::ToolOutput { channel_id, .. } => channel_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 impl ApiState {
     pub fn new_with_provider_sender(
         provider_setup_tx: mpsc::Sender<crate::ProviderSetupEvent>,
@@ -520,10 +602,13 @@ impl ApiState {
         agent_remove_tx: mpsc::Sender<String>,
         injection_tx: mpsc::Sender<crate::ChannelInjection>,
     ) -> Self {
-        let (event_tx, _) = broadcast::channel(512);
+        let event_tx = EventBus::new(512);
         Self {
             started_at: Instant::now(),
             auth_token: None,
+            oncall_token: None,
+            public_url: None,
+            trust_forwarded_headers: false,
             event_tx,
             agent_pools: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             agent_configs: arc_swap::ArcSwap::from_pointee(Vec::new()),
@@ -570,6 +655,7 @@ impl ApiState {
             live_worker_transcripts: Arc::new(RwLock::new(HashMap::new())),
             completed_worker_tombstones: Arc::new(RwLock::new(HashSet::new())),
             live_channel_tool_calls: Arc::new(RwLock::new(HashMap::new())),
+            last_tool_calls: Arc::new(RwLock::new(HashMap::new())),
             ssh_mutex: tokio::sync::Mutex::new(()),
         }
     }
@@ -599,6 +685,7 @@ impl ApiState {
     /// Remove a channel's state when it's dropped.
     pub async fn unregister_channel_state(&self, channel_id: &str) {
         self.channel_states.write().await.remove(channel_id);
+        self.last_tool_calls.write().await.remove(channel_id);
     }
 
     /// Retrieve the live transcript cache for a running worker.
@@ -622,6 +709,7 @@ impl ApiState {
         let live_transcripts = self.live_worker_transcripts.clone();
         let completed_worker_tombstones = self.completed_worker_tombstones.clone();
         let live_channel_tools = self.live_channel_tool_calls.clone();
+        let last_tool_calls = self.last_tool_calls.clone();
         // Snapshot the notification store at registration time. It is set once
         // at startup before any agents register, so the snapshot is always valid.
         let _notif_store_snap = self.notification_store.load_full();
@@ -792,6 +880,16 @@ impl ApiState {
                                 ..
                             } => {
                                 let (process_type, id_str) = process_id_info(process_id);
+                                if let Some(channel_id) = channel_id {
+                                    last_tool_calls.write().await.insert(
+                                        channel_id.to_string(),
+                                        LastToolCall {
+                                            tool_name: tool_name.clone(),
+                                            process_type: process_type.clone(),
+                                            started_at: chrono::Utc::now().to_rfc3339(),
+                                        },
+                                    );
+                                }
                                 // Accumulate tool call into live transcript for workers.
                                 if let ProcessId::Worker(worker_id) = process_id {
                                     let worker_key = worker_id.to_string();
@@ -946,13 +1044,28 @@ impl ApiState {
                                     .ok();
                             }
                             ProcessEvent::OpenCodePartUpdated {
-                                worker_id, part, ..
+                                worker_id,
+                                part,
+                                subagent,
+                                ..
                             } => {
                                 api_tx
                                     .send(ApiEvent::OpenCodePartUpdated {
                                         agent_id: agent_id.clone(),
                                         worker_id: worker_id.to_string(),
                                         part: part.clone(),
+                                        subagent: subagent.clone(),
+                                    })
+                                    .ok();
+                            }
+                            ProcessEvent::OpenCodeServerLog {
+                                worker_id, entry, ..
+                            } => {
+                                api_tx
+                                    .send(ApiEvent::OpenCodeServerLog {
+                                        agent_id: agent_id.clone(),
+                                        worker_id: worker_id.to_string(),
+                                        entry: entry.clone(),
                                     })
                                     .ok();
                             }
@@ -1233,6 +1346,18 @@ impl ApiState {
         *self.messaging_manager.write().await = Some(manager);
     }
 
+    /// External base URL for links generated while serving a request.
+    pub(super) fn external_base(
+        &self,
+        headers: &axum::http::HeaderMap,
+    ) -> super::public_url::ExternalBase {
+        super::public_url::ExternalBase::resolve(
+            self.public_url.as_deref(),
+            self.trust_forwarded_headers,
+            headers,
+        )
+    }
+
     /// Set the instance directory path.
     pub fn set_instance_dir(&self, dir: PathBuf) {
         self.instance_dir.store(Arc::new(dir));
@@ -36,6 +36,14 @@ pub(super) struct StatusResponse {
     uptime_seconds: u64,
 }
 
+#[derive(Serialize, utoipa::ToSchema)]
+pub(super) struct DeadLettersResponse {
+    /// Events dead-lettered since startup, including ones no longer retained.
+    total: u64,
+    /// Retained entries, oldest first.
+    dead_letters: Vec<crate::dead_letters::DeadLetter>,
+}
+
 #[utoipa::path(
     get,
     path = "/health",
@@ -94,6 +102,23 @@ pub(super) async fn status(State(state): State<Arc<ApiState>>) -> Json<StatusRes
     })
 }
 
+/// Recent SSE events from OpenCode and signal-cli that failed to parse.
+#[utoipa::path(
+    get,
+    path = "/debug/dead-letters",
+    responses(
+        (status = 200, body = DeadLettersResponse),
+    ),
+    tag = "system",
+)]
+pub(super) async fn dead_letters() -> Json<DeadLettersResponse> {
+    let queue = crate::dead_letters::DeadLetterQueue::global();
+    Json(DeadLettersResponse {
+        total: queue.total(),
+        dead_letters: queue.snapshot(),
+    })
+}
+
 /// SSE endpoint streaming all agent events to connected clients.
 #[utoipa::path(
     get,
@@ -113,30 +138,7 @@ pub(super) async fn events_sse(
             match rx.recv().await {
                 Ok(event) => {
                     if let Ok(json) = serde_json::to_string(&event) {
-                        let event_type = match &event {
-                            ApiEvent::InboundMessage { .. } => "inbound_message",
-                            ApiEvent::OutboundMessage { .. } => "outbound_message",
-                            ApiEvent::OutboundMessageDelta { .. } => "outbound_message_delta",
-                            ApiEvent::TypingState { .. } => "typing_state",
-                            ApiEvent::WorkerStarted { .. } => "worker_started",
-                            ApiEvent::WorkerStatusUpdate { .. } => "worker_status",
-                            ApiEvent::WorkerIdle { .. } => "worker_idle",
-                            ApiEvent::WorkerCompleted { .. } => "worker_completed",
-                            ApiEvent::BranchStarted { .. } => "branch_started",
-                            ApiEvent::BranchCompleted { .. } => "branch_completed",
-                            ApiEvent::ToolStarted { .. } => "tool_started",
-                            ApiEvent::ToolCompleted { .. } => "tool_completed",
-                            ApiEvent::ConfigReloaded => "config_reloaded",
-                            ApiEvent::AgentMessageSent { .. } => "agent_message_sent",
-                            ApiEvent::AgentMessageReceived { .. } => "agent_message_received",
-                            ApiEvent::TaskUpdated { .. } => "task_updated",
-                            ApiEvent::OpenCodePartUpdated { .. } => "opencode_part_updated",
-                            ApiEvent::WorkerText { .. } => "worker_text",
-                            ApiEvent::CortexChatMessage { .. } => "cortex_chat_message",
-                            ApiEvent::NotificationCreated { .. } => "notification_created",
-                            ApiEvent::NotificationUpdated { .. } => "notification_updated",
-                            ApiEvent::ToolOutput { .. } => "tool_output",
-                        };
+                        let event_type = event.event_type();
                         yield Ok(axum::response::sse::Event::default()
                             .event(event_type)
                             .data(json));
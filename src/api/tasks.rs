@@ -337,6 +337,7 @@ pub(super) async fn update_task(
                 clear_worker_id: false,
                 approved_by: request.approved_by,
                 complete_subtask: request.complete_subtask,
+                checkpoint: None,
             },
         )
         .await
@@ -0,0 +1,267 @@
+//! GitHub webhook receiver.
+//!
+//! GitHub can't send the API bearer token, so `/api/webhooks/github/*` skips
+//! the auth middleware and each delivery is verified against the agent's
+//! `[agents.github] secret` instead. Matching deliveries render their
+//! mapping's prompt template and run it once in a fresh cron channel, which
+//! posts the agent's reply to the mapping's `deliver_to`.
+
+use super::state::ApiState;
+use crate::config::GitHubEventMapping;
+use crate::notifications::webhook::hmac_sha256;
+
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub(super) struct GitHubWebhookResponse {
+    /// Number of mappings that started a run for this delivery.
+    started: usize,
+}
+
+/// Receive a GitHub webhook delivery for one agent.
+#[utoipa::path(
+    post,
+    path = "/webhooks/github/{agent_id}",
+    params(
+        ("agent_id" = String, Path, description = "Agent ID"),
+    ),
+    request_body(content = String, content_type = "application/json", description = "GitHub webhook payload"),
+    responses(
+        (status = 202, body = GitHubWebhookResponse),
+        (status = 400, description = "Missing event header or malformed payload"),
+        (status = 401, description = "Signature missing or invalid"),
+        (status = 404, description = "Agent not found or GitHub webhooks not configured"),
+        (status = 503, description = "Cron scheduler not available"),
+    ),
+    tag = "webhooks",
+)]
+pub(super) async fn github_webhook(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<GitHubWebhookResponse>), StatusCode> {
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(&agent_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let config = runtime_config.github.load();
+    let secret = config.secret.as_deref().ok_or(StatusCode::NOT_FOUND)?;
+
+    let signature = headers
+        .get("x-hub-signature-256")
+        .and_then(|value| value.to_str().ok());
+    if !verify_signature(secret, &body, signature) {
+        tracing::warn!(%agent_id, "rejected github webhook with an invalid signature");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let event = headers
+        .get("x-github-event")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let delivery_id = headers
+        .get("x-github-delivery")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let payload: Value = serde_json::from_slice(&body).map_err(|error| {
+        tracing::warn!(%error, %agent_id, event, "malformed github webhook payload");
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let matched: Vec<&GitHubEventMapping> = config
+        .mappings
+        .iter()
+        .filter(|mapping| mapping_matches(mapping, event, &payload))
+        .collect();
+    if matched.is_empty() {
+        tracing::debug!(%agent_id, event, "github webhook matched no mappings");
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(GitHubWebhookResponse { started: 0 }),
+        ));
+    }
+
+    let schedulers = state.cron_schedulers.load();
+    let scheduler = schedulers
+        .get(&agent_id)
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let mut started = 0;
+    for (index, mapping) in matched.into_iter().enumerate() {
+        let prompt = match render_prompt(mapping, event, &payload) {
+            Ok(prompt) => prompt,
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    %agent_id,
+                    mapping = %mapping.event,
+                    "failed to render github webhook prompt"
+                );
+                continue;
+            }
+        };
+        let run_id = format!("github-{event}-{delivery_id}-{index}");
+        tracing::info!(
+            %agent_id,
+            %run_id,
+            mapping = %mapping.event,
+            "github webhook starting run"
+        );
+        scheduler.dispatch_once(
+            run_id,
+            prompt,
+            mapping.deliver_to.clone(),
+            mapping.timeout_secs,
+        );
+        started += 1;
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(GitHubWebhookResponse { started }),
+    ))
+}
+
+/// Keys a delivery answers to: `<event>`, `<event>.<action>`, and for
+/// check and workflow payloads `<event>.<conclusion>`.
+fn event_keys(event: &str, payload: &Value) -> Vec<String> {
+    let mut keys = vec![event.to_string()];
+    if let Some(action) = payload.get("action").and_then(Value::as_str) {
+        keys.push(format!("{event}.{action}"));
+    }
+    if let Some(conclusion) = payload
+        .get(event)
+        .and_then(|object| object.get("conclusion"))
+        .and_then(Value::as_str)
+    {
+        keys.push(format!("{event}.{conclusion}"));
+    }
+    keys
+}
+
+fn mapping_matches(mapping: &GitHubEventMapping, event: &str, payload: &Value) -> bool {
+    if !event_keys(event, payload).contains(&mapping.event) {
+        return false;
+    }
+    match &mapping.repository {
+        Some(repository) => payload
+            .pointer("/repository/full_name")
+            .and_then(Value::as_str)
+            .is_some_and(|full_name| full_name.eq_ignore_ascii_case(repository)),
+        None => true,
+    }
+}
+
+/// Render the mapping's template with the payload's top-level fields plus
+/// `event` in scope.
+fn render_prompt(
+    mapping: &GitHubEventMapping,
+    event: &str,
+    payload: &Value,
+) -> Result<String, minijinja::Error> {
+    let mut context = payload.clone();
+    if let Some(object) = context.as_object_mut() {
+        object.insert("event".into(), Value::String(event.to_string()));
+    }
+    minijinja::Environment::new().render_str(&mapping.prompt, context)
+}
+
+/// Check a `sha256=<hex>` signature header against the body.
+fn verify_signature(secret: &str, body: &[u8], header: Option<&str>) -> bool {
+    let Some(expected) = header
+        .and_then(|value| value.strip_prefix("sha256="))
+        .and_then(|hex_digest| hex::decode(hex_digest).ok())
+    else {
+        return false;
+    };
+    let actual = hmac_sha256(secret.as_bytes(), body);
+    // Constant-time comparison so the signature can't be probed byte by byte.
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual.iter())
+            .fold(0u8, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(event: &str, repository: Option<&str>, prompt: &str) -> GitHubEventMapping {
+        GitHubEventMapping {
+            event: event.into(),
+            repository: repository.map(Into::into),
+            prompt: prompt.into(),
+            deliver_to: crate::messaging::target::parse_delivery_target("discord:123").unwrap(),
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn verifies_hmac_signatures() {
+        let body = br#"{"action":"opened"}"#;
+        let signature = format!("sha256={}", hex::encode(hmac_sha256(b"secret", body)));
+        assert!(verify_signature("secret", body, Some(&signature)));
+        assert!(!verify_signature("other", body, Some(&signature)));
+        assert!(!verify_signature("secret", body, None));
+        assert!(!verify_signature("secret", body, Some("sha1=abc")));
+    }
+
+    #[test]
+    fn matches_actions_conclusions_and_repositories() {
+        let failed_run = serde_json::json!({
+            "action": "completed",
+            "workflow_run": { "conclusion": "failure", "name": "CI" },
+            "repository": { "full_name": "acme/api" },
+        });
+        assert!(mapping_matches(
+            &mapping("workflow_run.failure", None, ""),
+            "workflow_run",
+            &failed_run
+        ));
+        assert!(mapping_matches(
+            &mapping("workflow_run.completed", Some("Acme/API"), ""),
+            "workflow_run",
+            &failed_run
+        ));
+        assert!(!mapping_matches(
+            &mapping("workflow_run.success", None, ""),
+            "workflow_run",
+            &failed_run
+        ));
+        assert!(!mapping_matches(
+            &mapping("workflow_run", Some("acme/web"), ""),
+            "workflow_run",
+            &failed_run
+        ));
+    }
+
+    #[test]
+    fn renders_prompt_from_payload() {
+        let payload = serde_json::json!({
+            "action": "opened",
+            "issue": { "number": 42, "title": "Crash on start" },
+            "repository": { "full_name": "acme/api" },
+        });
+        let prompt = render_prompt(
+            &mapping(
+                "issues.opened",
+                None,
+                "{{ event }}: triage {{ repository.full_name }}#{{ issue.number }} \"{{ issue.title }}\"",
+            ),
+            "issues",
+            &payload,
+        )
+        .unwrap();
+        assert_eq!(prompt, "issues: triage acme/api#42 \"Crash on start\"");
+    }
+}
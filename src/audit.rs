@@ -0,0 +1,390 @@
+//! Append-only audit log of prompts, tool executions, approvals, admin
+//! commands, and files changed by workers.
+
+use crate::{AgentId, BroadcastRecvResult, ProcessEvent, classify_broadcast_recv_result};
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use tokio::sync::broadcast;
+
+/// Maximum characters kept in an entry's summary. Full inputs go in `detail`.
+const SUMMARY_MAX_CHARS: usize = 300;
+
+/// Maximum characters kept in an entry's detail payload.
+const DETAIL_MAX_CHARS: usize = 8_000;
+
+/// What an audit entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    /// A user message delivered to a channel.
+    Prompt,
+    /// A tool call started by any process, with its arguments.
+    ToolCall,
+    /// A permission requested by a worker and how it was resolved.
+    Permission,
+    /// A privileged chat command such as `/model`.
+    Command,
+    /// A user or bot message deleted on the platform.
+    Deletion,
+    /// A file created, modified, or deleted by a coding worker.
+    FileChange,
+}
+
+impl AuditKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prompt => "prompt",
+            Self::ToolCall => "tool_call",
+            Self::Permission => "permission",
+            Self::Command => "command",
+            Self::Deletion => "deletion",
+            Self::FileChange => "file_change",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "prompt" => Some(Self::Prompt),
+            "tool_call" | "tool" => Some(Self::ToolCall),
+            "permission" => Some(Self::Permission),
+            "command" => Some(Self::Command),
+            "deletion" => Some(Self::Deletion),
+            "file_change" | "file" => Some(Self::FileChange),
+            _ => None,
+        }
+    }
+}
+
+/// A single audit log row.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct AuditEntry {
+    pub kind: AuditKind,
+    /// Who caused the entry: `{platform}:{sender_id}` for users, or the
+    /// process/policy name for automated actions.
+    pub actor: String,
+    pub channel_id: Option<String>,
+    pub summary: String,
+    pub detail: Option<String>,
+    /// Empty until read back from the database.
+    pub recorded_at: String,
+}
+
+impl AuditEntry {
+    pub fn new(kind: AuditKind, actor: impl Into<String>, summary: impl AsRef<str>) -> Self {
+        Self {
+            kind,
+            actor: actor.into(),
+            channel_id: None,
+            summary: truncate(summary.as_ref(), SUMMARY_MAX_CHARS),
+            detail: None,
+            recorded_at: String::new(),
+        }
+    }
+
+    pub fn with_channel(mut self, channel_id: impl Into<String>) -> Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl AsRef<str>) -> Self {
+        self.detail = Some(truncate(detail.as_ref(), DETAIL_MAX_CHARS));
+        self
+    }
+}
+
+/// Writer and reader for the `audit_log` table. There is intentionally no
+/// update or delete path.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pool: SqlitePool,
+}
+
+impl AuditLog {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Append an entry without blocking the caller (fire-and-forget).
+    pub fn log(&self, agent_id: &str, entry: AuditEntry) {
+        let log = self.clone();
+        let agent_id = agent_id.to_string();
+        tokio::spawn(async move {
+            if let Err(error) = log.append(&agent_id, &entry).await {
+                tracing::warn!(%error, kind = entry.kind.as_str(), "failed to write audit entry");
+            }
+        });
+    }
+
+    /// Append an entry and wait for the write.
+    pub async fn append(&self, agent_id: &str, entry: &AuditEntry) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO audit_log (agent_id, kind, actor, channel_id, summary, detail) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(agent_id)
+        .bind(entry.kind.as_str())
+        .bind(&entry.actor)
+        .bind(&entry.channel_id)
+        .bind(&entry.summary)
+        .bind(&entry.detail)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(())
+    }
+
+    /// Most recent entries first, optionally filtered by kind.
+    pub async fn recent(
+        &self,
+        agent_id: &str,
+        kind: Option<AuditKind>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<AuditEntry>> {
+        let rows = sqlx::query(
+            "SELECT kind, actor, channel_id, summary, detail, recorded_at FROM audit_log \
+             WHERE agent_id = ? AND (? IS NULL OR kind = ?) \
+             ORDER BY id DESC LIMIT ?",
+        )
+        .bind(agent_id)
+        .bind(kind.map(AuditKind::as_str))
+        .bind(kind.map(AuditKind::as_str))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let kind: String = row.try_get("kind").ok()?;
+                Some(AuditEntry {
+                    kind: AuditKind::parse(&kind)?,
+                    actor: row.try_get("actor").unwrap_or_default(),
+                    channel_id: row.try_get("channel_id").ok().flatten(),
+                    summary: row.try_get("summary").unwrap_or_default(),
+                    detail: row.try_get("detail").ok().flatten(),
+                    recorded_at: row.try_get("recorded_at").unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
+    /// Estimated spend recorded in `token_usage` over the trailing window.
+    /// Costs live in the usage table already; the audit view reads them
+    /// rather than duplicating every LLM call here.
+    pub async fn cost_since_hours(&self, agent_id: &str, hours: i64) -> crate::error::Result<f64> {
+        let row = sqlx::query(
+            "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) AS total FROM token_usage \
+             WHERE agent_id = ? AND recorded_at >= strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?)",
+        )
+        .bind(agent_id)
+        .bind(format!("-{hours} hours"))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(row.try_get("total").unwrap_or(0.0))
+    }
+}
+
+/// Record tool calls, permission decisions, and changed files from an
+/// agent's event bus.
+pub fn spawn_audit_recorder(
+    agent_id: AgentId,
+    mut event_rx: broadcast::Receiver<ProcessEvent>,
+    pool: SqlitePool,
+) -> tokio::task::JoinHandle<()> {
+    let log = AuditLog::new(pool);
+    tokio::spawn(async move {
+        loop {
+            match classify_broadcast_recv_result(event_rx.recv().await) {
+                BroadcastRecvResult::Event(event) => {
+                    for entry in entries_for_event(&agent_id, &event) {
+                        if let Err(error) = log.append(&agent_id, &entry).await {
+                            tracing::warn!(%error, %agent_id, "failed to write audit entry");
+                        }
+                    }
+                }
+                BroadcastRecvResult::Lagged(count) => {
+                    tracing::warn!(
+                        %agent_id,
+                        skipped = count,
+                        "audit recorder lagged, events were not recorded"
+                    );
+                }
+                BroadcastRecvResult::Closed => break,
+            }
+        }
+    })
+}
+
+fn entries_for_event(agent_id: &AgentId, event: &ProcessEvent) -> Vec<AuditEntry> {
+    let in_channel = |entry: AuditEntry, channel_id: &Option<crate::ChannelId>| match channel_id {
+        Some(channel_id) => entry.with_channel(channel_id.to_string()),
+        None => entry,
+    };
+    match event {
+        ProcessEvent::ToolStarted {
+            agent_id: event_agent_id,
+            process_id,
+            channel_id,
+            tool_name,
+            args,
+            ..
+        } if event_agent_id == agent_id => {
+            let entry = AuditEntry::new(AuditKind::ToolCall, process_id.to_string(), tool_name)
+                .with_detail(args);
+            vec![in_channel(entry, channel_id)]
+        }
+        ProcessEvent::WorkerPermission {
+            agent_id: event_agent_id,
+            worker_id,
+            channel_id,
+            description,
+            awaiting_approval,
+            ..
+        } if event_agent_id == agent_id => {
+            // Requests nobody is asked about are allowed by the worker's
+            // policy. The rest are logged again with the admin's decision.
+            let entry = if *awaiting_approval {
+                AuditEntry::new(
+                    AuditKind::Permission,
+                    crate::ProcessId::Worker(*worker_id).to_string(),
+                    format!("worker {worker_id} asked: {description}"),
+                )
+            } else {
+                AuditEntry::new(
+                    AuditKind::Permission,
+                    "policy:auto-approve",
+                    format!("worker {worker_id} allowed once: {description}"),
+                )
+            };
+            vec![in_channel(entry, channel_id)]
+        }
+        // One row per file, path in the summary, so the weekly digest can
+        // count distinct files with a plain GROUP BY.
+        ProcessEvent::WorkerFilesChanged {
+            agent_id: event_agent_id,
+            worker_id,
+            channel_id,
+            files,
+            ..
+        } if event_agent_id == agent_id => files
+            .iter()
+            .map(|file| {
+                let entry = AuditEntry::new(
+                    AuditKind::FileChange,
+                    crate::ProcessId::Worker(*worker_id).to_string(),
+                    &file.path,
+                )
+                .with_detail(file.kind.as_str());
+                in_channel(entry, channel_id)
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    match value.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &value[..index]),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn tool_started_events_become_tool_call_entries() {
+        let agent_id: AgentId = Arc::from("main");
+        let event = ProcessEvent::ToolStarted {
+            agent_id: agent_id.clone(),
+            process_id: crate::ProcessId::Channel(Arc::from("discord:1")),
+            channel_id: Some(Arc::from("discord:1")),
+            call_id: "call-1".into(),
+            tool_name: "shell".into(),
+            args: r#"{"command":"ls"}"#.into(),
+        };
+
+        let entry = entries_for_event(&agent_id, &event)
+            .pop()
+            .expect("entry expected");
+        assert_eq!(entry.kind, AuditKind::ToolCall);
+        assert_eq!(entry.summary, "shell");
+        assert_eq!(entry.channel_id.as_deref(), Some("discord:1"));
+        assert!(entry.detail.unwrap_or_default().contains("ls"));
+    }
+
+    #[test]
+    fn events_from_other_agents_are_ignored() {
+        let event = ProcessEvent::ToolStarted {
+            agent_id: Arc::from("other"),
+            process_id: crate::ProcessId::Channel(Arc::from("discord:1")),
+            channel_id: None,
+            call_id: "call-1".into(),
+            tool_name: "shell".into(),
+            args: String::new(),
+        };
+        assert!(entries_for_event(&Arc::from("main"), &event).is_empty());
+    }
+
+    #[test]
+    fn changed_files_become_one_entry_each() {
+        use crate::opencode::types::{FileChange, FileChangeKind};
+
+        let agent_id: AgentId = Arc::from("main");
+        let event = ProcessEvent::WorkerFilesChanged {
+            agent_id: agent_id.clone(),
+            worker_id: uuid::Uuid::new_v4(),
+            channel_id: Some(Arc::from("discord:1")),
+            files: vec![
+                FileChange::new("src/main.rs", FileChangeKind::Modified),
+                FileChange::new("src/new.rs", FileChangeKind::Created),
+            ],
+            session_total: 2,
+        };
+
+        let entries = entries_for_event(&agent_id, &event);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, AuditKind::FileChange);
+        assert_eq!(entries[0].summary, "src/main.rs");
+        assert_eq!(entries[1].detail.as_deref(), Some("created"));
+        assert!(entries[1].actor.starts_with("worker:"));
+    }
+
+    #[test]
+    fn permission_requests_name_who_allowed_them() {
+        let agent_id: AgentId = Arc::from("main");
+        let permission = |awaiting_approval| ProcessEvent::WorkerPermission {
+            agent_id: agent_id.clone(),
+            worker_id: uuid::Uuid::new_v4(),
+            channel_id: Some(Arc::from("discord:1")),
+            session_id: "ses-1".into(),
+            permission_id: "per-1".into(),
+            description: "bash: rm -rf target".into(),
+            patterns: vec!["rm -rf target".into()],
+            awaiting_approval,
+        };
+
+        let allowed = entries_for_event(&agent_id, &permission(false)).remove(0);
+        assert_eq!(allowed.actor, "policy:auto-approve");
+        assert!(allowed.summary.contains("allowed once"));
+
+        let asked = entries_for_event(&agent_id, &permission(true)).remove(0);
+        assert_eq!(asked.kind, AuditKind::Permission);
+        assert!(asked.actor.starts_with("worker:"));
+        assert!(asked.summary.contains("asked"));
+    }
+
+    #[test]
+    fn summaries_are_truncated() {
+        let entry = AuditEntry::new(AuditKind::Prompt, "discord:1", "x".repeat(1_000));
+        assert_eq!(entry.summary.chars().count(), SUMMARY_MAX_CHARS + 1);
+    }
+}
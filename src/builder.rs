@@ -0,0 +1,241 @@
+//! Library entry point for running spacebot inside another process.
+//!
+//! [`SpacebotBuilder`] collects what the `spacebot` binary otherwise wires up
+//! from config.toml and the CLI — config, the secrets store, OpenCode
+//! backends, messaging bridges, plugins, and worker policies — then starts
+//! the same runtime with [`SpacebotBuilder::run`].
+//!
+//! ```no_run
+//! # async fn example(bridge: impl spacebot::messaging::Messaging) -> anyhow::Result<()> {
+//! spacebot::SpacebotBuilder::new()
+//!     .instance_dir("/var/lib/my-service/spacebot")
+//!     .bridge(bridge)
+//!     .shutdown_signal(async {
+//!         tokio::signal::ctrl_c().await.ok();
+//!     })
+//!     .run()
+//!     .await
+//! # }
+//! ```
+//!
+//! Backends and policies registered here are applied on top of every config
+//! the runtime loads, including reloads after config.toml changes.
+
+use crate::config::{Config, ConfigOverrides, OpenCodeBackendConfig};
+use crate::db::Migrations;
+use crate::messaging::Messaging;
+use crate::messaging::traits::MessagingDyn;
+use crate::opencode::{OpenCodePermissions, ToolOutputRenderer, ToolOutputRenderers};
+use crate::plugins::{Plugin, PluginDyn, PluginRegistry};
+use crate::runtime::RunOptions;
+use crate::secrets::store::SecretsStore;
+
+use anyhow::Context as _;
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Where the builder gets its config from.
+enum ConfigSource {
+    /// `~/.spacebot`, or `SPACEBOT_DIR`, as the binary does.
+    Default,
+    InstanceDir(PathBuf),
+    Path(PathBuf),
+    Loaded(Box<Config>),
+}
+
+/// Configures and runs an embedded spacebot instance.
+pub struct SpacebotBuilder {
+    config: ConfigSource,
+    secrets_store: Option<Arc<SecretsStore>>,
+    overrides: ConfigOverrides,
+    bridges: Vec<Arc<dyn MessagingDyn>>,
+    tool_renderers: Vec<(String, Arc<dyn ToolOutputRenderer>)>,
+    plugins: Vec<Arc<dyn PluginDyn>>,
+    migrations: Migrations,
+    shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl SpacebotBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: ConfigSource::Default,
+            secrets_store: None,
+            overrides: ConfigOverrides::default(),
+            bridges: Vec::new(),
+            tool_renderers: Vec::new(),
+            plugins: Vec::new(),
+            migrations: Migrations::Run,
+            shutdown: None,
+        }
+    }
+
+    /// Load config from `<instance_dir>/config.toml`, or from the
+    /// environment when that file doesn't exist.
+    pub fn instance_dir(mut self, instance_dir: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSource::InstanceDir(instance_dir.into());
+        self
+    }
+
+    /// Load config from a specific TOML file.
+    pub fn config_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSource::Path(path.into());
+        self
+    }
+
+    /// Start from an already built config. Reloads still read
+    /// `<instance_dir>/config.toml`.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = ConfigSource::Loaded(Box::new(config));
+        self
+    }
+
+    /// Secrets store used to resolve `secret:` references and handed to
+    /// agents. Must already be unlocked if it is encrypted.
+    pub fn secrets_store(mut self, store: Arc<SecretsStore>) -> Self {
+        self.secrets_store = Some(store);
+        self
+    }
+
+    /// Add a remote OpenCode backend, replacing any configured backend with
+    /// the same name.
+    pub fn opencode_backend(mut self, backend: OpenCodeBackendConfig) -> Self {
+        self.overrides.opencode_backends.push(backend);
+        self
+    }
+
+    /// Permission policy for OpenCode workers, replacing
+    /// `defaults.opencode.permissions`.
+    pub fn opencode_permissions(mut self, permissions: OpenCodePermissions) -> Self {
+        self.overrides.opencode_permissions = Some(permissions);
+        self
+    }
+
+    /// Register a messaging adapter next to the configured ones. Bindings
+    /// route to it by its [`Messaging::name`].
+    pub fn bridge(self, adapter: impl Messaging) -> Self {
+        self.bridge_shared(Arc::new(adapter))
+    }
+
+    /// Like [`bridge`](Self::bridge), for an adapter the caller keeps a
+    /// handle to.
+    pub fn bridge_shared(mut self, adapter: Arc<dyn MessagingDyn>) -> Self {
+        self.bridges.push(adapter);
+        self
+    }
+
+    /// Render `tool`'s output in worker transcripts with `renderer`.
+    pub fn tool_renderer(
+        mut self,
+        tool: impl Into<String>,
+        renderer: impl ToolOutputRenderer + 'static,
+    ) -> Self {
+        self.tool_renderers.push((tool.into(), Arc::new(renderer)));
+        self
+    }
+
+    /// Add chat commands and event hooks from `plugin`.
+    pub fn plugin(mut self, plugin: impl Plugin) -> Self {
+        self.plugins.push(Arc::new(plugin));
+        self
+    }
+
+    /// Open the databases without applying pending migrations, for hosts
+    /// that migrate them out of band.
+    pub fn skip_migrations(mut self) -> Self {
+        self.migrations = Migrations::Skip;
+        self
+    }
+
+    /// Shut down once `signal` resolves, in addition to IPC stop requests
+    /// and Ctrl-C.
+    pub fn shutdown_signal(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown = Some(Box::pin(signal));
+        self
+    }
+
+    /// Load config and run until shutdown.
+    ///
+    /// Tracing is left to the caller. Config overrides, the secrets store
+    /// used for `secret:` references, tool renderers, and plugins are
+    /// process-wide, so run one instance per process.
+    pub async fn run(self) -> anyhow::Result<()> {
+        if let Some(store) = &self.secrets_store {
+            crate::config::set_resolve_secrets_store(store.clone());
+        }
+        crate::config::set_config_overrides(self.overrides.clone());
+        let config = load_config(self.config, &self.overrides)?;
+
+        for (tool, renderer) in self.tool_renderers {
+            ToolOutputRenderers::global().register_shared(tool, renderer);
+        }
+        for plugin in self.plugins {
+            PluginRegistry::global()
+                .register_shared(plugin)
+                .context("failed to register plugin")?;
+        }
+
+        crate::runtime::run(
+            config,
+            RunOptions {
+                foreground: false,
+                otel_provider: None,
+                secrets_store: self.secrets_store,
+                bridges: self.bridges,
+                migrations: self.migrations,
+                shutdown: self.shutdown,
+            },
+        )
+        .await
+    }
+}
+
+impl Default for SpacebotBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn load_config(source: ConfigSource, overrides: &ConfigOverrides) -> anyhow::Result<Config> {
+    match source {
+        ConfigSource::Default => Config::load().context("failed to load configuration"),
+        ConfigSource::InstanceDir(instance_dir) => Config::load_for_instance(&instance_dir)
+            .with_context(|| {
+                format!(
+                    "failed to load configuration for {}",
+                    instance_dir.display()
+                )
+            }),
+        ConfigSource::Path(path) => Config::load_from_path(&path)
+            .with_context(|| format!("failed to load config from {}", path.display())),
+        ConfigSource::Loaded(mut config) => {
+            overrides.apply(&mut config);
+            Ok(*config)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaded_config_gets_the_builder_overrides() {
+        let directory = tempfile::tempdir().unwrap();
+        let config = Config::load_from_env(directory.path()).unwrap();
+        let builder =
+            SpacebotBuilder::new()
+                .config(config)
+                .opencode_permissions(OpenCodePermissions {
+                    edit: "deny".into(),
+                    bash: "ask".into(),
+                    webfetch: "deny".into(),
+                });
+
+        let config = load_config(builder.config, &builder.overrides).unwrap();
+        assert_eq!(config.defaults.opencode.permissions.bash, "ask");
+        assert_eq!(config.instance_dir, directory.path());
+    }
+}
@@ -0,0 +1,212 @@
+//! Session ownership across replicas (`[cluster]`).
+//!
+//! Several replicas can run behind the same bot tokens, so each of them sees
+//! every inbound message. A session (one agent's channel for one
+//! conversation) is handled by exactly one of them: the replica holding its
+//! lease in the shared Postgres database. The first replica to see a message
+//! for an unowned session takes the lease and keeps it while it lives,
+//! renewing every third of the TTL. The others drop that session's messages.
+//!
+//! A replica that shuts down cleanly releases its leases, so the next message
+//! moves to a live replica at once. One that dies stops renewing; its leases
+//! expire after `lease_ttl_secs` and the next replica to see a message for
+//! the session takes it over. Leases for sessions that stay quiet for
+//! [`IDLE_RELEASE`] are released too, so ownership doesn't pile up on the
+//! longest-running replica.
+
+use crate::config::ClusterConfig;
+
+use sqlx::PgPool;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A lease for a session that has had no messages for this long is
+/// released at the next renewal.
+pub const IDLE_RELEASE: Duration = Duration::from_secs(60 * 60);
+
+/// The lease key for an agent's session in a conversation.
+pub fn session_key(agent_id: &str, conversation_id: &str) -> String {
+    format!("{agent_id}:{conversation_id}")
+}
+
+#[derive(Debug, Default)]
+struct Owned {
+    /// Sessions this replica holds, with when each last had a message.
+    sessions: HashMap<String, Instant>,
+    /// When the database last confirmed this replica's leases.
+    confirmed_at: Option<Instant>,
+}
+
+/// This replica's view of the lease table.
+#[derive(Debug)]
+pub struct SessionLeases {
+    pool: PgPool,
+    replica_id: String,
+    ttl: Duration,
+    owned: Mutex<Owned>,
+}
+
+impl SessionLeases {
+    pub fn new(pool: PgPool, config: &ClusterConfig) -> Self {
+        Self {
+            pool,
+            replica_id: config.replica_id.clone(),
+            ttl: Duration::from_secs(config.lease_ttl_secs),
+            owned: Mutex::new(Owned::default()),
+        }
+    }
+
+    pub fn replica_id(&self) -> &str {
+        &self.replica_id
+    }
+
+    /// Whether this replica handles `key`, taking its lease if it is free
+    /// or expired. A session this replica already holds is answered from
+    /// memory while renewals are succeeding. When the database can't be
+    /// reached, only those sessions are handled.
+    pub async fn claim(&self, key: &str) -> bool {
+        {
+            let mut owned = self.lock();
+            let fresh = owned
+                .confirmed_at
+                .is_some_and(|confirmed| confirmed.elapsed() < self.ttl);
+            if fresh && let Some(last_message) = owned.sessions.get_mut(key) {
+                *last_message = Instant::now();
+                return true;
+            }
+        }
+
+        let acquired = sqlx::query_scalar::<_, String>(
+            "INSERT INTO session_leases (session_key, owner, expires_at) \
+             VALUES ($1, $2, now() + make_interval(secs => $3)) \
+             ON CONFLICT (session_key) DO UPDATE \
+             SET owner = EXCLUDED.owner, expires_at = EXCLUDED.expires_at \
+             WHERE session_leases.owner = EXCLUDED.owner \
+             OR session_leases.expires_at < now() \
+             RETURNING owner",
+        )
+        .bind(key)
+        .bind(&self.replica_id)
+        .bind(self.ttl.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await;
+        match acquired {
+            Ok(Some(_)) => {
+                let mut owned = self.lock();
+                if owned
+                    .sessions
+                    .insert(key.to_string(), Instant::now())
+                    .is_none()
+                {
+                    tracing::info!(session = %key, replica = %self.replica_id, "took session lease");
+                }
+                owned.confirmed_at.get_or_insert_with(Instant::now);
+                true
+            }
+            Ok(None) => {
+                self.lock().sessions.remove(key);
+                false
+            }
+            Err(error) => {
+                tracing::warn!(%error, session = %key, "failed to claim session lease");
+                false
+            }
+        }
+    }
+
+    /// Extend every lease this replica holds, release idle ones, and forget
+    /// any another replica took after they expired.
+    pub async fn renew(&self) {
+        let (active, idle): (Vec<String>, Vec<String>) = {
+            let owned = self.lock();
+            let (active, idle): (Vec<_>, Vec<_>) = owned
+                .sessions
+                .iter()
+                .partition(|(_, last_message)| last_message.elapsed() < IDLE_RELEASE);
+            (
+                active.into_iter().map(|(key, _)| key.clone()).collect(),
+                idle.into_iter().map(|(key, _)| key.clone()).collect(),
+            )
+        };
+
+        if !idle.is_empty() {
+            self.release(&idle).await;
+        }
+
+        let renewed = sqlx::query_scalar::<_, String>(
+            "UPDATE session_leases SET expires_at = now() + make_interval(secs => $2) \
+             WHERE owner = $1 AND session_key = ANY($3) \
+             RETURNING session_key",
+        )
+        .bind(&self.replica_id)
+        .bind(self.ttl.as_secs_f64())
+        .bind(&active)
+        .fetch_all(&self.pool)
+        .await;
+        let renewed = match renewed {
+            Ok(renewed) => renewed,
+            Err(error) => {
+                tracing::warn!(%error, "failed to renew session leases");
+                return;
+            }
+        };
+
+        let mut owned = self.lock();
+        owned.confirmed_at = Some(Instant::now());
+        for key in active.iter().filter(|key| !renewed.contains(key)) {
+            tracing::warn!(session = %key, "session lease lost to another replica");
+            owned.sessions.remove(key);
+        }
+    }
+
+    async fn release(&self, keys: &[String]) {
+        let released =
+            sqlx::query("DELETE FROM session_leases WHERE owner = $1 AND session_key = ANY($2)")
+                .bind(&self.replica_id)
+                .bind(keys)
+                .execute(&self.pool)
+                .await;
+        match released {
+            Ok(_) => {
+                let mut owned = self.lock();
+                for key in keys {
+                    owned.sessions.remove(key);
+                }
+            }
+            Err(error) => tracing::warn!(%error, "failed to release idle session leases"),
+        }
+    }
+
+    /// Release every lease this replica holds, on shutdown.
+    pub async fn release_all(&self) {
+        if let Err(error) = sqlx::query("DELETE FROM session_leases WHERE owner = $1")
+            .bind(&self.replica_id)
+            .execute(&self.pool)
+            .await
+        {
+            tracing::warn!(%error, "failed to release session leases");
+        }
+        self.lock().sessions.clear();
+    }
+
+    /// Renew leases in the background for as long as the returned task runs.
+    pub fn spawn_renewal(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let leases = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(leases.ttl / 3);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                leases.renew().await;
+            }
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Owned> {
+        self.owned
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
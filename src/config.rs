@@ -12,7 +12,7 @@ mod watcher;
 // Re-export all public types from submodules so external consumers
 // continue to use `crate::config::TypeName` unchanged.
 pub(crate) use load::resolve_env_value;
-pub use load::set_resolve_secrets_store;
+pub use load::{set_config_overrides, set_resolve_secrets_store};
 pub use onboarding::run_onboarding;
 pub use permissions::{
     DiscordPermissions, MattermostPermissions, SignalPermissions, SlackPermissions,
@@ -1637,6 +1637,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![
             Binding {
@@ -1687,6 +1691,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -1749,12 +1757,18 @@ id = "main"
                 allowed_senders: vec![],
                 max_body_bytes: 1_000_000,
                 max_attachment_bytes: 10_000_000,
+                prompt_addresses: vec![],
+                webhook: None,
                 instances: vec![],
             }),
             webhook: None,
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -1798,6 +1812,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         // Binding targets default adapter, but no default credentials exist
         let bindings = vec![Binding {
@@ -1842,6 +1860,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![
             // Valid: default adapter with credentials
@@ -1923,6 +1945,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -1956,6 +1982,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -1998,6 +2028,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -2036,6 +2070,10 @@ id = "main"
             twitch: None,
             signal: None,
             mattermost: None,
+            irc: None,
+            redaction: RedactionConfig::default(),
+            overflow: OverflowConfig::default(),
+            links: LinksConfig::default(),
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -2256,4 +2294,272 @@ tool_use_enforcement = ["gemini", "deepseek"]
                 .should_inject("anthropic/claude-sonnet-4")
         );
     }
+
+    #[test]
+    fn opencode_backends_parse_auth_and_tls() {
+        let toml = r#"
+[defaults.opencode]
+enabled = true
+
+[[defaults.opencode.backends]]
+name = "shared"
+url = "https://opencode.internal.example.com/"
+directory = "/srv/repos/app"
+bearer_token = "token-123"
+client_cert = "/etc/spacebot/client.pem"
+client_key = "/etc/spacebot/client.key"
+headers = { "X-Forwarded-User" = "spacebot" }
+
+[[agents]]
+id = "main"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        let backend = &config.defaults.opencode.backends[0];
+        assert_eq!(backend.url, "https://opencode.internal.example.com");
+        assert_eq!(backend.directory, PathBuf::from("/srv/repos/app"));
+        assert_eq!(
+            backend.auth,
+            OpenCodeBackendAuth::Bearer {
+                token: "token-123".to_string()
+            }
+        );
+        assert_eq!(
+            backend.headers,
+            vec![("X-Forwarded-User".to_string(), "spacebot".to_string())]
+        );
+        assert!(!format!("{backend:?}").contains("token-123"));
+    }
+
+    #[test]
+    fn config_overrides_replace_file_backends_by_name() {
+        let toml = r#"
+[[defaults.opencode.backends]]
+name = "shared"
+url = "https://opencode.internal.example.com"
+directory = "/srv/repos/app"
+
+[[agents]]
+id = "main"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let mut config =
+            Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let overrides = ConfigOverrides {
+            opencode_backends: vec![OpenCodeBackendConfig {
+                name: "shared".into(),
+                url: "http://127.0.0.1:4096".into(),
+                directory: PathBuf::from("/tmp/app"),
+                auth: OpenCodeBackendAuth::None,
+                headers: Vec::new(),
+                client_cert: None,
+                client_key: None,
+                ca_cert: None,
+                mirror: false,
+            }],
+            opencode_permissions: Some(crate::opencode::OpenCodePermissions {
+                edit: "deny".into(),
+                bash: "deny".into(),
+                webfetch: "deny".into(),
+            }),
+        };
+        overrides.apply(&mut config);
+
+        let opencode = &config.defaults.opencode;
+        assert_eq!(opencode.backends.len(), 1);
+        assert_eq!(opencode.backends[0].url, "http://127.0.0.1:4096");
+        assert_eq!(opencode.permissions.bash, "deny");
+    }
+
+    #[test]
+    fn tenant_messages_only_reach_the_tenant_agent() {
+        let toml = r#"
+[[agents]]
+id = "main"
+default = true
+
+[[agents]]
+id = "acme"
+
+[[tenants]]
+id = "acme"
+discord_guild_ids = ["42"]
+daily_message_budget = 500
+
+[[bindings]]
+agent_id = "main"
+channel = "discord"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        assert_eq!(config.tenants.len(), 1);
+        assert_eq!(config.tenants[0].agent_id, "acme");
+
+        let mut message = test_inbound_message("discord", None);
+        message
+            .metadata
+            .insert("discord_guild_id".into(), serde_json::json!(42));
+        let tenant = resolve_tenant(&config.tenants, &message).expect("guild 42 is acme's");
+        let (agent_id, _) = resolve_agent_for_tenant(tenant, &config.bindings, &message).unwrap();
+        assert_eq!(agent_id.as_ref(), "acme");
+
+        message
+            .metadata
+            .insert("discord_guild_id".into(), serde_json::json!(7));
+        assert!(resolve_tenant(&config.tenants, &message).is_none());
+    }
+
+    #[test]
+    fn tenants_reject_the_default_agent() {
+        let toml = r#"
+[[agents]]
+id = "main"
+
+[[tenants]]
+id = "acme"
+agent_id = "main"
+slack_workspace_ids = ["T123"]
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let error = Config::from_toml(parsed, PathBuf::from(".")).unwrap_err();
+        assert!(error.to_string().contains("cannot use the default agent"));
+    }
+
+    #[test]
+    fn opencode_backend_rejects_conflicting_auth() {
+        let toml = r#"
+[[defaults.opencode.backends]]
+name = "shared"
+url = "https://opencode.internal.example.com"
+directory = "/srv/repos/app"
+bearer_token = "token-123"
+basic_username = "spacebot"
+
+[[agents]]
+id = "main"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let error = Config::from_toml(parsed, PathBuf::from("."))
+            .expect_err("conflicting auth should be rejected");
+        assert!(
+            error
+                .to_string()
+                .contains("bearer_token and basic_username")
+        );
+    }
+
+    #[test]
+    fn human_access_tiers_parse_and_validate() {
+        let toml = r#"
+[[humans]]
+id = "jamie"
+discord_id = "42"
+access = "read-only"
+
+[[agents]]
+id = "main"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build config");
+        assert_eq!(
+            config.humans[0].access,
+            Some(crate::access::AccessTier::ReadOnly)
+        );
+
+        let invalid = toml.replace("read-only", "owner");
+        let parsed: TomlConfig = toml::from_str(&invalid).expect("failed to parse test TOML");
+        let error = Config::from_toml(parsed, PathBuf::from("."))
+            .expect_err("unknown access tier should be rejected");
+        assert!(error.to_string().contains("invalid access tier"));
+    }
+
+    #[test]
+    fn abort_on_prompt_deleted_inherits_from_defaults() {
+        let toml = r#"
+[defaults.channel]
+abort_on_prompt_deleted = true
+
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "relaxed"
+[agents.channel]
+abort_on_prompt_deleted = false
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build config");
+        assert!(config.defaults.channel.abort_on_prompt_deleted);
+        let relaxed = config
+            .agents
+            .iter()
+            .find(|agent| agent.id == "relaxed")
+            .expect("agent should exist");
+        assert_eq!(
+            relaxed
+                .channel
+                .as_ref()
+                .map(|channel| channel.abort_on_prompt_deleted),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn cluster_mode_requires_a_shared_database() {
+        let toml = r#"
+[cluster]
+enabled = true
+replica_id = "replica-a"
+
+[[agents]]
+id = "main"
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let error = Config::from_toml(parsed, PathBuf::from("."))
+            .expect_err("cluster mode without postgres should be rejected");
+        assert!(error.to_string().contains("cluster.enabled requires"));
+
+        let shared = format!(
+            "[database]\nbackend = \"postgres\"\nurl = \"postgres://spacebot@db/spacebot\"\n{toml}"
+        );
+        let parsed: TomlConfig = toml::from_str(&shared).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build config");
+        assert!(config.cluster.enabled);
+        assert_eq!(config.cluster.replica_id, "replica-a");
+        assert_eq!(config.cluster.lease_ttl_secs, 30);
+        assert!(matches!(config.database, DatabaseConfig::Postgres(_)));
+    }
+
+    #[test]
+    fn event_bus_accepts_redis_urls() {
+        let parse = |event_bus: &str| {
+            let toml = format!("[event_bus]\n{event_bus}\n\n[[agents]]\nid = \"main\"\n");
+            let parsed: TomlConfig = toml::from_str(&toml).expect("failed to parse test TOML");
+            Config::from_toml(parsed, PathBuf::from("."))
+        };
+
+        let config = parse("backend = \"redis\"\nurl = \"redis://:hunter2@cache:6380\"")
+            .expect("failed to build config");
+        let EventBusConfig::Redis(redis) = config.event_bus else {
+            panic!("expected a redis event bus");
+        };
+        assert_eq!(redis.channel, "spacebot:events");
+        assert!(!format!("{redis:?}").contains("hunter2"));
+
+        assert!(parse("backend = \"redis\"").is_err());
+        assert!(parse("backend = \"redis\"\nurl = \"rediss://cache:6380/2\"").is_ok());
+        assert!(parse("backend = \"redis\"\nurl = \"redis://cache/events\"").is_err());
+        assert!(parse("backend = \"redis\"\nurl = \"http://cache:6380\"").is_err());
+        assert!(parse("backend = \"kafka\"").is_err());
+        assert_eq!(parse("").unwrap().event_bus, EventBusConfig::Local);
+    }
 }
@@ -11,15 +11,21 @@ use super::providers::{
 };
 use super::toml_schema::*;
 use super::{
-    AgentConfig, ApiConfig, ApiType, Binding, BrowserConfig, ChannelConfig, ClosePolicy,
-    CoalesceConfig, CompactionConfig, Config, CortexConfig, CronDef, DefaultsConfig, DiscordConfig,
-    DiscordInstanceConfig, EmailConfig, EmailInstanceConfig, GroupDef, HumanDef, IngestionConfig,
-    LinkDef, LlmConfig, MattermostConfig, MattermostInstanceConfig, McpServerConfig, McpTransport,
-    MemoryJanitorConfig, MemoryPersistenceConfig, MessagingConfig, MetricsConfig, OpenCodeConfig,
-    ParticipantContextConfig, ProjectsConfig, ProviderConfig, SignalConfig, SignalInstanceConfig,
+    AgentConfig, ApiConfig, ApiType, Binding, BlobStoreConfig, BrowserConfig, ChannelConfig,
+    ClosePolicy, ClusterConfig, CoalesceConfig, CompactionConfig, Config, ConfigOverrides,
+    ContentFilterAction, ContentFilterRule, CortexConfig, CostReportConfig, CronDef,
+    DatabaseConfig, DeadLettersConfig, DefaultsConfig, DigestConfig, DiscordConfig,
+    DiscordInstanceConfig, EmailConfig, EmailInstanceConfig, EmailWebhookConfig, EventBusConfig,
+    GroupDef, HumanDef, IngestionConfig, IrcConfig, LinkDef, LinksConfig, LlmConfig,
+    MattermostConfig, MattermostInstanceConfig, McpServerConfig, McpTransport, MemoryJanitorConfig,
+    MemoryPersistenceConfig, MessagingConfig, MetricsConfig, NotifyConfig, OpenCodeBackendAuth,
+    OpenCodeBackendConfig, OpenCodeConfig, OverflowConfig, OverflowMode, ParticipantContextConfig,
+    PostgresConfig, PrivateSessionConfig, ProjectsConfig, ProviderConfig, RateLimitConfig,
+    RedactionConfig, RedisConfig, S3BlobConfig, SignalConfig, SignalInstanceConfig,
     SlackCommandConfig, SlackConfig, SlackInstanceConfig, TelegramConfig, TelegramInstanceConfig,
-    TelemetryConfig, TwitchConfig, TwitchInstanceConfig, WarmupConfig, WebhookConfig,
-    normalize_adapter, validate_named_messaging_adapters,
+    TelemetryConfig, TenantConfig, TokenBucketConfig, TwitchConfig, TwitchInstanceConfig,
+    WarmupConfig, WebhookConfig, normalize_adapter, normalize_public_url,
+    validate_named_messaging_adapters,
 };
 use crate::error::{ConfigError, Result};
 
@@ -34,6 +40,215 @@ use std::path::{Path, PathBuf};
 /// - `secret:NAME` — look up from the secrets store (if available).
 /// - `env:VAR_NAME` — read from system environment variable.
 /// - Anything else — literal value.
+/// Parse a `[*.tool_rendering]` table, rejecting unknown detail levels.
+fn parse_tool_rendering(
+    toml: TomlToolRenderingConfig,
+    section: &str,
+) -> Result<crate::conversation::settings::ToolRenderingSettings> {
+    use crate::conversation::settings::ToolDetail;
+
+    let parse = |value: &str| {
+        ToolDetail::parse(value).ok_or_else(|| {
+            ConfigError::Invalid(format!(
+                "invalid {section} detail '{value}', expected hidden, name, summary, or full"
+            ))
+        })
+    };
+    let default = toml.default.as_deref().map(parse).transpose()?;
+    let tools = toml
+        .tools
+        .iter()
+        .map(|(tool, value)| Ok((tool.clone(), parse(value)?)))
+        .collect::<std::result::Result<_, ConfigError>>()?;
+
+    let summarize = toml
+        .summarize
+        .map(
+            |summarize| crate::conversation::settings::ToolSummarySettings {
+                min_lines: summarize.min_lines,
+                model: summarize.model,
+            },
+        )
+        .unwrap_or_default();
+
+    Ok(crate::conversation::settings::ToolRenderingSettings {
+        default,
+        tools,
+        summarize,
+    })
+}
+
+/// Parse `[defaults.opencode.server_logs]`, validating the target and level.
+fn parse_opencode_server_logs(
+    toml: TomlOpenCodeServerLogConfig,
+) -> Result<super::OpenCodeServerLogConfig> {
+    let defaults = super::OpenCodeServerLogConfig::default();
+    let forward_to = toml
+        .forward_to
+        .map(|raw| {
+            crate::messaging::target::parse_delivery_target(&raw).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "invalid opencode.server_logs.forward_to '{raw}', expected adapter:target"
+                ))
+            })
+        })
+        .transpose()?;
+    let min_level = toml
+        .min_level
+        .map(|raw| {
+            crate::opencode::types::ServerLogLevel::parse(&raw).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "invalid opencode.server_logs.min_level '{raw}', expected debug, info, warn, or error"
+                ))
+            })
+        })
+        .transpose()?
+        .unwrap_or(defaults.min_level);
+
+    Ok(super::OpenCodeServerLogConfig {
+        forward_to,
+        min_level,
+        max_per_minute: toml.max_per_minute.unwrap_or(defaults.max_per_minute),
+    })
+}
+
+fn parse_github_webhooks(toml: TomlGitHubWebhookConfig) -> Result<super::GitHubWebhookConfig> {
+    let mappings = toml
+        .mappings
+        .into_iter()
+        .map(|mapping| {
+            let event = mapping.event.trim().to_string();
+            if event.is_empty() {
+                return Err(ConfigError::Invalid(
+                    "agents.github.mappings entry has an empty event".into(),
+                ));
+            }
+            let deliver_to = crate::messaging::target::parse_delivery_target(&mapping.deliver_to)
+                .ok_or_else(|| {
+                    ConfigError::Invalid(format!(
+                        "invalid agents.github.mappings deliver_to '{}' for '{event}', expected adapter:target",
+                        mapping.deliver_to
+                    ))
+                })?;
+            minijinja::Environment::new()
+                .template_from_str(&mapping.prompt)
+                .map_err(|error| {
+                    ConfigError::Invalid(format!(
+                        "invalid agents.github.mappings prompt template for '{event}': {error}"
+                    ))
+                })?;
+            Ok(super::GitHubEventMapping {
+                event,
+                repository: mapping.repository,
+                prompt: mapping.prompt,
+                deliver_to,
+                timeout_secs: mapping.timeout_secs,
+            })
+        })
+        .collect::<std::result::Result<Vec<_>, ConfigError>>()?;
+
+    Ok(super::GitHubWebhookConfig {
+        secret: toml.secret.as_deref().and_then(resolve_env_value),
+        token: toml.token.as_deref().and_then(resolve_env_value),
+        mappings,
+    })
+}
+
+fn parse_notify_webhook(toml: TomlNotifyWebhookConfig) -> Result<super::NotifyWebhookConfig> {
+    let url = toml.url.trim().to_string();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(ConfigError::Invalid(format!(
+            "invalid agents.notify_webhooks url '{url}', expected an http(s) URL"
+        ))
+        .into());
+    }
+    let events = match toml.events {
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                super::NotifyEvent::parse(name.trim()).ok_or_else(|| {
+                    ConfigError::Invalid(format!(
+                        "unknown agents.notify_webhooks event '{name}' for {url}"
+                    ))
+                })
+            })
+            .collect::<std::result::Result<Vec<_>, ConfigError>>()?,
+        None => super::NotifyEvent::ALL.to_vec(),
+    };
+    Ok(super::NotifyWebhookConfig {
+        secret: toml.secret.as_deref().and_then(resolve_env_value),
+        url,
+        events,
+    })
+}
+
+fn parse_prompt_template(toml: TomlPromptTemplate) -> Result<crate::templates::PromptTemplate> {
+    let name = toml.name.trim().to_string();
+    crate::templates::validate_name(&name)
+        .map_err(|error| ConfigError::Invalid(format!("agents.templates: {error}")))?;
+    crate::templates::placeholders(&toml.prompt)
+        .map_err(|error| ConfigError::Invalid(format!("agents.templates '{name}': {error}")))?;
+    Ok(crate::templates::PromptTemplate {
+        name,
+        prompt: toml.prompt,
+        description: toml.description,
+    })
+}
+
+fn parse_transcribe(toml: TomlTranscribeConfig) -> Result<super::TranscribeConfig> {
+    let base_url = toml
+        .base_url
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("https://api.openai.com/v1")
+        .trim_end_matches('/')
+        .to_string();
+    if !(base_url.starts_with("https://") || base_url.starts_with("http://")) {
+        return Err(ConfigError::Invalid(format!(
+            "invalid agents.transcribe base_url '{base_url}', expected an http(s) URL"
+        ))
+        .into());
+    }
+    if !cfg!(feature = "transcribe") {
+        tracing::warn!(
+            "agents.transcribe is set but spacebot was built without the transcribe feature; \
+             voice notes use routing.voice instead"
+        );
+    }
+    Ok(super::TranscribeConfig {
+        base_url,
+        api_key: toml.api_key.as_deref().and_then(resolve_env_value),
+        model: toml.model.unwrap_or_else(|| "whisper-1".to_string()),
+        language: toml.language,
+        echo: toml.echo.unwrap_or(true),
+    })
+}
+
+/// Reject a webhook `auth_token` that is configured but resolves to nothing.
+///
+/// An unresolved token would otherwise become `None`, which the receiver
+/// treats as open access.
+fn validate_email_webhook(toml: &TomlEmailWebhookConfig, section: &str) -> Result<()> {
+    let Some(reference) = toml.auth_token.as_deref() else {
+        return Ok(());
+    };
+    match resolve_env_value(reference) {
+        Some(token) if !token.is_empty() => Ok(()),
+        _ => Err(ConfigError::Invalid(format!(
+            "{section} auth_token '{reference}' could not be resolved; refusing to accept unauthenticated webhooks"
+        ))
+        .into()),
+    }
+}
+
+fn parse_email_webhook(toml: TomlEmailWebhookConfig) -> EmailWebhookConfig {
+    EmailWebhookConfig {
+        bind: toml.bind,
+        port: toml.port,
+        auth_token: toml.auth_token.as_deref().and_then(resolve_env_value),
+    }
+}
+
 pub(crate) fn resolve_env_value(value: &str) -> Option<String> {
     if let Some(alias) = value.strip_prefix("secret:") {
         let guard = RESOLVE_SECRETS_STORE.load();
@@ -67,6 +282,18 @@ pub fn set_resolve_secrets_store(store: std::sync::Arc<crate::secrets::store::Se
     RESOLVE_SECRETS_STORE.store(std::sync::Arc::new(Some(store)));
 }
 
+/// Overrides applied to every config loaded from disk or the environment.
+///
+/// Process-wide so that reloads from the file watcher and the API keep the
+/// settings an embedding application registered in code.
+static CONFIG_OVERRIDES: std::sync::LazyLock<arc_swap::ArcSwap<ConfigOverrides>> =
+    std::sync::LazyLock::new(|| arc_swap::ArcSwap::from_pointee(ConfigOverrides::default()));
+
+/// Set the overrides applied on top of every subsequently loaded config.
+pub fn set_config_overrides(overrides: ConfigOverrides) {
+    CONFIG_OVERRIDES.store(std::sync::Arc::new(overrides));
+}
+
 /// Known top-level keys in config.toml (must match `TomlConfig` field names).
 const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
     "llm",
@@ -81,6 +308,16 @@ const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
     "metrics",
     "telemetry",
     "memory_janitor",
+    "dead_letters",
+    "tenants",
+    "rate_limit",
+    "blob_store",
+    "database",
+    "cluster",
+    "event_bus",
+    "cost_report",
+    "digest",
+    "notify",
 ];
 
 /// Pre-parse check that warns about unrecognised top-level keys in a config
@@ -350,6 +587,522 @@ fn parse_mcp_server_config(raw: TomlMcpServerConfig) -> Result<McpServerConfig>
     })
 }
 
+fn parse_rate_limit(raw: TomlRateLimitConfig) -> Result<RateLimitConfig> {
+    let defaults = RateLimitConfig::default();
+    let config = RateLimitConfig {
+        enabled: raw.enabled.unwrap_or(defaults.enabled),
+        user: TokenBucketConfig {
+            burst: raw.user_burst.unwrap_or(defaults.user.burst),
+            per_minute: raw.user_per_minute.unwrap_or(defaults.user.per_minute),
+        },
+        channel: TokenBucketConfig {
+            burst: raw.channel_burst.unwrap_or(defaults.channel.burst),
+            per_minute: raw
+                .channel_per_minute
+                .unwrap_or(defaults.channel.per_minute),
+        },
+    };
+    for (name, bucket) in [("user", config.user), ("channel", config.channel)] {
+        if bucket.burst == 0 || bucket.per_minute == 0 {
+            return Err(ConfigError::Invalid(format!(
+                "rate_limit.{name}_burst and rate_limit.{name}_per_minute must be >= 1"
+            ))
+            .into());
+        }
+    }
+    Ok(config)
+}
+
+fn parse_private_sessions(raw: TomlPrivateSessionConfig) -> Result<PrivateSessionConfig> {
+    let defaults = PrivateSessionConfig::default();
+    let worker_tier = raw
+        .worker_tier
+        .as_deref()
+        .map(|value| {
+            crate::access::AccessTier::parse(value).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "invalid private_sessions.worker_tier '{value}' \
+                     (expected admin, developer, or read_only)"
+                ))
+            })
+        })
+        .transpose()?;
+    Ok(PrivateSessionConfig {
+        enabled: raw.enabled.unwrap_or(defaults.enabled),
+        model: raw.model.or(defaults.model),
+        memory: raw.memory.unwrap_or(defaults.memory),
+        allow_opencode: raw.allow_opencode.unwrap_or(defaults.allow_opencode),
+        worker_tier: worker_tier.unwrap_or(defaults.worker_tier),
+    })
+}
+
+/// Validated `post_to`, `weekday`, `hour`, and `locale` of a weekly report
+/// section. Unset keys stay `None` for the caller to default.
+type WeeklyPost = (
+    Option<crate::messaging::target::BroadcastTarget>,
+    Option<chrono::Weekday>,
+    Option<u32>,
+    Option<String>,
+);
+
+fn parse_weekly_post(
+    section: &str,
+    post_to: Option<String>,
+    weekday: Option<String>,
+    hour: Option<u32>,
+    locale: Option<String>,
+) -> Result<WeeklyPost> {
+    let post_to = post_to
+        .map(|raw| {
+            crate::messaging::target::parse_delivery_target(&raw).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "invalid {section}.post_to '{raw}', expected adapter:target"
+                ))
+            })
+        })
+        .transpose()?;
+    let weekday = weekday
+        .map(|raw| {
+            raw.parse::<chrono::Weekday>().map_err(|_| {
+                ConfigError::Invalid(format!(
+                    "invalid {section}.weekday '{raw}', expected a day such as monday"
+                ))
+            })
+        })
+        .transpose()?;
+    if let Some(hour) = hour
+        && hour > 23
+    {
+        return Err(ConfigError::Invalid(format!(
+            "{section}.hour must be between 0 and 23, got {hour}"
+        ))
+        .into());
+    }
+    if let Some(locale) = &locale
+        && !crate::prompts::messages::is_supported(locale)
+    {
+        return Err(ConfigError::Invalid(format!(
+            "unsupported {section}.locale '{locale}', expected one of {}",
+            crate::prompts::messages::LOCALES.join(", ")
+        ))
+        .into());
+    }
+    Ok((post_to, weekday, hour, locale))
+}
+
+fn parse_cost_report(raw: TomlCostReportConfig) -> Result<CostReportConfig> {
+    let defaults = CostReportConfig::default();
+    let (post_to, weekday, hour, locale) = parse_weekly_post(
+        "cost_report",
+        raw.post_to,
+        raw.weekday,
+        raw.hour,
+        raw.locale,
+    )?;
+    Ok(CostReportConfig {
+        post_to,
+        weekday: weekday.unwrap_or(defaults.weekday),
+        hour: hour.unwrap_or(defaults.hour),
+        locale: locale.unwrap_or(defaults.locale),
+    })
+}
+
+fn parse_digest(raw: TomlDigestConfig) -> Result<DigestConfig> {
+    let defaults = DigestConfig::default();
+    let (post_to, weekday, hour, locale) =
+        parse_weekly_post("digest", raw.post_to, raw.weekday, raw.hour, raw.locale)?;
+    Ok(DigestConfig {
+        post_to,
+        weekday: weekday.unwrap_or(defaults.weekday),
+        hour: hour.unwrap_or(defaults.hour),
+        locale: locale.unwrap_or(defaults.locale),
+    })
+}
+
+fn parse_notify(raw: TomlNotifyConfig) -> Result<NotifyConfig> {
+    let defaults = NotifyConfig::default();
+    let ops_channel = raw
+        .ops_channel
+        .map(|raw| {
+            crate::messaging::target::parse_delivery_target(&raw).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "invalid notify.ops_channel '{raw}', expected adapter:target"
+                ))
+            })
+        })
+        .transpose()?;
+    let min_severity = raw
+        .min_severity
+        .map(|raw| {
+            crate::notifications::NotificationSeverity::parse(&raw).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "invalid notify.min_severity '{raw}', expected info, warn, or error"
+                ))
+            })
+        })
+        .transpose()?
+        .unwrap_or(defaults.min_severity);
+    let config = NotifyConfig {
+        ops_channel,
+        min_severity,
+        dedupe_window_secs: raw
+            .dedupe_window_secs
+            .unwrap_or(defaults.dedupe_window_secs),
+        reconnect_storm_threshold: raw
+            .reconnect_storm_threshold
+            .unwrap_or(defaults.reconnect_storm_threshold),
+        reconnect_storm_window_secs: raw
+            .reconnect_storm_window_secs
+            .unwrap_or(defaults.reconnect_storm_window_secs),
+    };
+    if config.reconnect_storm_threshold == 0 || config.reconnect_storm_window_secs == 0 {
+        return Err(ConfigError::Invalid(
+            "notify.reconnect_storm_threshold and notify.reconnect_storm_window_secs must be >= 1"
+                .to_string(),
+        )
+        .into());
+    }
+    Ok(config)
+}
+
+fn parse_blob_store(raw: TomlBlobStoreConfig, instance_dir: &Path) -> Result<BlobStoreConfig> {
+    match raw.backend.as_deref().unwrap_or("filesystem") {
+        "filesystem" => Ok(match raw.path {
+            Some(path) => BlobStoreConfig::Filesystem {
+                root: instance_dir.join(path),
+            },
+            None => BlobStoreConfig::default_for(instance_dir),
+        }),
+        "s3" => {
+            let required = |value: Option<String>, key: &str| {
+                value
+                    .as_deref()
+                    .and_then(resolve_env_value)
+                    .filter(|value| !value.trim().is_empty())
+                    .ok_or_else(|| {
+                        ConfigError::Invalid(format!("blob_store.{key} is required for s3"))
+                    })
+            };
+            let region = raw.region.unwrap_or_else(|| "us-east-1".to_string());
+            let endpoint = raw
+                .endpoint
+                .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+            if url::Url::parse(&endpoint).is_err() {
+                return Err(ConfigError::Invalid(format!(
+                    "invalid blob_store.endpoint '{endpoint}'"
+                ))
+                .into());
+            }
+            Ok(BlobStoreConfig::S3(S3BlobConfig {
+                endpoint: endpoint.trim_end_matches('/').to_string(),
+                region,
+                bucket: required(raw.bucket, "bucket")?,
+                prefix: raw.prefix.unwrap_or_default(),
+                access_key_id: required(raw.access_key_id, "access_key_id")?,
+                secret_access_key: required(raw.secret_access_key, "secret_access_key")?,
+                path_style: raw.path_style.unwrap_or(false),
+            }))
+        }
+        other => Err(ConfigError::Invalid(format!(
+            "unknown blob_store.backend '{other}', expected filesystem or s3"
+        ))
+        .into()),
+    }
+}
+
+fn parse_database(raw: TomlDatabaseConfig) -> Result<DatabaseConfig> {
+    match raw.backend.as_deref().unwrap_or("sqlite") {
+        "sqlite" => Ok(DatabaseConfig::Sqlite),
+        "postgres" => {
+            let url = raw
+                .url
+                .as_deref()
+                .and_then(resolve_env_value)
+                .filter(|url| !url.trim().is_empty())
+                .ok_or_else(|| {
+                    ConfigError::Invalid("database.url is required for postgres".into())
+                })?;
+            let scheme = url::Url::parse(&url)
+                .map(|url| url.scheme().to_string())
+                .unwrap_or_default();
+            if !matches!(scheme.as_str(), "postgres" | "postgresql") {
+                return Err(ConfigError::Invalid(
+                    "database.url must be a postgres:// or postgresql:// URL".into(),
+                )
+                .into());
+            }
+            let max_connections = raw.max_connections.unwrap_or(10);
+            if max_connections == 0 {
+                return Err(
+                    ConfigError::Invalid("database.max_connections must be >= 1".into()).into(),
+                );
+            }
+            Ok(DatabaseConfig::Postgres(PostgresConfig {
+                url,
+                max_connections,
+            }))
+        }
+        other => Err(ConfigError::Invalid(format!(
+            "unknown database.backend '{other}', expected sqlite or postgres"
+        ))
+        .into()),
+    }
+}
+
+fn parse_cluster(raw: TomlClusterConfig, database: &DatabaseConfig) -> Result<ClusterConfig> {
+    let enabled = raw.enabled.unwrap_or(false);
+    if enabled && !matches!(database, DatabaseConfig::Postgres(_)) {
+        return Err(ConfigError::Invalid(
+            "cluster.enabled requires [database] backend = \"postgres\"".into(),
+        )
+        .into());
+    }
+    let lease_ttl_secs = raw
+        .lease_ttl_secs
+        .unwrap_or(ClusterConfig::default().lease_ttl_secs);
+    if lease_ttl_secs < 3 {
+        return Err(ConfigError::Invalid("cluster.lease_ttl_secs must be >= 3".into()).into());
+    }
+    let replica_id = raw
+        .replica_id
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    Ok(ClusterConfig {
+        enabled,
+        replica_id,
+        lease_ttl_secs,
+    })
+}
+
+fn parse_event_bus(raw: TomlEventBusConfig) -> Result<EventBusConfig> {
+    match raw.backend.as_deref().unwrap_or("local") {
+        "local" => Ok(EventBusConfig::Local),
+        "redis" => {
+            let url = raw
+                .url
+                .as_deref()
+                .and_then(resolve_env_value)
+                .filter(|url| !url.trim().is_empty())
+                .ok_or_else(|| {
+                    ConfigError::Invalid("event_bus.url is required for redis".into())
+                })?;
+            let parsed = url::Url::parse(&url).ok();
+            let Some(parsed) =
+                parsed.filter(|parsed| matches!(parsed.scheme(), "redis" | "rediss"))
+            else {
+                return Err(ConfigError::Invalid(
+                    "event_bus.url must be a redis:// or rediss:// URL".into(),
+                )
+                .into());
+            };
+            if parsed.host_str().is_none() {
+                return Err(ConfigError::Invalid("event_bus.url has no host".into()).into());
+            }
+            if let Err(error) = crate::api::event_bus::redis::database_index(&parsed) {
+                return Err(ConfigError::Invalid(format!("event_bus.url: {error}")).into());
+            }
+            let channel = raw
+                .channel
+                .filter(|channel| !channel.trim().is_empty())
+                .unwrap_or_else(|| "spacebot:events".into());
+            Ok(EventBusConfig::Redis(RedisConfig { url, channel }))
+        }
+        other => Err(ConfigError::Invalid(format!(
+            "unknown event_bus.backend '{other}', expected local or redis"
+        ))
+        .into()),
+    }
+}
+
+fn parse_tenants(raw: Vec<TomlTenantConfig>, agents: &[AgentConfig]) -> Result<Vec<TenantConfig>> {
+    let default_agent_id = agents
+        .iter()
+        .find(|agent| agent.default)
+        .map(|agent| agent.id.as_str());
+    let mut tenants: Vec<TenantConfig> = Vec::with_capacity(raw.len());
+    for tenant in raw {
+        if tenant.id.trim().is_empty() {
+            return Err(ConfigError::Invalid("tenant id cannot be empty".into()).into());
+        }
+        let agent_id = tenant.agent_id.unwrap_or_else(|| tenant.id.clone());
+        if !agents.iter().any(|agent| agent.id == agent_id) {
+            return Err(ConfigError::Invalid(format!(
+                "tenant '{}' uses agent '{agent_id}', which is not defined",
+                tenant.id
+            ))
+            .into());
+        }
+        if default_agent_id == Some(agent_id.as_str()) {
+            return Err(ConfigError::Invalid(format!(
+                "tenant '{}' cannot use the default agent '{agent_id}'",
+                tenant.id
+            ))
+            .into());
+        }
+        if tenant.discord_guild_ids.is_empty() && tenant.slack_workspace_ids.is_empty() {
+            return Err(ConfigError::Invalid(format!(
+                "tenant '{}' needs at least one discord_guild_ids or slack_workspace_ids entry",
+                tenant.id
+            ))
+            .into());
+        }
+        for existing in &tenants {
+            if existing.id == tenant.id {
+                return Err(
+                    ConfigError::Invalid(format!("duplicate tenant id '{}'", tenant.id)).into(),
+                );
+            }
+            if existing.agent_id == agent_id {
+                return Err(ConfigError::Invalid(format!(
+                    "tenants '{}' and '{}' both use agent '{agent_id}'",
+                    existing.id, tenant.id
+                ))
+                .into());
+            }
+            let shared = tenant
+                .discord_guild_ids
+                .iter()
+                .find(|id| existing.discord_guild_ids.contains(id))
+                .or_else(|| {
+                    tenant
+                        .slack_workspace_ids
+                        .iter()
+                        .find(|id| existing.slack_workspace_ids.contains(id))
+                });
+            if let Some(shared) = shared {
+                return Err(ConfigError::Invalid(format!(
+                    "tenants '{}' and '{}' both claim '{shared}'",
+                    existing.id, tenant.id
+                ))
+                .into());
+            }
+        }
+        tenants.push(TenantConfig {
+            id: tenant.id,
+            agent_id,
+            discord_guild_ids: tenant.discord_guild_ids,
+            slack_workspace_ids: tenant.slack_workspace_ids,
+            daily_message_budget: tenant.daily_message_budget,
+            content_filters: parse_content_filters(&tenant.id, tenant.content_filters)?,
+        });
+    }
+    Ok(tenants)
+}
+
+fn parse_content_filters(
+    tenant_id: &str,
+    raw: Vec<TomlContentFilterRule>,
+) -> Result<Vec<ContentFilterRule>> {
+    raw.into_iter()
+        .map(|rule| {
+            let action = match rule.action.as_str() {
+                "mask" => ContentFilterAction::Mask,
+                "block" => ContentFilterAction::Block,
+                "flag" => ContentFilterAction::Flag,
+                other => {
+                    return Err(ConfigError::Invalid(format!(
+                        "tenant '{tenant_id}' content filter '{}' has invalid action '{other}', \
+                         expected mask, block, or flag",
+                        rule.name
+                    ))
+                    .into());
+                }
+            };
+            if rule.patterns.is_empty() && rule.words.is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "tenant '{tenant_id}' content filter '{}' needs patterns or words",
+                    rule.name
+                ))
+                .into());
+            }
+            for pattern in &rule.patterns {
+                if let Err(error) = regex::Regex::new(pattern) {
+                    return Err(ConfigError::Invalid(format!(
+                        "tenant '{tenant_id}' content filter '{}' has invalid pattern \
+                         '{pattern}': {error}",
+                        rule.name
+                    ))
+                    .into());
+                }
+            }
+            if let Some(Err(error)) = crate::messaging::content_filter::words_regex(&rule.words) {
+                return Err(ConfigError::Invalid(format!(
+                    "tenant '{tenant_id}' content filter '{}' has a word list that can't be \
+                     compiled (split it across rules if it is very long): {error}",
+                    rule.name
+                ))
+                .into());
+            }
+            Ok(ContentFilterRule {
+                name: rule.name,
+                patterns: rule.patterns,
+                words: rule.words,
+                action,
+            })
+        })
+        .collect()
+}
+
+fn parse_opencode_backend_config(raw: TomlOpenCodeBackend) -> Result<OpenCodeBackendConfig> {
+    if raw.name.trim().is_empty() {
+        return Err(ConfigError::Invalid("opencode backend name cannot be empty".into()).into());
+    }
+    let url = raw.url.trim().trim_end_matches('/').to_string();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(ConfigError::Invalid(format!(
+            "opencode backend '{}' url must start with http:// or https://",
+            raw.name
+        ))
+        .into());
+    }
+
+    let bearer_token = raw.bearer_token.as_deref().and_then(resolve_env_value);
+    let basic_username = raw.basic_username.as_deref().and_then(resolve_env_value);
+    let auth = match (bearer_token, basic_username) {
+        (Some(_), Some(_)) => {
+            return Err(ConfigError::Invalid(format!(
+                "opencode backend '{}' cannot set both bearer_token and basic_username",
+                raw.name
+            ))
+            .into());
+        }
+        (Some(token), None) => OpenCodeBackendAuth::Bearer { token },
+        (None, Some(username)) => OpenCodeBackendAuth::Basic {
+            username,
+            password: raw.basic_password.as_deref().and_then(resolve_env_value),
+        },
+        (None, None) => OpenCodeBackendAuth::None,
+    };
+
+    if raw.client_key.is_some() && raw.client_cert.is_none() {
+        return Err(ConfigError::Invalid(format!(
+            "opencode backend '{}' sets client_key without client_cert",
+            raw.name
+        ))
+        .into());
+    }
+
+    let mut headers: Vec<(String, String)> = raw
+        .headers
+        .into_iter()
+        .filter_map(|(name, value)| resolve_env_value(&value).map(|value| (name, value)))
+        .collect();
+    headers.sort();
+
+    Ok(OpenCodeBackendConfig {
+        name: raw.name,
+        url,
+        directory: PathBuf::from(raw.directory),
+        auth,
+        headers,
+        client_cert: raw.client_cert.map(PathBuf::from),
+        client_key: raw.client_key.map(PathBuf::from),
+        ca_cert: raw.ca_cert.map(PathBuf::from),
+        mirror: raw.mirror,
+    })
+}
+
 impl Config {
     /// Resolve the instance directory from env or default (~/.spacebot).
     pub fn default_instance_dir() -> PathBuf {
@@ -457,7 +1210,9 @@ impl Config {
         let toml_config: TomlConfig = toml::from_str(&content)
             .with_context(|| format!("failed to parse config from {}", path.display()))?;
 
-        Self::from_toml(toml_config, instance_dir)
+        let mut config = Self::from_toml(toml_config, instance_dir)?;
+        CONFIG_OVERRIDES.load().apply(&mut config);
+        Ok(config)
     }
 
     /// Load from environment variables only (no config file).
@@ -944,6 +1699,7 @@ impl Config {
             warmup: None,
             browser: None,
             channel: None,
+            tool_rendering: None,
             mcp: None,
             brave_search_key: None,
             cron_timezone: None,
@@ -951,6 +1707,10 @@ impl Config {
             sandbox: None,
             projects: None,
             cron: Vec::new(),
+            github: None,
+            notify_webhooks: Vec::new(),
+            templates: Vec::new(),
+            transcribe: None,
         }];
 
         let mut api = ApiConfig::default();
@@ -959,7 +1719,7 @@ impl Config {
         let mut defaults = DefaultsConfig::default();
         defaults.browser.chrome_cache_dir = instance_dir.join("chrome_cache");
 
-        Ok(Self {
+        let mut config = Self {
             instance_dir: instance_dir.to_path_buf(),
             llm,
             defaults,
@@ -981,6 +1741,7 @@ impl Config {
                 telegram_id: None,
                 slack_id: None,
                 email: None,
+                access: None,
             }],
             messaging: MessagingConfig::default(),
             bindings: Vec::new(),
@@ -994,7 +1755,19 @@ impl Config {
                 sample_rate: 1.0,
             },
             memory_janitor: MemoryJanitorConfig::default(),
-        })
+            dead_letters: DeadLettersConfig::default(),
+            tenants: Vec::new(),
+            rate_limit: RateLimitConfig::default(),
+            blob_store: BlobStoreConfig::default_for(instance_dir),
+            database: DatabaseConfig::default(),
+            cluster: ClusterConfig::default(),
+            event_bus: EventBusConfig::default(),
+            cost_report: CostReportConfig::default(),
+            digest: DigestConfig::default(),
+            notify: NotifyConfig::default(),
+        };
+        CONFIG_OVERRIDES.load().apply(&mut config);
+        Ok(config)
     }
 
     /// Validate a raw TOML string as a valid Spacebot config.
@@ -1691,6 +2464,12 @@ impl Config {
                         ..base_defaults.browser.clone()
                     })
             },
+            tool_rendering: match toml.defaults.tool_rendering {
+                Some(tool_rendering) => {
+                    parse_tool_rendering(tool_rendering, "defaults.tool_rendering")?
+                }
+                None => base_defaults.tool_rendering.clone(),
+            },
             channel: toml
                 .defaults
                 .channel
@@ -1707,6 +2486,9 @@ impl Config {
                         save_attachments: channel_config
                             .save_attachments
                             .unwrap_or(base_defaults.channel.save_attachments),
+                        abort_on_prompt_deleted: channel_config
+                            .abort_on_prompt_deleted
+                            .unwrap_or(base_defaults.channel.abort_on_prompt_deleted),
                     }
                 })
                 .unwrap_or(base_defaults.channel),
@@ -1732,12 +2514,12 @@ impl Config {
             opencode: toml
                 .defaults
                 .opencode
-                .map(|oc| {
+                .map(|oc| -> Result<OpenCodeConfig> {
                     let base = &base_defaults.opencode;
                     let path_raw = oc.path.unwrap_or_else(|| base.path.clone());
                     let resolved_path =
                         resolve_env_value(&path_raw).unwrap_or_else(|| base.path.clone());
-                    OpenCodeConfig {
+                    Ok(OpenCodeConfig {
                         enabled: oc.enabled.unwrap_or(base.enabled),
                         path: resolved_path,
                         max_servers: oc.max_servers.unwrap_or(base.max_servers),
@@ -1757,9 +2539,31 @@ impl Config {
                                     .unwrap_or_else(|| base.permissions.webfetch.clone()),
                             })
                             .unwrap_or_else(|| base.permissions.clone()),
-                    }
+                        backends: oc
+                            .backends
+                            .into_iter()
+                            .map(parse_opencode_backend_config)
+                            .collect::<Result<Vec<_>>>()?,
+                        server_logs: match oc.server_logs {
+                            Some(server_logs) => parse_opencode_server_logs(server_logs)?,
+                            None => base.server_logs.clone(),
+                        },
+                        session_ttl_secs: oc.session_ttl_secs.unwrap_or(base.session_ttl_secs),
+                        rename_threads: oc.rename_threads.unwrap_or(base.rename_threads),
+                        dry_run: oc.dry_run.unwrap_or(base.dry_run),
+                        question_timeout_secs: oc
+                            .question_timeout_secs
+                            .unwrap_or(base.question_timeout_secs),
+                    })
                 })
+                .transpose()?
                 .unwrap_or_else(|| base_defaults.opencode.clone()),
+            private_sessions: toml
+                .defaults
+                .private_sessions
+                .map(parse_private_sessions)
+                .transpose()?
+                .unwrap_or_else(|| base_defaults.private_sessions.clone()),
             worker_log_mode: toml
                 .defaults
                 .worker_log_mode
@@ -1907,6 +2711,12 @@ impl Config {
                         ),
                         chrome_cache_dir: defaults.browser.chrome_cache_dir.clone(),
                     }),
+                    tool_rendering: a
+                        .tool_rendering
+                        .map(|tool_rendering| {
+                            parse_tool_rendering(tool_rendering, "agents.tool_rendering")
+                        })
+                        .transpose()?,
                     channel: a.channel.map(|channel_config| {
                         let response_mode = parse_response_mode(
                             channel_config.response_mode.as_deref(),
@@ -1921,6 +2731,9 @@ impl Config {
                             save_attachments: channel_config
                                 .save_attachments
                                 .unwrap_or(defaults.channel.save_attachments),
+                            abort_on_prompt_deleted: channel_config
+                                .abort_on_prompt_deleted
+                                .unwrap_or(defaults.channel.abort_on_prompt_deleted),
                         }
                     }),
                     mcp: match a.mcp {
@@ -1958,6 +2771,18 @@ impl Config {
                         }
                     }),
                     cron,
+                    github: a.github.map(parse_github_webhooks).transpose()?,
+                    notify_webhooks: a
+                        .notify_webhooks
+                        .into_iter()
+                        .map(parse_notify_webhook)
+                        .collect::<Result<Vec<_>>>()?,
+                    templates: a
+                        .templates
+                        .into_iter()
+                        .map(parse_prompt_template)
+                        .collect::<Result<Vec<_>>>()?,
+                    transcribe: a.transcribe.map(parse_transcribe).transpose()?,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
@@ -1986,6 +2811,7 @@ impl Config {
                 warmup: None,
                 browser: None,
                 channel: None,
+                tool_rendering: None,
                 mcp: None,
                 brave_search_key: None,
                 cron_timezone: None,
@@ -1993,6 +2819,10 @@ impl Config {
                 sandbox: None,
                 projects: None,
                 cron: Vec::new(),
+                github: None,
+                notify_webhooks: Vec::new(),
+                templates: Vec::new(),
+                transcribe: None,
             });
         }
 
@@ -2002,6 +2832,82 @@ impl Config {
             first.default = true;
         }
 
+        let redaction = match toml.messaging.redaction {
+            Some(redaction) => {
+                for pattern in &redaction.patterns {
+                    if let Err(error) = regex::Regex::new(pattern) {
+                        return Err(ConfigError::Invalid(format!(
+                            "invalid messaging.redaction pattern '{pattern}': {error}"
+                        ))
+                        .into());
+                    }
+                }
+                RedactionConfig {
+                    enabled: redaction.enabled.unwrap_or(true),
+                    patterns: redaction.patterns,
+                    env_vars: redaction.env_vars,
+                }
+            }
+            None => RedactionConfig::default(),
+        };
+
+        let overflow = match toml.messaging.overflow {
+            Some(overflow) => {
+                let mode = match overflow.mode.as_deref() {
+                    None | Some("split") => OverflowMode::Split,
+                    Some("file") => OverflowMode::File,
+                    Some("paste") => OverflowMode::Paste,
+                    Some(other) => {
+                        return Err(ConfigError::Invalid(format!(
+                            "invalid messaging.overflow mode '{other}', expected split, file, or paste"
+                        ))
+                        .into());
+                    }
+                };
+                let paste_url = overflow.paste_url.as_deref().and_then(resolve_env_value);
+                if mode == OverflowMode::Paste && paste_url.is_none() {
+                    return Err(ConfigError::Invalid(
+                        "messaging.overflow mode 'paste' requires paste_url".into(),
+                    )
+                    .into());
+                }
+                let defaults = OverflowConfig::default();
+                OverflowConfig {
+                    mode,
+                    preview_chars: overflow.preview_chars.unwrap_or(defaults.preview_chars),
+                    paste_url,
+                    paste_token: overflow.paste_token.as_deref().and_then(resolve_env_value),
+                    paste_url_field: overflow.paste_url_field,
+                }
+            }
+            None => OverflowConfig::default(),
+        };
+
+        let links = match toml.messaging.links {
+            Some(links) => {
+                let defaults = LinksConfig::default();
+                LinksConfig {
+                    suppress_unfurls: links.suppress_unfurls.unwrap_or(defaults.suppress_unfurls),
+                    strip_tracking: links.strip_tracking.unwrap_or(defaults.strip_tracking),
+                }
+            }
+            None => LinksConfig::default(),
+        };
+
+        if let Some(email) = &toml.messaging.email {
+            if let Some(webhook) = &email.webhook {
+                validate_email_webhook(webhook, "messaging.email.webhook")?;
+            }
+            for instance in &email.instances {
+                if let Some(webhook) = &instance.webhook {
+                    validate_email_webhook(
+                        webhook,
+                        &format!("messaging.email.instances.{}.webhook", instance.name),
+                    )?;
+                }
+            }
+        }
+
         let messaging = MessagingConfig {
             discord: toml.messaging.discord.and_then(|d| {
                 let instances = d
@@ -2154,10 +3060,12 @@ impl Config {
                         let smtp_host =
                             instance.smtp_host.as_deref().and_then(resolve_env_value);
 
-                        let has_credentials = imap_host.is_some()
+                        let webhook = instance.webhook.map(parse_email_webhook);
+                        let has_inbound = (imap_host.is_some()
                             && imap_username.is_some()
-                            && imap_password.is_some()
-                            && smtp_host.is_some();
+                            && imap_password.is_some())
+                            || webhook.is_some();
+                        let has_credentials = has_inbound && smtp_host.is_some();
 
                         if instance.enabled && !has_credentials {
                             tracing::warn!(
@@ -2210,6 +3118,8 @@ impl Config {
                             allowed_senders: instance.allowed_senders,
                             max_body_bytes: instance.max_body_bytes,
                             max_attachment_bytes: instance.max_attachment_bytes,
+                            prompt_addresses: instance.prompt_addresses,
+                            webhook,
                         }
                     })
                     .collect::<Vec<_>>();
@@ -2227,9 +3137,11 @@ impl Config {
                     .ok()
                     .or_else(|| email.smtp_host.as_deref().and_then(resolve_env_value));
 
-                let has_default = imap_host.is_some()
+                let webhook = email.webhook.map(parse_email_webhook);
+                let has_default = ((imap_host.is_some()
                     && imap_username.is_some()
-                    && imap_password.is_some()
+                    && imap_password.is_some())
+                    || webhook.is_some())
                     && smtp_host.is_some();
 
                 if !has_default && instances.is_empty() {
@@ -2280,9 +3192,34 @@ impl Config {
                     allowed_senders: email.allowed_senders,
                     max_body_bytes: email.max_body_bytes,
                     max_attachment_bytes: email.max_attachment_bytes,
+                    prompt_addresses: email.prompt_addresses,
+                    webhook,
                     instances,
                 })
             }),
+            irc: toml.messaging.irc.map(|irc| IrcConfig {
+                enabled: irc.enabled,
+                server: irc.server.trim().to_string(),
+                port: irc.port.unwrap_or(if irc.tls { 6697 } else { 6667 }),
+                tls: irc.tls,
+                nickname: irc.nickname.trim().to_string(),
+                password: irc.password.as_deref().and_then(resolve_env_value),
+                nickserv_password: irc.nickserv_password.as_deref().and_then(resolve_env_value),
+                channels: irc
+                    .channels
+                    .iter()
+                    .map(|channel| {
+                        let channel = channel.trim();
+                        if channel.starts_with(['#', '&']) {
+                            channel.to_string()
+                        } else {
+                            format!("#{channel}")
+                        }
+                    })
+                    .collect(),
+                command_prefix: irc.command_prefix,
+                allowed_nicks: irc.allowed_nicks,
+            }),
             webhook: toml.messaging.webhook.map(|w| WebhookConfig {
                 enabled: w.enabled,
                 port: w.port,
@@ -2464,6 +3401,9 @@ impl Config {
                     max_attachment_bytes: mm.max_attachment_bytes,
                 })
             }),
+            redaction,
+            overflow,
+            links,
         };
 
         let bindings: Vec<Binding> = toml
@@ -2475,6 +3415,9 @@ impl Config {
                     let mut cs = ConversationSettings {
                         model: s.model,
                         save_attachments: s.save_attachments,
+                        plan_approval: s.plan_approval,
+                        dry_run: s.dry_run,
+                        collapse: s.collapse,
                         ..Default::default()
                     };
                     // Only override enum fields when explicitly set in TOML,
@@ -2511,6 +3454,72 @@ impl Config {
                             ),
                         }
                     }
+                    if let Some(tool_rendering) = s.tool_rendering {
+                        match parse_tool_rendering(
+                            tool_rendering,
+                            "bindings.settings.tool_rendering",
+                        ) {
+                            Ok(parsed) => cs.tool_rendering = Some(parsed),
+                            Err(error) => tracing::warn!(
+                                %error,
+                                "invalid tool_rendering in binding settings, ignoring"
+                            ),
+                        }
+                    }
+                    if let Some(fallback_models) = s.fallback_models {
+                        let (valid, invalid): (Vec<_>, Vec<_>) = fallback_models
+                            .into_iter()
+                            .partition(|model| model.contains('/'));
+                        for model in invalid {
+                            tracing::warn!(
+                                %model,
+                                "fallback model must be in provider/model form, ignoring"
+                            );
+                        }
+                        cs.fallback_models = Some(valid);
+                    }
+                    if let Some(delivery) = s.delivery.as_deref() {
+                        match DeliveryMode::parse(delivery) {
+                            Some(delivery) => cs.delivery = Some(delivery),
+                            None => tracing::warn!(
+                                value = delivery,
+                                "unknown delivery mode in binding settings, ignoring"
+                            ),
+                        }
+                    }
+                    if let Some(repository_url) = s.repository_url {
+                        if repository_url.starts_with("https://")
+                            || repository_url.starts_with("http://")
+                        {
+                            cs.repository_url = Some(repository_url);
+                        } else {
+                            tracing::warn!(
+                                %repository_url,
+                                "repository_url must be an http(s) URL, ignoring"
+                            );
+                        }
+                    }
+                    if let Some(timezone) = s.timezone {
+                        if timezone.parse::<chrono_tz::Tz>().is_ok() {
+                            cs.timezone = Some(timezone);
+                        } else {
+                            tracing::warn!(
+                                %timezone,
+                                "unknown timezone in binding settings, ignoring"
+                            );
+                        }
+                    }
+                    if let Some(locale) = s.locale {
+                        if crate::prompts::messages::is_supported(&locale) {
+                            cs.locale = Some(locale);
+                        } else {
+                            tracing::warn!(
+                                %locale,
+                                available = ?crate::prompts::messages::LOCALES,
+                                "unsupported locale in binding settings, ignoring"
+                            );
+                        }
+                    }
                     cs
                 });
                 Binding {
@@ -2536,6 +3545,15 @@ impl Config {
             port: toml.api.port,
             bind: hosted_api_bind(toml.api.bind),
             auth_token: toml.api.auth_token.as_deref().and_then(resolve_env_value),
+            oncall_token: toml.api.oncall_token.as_deref().and_then(resolve_env_value),
+            public_url: toml
+                .api
+                .public_url
+                .as_deref()
+                .and_then(resolve_env_value)
+                .map(|url| normalize_public_url(&url))
+                .transpose()?,
+            trust_forwarded_headers: toml.api.trust_forwarded_headers,
         };
 
         let metrics = MetricsConfig {
@@ -2602,7 +3620,20 @@ impl Config {
             .into_iter()
             .map(|h| {
                 let description = load_human_md(&humans_dir.join(&h.id));
-                HumanDef {
+                let access = h
+                    .access
+                    .as_deref()
+                    .map(|value| {
+                        crate::access::AccessTier::parse(value).ok_or_else(|| {
+                            ConfigError::Invalid(format!(
+                                "human '{}' has invalid access tier '{value}' \
+                                 (expected admin, developer, or read_only)",
+                                h.id
+                            ))
+                        })
+                    })
+                    .transpose()?;
+                Ok(HumanDef {
                     id: h.id,
                     display_name: h.display_name,
                     role: h.role,
@@ -2612,9 +3643,10 @@ impl Config {
                     telegram_id: h.telegram_id,
                     slack_id: h.slack_id,
                     email: h.email,
-                }
+                    access,
+                })
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         // Default admin human if none defined
         if humans.is_empty() {
@@ -2629,6 +3661,7 @@ impl Config {
                 telegram_id: None,
                 slack_id: None,
                 email: None,
+                access: None,
             });
 
             // Link the default admin to the default agent so the agent sees
@@ -2656,6 +3689,32 @@ impl Config {
                 .unwrap_or_else(|| MemoryJanitorConfig::default().interval_secs),
         };
 
+        let dead_letters = DeadLettersConfig {
+            capacity: toml
+                .dead_letters
+                .capacity
+                .unwrap_or_else(|| DeadLettersConfig::default().capacity),
+            file: toml.dead_letters.file.map(|file| instance_dir.join(file)),
+        };
+
+        let tenants = parse_tenants(toml.tenants, &agents)?;
+
+        let rate_limit = parse_rate_limit(toml.rate_limit)?;
+
+        let blob_store = parse_blob_store(toml.blob_store, &instance_dir)?;
+
+        let database = parse_database(toml.database)?;
+
+        let cluster = parse_cluster(toml.cluster, &database)?;
+
+        let event_bus = parse_event_bus(toml.event_bus)?;
+
+        let cost_report = parse_cost_report(toml.cost_report)?;
+
+        let digest = parse_digest(toml.digest)?;
+
+        let notify = parse_notify(toml.notify)?;
+
         Ok(Config {
             instance_dir,
             llm,
@@ -2670,6 +3729,16 @@ impl Config {
             metrics,
             telemetry,
             memory_janitor,
+            dead_letters,
+            tenants,
+            rate_limit,
+            blob_store,
+            database,
+            cluster,
+            event_bus,
+            cost_report,
+            digest,
+            notify,
         })
     }
 }
@@ -5,8 +5,9 @@ use arc_swap::ArcSwap;
 
 use super::{
     BrowserConfig, ChannelConfig, CoalesceConfig, CompactionConfig, Config, CortexConfig,
-    DefaultsConfig, IngestionConfig, McpServerConfig, MemoryPersistenceConfig, OpenCodeConfig,
-    ResolvedAgentConfig, ToolUseEnforcement, WarmupConfig, WarmupStatus, WorkReadiness,
+    DefaultsConfig, GitHubWebhookConfig, IngestionConfig, McpServerConfig, MemoryPersistenceConfig,
+    NotifyWebhookConfig, OpenCodeConfig, PrivateSessionConfig, ResolvedAgentConfig,
+    ToolUseEnforcement, TranscribeConfig, WarmupConfig, WarmupStatus, WorkReadiness,
     evaluate_work_readiness,
 };
 use crate::llm::routing::RoutingConfig;
@@ -32,6 +33,9 @@ pub struct RuntimeConfig {
     pub coalesce: ArcSwap<CoalesceConfig>,
     pub ingestion: ArcSwap<IngestionConfig>,
     pub channel_config: ArcSwap<ChannelConfig>,
+    /// Instance and agent tool rendering detail, merged. Channel overrides
+    /// are layered on top at render time.
+    pub tool_rendering: ArcSwap<crate::conversation::settings::ToolRenderingSettings>,
     pub max_turns: ArcSwap<usize>,
     pub branch_max_turns: ArcSwap<usize>,
     pub context_window: ArcSwap<usize>,
@@ -43,6 +47,14 @@ pub struct RuntimeConfig {
     pub history_backfill_count: ArcSwap<usize>,
     pub brave_search_key: ArcSwap<Option<String>>,
     pub cron_timezone: ArcSwap<Option<String>>,
+    /// GitHub webhook secret and event mappings.
+    pub github: ArcSwap<GitHubWebhookConfig>,
+    /// Outgoing session lifecycle webhooks.
+    pub notify_webhooks: ArcSwap<Vec<NotifyWebhookConfig>>,
+    /// Prompt templates from config for `/run`.
+    pub templates: ArcSwap<Vec<crate::templates::PromptTemplate>>,
+    /// Voice note transcription settings.
+    pub transcribe: ArcSwap<Option<TranscribeConfig>>,
     pub user_timezone: ArcSwap<Option<String>>,
     pub cortex: ArcSwap<CortexConfig>,
     pub warmup: ArcSwap<WarmupConfig>,
@@ -70,6 +82,8 @@ pub struct RuntimeConfig {
     pub opencode: ArcSwap<OpenCodeConfig>,
     /// Shared pool of OpenCode server processes. Lazily initialized on first use.
     pub opencode_server_pool: ArcSwap<crate::opencode::OpenCodeServerPool>,
+    /// Defaults and tool policy for direct-message sessions.
+    pub private_sessions: ArcSwap<PrivateSessionConfig>,
     /// Cron store, set after agent initialization.
     pub cron_store: ArcSwap<Option<Arc<crate::cron::CronStore>>>,
     /// Cron scheduler, set after agent initialization.
@@ -91,6 +105,9 @@ pub struct RuntimeConfig {
     pub working_memory: ArcSwap<crate::config::types::WorkingMemoryConfig>,
     /// Participant context configuration for prompt-time participant awareness.
     pub participant_context: ArcSwap<crate::config::types::ParticipantContextConfig>,
+    /// `[api] public_url` for dashboard links posted in chat. Instance-level,
+    /// set after construction and on reload.
+    pub public_url: ArcSwap<Option<String>>,
     /// Shared browser state for persistent sessions.
     ///
     /// When `browser.persist_session = true`, all workers share this handle so
@@ -114,6 +131,7 @@ impl RuntimeConfig {
             opencode_config.path.clone(),
             opencode_config.permissions.clone(),
             opencode_config.max_servers,
+            opencode_config.backends.clone(),
         );
 
         Self {
@@ -126,6 +144,7 @@ impl RuntimeConfig {
             coalesce: ArcSwap::from_pointee(agent_config.coalesce),
             ingestion: ArcSwap::from_pointee(agent_config.ingestion),
             channel_config: ArcSwap::from_pointee(agent_config.channel),
+            tool_rendering: ArcSwap::from_pointee(agent_config.tool_rendering.clone()),
             max_turns: ArcSwap::from_pointee(agent_config.max_turns),
             branch_max_turns: ArcSwap::from_pointee(agent_config.branch_max_turns),
             context_window: ArcSwap::from_pointee(agent_config.context_window),
@@ -137,6 +156,10 @@ impl RuntimeConfig {
             history_backfill_count: ArcSwap::from_pointee(agent_config.history_backfill_count),
             brave_search_key: ArcSwap::from_pointee(agent_config.brave_search_key.clone()),
             cron_timezone: ArcSwap::from_pointee(agent_config.cron_timezone.clone()),
+            github: ArcSwap::from_pointee(agent_config.github.clone()),
+            notify_webhooks: ArcSwap::from_pointee(agent_config.notify_webhooks.clone()),
+            templates: ArcSwap::from_pointee(agent_config.templates.clone()),
+            transcribe: ArcSwap::from_pointee(agent_config.transcribe.clone()),
             user_timezone: ArcSwap::from_pointee(agent_config.user_timezone.clone()),
             cortex: ArcSwap::from_pointee(agent_config.cortex),
             warmup: ArcSwap::from_pointee(agent_config.warmup),
@@ -152,6 +175,7 @@ impl RuntimeConfig {
             skills: ArcSwap::from_pointee(skills),
             opencode: ArcSwap::from_pointee(defaults.opencode.clone()),
             opencode_server_pool: ArcSwap::from_pointee(server_pool),
+            private_sessions: ArcSwap::from_pointee(defaults.private_sessions.clone()),
             cron_store: ArcSwap::from_pointee(None),
             cron_scheduler: ArcSwap::from_pointee(None),
             settings: ArcSwap::from_pointee(None),
@@ -163,6 +187,7 @@ impl RuntimeConfig {
                 crate::config::types::WorkingMemoryConfig::default(),
             ),
             participant_context: ArcSwap::from_pointee(defaults.participant_context),
+            public_url: ArcSwap::from_pointee(None),
             shared_browser: if agent_config.browser.persist_session {
                 Some(crate::tools::browser::new_shared_browser_handle())
             } else {
@@ -215,6 +240,15 @@ impl RuntimeConfig {
         self.work_readiness().ready
     }
 
+    /// Absolute dashboard URL for a SPA route like `/agents/main/workers`, or
+    /// `None` when no `public_url` is configured.
+    pub fn dashboard_link(&self, route: &str) -> Option<String> {
+        self.public_url
+            .load()
+            .as_deref()
+            .map(|base| format!("{base}{route}"))
+    }
+
     /// Path to the saved attachments directory for persisted channel files.
     pub fn saved_dir(&self) -> std::path::PathBuf {
         self.workspace_dir.join("saved")
@@ -242,6 +276,8 @@ impl RuntimeConfig {
         let old_mcp = (**self.mcp.load()).clone();
         let new_mcp = resolved.mcp.clone();
 
+        self.public_url
+            .store(Arc::new(config.api.public_url.clone()));
         self.routing.store(Arc::new(resolved.routing));
         self.compaction.store(Arc::new(resolved.compaction));
         self.memory_persistence
@@ -250,6 +286,8 @@ impl RuntimeConfig {
         self.ingestion.store(Arc::new(resolved.ingestion));
         let resolved_channel = resolved.channel;
         self.channel_config.store(Arc::new(resolved_channel));
+        self.tool_rendering
+            .store(Arc::new(resolved.tool_rendering.clone()));
         self.max_turns.store(Arc::new(resolved.max_turns));
         self.branch_max_turns
             .store(Arc::new(resolved.branch_max_turns));
@@ -277,6 +315,11 @@ impl RuntimeConfig {
         self.brave_search_key
             .store(Arc::new(resolved.brave_search_key));
         self.cron_timezone.store(Arc::new(resolved.cron_timezone));
+        self.github.store(Arc::new(resolved.github.clone()));
+        self.notify_webhooks
+            .store(Arc::new(resolved.notify_webhooks.clone()));
+        self.templates.store(Arc::new(resolved.templates.clone()));
+        self.transcribe.store(Arc::new(resolved.transcribe.clone()));
         self.user_timezone.store(Arc::new(resolved.user_timezone));
         self.cortex.store(Arc::new(resolved.cortex));
         self.warmup.store(Arc::new(resolved.warmup));
@@ -290,23 +333,28 @@ impl RuntimeConfig {
         self.sandbox.store(Arc::new(new_sandbox));
         self.projects.store(Arc::new(resolved.projects.clone()));
 
+        self.private_sessions
+            .store(Arc::new(config.defaults.private_sessions.clone()));
+
         let old_opencode = self.opencode.load().as_ref().clone();
         let new_opencode = config.defaults.opencode.clone();
         self.opencode.store(Arc::new(new_opencode.clone()));
 
         let should_rebuild_opencode_pool = old_opencode.path != new_opencode.path
             || old_opencode.max_servers != new_opencode.max_servers
-            || old_opencode.permissions != new_opencode.permissions;
+            || old_opencode.permissions != new_opencode.permissions
+            || old_opencode.backends != new_opencode.backends;
         if should_rebuild_opencode_pool {
             let new_pool = crate::opencode::OpenCodeServerPool::new(
                 new_opencode.path.clone(),
                 new_opencode.permissions.clone(),
                 new_opencode.max_servers,
+                new_opencode.backends.clone(),
             );
             self.opencode_server_pool.store(Arc::new(new_pool));
             tracing::info!(
                 agent_id,
-                "reloaded opencode server pool with updated path/permissions/limits"
+                "reloaded opencode server pool with updated path/permissions/limits/backends"
             );
         }
 
@@ -31,6 +31,94 @@ pub(super) struct TomlConfig {
     pub(super) telemetry: TomlTelemetryConfig,
     #[serde(default)]
     pub(super) memory_janitor: TomlMemoryJanitorConfig,
+    #[serde(default)]
+    pub(super) dead_letters: TomlDeadLettersConfig,
+    #[serde(default)]
+    pub(super) tenants: Vec<TomlTenantConfig>,
+    #[serde(default)]
+    pub(super) rate_limit: TomlRateLimitConfig,
+    #[serde(default)]
+    pub(super) blob_store: TomlBlobStoreConfig,
+    #[serde(default)]
+    pub(super) database: TomlDatabaseConfig,
+    #[serde(default)]
+    pub(super) cluster: TomlClusterConfig,
+    #[serde(default)]
+    pub(super) event_bus: TomlEventBusConfig,
+    #[serde(default)]
+    pub(super) cost_report: TomlCostReportConfig,
+    #[serde(default)]
+    pub(super) digest: TomlDigestConfig,
+    #[serde(default)]
+    pub(super) notify: TomlNotifyConfig,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlNotifyConfig {
+    pub(super) ops_channel: Option<String>,
+    pub(super) min_severity: Option<String>,
+    pub(super) dedupe_window_secs: Option<u64>,
+    pub(super) reconnect_storm_threshold: Option<u32>,
+    pub(super) reconnect_storm_window_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlCostReportConfig {
+    pub(super) post_to: Option<String>,
+    pub(super) weekday: Option<String>,
+    pub(super) hour: Option<u32>,
+    pub(super) locale: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlDigestConfig {
+    pub(super) post_to: Option<String>,
+    pub(super) weekday: Option<String>,
+    pub(super) hour: Option<u32>,
+    pub(super) locale: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlBlobStoreConfig {
+    pub(super) backend: Option<String>,
+    pub(super) path: Option<String>,
+    pub(super) endpoint: Option<String>,
+    pub(super) region: Option<String>,
+    pub(super) bucket: Option<String>,
+    pub(super) prefix: Option<String>,
+    pub(super) access_key_id: Option<String>,
+    pub(super) secret_access_key: Option<String>,
+    pub(super) path_style: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlDatabaseConfig {
+    pub(super) backend: Option<String>,
+    pub(super) url: Option<String>,
+    pub(super) max_connections: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlClusterConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) replica_id: Option<String>,
+    pub(super) lease_ttl_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlEventBusConfig {
+    pub(super) backend: Option<String>,
+    pub(super) url: Option<String>,
+    pub(super) channel: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlRateLimitConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) user_burst: Option<u32>,
+    pub(super) user_per_minute: Option<u32>,
+    pub(super) channel_burst: Option<u32>,
+    pub(super) channel_per_minute: Option<u32>,
 }
 
 #[derive(Deserialize, Default)]
@@ -39,6 +127,37 @@ pub(super) struct TomlMemoryJanitorConfig {
     pub(super) interval_secs: Option<u64>,
 }
 
+#[derive(Deserialize, Default)]
+pub(super) struct TomlDeadLettersConfig {
+    pub(super) capacity: Option<usize>,
+    pub(super) file: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlTenantConfig {
+    pub(super) id: String,
+    /// Defaults to the tenant ID.
+    pub(super) agent_id: Option<String>,
+    #[serde(default)]
+    pub(super) discord_guild_ids: Vec<String>,
+    #[serde(default)]
+    pub(super) slack_workspace_ids: Vec<String>,
+    pub(super) daily_message_budget: Option<u64>,
+    #[serde(default)]
+    pub(super) content_filters: Vec<TomlContentFilterRule>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlContentFilterRule {
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) patterns: Vec<String>,
+    #[serde(default)]
+    pub(super) words: Vec<String>,
+    /// `mask`, `block`, or `flag`.
+    pub(super) action: String,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlLinkDef {
     pub(super) from: String,
@@ -78,6 +197,7 @@ pub(super) struct TomlHumanDef {
     pub(super) telegram_id: Option<String>,
     pub(super) slack_id: Option<String>,
     pub(super) email: Option<String>,
+    pub(super) access: Option<String>,
 }
 
 #[derive(Deserialize, Default)]
@@ -98,6 +218,12 @@ pub(super) struct TomlApiConfig {
     pub(super) bind: String,
     #[serde(default)]
     pub(super) auth_token: Option<String>,
+    #[serde(default)]
+    pub(super) oncall_token: Option<String>,
+    #[serde(default)]
+    pub(super) public_url: Option<String>,
+    #[serde(default)]
+    pub(super) trust_forwarded_headers: bool,
 }
 
 impl Default for TomlApiConfig {
@@ -107,6 +233,9 @@ impl Default for TomlApiConfig {
             port: default_api_port(),
             bind: default_api_bind(),
             auth_token: None,
+            oncall_token: None,
+            public_url: None,
+            trust_forwarded_headers: false,
         }
     }
 }
@@ -302,12 +431,14 @@ pub(super) struct TomlDefaultsConfig {
     pub(super) participant_context: Option<TomlParticipantContextConfig>,
     pub(super) browser: Option<TomlBrowserConfig>,
     pub(super) channel: Option<TomlChannelConfig>,
+    pub(super) tool_rendering: Option<TomlToolRenderingConfig>,
     #[serde(default)]
     pub(super) mcp: Vec<TomlMcpServerConfig>,
     pub(super) brave_search_key: Option<String>,
     pub(super) cron_timezone: Option<String>,
     pub(super) user_timezone: Option<String>,
     pub(super) opencode: Option<TomlOpenCodeConfig>,
+    pub(super) private_sessions: Option<TomlPrivateSessionConfig>,
     pub(super) worker_log_mode: Option<String>,
     pub(super) projects: Option<TomlProjectsConfig>,
 }
@@ -414,11 +545,35 @@ pub(super) struct TomlBrowserConfig {
     pub(super) close_policy: Option<String>,
 }
 
+#[derive(Deserialize, Default)]
+pub(super) struct TomlToolRenderingConfig {
+    pub(super) default: Option<String>,
+    #[serde(default)]
+    pub(super) tools: HashMap<String, String>,
+    pub(super) summarize: Option<TomlToolSummaryConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlToolSummaryConfig {
+    pub(super) min_lines: Option<usize>,
+    pub(super) model: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlChannelConfig {
     pub(super) listen_only_mode: Option<bool>,
     pub(super) response_mode: Option<String>,
     pub(super) save_attachments: Option<bool>,
+    pub(super) abort_on_prompt_deleted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlPrivateSessionConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) model: Option<String>,
+    pub(super) memory: Option<crate::conversation::settings::MemoryMode>,
+    pub(super) allow_opencode: Option<bool>,
+    pub(super) worker_tier: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -429,6 +584,37 @@ pub(super) struct TomlOpenCodeConfig {
     pub(super) server_startup_timeout_secs: Option<u64>,
     pub(super) max_restart_retries: Option<u32>,
     pub(super) permissions: Option<TomlOpenCodePermissions>,
+    #[serde(default)]
+    pub(super) backends: Vec<TomlOpenCodeBackend>,
+    pub(super) server_logs: Option<TomlOpenCodeServerLogConfig>,
+    pub(super) session_ttl_secs: Option<u64>,
+    pub(super) rename_threads: Option<bool>,
+    pub(super) dry_run: Option<bool>,
+    pub(super) question_timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOpenCodeServerLogConfig {
+    pub(super) forward_to: Option<String>,
+    pub(super) min_level: Option<String>,
+    pub(super) max_per_minute: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOpenCodeBackend {
+    pub(super) name: String,
+    pub(super) url: String,
+    pub(super) directory: String,
+    pub(super) bearer_token: Option<String>,
+    pub(super) basic_username: Option<String>,
+    pub(super) basic_password: Option<String>,
+    #[serde(default)]
+    pub(super) headers: HashMap<String, String>,
+    pub(super) client_cert: Option<String>,
+    pub(super) client_key: Option<String>,
+    pub(super) ca_cert: Option<String>,
+    #[serde(default)]
+    pub(super) mirror: bool,
 }
 
 #[derive(Deserialize)]
@@ -493,6 +679,7 @@ pub(super) struct TomlAgentConfig {
     pub(super) warmup: Option<TomlWarmupConfig>,
     pub(super) browser: Option<TomlBrowserConfig>,
     pub(super) channel: Option<TomlChannelConfig>,
+    pub(super) tool_rendering: Option<TomlToolRenderingConfig>,
     pub(super) mcp: Option<Vec<TomlMcpServerConfig>>,
     pub(super) brave_search_key: Option<String>,
     pub(super) cron_timezone: Option<String>,
@@ -501,6 +688,52 @@ pub(super) struct TomlAgentConfig {
     pub(super) projects: Option<TomlProjectsConfig>,
     #[serde(default)]
     pub(super) cron: Vec<TomlCronDef>,
+    pub(super) github: Option<TomlGitHubWebhookConfig>,
+    #[serde(default)]
+    pub(super) notify_webhooks: Vec<TomlNotifyWebhookConfig>,
+    #[serde(default)]
+    pub(super) templates: Vec<TomlPromptTemplate>,
+    pub(super) transcribe: Option<TomlTranscribeConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlTranscribeConfig {
+    pub(super) base_url: Option<String>,
+    pub(super) api_key: Option<String>,
+    pub(super) model: Option<String>,
+    pub(super) language: Option<String>,
+    pub(super) echo: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlPromptTemplate {
+    pub(super) name: String,
+    pub(super) prompt: String,
+    pub(super) description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlNotifyWebhookConfig {
+    pub(super) url: String,
+    pub(super) secret: Option<String>,
+    pub(super) events: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlGitHubWebhookConfig {
+    pub(super) secret: Option<String>,
+    pub(super) token: Option<String>,
+    #[serde(default)]
+    pub(super) mappings: Vec<TomlGitHubEventMapping>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlGitHubEventMapping {
+    pub(super) event: String,
+    pub(super) repository: Option<String>,
+    pub(super) prompt: String,
+    pub(super) deliver_to: String,
+    pub(super) timeout_secs: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -534,6 +767,37 @@ pub(super) struct TomlMessagingConfig {
     pub(super) signal: Option<TomlSignalConfig>,
     #[serde(default)]
     pub(super) mattermost: Option<TomlMattermostConfig>,
+    pub(super) irc: Option<TomlIrcConfig>,
+    #[serde(default)]
+    pub(super) redaction: Option<TomlRedactionConfig>,
+    #[serde(default)]
+    pub(super) overflow: Option<TomlOverflowConfig>,
+    #[serde(default)]
+    pub(super) links: Option<TomlLinksConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlLinksConfig {
+    pub(super) suppress_unfurls: Option<bool>,
+    pub(super) strip_tracking: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOverflowConfig {
+    pub(super) mode: Option<String>,
+    pub(super) preview_chars: Option<usize>,
+    pub(super) paste_url: Option<String>,
+    pub(super) paste_token: Option<String>,
+    pub(super) paste_url_field: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlRedactionConfig {
+    pub(super) enabled: Option<bool>,
+    #[serde(default)]
+    pub(super) patterns: Vec<String>,
+    #[serde(default)]
+    pub(super) env_vars: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -647,6 +911,9 @@ pub(super) struct TomlEmailConfig {
     #[serde(default = "default_email_max_attachment_bytes")]
     pub(super) max_attachment_bytes: usize,
     #[serde(default)]
+    pub(super) prompt_addresses: Vec<String>,
+    pub(super) webhook: Option<TomlEmailWebhookConfig>,
+    #[serde(default)]
     pub(super) instances: Vec<TomlEmailInstanceConfig>,
 }
 
@@ -681,6 +948,18 @@ pub(super) struct TomlEmailInstanceConfig {
     pub(super) max_body_bytes: usize,
     #[serde(default = "default_email_max_attachment_bytes")]
     pub(super) max_attachment_bytes: usize,
+    #[serde(default)]
+    pub(super) prompt_addresses: Vec<String>,
+    pub(super) webhook: Option<TomlEmailWebhookConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlEmailWebhookConfig {
+    #[serde(default = "default_webhook_bind")]
+    pub(super) bind: String,
+    #[serde(default = "default_email_webhook_port")]
+    pub(super) port: u16,
+    pub(super) auth_token: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -694,6 +973,32 @@ pub(super) struct TomlWebhookConfig {
     pub(super) auth_token: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlIrcConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    pub(super) server: String,
+    pub(super) port: Option<u16>,
+    #[serde(default = "default_irc_tls")]
+    pub(super) tls: bool,
+    pub(super) nickname: String,
+    pub(super) password: Option<String>,
+    pub(super) nickserv_password: Option<String>,
+    #[serde(default)]
+    pub(super) channels: Vec<String>,
+    #[serde(default = "default_irc_command_prefix")]
+    pub(super) command_prefix: String,
+    #[serde(default)]
+    pub(super) allowed_nicks: Vec<String>,
+}
+
+pub(super) fn default_irc_tls() -> bool {
+    true
+}
+pub(super) fn default_irc_command_prefix() -> String {
+    "!".into()
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlTwitchConfig {
     #[serde(default)]
@@ -803,6 +1108,10 @@ pub(super) fn default_email_max_attachment_bytes() -> usize {
     10 * 1024 * 1024
 }
 
+pub(super) fn default_email_webhook_port() -> u16 {
+    18790
+}
+
 /// Conversation settings that can be set on a binding as defaults for matched channels.
 #[derive(Deserialize, Default)]
 pub(super) struct TomlConversationSettings {
@@ -811,6 +1120,15 @@ pub(super) struct TomlConversationSettings {
     pub(super) delegation: Option<String>,
     pub(super) response_mode: Option<String>,
     pub(super) save_attachments: Option<bool>,
+    pub(super) tool_rendering: Option<TomlToolRenderingConfig>,
+    pub(super) plan_approval: Option<bool>,
+    pub(super) fallback_models: Option<Vec<String>>,
+    pub(super) locale: Option<String>,
+    pub(super) dry_run: Option<bool>,
+    pub(super) delivery: Option<String>,
+    pub(super) collapse: Option<bool>,
+    pub(super) repository_url: Option<String>,
+    pub(super) timezone: Option<String>,
 }
 
 #[derive(Deserialize)]
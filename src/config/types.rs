@@ -63,6 +63,232 @@ pub struct Config {
     /// (their cortex tick never runs maintenance), additive on active-mode
     /// agents.
     pub memory_janitor: MemoryJanitorConfig,
+    /// Capture of SSE events that fail to parse.
+    pub dead_letters: DeadLettersConfig,
+    /// Discord guilds and Slack workspaces served in isolation.
+    pub tenants: Vec<TenantConfig>,
+    /// Token-bucket limits on inbound prompts.
+    pub rate_limit: RateLimitConfig,
+    /// Backend for large artifacts referenced by blob ID.
+    pub blob_store: BlobStoreConfig,
+    /// Backend for instance-level stores that replicas can share.
+    pub database: DatabaseConfig,
+    /// Session ownership across replicas sharing one bot token.
+    pub cluster: ClusterConfig,
+    /// Where dashboard events are published.
+    pub event_bus: EventBusConfig,
+    /// Weekly spend report posted to an admin channel.
+    pub cost_report: CostReportConfig,
+    /// Weekly activity digest posted to a channel.
+    pub digest: DigestConfig,
+    /// Ops alerts: where operational problems are posted.
+    pub notify: NotifyConfig,
+}
+
+/// Routing of ops alerts (failed sessions, reconnect storms, budget
+/// breaches, dead letters) to an ops channel.
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    /// Delivery target (`adapter:target`). No alerts are posted when unset.
+    pub ops_channel: Option<crate::messaging::target::BroadcastTarget>,
+    /// Alerts below this severity are dropped. Default `info`.
+    pub min_severity: crate::notifications::NotificationSeverity,
+    /// An identical alert is posted at most once per window. Default 900.
+    pub dedupe_window_secs: u64,
+    /// Reconnects within `reconnect_storm_window_secs` that make a storm.
+    /// Default 5.
+    pub reconnect_storm_threshold: u32,
+    /// Default 300.
+    pub reconnect_storm_window_secs: u64,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            ops_channel: None,
+            min_severity: crate::notifications::NotificationSeverity::Info,
+            dedupe_window_secs: 900,
+            reconnect_storm_threshold: 5,
+            reconnect_storm_window_secs: 300,
+        }
+    }
+}
+
+/// Weekly per-user and per-model spend report.
+#[derive(Debug, Clone)]
+pub struct CostReportConfig {
+    /// Delivery target (`adapter:target`). No report when unset.
+    pub post_to: Option<crate::messaging::target::BroadcastTarget>,
+    /// Day the report is posted, in UTC. Default Monday.
+    pub weekday: chrono::Weekday,
+    /// Hour (0-23, UTC) the report is posted. Default 9.
+    pub hour: u32,
+    /// Locale the report is written in.
+    pub locale: String,
+}
+
+impl Default for CostReportConfig {
+    fn default() -> Self {
+        Self {
+            post_to: None,
+            weekday: chrono::Weekday::Mon,
+            hour: 9,
+            locale: crate::prompts::messages::DEFAULT_LOCALE.to_string(),
+        }
+    }
+}
+
+/// Weekly digest of sessions, spend, tools, errors, and changed files.
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// Delivery target (`adapter:target`). No digest when unset.
+    pub post_to: Option<crate::messaging::target::BroadcastTarget>,
+    /// Day the digest is posted, in UTC. Default Friday.
+    pub weekday: chrono::Weekday,
+    /// Hour (0-23, UTC) the digest is posted. Default 16.
+    pub hour: u32,
+    /// Locale the digest is written in.
+    pub locale: String,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            post_to: None,
+            weekday: chrono::Weekday::Fri,
+            hour: 16,
+            locale: crate::prompts::messages::DEFAULT_LOCALE.to_string(),
+        }
+    }
+}
+
+/// Where large artifacts (exported transcripts, full tool outputs,
+/// attachments) are stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlobStoreConfig {
+    /// Files under `root`. Defaults to `<instance>/data/blobs`.
+    Filesystem { root: PathBuf },
+    /// An S3-compatible bucket.
+    S3(S3BlobConfig),
+}
+
+impl BlobStoreConfig {
+    /// The filesystem store inside `instance_dir`.
+    pub fn default_for(instance_dir: &std::path::Path) -> Self {
+        Self::Filesystem {
+            root: instance_dir.join("data").join("blobs"),
+        }
+    }
+}
+
+/// Connection details for an S3-compatible blob bucket.
+#[derive(Clone, PartialEq, Eq)]
+pub struct S3BlobConfig {
+    /// Service endpoint, e.g. `https://s3.us-east-1.amazonaws.com`.
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// Prepended to every object key.
+    pub prefix: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Address the bucket in the path instead of the host name, as MinIO
+    /// and most self-hosted stores expect.
+    pub path_style: bool,
+}
+
+impl std::fmt::Debug for S3BlobConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3BlobConfig")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("path_style", &self.path_style)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where the dashboard notification inbox is kept, and the session lease
+/// table for `[cluster]`. Every other store (tasks, projects, the wiki, and
+/// each agent's `agent.db`) stays in local SQLite whatever this is set to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DatabaseConfig {
+    /// `<instance>/data/spacebot.db`, private to this instance.
+    #[default]
+    Sqlite,
+    /// A Postgres database shared by every replica, holding notifications
+    /// and session leases only.
+    Postgres(PostgresConfig),
+}
+
+/// Connection details for a shared Postgres database.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PostgresConfig {
+    /// `postgres://` connection URL, including credentials.
+    pub url: String,
+    pub max_connections: u32,
+}
+
+impl std::fmt::Debug for PostgresConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresConfig")
+            .field("url", &"[REDACTED]")
+            .field("max_connections", &self.max_connections)
+            .finish()
+    }
+}
+
+/// Running several replicas behind the same bot tokens. Each session is
+/// leased to one replica in the shared Postgres database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterConfig {
+    pub enabled: bool,
+    /// This replica's name in the lease table. Defaults to `$HOSTNAME`.
+    pub replica_id: String,
+    /// Seconds a lease outlives its last renewal, and so how long a dead
+    /// replica's sessions wait before another replica takes them. Default 30.
+    pub lease_ttl_secs: u64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            replica_id: String::new(),
+            lease_ttl_secs: 30,
+        }
+    }
+}
+
+/// Where the events behind the dashboard's SSE stream are published.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EventBusConfig {
+    /// An in-process broadcast channel. Only this replica's SSE clients see
+    /// its events.
+    #[default]
+    Local,
+    /// Redis pub/sub, so every replica's SSE clients see every replica's
+    /// events, and other services can subscribe to them.
+    Redis(RedisConfig),
+}
+
+/// Connection details for a Redis pub/sub channel.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RedisConfig {
+    /// `redis://[[user]:password@]host[:port]` connection URL.
+    pub url: String,
+    /// Pub/sub channel events are published on. Default `spacebot:events`.
+    pub channel: String,
+}
+
+impl std::fmt::Debug for RedisConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisConfig")
+            .field("url", &"[REDACTED]")
+            .field("channel", &self.channel)
+            .finish()
+    }
 }
 
 /// Instance-wide memory maintenance scheduler.
@@ -86,6 +312,59 @@ impl Default for MemoryJanitorConfig {
     }
 }
 
+/// Retention for SSE events whose envelope fails to parse.
+#[derive(Debug, Clone)]
+pub struct DeadLettersConfig {
+    /// Entries kept in memory for `/api/debug/dead-letters`. Default `200`;
+    /// `0` keeps none.
+    pub capacity: usize,
+    /// JSONL file every dead letter is appended to. Unset by default.
+    pub file: Option<PathBuf>,
+}
+
+impl Default for DeadLettersConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 200,
+            file: None,
+        }
+    }
+}
+
+/// Limits on how fast users can send prompts, checked before routing.
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Disabled by default.
+    pub enabled: bool,
+    /// Bucket per sender, across all conversations.
+    pub user: TokenBucketConfig,
+    /// Bucket per conversation, across all senders.
+    pub channel: TokenBucketConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            user: TokenBucketConfig {
+                burst: 5,
+                per_minute: 10,
+            },
+            channel: TokenBucketConfig {
+                burst: 20,
+                per_minute: 40,
+            },
+        }
+    }
+}
+
+/// A token bucket that holds `burst` prompts and refills at `per_minute`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBucketConfig {
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
 impl Config {
     /// Get the default agent ID.
     pub fn default_agent_id(&self) -> &str {
@@ -142,6 +421,10 @@ pub struct HumanDef {
     pub slack_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    /// Authorization tier for chat commands. Once any human has a tier,
+    /// unmapped senders are treated as read-only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<crate::access::AccessTier>,
 }
 
 /// A visual group definition for the topology UI.
@@ -163,6 +446,17 @@ pub struct ApiConfig {
     /// Address to bind the HTTP server on.
     pub bind: String,
     pub auth_token: Option<String>,
+    /// Read-only token for the `/oncall` page. It only lists sessions and
+    /// follows their streams, so the page never needs `auth_token`.
+    pub oncall_token: Option<String>,
+    /// Externally reachable base URL (e.g. `https://example.com/spacebot`)
+    /// used for generated links. A path component mounts the HTTP API and
+    /// dashboard under that subpath. Stored without a trailing slash.
+    pub public_url: Option<String>,
+    /// Derive the external base from `Forwarded` / `X-Forwarded-*` request
+    /// headers when `public_url` is unset. Only enable behind a proxy that
+    /// overwrites these headers.
+    pub trust_forwarded_headers: bool,
 }
 
 impl Default for ApiConfig {
@@ -172,10 +466,36 @@ impl Default for ApiConfig {
             port: 19898,
             bind: "127.0.0.1".into(),
             auth_token: None,
+            oncall_token: None,
+            public_url: None,
+            trust_forwarded_headers: false,
         }
     }
 }
 
+/// Validate `[api] public_url` and strip its trailing slash.
+pub(crate) fn normalize_public_url(url: &str) -> Result<String> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| ConfigError::Invalid(format!("invalid api public_url '{url}': {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ConfigError::Invalid(format!(
+            "api public_url must use http or https, got: {}",
+            parsed.scheme()
+        ))
+        .into());
+    }
+    if parsed.host().is_none() {
+        return Err(ConfigError::Invalid(format!("api public_url has no host: {url}")).into());
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err(ConfigError::Invalid(
+            "api public_url must not contain a query string or fragment".to_string(),
+        )
+        .into());
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
 /// Prometheus metrics endpoint configuration.
 #[derive(Debug, Clone)]
 pub struct MetricsConfig {
@@ -645,6 +965,8 @@ pub struct DefaultsConfig {
     pub participant_context: ParticipantContextConfig,
     pub browser: BrowserConfig,
     pub channel: ChannelConfig,
+    /// Instance-wide tool rendering detail (the top of the hierarchy).
+    pub tool_rendering: crate::conversation::settings::ToolRenderingSettings,
     pub mcp: Vec<McpServerConfig>,
     /// Brave Search API key for web search tool. Supports "env:VAR_NAME" references.
     pub brave_search_key: Option<String>,
@@ -658,6 +980,8 @@ pub struct DefaultsConfig {
     /// "auto" (default) — matches GPT/Codex models; true — always inject; false — never inject.
     pub tool_use_enforcement: ToolUseEnforcement,
     pub opencode: OpenCodeConfig,
+    /// Defaults and tool policy for direct-message sessions.
+    pub private_sessions: PrivateSessionConfig,
     /// Worker log mode: "errors_only", "all_separate", or "all_combined".
     pub worker_log_mode: crate::settings::WorkerLogMode,
     /// Projects workspace management defaults.
@@ -682,6 +1006,7 @@ impl std::fmt::Debug for DefaultsConfig {
             .field("participant_context", &self.participant_context)
             .field("browser", &self.browser)
             .field("channel", &self.channel)
+            .field("tool_rendering", &self.tool_rendering)
             .field("mcp", &self.mcp)
             .field(
                 "brave_search_key",
@@ -693,6 +1018,7 @@ impl std::fmt::Debug for DefaultsConfig {
             .field("cron", &self.cron)
             .field("tool_use_enforcement", &self.tool_use_enforcement)
             .field("opencode", &self.opencode)
+            .field("private_sessions", &self.private_sessions)
             .field("worker_log_mode", &self.worker_log_mode)
             .field("projects", &self.projects)
             .finish()
@@ -1018,6 +1344,9 @@ pub struct ChannelConfig {
     /// `workspace/saved/` and tracked in the `saved_attachments` table so
     /// they can be recalled on later turns.
     pub save_attachments: bool,
+    /// When true, deleting the message that started the current turn cancels
+    /// the workers and branches it spawned.
+    pub abort_on_prompt_deleted: bool,
 }
 
 impl Default for ChannelConfig {
@@ -1026,6 +1355,35 @@ impl Default for ChannelConfig {
             listen_only_mode: false,
             response_mode: None,
             save_attachments: true,
+            abort_on_prompt_deleted: false,
+        }
+    }
+}
+
+/// Direct-message sessions with the bot (`[defaults.private_sessions]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateSessionConfig {
+    /// Route DMs to one session per user and apply the settings below.
+    pub enabled: bool,
+    /// Model for private sessions unless the conversation picks its own.
+    pub model: Option<String>,
+    /// Memory mode for private sessions. Defaults to ambient so a DM can
+    /// read the agent's memories without writing what was said into them.
+    pub memory: crate::conversation::settings::MemoryMode,
+    /// Whether a private session may spawn OpenCode workers.
+    pub allow_opencode: bool,
+    /// Lowest tier allowed to spawn workers from a private session.
+    pub worker_tier: crate::access::AccessTier,
+}
+
+impl Default for PrivateSessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: None,
+            memory: crate::conversation::settings::MemoryMode::Ambient,
+            allow_opencode: false,
+            worker_tier: crate::access::AccessTier::Developer,
         }
     }
 }
@@ -1046,6 +1404,45 @@ pub struct OpenCodeConfig {
     pub max_restart_retries: u32,
     /// Permission settings passed to OpenCode's config.
     pub permissions: crate::opencode::OpenCodePermissions,
+    /// Externally managed OpenCode servers, used instead of spawning a local
+    /// subprocess when a worker targets the backend's directory.
+    pub backends: Vec<OpenCodeBackendConfig>,
+    /// Forwarding of server-level warnings to an ops channel.
+    pub server_logs: OpenCodeServerLogConfig,
+    /// Seconds an interactive session may sit idle before it is closed and
+    /// its transcript archived. Zero keeps sessions open indefinitely.
+    pub session_ttl_secs: u64,
+    /// Rename the chat thread a session runs in after the session's title.
+    pub rename_threads: bool,
+    /// Post the prompts OpenCode workers would send instead of sending them.
+    /// Channels override this with `/dryrun`.
+    pub dry_run: bool,
+    /// Seconds a question from OpenCode waits for an answer in chat before
+    /// its first option is picked, and a permission request waits for an
+    /// admin before it is rejected. Zero answers both without asking.
+    pub question_timeout_secs: u64,
+}
+
+/// Where OpenCode server log entries are forwarded, and how many.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenCodeServerLogConfig {
+    /// Delivery target (`adapter:target`). Unset disables forwarding.
+    pub forward_to: Option<crate::messaging::target::BroadcastTarget>,
+    /// Least severe level forwarded.
+    pub min_level: crate::opencode::types::ServerLogLevel,
+    /// Forwarded entries per minute; extras are counted and reported with
+    /// the next forwarded entry.
+    pub max_per_minute: u32,
+}
+
+impl Default for OpenCodeServerLogConfig {
+    fn default() -> Self {
+        Self {
+            forward_to: None,
+            min_level: crate::opencode::types::ServerLogLevel::Warn,
+            max_per_minute: 5,
+        }
+    }
 }
 
 impl Default for OpenCodeConfig {
@@ -1057,6 +1454,118 @@ impl Default for OpenCodeConfig {
             server_startup_timeout_secs: 30,
             max_restart_retries: 5,
             permissions: crate::opencode::OpenCodePermissions::default(),
+            backends: Vec::new(),
+            server_logs: OpenCodeServerLogConfig::default(),
+            session_ttl_secs: 0,
+            rename_threads: true,
+            dry_run: false,
+            question_timeout_secs: 300,
+        }
+    }
+}
+
+/// A remote OpenCode server, typically exposed behind a reverse proxy.
+///
+/// Auth headers and TLS client identity apply to every request, including
+/// the long-lived SSE event stream.
+#[derive(Clone, PartialEq, Eq)]
+pub struct OpenCodeBackendConfig {
+    pub name: String,
+    /// Base URL of the server, e.g. `https://opencode.internal.example.com`.
+    pub url: String,
+    /// Working directory on the remote host. Workers targeting this
+    /// directory are routed to this backend.
+    pub directory: PathBuf,
+    pub auth: OpenCodeBackendAuth,
+    /// Extra headers sent on every request (values are already resolved).
+    pub headers: Vec<(String, String)>,
+    /// PEM client certificate for mutual TLS.
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key for `client_cert`. May be omitted when the key is
+    /// bundled into the certificate file.
+    pub client_key: Option<PathBuf>,
+    /// Extra PEM CA bundle trusted when verifying the server certificate.
+    pub ca_cert: Option<PathBuf>,
+    /// Record every session on this server, including ones started outside
+    /// spacebot (e.g. from the OpenCode TUI), without ever sending prompts.
+    pub mirror: bool,
+}
+
+/// Authentication scheme for a remote OpenCode backend.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub enum OpenCodeBackendAuth {
+    #[default]
+    None,
+    Bearer {
+        token: String,
+    },
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+}
+
+impl std::fmt::Debug for OpenCodeBackendAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Bearer { .. } => f
+                .debug_struct("Bearer")
+                .field("token", &"[REDACTED]")
+                .finish(),
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"[REDACTED]")
+                .finish(),
+        }
+    }
+}
+
+impl std::fmt::Debug for OpenCodeBackendConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header_names: Vec<&str> = self.headers.iter().map(|(name, _)| name.as_str()).collect();
+        f.debug_struct("OpenCodeBackendConfig")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("directory", &self.directory)
+            .field("auth", &self.auth)
+            .field("headers", &header_names)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .field("ca_cert", &self.ca_cert)
+            .field("mirror", &self.mirror)
+            .finish()
+    }
+}
+
+/// Settings supplied in code rather than config.toml, applied on top of every
+/// loaded config. See [`set_config_overrides`](super::set_config_overrides).
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    /// Extra OpenCode backends. One named like a backend in config.toml
+    /// replaces it.
+    pub opencode_backends: Vec<OpenCodeBackendConfig>,
+    /// Replaces `defaults.opencode.permissions`.
+    pub opencode_permissions: Option<crate::opencode::OpenCodePermissions>,
+}
+
+impl ConfigOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.opencode_backends.is_empty() && self.opencode_permissions.is_none()
+    }
+
+    /// Apply the overrides to an already loaded config.
+    pub fn apply(&self, config: &mut Config) {
+        let opencode = &mut config.defaults.opencode;
+        for backend in &self.opencode_backends {
+            opencode
+                .backends
+                .retain(|existing| existing.name != backend.name);
+            opencode.backends.push(backend.clone());
+        }
+        if let Some(permissions) = &self.opencode_permissions {
+            opencode.permissions = permissions.clone();
         }
     }
 }
@@ -1401,6 +1910,8 @@ pub struct AgentConfig {
     pub warmup: Option<WarmupConfig>,
     pub browser: Option<BrowserConfig>,
     pub channel: Option<ChannelConfig>,
+    /// Agent-level tool rendering overrides, layered over the defaults.
+    pub tool_rendering: Option<crate::conversation::settings::ToolRenderingSettings>,
     pub mcp: Option<Vec<McpServerConfig>>,
     /// Per-agent Brave Search API key override. None inherits from defaults.
     pub brave_search_key: Option<String>,
@@ -1414,6 +1925,14 @@ pub struct AgentConfig {
     pub projects: Option<ProjectsConfig>,
     /// Cron job definitions for this agent.
     pub cron: Vec<CronDef>,
+    /// GitHub webhook receiver (`[agents.github]`).
+    pub github: Option<GitHubWebhookConfig>,
+    /// Outgoing session lifecycle webhooks (`[[agents.notify_webhooks]]`).
+    pub notify_webhooks: Vec<NotifyWebhookConfig>,
+    /// Prompt templates for `/run` (`[[agents.templates]]`).
+    pub templates: Vec<crate::templates::PromptTemplate>,
+    /// Voice note transcription (`[agents.transcribe]`).
+    pub transcribe: Option<TranscribeConfig>,
 }
 
 /// A cron job definition from config.
@@ -1436,6 +1955,127 @@ pub struct CronDef {
     pub timeout_secs: Option<u64>,
 }
 
+/// GitHub webhook receiver for one agent.
+///
+/// Deliveries to `/api/webhooks/github/{agent_id}` are verified against
+/// `secret` and matched against `mappings`; each match runs its prompt in a
+/// fresh cron-style session and delivers the reply to `deliver_to`.
+#[derive(Clone, Default)]
+pub struct GitHubWebhookConfig {
+    /// Shared secret for `X-Hub-Signature-256`. Deliveries are rejected when unset.
+    pub secret: Option<String>,
+    /// API token `/git pr` uses to open pull requests. Without it, the
+    /// OpenCode session opens them with `gh`.
+    pub token: Option<String>,
+    pub mappings: Vec<GitHubEventMapping>,
+}
+
+impl std::fmt::Debug for GitHubWebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubWebhookConfig")
+            .field("secret", &self.secret.as_ref().map(|_| "[REDACTED]"))
+            .field("token", &self.token.as_ref().map(|_| "[REDACTED]"))
+            .field("mappings", &self.mappings)
+            .finish()
+    }
+}
+
+/// Whisper-compatible transcription of Telegram and Discord voice notes
+/// (`[agents.transcribe]`). Only used when built with the `transcribe`
+/// feature; otherwise voice notes go through `routing.voice` as before.
+#[derive(Clone)]
+pub struct TranscribeConfig {
+    /// Base URL of an OpenAI-compatible API; `/audio/transcriptions` is appended.
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// ISO-639-1 hint passed to the API. Detected when unset.
+    pub language: Option<String>,
+    /// Post the transcription back to the chat before answering it.
+    pub echo: bool,
+}
+
+impl std::fmt::Debug for TranscribeConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranscribeConfig")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("model", &self.model)
+            .field("language", &self.language)
+            .field("echo", &self.echo)
+            .finish()
+    }
+}
+
+/// Maps a GitHub event to a prompt template.
+#[derive(Debug, Clone)]
+pub struct GitHubEventMapping {
+    /// `<event>` or `<event>.<action>` (e.g. `issues.opened`). Completed
+    /// check and workflow events also match `<event>.<conclusion>`, so
+    /// `workflow_run.failure` selects failed CI runs.
+    pub event: String,
+    /// Only match deliveries for this `owner/name` repository.
+    pub repository: Option<String>,
+    /// Template rendered with the delivery payload as its context.
+    pub prompt: String,
+    /// Delivery target (`adapter:target`) for the agent's reply.
+    pub deliver_to: crate::messaging::target::BroadcastTarget,
+    /// Run timeout. `None` uses the cron default.
+    pub timeout_secs: Option<u64>,
+}
+
+/// Session lifecycle events an outgoing webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotifyEvent {
+    /// A worker session finished with a result.
+    SessionCompleted,
+    /// A worker session failed or was blocked.
+    SessionFailed,
+    /// A worker session hit its wall-clock budget
+    /// (`cortex.worker_wall_clock_timeout_secs`).
+    BudgetExhausted,
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 3] = [
+        NotifyEvent::SessionCompleted,
+        NotifyEvent::SessionFailed,
+        NotifyEvent::BudgetExhausted,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotifyEvent::SessionCompleted => "session_completed",
+            NotifyEvent::SessionFailed => "session_failed",
+            NotifyEvent::BudgetExhausted => "budget_exhausted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+}
+
+/// An outgoing webhook that receives session lifecycle events as JSON.
+#[derive(Clone)]
+pub struct NotifyWebhookConfig {
+    pub url: String,
+    /// Key for the `X-Spacebot-Signature-256` header. Unsigned when unset.
+    pub secret: Option<String>,
+    /// Events to send. Defaults to all of them.
+    pub events: Vec<NotifyEvent>,
+}
+
+impl std::fmt::Debug for NotifyWebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotifyWebhookConfig")
+            .field("url", &self.url)
+            .field("secret", &self.secret.as_ref().map(|_| "[REDACTED]"))
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
 /// Fully resolved agent config (merged with defaults, paths resolved).
 #[derive(Debug, Clone)]
 pub struct ResolvedAgentConfig {
@@ -1464,6 +2104,8 @@ pub struct ResolvedAgentConfig {
     pub warmup: WarmupConfig,
     pub browser: BrowserConfig,
     pub channel: ChannelConfig,
+    /// Instance defaults with this agent's overrides applied.
+    pub tool_rendering: crate::conversation::settings::ToolRenderingSettings,
     pub mcp: Vec<McpServerConfig>,
     pub brave_search_key: Option<String>,
     pub cron_timezone: Option<String>,
@@ -1475,6 +2117,10 @@ pub struct ResolvedAgentConfig {
     /// Number of messages to fetch from the platform when a new channel is created.
     pub history_backfill_count: usize,
     pub cron: Vec<CronDef>,
+    pub github: GitHubWebhookConfig,
+    pub notify_webhooks: Vec<NotifyWebhookConfig>,
+    pub templates: Vec<crate::templates::PromptTemplate>,
+    pub transcribe: Option<TranscribeConfig>,
     /// Tool-use enforcement for preventing models from describing actions instead of calling tools.
     pub tool_use_enforcement: ToolUseEnforcement,
 }
@@ -1505,8 +2151,10 @@ impl Default for DefaultsConfig {
             cron: Vec::new(),
             tool_use_enforcement: ToolUseEnforcement::default(),
             opencode: OpenCodeConfig::default(),
+            private_sessions: PrivateSessionConfig::default(),
             worker_log_mode: crate::settings::WorkerLogMode::default(),
             projects: ProjectsConfig::default(),
+            tool_rendering: crate::conversation::settings::ToolRenderingSettings::default(),
         }
     }
 }
@@ -1566,6 +2214,10 @@ impl AgentConfig {
                 .clone()
                 .unwrap_or_else(|| defaults.browser.clone()),
             channel: self.channel.unwrap_or(defaults.channel),
+            tool_rendering: match &self.tool_rendering {
+                Some(agent) => defaults.tool_rendering.overlay(agent),
+                None => defaults.tool_rendering.clone(),
+            },
             mcp: resolve_mcp_configs(&defaults.mcp, self.mcp.as_deref()),
             brave_search_key: self
                 .brave_search_key
@@ -1580,6 +2232,10 @@ impl AgentConfig {
                 .unwrap_or_else(|| defaults.projects.clone()),
             history_backfill_count: defaults.history_backfill_count,
             cron: self.cron.clone(),
+            github: self.github.clone().unwrap_or_default(),
+            notify_webhooks: self.notify_webhooks.clone(),
+            templates: self.templates.clone(),
+            transcribe: self.transcribe.clone(),
             tool_use_enforcement: self
                 .tool_use_enforcement
                 .clone()
@@ -1590,7 +2246,7 @@ impl AgentConfig {
 
 impl ResolvedAgentConfig {
     pub fn sqlite_path(&self) -> PathBuf {
-        self.data_dir.join("spacebot.db")
+        self.data_dir.join("agent.db")
     }
     pub fn lancedb_path(&self) -> PathBuf {
         self.data_dir.join("lancedb")
@@ -1857,12 +2513,20 @@ impl Binding {
                 .get("mattermost_channel_id")
                 .and_then(|v| v.as_str());
 
+            // IRC channel names, e.g. "#rust"
+            let irc_channel = message.metadata.get("irc_channel").and_then(|v| v.as_str());
+
             let direct_match = message_channel
                 .as_ref()
                 .is_some_and(|id| self.channel_ids.contains(id))
                 || slack_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()))
                 || twitch_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()))
-                || mattermost_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()));
+                || mattermost_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()))
+                || irc_channel.is_some_and(|name| {
+                    self.channel_ids
+                        .iter()
+                        .any(|id| id.eq_ignore_ascii_case(name))
+                });
             let parent_match = parent_channel
                 .as_ref()
                 .is_some_and(|id| self.channel_ids.contains(id));
@@ -1939,6 +2603,7 @@ impl Binding {
             "twitch" => "twitch_mentions_or_replies_to_bot",
             "telegram" => "telegram_mentions_or_replies_to_bot",
             "mattermost" => "mattermost_mentions_or_replies_to_bot",
+            "irc" => "irc_mentions_bot",
             // Unknown platforms: if require_mention is set, default to
             // requiring a mention (safe default).
             _ => return false,
@@ -2458,6 +3123,123 @@ pub fn resolve_agent_for_message(
     Some((std::sync::Arc::from(default_agent_id), None))
 }
 
+/// Locale of bot-authored messages for `message`, from the settings of the
+/// binding that routes it. For replies sent before a channel exists.
+pub fn binding_locale(bindings: &[Binding], message: &crate::InboundMessage) -> String {
+    bindings
+        .iter()
+        .find(|binding| binding.matches_route(message))
+        .and_then(|binding| binding.settings.as_ref()?.locale.clone())
+        .unwrap_or_else(|| crate::prompts::messages::DEFAULT_LOCALE.to_string())
+}
+
+/// A Discord guild or Slack workspace served by its own agent.
+///
+/// The tenant's agent carries its own config, memory and conversation
+/// stores, and workspace, so tenants share nothing but the process and the
+/// platform connections. Messages from a tenant's guilds and workspaces only
+/// ever reach that agent.
+#[derive(Debug, Clone)]
+pub struct TenantConfig {
+    pub id: String,
+    /// Agent serving this tenant. No other tenant may use it, and it can't
+    /// be the default agent.
+    pub agent_id: String,
+    pub discord_guild_ids: Vec<String>,
+    pub slack_workspace_ids: Vec<String>,
+    /// Inbound messages accepted per UTC day. Further messages are dropped
+    /// until the next day. `None` is unlimited.
+    pub daily_message_budget: Option<u64>,
+    /// Outbound content filters, checked in order against every message the
+    /// tenant's agent posts.
+    pub content_filters: Vec<ContentFilterRule>,
+}
+
+/// What an outbound content filter does with a message that matches it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentFilterAction {
+    /// Replace each match with `***` and send the rest.
+    Mask,
+    /// Withhold the message and quarantine it for admin review.
+    Block,
+    /// Send the message unchanged and quarantine a copy for admin review.
+    Flag,
+}
+
+impl ContentFilterAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentFilterAction::Mask => "mask",
+            ContentFilterAction::Block => "block",
+            ContentFilterAction::Flag => "flag",
+        }
+    }
+}
+
+/// One outbound content filter. A message matches when any pattern or word
+/// does.
+#[derive(Debug, Clone)]
+pub struct ContentFilterRule {
+    pub name: String,
+    /// Regexes, validated at load.
+    pub patterns: Vec<String>,
+    /// Words matched whole and case-insensitively.
+    pub words: Vec<String>,
+    pub action: ContentFilterAction,
+}
+
+impl TenantConfig {
+    /// Whether `message` came from one of this tenant's guilds or
+    /// workspaces.
+    pub fn matches(&self, message: &crate::InboundMessage) -> bool {
+        match message.source.as_str() {
+            "discord" => message
+                .metadata
+                .get("discord_guild_id")
+                .and_then(|value| value.as_u64())
+                .is_some_and(|guild_id| {
+                    self.discord_guild_ids
+                        .iter()
+                        .any(|id| id == &guild_id.to_string())
+                }),
+            "slack" => message
+                .metadata
+                .get("slack_workspace_id")
+                .and_then(|value| value.as_str())
+                .is_some_and(|workspace_id| {
+                    self.slack_workspace_ids.iter().any(|id| id == workspace_id)
+                }),
+            _ => false,
+        }
+    }
+}
+
+/// Find the tenant `message` belongs to, if any.
+pub fn resolve_tenant<'a>(
+    tenants: &'a [TenantConfig],
+    message: &crate::InboundMessage,
+) -> Option<&'a TenantConfig> {
+    tenants.iter().find(|tenant| tenant.matches(message))
+}
+
+/// Route a tenant's message. Only bindings to the tenant's agent apply, and
+/// the tenant's agent stands in for the instance default.
+pub fn resolve_agent_for_tenant(
+    tenant: &TenantConfig,
+    bindings: &[Binding],
+    message: &crate::InboundMessage,
+) -> Option<(
+    crate::AgentId,
+    Option<crate::conversation::ConversationSettings>,
+)> {
+    let tenant_bindings: Vec<Binding> = bindings
+        .iter()
+        .filter(|binding| binding.agent_id == tenant.agent_id)
+        .cloned()
+        .collect();
+    resolve_agent_for_message(&tenant_bindings, message, &tenant.agent_id)
+}
+
 // ---------------------------------------------------------------------------
 // Messaging platform configs
 // ---------------------------------------------------------------------------
@@ -2473,6 +3255,87 @@ pub struct MessagingConfig {
     pub twitch: Option<TwitchConfig>,
     pub signal: Option<SignalConfig>,
     pub mattermost: Option<MattermostConfig>,
+    pub irc: Option<IrcConfig>,
+    pub redaction: RedactionConfig,
+    pub overflow: OverflowConfig,
+    pub links: LinksConfig,
+}
+
+/// Rewriting applied to URLs in outbound chat messages.
+#[derive(Debug, Clone)]
+pub struct LinksConfig {
+    /// Wrap URLs so platforms that support it skip the link preview.
+    pub suppress_unfurls: bool,
+    /// Drop `utm_*` and click-id query parameters from URLs.
+    pub strip_tracking: bool,
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        Self {
+            suppress_unfurls: false,
+            strip_tracking: true,
+        }
+    }
+}
+
+/// What to do with a text reply longer than the platform's message limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// Let the adapter split the text into several messages.
+    #[default]
+    Split,
+    /// Send a short preview with the full text attached as a file.
+    File,
+    /// Upload the full text to a paste service and link it.
+    Paste,
+}
+
+/// Overflow handling for replies that exceed a platform's message limit.
+#[derive(Debug, Clone)]
+pub struct OverflowConfig {
+    pub mode: OverflowMode,
+    /// Characters of the reply kept inline ahead of the file or link.
+    pub preview_chars: usize,
+    /// Endpoint that accepts a `text/plain` POST and returns the paste URL.
+    pub paste_url: Option<String>,
+    /// Bearer token sent to the paste service.
+    pub paste_token: Option<String>,
+    /// JSON field holding the URL when the paste service responds with JSON.
+    /// When unset, the trimmed response body is used as the URL.
+    pub paste_url_field: Option<String>,
+}
+
+impl Default for OverflowConfig {
+    fn default() -> Self {
+        Self {
+            mode: OverflowMode::default(),
+            preview_chars: 400,
+            paste_url: None,
+            paste_token: None,
+            paste_url_field: None,
+        }
+    }
+}
+
+/// Secret masking applied to every outbound chat message.
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Extra regexes matched on top of the built-in API key patterns.
+    pub patterns: Vec<String>,
+    /// Environment variables whose current values are masked verbatim.
+    pub env_vars: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+            env_vars: Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -2721,9 +3584,36 @@ pub struct EmailConfig {
     pub allowed_senders: Vec<String>,
     pub max_body_bytes: usize,
     pub max_attachment_bytes: usize,
+    /// Recipient addresses whose mail is treated as a prompt and answered by
+    /// email, instead of being triaged as inbound-only intake.
+    pub prompt_addresses: Vec<String>,
+    /// Accept inbound mail over HTTP in addition to (or instead of) IMAP.
+    pub webhook: Option<EmailWebhookConfig>,
     pub instances: Vec<EmailInstanceConfig>,
 }
 
+/// Inbound mail pushed by a provider's inbound-parse webhook
+/// (`[messaging.email.webhook]`).
+#[derive(Clone)]
+pub struct EmailWebhookConfig {
+    pub bind: String,
+    pub port: u16,
+    pub auth_token: Option<String>,
+}
+
+impl std::fmt::Debug for EmailWebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailWebhookConfig")
+            .field("bind", &self.bind)
+            .field("port", &self.port)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .finish()
+    }
+}
+
 /// Per-instance config for a named email adapter.
 #[derive(Clone)]
 pub struct EmailInstanceConfig {
@@ -2746,6 +3636,8 @@ pub struct EmailInstanceConfig {
     pub allowed_senders: Vec<String>,
     pub max_body_bytes: usize,
     pub max_attachment_bytes: usize,
+    pub prompt_addresses: Vec<String>,
+    pub webhook: Option<EmailWebhookConfig>,
 }
 
 impl std::fmt::Debug for EmailInstanceConfig {
@@ -2770,6 +3662,8 @@ impl std::fmt::Debug for EmailInstanceConfig {
             .field("allowed_senders", &"[REDACTED]")
             .field("max_body_bytes", &self.max_body_bytes)
             .field("max_attachment_bytes", &self.max_attachment_bytes)
+            .field("prompt_addresses", &"[REDACTED]")
+            .field("webhook", &self.webhook)
             .finish()
     }
 }
@@ -2795,6 +3689,8 @@ impl std::fmt::Debug for EmailConfig {
             .field("allowed_senders", &"[REDACTED]")
             .field("max_body_bytes", &self.max_body_bytes)
             .field("max_attachment_bytes", &self.max_attachment_bytes)
+            .field("prompt_addresses", &"[REDACTED]")
+            .field("webhook", &self.webhook)
             .finish()
     }
 }
@@ -2959,6 +3855,45 @@ impl SystemSecrets for TwitchConfig {
     }
 }
 
+/// IRC network connection (`[messaging.irc]`).
+#[derive(Clone)]
+pub struct IrcConfig {
+    pub enabled: bool,
+    pub server: String,
+    pub port: u16,
+    pub tls: bool,
+    pub nickname: String,
+    /// Server password sent with `PASS`.
+    pub password: Option<String>,
+    /// Sent to NickServ with `IDENTIFY` after connecting.
+    pub nickserv_password: Option<String>,
+    pub channels: Vec<String>,
+    /// Prefix IRC users type instead of `/` for chat commands.
+    pub command_prefix: String,
+    /// Nicks allowed to talk to the bot. Empty allows everyone.
+    pub allowed_nicks: Vec<String>,
+}
+
+impl std::fmt::Debug for IrcConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IrcConfig")
+            .field("enabled", &self.enabled)
+            .field("server", &self.server)
+            .field("port", &self.port)
+            .field("tls", &self.tls)
+            .field("nickname", &self.nickname)
+            .field("password", &self.password.as_ref().map(|_| "[REDACTED]"))
+            .field(
+                "nickserv_password",
+                &self.nickserv_password.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("channels", &self.channels)
+            .field("command_prefix", &self.command_prefix)
+            .field("allowed_nicks", &self.allowed_nicks)
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WebhookConfig {
     pub enabled: bool,
@@ -3,7 +3,8 @@ use std::sync::Arc;
 
 use super::{
     Binding, Config, DiscordPermissions, MattermostPermissions, RuntimeConfig, SignalPermissions,
-    SlackPermissions, TelegramPermissions, TwitchPermissions, binding_runtime_adapter_key,
+    SlackPermissions, TelegramPermissions, TenantConfig, TwitchPermissions,
+    binding_runtime_adapter_key,
 };
 
 /// Per-agent context needed by the file watcher: (id, prompt_dir, identity_dir,
@@ -34,6 +35,7 @@ pub fn spawn_file_watcher(
     mattermost_permissions: Option<Arc<arc_swap::ArcSwap<MattermostPermissions>>>,
     signal_permissions: Option<Arc<arc_swap::ArcSwap<SignalPermissions>>>,
     bindings: Arc<arc_swap::ArcSwap<Vec<Binding>>>,
+    tenants: Arc<arc_swap::ArcSwap<Vec<TenantConfig>>>,
     messaging_manager: Option<Arc<crate::messaging::MessagingManager>>,
     llm_manager: Arc<crate::llm::LlmManager>,
     agent_links: Arc<arc_swap::ArcSwap<Vec<crate::links::AgentLink>>>,
@@ -85,6 +87,14 @@ pub fn spawn_file_watcher(
             tracing::warn!(%error, path = %instance_skills_dir.display(), "failed to watch instance skills dir");
         }
 
+        // Watch operator scripts
+        let scripts_dir = instance_dir.join("scripts");
+        if scripts_dir.is_dir()
+            && let Err(error) = watcher.watch(&scripts_dir, RecursiveMode::NonRecursive)
+        {
+            tracing::warn!(%error, path = %scripts_dir.display(), "failed to watch scripts dir");
+        }
+
         // Watch per-agent directories
         for (_, workspace, identity_dir, _, _) in &agents {
             // Watch workspace/skills for skill file changes
@@ -134,9 +144,10 @@ pub fn spawn_file_watcher(
             let skills_changed = changed_paths
                 .iter()
                 .any(|p| p.to_string_lossy().contains("skills"));
+            let scripts_changed = changed_paths.iter().any(|p| p.starts_with(&scripts_dir));
 
             // Skip entirely if nothing relevant changed
-            if !config_changed && !identity_changed && !skills_changed {
+            if !config_changed && !identity_changed && !skills_changed && !scripts_changed {
                 continue;
             }
 
@@ -153,7 +164,7 @@ pub fn spawn_file_watcher(
                 if current_hash == last_config_hash {
                     config_changed = false;
                     // If config was the only thing that "changed", skip entirely
-                    if !identity_changed && !skills_changed {
+                    if !identity_changed && !skills_changed && !scripts_changed {
                         continue;
                     }
                 } else {
@@ -165,6 +176,7 @@ pub fn spawn_file_watcher(
                 config_changed.then_some("config"),
                 identity_changed.then_some("identity"),
                 skills_changed.then_some("skills"),
+                scripts_changed.then_some("scripts"),
             ]
             .into_iter()
             .flatten()
@@ -175,6 +187,10 @@ pub fn spawn_file_watcher(
                 "file change detected, reloading"
             );
 
+            if scripts_changed {
+                crate::scripting::ScriptHooks::global().load_dir(&scripts_dir);
+            }
+
             // Reload config.toml if it changed
             let new_config = if config_changed {
                 match Config::load_from_path(&config_path) {
@@ -195,6 +211,14 @@ pub fn spawn_file_watcher(
                 bindings.store(Arc::new(config.bindings.clone()));
                 tracing::info!("bindings reloaded ({} entries)", config.bindings.len());
 
+                tenants.store(Arc::new(config.tenants.clone()));
+                tracing::info!("tenants reloaded ({} entries)", config.tenants.len());
+
+                crate::rate_limit::PromptRateLimiter::global()
+                    .reconfigure(config.rate_limit.clone());
+                crate::db::blob::BlobStore::configure(&config.blob_store);
+                crate::notifications::ops::OpsAlerts::global().configure(config.notify.clone());
+
                 match crate::links::AgentLink::from_config(&config.links) {
                     Ok(links) => {
                         agent_links.store(Arc::new(links));
@@ -437,7 +461,7 @@ pub fn spawn_file_watcher(
                         // Email: start default + named instances that are enabled and not already running.
                         if let Some(email_config) = &config.messaging.email
                             && email_config.enabled {
-                                if !email_config.imap_host.is_empty() && !manager.has_adapter("email").await {
+                                if (!email_config.imap_host.is_empty() || email_config.webhook.is_some()) && !manager.has_adapter("email").await {
                                     match crate::messaging::email::EmailAdapter::from_config(email_config) {
                                         Ok(adapter) => {
                                             if let Err(error) = manager.register_and_start(adapter).await {
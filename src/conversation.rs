@@ -6,8 +6,11 @@ pub mod context;
 pub mod history;
 pub mod participants;
 pub mod portal;
+pub mod private;
 pub mod settings;
+pub mod transcript_search;
 pub mod worker_transcript;
+pub mod write_behind;
 
 pub use channel_settings::ChannelSettingsStore;
 pub use channels::ChannelStore;
@@ -24,4 +27,6 @@ pub use settings::{
     ResolvedConversationSettings, ResponseMode, WorkerContextMode, WorkerHistoryMode,
     WorkerMemoryMode,
 };
+pub use transcript_search::{TranscriptHit, TranscriptSearch};
 pub use worker_transcript::{ActionContent, TranscriptStep};
+pub use write_behind::WriteBehind;
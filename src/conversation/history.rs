@@ -2,7 +2,7 @@
 
 use crate::{BranchId, ChannelId, WorkerId};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::{Row as _, SqlitePool};
 use std::collections::HashMap;
 
@@ -259,7 +259,7 @@ impl ConversationLogger {
 }
 
 /// A unified timeline item combining messages, branch runs, and worker runs.
-#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TimelineItem {
     Message {
@@ -472,16 +472,32 @@ impl ProcessRunLogger {
         let id = worker_id.to_string();
 
         tokio::spawn(async move {
-            if let Err(error) = sqlx::query("UPDATE worker_runs SET status = 'idle' WHERE id = ?")
-                .bind(&id)
-                .execute(&pool)
-                .await
+            if let Err(error) = sqlx::query(
+                "UPDATE worker_runs SET status = 'idle' WHERE id = ? AND status <> 'detached'",
+            )
+            .bind(&id)
+            .execute(&pool)
+            .await
             {
                 tracing::warn!(%error, worker_id = %id, "failed to persist worker idle state");
             }
         });
     }
 
+    /// Mark an idle worker as detached from its channel's OpenCode session
+    /// (`/detach`), so it isn't resumed at startup. Awaited rather than
+    /// fire-and-forget: the channel only confirms a detach once it's stored.
+    pub async fn log_worker_detached(&self, worker_id: WorkerId) -> crate::error::Result<()> {
+        sqlx::query(
+            "UPDATE worker_runs SET status = 'detached' WHERE id = ? AND completed_at IS NULL",
+        )
+        .bind(worker_id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(())
+    }
+
     /// Mark an idle worker as running again (follow-up received).
     pub fn log_worker_resumed(&self, worker_id: WorkerId) {
         let pool = self.pool.clone();
@@ -518,7 +534,7 @@ impl ProcessRunLogger {
 
         tokio::spawn(async move {
             if let Err(error) = sqlx::query(
-                "UPDATE worker_runs SET result = ?, status = ?, completed_at = CURRENT_TIMESTAMP WHERE id = ? AND completed_at IS NULL"
+                "UPDATE worker_runs SET result = ?, status = CASE WHEN status = 'detached' THEN status ELSE ? END, completed_at = CURRENT_TIMESTAMP WHERE id = ? AND completed_at IS NULL"
             )
             .bind(&result)
             .bind(status)
@@ -692,6 +708,36 @@ impl ProcessRunLogger {
         Ok(())
     }
 
+    /// Copy a worker's persisted transcript to the blob store and record
+    /// its blob ID on the run. Returns `None` when there is no transcript.
+    pub async fn export_transcript(
+        &self,
+        worker_id: &str,
+        store: &crate::db::blob::BlobStore,
+    ) -> crate::error::Result<Option<crate::db::blob::BlobId>> {
+        let transcript: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT transcript FROM worker_runs WHERE id = ?")
+                .bind(worker_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|error| anyhow::anyhow!(error))?
+                .flatten();
+        let Some(transcript) = transcript else {
+            return Ok(None);
+        };
+
+        let blob_id = store
+            .put(crate::db::blob::BlobKind::Transcript, transcript)
+            .await?;
+        sqlx::query("UPDATE worker_runs SET transcript_blob_id = ? WHERE id = ?")
+            .bind(blob_id.as_str())
+            .bind(worker_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(Some(blob_id))
+    }
+
     /// Mark a detached running worker as cancelled.
     ///
     /// Used by API cancellation when the in-memory channel state no longer has
@@ -1181,6 +1227,38 @@ mod tests {
         assert_eq!(result, "Worker cancelled");
     }
 
+    #[tokio::test]
+    async fn detached_workers_stay_detached() {
+        let pool = setup_worker_runs_table().await;
+        let logger = ProcessRunLogger::new(pool.clone());
+        let worker_id = uuid::Uuid::new_v4();
+
+        sqlx::query("INSERT INTO worker_runs (id, channel_id, status, result) VALUES (?, 'ch-1', 'idle', '')")
+            .bind(worker_id.to_string())
+            .execute(&pool)
+            .await
+            .expect("insert");
+
+        logger
+            .log_worker_detached(worker_id)
+            .await
+            .expect("detach should succeed");
+        // A late idle write from the worker must not make it resumable again,
+        // and its completion keeps the detached status.
+        logger.log_worker_idle(worker_id);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        logger.log_worker_completed(worker_id, "done", true);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let row = sqlx::query("SELECT status FROM worker_runs WHERE id = ?")
+            .bind(worker_id.to_string())
+            .fetch_one(&pool)
+            .await
+            .expect("fetch");
+        let status: String = sqlx::Row::try_get(&row, "status").expect("status");
+        assert_eq!(status, "detached");
+    }
+
     /// Poll until a worker's status changes from "running", with a timeout.
     async fn poll_worker_status(
         pool: &sqlx::SqlitePool,
@@ -228,6 +228,7 @@ mod tests {
             telegram_id: None,
             slack_id: None,
             email: None,
+            access: None,
         }];
 
         track_active_participant(&mut participants, &humans, &message);
@@ -351,6 +352,7 @@ mod tests {
             telegram_id: None,
             slack_id: None,
             email: None,
+            access: None,
         }];
 
         track_active_participant(&mut participants, &humans, &message);
@@ -0,0 +1,147 @@
+//! Private sessions: direct messages to the bot.
+//!
+//! A DM is a one-user session. The router keys it by the sender instead of
+//! the conversation, so nobody else can land in it, and it starts from
+//! `[defaults.private_sessions]` rather than the agent's channel defaults.
+//! Its tool policy is stricter than a shared channel's: no direct mode,
+//! OpenCode workers only when allowed, and workers only for senders at
+//! `worker_tier` or above. Usage recorded in a DM is marked private and left
+//! out of shared spend reports.
+
+use super::settings::{ConversationSettings, DelegationMode, ResolvedConversationSettings};
+use crate::InboundMessage;
+use crate::access::AccessTier;
+use crate::config::PrivateSessionConfig;
+
+/// Check if a conversation ID represents a DM (direct message).
+///
+/// Discord and Mattermost embed a `:dm:` segment in the conversation ID.
+/// Slack uses `slack:TEAM:DCHANNEL` where the channel ID starts with `D`.
+pub fn is_dm_conversation_id(conv_id: &str) -> bool {
+    conv_id.contains(":dm:")
+        || conv_id.starts_with("slack:")
+            && conv_id
+                .rsplit(':')
+                .next()
+                .is_some_and(|last| last.starts_with('D'))
+}
+
+/// Router key for the private session `message` belongs to, or `None` when
+/// it isn't a DM or private sessions are off.
+pub fn session_key(config: &PrivateSessionConfig, message: &InboundMessage) -> Option<String> {
+    if !config.enabled || !is_dm_conversation_id(&message.conversation_id) {
+        return None;
+    }
+    Some(format!("private:{}:{}", message.source, message.sender_id))
+}
+
+/// Settings a private session starts from, below any the conversation or
+/// its binding set.
+pub fn default_settings(config: &PrivateSessionConfig) -> ConversationSettings {
+    ConversationSettings {
+        model: config.model.clone(),
+        memory: config.memory,
+        ..Default::default()
+    }
+}
+
+/// Apply the private-session tool policy on top of resolved settings.
+pub fn restrict(settings: &mut ResolvedConversationSettings) {
+    settings.delegation = DelegationMode::Standard;
+}
+
+/// Check that a private session may spawn a worker for a sender at
+/// `requester_tier`.
+pub fn check_worker_spawn(
+    config: &PrivateSessionConfig,
+    requester_tier: AccessTier,
+    opencode: bool,
+) -> Result<(), String> {
+    if opencode && !config.allow_opencode {
+        return Err("OpenCode workers are disabled in private sessions".into());
+    }
+    if requester_tier < config.worker_tier {
+        return Err(format!(
+            "spawning workers in a private session requires the {} tier",
+            config.worker_tier
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(source: &str, sender_id: &str, conversation_id: &str) -> InboundMessage {
+        InboundMessage {
+            source: source.to_string(),
+            adapter: Some(source.to_string()),
+            conversation_id: conversation_id.to_string(),
+            sender_id: sender_id.to_string(),
+            content: crate::MessageContent::Text("hello".to_string()),
+            ..InboundMessage::empty()
+        }
+    }
+
+    #[test]
+    fn is_dm_conversation_id_detects_dm_patterns() {
+        // Slack DMs — channel ID starts with 'D'
+        assert!(is_dm_conversation_id("slack:T07GZRRFRRT:D0AHN0BM8D8"));
+        assert!(is_dm_conversation_id(
+            "slack:adapter:T07GZRRFRRT:D0AHN0BM8D8"
+        ));
+
+        // Discord DMs
+        assert!(is_dm_conversation_id("discord:dm:123456789"));
+
+        // Mattermost DMs
+        assert!(is_dm_conversation_id("mattermost:team1:dm:user1"));
+
+        // Generic :dm: pattern
+        assert!(is_dm_conversation_id("platform:dm:some-id"));
+
+        // Non-DM patterns
+        assert!(!is_dm_conversation_id("slack:T07GZRRFRRT:C12345"));
+        assert!(!is_dm_conversation_id("discord:guild:123:channel:456"));
+        assert!(!is_dm_conversation_id("discord:conversation"));
+        assert!(!is_dm_conversation_id(""));
+    }
+
+    #[test]
+    fn dms_are_keyed_by_sender() {
+        let config = PrivateSessionConfig::default();
+        assert_eq!(
+            session_key(&config, &message("slack", "U42", "slack:T1:D0AHN0BM8D8")).as_deref(),
+            Some("private:slack:U42")
+        );
+        assert_eq!(
+            session_key(&config, &message("slack", "U42", "slack:T1:C12345")),
+            None
+        );
+
+        let disabled = PrivateSessionConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            session_key(&disabled, &message("discord", "7", "discord:dm:7")),
+            None
+        );
+    }
+
+    #[test]
+    fn worker_policy_checks_opencode_and_tier() {
+        let config = PrivateSessionConfig::default();
+        assert!(check_worker_spawn(&config, AccessTier::Developer, false).is_ok());
+        assert!(check_worker_spawn(&config, AccessTier::ReadOnly, false).is_err());
+        assert!(check_worker_spawn(&config, AccessTier::Admin, true).is_err());
+
+        let permissive = PrivateSessionConfig {
+            allow_opencode: true,
+            worker_tier: AccessTier::ReadOnly,
+            ..Default::default()
+        };
+        assert!(check_worker_spawn(&permissive, AccessTier::ReadOnly, true).is_ok());
+    }
+}
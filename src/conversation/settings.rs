@@ -138,6 +138,159 @@ pub enum ResponseMode {
     MentionOnly,
 }
 
+/// How a channel's output reaches chat over a turn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryMode {
+    /// Worker tool calls, todo lists, and status lines are posted and
+    /// edited as they happen.
+    #[default]
+    Live,
+    /// Nothing is posted until the turn's reply, which goes out as one
+    /// message ending in a single line that tallies the tools that ran.
+    /// Suits busy channels and platforms that rate-limit edits.
+    Summary,
+}
+
+impl DeliveryMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "live" => Some(Self::Live),
+            "summary" => Some(Self::Summary),
+            _ => None,
+        }
+    }
+}
+
+/// How much of a worker tool call is posted to chat.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolDetail {
+    /// Nothing is posted.
+    Hidden,
+    /// Only the tool name.
+    Name,
+    /// Tool name, a one-line argument summary, and the outcome (exit code
+    /// for shell commands).
+    Summary,
+    /// Everything in `Summary` plus the tool output.
+    Full,
+}
+
+impl ToolDetail {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "hidden" | "hide" | "off" => Some(Self::Hidden),
+            "name" => Some(Self::Name),
+            "summary" => Some(Self::Summary),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Per-tool rendering detail at one level of the hierarchy
+/// (instance defaults → agent → channel).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ToolRenderingSettings {
+    /// Detail for tools without an explicit entry. Unset inherits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<ToolDetail>,
+    /// Detail keyed by tool name (`shell`, `file_read`, or an OpenCode tool
+    /// such as `bash` or `webfetch`).
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub tools: std::collections::BTreeMap<String, ToolDetail>,
+    /// Cheap-model summaries for long tool output.
+    #[serde(default, skip_serializing_if = "ToolSummarySettings::is_unset")]
+    pub summarize: ToolSummarySettings,
+}
+
+/// Summaries of long tool output at one level of the hierarchy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ToolSummarySettings {
+    /// Summarize output longer than this many lines; `0` turns summaries
+    /// off. Unset inherits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lines: Option<usize>,
+    /// Model used for summaries. Unset inherits, falling back to the
+    /// compactor's routed model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl ToolSummarySettings {
+    fn is_unset(&self) -> bool {
+        self.min_lines.is_none() && self.model.is_none()
+    }
+}
+
+impl ToolRenderingSettings {
+    /// Layer `more_specific` on top of `self`. Its per-tool entries and
+    /// default win where set.
+    pub fn overlay(&self, more_specific: &Self) -> Self {
+        let mut tools = self.tools.clone();
+        tools.extend(
+            more_specific
+                .tools
+                .iter()
+                .map(|(tool, detail)| (tool.clone(), *detail)),
+        );
+        Self {
+            default: more_specific.default.or(self.default),
+            tools,
+            summarize: ToolSummarySettings {
+                min_lines: more_specific
+                    .summarize
+                    .min_lines
+                    .or(self.summarize.min_lines),
+                model: more_specific
+                    .summarize
+                    .model
+                    .clone()
+                    .or_else(|| self.summarize.model.clone()),
+            },
+        }
+    }
+
+    /// Line count above which a rendered tool output is summarized, or
+    /// `None` when summaries are off.
+    pub fn summary_threshold(&self) -> Option<usize> {
+        self.summarize.min_lines.filter(|lines| *lines > 0)
+    }
+
+    /// Resolve the detail for a tool. An explicit per-tool entry at any
+    /// level beats a blanket default; with no default set, nothing renders.
+    /// Built-in caps keep noisy read-style tools quiet under a blanket
+    /// default unless they are configured by name.
+    pub fn resolve(&self, tool: &str) -> ToolDetail {
+        if let Some(detail) = self.tools.get(tool) {
+            return *detail;
+        }
+        let default = self.default.unwrap_or(ToolDetail::Hidden);
+        match builtin_tool_cap(tool) {
+            Some(cap) => default.min(cap),
+            None => default,
+        }
+    }
+}
+
+/// Upper bound applied to a blanket default for tools whose output is
+/// rarely worth posting.
+fn builtin_tool_cap(tool: &str) -> Option<ToolDetail> {
+    match tool {
+        "file_read" | "file_list" | "read" | "glob" | "grep" | "list" | "wiki_read"
+        | "memory_recall" | "channel_recall" | "read_skill" | "set_status" | "todoread" => {
+            Some(ToolDetail::Hidden)
+        }
+        "webfetch" | "web_search" | "browser_snapshot" | "browser_navigate" => {
+            Some(ToolDetail::Summary)
+        }
+        _ => None,
+    }
+}
+
 /// Worker context settings control what context workers receive when spawned.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct WorkerContextMode {
@@ -211,6 +364,64 @@ pub struct ConversationSettings {
     /// What context workers spawned from this conversation receive.
     #[serde(default)]
     pub worker_context: WorkerContextMode,
+
+    /// Channel-level tool rendering overrides, layered over the agent's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_rendering: Option<ToolRenderingSettings>,
+
+    /// Custom instructions appended to the channel's system prompt and
+    /// passed to OpenCode sessions spawned from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    /// Repository directory on the OpenCode host that OpenCode workers from
+    /// this channel use when no directory is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_directory: Option<String>,
+
+    /// OpenCode workers post a plan and wait for approval before building.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_approval: Option<bool>,
+
+    /// Models OpenCode workers retry a prompt on, in order, when the
+    /// provider is rate limited or unavailable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_models: Option<Vec<String>>,
+
+    /// Language of bot-authored chat messages, e.g. `"es"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+
+    /// OpenCode workers post the prompts they would send instead of sending
+    /// them. Unset follows `[opencode] dry_run`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+
+    /// Post every update live, or one message per turn. Unset inherits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliveryMode>,
+
+    /// Post worker reasoning and long tool output collapsed: Discord
+    /// spoilers, Slack context blocks. Unset inherits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collapse: Option<bool>,
+
+    /// Browse URL of the channel's repository, e.g.
+    /// `https://github.com/acme/app/blob/main`. File paths in tool output
+    /// link under it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository_url: Option<String>,
+
+    /// IANA timezone, e.g. `"Europe/Berlin"`, for times shown in this
+    /// channel and given to the model. Unset follows the agent's
+    /// `user_timezone`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// Wall-clock budget, in seconds, of builtin workers spawned from this
+    /// conversation. Unset follows `cortex.worker_wall_clock_timeout_secs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_budget_secs: Option<u64>,
 }
 
 /// Resolved conversation settings after applying defaults.
@@ -231,6 +442,31 @@ pub struct ResolvedConversationSettings {
     pub save_attachments: bool,
     /// The resolved worker context settings.
     pub worker_context: WorkerContextMode,
+    /// Channel-level tool rendering overrides (agent-level settings live in
+    /// `RuntimeConfig::tool_rendering`).
+    pub tool_rendering: ToolRenderingSettings,
+    /// Custom instructions set with `/instructions`.
+    pub instructions: Option<String>,
+    /// Directory bound with `/project`.
+    pub project_directory: Option<String>,
+    /// Plan-then-build mode set with `/plan`.
+    pub plan_approval: bool,
+    /// Fallback chain for OpenCode prompts.
+    pub fallback_models: Vec<String>,
+    /// Language of bot-authored chat messages, set with `/locale`.
+    pub locale: String,
+    /// Dry-run mode set with `/dryrun`; `None` follows the global flag.
+    pub dry_run: Option<bool>,
+    /// Delivery mode set with `/delivery`.
+    pub delivery: DeliveryMode,
+    /// Collapsed detail set with `/collapse`.
+    pub collapse: bool,
+    /// Base URL that file paths in tool output link under.
+    pub repository_url: Option<String>,
+    /// Timezone set with `/timezone`; `None` follows the agent's.
+    pub timezone: Option<String>,
+    /// Worker budget set with `/budget`; `None` follows the agent's.
+    pub worker_budget_secs: Option<u64>,
 }
 
 impl ResolvedConversationSettings {
@@ -273,6 +509,32 @@ impl ResolvedConversationSettings {
                 resolved.save_attachments = sa;
             }
             resolved.worker_context = default.worker_context.clone();
+            if let Some(tool_rendering) = &default.tool_rendering {
+                resolved.tool_rendering = resolved.tool_rendering.overlay(tool_rendering);
+            }
+            resolved.instructions = default.instructions.clone();
+            resolved.project_directory = default.project_directory.clone();
+            if let Some(plan_approval) = default.plan_approval {
+                resolved.plan_approval = plan_approval;
+            }
+            if let Some(fallback_models) = &default.fallback_models {
+                resolved.fallback_models = fallback_models.clone();
+            }
+            if let Some(locale) = &default.locale {
+                resolved.locale = locale.clone();
+            }
+            if default.dry_run.is_some() {
+                resolved.dry_run = default.dry_run;
+            }
+            if let Some(delivery) = default.delivery {
+                resolved.delivery = delivery;
+            }
+            if let Some(collapse) = default.collapse {
+                resolved.collapse = collapse;
+            }
+            resolved.repository_url = default.repository_url.clone();
+            resolved.timezone = default.timezone.clone();
+            resolved.worker_budget_secs = default.worker_budget_secs;
         }
 
         // Apply channel overrides if present
@@ -291,6 +553,42 @@ impl ResolvedConversationSettings {
                 resolved.save_attachments = sa;
             }
             resolved.worker_context = channel_settings.worker_context.clone();
+            if let Some(tool_rendering) = &channel_settings.tool_rendering {
+                resolved.tool_rendering = resolved.tool_rendering.overlay(tool_rendering);
+            }
+            if channel_settings.instructions.is_some() {
+                resolved.instructions = channel_settings.instructions.clone();
+            }
+            if channel_settings.project_directory.is_some() {
+                resolved.project_directory = channel_settings.project_directory.clone();
+            }
+            if let Some(plan_approval) = channel_settings.plan_approval {
+                resolved.plan_approval = plan_approval;
+            }
+            if let Some(fallback_models) = &channel_settings.fallback_models {
+                resolved.fallback_models = fallback_models.clone();
+            }
+            if let Some(locale) = &channel_settings.locale {
+                resolved.locale = locale.clone();
+            }
+            if channel_settings.dry_run.is_some() {
+                resolved.dry_run = channel_settings.dry_run;
+            }
+            if let Some(delivery) = channel_settings.delivery {
+                resolved.delivery = delivery;
+            }
+            if let Some(collapse) = channel_settings.collapse {
+                resolved.collapse = collapse;
+            }
+            if channel_settings.repository_url.is_some() {
+                resolved.repository_url = channel_settings.repository_url.clone();
+            }
+            if channel_settings.timezone.is_some() {
+                resolved.timezone = channel_settings.timezone.clone();
+            }
+            if channel_settings.worker_budget_secs.is_some() {
+                resolved.worker_budget_secs = channel_settings.worker_budget_secs;
+            }
         }
 
         // Apply conversation overrides if present (highest priority)
@@ -309,6 +607,42 @@ impl ResolvedConversationSettings {
                 resolved.save_attachments = sa;
             }
             resolved.worker_context = conv_settings.worker_context.clone();
+            if let Some(tool_rendering) = &conv_settings.tool_rendering {
+                resolved.tool_rendering = resolved.tool_rendering.overlay(tool_rendering);
+            }
+            if conv_settings.instructions.is_some() {
+                resolved.instructions = conv_settings.instructions.clone();
+            }
+            if conv_settings.project_directory.is_some() {
+                resolved.project_directory = conv_settings.project_directory.clone();
+            }
+            if let Some(plan_approval) = conv_settings.plan_approval {
+                resolved.plan_approval = plan_approval;
+            }
+            if let Some(fallback_models) = &conv_settings.fallback_models {
+                resolved.fallback_models = fallback_models.clone();
+            }
+            if let Some(locale) = &conv_settings.locale {
+                resolved.locale = locale.clone();
+            }
+            if conv_settings.dry_run.is_some() {
+                resolved.dry_run = conv_settings.dry_run;
+            }
+            if let Some(delivery) = conv_settings.delivery {
+                resolved.delivery = delivery;
+            }
+            if let Some(collapse) = conv_settings.collapse {
+                resolved.collapse = collapse;
+            }
+            if conv_settings.repository_url.is_some() {
+                resolved.repository_url = conv_settings.repository_url.clone();
+            }
+            if conv_settings.timezone.is_some() {
+                resolved.timezone = conv_settings.timezone.clone();
+            }
+            if conv_settings.worker_budget_secs.is_some() {
+                resolved.worker_budget_secs = conv_settings.worker_budget_secs;
+            }
         }
 
         resolved
@@ -341,6 +675,18 @@ impl Default for ResolvedConversationSettings {
             response_mode: ResponseMode::Active,
             save_attachments: true,
             worker_context: WorkerContextMode::default(),
+            tool_rendering: ToolRenderingSettings::default(),
+            instructions: None,
+            project_directory: None,
+            plan_approval: false,
+            fallback_models: Vec::new(),
+            locale: crate::prompts::messages::DEFAULT_LOCALE.to_string(),
+            dry_run: None,
+            delivery: DeliveryMode::Live,
+            collapse: false,
+            repository_url: None,
+            timezone: None,
+            worker_budget_secs: None,
         }
     }
 }
@@ -425,6 +771,7 @@ mod tests {
         let channel_settings = ConversationSettings {
             model: Some("channel-model".to_string()),
             memory: MemoryMode::Ambient,
+            instructions: Some("Answer in French.".to_string()),
             ..Default::default()
         };
 
@@ -455,6 +802,46 @@ mod tests {
             WorkerHistoryMode::Recent(20)
         );
         assert_eq!(resolved.worker_context.memory, WorkerMemoryMode::Tools);
+        // Unset at the conversation level, so the channel's instructions hold.
+        assert_eq!(resolved.instructions.as_deref(), Some("Answer in French."));
+    }
+
+    #[test]
+    fn tool_rendering_resolves_per_tool_before_defaults() {
+        let instance = ToolRenderingSettings {
+            default: Some(ToolDetail::Summary),
+            tools: [("shell".to_string(), ToolDetail::Full)].into(),
+            summarize: ToolSummarySettings {
+                min_lines: Some(40),
+                model: Some("anthropic/claude-haiku-4-5".into()),
+            },
+        };
+        let channel = ToolRenderingSettings {
+            default: Some(ToolDetail::Name),
+            tools: [("webfetch".to_string(), ToolDetail::Hidden)].into(),
+            summarize: ToolSummarySettings {
+                min_lines: Some(0),
+                model: None,
+            },
+        };
+        let resolved = instance.overlay(&channel);
+
+        assert_eq!(resolved.resolve("shell"), ToolDetail::Full);
+        assert_eq!(resolved.resolve("webfetch"), ToolDetail::Hidden);
+        assert_eq!(resolved.resolve("file_write"), ToolDetail::Name);
+        // Read-style tools stay hidden under a blanket default.
+        assert_eq!(resolved.resolve("file_read"), ToolDetail::Hidden);
+        // The channel turns summaries off but keeps the inherited model.
+        assert_eq!(resolved.summary_threshold(), None);
+        assert_eq!(
+            resolved.summarize.model.as_deref(),
+            Some("anthropic/claude-haiku-4-5")
+        );
+        assert_eq!(instance.summary_threshold(), Some(40));
+        assert_eq!(
+            ToolRenderingSettings::default().resolve("shell"),
+            ToolDetail::Hidden
+        );
     }
 
     #[test]
@@ -467,5 +854,92 @@ mod tests {
         assert_eq!(resolved.delegation, DelegationMode::Standard);
         assert_eq!(resolved.worker_context.history, WorkerHistoryMode::None);
         assert_eq!(resolved.worker_context.memory, WorkerMemoryMode::None);
+        assert_eq!(resolved.dry_run, None);
+        assert_eq!(resolved.delivery, DeliveryMode::Live);
+        assert!(!resolved.collapse);
+    }
+
+    #[test]
+    fn dry_run_is_unset_until_a_level_sets_it() {
+        let channel_settings = ConversationSettings {
+            dry_run: Some(true),
+            ..Default::default()
+        };
+        let conversation_settings = ConversationSettings::default();
+
+        let resolved = ResolvedConversationSettings::resolve(
+            Some(&conversation_settings),
+            Some(&channel_settings),
+            None,
+        );
+        assert_eq!(resolved.dry_run, Some(true));
+
+        let conversation_settings = ConversationSettings {
+            dry_run: Some(false),
+            ..Default::default()
+        };
+        let resolved = ResolvedConversationSettings::resolve(
+            Some(&conversation_settings),
+            Some(&channel_settings),
+            None,
+        );
+        assert_eq!(resolved.dry_run, Some(false));
+    }
+
+    #[test]
+    fn worker_budget_inherits_until_a_level_sets_it() {
+        let agent_default = ConversationSettings {
+            worker_budget_secs: Some(600),
+            ..Default::default()
+        };
+        let resolved = ResolvedConversationSettings::resolve(
+            None,
+            Some(&ConversationSettings::default()),
+            Some(&agent_default),
+        );
+        assert_eq!(resolved.worker_budget_secs, Some(600));
+
+        let channel_settings = ConversationSettings {
+            worker_budget_secs: Some(120),
+            ..Default::default()
+        };
+        let resolved = ResolvedConversationSettings::resolve(
+            None,
+            Some(&channel_settings),
+            Some(&agent_default),
+        );
+        assert_eq!(resolved.worker_budget_secs, Some(120));
+    }
+
+    #[test]
+    fn delivery_mode_inherits_until_a_level_sets_it() {
+        let agent_default = ConversationSettings {
+            delivery: Some(DeliveryMode::Summary),
+            ..Default::default()
+        };
+        let channel_settings = ConversationSettings::default();
+
+        let resolved = ResolvedConversationSettings::resolve(
+            None,
+            Some(&channel_settings),
+            Some(&agent_default),
+        );
+        assert_eq!(resolved.delivery, DeliveryMode::Summary);
+
+        let channel_settings = ConversationSettings {
+            delivery: Some(DeliveryMode::Live),
+            ..Default::default()
+        };
+        let resolved = ResolvedConversationSettings::resolve(
+            None,
+            Some(&channel_settings),
+            Some(&agent_default),
+        );
+        assert_eq!(resolved.delivery, DeliveryMode::Live);
+        assert_eq!(
+            DeliveryMode::parse(" Summary "),
+            Some(DeliveryMode::Summary)
+        );
+        assert_eq!(DeliveryMode::parse("stream"), None);
     }
 }
@@ -0,0 +1,430 @@
+//! Full-text search over stored worker transcripts (`/search`).
+//!
+//! Transcripts are gzip-compressed in `worker_runs.transcript`, so SQLite
+//! can't index them in place. [`TranscriptSearch::sync`] decompresses new and
+//! grown transcripts into the `worker_transcripts_fts` FTS5 table, and
+//! [`TranscriptSearch::search`] runs it before every query so results include
+//! everything persisted up to that point.
+
+use crate::conversation::worker_transcript::{
+    ActionContent, TranscriptStep, deserialize_transcript,
+};
+
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::HashMap;
+
+/// Transcripts decompressed and indexed per sync transaction.
+const SYNC_BATCH: i64 = 100;
+
+/// Tokens of context around each match in a snippet.
+const SNIPPET_TOKENS: i64 = 16;
+
+/// Characters of a single tool result kept in the index. Huge file dumps
+/// and build logs add little to search and bloat the table.
+const MAX_TOOL_RESULT_CHARS: usize = 4_000;
+
+/// A transcript matching a search, with the chat message that started it.
+#[derive(Debug, Clone)]
+pub struct TranscriptHit {
+    pub worker_id: String,
+    pub task: String,
+    pub opencode_session_id: Option<String>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Matching excerpt, with matches wrapped in `**`.
+    pub snippet: String,
+    /// Platform ID of the user message the worker was started from.
+    pub message_id: Option<String>,
+    /// Link to that message, on platforms with message permalinks.
+    pub message_link: Option<String>,
+}
+
+/// Search index over one agent's worker transcripts.
+#[derive(Debug, Clone)]
+pub struct TranscriptSearch {
+    pool: SqlitePool,
+}
+
+impl TranscriptSearch {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Index transcripts that are new or have grown since they were last
+    /// indexed. Returns how many were (re)indexed.
+    pub async fn sync(&self) -> crate::error::Result<usize> {
+        let mut indexed = 0;
+        loop {
+            let batch = self.sync_batch().await?;
+            indexed += batch;
+            if batch < SYNC_BATCH as usize {
+                return Ok(indexed);
+            }
+        }
+    }
+
+    async fn sync_batch(&self) -> crate::error::Result<usize> {
+        let rows = sqlx::query(
+            "SELECT w.id, w.transcript, length(w.transcript) AS transcript_size \
+             FROM worker_runs w \
+             LEFT JOIN worker_transcript_index i ON i.worker_id = w.id \
+             WHERE w.transcript IS NOT NULL \
+               AND (i.worker_id IS NULL OR i.transcript_size != length(w.transcript)) \
+             LIMIT ?",
+        )
+        .bind(SYNC_BATCH)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+        for row in &rows {
+            let worker_id: String = row.try_get("id").map_err(|error| anyhow::anyhow!(error))?;
+            let blob: Vec<u8> = row
+                .try_get("transcript")
+                .map_err(|error| anyhow::anyhow!(error))?;
+            let transcript_size: i64 = row
+                .try_get("transcript_size")
+                .map_err(|error| anyhow::anyhow!(error))?;
+
+            // An unreadable transcript is still recorded, with no content, so
+            // it isn't retried on every search.
+            let content = match deserialize_transcript(&blob) {
+                Ok(steps) => searchable_text(&steps),
+                Err(error) => {
+                    tracing::warn!(%error, %worker_id, "failed to decode transcript for search");
+                    String::new()
+                }
+            };
+
+            sqlx::query(
+                "DELETE FROM worker_transcripts_fts WHERE rowid IN \
+                 (SELECT id FROM worker_transcript_index WHERE worker_id = ?)",
+            )
+            .bind(&worker_id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+            let index_id: i64 = sqlx::query_scalar(
+                "INSERT INTO worker_transcript_index (worker_id, transcript_size) VALUES (?, ?) \
+                 ON CONFLICT(worker_id) DO UPDATE SET \
+                     transcript_size = excluded.transcript_size, \
+                     indexed_at = CURRENT_TIMESTAMP \
+                 RETURNING id",
+            )
+            .bind(&worker_id)
+            .bind(transcript_size)
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+            sqlx::query("INSERT INTO worker_transcripts_fts (rowid, content) VALUES (?, ?)")
+                .bind(index_id)
+                .bind(&content)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|error| anyhow::anyhow!(error))?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows.len())
+    }
+
+    /// Transcripts from `channel_id` matching `query`, best match first.
+    ///
+    /// The query is split into terms that must all match; the last term
+    /// matches as a prefix.
+    pub async fn search(
+        &self,
+        channel_id: &str,
+        query: &str,
+        limit: i64,
+    ) -> crate::error::Result<Vec<TranscriptHit>> {
+        let fts_query = crate::wiki::sanitize_fts_query(query);
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.sync().await?;
+
+        let rows = sqlx::query(
+            "SELECT w.id, w.task, w.opencode_session_id, w.started_at, \
+                    snippet(worker_transcripts_fts, 0, '**', '**', '…', ?) AS snippet, \
+                    (SELECT m.metadata FROM conversation_messages m \
+                     WHERE m.channel_id = w.channel_id AND m.role = 'user' \
+                       AND m.created_at <= w.started_at \
+                     ORDER BY m.created_at DESC LIMIT 1) AS origin_metadata \
+             FROM worker_transcripts_fts f \
+             JOIN worker_transcript_index i ON i.id = f.rowid \
+             JOIN worker_runs w ON w.id = i.worker_id \
+             WHERE worker_transcripts_fts MATCH ? AND w.channel_id = ? \
+             ORDER BY rank \
+             LIMIT ?",
+        )
+        .bind(SNIPPET_TOKENS)
+        .bind(&fts_query)
+        .bind(channel_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let metadata: HashMap<String, serde_json::Value> = row
+                    .try_get::<Option<String>, _>("origin_metadata")
+                    .ok()
+                    .flatten()
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+                TranscriptHit {
+                    worker_id: row.try_get("id").unwrap_or_default(),
+                    task: row.try_get("task").unwrap_or_default(),
+                    opencode_session_id: row.try_get("opencode_session_id").ok().flatten(),
+                    started_at: row.try_get("started_at").ok(),
+                    snippet: row.try_get("snippet").unwrap_or_default(),
+                    message_id: metadata_string(&metadata, crate::metadata_keys::MESSAGE_ID),
+                    message_link: message_link(&metadata),
+                }
+            })
+            .collect())
+    }
+}
+
+/// The text of a transcript that search matches against: user input, agent
+/// replies, tool calls, and (truncated) tool results. System prompts are left
+/// out — they repeat across every transcript.
+pub fn searchable_text(steps: &[TranscriptStep]) -> String {
+    let mut parts = Vec::new();
+    for step in steps {
+        match step {
+            TranscriptStep::UserText { text } => parts.push(text.clone()),
+            TranscriptStep::SystemText { .. } => {}
+            TranscriptStep::Action { content } => {
+                for item in content {
+                    match item {
+                        ActionContent::Text { text } => parts.push(text.clone()),
+                        ActionContent::ToolCall { name, args, .. } => {
+                            parts.push(format!("{name} {args}"));
+                        }
+                    }
+                }
+            }
+            TranscriptStep::ToolResult { name, text, .. } => {
+                let text: String = text.chars().take(MAX_TOOL_RESULT_CHARS).collect();
+                parts.push(format!("{name} {text}"));
+            }
+        }
+    }
+    parts.join("\n")
+}
+
+fn metadata_string(metadata: &HashMap<String, serde_json::Value>, key: &str) -> Option<String> {
+    match metadata.get(key)? {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// A permalink to the message described by `metadata`, where the platform
+/// has one that can be built from adapter metadata alone.
+fn message_link(metadata: &HashMap<String, serde_json::Value>) -> Option<String> {
+    let guild_id = metadata_string(metadata, "discord_guild_id")?;
+    let channel_id = metadata_string(metadata, "discord_channel_id")?;
+    let message_id = metadata_string(metadata, "discord_message_id")?;
+    Some(format!(
+        "https://discord.com/channels/{guild_id}/{channel_id}/{message_id}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::worker_transcript::serialize_steps;
+
+    async fn setup() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        for channel_id in ["discord:1:2", "discord:1:3"] {
+            sqlx::query("INSERT INTO channels (id, platform) VALUES (?, 'discord')")
+                .bind(channel_id)
+                .execute(&pool)
+                .await
+                .expect("insert channel");
+        }
+        pool
+    }
+
+    fn transcript(reply: &str) -> Vec<u8> {
+        serialize_steps(&[
+            TranscriptStep::SystemText {
+                text: "You are a worker.".to_string(),
+            },
+            TranscriptStep::UserText {
+                text: "fix the flaky test".to_string(),
+            },
+            TranscriptStep::Action {
+                content: vec![ActionContent::Text {
+                    text: reply.to_string(),
+                }],
+            },
+        ])
+    }
+
+    async fn insert_worker(pool: &SqlitePool, worker_id: &str, channel_id: &str, blob: &[u8]) {
+        sqlx::query(
+            "INSERT INTO worker_runs (id, channel_id, task, status, transcript, started_at) \
+             VALUES (?, ?, 'fix the flaky test', 'done', ?, '2026-10-15 12:00:00')",
+        )
+        .bind(worker_id)
+        .bind(channel_id)
+        .bind(blob)
+        .execute(pool)
+        .await
+        .expect("insert worker run");
+    }
+
+    #[test]
+    fn searchable_text_skips_system_prompts_and_truncates_tool_output() {
+        let text = searchable_text(&[
+            TranscriptStep::SystemText {
+                text: "You are a worker.".to_string(),
+            },
+            TranscriptStep::Action {
+                content: vec![ActionContent::ToolCall {
+                    id: "call-1".to_string(),
+                    name: "shell".to_string(),
+                    args: "{\"command\":\"cargo test\"}".to_string(),
+                }],
+            },
+            TranscriptStep::ToolResult {
+                call_id: "call-1".to_string(),
+                name: "shell".to_string(),
+                text: "x".repeat(MAX_TOOL_RESULT_CHARS * 2),
+                live_output: None,
+                status: Default::default(),
+            },
+        ]);
+        assert!(!text.contains("You are a worker."));
+        assert!(text.starts_with("shell {\"command\":\"cargo test\"}\n"));
+        assert!(text.len() < MAX_TOOL_RESULT_CHARS + 100);
+    }
+
+    #[tokio::test]
+    async fn finds_transcripts_in_the_channel_and_links_the_origin_message() {
+        let pool = setup().await;
+        insert_worker(
+            &pool,
+            "worker-1",
+            "discord:1:2",
+            &transcript("the race is in the retry loop"),
+        )
+        .await;
+        insert_worker(
+            &pool,
+            "worker-2",
+            "discord:1:3",
+            &transcript("the race is elsewhere"),
+        )
+        .await;
+        sqlx::query(
+            "INSERT INTO conversation_messages (id, channel_id, role, content, metadata, created_at) \
+             VALUES ('message-1', 'discord:1:2', 'user', 'fix the flaky test', ?, '2026-10-15 11:59:58')",
+        )
+        .bind(r#"{"message_id":"42","discord_guild_id":1,"discord_channel_id":2,"discord_message_id":42}"#)
+        .execute(&pool)
+        .await
+        .expect("insert message");
+
+        let search = TranscriptSearch::new(pool.clone());
+        let hits = search
+            .search("discord:1:2", "retry lo", 5)
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.worker_id, "worker-1");
+        assert!(hit.snippet.contains("**retry**"), "{}", hit.snippet);
+        assert_eq!(hit.message_id.as_deref(), Some("42"));
+        assert_eq!(
+            hit.message_link.as_deref(),
+            Some("https://discord.com/channels/1/2/42")
+        );
+
+        assert!(
+            search
+                .search("discord:1:2", "\"*", 5)
+                .await
+                .expect("search")
+                .is_empty()
+        );
+        assert!(
+            search
+                .search("discord:1:2", "elsewhere", 5)
+                .await
+                .expect("search")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn reindexes_grown_transcripts() {
+        let pool = setup().await;
+        insert_worker(
+            &pool,
+            "worker-1",
+            "discord:1:2",
+            &transcript("still looking"),
+        )
+        .await;
+        let search = TranscriptSearch::new(pool.clone());
+        assert_eq!(search.sync().await.expect("sync"), 1);
+        assert_eq!(search.sync().await.expect("sync"), 0);
+
+        sqlx::query("UPDATE worker_runs SET transcript = ? WHERE id = 'worker-1'")
+            .bind(transcript(
+                "found it: the fixture leaks a tempdir between runs",
+            ))
+            .execute(&pool)
+            .await
+            .expect("update transcript");
+        let hits = search
+            .search("discord:1:2", "tempdir", 5)
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 1);
+        assert!(
+            search
+                .search("discord:1:2", "looking", 5)
+                .await
+                .expect("search")
+                .is_empty()
+        );
+
+        sqlx::query("DELETE FROM worker_runs WHERE id = 'worker-1'")
+            .execute(&pool)
+            .await
+            .expect("delete worker run");
+        assert!(
+            search
+                .search("discord:1:2", "tempdir", 5)
+                .await
+                .expect("search")
+                .is_empty()
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! Converts a Rig `Vec<Message>` history into a flat `Vec<TranscriptStep>`,
 //! then serializes to gzipped JSON for compact storage on the `worker_runs` row.
 
+use crate::opencode::render::{ToolOutput, ToolOutputRenderers};
 use crate::tools::{MAX_TOOL_OUTPUT_BYTES, truncate_output};
 
 use flate2::Compression;
@@ -14,6 +15,13 @@ use std::io::{Read, Write};
 /// Maximum byte length for tool call arguments in transcripts.
 const MAX_TOOL_ARGS_BYTES: usize = 2_000;
 
+/// Part versions remembered for spotting re-sent updates.
+const REPEAT_WINDOW: usize = 512;
+
+/// Distinct parts an assembler keeps. Updates to parts it already has still
+/// apply past this; new parts are dropped.
+pub const MAX_ASSEMBLED_PARTS: usize = 10_000;
+
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolResultStatus {
@@ -199,7 +207,13 @@ pub fn convert_opencode_messages(messages: &[serde_json::Value]) -> (Vec<Transcr
                                 .and_then(|s| s.get("output"))
                                 .and_then(|o| o.as_str())
                                 .unwrap_or("");
-                            let truncated = truncate_output(output, MAX_TOOL_OUTPUT_BYTES);
+                            let rendered = ToolOutputRenderers::global().render(&ToolOutput {
+                                tool: tool_name,
+                                input: state.and_then(|s| s.get("input")),
+                                output,
+                                metadata: state.and_then(|s| s.get("metadata")),
+                            });
+                            let truncated = truncate_output(&rendered, MAX_TOOL_OUTPUT_BYTES);
                             steps.push(TranscriptStep::ToolResult {
                                 call_id,
                                 name: tool_name.to_string(),
@@ -247,98 +261,223 @@ pub fn convert_opencode_messages(messages: &[serde_json::Value]) -> (Vec<Transcr
 pub fn convert_opencode_parts(
     parts: &[crate::opencode::types::OpenCodePart],
 ) -> Vec<TranscriptStep> {
-    use crate::opencode::types::{OpenCodePart, OpenCodeToolState};
+    parts
+        .iter()
+        .cloned()
+        .collect::<OpenCodeTranscriptAssembler>()
+        .steps()
+}
+
+/// Transcript of a streaming OpenCode session, assembled part by part.
+///
+/// SSE updates re-send a whole part under the same ID: text on every delta,
+/// tools on every state change. The assembler keeps one entry per ID in
+/// first-seen order and caches each part's rendered steps, so a snapshot
+/// only re-renders the parts that changed since the previous one.
+///
+/// After a reconnect OpenCode may re-send updates it already delivered. The
+/// assembler remembers the last [`REPEAT_WINDOW`] part versions, keyed on
+/// message ID, part ID, and a hash of the part's state, so callers can drop
+/// exact repeats before they double a tool entry or a status update.
+///
+/// At most [`MAX_ASSEMBLED_PARTS`] parts are kept, so a runaway or hostile
+/// stream can't grow a transcript without bound.
+#[derive(Debug, Default)]
+pub struct OpenCodeTranscriptAssembler {
+    positions: std::collections::HashMap<String, usize>,
+    parts: Vec<AssembledPart>,
+    recent: std::collections::VecDeque<PartVersion>,
+    recent_set: std::collections::HashSet<PartVersion>,
+    dropped_parts: usize,
+}
 
-    // Deduplicate: keep insertion order, but replace earlier versions with later ones.
-    let mut seen = std::collections::HashMap::<String, usize>::new();
-    let mut deduped: Vec<&OpenCodePart> = Vec::new();
-    for part in parts {
-        let id = part.id().to_string();
-        if let Some(&index) = seen.get(&id) {
-            deduped[index] = part;
-        } else {
-            seen.insert(id, deduped.len());
-            deduped.push(part);
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PartVersion {
+    message_id: Option<String>,
+    part_id: String,
+    state_hash: u64,
+}
+
+#[derive(Debug)]
+struct AssembledPart {
+    part: crate::opencode::types::OpenCodePart,
+    /// Rendered steps. `None` until first rendered and after each update.
+    steps: Option<Vec<TranscriptStep>>,
+}
+
+impl OpenCodeTranscriptAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a part, or replace the earlier version with the same ID. A new
+    /// part is dropped once the assembler holds [`MAX_ASSEMBLED_PARTS`].
+    pub fn upsert(&mut self, part: crate::opencode::types::OpenCodePart) {
+        match self.positions.get(part.id()) {
+            Some(&position) => {
+                let entry = &mut self.parts[position];
+                entry.part = part;
+                entry.steps = None;
+            }
+            None if self.parts.len() >= MAX_ASSEMBLED_PARTS => {
+                if self.dropped_parts == 0 {
+                    tracing::warn!(
+                        limit = MAX_ASSEMBLED_PARTS,
+                        "OpenCode transcript is full, dropping new parts"
+                    );
+                }
+                self.dropped_parts += 1;
+            }
+            None => {
+                self.positions
+                    .insert(part.id().to_string(), self.parts.len());
+                self.parts.push(AssembledPart { part, steps: None });
+            }
+        }
+    }
+
+    /// Whether this exact version of a part was seen within the window.
+    /// Records the version either way.
+    pub fn is_repeat(
+        &mut self,
+        message_id: Option<&str>,
+        part: &crate::opencode::types::OpenCodePart,
+    ) -> bool {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        serde_json::to_string(part)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        let version = PartVersion {
+            message_id: message_id.map(String::from),
+            part_id: part.id().to_string(),
+            state_hash: hasher.finish(),
+        };
+        if self.recent_set.contains(&version) {
+            return true;
+        }
+        if self.recent.len() >= REPEAT_WINDOW
+            && let Some(oldest) = self.recent.pop_front()
+        {
+            self.recent_set.remove(&oldest);
+        }
+        self.recent.push_back(version.clone());
+        self.recent_set.insert(version);
+        false
+    }
+
+    /// Number of distinct parts kept.
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Transcript steps for every part, rendering only parts that changed.
+    pub fn steps(&mut self) -> Vec<TranscriptStep> {
+        let mut steps = Vec::new();
+        for entry in &mut self.parts {
+            let rendered = entry
+                .steps
+                .get_or_insert_with(|| render_opencode_part(&entry.part));
+            steps.extend(rendered.iter().cloned());
         }
+        steps
     }
+}
+
+impl FromIterator<crate::opencode::types::OpenCodePart> for OpenCodeTranscriptAssembler {
+    fn from_iter<I: IntoIterator<Item = crate::opencode::types::OpenCodePart>>(parts: I) -> Self {
+        let mut assembler = Self::new();
+        for part in parts {
+            assembler.upsert(part);
+        }
+        assembler
+    }
+}
+
+/// Transcript steps for one `OpenCodePart`.
+fn render_opencode_part(part: &crate::opencode::types::OpenCodePart) -> Vec<TranscriptStep> {
+    use crate::opencode::types::{OpenCodePart, OpenCodeToolState};
 
     let mut steps = Vec::new();
-    for part in deduped {
-        match part {
-            OpenCodePart::Text { text, .. } => {
-                if !text.is_empty() {
+    match part {
+        OpenCodePart::Text { text, .. } => {
+            if !text.is_empty() {
+                steps.push(TranscriptStep::Action {
+                    content: vec![ActionContent::Text { text: text.clone() }],
+                });
+            }
+        }
+        OpenCodePart::Tool { id, tool, state } => {
+            match state {
+                OpenCodeToolState::Running { input, .. }
+                | OpenCodeToolState::Completed { input, .. } => {
+                    let args = input.clone().unwrap_or_default();
+                    let args = if args.len() > MAX_TOOL_ARGS_BYTES {
+                        truncate_output(&args, MAX_TOOL_ARGS_BYTES)
+                    } else {
+                        args
+                    };
                     steps.push(TranscriptStep::Action {
-                        content: vec![ActionContent::Text { text: text.clone() }],
+                        content: vec![ActionContent::ToolCall {
+                            id: id.clone(),
+                            name: tool.clone(),
+                            args,
+                        }],
                     });
                 }
-            }
-            OpenCodePart::Tool { id, tool, state } => {
-                match state {
-                    OpenCodeToolState::Running { input, .. }
-                    | OpenCodeToolState::Completed { input, .. } => {
-                        let args = input.clone().unwrap_or_default();
-                        let args = if args.len() > MAX_TOOL_ARGS_BYTES {
-                            truncate_output(&args, MAX_TOOL_ARGS_BYTES)
-                        } else {
-                            args
-                        };
-                        steps.push(TranscriptStep::Action {
-                            content: vec![ActionContent::ToolCall {
-                                id: id.clone(),
-                                name: tool.clone(),
-                                args,
-                            }],
-                        });
-                    }
-                    OpenCodeToolState::Pending => {
-                        steps.push(TranscriptStep::Action {
-                            content: vec![ActionContent::ToolCall {
-                                id: id.clone(),
-                                name: tool.clone(),
-                                args: String::new(),
-                            }],
-                        });
-                    }
-                    OpenCodeToolState::Error { .. } => {
-                        steps.push(TranscriptStep::Action {
-                            content: vec![ActionContent::ToolCall {
-                                id: id.clone(),
-                                name: tool.clone(),
-                                args: String::new(),
-                            }],
-                        });
-                    }
-                }
-
-                // Add result for completed/error states
-                match state {
-                    OpenCodeToolState::Completed { output, .. } => {
-                        let text = output.as_deref().unwrap_or("");
-                        let truncated = truncate_output(text, MAX_TOOL_OUTPUT_BYTES);
-                        steps.push(TranscriptStep::ToolResult {
-                            call_id: id.clone(),
+                OpenCodeToolState::Pending => {
+                    steps.push(TranscriptStep::Action {
+                        content: vec![ActionContent::ToolCall {
+                            id: id.clone(),
                             name: tool.clone(),
-                            text: truncated,
-                            live_output: None,
-                            status: ToolResultStatus::Final,
-                        });
-                    }
-                    OpenCodeToolState::Error { error } => {
-                        let error_text = error.as_deref().unwrap_or("unknown error");
-                        steps.push(TranscriptStep::ToolResult {
-                            call_id: id.clone(),
+                            args: String::new(),
+                        }],
+                    });
+                }
+                OpenCodeToolState::Error { .. } => {
+                    steps.push(TranscriptStep::Action {
+                        content: vec![ActionContent::ToolCall {
+                            id: id.clone(),
                             name: tool.clone(),
-                            text: format!("Error: {error_text}"),
-                            live_output: None,
-                            status: ToolResultStatus::Final,
-                        });
-                    }
-                    _ => {}
+                            args: String::new(),
+                        }],
+                    });
+                }
+            }
+
+            // Add result for completed/error states
+            match state {
+                OpenCodeToolState::Completed { output, .. } => {
+                    let text = output.as_deref().unwrap_or("");
+                    let truncated = truncate_output(text, MAX_TOOL_OUTPUT_BYTES);
+                    steps.push(TranscriptStep::ToolResult {
+                        call_id: id.clone(),
+                        name: tool.clone(),
+                        text: truncated,
+                        live_output: None,
+                        status: ToolResultStatus::Final,
+                    });
+                }
+                OpenCodeToolState::Error { error } => {
+                    let error_text = error.as_deref().unwrap_or("unknown error");
+                    steps.push(TranscriptStep::ToolResult {
+                        call_id: id.clone(),
+                        name: tool.clone(),
+                        text: format!("Error: {error_text}"),
+                        live_output: None,
+                        status: ToolResultStatus::Final,
+                    });
                 }
+                _ => {}
             }
-            // step_start/step_finish are visual separators, skip for transcript
-            OpenCodePart::StepStart { .. } | OpenCodePart::StepFinish { .. } => {}
         }
+        // step_start/step_finish are visual separators, skip for transcript
+        OpenCodePart::StepStart { .. } | OpenCodePart::StepFinish { .. } => {}
     }
     steps
 }
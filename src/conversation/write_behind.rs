@@ -0,0 +1,251 @@
+//! Write-behind buffer for transcript and token usage writes.
+//!
+//! Workers snapshot their transcript after every segment and at every idle
+//! boundary, and every LLM process records its token usage when it finishes.
+//! Instead of one SQLite transaction per write, [`WriteBehind`] queues them
+//! and a background task commits the queue in a single transaction once it
+//! holds [`MAX_PENDING_WRITES`] writes or [`FLUSH_INTERVAL`] has passed.
+//! Transcript snapshots for the same worker coalesce, so only the newest one
+//! reaches the database.
+//!
+//! Callers that must see a write on disk before continuing (a worker going
+//! idle, shutdown) await [`WriteBehind::flush`]. A crash loses at most the
+//! writes queued since the last flush. Committed batches survive it because
+//! the agent database runs in WAL mode.
+
+use crate::llm::usage::UsageRecord;
+
+use sqlx::SqlitePool;
+use tokio::sync::{mpsc, oneshot};
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Longest a queued write waits before it is committed.
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Queued writes that trigger a commit without waiting for the timer.
+pub const MAX_PENDING_WRITES: usize = 64;
+
+/// Commands buffered between callers and the writer task.
+const COMMAND_BUFFER: usize = 1024;
+
+enum Command {
+    Transcript(TranscriptWrite),
+    Usage(UsageRecord),
+    Flush(oneshot::Sender<()>),
+}
+
+struct TranscriptWrite {
+    worker_id: String,
+    blob: Vec<u8>,
+    tool_calls: i64,
+}
+
+/// Handle to the background writer for one agent database.
+#[derive(Debug, Clone)]
+pub struct WriteBehind {
+    command_tx: mpsc::Sender<Command>,
+}
+
+impl WriteBehind {
+    /// Start the writer task for `pool`. The task commits anything left and
+    /// exits once every handle is dropped.
+    pub fn spawn(pool: SqlitePool) -> Self {
+        let (command_tx, command_rx) = mpsc::channel(COMMAND_BUFFER);
+        tokio::spawn(run_writer(pool, command_rx));
+        Self { command_tx }
+    }
+
+    /// Queue a worker's transcript blob and tool call count. Replaces any
+    /// snapshot for the same worker that hasn't been committed yet.
+    pub async fn write_transcript(
+        &self,
+        worker_id: impl Into<String>,
+        blob: Vec<u8>,
+        tool_calls: i64,
+    ) {
+        self.send(Command::Transcript(TranscriptWrite {
+            worker_id: worker_id.into(),
+            blob,
+            tool_calls,
+        }))
+        .await;
+    }
+
+    /// Queue a `token_usage` row.
+    pub async fn write_usage(&self, record: UsageRecord) {
+        self.send(Command::Usage(record)).await;
+    }
+
+    /// Commit everything queued so far and wait for it to land.
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        self.send(Command::Flush(done_tx)).await;
+        done_rx.await.ok();
+    }
+
+    async fn send(&self, command: Command) {
+        if self.command_tx.send(command).await.is_err() {
+            tracing::warn!("write-behind task has stopped, dropping write");
+        }
+    }
+}
+
+/// Writes waiting for the next commit.
+#[derive(Default)]
+struct PendingWrites {
+    transcripts: Vec<TranscriptWrite>,
+    /// Index into `transcripts` by worker ID, for coalescing.
+    transcript_positions: HashMap<String, usize>,
+    usage: Vec<UsageRecord>,
+}
+
+impl PendingWrites {
+    fn len(&self) -> usize {
+        self.transcripts.len() + self.usage.len()
+    }
+
+    fn push_transcript(&mut self, write: TranscriptWrite) {
+        match self.transcript_positions.get(&write.worker_id) {
+            Some(&position) => self.transcripts[position] = write,
+            None => {
+                self.transcript_positions
+                    .insert(write.worker_id.clone(), self.transcripts.len());
+                self.transcripts.push(write);
+            }
+        }
+    }
+
+    /// Commit all pending writes in one transaction. On failure the batch is
+    /// dropped with a warning, as individual writes were before batching.
+    async fn commit(&mut self, pool: &SqlitePool) {
+        if self.len() == 0 {
+            return;
+        }
+        let writes = self.len();
+        let transcripts = std::mem::take(&mut self.transcripts);
+        let usage = std::mem::take(&mut self.usage);
+        self.transcript_positions.clear();
+
+        if let Err(error) = commit_batch(pool, &transcripts, &usage).await {
+            tracing::warn!(%error, writes, "failed to commit write-behind batch");
+        }
+    }
+}
+
+async fn commit_batch(
+    pool: &SqlitePool,
+    transcripts: &[TranscriptWrite],
+    usage: &[UsageRecord],
+) -> Result<(), sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    for write in transcripts {
+        sqlx::query("UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?")
+            .bind(&write.blob)
+            .bind(write.tool_calls)
+            .bind(&write.worker_id)
+            .execute(&mut *transaction)
+            .await?;
+    }
+    for record in usage {
+        record.insert(&mut *transaction).await?;
+    }
+    transaction.commit().await
+}
+
+async fn run_writer(pool: SqlitePool, mut command_rx: mpsc::Receiver<Command>) {
+    let mut pending = PendingWrites::default();
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            command = command_rx.recv() => match command {
+                Some(Command::Transcript(write)) => pending.push_transcript(write),
+                Some(Command::Usage(record)) => pending.usage.push(record),
+                Some(Command::Flush(done_tx)) => {
+                    pending.commit(&pool).await;
+                    done_tx.send(()).ok();
+                    continue;
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {
+                pending.commit(&pool).await;
+                continue;
+            }
+        }
+        if pending.len() >= MAX_PENDING_WRITES {
+            pending.commit(&pool).await;
+        }
+    }
+    pending.commit(&pool).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_worker_runs_table() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::query(
+            "CREATE TABLE worker_runs (id TEXT PRIMARY KEY, transcript BLOB, tool_calls INTEGER)",
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create worker_runs table");
+        pool
+    }
+
+    async fn transcript_of(pool: &SqlitePool, worker_id: &str) -> (Option<Vec<u8>>, Option<i64>) {
+        sqlx::query_as("SELECT transcript, tool_calls FROM worker_runs WHERE id = ?")
+            .bind(worker_id)
+            .fetch_one(pool)
+            .await
+            .expect("worker row exists")
+    }
+
+    #[tokio::test]
+    async fn flush_commits_only_the_newest_snapshot_per_worker() {
+        let pool = setup_worker_runs_table().await;
+        for worker_id in ["a", "b"] {
+            sqlx::query("INSERT INTO worker_runs (id) VALUES (?)")
+                .bind(worker_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let writer = WriteBehind::spawn(pool.clone());
+        for tool_calls in 1..=3 {
+            writer
+                .write_transcript("a", vec![tool_calls as u8], tool_calls)
+                .await;
+        }
+        writer.write_transcript("b", vec![9], 9).await;
+        writer.flush().await;
+
+        assert_eq!(transcript_of(&pool, "a").await, (Some(vec![3]), Some(3)));
+        assert_eq!(transcript_of(&pool, "b").await, (Some(vec![9]), Some(9)));
+    }
+
+    #[tokio::test]
+    async fn queued_writes_commit_after_the_flush_interval() {
+        let pool = setup_worker_runs_table().await;
+        sqlx::query("INSERT INTO worker_runs (id) VALUES ('a')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let writer = WriteBehind::spawn(pool.clone());
+        writer.write_transcript("a", vec![1], 1).await;
+        tokio::time::sleep(FLUSH_INTERVAL * 2).await;
+
+        assert_eq!(transcript_of(&pool, "a").await, (Some(vec![1]), Some(1)));
+    }
+}
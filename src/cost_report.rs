@@ -0,0 +1,468 @@
+//! Spend reports: estimated LLM cost per user and per model, read from
+//! `token_usage`.
+//!
+//! Channel turns, and the branches and workers they spawn, record the
+//! `source:sender_id` of the user who prompted the turn. Background work
+//! (cortex, cron, resumed workers) has no user and is reported as
+//! unattributed. Usage from private (DM) sessions is left out. Reports back
+//! `/stats cost` and the optional weekly report configured by
+//! `[cost_report]`.
+
+use crate::AgentDeps;
+use crate::AgentId;
+use crate::config::CostReportConfig;
+use crate::prompts::messages;
+
+use chrono::{DateTime, Datelike as _, Duration, TimeZone as _, Utc};
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Rows shown per table.
+const MAX_ROWS: i64 = 10;
+
+/// Longest user or model label before it is cut.
+const MAX_LABEL_CHARS: usize = 28;
+
+/// Trailing window a report covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl CostPeriod {
+    /// Parse `day`, `week`, or `month`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    pub fn hours(self) -> i64 {
+        match self {
+            Self::Day => 24,
+            Self::Week => 7 * 24,
+            Self::Month => 30 * 24,
+        }
+    }
+
+    fn message_key(self) -> &'static str {
+        match self {
+            Self::Day => "stats.cost_day",
+            Self::Week => "stats.cost_week",
+            Self::Month => "stats.cost_month",
+        }
+    }
+}
+
+/// Spend attributed to one user or model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostLine {
+    /// Display name, user ID, or model. `None` for unattributed usage.
+    pub label: Option<String>,
+    pub requests: i64,
+    pub cost_usd: f64,
+}
+
+/// An agent's spend over a window, broken down by user and by model.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostBreakdown {
+    pub total_usd: f64,
+    pub by_user: Vec<CostLine>,
+    pub by_model: Vec<CostLine>,
+}
+
+impl CostBreakdown {
+    pub fn is_empty(&self) -> bool {
+        self.by_user.is_empty() && self.by_model.is_empty()
+    }
+}
+
+/// The agent's spend over the trailing `hours`, largest first.
+pub async fn cost_breakdown(
+    pool: &SqlitePool,
+    agent_id: &str,
+    hours: i64,
+) -> crate::error::Result<CostBreakdown> {
+    let window = format!("-{hours} hours");
+    // Users are labelled with the latest display name they sent a message
+    // under; `user_id` is `source:sender_id`, conversation rows store the
+    // bare sender ID.
+    let user_rows = sqlx::query(
+        "SELECT user_id, \
+                (SELECT sender_name FROM conversation_messages \
+                 WHERE sender_id = substr(token_usage.user_id, instr(token_usage.user_id, ':') + 1) \
+                   AND sender_name IS NOT NULL \
+                 ORDER BY created_at DESC LIMIT 1) AS sender_name, \
+                SUM(request_count) AS requests, \
+                COALESCE(SUM(estimated_cost_usd), 0.0) AS cost_usd \
+         FROM token_usage \
+         WHERE agent_id = ? AND private = 0 \
+           AND recorded_at >= strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?) \
+         GROUP BY user_id \
+         ORDER BY cost_usd DESC, requests DESC \
+         LIMIT ?",
+    )
+    .bind(agent_id)
+    .bind(&window)
+    .bind(MAX_ROWS)
+    .fetch_all(pool);
+    let model_rows = sqlx::query(
+        "SELECT model, SUM(request_count) AS requests, \
+                COALESCE(SUM(estimated_cost_usd), 0.0) AS cost_usd \
+         FROM token_usage \
+         WHERE agent_id = ? AND private = 0 \
+           AND recorded_at >= strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?) \
+         GROUP BY model \
+         ORDER BY cost_usd DESC, requests DESC \
+         LIMIT ?",
+    )
+    .bind(agent_id)
+    .bind(&window)
+    .bind(MAX_ROWS)
+    .fetch_all(pool);
+    let total = sqlx::query(
+        "SELECT COALESCE(SUM(estimated_cost_usd), 0.0) AS total FROM token_usage \
+         WHERE agent_id = ? AND private = 0 \
+           AND recorded_at >= strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?)",
+    )
+    .bind(agent_id)
+    .bind(&window)
+    .fetch_one(pool);
+    let (user_rows, model_rows, total) =
+        tokio::try_join!(user_rows, model_rows, total).map_err(|error| anyhow::anyhow!(error))?;
+
+    let line = |label: Option<String>, row: &sqlx::sqlite::SqliteRow| CostLine {
+        label,
+        requests: row.try_get("requests").unwrap_or_default(),
+        cost_usd: row.try_get("cost_usd").unwrap_or_default(),
+    };
+    Ok(CostBreakdown {
+        total_usd: total.try_get("total").unwrap_or_default(),
+        by_user: user_rows
+            .iter()
+            .map(|row| {
+                let user_id: Option<String> = row.try_get("user_id").ok().flatten();
+                let sender_name: Option<String> = row.try_get("sender_name").ok().flatten();
+                line(sender_name.or(user_id), row)
+            })
+            .collect(),
+        by_model: model_rows
+            .iter()
+            .map(|row| line(row.try_get("model").ok(), row))
+            .collect(),
+    })
+}
+
+/// Render `breakdown` as a header and two monospace tables, in `locale`.
+pub fn render(breakdown: &CostBreakdown, period: CostPeriod, locale: &str) -> String {
+    let period_name = messages::get(locale, period.message_key());
+    let mut sections = vec![messages::get_with(
+        locale,
+        "stats.cost_header",
+        &[
+            ("period", &period_name),
+            ("total", &format_usd(breakdown.total_usd)),
+        ],
+    )];
+    if breakdown.is_empty() {
+        sections.push(messages::get(locale, "stats.cost_none"));
+        return sections.join("\n");
+    }
+
+    let unattributed = messages::get(locale, "stats.cost_unattributed");
+    for (column, lines) in [
+        ("stats.cost_user", &breakdown.by_user),
+        ("stats.cost_model", &breakdown.by_model),
+    ] {
+        let rows: Vec<[String; 3]> = lines
+            .iter()
+            .map(|line| {
+                [
+                    truncate_label(line.label.as_deref().unwrap_or(&unattributed)),
+                    line.requests.to_string(),
+                    format_usd(line.cost_usd),
+                ]
+            })
+            .collect();
+        sections.push(render_table(
+            [
+                messages::get(locale, column),
+                messages::get(locale, "stats.cost_requests"),
+                messages::get(locale, "stats.cost_spend"),
+            ],
+            &rows,
+        ));
+    }
+    sections.join("\n")
+}
+
+/// A fenced table with the first column left-aligned and the rest
+/// right-aligned.
+fn render_table(header: [String; 3], rows: &[[String; 3]]) -> String {
+    let mut widths = [0; 3];
+    for row in std::iter::once(&header).chain(rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut lines = vec!["```".to_string()];
+    for row in std::iter::once(&header).chain(rows) {
+        lines.push(format!(
+            "{:<label$}  {:>requests$}  {:>cost$}",
+            row[0],
+            row[1],
+            row[2],
+            label = widths[0],
+            requests = widths[1],
+            cost = widths[2],
+        ));
+    }
+    lines.push("```".to_string());
+    lines.join("\n")
+}
+
+fn truncate_label(label: &str) -> String {
+    if label.chars().count() <= MAX_LABEL_CHARS {
+        return label.to_string();
+    }
+    let mut truncated: String = label.chars().take(MAX_LABEL_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+pub(crate) fn format_usd(amount: f64) -> String {
+    if amount > 0.0 && amount < 0.01 {
+        return "<$0.01".to_string();
+    }
+    format!("${amount:.2}")
+}
+
+/// The first `weekday` at `hour`:00 UTC strictly after `now`.
+pub(crate) fn next_report_at(
+    now: DateTime<Utc>,
+    weekday: chrono::Weekday,
+    hour: u32,
+) -> DateTime<Utc> {
+    let days_ahead = (7 + weekday.num_days_from_monday() as i64
+        - now.weekday().num_days_from_monday() as i64)
+        % 7;
+    let date = now.date_naive() + Duration::days(days_ahead);
+    let candidate =
+        Utc.from_utc_datetime(&date.and_hms_opt(hour.min(23), 0, 0).unwrap_or_default());
+    if candidate > now {
+        candidate
+    } else {
+        candidate + Duration::weeks(1)
+    }
+}
+
+/// Post every agent's weekly spend to `config.post_to`, at
+/// `config.weekday` `config.hour`:00 UTC.
+pub fn spawn_weekly_cost_report(
+    registry: Arc<tokio::sync::RwLock<HashMap<AgentId, AgentDeps>>>,
+    config: CostReportConfig,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let Some(target) = config.post_to.clone() else {
+            return;
+        };
+        loop {
+            let now = Utc::now();
+            let next = next_report_at(now, config.weekday, config.hour);
+            tracing::debug!(next = %next, "next weekly cost report scheduled");
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+            let mut agents: Vec<AgentDeps> = registry.read().await.values().cloned().collect();
+            agents.sort_by(|left, right| left.agent_id.cmp(&right.agent_id));
+            for deps in agents {
+                let Some(messaging_manager) = deps.messaging_manager.as_ref() else {
+                    continue;
+                };
+                let breakdown = match cost_breakdown(
+                    &deps.sqlite_pool,
+                    &deps.agent_id,
+                    CostPeriod::Week.hours(),
+                )
+                .await
+                {
+                    Ok(breakdown) => breakdown,
+                    Err(error) => {
+                        tracing::warn!(%error, agent_id = %deps.agent_id, "failed to read weekly cost report");
+                        continue;
+                    }
+                };
+                let text = format!(
+                    "{}\n{}",
+                    messages::get_with(
+                        &config.locale,
+                        "stats.cost_weekly",
+                        &[("agent", &deps.agent_id)]
+                    ),
+                    render(&breakdown, CostPeriod::Week, &config.locale)
+                );
+                if let Err(error) = messaging_manager
+                    .broadcast_proactive(
+                        &target.adapter,
+                        &target.target,
+                        crate::OutboundResponse::Text(text),
+                    )
+                    .await
+                {
+                    tracing::warn!(
+                        %error,
+                        agent_id = %deps.agent_id,
+                        adapter = %target.adapter,
+                        "failed to post weekly cost report"
+                    );
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Weekday;
+
+    #[test]
+    fn schedules_the_next_weekly_slot() {
+        // 2026-10-15 is a Thursday.
+        let thursday = Utc.with_ymd_and_hms(2026, 10, 15, 12, 30, 0).unwrap();
+        assert_eq!(
+            next_report_at(thursday, Weekday::Mon, 9),
+            Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            next_report_at(thursday, Weekday::Thu, 18),
+            Utc.with_ymd_and_hms(2026, 10, 15, 18, 0, 0).unwrap()
+        );
+        assert_eq!(
+            next_report_at(thursday, Weekday::Thu, 9),
+            Utc.with_ymd_and_hms(2026, 10, 22, 9, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn renders_aligned_tables() {
+        let breakdown = CostBreakdown {
+            total_usd: 4.256,
+            by_user: vec![
+                CostLine {
+                    label: Some("alice".into()),
+                    requests: 120,
+                    cost_usd: 4.25,
+                },
+                CostLine {
+                    label: None,
+                    requests: 3,
+                    cost_usd: 0.006,
+                },
+            ],
+            by_model: vec![CostLine {
+                label: Some("anthropic/claude-sonnet-4".into()),
+                requests: 123,
+                cost_usd: 4.256,
+            }],
+        };
+        let text = render(&breakdown, CostPeriod::Week, "en");
+        assert_eq!(
+            text,
+            "estimated spend, last 7 days: $4.26\n\
+             ```\n\
+             user          requests    cost\n\
+             alice              120   $4.25\n\
+             (background)         3  <$0.01\n\
+             ```\n\
+             ```\n\
+             model                      requests   cost\n\
+             anthropic/claude-sonnet-4       123  $4.26\n\
+             ```"
+        );
+    }
+
+    #[tokio::test]
+    async fn breaks_down_spend_by_user_and_model() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        sqlx::query(
+            "INSERT INTO conversation_messages (id, channel_id, role, sender_name, sender_id, content) \
+             VALUES ('m1', 'discord:1', 'user', 'Alice', '42', 'hi')",
+        )
+        .execute(&pool)
+        .await
+        .expect("insert message");
+        for (user_id, model, cost) in [
+            (Some("discord:42"), "openai/gpt-4.1", 1.5),
+            (Some("discord:42"), "anthropic/claude-sonnet-4", 0.5),
+            (Some("slack:U7"), "openai/gpt-4.1", 0.75),
+            (None, "openai/gpt-4.1", 0.25),
+        ] {
+            sqlx::query(
+                "INSERT INTO token_usage (agent_id, process_type, user_id, model, provider, \
+                 request_count, estimated_cost_usd) VALUES ('main', 'channel', ?, ?, 'test', 1, ?)",
+            )
+            .bind(user_id)
+            .bind(model)
+            .bind(cost)
+            .execute(&pool)
+            .await
+            .expect("insert usage");
+        }
+        // A DM is a private session and stays out of the shared report.
+        crate::llm::usage::UsageRecord {
+            agent_id: "main".into(),
+            process_type: "channel".into(),
+            conversation_id: Some("discord:dm:42".into()),
+            user_id: Some("discord:42".into()),
+            model: "openai/gpt-4.1".into(),
+            provider: "test".into(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            reasoning_tokens: 0,
+            request_count: 1,
+            estimated_cost_usd: Some(9.0),
+            cost_status: crate::llm::usage::CostStatus::Estimated,
+        }
+        .insert(&pool)
+        .await
+        .expect("insert private usage");
+
+        let breakdown = cost_breakdown(&pool, "main", 24).await.expect("breakdown");
+        assert!((breakdown.total_usd - 3.0).abs() < 1e-9);
+        let users: Vec<(Option<&str>, i64)> = breakdown
+            .by_user
+            .iter()
+            .map(|line| (line.label.as_deref(), line.requests))
+            .collect();
+        assert_eq!(
+            users,
+            [(Some("Alice"), 2), (Some("slack:U7"), 1), (None, 1)]
+        );
+        assert_eq!(
+            breakdown.by_model[0].label.as_deref(),
+            Some("openai/gpt-4.1")
+        );
+        assert_eq!(breakdown.by_model[0].requests, 3);
+
+        let other = cost_breakdown(&pool, "other", 24).await.expect("breakdown");
+        assert!(other.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Cron scheduler for timed tasks.
 
+pub mod phrase;
 pub mod scheduler;
 pub mod store;
 
@@ -0,0 +1,281 @@
+//! Plain-language schedules for the `/schedule` chat command.
+//!
+//! Admins write `/schedule add <id> every weekday at 9:00 run "summarize open
+//! PRs" in #dev`; this module turns the schedule phrase into the 5-field cron
+//! expression the scheduler already understands and splits out the prompt
+//! and optional delivery channel. Validation of the resulting job (id format,
+//! prompt length, adapter availability) stays with [`crate::tools::CronTool`].
+
+/// A parsed `/schedule add` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleRequest {
+    pub id: String,
+    /// 5-field cron expression (`min hour dom month dow`).
+    pub cron_expr: String,
+    pub prompt: String,
+    /// `#channel-name` or `adapter:target`. `None` delivers to the channel
+    /// the command was sent from.
+    pub target: Option<String>,
+}
+
+const DAY_NAMES: [&str; 7] = [
+    "sunday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+];
+
+/// Parse the arguments of `/schedule add`: `<id> <when> run <prompt> [in <target>]`.
+/// The prompt may be quoted, which is required when it contains ` in `.
+pub fn parse_schedule_request(input: &str) -> Result<ScheduleRequest, String> {
+    let (id, rest) = input
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or("missing schedule and prompt")?;
+    let (when, action) = rest
+        .split_once(" run ")
+        .ok_or("missing `run <prompt>` after the schedule")?;
+    let cron_expr = parse_schedule_phrase(when)?;
+    let (prompt, target) = split_prompt_and_target(action.trim())?;
+    if prompt.is_empty() {
+        return Err("the prompt is empty".into());
+    }
+    Ok(ScheduleRequest {
+        id: id.to_string(),
+        cron_expr,
+        prompt,
+        target,
+    })
+}
+
+/// Convert a schedule phrase to a 5-field cron expression.
+///
+/// Accepts `every day|weekday|weekend|<day names> at <time>`, `daily at
+/// <time>`, `every hour`, `every N minutes|hours`, and raw cron expressions.
+pub fn parse_schedule_phrase(phrase: &str) -> Result<String, String> {
+    let phrase = phrase.trim().to_lowercase();
+    let fields: Vec<&str> = phrase.split_whitespace().collect();
+    if fields.len() == 5
+        && fields[0].starts_with(|character: char| character.is_ascii_digit() || character == '*')
+    {
+        return Ok(fields.join(" "));
+    }
+
+    let body = phrase
+        .strip_prefix("every ")
+        .or_else(|| phrase.strip_prefix("each "))
+        .unwrap_or(&phrase)
+        .trim();
+    match body.split_once(" at ") {
+        Some((days, time)) => {
+            let (hour, minute) = parse_time(time.trim())?;
+            let day_of_week = parse_days(days.trim())?;
+            Ok(format!("{minute} {hour} * * {day_of_week}"))
+        }
+        None => parse_repeat(body),
+    }
+}
+
+/// `hour`, `minute`, `N minutes`, `N hours`.
+fn parse_repeat(body: &str) -> Result<String, String> {
+    let unrecognized = || format!("couldn't understand the schedule '{body}'");
+    match body.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["hour"] | ["hourly"] => Ok("0 * * * *".into()),
+        ["minute"] => Ok("* * * * *".into()),
+        [count, unit] => {
+            let count: u32 = count.parse().map_err(|_| unrecognized())?;
+            match *unit {
+                "minute" | "minutes" | "min" | "mins" if (1..60).contains(&count) => {
+                    Ok(format!("*/{count} * * * *"))
+                }
+                "hour" | "hours" if (1..24).contains(&count) => Ok(format!("0 */{count} * * *")),
+                "minute" | "minutes" | "min" | "mins" => {
+                    Err("minute intervals must be between 1 and 59".into())
+                }
+                "hour" | "hours" => Err("hour intervals must be between 1 and 23".into()),
+                _ => Err(unrecognized()),
+            }
+        }
+        _ => Err(unrecognized()),
+    }
+}
+
+/// Day-of-week field for `day`, `weekday`, `weekend`, or a list of day names.
+fn parse_days(days: &str) -> Result<String, String> {
+    match days {
+        "" | "day" | "daily" | "days" => return Ok("*".into()),
+        "weekday" | "weekdays" => return Ok("1-5".into()),
+        "weekend" | "weekends" => return Ok("0,6".into()),
+        _ => {}
+    }
+
+    let mut numbers = Vec::new();
+    for word in days
+        .split(|character: char| character == ',' || character.is_whitespace())
+        .filter(|word| !word.is_empty() && *word != "and")
+    {
+        let word = word.strip_suffix('s').unwrap_or(word);
+        let number = DAY_NAMES
+            .iter()
+            .position(|name| word.len() >= 3 && name.starts_with(word))
+            .ok_or_else(|| format!("unknown day '{word}'"))?;
+        if !numbers.contains(&number) {
+            numbers.push(number);
+        }
+    }
+    if numbers.is_empty() {
+        return Err(format!("couldn't understand the days '{days}'"));
+    }
+    numbers.sort_unstable();
+    Ok(numbers
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// `9`, `9:30`, `17:00`, `9am`, `5:30 pm`, `noon`, `midnight` → (hour, minute).
+fn parse_time(time: &str) -> Result<(u32, u32), String> {
+    match time {
+        "noon" => return Ok((12, 0)),
+        "midnight" => return Ok((0, 0)),
+        _ => {}
+    }
+    let invalid = || format!("couldn't understand the time '{time}'");
+    let compact: String = time.split_whitespace().collect();
+    let (clock, meridiem) = if let Some(clock) = compact.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = compact.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (compact.as_str(), None)
+    };
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+    if minute > 59 {
+        return Err(invalid());
+    }
+    let hour = match meridiem {
+        None if hour < 24 => hour,
+        Some(is_pm) if (1..=12).contains(&hour) => (hour % 12) + if is_pm { 12 } else { 0 },
+        _ => return Err(invalid()),
+    };
+    Ok((hour, minute))
+}
+
+/// Split `"<prompt>" in <target>` or `<prompt> in <target>`.
+fn split_prompt_and_target(action: &str) -> Result<(String, Option<String>), String> {
+    let quote = action
+        .chars()
+        .next()
+        .and_then(|opening| closing_quote(opening).map(|closing| (opening, closing)));
+    if let Some((opening, closing)) = quote {
+        let inner = &action[opening.len_utf8()..];
+        let end = inner
+            .find(closing)
+            .ok_or("the prompt's closing quote is missing")?;
+        let prompt = inner[..end].trim().to_string();
+        let remainder = inner[end + closing.len_utf8()..].trim();
+        if remainder.is_empty() {
+            return Ok((prompt, None));
+        }
+        let target = remainder
+            .strip_prefix("in ")
+            .map(str::trim)
+            .filter(|target| !target.is_empty() && !target.contains(char::is_whitespace))
+            .ok_or_else(|| {
+                format!("expected `in <channel>` after the prompt, got '{remainder}'")
+            })?;
+        return Ok((prompt, Some(target.to_string())));
+    }
+
+    if let Some((prompt, target)) = action.rsplit_once(" in ")
+        && is_target(target.trim())
+    {
+        return Ok((prompt.trim().to_string(), Some(target.trim().to_string())));
+    }
+    Ok((action.to_string(), None))
+}
+
+fn closing_quote(opening: char) -> Option<char> {
+    match opening {
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '“' => Some('”'),
+        '‘' => Some('’'),
+        _ => None,
+    }
+}
+
+/// `#channel-name` or `adapter:target`, as a single word.
+fn is_target(word: &str) -> bool {
+    !word.is_empty()
+        && !word.contains(char::is_whitespace)
+        && (word.starts_with('#') || word.contains(':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_phrases_to_cron_expressions() {
+        let cases = [
+            ("every weekday at 9:00", "0 9 * * 1-5"),
+            ("every day at 5:30pm", "30 17 * * *"),
+            ("daily at 12am", "0 0 * * *"),
+            ("every monday and friday at 08:15", "15 8 * * 1,5"),
+            ("every tue, thu at noon", "0 12 * * 2,4"),
+            ("every weekend at 10 am", "0 10 * * 0,6"),
+            ("every hour", "0 * * * *"),
+            ("every 15 minutes", "*/15 * * * *"),
+            ("every 6 hours", "0 */6 * * *"),
+            ("0 9 * * 1-5", "0 9 * * 1-5"),
+        ];
+        for (phrase, expected) in cases {
+            assert_eq!(
+                parse_schedule_phrase(phrase).as_deref(),
+                Ok(expected),
+                "{phrase}"
+            );
+        }
+        assert!(parse_schedule_phrase("every blursday at 9").is_err());
+        assert!(parse_schedule_phrase("every day at 25:00").is_err());
+        assert!(parse_schedule_phrase("every 90 minutes").is_err());
+    }
+
+    #[test]
+    fn splits_prompt_and_target() {
+        let request = parse_schedule_request(
+            "open-prs every weekday at 9:00 run 'summarize open PRs' in #dev",
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            ScheduleRequest {
+                id: "open-prs".into(),
+                cron_expr: "0 9 * * 1-5".into(),
+                prompt: "summarize open PRs".into(),
+                target: Some("#dev".into()),
+            }
+        );
+
+        let request =
+            parse_schedule_request("standup daily at 9:30 run post the standup reminder").unwrap();
+        assert_eq!(request.prompt, "post the standup reminder");
+        assert_eq!(request.target, None);
+
+        let request = parse_schedule_request(
+            "triage every 2 hours run check issues in the tracker in discord:123",
+        )
+        .unwrap();
+        assert_eq!(request.prompt, "check issues in the tracker");
+        assert_eq!(request.target.as_deref(), Some("discord:123"));
+
+        assert!(parse_schedule_request("missing every day at 9").is_err());
+    }
+}
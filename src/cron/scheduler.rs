@@ -37,6 +37,9 @@ pub struct CronJob {
     /// Maximum wall-clock seconds to wait for the job to complete.
     /// `None` uses the default of 120 seconds.
     pub timeout_secs: Option<u64>,
+    /// Whether the job has a `cron_jobs` row. One-off runs (webhook
+    /// triggers) have none, so their executions aren't logged.
+    pub persisted: bool,
 }
 
 /// Reply buffer for cron job outcome delivery.
@@ -762,6 +765,48 @@ impl Scheduler {
         }
     }
 
+    /// Run a prompt once in a fresh cron channel and deliver the reply to
+    /// `delivery_target`, without registering or persisting a job. Returns
+    /// immediately; the run continues in the background.
+    pub fn dispatch_once(
+        &self,
+        run_id: String,
+        prompt: String,
+        delivery_target: BroadcastTarget,
+        timeout_secs: Option<u64>,
+    ) {
+        let job = CronJob {
+            id: run_id,
+            prompt,
+            cron_expr: None,
+            interval_secs: 0,
+            delivery_target,
+            active_hours: None,
+            enabled: true,
+            run_once: true,
+            consecutive_failures: 0,
+            next_run_at: None,
+            timeout_secs,
+            persisted: false,
+        };
+        let context = self.context.clone();
+        tokio::spawn(async move {
+            tracing::info!(
+                run_id = %job.id,
+                target = %job.delivery_target,
+                "one-off cron run started"
+            );
+            if let Err(error) = run_cron_job(&job, &context).await {
+                tracing::warn!(
+                    run_id = %job.id,
+                    error = %error.as_error(),
+                    failure_class = error.failure_class(),
+                    "one-off cron run failed"
+                );
+            }
+        });
+    }
+
     /// Update a job's enabled state and manage its timer accordingly.
     ///
     /// Handles three cases:
@@ -872,6 +917,7 @@ fn cron_job_from_config(config: &CronConfig) -> Result<CronJob> {
         consecutive_failures: 0,
         next_run_at: config.next_run_at.as_deref().and_then(parse_cron_timestamp),
         timeout_secs: config.timeout_secs,
+        persisted: true,
     })
 }
 
@@ -1031,7 +1077,7 @@ fn interval_initial_delay(interval_secs: u64) -> Duration {
     }
 }
 
-fn parse_cron_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+pub(crate) fn parse_cron_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
     chrono::DateTime::parse_from_rfc3339(value)
         .ok()
         .map(|timestamp| timestamp.to_utc())
@@ -1374,7 +1420,7 @@ async fn run_cron_job(
         let error_message = format!("failed to send cron prompt to channel: {error}");
         persist_cron_execution(
             context,
-            &job.id,
+            job,
             CronExecutionRecord {
                 execution_succeeded: false,
                 delivery_attempted: false,
@@ -1411,7 +1457,7 @@ async fn run_cron_job(
             let error_message = format!("cron channel failed: {error}");
             persist_cron_execution(
                 context,
-                &job.id,
+                job,
                 CronExecutionRecord {
                     execution_succeeded: false,
                     delivery_attempted: false,
@@ -1429,7 +1475,7 @@ async fn run_cron_job(
             let error_message = format!("cron channel join failed: {join_error}");
             persist_cron_execution(
                 context,
-                &job.id,
+                job,
                 CronExecutionRecord {
                     execution_succeeded: false,
                     delivery_attempted: false,
@@ -1524,7 +1570,7 @@ async fn run_cron_job(
             );
             persist_cron_execution(
                 context,
-                &job.id,
+                job,
                 CronExecutionRecord {
                     execution_succeeded: true,
                     delivery_attempted: true,
@@ -1544,7 +1590,7 @@ async fn run_cron_job(
         );
         persist_cron_execution(
             context,
-            &job.id,
+            job,
             CronExecutionRecord {
                 execution_succeeded: true,
                 delivery_attempted: true,
@@ -1558,7 +1604,7 @@ async fn run_cron_job(
         tracing::debug!(cron_id = %job.id, "cron job produced no output, skipping delivery");
         persist_cron_execution(
             context,
-            &job.id,
+            job,
             CronExecutionRecord {
                 execution_succeeded: true,
                 delivery_attempted: false,
@@ -1573,12 +1619,16 @@ async fn run_cron_job(
     Ok(())
 }
 
-fn persist_cron_execution(context: &CronContext, cron_id: &str, record: CronExecutionRecord) {
+fn persist_cron_execution(context: &CronContext, job: &CronJob, record: CronExecutionRecord) {
+    if !job.persisted {
+        return;
+    }
+
     #[cfg(feature = "metrics")]
-    record_cron_metrics(&context.deps.agent_id, cron_id, &record);
+    record_cron_metrics(&context.deps.agent_id, &job.id, &record);
 
     let store = context.store.clone();
-    let cron_id = cron_id.to_string();
+    let cron_id = job.id.clone();
     tokio::spawn(async move {
         if let Err(error) = store.log_execution(&cron_id, &record).await {
             tracing::warn!(cron_id = %cron_id, %error, "failed to log cron execution");
@@ -1959,6 +2009,7 @@ mod tests {
             consecutive_failures,
             next_run_at,
             timeout_secs: None,
+            persisted: true,
         }
     }
 
@@ -0,0 +1,290 @@
+//! Operator commands against a running instance: `spacebot sessions`,
+//! `spacebot config check`, and `spacebot export`.
+//!
+//! Each command is a thin client over the daemon's HTTP API, so an operator
+//! can inspect and administer the bot without chat access. A session here is
+//! a channel: one conversation with an agent on one platform, identified by
+//! its channel ID.
+
+use crate::conversation::history::TimelineItem;
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+use std::fmt::Write as _;
+
+/// A channel as listed by `spacebot sessions list`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionSummary {
+    pub agent_id: String,
+    pub id: String,
+    pub platform: String,
+    pub display_name: Option<String>,
+    pub is_active: bool,
+    pub last_activity_at: String,
+}
+
+#[derive(Deserialize)]
+struct SessionList {
+    channels: Vec<SessionSummary>,
+}
+
+/// The daemon's answer to a config check or a kill.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Outcome {
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CtlClient {
+    http: reqwest::Client,
+    /// API root including the `/api` prefix, without a trailing slash.
+    api_base: String,
+    auth_token: Option<String>,
+}
+
+impl CtlClient {
+    pub fn new(api_base: impl Into<String>, auth_token: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_base: api_base.into().trim_end_matches('/').to_string(),
+            auth_token,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .http
+            .request(method, format!("{}/{path}", self.api_base));
+        match &self.auth_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(request: reqwest::RequestBuilder) -> anyhow::Result<reqwest::Response> {
+        let response = request
+            .send()
+            .await
+            .context("failed to connect to spacebot API — is the daemon running?")?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            anyhow::bail!("not found");
+        }
+        if !status.is_success() {
+            anyhow::bail!("API returned {status}");
+        }
+        Ok(response)
+    }
+
+    /// Channels, most recently active first. Only active ones unless
+    /// `include_inactive` is set.
+    pub async fn sessions(
+        &self,
+        agent_id: Option<&str>,
+        include_inactive: bool,
+    ) -> anyhow::Result<Vec<SessionSummary>> {
+        let mut request = self
+            .request(reqwest::Method::GET, "channels")
+            .query(&[("include_inactive", include_inactive)]);
+        if let Some(agent_id) = agent_id {
+            request = request.query(&[("agent_id", agent_id)]);
+        }
+        let list: SessionList = Self::send(request)
+            .await?
+            .json()
+            .await
+            .context("failed to decode API response")?;
+        Ok(list.channels)
+    }
+
+    /// Cancel every worker and branch running in a channel.
+    pub async fn kill(&self, channel_id: &str) -> anyhow::Result<Outcome> {
+        let request = self
+            .request(reqwest::Method::POST, "channels/kill")
+            .json(&serde_json::json!({ "channel_id": channel_id }));
+        Self::send(request)
+            .await
+            .with_context(|| format!("session {channel_id} is not running"))?
+            .json()
+            .await
+            .context("failed to decode API response")
+    }
+
+    /// Validate `content`, or the daemon's own config file when `None`.
+    pub async fn check_config(&self, content: Option<String>) -> anyhow::Result<Outcome> {
+        let request = self
+            .request(reqwest::Method::POST, "settings/raw/validate")
+            .json(&serde_json::json!({ "content": content }));
+        Self::send(request)
+            .await?
+            .json()
+            .await
+            .context("failed to decode API response")
+    }
+
+    /// A channel's timeline as JSON Lines, oldest first.
+    pub async fn export(&self, channel_id: &str) -> anyhow::Result<String> {
+        let request = self
+            .request(reqwest::Method::GET, "channels/export")
+            .query(&[("channel_id", channel_id)]);
+        Self::send(request)
+            .await
+            .with_context(|| format!("no session {channel_id}"))?
+            .text()
+            .await
+            .context("failed to read export")
+    }
+}
+
+/// Render sessions as an aligned table.
+pub fn format_sessions(sessions: &[SessionSummary]) -> String {
+    let rows: Vec<[&str; 5]> = sessions
+        .iter()
+        .map(|session| {
+            [
+                session.id.as_str(),
+                session.agent_id.as_str(),
+                session.platform.as_str(),
+                if session.is_active {
+                    "active"
+                } else {
+                    "inactive"
+                },
+                session.last_activity_at.as_str(),
+            ]
+        })
+        .collect();
+    let header = ["SESSION", "AGENT", "PLATFORM", "STATE", "LAST ACTIVITY"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let names = sessions
+        .iter()
+        .map(|session| session.display_name.as_deref().unwrap_or_default());
+    let mut output = String::new();
+    for (row, name) in std::iter::once((header, "NAME")).chain(rows.into_iter().zip(names)) {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(widths) {
+            let _ = write!(line, "{cell:<width$}  ");
+        }
+        line.push_str(name);
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// Render a JSON Lines export as a Markdown transcript.
+pub fn render_transcript(jsonl: &str) -> anyhow::Result<String> {
+    let mut output = String::new();
+    for (number, line) in jsonl.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let item: TimelineItem = serde_json::from_str(line)
+            .with_context(|| format!("invalid export line {}", number + 1))?;
+        match item {
+            TimelineItem::Message {
+                role,
+                sender_name,
+                content,
+                created_at,
+                ..
+            } => {
+                let author = sender_name.unwrap_or(role);
+                let _ = writeln!(output, "**{author}** ({created_at}):\n{content}\n");
+            }
+            TimelineItem::WorkerRun {
+                task,
+                status,
+                result,
+                started_at,
+                ..
+            } => {
+                let _ = writeln!(output, "> worker ({status}, {started_at}): {task}");
+                if let Some(result) = result.filter(|result| !result.is_empty()) {
+                    for line in result.lines() {
+                        let _ = writeln!(output, "> {line}");
+                    }
+                }
+                output.push('\n');
+            }
+            TimelineItem::BranchRun {
+                description,
+                conclusion,
+                started_at,
+                ..
+            } => {
+                let _ = writeln!(output, "> branch ({started_at}): {description}");
+                if let Some(conclusion) = conclusion.filter(|conclusion| !conclusion.is_empty()) {
+                    let _ = writeln!(output, "> {conclusion}");
+                }
+                output.push('\n');
+            }
+            TimelineItem::ToolCallRun {
+                tool_name, status, ..
+            } => {
+                let _ = writeln!(output, "> tool `{tool_name}` ({status})\n");
+            }
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_render_as_a_table() {
+        let sessions = vec![
+            SessionSummary {
+                agent_id: "main".into(),
+                id: "discord:1:2".into(),
+                platform: "discord".into(),
+                display_name: Some("#general".into()),
+                is_active: true,
+                last_activity_at: "2026-10-15T09:00:00+00:00".into(),
+            },
+            SessionSummary {
+                agent_id: "ops".into(),
+                id: "portal:chat:ops:cli".into(),
+                platform: "portal".into(),
+                display_name: None,
+                is_active: false,
+                last_activity_at: "2026-10-14T18:30:00+00:00".into(),
+            },
+        ];
+        assert_eq!(
+            format_sessions(&sessions),
+            "SESSION              AGENT  PLATFORM  STATE     LAST ACTIVITY              NAME\n\
+             discord:1:2          main   discord   active    2026-10-15T09:00:00+00:00  #general\n\
+             portal:chat:ops:cli  ops    portal    inactive  2026-10-14T18:30:00+00:00\n"
+        );
+    }
+
+    #[test]
+    fn exports_render_as_markdown() {
+        let jsonl = concat!(
+            r#"{"type":"message","id":"1","role":"user","sender_name":"alice","sender_id":"7","content":"fix the build","created_at":"t1"}"#,
+            "\n",
+            r#"{"type":"worker_run","id":"w","task":"fix the build","result":"done","status":"done","started_at":"t2","completed_at":"t3"}"#,
+            "\n",
+            r#"{"type":"message","id":"2","role":"assistant","sender_name":null,"sender_id":null,"content":"fixed","created_at":"t4"}"#,
+            "\n",
+        );
+        assert_eq!(
+            render_transcript(jsonl).unwrap(),
+            "**alice** (t1):\nfix the build\n\n\
+             > worker (done, t2): fix the build\n> done\n\n\
+             **assistant** (t4):\nfixed\n\n"
+        );
+        assert!(render_transcript("{not json}").is_err());
+    }
+}
@@ -1,13 +1,142 @@
 //! Database connection management and migrations.
 
+pub mod blob;
+
 use crate::error::{DbError, Result};
 
 use anyhow::Context as _;
-use sqlx::SqlitePool;
+use sqlx::migrate::Migrator;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+use sqlx::{PgPool, Row as _, SqlitePool};
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 
+/// Migrations for each agent's `agent.db`.
+pub static AGENT_MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Migrations for the instance-level `spacebot.db`.
+pub static INSTANCE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/global");
+
+/// Migrations for the shared Postgres database (`[database] backend =
+/// "postgres"`), covering the stores that can live there.
+pub static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Whether opening a database applies its pending migrations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Migrations {
+    #[default]
+    Run,
+    /// Leave the schema as it is (`--no-migrate`). Pending migrations are
+    /// logged, and the daemon runs against the old schema.
+    Skip,
+}
+
+/// How a database's applied migrations compare with the ones this build
+/// embeds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationState {
+    /// Embedded migrations not applied yet, oldest first.
+    pub pending: Vec<i64>,
+    /// Applied migrations this build doesn't know, from a newer build.
+    pub unknown: Vec<i64>,
+    /// A migration that started but did not finish.
+    pub failed: Option<i64>,
+}
+
+impl MigrationState {
+    pub(crate) fn compare(migrator: &Migrator, applied: &[(i64, bool)]) -> Self {
+        let known: Vec<i64> = migrator
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| migration.version)
+            .collect();
+        let applied_versions: HashSet<i64> = applied.iter().map(|(version, _)| *version).collect();
+        let known_versions: HashSet<i64> = known.iter().copied().collect();
+        Self {
+            pending: known
+                .into_iter()
+                .filter(|version| !applied_versions.contains(version))
+                .collect(),
+            unknown: applied
+                .iter()
+                .map(|(version, _)| *version)
+                .filter(|version| !known_versions.contains(version))
+                .collect(),
+            failed: applied
+                .iter()
+                .find(|(_, success)| !success)
+                .map(|(version, _)| *version),
+        }
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.pending.is_empty() && self.unknown.is_empty() && self.failed.is_none()
+    }
+}
+
+/// Read which of `migrator`'s migrations `pool` has applied, without
+/// applying any.
+pub async fn migration_state(pool: &SqlitePool, migrator: &Migrator) -> Result<MigrationState> {
+    let has_history = sqlx::query(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_optional(pool)
+    .await
+    .context("failed to read database schema")?
+    .is_some();
+    let applied = if has_history {
+        sqlx::query("SELECT version, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .context("failed to read migration history")?
+            .into_iter()
+            .map(|row| {
+                (
+                    row.try_get::<i64, _>("version").unwrap_or_default(),
+                    row.try_get::<bool, _>("success").unwrap_or(false),
+                )
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(MigrationState::compare(migrator, &applied))
+}
+
+/// Apply `migrator` to `pool`, or with [`Migrations::Skip`] only report
+/// what is pending.
+async fn migrate(
+    pool: &SqlitePool,
+    migrator: &Migrator,
+    migrations: Migrations,
+    database: &Path,
+) -> Result<()> {
+    match migrations {
+        Migrations::Run => {
+            migrator
+                .run(pool)
+                .await
+                .with_context(|| format!("failed to run migrations for {}", database.display()))?;
+        }
+        Migrations::Skip => {
+            let state = migration_state(pool, migrator).await?;
+            if !state.is_current() {
+                tracing::warn!(
+                    database = %database.display(),
+                    pending = ?state.pending,
+                    unknown = ?state.unknown,
+                    failed = ?state.failed,
+                    "database schema is not current; migrations skipped (--no-migrate)"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Database connections bundle for per-agent databases.
 pub struct Db {
     /// SQLite pool for relational data.
@@ -21,8 +150,9 @@ pub struct Db {
 }
 
 impl Db {
-    /// Connect to all databases and run migrations.
-    pub async fn connect(data_dir: &Path) -> Result<Self> {
+    /// Connect to all databases, running migrations unless `migrations` is
+    /// [`Migrations::Skip`].
+    pub async fn connect(data_dir: &Path, migrations: Migrations) -> Result<Self> {
         // SQLite — per-agent agent.db. If an old spacebot.db exists from
         // before the rename, move it to agent.db.
         let agent_db = data_dir.join("agent.db");
@@ -36,16 +166,19 @@ impl Db {
                 )
             })?;
         }
-        let sqlite_url = format!("sqlite:{}?mode=rwc", agent_db.display());
-        let sqlite = SqlitePool::connect(&sqlite_url)
+        // WAL keeps committed write-behind batches across a process crash and
+        // lets readers proceed while a batch commits. With WAL, NORMAL sync
+        // only risks the last commits on power loss, never corruption.
+        let options = SqliteConnectOptions::new()
+            .filename(&agent_db)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal);
+        let sqlite = SqlitePool::connect_with(options)
             .await
             .with_context(|| "failed to connect to SQLite")?;
 
-        // Run migrations
-        sqlx::migrate!("./migrations")
-            .run(&sqlite)
-            .await
-            .with_context(|| "failed to run database migrations")?;
+        migrate(&sqlite, &AGENT_MIGRATOR, migrations, &agent_db).await?;
 
         // LanceDB
         let lance_path = data_dir.join("lancedb");
@@ -80,7 +213,8 @@ impl Db {
     }
 }
 
-/// Connect to the instance-level spacebot database and run its migrations.
+/// Connect to the instance-level spacebot database, running its migrations
+/// unless `migrations` is [`Migrations::Skip`].
 ///
 /// The instance database lives at `{instance_dir}/data/spacebot.db` and holds
 /// data shared across all agents: tasks, projects, repos, worktrees. This
@@ -88,7 +222,7 @@ impl Db {
 ///
 /// If an old `tasks.db` exists from before the rename, it is moved to
 /// `spacebot.db` first.
-pub async fn connect_instance_db(data_dir: &Path) -> Result<SqlitePool> {
+pub async fn connect_instance_db(data_dir: &Path, migrations: Migrations) -> Result<SqlitePool> {
     std::fs::create_dir_all(data_dir)
         .with_context(|| format!("failed to create data directory: {}", data_dir.display()))?;
 
@@ -111,10 +245,82 @@ pub async fn connect_instance_db(data_dir: &Path) -> Result<SqlitePool> {
         )
     })?;
 
-    sqlx::migrate!("./migrations/global")
-        .run(&pool)
+    migrate(&pool, &INSTANCE_MIGRATOR, migrations, &db_path).await?;
+
+    Ok(pool)
+}
+
+/// Connect to the shared Postgres database, running its migrations unless
+/// `migrations` is [`Migrations::Skip`]. It holds the notification inbox and
+/// session leases; nothing else is stored there.
+pub async fn connect_postgres(
+    config: &crate::config::PostgresConfig,
+    migrations: Migrations,
+) -> Result<PgPool> {
+    let pool = PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .connect(&config.url)
         .await
-        .with_context(|| "failed to run instance database migrations")?;
+        .context("failed to connect to Postgres")?;
+
+    match migrations {
+        Migrations::Run => {
+            // Replicas starting together serialize on sqlx's advisory lock.
+            POSTGRES_MIGRATOR
+                .run(&pool)
+                .await
+                .context("failed to run Postgres migrations")?;
+        }
+        Migrations::Skip => {
+            tracing::warn!("Postgres migrations skipped (--no-migrate)");
+        }
+    }
 
     Ok(pool)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn migration_state_tracks_applied_versions() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+
+        let fresh = migration_state(&pool, &INSTANCE_MIGRATOR).await.unwrap();
+        assert_eq!(fresh.pending.len(), INSTANCE_MIGRATOR.iter().count());
+        assert!(!fresh.is_current());
+
+        migrate(
+            &pool,
+            &INSTANCE_MIGRATOR,
+            Migrations::Skip,
+            Path::new(":memory:"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            migration_state(&pool, &INSTANCE_MIGRATOR).await.unwrap(),
+            fresh
+        );
+
+        migrate(
+            &pool,
+            &INSTANCE_MIGRATOR,
+            Migrations::Run,
+            Path::new(":memory:"),
+        )
+        .await
+        .unwrap();
+        assert!(
+            migration_state(&pool, &INSTANCE_MIGRATOR)
+                .await
+                .unwrap()
+                .is_current()
+        );
+    }
+}
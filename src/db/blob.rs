@@ -0,0 +1,431 @@
+//! Storage for large artifacts that don't belong in SQLite rows: exported
+//! transcripts, full tool outputs, and uploaded attachments.
+//!
+//! Blobs are content-addressed. [`BlobStore::put`] returns a [`BlobId`] of
+//! the form `{kind}/{sha256}`, and rows in the relational store keep that ID
+//! rather than the bytes. The backend is a directory on disk by default, or
+//! an S3-compatible bucket (AWS, R2, MinIO) signed with SigV4.
+
+use crate::config::{BlobStoreConfig, S3BlobConfig};
+
+use anyhow::Context as _;
+use arc_swap::ArcSwapOption;
+use sha2::{Digest as _, Sha256};
+
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+
+static STORE: LazyLock<ArcSwapOption<BlobStore>> = LazyLock::new(ArcSwapOption::empty);
+
+/// What a blob holds. Each kind gets its own key prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobKind {
+    Transcript,
+    ToolOutput,
+    Attachment,
+}
+
+impl BlobKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transcript => "transcripts",
+            Self::ToolOutput => "tool-outputs",
+            Self::Attachment => "attachments",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Transcript, Self::ToolOutput, Self::Attachment]
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+    }
+}
+
+/// Reference to a stored blob, as kept in the relational store.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlobId(String);
+
+impl BlobId {
+    fn new(kind: BlobKind, bytes: &[u8]) -> Self {
+        Self(format!(
+            "{}/{}",
+            kind.as_str(),
+            hex::encode(Sha256::digest(bytes))
+        ))
+    }
+
+    /// Parse an ID read back from the database.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (kind, digest) = value.split_once('/')?;
+        BlobKind::parse(kind)?;
+        let valid = digest.len() == 64 && digest.bytes().all(|byte| byte.is_ascii_hexdigit());
+        valid.then(|| Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for BlobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A configured blob backend.
+pub enum BlobStore {
+    Filesystem(FilesystemBlobs),
+    S3(S3Blobs),
+}
+
+impl BlobStore {
+    pub fn new(config: &BlobStoreConfig) -> Self {
+        match config {
+            BlobStoreConfig::Filesystem { root } => {
+                Self::Filesystem(FilesystemBlobs { root: root.clone() })
+            }
+            BlobStoreConfig::S3(config) => Self::S3(S3Blobs::new(config.clone())),
+        }
+    }
+
+    /// The process-wide store, once [`BlobStore::configure`] has run.
+    pub fn global() -> Option<Arc<Self>> {
+        STORE.load_full()
+    }
+
+    /// Replace the process-wide store.
+    pub fn configure(config: &BlobStoreConfig) {
+        STORE.store(Some(Arc::new(Self::new(config))));
+    }
+
+    /// Short backend name for logs.
+    pub fn backend(&self) -> &'static str {
+        match self {
+            Self::Filesystem(_) => "filesystem",
+            Self::S3(_) => "s3",
+        }
+    }
+
+    /// Store `bytes` and return their ID. Storing the same bytes twice
+    /// returns the same ID.
+    pub async fn put(&self, kind: BlobKind, bytes: Vec<u8>) -> anyhow::Result<BlobId> {
+        let id = BlobId::new(kind, &bytes);
+        match self {
+            Self::Filesystem(store) => store.put(&id, &bytes).await?,
+            Self::S3(store) => store.put(&id, bytes).await?,
+        }
+        Ok(id)
+    }
+
+    pub async fn get(&self, id: &BlobId) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Filesystem(store) => store.get(id).await,
+            Self::S3(store) => store.get(id).await,
+        }
+    }
+
+    /// Remove a blob. Removing a missing blob is not an error.
+    pub async fn delete(&self, id: &BlobId) -> anyhow::Result<()> {
+        match self {
+            Self::Filesystem(store) => store.delete(id).await,
+            Self::S3(store) => store.delete(id).await,
+        }
+    }
+}
+
+/// Blobs as files under a root directory, one subdirectory per kind.
+pub struct FilesystemBlobs {
+    root: PathBuf,
+}
+
+impl FilesystemBlobs {
+    fn path(&self, id: &BlobId) -> PathBuf {
+        self.root.join(id.as_str())
+    }
+
+    async fn put(&self, id: &BlobId, bytes: &[u8]) -> anyhow::Result<()> {
+        let path = self.path(id);
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        // Write then rename so a reader never sees a partial blob.
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes)
+            .await
+            .with_context(|| format!("failed to write {}", partial.display()))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .with_context(|| format!("failed to move blob into {}", path.display()))
+    }
+
+    async fn get(&self, id: &BlobId) -> anyhow::Result<Vec<u8>> {
+        let path = self.path(id);
+        tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("failed to read blob {}", path.display()))
+    }
+
+    async fn delete(&self, id: &BlobId) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path(id)).await {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                Err(error).with_context(|| format!("failed to delete blob {id}"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Blobs as objects in an S3-compatible bucket.
+pub struct S3Blobs {
+    client: reqwest::Client,
+    config: S3BlobConfig,
+}
+
+impl S3Blobs {
+    fn new(config: S3BlobConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .unwrap_or_default();
+        Self { client, config }
+    }
+
+    fn object_url(&self, id: &BlobId) -> anyhow::Result<url::Url> {
+        let key = format!("{}{}", self.config.prefix, id);
+        let mut url = url::Url::parse(&self.config.endpoint)
+            .with_context(|| format!("invalid S3 endpoint '{}'", self.config.endpoint))?;
+        let path = if self.config.path_style {
+            format!("/{}/{}", uri_encode(&self.config.bucket), encode_key(&key))
+        } else {
+            let host = url.host_str().context("S3 endpoint has no host")?;
+            let host = format!("{}.{host}", self.config.bucket);
+            url.set_host(Some(&host))
+                .with_context(|| format!("invalid S3 bucket host '{host}'"))?;
+            format!("/{}", encode_key(&key))
+        };
+        url.set_path(&path);
+        Ok(url)
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        id: &BlobId,
+        body: Vec<u8>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let url = self.object_url(id)?;
+        let now = chrono::Utc::now();
+        let headers = sign_request(&self.config, method.as_str(), &url, &body, now)?;
+        let mut request = self.client.request(method, url);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("S3 request for blob {id} failed"))
+    }
+
+    async fn put(&self, id: &BlobId, bytes: Vec<u8>) -> anyhow::Result<()> {
+        self.send(reqwest::Method::PUT, id, bytes)
+            .await?
+            .error_for_status()
+            .with_context(|| format!("S3 rejected blob {id}"))?;
+        Ok(())
+    }
+
+    async fn get(&self, id: &BlobId) -> anyhow::Result<Vec<u8>> {
+        let response = self
+            .send(reqwest::Method::GET, id, Vec::new())
+            .await?
+            .error_for_status()
+            .with_context(|| format!("S3 could not return blob {id}"))?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, id: &BlobId) -> anyhow::Result<()> {
+        let response = self.send(reqwest::Method::DELETE, id, Vec::new()).await?;
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            response
+                .error_for_status()
+                .with_context(|| format!("S3 could not delete blob {id}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// AWS Signature Version 4 headers for a request without a query string.
+fn sign_request(
+    config: &S3BlobConfig,
+    method: &str,
+    url: &url::Url,
+    body: &[u8],
+    now: chrono::DateTime<chrono::Utc>,
+) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        (None, _) => anyhow::bail!("S3 URL has no host"),
+    };
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(body));
+
+    let canonical_request = format!(
+        "{method}\n{}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\nhost;x-amz-content-sha256;x-amz-date\n{payload_hash}",
+        url.path()
+    );
+    let scope = format!("{date}/{}/s3/aws4_request", config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac_sha256(
+        format!("AWS4{}", config.secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    for part in [config.region.as_str(), "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    Ok(vec![
+        ("x-amz-date", amz_date),
+        ("x-amz-content-sha256", payload_hash),
+        (
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={signature}",
+                config.access_key_id
+            ),
+        ),
+    ])
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn encode_key(key: &str) -> String {
+    key.split('/').map(uri_encode).collect::<Vec<_>>().join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s3_config(path_style: bool) -> S3BlobConfig {
+        S3BlobConfig {
+            endpoint: "https://s3.eu-west-1.amazonaws.com".to_string(),
+            region: "eu-west-1".to_string(),
+            bucket: "spacebot-archive".to_string(),
+            prefix: "prod/".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            path_style,
+        }
+    }
+
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn ids_are_content_addressed_and_validated() {
+        let id = BlobId::new(BlobKind::Transcript, b"hello");
+        assert_eq!(
+            id.as_str(),
+            "transcripts/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(BlobId::parse(id.as_str()), Some(id));
+        assert_eq!(BlobId::parse("transcripts/../../etc/passwd"), None);
+        assert_eq!(BlobId::parse("secrets/2cf24dba"), None);
+    }
+
+    #[test]
+    fn object_urls_follow_the_addressing_style() {
+        let id = BlobId::new(BlobKind::ToolOutput, b"output");
+        let virtual_hosted = S3Blobs::new(s3_config(false)).object_url(&id).unwrap();
+        assert_eq!(
+            virtual_hosted.as_str(),
+            format!("https://spacebot-archive.s3.eu-west-1.amazonaws.com/prod/{id}")
+        );
+        let path_style = S3Blobs::new(s3_config(true)).object_url(&id).unwrap();
+        assert_eq!(
+            path_style.as_str(),
+            format!("https://s3.eu-west-1.amazonaws.com/spacebot-archive/prod/{id}")
+        );
+
+        let now = chrono::DateTime::parse_from_rfc3339("2026-10-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let headers = sign_request(&s3_config(true), "GET", &path_style, b"", now).unwrap();
+        assert_eq!(headers[0], ("x-amz-date", "20261015T120000Z".to_string()));
+        assert!(headers[2].1.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20261015/eu-west-1/s3/aws4_request, "
+        ));
+    }
+
+    #[tokio::test]
+    async fn filesystem_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = BlobStore::new(&BlobStoreConfig::Filesystem {
+            root: directory.path().to_path_buf(),
+        });
+
+        let id = store
+            .put(BlobKind::Attachment, b"attachment".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .put(BlobKind::Attachment, b"attachment".to_vec())
+                .await
+                .unwrap(),
+            id
+        );
+        assert_eq!(store.get(&id).await.unwrap(), b"attachment");
+
+        store.delete(&id).await.unwrap();
+        store.delete(&id).await.unwrap();
+        assert!(store.get(&id).await.is_err());
+    }
+}
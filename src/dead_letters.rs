@@ -0,0 +1,196 @@
+//! Dead-letter capture for SSE events that fail to parse.
+//!
+//! Event streams from OpenCode and signal-cli are parsed one envelope at a
+//! time. An envelope that doesn't deserialize is skipped so the stream keeps
+//! flowing, but its raw JSON is kept here first: in a bounded in-memory ring
+//! served at `/api/debug/dead-letters`, and optionally appended to a JSONL
+//! file. Each capture also increments `spacebot_sse_dead_letters_total`.
+
+use serde::Serialize;
+
+use std::collections::VecDeque;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+/// Longest raw payload kept per entry. Longer payloads are truncated.
+const MAX_RAW_BYTES: usize = 64 * 1024;
+
+static DEAD_LETTERS: LazyLock<DeadLetterQueue> =
+    LazyLock::new(|| DeadLetterQueue::new(crate::config::DeadLettersConfig::default().capacity));
+
+/// One SSE event that failed to parse.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct DeadLetter {
+    /// Stream the event came from, e.g. `opencode` or `signal`.
+    pub source: &'static str,
+    /// The deserialization error.
+    pub error: String,
+    /// The event's raw JSON, truncated to 64 KiB.
+    pub raw: String,
+    /// Whether `raw` was truncated.
+    pub truncated: bool,
+    pub received_at: chrono::DateTime<chrono::Utc>,
+}
+
+struct DeadLetterState {
+    entries: VecDeque<DeadLetter>,
+    capacity: usize,
+    file: Option<PathBuf>,
+    total: u64,
+}
+
+/// Bounded ring of recent dead letters. Access via [`DeadLetterQueue::global`].
+pub struct DeadLetterQueue {
+    state: Mutex<DeadLetterState>,
+}
+
+impl DeadLetterQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(DeadLetterState {
+                entries: VecDeque::new(),
+                capacity,
+                file: None,
+                total: 0,
+            }),
+        }
+    }
+
+    /// The process-wide queue.
+    pub fn global() -> &'static Self {
+        &DEAD_LETTERS
+    }
+
+    /// Apply `[dead_letters]` config. Shrinking the capacity drops the
+    /// oldest entries.
+    pub fn configure(&self, capacity: usize, file: Option<PathBuf>) {
+        let mut state = self.lock();
+        state.capacity = capacity;
+        state.file = file;
+        while state.entries.len() > capacity {
+            state.entries.pop_front();
+        }
+    }
+
+    /// Capture an event from `source` that failed to parse with `error`.
+    pub fn record(&self, source: &'static str, error: &serde_json::Error, raw: &str) {
+        let truncated = raw.len() > MAX_RAW_BYTES;
+        let raw = if truncated {
+            let mut end = MAX_RAW_BYTES;
+            while !raw.is_char_boundary(end) {
+                end -= 1;
+            }
+            &raw[..end]
+        } else {
+            raw
+        };
+        let entry = DeadLetter {
+            source,
+            error: error.to_string(),
+            raw: raw.to_string(),
+            truncated,
+            received_at: chrono::Utc::now(),
+        };
+
+        tracing::warn!(source, %error, "dead-lettered unparseable SSE event");
+        // Without the position, so repeats of one schema mismatch dedupe.
+        let message = error.to_string();
+        let message = message.split(" at line ").next().unwrap_or_default();
+        crate::notifications::ops::OpsAlerts::global().notify(
+            crate::notifications::ops::Alert::new(
+                crate::notifications::ops::AlertKind::DeadLetter,
+                format!("{source} event stream"),
+                format!("unparseable event: {message}"),
+            ),
+        );
+
+        #[cfg(feature = "metrics")]
+        crate::telemetry::Metrics::global()
+            .sse_dead_letters_total
+            .with_label_values(&[source])
+            .inc();
+
+        let mut state = self.lock();
+        state.total += 1;
+        if let Some(path) = &state.file
+            && let Err(error) = append_to_file(path, &entry)
+        {
+            tracing::warn!(%error, path = %path.display(), "failed to write dead letter to file");
+        }
+        if state.capacity == 0 {
+            return;
+        }
+        if state.entries.len() >= state.capacity {
+            state.entries.pop_front();
+        }
+        state.entries.push_back(entry);
+    }
+
+    /// Retained entries, oldest first.
+    pub fn snapshot(&self) -> Vec<DeadLetter> {
+        self.lock().entries.iter().cloned().collect()
+    }
+
+    /// Events dead-lettered since startup, including ones no longer retained.
+    pub fn total(&self) -> u64 {
+        self.lock().total
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DeadLetterState> {
+        // A panic while holding the lock leaves the ring consistent, so
+        // recover rather than poisoning every later stream.
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn append_to_file(path: &std::path::Path, entry: &DeadLetter) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error() -> serde_json::Error {
+        serde_json::from_str::<serde_json::Value>("{").unwrap_err()
+    }
+
+    #[test]
+    fn ring_keeps_newest_entries_up_to_capacity() {
+        let queue = DeadLetterQueue::new(2);
+        for raw in ["one", "two", "three"] {
+            queue.record("test", &parse_error(), raw);
+        }
+
+        let raws: Vec<_> = queue
+            .snapshot()
+            .into_iter()
+            .map(|entry| entry.raw)
+            .collect();
+        assert_eq!(raws, ["two", "three"]);
+        assert_eq!(queue.total(), 3);
+
+        queue.configure(1, None);
+        assert_eq!(queue.snapshot().len(), 1);
+    }
+
+    #[test]
+    fn oversized_payloads_are_truncated_on_a_char_boundary() {
+        let queue = DeadLetterQueue::new(1);
+        let raw = "é".repeat(MAX_RAW_BYTES);
+        queue.record("test", &parse_error(), &raw);
+
+        let entry = &queue.snapshot()[0];
+        assert!(entry.truncated);
+        assert!(entry.raw.len() <= MAX_RAW_BYTES);
+    }
+}
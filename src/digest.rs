@@ -0,0 +1,431 @@
+//! Weekly activity digest: worker sessions run, estimated spend, most-used
+//! tools, notable errors, and files changed over the past seven days,
+//! posted to the channel configured by `[digest]`.
+//!
+//! Built from the stores that already exist: `worker_runs` for sessions and
+//! failures, `token_usage` (via [`crate::cost_report`]) for spend,
+//! `tool_executions` for tools, and `file_change` rows in the audit log for
+//! files. Failures and file paths from private (DM) sessions are counted but
+//! never named.
+
+use crate::AgentDeps;
+use crate::AgentId;
+use crate::config::DigestConfig;
+use crate::cost_report::{cost_breakdown, format_usd, next_report_at};
+use crate::prompts::messages;
+use crate::tool_stats::{ToolStats, ToolSummary};
+
+use chrono::Utc;
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Days a digest covers.
+const DIGEST_DAYS: i64 = 7;
+
+/// Tools, errors, and files listed per section.
+const MAX_ITEMS: usize = 5;
+
+/// Longest task or error text before it is cut.
+const MAX_TEXT_CHARS: usize = 80;
+
+/// A failed worker session worth naming in the digest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestError {
+    pub task: String,
+    pub error: String,
+}
+
+/// A file and how many times workers changed it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestFile {
+    pub path: String,
+    pub changes: i64,
+}
+
+/// One agent's activity over the digest window.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActivityDigest {
+    pub sessions: i64,
+    pub sessions_done: i64,
+    pub sessions_failed: i64,
+    pub cost_usd: f64,
+    pub top_tools: Vec<ToolSummary>,
+    pub errors: Vec<DigestError>,
+    /// Distinct files changed, including ones not listed in `files`.
+    pub files_changed: i64,
+    pub files: Vec<DigestFile>,
+}
+
+impl ActivityDigest {
+    pub fn is_empty(&self) -> bool {
+        self.sessions == 0
+            && self.cost_usd == 0.0
+            && self.top_tools.is_empty()
+            && self.files_changed == 0
+    }
+}
+
+/// Compile the agent's activity over the trailing `days`.
+pub async fn compile(
+    pool: &SqlitePool,
+    agent_id: &str,
+    days: i64,
+) -> crate::error::Result<ActivityDigest> {
+    let window = format!("-{days} days");
+    // `worker_runs` timestamps use SQLite's `CURRENT_TIMESTAMP` format, the
+    // audit log uses ISO 8601; each is compared in its own format.
+    let sessions = sqlx::query(
+        "SELECT COUNT(*) AS total, \
+                COALESCE(SUM(status = 'done'), 0) AS done, \
+                COALESCE(SUM(status = 'failed'), 0) AS failed \
+         FROM worker_runs \
+         WHERE agent_id = ? AND started_at >= datetime('now', ?)",
+    )
+    .bind(agent_id)
+    .bind(&window)
+    .fetch_one(pool);
+    let failures = sqlx::query(
+        "SELECT channel_id, task, result FROM worker_runs \
+         WHERE agent_id = ? AND status = 'failed' AND started_at >= datetime('now', ?) \
+         ORDER BY started_at DESC \
+         LIMIT 50",
+    )
+    .bind(agent_id)
+    .bind(&window)
+    .fetch_all(pool);
+    let files = sqlx::query(
+        "SELECT summary AS path, channel_id, COUNT(*) AS changes FROM audit_log \
+         WHERE agent_id = ? AND kind = 'file_change' \
+           AND recorded_at >= strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?) \
+         GROUP BY summary, channel_id",
+    )
+    .bind(agent_id)
+    .bind(&window)
+    .fetch_all(pool);
+    let (sessions, failures, files) =
+        tokio::try_join!(sessions, failures, files).map_err(|error| anyhow::anyhow!(error))?;
+
+    let cost = cost_breakdown(pool, agent_id, days * 24).await?;
+    let top_tools = ToolStats::new(pool.clone())
+        .summary(agent_id, None, days, MAX_ITEMS as i64)
+        .await?;
+
+    let errors = failures
+        .iter()
+        .filter(|row| {
+            let channel_id: Option<String> = row.try_get("channel_id").ok().flatten();
+            !channel_id.as_deref().is_some_and(is_private)
+        })
+        .take(MAX_ITEMS)
+        .map(|row| {
+            let task: String = row.try_get("task").unwrap_or_default();
+            let result: Option<String> = row.try_get("result").ok().flatten();
+            DigestError {
+                task: first_line(&task),
+                error: first_line(result.as_deref().unwrap_or("")),
+            }
+        })
+        .collect();
+
+    // Rows come per (path, channel) so private paths can be dropped from the
+    // listing while still counting towards the total.
+    let mut changes_by_path: HashMap<String, (i64, bool)> = HashMap::new();
+    for row in &files {
+        let path: String = row.try_get("path").unwrap_or_default();
+        let channel_id: Option<String> = row.try_get("channel_id").ok().flatten();
+        let changes: i64 = row.try_get("changes").unwrap_or_default();
+        let entry = changes_by_path.entry(path).or_default();
+        entry.0 += changes;
+        entry.1 |= channel_id.as_deref().is_some_and(is_private);
+    }
+    let files_changed = changes_by_path.len() as i64;
+    let mut listed: Vec<DigestFile> = changes_by_path
+        .into_iter()
+        .filter(|(_, (_, private))| !private)
+        .map(|(path, (changes, _))| DigestFile { path, changes })
+        .collect();
+    listed.sort_by(|left, right| {
+        right
+            .changes
+            .cmp(&left.changes)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    listed.truncate(MAX_ITEMS);
+
+    Ok(ActivityDigest {
+        sessions: sessions.try_get("total").unwrap_or_default(),
+        sessions_done: sessions.try_get("done").unwrap_or_default(),
+        sessions_failed: sessions.try_get("failed").unwrap_or_default(),
+        cost_usd: cost.total_usd,
+        top_tools,
+        errors,
+        files_changed,
+        files: listed,
+    })
+}
+
+/// Render `digest` for `agent_id` in `locale`.
+pub fn render(digest: &ActivityDigest, agent_id: &str, locale: &str) -> String {
+    let mut lines = vec![messages::get_with(
+        locale,
+        "digest.header",
+        &[("agent", agent_id)],
+    )];
+    if digest.is_empty() {
+        lines.push(messages::get(locale, "digest.quiet"));
+        return lines.join("\n");
+    }
+
+    lines.push(messages::get_with(
+        locale,
+        "digest.sessions",
+        &[
+            ("count", &digest.sessions.to_string()),
+            ("done", &digest.sessions_done.to_string()),
+            ("failed", &digest.sessions_failed.to_string()),
+        ],
+    ));
+    lines.push(messages::get_with(
+        locale,
+        "digest.cost",
+        &[("total", &format_usd(digest.cost_usd))],
+    ));
+
+    if !digest.top_tools.is_empty() {
+        lines.push(String::new());
+        lines.push(messages::get(locale, "digest.tools_header"));
+        for tool in &digest.top_tools {
+            lines.push(messages::get_with(
+                locale,
+                "digest.tool_line",
+                &[
+                    ("tool", &tool.tool_name),
+                    ("runs", &tool.runs.to_string()),
+                    ("failures", &tool.failures.to_string()),
+                ],
+            ));
+        }
+    }
+
+    if !digest.errors.is_empty() {
+        lines.push(String::new());
+        lines.push(messages::get(locale, "digest.errors_header"));
+        for error in &digest.errors {
+            lines.push(messages::get_with(
+                locale,
+                "digest.error_line",
+                &[("task", &error.task), ("error", &error.error)],
+            ));
+        }
+    }
+
+    if digest.files_changed > 0 {
+        lines.push(String::new());
+        lines.push(messages::get_with(
+            locale,
+            "digest.files_header",
+            &[("count", &digest.files_changed.to_string())],
+        ));
+        for file in &digest.files {
+            lines.push(messages::get_with(
+                locale,
+                "digest.file_line",
+                &[
+                    ("path", &format!("`{}`", file.path)),
+                    ("changes", &file.changes.to_string()),
+                ],
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Post every agent's digest to `config.post_to`, at `config.weekday`
+/// `config.hour`:00 UTC.
+pub fn spawn_weekly_digest(
+    registry: Arc<tokio::sync::RwLock<HashMap<AgentId, AgentDeps>>>,
+    config: DigestConfig,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let Some(target) = config.post_to.clone() else {
+            return;
+        };
+        loop {
+            let now = Utc::now();
+            let next = next_report_at(now, config.weekday, config.hour);
+            tracing::debug!(next = %next, "next weekly digest scheduled");
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+            let mut agents: Vec<AgentDeps> = registry.read().await.values().cloned().collect();
+            agents.sort_by(|left, right| left.agent_id.cmp(&right.agent_id));
+            for deps in agents {
+                let Some(messaging_manager) = deps.messaging_manager.as_ref() else {
+                    continue;
+                };
+                let digest = match compile(&deps.sqlite_pool, &deps.agent_id, DIGEST_DAYS).await {
+                    Ok(digest) => digest,
+                    Err(error) => {
+                        tracing::warn!(%error, agent_id = %deps.agent_id, "failed to compile weekly digest");
+                        continue;
+                    }
+                };
+                let text = render(&digest, &deps.agent_id, &config.locale);
+                if let Err(error) = messaging_manager
+                    .broadcast_proactive(
+                        &target.adapter,
+                        &target.target,
+                        crate::OutboundResponse::Text(text),
+                    )
+                    .await
+                {
+                    tracing::warn!(
+                        %error,
+                        agent_id = %deps.agent_id,
+                        adapter = %target.adapter,
+                        "failed to post weekly digest"
+                    );
+                }
+            }
+        }
+    })
+}
+
+fn is_private(channel_id: &str) -> bool {
+    channel_id.starts_with("private:")
+        || crate::conversation::private::is_dm_conversation_id(channel_id)
+}
+
+/// The first non-empty line of `text`, cut to [`MAX_TEXT_CHARS`].
+fn first_line(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    if line.chars().count() <= MAX_TEXT_CHARS {
+        return line.to_string();
+    }
+    let mut truncated: String = line.chars().take(MAX_TEXT_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_each_section() {
+        let digest = ActivityDigest {
+            sessions: 12,
+            sessions_done: 10,
+            sessions_failed: 1,
+            cost_usd: 3.5,
+            top_tools: vec![ToolSummary {
+                tool_name: "shell".into(),
+                runs: 40,
+                failures: 2,
+                average_duration_ms: 120.0,
+            }],
+            errors: vec![DigestError {
+                task: "fix the flaky test".into(),
+                error: "cargo test exited with 101".into(),
+            }],
+            files_changed: 4,
+            files: vec![DigestFile {
+                path: "src/main.rs".into(),
+                changes: 3,
+            }],
+        };
+        assert_eq!(
+            render(&digest, "main", "en"),
+            "weekly digest for main, last 7 days\n\
+             worker sessions: 12 run, 10 done, 1 failed\n\
+             estimated spend: $3.50\n\
+             \n\
+             top tools:\n\
+             - shell: 40 runs, 2 failed\n\
+             \n\
+             notable errors:\n\
+             - fix the flaky test: cargo test exited with 101\n\
+             \n\
+             files changed: 4\n\
+             - `src/main.rs` (3×)"
+        );
+        assert_eq!(
+            render(&ActivityDigest::default(), "main", "en"),
+            "weekly digest for main, last 7 days\nno activity this week."
+        );
+    }
+
+    #[tokio::test]
+    async fn compiles_from_worker_runs_and_the_audit_log() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        sqlx::query("INSERT INTO channels (id, platform) VALUES ('private:discord:42', 'discord')")
+            .execute(&pool)
+            .await
+            .expect("insert channel");
+        for (id, channel_id, status, result) in [
+            ("w1", None, "done", None),
+            ("w2", None, "failed", Some("build failed\nmore detail")),
+            ("w3", Some("private:discord:42"), "failed", Some("secret")),
+        ] {
+            sqlx::query(
+                "INSERT INTO worker_runs (id, channel_id, task, result, status, agent_id) \
+                 VALUES (?, ?, 'task', ?, ?, 'main')",
+            )
+            .bind(id)
+            .bind(channel_id)
+            .bind(result)
+            .bind(status)
+            .execute(&pool)
+            .await
+            .expect("insert worker run");
+        }
+        for (path, channel_id) in [
+            ("src/lib.rs", "discord:1"),
+            ("src/lib.rs", "discord:1"),
+            ("notes/private.md", "private:discord:42"),
+        ] {
+            sqlx::query(
+                "INSERT INTO audit_log (agent_id, kind, actor, channel_id, summary) \
+                 VALUES ('main', 'file_change', 'worker:1', ?, ?)",
+            )
+            .bind(channel_id)
+            .bind(path)
+            .execute(&pool)
+            .await
+            .expect("insert audit entry");
+        }
+
+        let digest = compile(&pool, "main", 7).await.expect("compile digest");
+        assert_eq!(digest.sessions, 3);
+        assert_eq!(digest.sessions_done, 1);
+        assert_eq!(digest.sessions_failed, 2);
+        assert_eq!(
+            digest.errors,
+            vec![DigestError {
+                task: "task".into(),
+                error: "build failed".into(),
+            }]
+        );
+        assert_eq!(digest.files_changed, 2);
+        assert_eq!(
+            digest.files,
+            vec![DigestFile {
+                path: "src/lib.rs".into(),
+                changes: 2,
+            }]
+        );
+    }
+}
@@ -0,0 +1,543 @@
+//! `spacebot doctor`: a preflight report for a deployment.
+//!
+//! Runs without the daemon. It loads the config the daemon would load, then
+//! checks what a first start would trip over: the OpenCode binary and every
+//! remote backend, each chat platform's token against that platform's API,
+//! and whether the databases have migrations the daemon has not applied yet.
+//! Nothing is written; pending migrations are reported, not run.
+
+use crate::config::{Config, DatabaseConfig, OpenCodeConfig, PostgresConfig};
+use crate::db::MigrationState;
+use crate::opencode::OpenCodeServer;
+
+use serde_json::Value;
+use sqlx::migrate::Migrator;
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+/// Timeout for each network check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            Self::Pass => "\x1b[32m",
+            Self::Warn => "\x1b[33m",
+            Self::Fail => "\x1b[31m",
+        }
+    }
+}
+
+/// One line of the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|check| check.status == Status::Fail)
+    }
+}
+
+/// Run every check against a loaded config, or report why it didn't load.
+pub async fn run(config: anyhow::Result<Config>) -> Report {
+    let mut report = Report::default();
+    let config = match config {
+        Ok(config) => config,
+        Err(error) => {
+            report
+                .checks
+                .push(Check::new("config", Status::Fail, format!("{error:#}")));
+            return report;
+        }
+    };
+    report.checks.push(Check::new(
+        "config",
+        Status::Pass,
+        format!(
+            "loaded from {} ({} agent(s))",
+            config.instance_dir.display(),
+            config.agents.len()
+        ),
+    ));
+
+    let http = match reqwest::Client::builder().timeout(CHECK_TIMEOUT).build() {
+        Ok(http) => http,
+        Err(error) => {
+            report.checks.push(Check::new(
+                "http",
+                Status::Fail,
+                format!("failed to build HTTP client: {error}"),
+            ));
+            return report;
+        }
+    };
+
+    report
+        .checks
+        .extend(check_opencode(&config.defaults.opencode).await);
+    report.checks.extend(check_messaging(&config, &http).await);
+    report.checks.extend(check_migrations(&config).await);
+    report
+}
+
+async fn check_opencode(opencode: &OpenCodeConfig) -> Vec<Check> {
+    if !opencode.enabled {
+        return vec![Check::new("opencode", Status::Pass, "disabled")];
+    }
+
+    let mut checks = Vec::new();
+    let version = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        tokio::process::Command::new(&opencode.path)
+            .arg("--version")
+            .output(),
+    )
+    .await;
+    checks.push(match version {
+        Ok(Ok(output)) if output.status.success() => Check::new(
+            "opencode binary",
+            Status::Pass,
+            format!(
+                "{} {}",
+                opencode.path,
+                String::from_utf8_lossy(&output.stdout).trim()
+            ),
+        ),
+        Ok(Ok(output)) => Check::new(
+            "opencode binary",
+            Status::Fail,
+            format!(
+                "`{} --version` exited with {}",
+                opencode.path, output.status
+            ),
+        ),
+        Ok(Err(error)) => Check::new(
+            "opencode binary",
+            // Remote backends can carry every worker without a local binary.
+            if opencode.backends.is_empty() {
+                Status::Fail
+            } else {
+                Status::Warn
+            },
+            format!("cannot run {}: {error}", opencode.path),
+        ),
+        Err(_) => Check::new(
+            "opencode binary",
+            Status::Warn,
+            format!("`{} --version` timed out", opencode.path),
+        ),
+    });
+
+    for backend in &opencode.backends {
+        let name = format!("opencode backend {}", backend.name);
+        let connected = tokio::time::timeout(
+            CHECK_TIMEOUT,
+            OpenCodeServer::connect_remote(
+                backend.directory.clone(),
+                backend,
+                &opencode.permissions,
+            ),
+        )
+        .await;
+        checks.push(match connected {
+            Ok(Ok(_)) => Check::new(name, Status::Pass, format!("{} is healthy", backend.url)),
+            Ok(Err(error)) => Check::new(name, Status::Fail, format!("{error:#}")),
+            Err(_) => Check::new(name, Status::Fail, format!("{} timed out", backend.url)),
+        });
+    }
+    checks
+}
+
+async fn check_messaging(config: &Config, http: &reqwest::Client) -> Vec<Check> {
+    let messaging = &config.messaging;
+    let mut checks = Vec::new();
+
+    if let Some(discord) = messaging.discord.as_ref() {
+        let instances = std::iter::once((None, discord.enabled, &discord.token)).chain(
+            discord
+                .instances
+                .iter()
+                .map(|instance| (Some(&instance.name), instance.enabled, &instance.token)),
+        );
+        for (instance, _, token) in instances.filter(|(_, enabled, _)| *enabled) {
+            let request = http
+                .get("https://discord.com/api/v10/users/@me")
+                .header(reqwest::header::AUTHORIZATION, format!("Bot {token}"));
+            checks.push(
+                token_check(platform_name("discord", instance), request, |body| {
+                    body.get("username")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .await,
+            );
+        }
+    }
+
+    if let Some(slack) = messaging.slack.as_ref() {
+        let instances = std::iter::once((None, slack.enabled, &slack.bot_token)).chain(
+            slack
+                .instances
+                .iter()
+                .map(|instance| (Some(&instance.name), instance.enabled, &instance.bot_token)),
+        );
+        for (instance, _, token) in instances.filter(|(_, enabled, _)| *enabled) {
+            let request = http
+                .post("https://slack.com/api/auth.test")
+                .bearer_auth(token);
+            checks.push(
+                token_check(platform_name("slack", instance), request, |body| {
+                    ok_field(body)?;
+                    body.get("user").and_then(Value::as_str).map(str::to_string)
+                })
+                .await,
+            );
+        }
+    }
+
+    if let Some(telegram) = messaging.telegram.as_ref() {
+        let instances = std::iter::once((None, telegram.enabled, &telegram.token)).chain(
+            telegram
+                .instances
+                .iter()
+                .map(|instance| (Some(&instance.name), instance.enabled, &instance.token)),
+        );
+        for (instance, _, token) in instances.filter(|(_, enabled, _)| *enabled) {
+            let request = http.get(format!("https://api.telegram.org/bot{token}/getMe"));
+            checks.push(
+                token_check(platform_name("telegram", instance), request, |body| {
+                    ok_field(body)?;
+                    body.pointer("/result/username")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .await,
+            );
+        }
+    }
+
+    if let Some(mattermost) = messaging.mattermost.as_ref() {
+        let instances = std::iter::once((
+            None,
+            mattermost.enabled,
+            &mattermost.base_url,
+            &mattermost.token,
+        ))
+        .chain(mattermost.instances.iter().map(|instance| {
+            (
+                Some(&instance.name),
+                instance.enabled,
+                &instance.base_url,
+                &instance.token,
+            )
+        }));
+        for (instance, _, base_url, token) in instances.filter(|(_, enabled, ..)| *enabled) {
+            let request = http
+                .get(format!(
+                    "{}/api/v4/users/me",
+                    base_url.trim_end_matches('/')
+                ))
+                .bearer_auth(token);
+            checks.push(
+                token_check(platform_name("mattermost", instance), request, |body| {
+                    body.get("username")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                })
+                .await,
+            );
+        }
+    }
+
+    let unchecked = [
+        ("email", messaging.email.as_ref().is_some_and(|c| c.enabled)),
+        (
+            "twitch",
+            messaging.twitch.as_ref().is_some_and(|c| c.enabled),
+        ),
+        (
+            "signal",
+            messaging.signal.as_ref().is_some_and(|c| c.enabled),
+        ),
+        ("irc", messaging.irc.as_ref().is_some_and(|c| c.enabled)),
+    ];
+    for (platform, _) in unchecked.iter().filter(|(_, enabled)| *enabled) {
+        checks.push(Check::new(
+            *platform,
+            Status::Warn,
+            "enabled; credentials are not checked by doctor",
+        ));
+    }
+
+    let webhook = messaging.webhook.as_ref().is_some_and(|c| c.enabled);
+    if checks.is_empty() && !webhook {
+        checks.push(Check::new(
+            "messaging",
+            Status::Warn,
+            "no chat platform is enabled; agents are reachable only through the API",
+        ));
+    }
+    checks
+}
+
+fn platform_name(platform: &str, instance: Option<&String>) -> String {
+    match instance {
+        Some(instance) => format!("{platform}:{instance}"),
+        None => platform.to_string(),
+    }
+}
+
+/// Slack and Telegram answer 200 with `ok: false` for a bad token.
+fn ok_field(body: &Value) -> Option<()> {
+    body.get("ok").and_then(Value::as_bool).filter(|ok| *ok)?;
+    Some(())
+}
+
+/// Send an authenticated request and report who the platform says the
+/// token belongs to.
+async fn token_check(
+    name: String,
+    request: reqwest::RequestBuilder,
+    identity: impl FnOnce(&Value) -> Option<String>,
+) -> Check {
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(error) => return Check::new(name, Status::Fail, format!("unreachable: {error}")),
+    };
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Check::new(name, Status::Fail, format!("token rejected ({status})"));
+    }
+    if !status.is_success() {
+        return Check::new(name, Status::Fail, format!("API returned {status}"));
+    }
+    let body: Value = response.json().await.unwrap_or_default();
+    match identity(&body) {
+        Some(identity) => Check::new(name, Status::Pass, format!("authenticated as {identity}")),
+        None => {
+            let error = body
+                .get("error")
+                .or_else(|| body.get("description"))
+                .and_then(Value::as_str)
+                .unwrap_or("unexpected response");
+            Check::new(name, Status::Fail, format!("token rejected: {error}"))
+        }
+    }
+}
+
+async fn check_migrations(config: &Config) -> Vec<Check> {
+    let mut checks = vec![
+        check_database(
+            "instance database".to_string(),
+            &config.instance_dir.join("data").join("spacebot.db"),
+            &crate::db::INSTANCE_MIGRATOR,
+        )
+        .await,
+    ];
+    if let DatabaseConfig::Postgres(postgres) = &config.database {
+        checks.push(check_postgres(postgres).await);
+    }
+    for agent in config.resolve_agents() {
+        checks.push(
+            check_database(
+                format!("agent {} database", agent.id),
+                &agent.sqlite_path(),
+                &crate::db::AGENT_MIGRATOR,
+            )
+            .await,
+        );
+    }
+    checks
+}
+
+/// Compare the migrations applied to the database at `path` with the ones
+/// this build embeds, without running any.
+async fn check_database(name: String, path: &Path, migrator: &Migrator) -> Check {
+    if !path.exists() {
+        return Check::new(
+            name,
+            Status::Pass,
+            format!("{} will be created on first start", path.display()),
+        );
+    }
+    let url = format!("sqlite:{}?mode=ro", path.display());
+    let pool = match sqlx::SqlitePool::connect(&url).await {
+        Ok(pool) => pool,
+        Err(error) => return Check::new(name, Status::Fail, format!("cannot open: {error}")),
+    };
+    let state = crate::db::migration_state(&pool, migrator).await;
+    pool.close().await;
+    match state {
+        Ok(state) => migration_status(name, &state),
+        Err(error) => Check::new(name, Status::Fail, error.to_string()),
+    }
+}
+
+async fn check_postgres(config: &PostgresConfig) -> Check {
+    let name = "shared database".to_string();
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(CHECK_TIMEOUT)
+        .connect(&config.url)
+        .await;
+    let pool = match pool {
+        Ok(pool) => pool,
+        Err(error) => return Check::new(name, Status::Fail, format!("cannot connect: {error}")),
+    };
+    let applied: Result<Vec<(i64, bool)>, _> =
+        sqlx::query_as("SELECT version, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(&pool)
+            .await;
+    pool.close().await;
+    // A database nothing has migrated yet has no history table.
+    let applied = applied.unwrap_or_default();
+    migration_status(
+        name,
+        &MigrationState::compare(&crate::db::POSTGRES_MIGRATOR, &applied),
+    )
+}
+
+fn migration_status(name: String, state: &MigrationState) -> Check {
+    if let Some(version) = state.failed {
+        return Check::new(
+            name,
+            Status::Fail,
+            format!("migration {version} failed partway; restore from a backup"),
+        );
+    }
+    if !state.unknown.is_empty() {
+        return Check::new(
+            name,
+            Status::Fail,
+            format!(
+                "{} migration(s) from a newer spacebot; this build is older",
+                state.unknown.len()
+            ),
+        );
+    }
+    match state.pending.len() {
+        0 => Check::new(name, Status::Pass, "up to date"),
+        pending => Check::new(
+            name,
+            Status::Warn,
+            format!("{pending} migration(s) pending; applied on next start"),
+        ),
+    }
+}
+
+/// Render a report, one check per line, colored when `color` is set.
+pub fn render(report: &Report, color: bool) -> String {
+    let width = report
+        .checks
+        .iter()
+        .map(|check| check.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::new();
+    for check in &report.checks {
+        let label = format!("{:<4}", check.status.label());
+        let label = if color {
+            format!("{}{label}\x1b[0m", check.status.color())
+        } else {
+            label
+        };
+        let _ = writeln!(output, "[{label}] {:<width$}  {}", check.name, check.detail);
+    }
+
+    let count = |status| {
+        report
+            .checks
+            .iter()
+            .filter(|check| check.status == status)
+            .count()
+    };
+    let _ = writeln!(
+        output,
+        "\n{} passed, {} warning(s), {} failed",
+        count(Status::Pass),
+        count(Status::Warn),
+        count(Status::Fail)
+    );
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_render_aligned_with_a_summary() {
+        let report = Report {
+            checks: vec![
+                Check::new("config", Status::Pass, "loaded"),
+                Check::new("slack", Status::Fail, "token rejected: invalid_auth"),
+                Check::new("irc", Status::Warn, "not checked"),
+            ],
+        };
+        assert!(report.has_failures());
+        assert_eq!(
+            render(&report, false),
+            "[ok  ] config  loaded\n\
+             [FAIL] slack   token rejected: invalid_auth\n\
+             [warn] irc     not checked\n\
+             \n1 passed, 1 warning(s), 1 failed\n"
+        );
+        assert!(render(&report, true).contains("\x1b[31mFAIL\x1b[0m"));
+    }
+
+    #[test]
+    fn migration_state_maps_to_a_status() {
+        let status = |state: MigrationState| migration_status("db".into(), &state).status;
+        assert_eq!(status(MigrationState::default()), Status::Pass);
+        assert_eq!(
+            status(MigrationState {
+                pending: vec![3],
+                ..Default::default()
+            }),
+            Status::Warn
+        );
+        assert_eq!(
+            status(MigrationState {
+                unknown: vec![4],
+                ..Default::default()
+            }),
+            Status::Fail
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Direct-to-terminal rendering of agent replies for `spacebot run --follow`.
+//!
+//! The follower reads the raw `/api/events` SSE byte stream and writes each
+//! `outbound_message_delta` for one channel straight to the output as it is
+//! parsed: no coalescing, no message model, and no per-event allocation
+//! once the line and data buffers have grown to the largest event seen.
+//! With latency recording on, it measures the time from a chunk arriving off
+//! the socket to its deltas being flushed, which is all the latency it adds
+//! over reading the stream with `curl -N`.
+
+use serde::Deserialize;
+
+use std::borrow::Cow;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// The SSE event kinds the follower reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventKind {
+    Delta,
+    Message,
+    Other,
+}
+
+/// Fields of `outbound_message_delta` and `outbound_message` the follower
+/// needs. Other fields, including the delta's `aggregated_text`, are skipped
+/// without being copied.
+#[derive(Deserialize)]
+struct ReplyEvent<'a> {
+    #[serde(borrow)]
+    channel_id: Cow<'a, str>,
+    #[serde(borrow, default)]
+    text_delta: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    text: Option<Cow<'a, str>>,
+}
+
+/// Incremental SSE parser that writes one channel's reply text to `output`.
+pub struct ReplyFollower<W: Write> {
+    channel_id: String,
+    output: W,
+    /// Bytes of an incomplete trailing line from the previous chunk.
+    partial_line: Vec<u8>,
+    event_kind: EventKind,
+    data: Vec<u8>,
+    /// Whether deltas were written for the reply in progress, in which case
+    /// its final `outbound_message` only ends the line.
+    streamed_current_reply: bool,
+    latency: Option<LatencyStats>,
+}
+
+impl<W: Write> ReplyFollower<W> {
+    pub fn new(channel_id: impl Into<String>, output: W) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            output,
+            partial_line: Vec::new(),
+            event_kind: EventKind::Other,
+            data: Vec::new(),
+            streamed_current_reply: false,
+            latency: None,
+        }
+    }
+
+    /// Record per-chunk write latency for [`Self::latency`].
+    pub fn with_latency_recording(mut self) -> Self {
+        self.latency = Some(LatencyStats::default());
+        self
+    }
+
+    pub fn latency(&self) -> Option<&LatencyStats> {
+        self.latency.as_ref()
+    }
+
+    /// Parse a chunk read off the socket at `received_at` and write any
+    /// reply text it completes.
+    pub fn feed(&mut self, chunk: &[u8], received_at: Instant) -> std::io::Result<()> {
+        let mut wrote = false;
+        let mut rest = chunk;
+        while let Some(newline) = rest.iter().position(|byte| *byte == b'\n') {
+            let (line, remainder) = rest.split_at(newline);
+            rest = &remainder[1..];
+            if self.partial_line.is_empty() {
+                wrote |= self.process_line(line)?;
+            } else {
+                let mut joined = std::mem::take(&mut self.partial_line);
+                joined.extend_from_slice(line);
+                wrote |= self.process_line(&joined)?;
+                joined.clear();
+                self.partial_line = joined;
+            }
+        }
+        self.partial_line.extend_from_slice(rest);
+
+        if wrote {
+            self.output.flush()?;
+            if let Some(latency) = &mut self.latency {
+                latency.record(received_at.elapsed());
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one SSE line. Returns `true` when reply text was written.
+    fn process_line(&mut self, line: &[u8]) -> std::io::Result<bool> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.is_empty() {
+            let wrote = self.dispatch()?;
+            self.event_kind = EventKind::Other;
+            self.data.clear();
+            return Ok(wrote);
+        }
+        if let Some(value) = field_value(line, b"event") {
+            self.event_kind = match value {
+                b"outbound_message_delta" => EventKind::Delta,
+                b"outbound_message" => EventKind::Message,
+                _ => EventKind::Other,
+            };
+        } else if let Some(value) = field_value(line, b"data")
+            && self.event_kind != EventKind::Other
+        {
+            if !self.data.is_empty() {
+                self.data.push(b'\n');
+            }
+            self.data.extend_from_slice(value);
+        }
+        Ok(false)
+    }
+
+    fn dispatch(&mut self) -> std::io::Result<bool> {
+        if self.event_kind == EventKind::Other || self.data.is_empty() {
+            return Ok(false);
+        }
+        let Ok(event) = serde_json::from_slice::<ReplyEvent>(&self.data) else {
+            return Ok(false);
+        };
+        if event.channel_id != self.channel_id {
+            return Ok(false);
+        }
+
+        match self.event_kind {
+            EventKind::Delta => {
+                let Some(text_delta) = event.text_delta else {
+                    return Ok(false);
+                };
+                self.output.write_all(text_delta.as_bytes())?;
+                self.streamed_current_reply = true;
+            }
+            EventKind::Message => {
+                if !self.streamed_current_reply
+                    && let Some(text) = event.text
+                {
+                    self.output.write_all(text.as_bytes())?;
+                }
+                self.output.write_all(b"\n")?;
+                self.streamed_current_reply = false;
+            }
+            EventKind::Other => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `event: value` → `value`, per the SSE field syntax.
+fn field_value<'a>(line: &'a [u8], field: &[u8]) -> Option<&'a [u8]> {
+    let value = line.strip_prefix(field)?.strip_prefix(b":")?;
+    Some(value.strip_prefix(b" ").unwrap_or(value))
+}
+
+/// How many recent samples percentiles are computed over, so a long
+/// `--follow` session doesn't grow without bound.
+const MAX_SAMPLES: usize = 4096;
+
+/// Added latency per chunk that produced output.
+#[derive(Debug, Default)]
+pub struct LatencyStats {
+    /// The most recent samples, overwritten in a ring once full.
+    samples: Vec<Duration>,
+    next: usize,
+    count: u64,
+    max: Duration,
+}
+
+impl LatencyStats {
+    fn record(&mut self, sample: Duration) {
+        self.count += 1;
+        self.max = self.max.max(sample);
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(sample);
+        } else {
+            self.samples[self.next] = sample;
+        }
+        self.next = (self.next + 1) % MAX_SAMPLES;
+    }
+
+    /// The given percentile (0.0–1.0) of the retained samples.
+    pub fn percentile(&self, fraction: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let index = ((sorted.len() - 1) as f64 * fraction).round() as usize;
+        Some(sorted[index])
+    }
+
+    /// `samples=N p50=… p99=… max=…`, or `None` before any output. The
+    /// percentiles cover the last [`MAX_SAMPLES`] chunks; the count and max
+    /// cover the whole session.
+    pub fn summary(&self) -> Option<String> {
+        Some(format!(
+            "samples={} p50={:?} p99={:?} max={:?}",
+            self.count,
+            self.percentile(0.50)?,
+            self.percentile(0.99)?,
+            self.max
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANNEL: &str = "portal:chat:main:cli";
+
+    fn delta(channel_id: &str, text_delta: &str) -> String {
+        format!(
+            "event: outbound_message_delta\ndata: {}\n\n",
+            serde_json::json!({
+                "type": "outbound_message_delta",
+                "agent_id": "main",
+                "channel_id": channel_id,
+                "text_delta": text_delta,
+                "aggregated_text": "ignored",
+            })
+        )
+    }
+
+    fn message(channel_id: &str, text: &str) -> String {
+        format!(
+            "event: outbound_message\ndata: {}\n\n",
+            serde_json::json!({
+                "type": "outbound_message",
+                "agent_id": "main",
+                "channel_id": channel_id,
+                "text": text,
+            })
+        )
+    }
+
+    #[test]
+    fn writes_deltas_for_the_followed_channel_only() {
+        let stream = [
+            delta(CHANNEL, "Hel"),
+            delta("discord:1", "nope"),
+            "event: typing_state\ndata: {}\n\n".to_string(),
+            delta(CHANNEL, "lo \"world\""),
+            message(CHANNEL, "Hello \"world\""),
+            message(CHANNEL, "Second reply"),
+        ]
+        .concat();
+        let mut follower = ReplyFollower::new(CHANNEL, Vec::new());
+        follower.feed(stream.as_bytes(), Instant::now()).unwrap();
+        assert_eq!(
+            String::from_utf8(follower.output).unwrap(),
+            "Hello \"world\"\nSecond reply\n"
+        );
+    }
+
+    #[test]
+    fn handles_events_split_across_chunks() {
+        let stream = [delta(CHANNEL, "héllo"), message(CHANNEL, "héllo")].concat();
+        let mut follower = ReplyFollower::new(CHANNEL, Vec::new()).with_latency_recording();
+        for chunk in stream.as_bytes().chunks(7) {
+            follower.feed(chunk, Instant::now()).unwrap();
+        }
+        assert_eq!(follower.latency().unwrap().count, 2);
+        assert_eq!(String::from_utf8(follower.output).unwrap(), "héllo\n");
+    }
+
+    #[test]
+    fn added_latency_stays_within_budget() {
+        // "A few milliseconds" over reading the stream raw. The follower's
+        // own work is microseconds, so this only trips on a real regression.
+        const BUDGET: Duration = Duration::from_millis(3);
+
+        let mut follower = ReplyFollower::new(CHANNEL, Vec::new()).with_latency_recording();
+        for index in 0..5_000 {
+            let chunk = delta(CHANNEL, &format!("token {index} "));
+            follower.feed(chunk.as_bytes(), Instant::now()).unwrap();
+        }
+        let latency = follower.latency().unwrap();
+        assert_eq!(latency.count, 5_000);
+        assert_eq!(latency.samples.len(), MAX_SAMPLES);
+        let p99 = latency.percentile(0.99).unwrap();
+        assert!(p99 < BUDGET, "p99 added latency {p99:?} exceeds {BUDGET:?}");
+    }
+}
@@ -1,29 +1,44 @@
 //! Spacebot: A Rust agentic system where every LLM process has a dedicated role.
 
+pub mod access;
 pub mod agent;
 pub mod api;
+pub mod audit;
 pub mod auth;
+pub mod builder;
+pub mod cluster;
 pub mod config;
 pub mod conversation;
+pub mod cost_report;
 pub mod cron;
+pub mod ctl;
 pub mod daemon;
 pub mod db;
+pub mod dead_letters;
+pub mod digest;
+pub mod doctor;
 pub mod error;
 pub mod factory;
+pub mod follow;
 pub mod github_copilot_auth;
 pub mod hooks;
 pub mod identity;
 pub mod links;
 pub mod llm;
+pub mod maintenance;
 pub mod mcp;
 pub mod memory;
 pub mod messaging;
 pub mod notifications;
 pub mod openai_auth;
 pub mod opencode;
+pub mod plugins;
 pub mod projects;
 pub mod prompts;
+pub mod rate_limit;
+pub mod runtime;
 pub mod sandbox;
+pub mod scripting;
 pub mod secrets;
 pub mod self_awareness;
 pub mod settings;
@@ -31,10 +46,18 @@ pub mod skills;
 pub mod tasks;
 #[cfg(feature = "metrics")]
 pub mod telemetry;
+pub mod templates;
+pub mod testkit;
+pub mod tool_stats;
 pub mod tools;
+#[cfg(feature = "transcribe")]
+pub mod transcribe;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod update;
 pub mod wiki;
 
+pub use builder::SpacebotBuilder;
 pub use error::{Error, Result};
 
 /// Generate the OpenAPI JSON specification.
@@ -197,6 +220,14 @@ pub enum ProcessEvent {
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
     },
+    /// An OpenCode worker's session started or stopped working, from its
+    /// `session.status` events. Drives the channel's typing heartbeat.
+    WorkerBusy {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        busy: bool,
+    },
     WorkerComplete {
         agent_id: AgentId,
         worker_id: WorkerId,
@@ -243,9 +274,13 @@ pub enum ProcessEvent {
         agent_id: AgentId,
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
+        session_id: String,
         permission_id: String,
         description: String,
         patterns: Vec<String>,
+        /// The worker waits for an admin to allow or deny it in chat.
+        /// Otherwise it was allowed once without asking.
+        awaiting_approval: bool,
     },
     WorkerQuestion {
         agent_id: AgentId,
@@ -286,7 +321,34 @@ pub enum ProcessEvent {
     OpenCodePartUpdated {
         agent_id: AgentId,
         worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
         part: crate::opencode::types::OpenCodePart,
+        /// Set when the part comes from a sub-agent session spawned by the
+        /// `task` tool rather than the worker's own session.
+        subagent: Option<crate::opencode::types::OpenCodeSubagent>,
+    },
+    /// An OpenCode worker's session was given a title (or a new one) by the
+    /// server's summarizer. Used to name the chat thread the session runs in.
+    WorkerSessionTitle {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        title: String,
+    },
+    /// An OpenCode worker's todo list changed. Carries the full list so the
+    /// channel can re-render its live checklist.
+    WorkerTodos {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        todos: Vec<crate::opencode::types::TodoItem>,
+    },
+    /// An OpenCode server emitted a log entry or notification not tied to
+    /// a session. Forwarded to the ops channel by the cortex.
+    OpenCodeServerLog {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        entry: crate::opencode::types::ServerLogEntry,
     },
     /// An interactive worker's initial task completed. The worker remains alive
     /// for follow-ups, but the channel should retrigger to deliver this result.
@@ -297,6 +359,48 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         result: String,
     },
+    /// An OpenCode worker in plan-approval mode produced a plan (or a revised
+    /// plan) and is waiting for it to be approved before building.
+    WorkerPlanReady {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        plan: String,
+    },
+    /// An OpenCode worker in dry-run mode built a prompt. `payload` is the
+    /// request body it would have sent, as pretty-printed JSON.
+    WorkerDryRun {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        payload: String,
+    },
+    /// A raw OpenCode SSE event for a worker's session, sent while the
+    /// channel mirrors events for `/debug`. `data` is the event's JSON with
+    /// secrets scrubbed.
+    WorkerRawEvent {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        data: String,
+    },
+    /// Files an OpenCode worker changed during its last assistant turn, in
+    /// first-changed order, relative to the worker's directory.
+    WorkerFilesChanged {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        files: Vec<crate::opencode::types::FileChange>,
+        /// Distinct files changed over the whole session so far.
+        session_total: usize,
+    },
+    /// An OpenCode worker's session overflowed its context window and was
+    /// compacted. The worker retries the prompt that overflowed.
+    WorkerContextCompacted {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+    },
     TextDelta {
         agent_id: AgentId,
         process_id: ProcessId,
@@ -340,6 +444,14 @@ pub enum ProcessEvent {
         agent_id: AgentId,
         channel_id: ChannelId,
     },
+    /// An admin broadcast for every active channel of the agent.
+    Broadcast { agent_id: AgentId, text: String },
+    /// Abort every busy worker session in every channel of the agent, or
+    /// only those on the named OpenCode backend (`/admin abort-all`).
+    AbortAll {
+        agent_id: AgentId,
+        backend: Option<String>,
+    },
 }
 
 /// Default broadcast capacity for the per-agent control event bus.
@@ -446,6 +558,8 @@ pub struct AgentDeps {
     /// control event bus so high-volume output doesn't crowd out critical events.
     pub tool_output_tx: tokio::sync::broadcast::Sender<ProcessEvent>,
     pub sqlite_pool: sqlx::SqlitePool,
+    /// Batched writer for transcript snapshots and token usage on `sqlite_pool`.
+    pub write_behind: conversation::WriteBehind,
     pub messaging_manager: Option<Arc<messaging::MessagingManager>>,
     pub sandbox: Arc<sandbox::Sandbox>,
     pub links: Arc<arc_swap::ArcSwap<Vec<links::AgentLink>>>,
@@ -606,6 +720,17 @@ pub enum MessageContent {
         /// Platform-specific message reference (`ts` on Slack, message ID on Discord).
         message_ts: Option<String>,
     },
+    /// A message in the conversation was deleted on the platform.
+    ///
+    /// Produced by adapters that receive deletion events (currently Discord).
+    /// Never starts a channel and never reaches the LLM.
+    Deleted {
+        /// Platform message ID of the deleted message.
+        message_id: String,
+        /// True when the deleted message was one the bot sent (e.g. a
+        /// streaming reply), false for user messages.
+        bot_message: bool,
+    },
 }
 
 impl std::fmt::Display for MessageContent {
@@ -633,6 +758,9 @@ impl std::fmt::Display for MessageContent {
                     write!(f, "[interaction: {}]", action_id)
                 }
             }
+            MessageContent::Deleted { message_id, .. } => {
+                write!(f, "[message deleted: {}]", message_id)
+            }
         }
     }
 }
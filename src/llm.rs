@@ -6,6 +6,7 @@ pub mod model;
 pub mod pricing;
 pub mod providers;
 pub mod routing;
+pub mod tokens;
 pub mod usage;
 
 pub use manager::LlmManager;
@@ -0,0 +1,207 @@
+//! Token count estimates for prompt budgeting.
+//!
+//! Splits text the way tiktoken's `cl100k`/`o200k` encoders pre-tokenize it
+//! (letter runs, digit runs of up to three, punctuation, whitespace) and
+//! prices each piece instead of running the BPE merges. No vocabulary is
+//! loaded, so the count is an estimate that leans high: good for deciding
+//! whether a prompt fits, not for billing.
+
+use rig::message::{AssistantContent, Message, UserContent};
+
+/// Tokens a chat API adds around every message (role and separators).
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Flat estimate for an image, audio clip, or video in a message.
+const MEDIA_TOKENS: usize = 500;
+
+/// Flat estimate for an attached document.
+const DOCUMENT_TOKENS: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Piece {
+    Letters,
+    Digits,
+    Punctuation,
+    /// Plain spaces.
+    Spaces,
+    /// Newlines, tabs, and other whitespace.
+    Whitespace,
+}
+
+/// Estimate how many tokens `text` encodes to.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run: Option<(Piece, usize)> = None;
+    for character in text.chars() {
+        let Some(piece) = classify(character) else {
+            tokens += close(run.take(), None) + wide_tokens(character);
+            continue;
+        };
+        match run {
+            Some((kind, length)) if kind == piece => run = Some((kind, length + 1)),
+            _ => {
+                tokens += close(run.take(), Some(piece));
+                run = Some((piece, 1));
+            }
+        }
+    }
+    tokens + close(run, None)
+}
+
+/// The run a character belongs to, or `None` for a character that is
+/// priced on its own.
+fn classify(character: char) -> Option<Piece> {
+    if character.is_ascii_digit() {
+        Some(Piece::Digits)
+    } else if character == ' ' {
+        Some(Piece::Spaces)
+    } else if character.is_whitespace() {
+        Some(Piece::Whitespace)
+    } else if is_wide(character) {
+        None
+    } else if character.is_alphabetic() || character == '\'' {
+        Some(Piece::Letters)
+    } else {
+        Some(Piece::Punctuation)
+    }
+}
+
+/// Price a finished run. `next` is the run that follows it.
+fn close(run: Option<(Piece, usize)>, next: Option<Piece>) -> usize {
+    match run {
+        None => 0,
+        Some((Piece::Letters, length)) => length.div_ceil(4),
+        // Numbers are split into groups of at most three digits.
+        Some((Piece::Digits, length)) => length.div_ceil(3),
+        Some((Piece::Punctuation, length)) => length.div_ceil(2),
+        // A lone space merges into the word after it (" hello").
+        Some((Piece::Spaces, 1)) if next == Some(Piece::Letters) => 0,
+        Some((Piece::Spaces | Piece::Whitespace, _)) => 1,
+    }
+}
+
+/// CJK ideographs, kana, and hangul, plus any non-ASCII symbol that isn't
+/// a letter (emoji, box drawing, math).
+fn is_wide(character: char) -> bool {
+    !character.is_ascii()
+        && (matches!(
+            character as u32,
+            0x3040..=0x30FF | 0x3400..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+        ) || !character.is_alphabetic())
+}
+
+/// CJK characters are roughly a token each; symbols take about one per two
+/// UTF-8 bytes.
+fn wide_tokens(character: char) -> usize {
+    if character.is_alphabetic() {
+        1
+    } else {
+        character.len_utf8().div_ceil(2)
+    }
+}
+
+/// Estimate the tokens one chat message takes, including its overhead.
+pub fn estimate_message_tokens(message: &Message) -> usize {
+    let content_tokens: usize = match message {
+        Message::User { content } => content.iter().map(estimate_user_content).sum(),
+        Message::Assistant { content, .. } => content.iter().map(estimate_assistant_content).sum(),
+        Message::System { content } => estimate_tokens(content),
+    };
+    content_tokens + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Estimate the tokens a chat history takes.
+pub fn estimate_history_tokens(history: &[Message]) -> usize {
+    history.iter().map(estimate_message_tokens).sum()
+}
+
+fn estimate_user_content(content: &UserContent) -> usize {
+    match content {
+        UserContent::Text(text) => estimate_tokens(&text.text),
+        UserContent::ToolResult(result) => result
+            .content
+            .iter()
+            .map(|item| match item {
+                rig::message::ToolResultContent::Text(text) => estimate_tokens(&text.text),
+                rig::message::ToolResultContent::Image(_) => MEDIA_TOKENS,
+            })
+            .sum(),
+        UserContent::Image(_) | UserContent::Audio(_) | UserContent::Video(_) => MEDIA_TOKENS,
+        UserContent::Document(_) => DOCUMENT_TOKENS,
+    }
+}
+
+fn estimate_assistant_content(content: &AssistantContent) -> usize {
+    match content {
+        AssistantContent::Text(text) => estimate_tokens(&text.text),
+        AssistantContent::ToolCall(call) => {
+            estimate_tokens(&call.function.name)
+                + estimate_tokens(&call.function.arguments.to_string())
+        }
+        AssistantContent::Reasoning(reasoning) => reasoning
+            .content
+            .iter()
+            .map(|content| match content {
+                rig::message::ReasoningContent::Text { text, .. } => estimate_tokens(text),
+                rig::message::ReasoningContent::Summary(summary) => estimate_tokens(summary),
+                // Encrypted and redacted reasoning is opaque base64.
+                rig::message::ReasoningContent::Encrypted(data) => data.len() / 4,
+                rig::message::ReasoningContent::Redacted { data } => data.len() / 4,
+                #[allow(unreachable_patterns)]
+                _ => 0,
+            })
+            .sum(),
+        AssistantContent::Image(_) => MEDIA_TOKENS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_absorb_the_space_before_them() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hello"), 2);
+        assert_eq!(estimate_tokens("fix the bug"), 3);
+        assert_eq!(estimate_tokens("fix  the"), 3);
+        assert_eq!(estimate_tokens("line one\nline two"), 5);
+    }
+
+    #[test]
+    fn numbers_split_into_groups_of_three() {
+        assert_eq!(estimate_tokens("2026"), 2);
+        assert_eq!(estimate_tokens("123456789"), 3);
+    }
+
+    #[test]
+    fn wide_characters_count_individually() {
+        assert_eq!(estimate_tokens("你好世界"), 4);
+        assert_eq!(estimate_tokens("🚀"), 2);
+        // Accented letters stay part of their word.
+        assert_eq!(estimate_tokens("también"), 2);
+    }
+
+    #[test]
+    fn prose_lands_near_four_characters_per_token() {
+        let text = "The channel keeps a rolling history of the conversation and \
+                    compacts it in the background once it grows past a threshold.";
+        let estimate = estimate_tokens(text);
+        let chars_over_four = text.len() / 4;
+        assert!(estimate >= chars_over_four * 3 / 4, "{estimate}");
+        assert!(estimate <= chars_over_four * 3 / 2, "{estimate}");
+    }
+
+    #[test]
+    fn messages_carry_overhead() {
+        let message = Message::from("hello");
+        assert_eq!(
+            estimate_message_tokens(&message),
+            2 + MESSAGE_OVERHEAD_TOKENS
+        );
+        assert_eq!(
+            estimate_history_tokens(&[message.clone(), message]),
+            2 * (2 + MESSAGE_OVERHEAD_TOKENS)
+        );
+    }
+}
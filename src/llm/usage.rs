@@ -35,6 +35,18 @@ impl ExtendedUsage {
         }
     }
 
+    /// Extract usage from the `tokens` object OpenCode puts on assistant
+    /// messages.
+    pub fn from_opencode_tokens(tokens: &serde_json::Value) -> Self {
+        Self {
+            input_tokens: tokens["input"].as_u64().unwrap_or(0),
+            output_tokens: tokens["output"].as_u64().unwrap_or(0),
+            cache_read_tokens: tokens["cache"]["read"].as_u64().unwrap_or(0),
+            cache_write_tokens: tokens["cache"]["write"].as_u64().unwrap_or(0),
+            reasoning_tokens: tokens["reasoning"].as_u64().unwrap_or(0),
+        }
+    }
+
     /// Extract extended usage from an OpenAI-compatible response body.
     pub fn from_openai_body(body: &serde_json::Value) -> Self {
         let usage = &body["usage"];
@@ -166,54 +178,146 @@ impl UsageAccumulator {
             .unwrap_or_else(|| "unknown".to_string())
     }
 
-    /// Flush accumulated usage to the database.
-    pub async fn flush(
+    /// The `token_usage` row for this accumulator, or `None` if nothing was
+    /// recorded.
+    pub fn to_record(
         &self,
-        pool: &SqlitePool,
         agent_id: &str,
         process_type: &str,
         conversation_id: Option<&str>,
-    ) -> Result<(), sqlx::Error> {
+    ) -> Option<UsageRecord> {
         if !self.has_usage() {
-            return Ok(());
+            return None;
         }
+        Some(UsageRecord {
+            agent_id: agent_id.to_string(),
+            process_type: process_type.to_string(),
+            conversation_id: conversation_id.map(ToString::to_string),
+            user_id: None,
+            model: self.primary_model(),
+            provider: self
+                .provider
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_read_tokens: self.cache_read_tokens,
+            cache_write_tokens: self.cache_write_tokens,
+            reasoning_tokens: self.reasoning_tokens,
+            request_count: self.request_count,
+            estimated_cost_usd: (self.cost_status != CostStatus::Unknown)
+                .then_some(self.estimated_cost_usd),
+            cost_status: self.cost_status,
+        })
+    }
+}
 
-        let model = self.primary_model();
-        let provider = self.provider.as_deref().unwrap_or("unknown");
-        let cost_status = self.cost_status.as_str();
-        let cost = if self.cost_status == CostStatus::Unknown {
-            None
-        } else {
-            Some(self.estimated_cost_usd)
-        };
+/// One `token_usage` row: a process's accumulated usage.
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub agent_id: String,
+    pub process_type: String,
+    pub conversation_id: Option<String>,
+    /// `source:sender_id` of the user whose prompt led to this usage.
+    pub user_id: Option<String>,
+    pub model: String,
+    pub provider: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub request_count: u32,
+    /// `None` when the cost is unknown.
+    pub estimated_cost_usd: Option<f64>,
+    pub cost_status: CostStatus,
+}
+
+impl UsageRecord {
+    /// Attribute the usage to `user_id`.
+    pub fn with_user(mut self, user_id: Option<String>) -> Self {
+        self.user_id = user_id;
+        self
+    }
 
+    /// Whether the usage came from a private (DM) session.
+    pub fn is_private(&self) -> bool {
+        self.conversation_id
+            .as_deref()
+            .is_some_and(crate::conversation::private::is_dm_conversation_id)
+    }
+
+    /// Insert the row through `executor`, a pool or an open transaction.
+    /// Usage in a DM conversation is stored as private.
+    pub async fn insert<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+    ) -> Result<(), sqlx::Error> {
         sqlx::query(
             "INSERT INTO token_usage (
-                agent_id, process_type, conversation_id, model, provider,
+                agent_id, process_type, conversation_id, user_id, model, provider,
                 input_tokens, output_tokens, cache_read_tokens, cache_write_tokens,
-                reasoning_tokens, request_count, estimated_cost_usd, cost_status
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                reasoning_tokens, request_count, estimated_cost_usd, cost_status, private
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
-        .bind(agent_id)
-        .bind(process_type)
-        .bind(conversation_id)
-        .bind(&model)
-        .bind(provider)
+        .bind(&self.agent_id)
+        .bind(&self.process_type)
+        .bind(&self.conversation_id)
+        .bind(&self.user_id)
+        .bind(&self.model)
+        .bind(&self.provider)
         .bind(self.input_tokens as i64)
         .bind(self.output_tokens as i64)
         .bind(self.cache_read_tokens as i64)
         .bind(self.cache_write_tokens as i64)
         .bind(self.reasoning_tokens as i64)
         .bind(self.request_count as i32)
-        .bind(cost)
-        .bind(cost_status)
-        .execute(pool)
+        .bind(self.estimated_cost_usd)
+        .bind(self.cost_status.as_str())
+        .bind(self.is_private())
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 }
 
+/// Totals recorded in `token_usage` for one conversation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConversationUsage {
+    pub request_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub estimated_cost_usd: f64,
+}
+
+/// Sum the usage every process (channel turns, branches, workers) recorded
+/// against a conversation.
+pub async fn conversation_usage(
+    pool: &SqlitePool,
+    conversation_id: &str,
+) -> Result<ConversationUsage, sqlx::Error> {
+    use sqlx::Row as _;
+
+    let row = sqlx::query(
+        "SELECT COALESCE(SUM(request_count), 0) AS request_count, \
+         COALESCE(SUM(input_tokens), 0) AS input_tokens, \
+         COALESCE(SUM(output_tokens), 0) AS output_tokens, \
+         COALESCE(SUM(estimated_cost_usd), 0.0) AS estimated_cost_usd \
+         FROM token_usage WHERE conversation_id = ?",
+    )
+    .bind(conversation_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ConversationUsage {
+        request_count: row.try_get("request_count")?,
+        input_tokens: row.try_get("input_tokens")?,
+        output_tokens: row.try_get("output_tokens")?,
+        estimated_cost_usd: row.try_get("estimated_cost_usd")?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
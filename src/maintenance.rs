@@ -0,0 +1,256 @@
+//! Instance-wide maintenance mode, admin broadcasts, and abort-all.
+//!
+//! While maintenance mode is on, the router stops accepting new prompts:
+//! user messages are dropped before they reach an agent, and each sender
+//! gets the maintenance notice privately, once per conversation. Work already running is
+//! left to finish — worker completions and other system messages still flow —
+//! so sessions drain before an OpenCode server upgrade. `/admin` commands
+//! always pass so an admin can turn it back off.
+//!
+//! Broadcasts fan a text message out to every active channel of every agent.
+//! Abort-all does the same with a request to abort every busy worker
+//! session, optionally only those on one OpenCode backend.
+
+use crate::InboundMessage;
+
+use tokio::sync::broadcast;
+
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+/// Broadcasts buffered for a slow router before the oldest are dropped.
+const BROADCAST_CAPACITY: usize = 16;
+
+/// Abort-all requests buffered for a slow router.
+const ABORT_CAPACITY: usize = 4;
+
+static CONTROLS: LazyLock<AdminControls> = LazyLock::new(AdminControls::new);
+
+/// What to do with an inbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    /// Drop the message. `notify` is set for each sender's first message in
+    /// a conversation; `notice` is the admin's custom notice, if any.
+    Paused {
+        notify: bool,
+        notice: Option<String>,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Maintenance {
+    notice: Option<String>,
+    /// (conversation, sender) pairs already sent the notice this spell.
+    notified: HashSet<(String, String)>,
+}
+
+/// Maintenance state and the broadcast buses. Access via
+/// [`AdminControls::global`].
+pub struct AdminControls {
+    maintenance: Mutex<Option<Maintenance>>,
+    broadcast_tx: broadcast::Sender<String>,
+    /// Abort-all requests, carrying the backend they are scoped to.
+    abort_tx: broadcast::Sender<Option<String>>,
+}
+
+impl AdminControls {
+    /// Controls with maintenance off.
+    pub fn new() -> Self {
+        let (broadcast_tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let (abort_tx, _) = broadcast::channel(ABORT_CAPACITY);
+        Self {
+            maintenance: Mutex::new(None),
+            broadcast_tx,
+            abort_tx,
+        }
+    }
+
+    /// The process-wide controls the router checks.
+    pub fn global() -> &'static Self {
+        &CONTROLS
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, Option<Maintenance>> {
+        self.maintenance
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Turn maintenance mode on, or replace the notice if it already is.
+    /// Replacing the notice re-sends it to every sender.
+    pub fn enable(&self, notice: Option<String>) {
+        *self.state() = Some(Maintenance {
+            notice: notice.filter(|notice| !notice.trim().is_empty()),
+            notified: HashSet::new(),
+        });
+    }
+
+    /// Turn maintenance mode off. Returns whether it was on.
+    pub fn disable(&self) -> bool {
+        self.state().take().is_some()
+    }
+
+    /// Whether maintenance mode is on, and its custom notice.
+    pub fn status(&self) -> Option<Option<String>> {
+        self.state()
+            .as_ref()
+            .map(|maintenance| maintenance.notice.clone())
+    }
+
+    /// Whether `message` may be routed to an agent.
+    ///
+    /// System messages, deletions, `/admin` commands, and the abort-all
+    /// confirmation buttons always pass.
+    pub fn check(&self, message: &InboundMessage) -> Decision {
+        let mut state = self.state();
+        let Some(maintenance) = state.as_mut() else {
+            return Decision::Allow;
+        };
+        if message.source == "system"
+            || matches!(message.content, crate::MessageContent::Deleted { .. })
+            || is_admin_command(message)
+        {
+            return Decision::Allow;
+        }
+        Decision::Paused {
+            notify: maintenance
+                .notified
+                .insert((message.conversation_id.clone(), message.sender_id.clone())),
+            notice: maintenance.notice.clone(),
+        }
+    }
+
+    /// Send `text` to every active channel. Returns false when the router
+    /// isn't listening.
+    pub fn broadcast(&self, text: String) -> bool {
+        self.broadcast_tx.send(text).is_ok()
+    }
+
+    /// Receive broadcasts sent after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.broadcast_tx.subscribe()
+    }
+
+    /// Abort every busy worker session in every channel, or only those on
+    /// `backend`. Returns false when the router isn't listening.
+    pub fn abort_all(&self, backend: Option<String>) -> bool {
+        self.abort_tx.send(backend).is_ok()
+    }
+
+    /// Receive abort-all requests sent after this call.
+    pub fn subscribe_aborts(&self) -> broadcast::Receiver<Option<String>> {
+        self.abort_tx.subscribe()
+    }
+}
+
+impl Default for AdminControls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_admin_command(message: &InboundMessage) -> bool {
+    let text = match &message.content {
+        crate::MessageContent::Interaction { action_id, .. } => {
+            return action_id.starts_with(crate::agent::channel_abort::ACTION_PREFIX);
+        }
+        crate::MessageContent::Text(text) => text.as_str(),
+        crate::MessageContent::Media {
+            text: Some(text), ..
+        } => text.as_str(),
+        _ => return false,
+    };
+    text.trim()
+        .strip_prefix("/admin")
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+/// The reply sent to a paused conversation, in `locale`.
+pub fn paused_message(notice: Option<String>, locale: &str) -> String {
+    notice.unwrap_or_else(|| crate::prompts::messages::get(locale, "maintenance.notice"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(conversation_id: &str, text: &str) -> InboundMessage {
+        InboundMessage {
+            source: "discord".to_string(),
+            adapter: Some("discord".to_string()),
+            conversation_id: conversation_id.to_string(),
+            sender_id: "alice".to_string(),
+            content: crate::MessageContent::Text(text.to_string()),
+            ..InboundMessage::empty()
+        }
+    }
+
+    #[test]
+    fn notifies_each_sender_once_per_conversation_and_spell() {
+        let controls = AdminControls::new();
+        let prompt = message("discord:1:2", "run the tests");
+        assert_eq!(controls.check(&prompt), Decision::Allow);
+
+        controls.enable(Some("upgrading opencode, back in 10m".to_string()));
+        let paused = |notify| Decision::Paused {
+            notify,
+            notice: Some("upgrading opencode, back in 10m".to_string()),
+        };
+        assert_eq!(controls.check(&prompt), paused(true));
+        assert_eq!(controls.check(&prompt), paused(false));
+        assert_eq!(
+            controls.check(&message("discord:1:3", "hello")),
+            paused(true)
+        );
+        let mut other_sender = message("discord:1:2", "me too");
+        other_sender.sender_id = "bob".to_string();
+        assert_eq!(controls.check(&other_sender), paused(true));
+
+        assert!(controls.disable());
+        assert!(!controls.disable());
+        assert_eq!(controls.check(&prompt), Decision::Allow);
+
+        controls.enable(None);
+        assert_eq!(
+            controls.check(&prompt),
+            Decision::Paused {
+                notify: true,
+                notice: None,
+            }
+        );
+    }
+
+    #[test]
+    fn admin_commands_and_system_messages_pass() {
+        let controls = AdminControls::new();
+        controls.enable(None);
+
+        assert_eq!(
+            controls.check(&message("discord:1:2", "/admin maintenance off")),
+            Decision::Allow
+        );
+        assert_eq!(
+            controls.check(&message("discord:1:2", " /admin")),
+            Decision::Allow
+        );
+        assert!(matches!(
+            controls.check(&message("discord:1:2", "/administer")),
+            Decision::Paused { .. }
+        ));
+
+        let mut confirm = message("discord:1:2", "");
+        confirm.content = crate::MessageContent::Interaction {
+            action_id: "admin_abort_all:confirm".to_string(),
+            block_id: None,
+            values: Vec::new(),
+            label: None,
+            message_ts: None,
+        };
+        assert_eq!(controls.check(&confirm), Decision::Allow);
+
+        let mut retrigger = message("discord:1:2", "worker finished");
+        retrigger.source = "system".to_string();
+        assert_eq!(controls.check(&retrigger), Decision::Allow);
+    }
+}
@@ -279,6 +279,8 @@ impl McpConnection {
                         retry_in_secs = delay_secs,
                         "mcp connection failed, retrying"
                     );
+                    crate::notifications::ops::OpsAlerts::global()
+                        .reconnect(&format!("mcp server {}", self.name));
                     tokio::time::sleep(std::time::Duration::from_secs(delay_secs)).await;
                     delay_secs = (delay_secs * 2).min(MAX_DELAY_SECS);
                 }
@@ -1,14 +1,26 @@
 //! Messaging adapters (Discord, Slack, Telegram, Twitch, Signal, Email, Webhook, Portal, Mattermost).
 
+pub mod backpressure;
+pub mod code_language;
+pub mod collapse;
+pub mod content_filter;
+pub mod continuation;
 pub mod discord;
+pub mod edit_pacing;
 pub mod email;
+pub mod irc;
+pub mod journal;
+pub mod links;
 pub mod manager;
 pub mod mattermost;
+pub mod overflow;
 pub mod portal;
+pub mod quota;
 pub mod signal;
 pub mod slack;
 pub mod target;
 pub mod telegram;
+pub mod timestamp;
 pub mod traits;
 pub mod twitch;
 pub mod webhook;
@@ -0,0 +1,289 @@
+//! Bounded pipeline stages with per-item overflow policies.
+//!
+//! A channel's outbound responses pass through a [`Stage`] on their way to the
+//! platform adapter. The stage holds a fixed number of items. When the
+//! adapter falls behind, each item's [`Overflow`] policy decides what
+//! happens:
+//!
+//! - Streaming edits carry the full text so far. A newer edit replaces one
+//!   that is still queued, so intermediate edits are dropped but the latest
+//!   always goes out.
+//! - Typing indicators are dropped while the stage is full.
+//! - Everything else, including tool status and stream ends, waits for room.
+//!   That stalls the producer, so memory stays bounded without losing them.
+//!
+//! Every drop is counted in `spacebot_pipeline_dropped_total`.
+
+use crate::{OutboundResponse, RoutedResponse, StatusUpdate};
+
+use tokio::sync::{Notify, mpsc};
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Items buffered between a channel and its messaging adapter.
+pub const OUTBOUND_STAGE_CAPACITY: usize = 64;
+
+/// What a full stage does with an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for room. Never dropped.
+    Keep,
+    /// Replace the newest queued item of the same stream if it is also
+    /// `Supersede`. Otherwise wait for room like `Keep`.
+    Supersede,
+    /// Dropped when the stage is full.
+    DropWhenFull,
+}
+
+/// An item that can pass through a [`Stage`].
+pub trait StageItem {
+    /// How a full stage treats this item.
+    fn overflow(&self) -> Overflow;
+
+    /// Whether `other` belongs to the same stream as this item. Only items
+    /// of the same stream supersede each other.
+    fn same_stream(&self, other: &Self) -> bool;
+}
+
+/// Why an item was dropped, for the drop metric.
+#[derive(Debug, Clone, Copy)]
+enum DropReason {
+    Superseded,
+    Full,
+}
+
+impl DropReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            DropReason::Superseded => "superseded",
+            DropReason::Full => "full",
+        }
+    }
+}
+
+/// A bounded queue between two tasks.
+pub struct Stage<T> {
+    name: &'static str,
+    capacity: usize,
+    queue: Mutex<VecDeque<T>>,
+    item_ready: Notify,
+    space_ready: Notify,
+    closed: AtomicBool,
+}
+
+impl<T: StageItem> Stage<T> {
+    /// A stage named `name` in metrics, holding up to `capacity` items.
+    pub fn new(name: &'static str, capacity: usize) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            capacity: capacity.max(1),
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            item_ready: Notify::new(),
+            space_ready: Notify::new(),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    /// Queue `item`, applying its overflow policy. Waits only for `Keep`
+    /// items, and `Supersede` items with nothing to replace, while the
+    /// stage is full.
+    pub async fn push(&self, item: T) {
+        let overflow = item.overflow();
+        let mut item = Some(item);
+        loop {
+            {
+                let mut queue = self.lock();
+                let pending = item.take().expect("item is only taken once");
+
+                if overflow == Overflow::Supersede
+                    && let Some(queued) = queue
+                        .iter_mut()
+                        .rev()
+                        .find(|queued| queued.same_stream(&pending))
+                    && queued.overflow() == Overflow::Supersede
+                {
+                    *queued = pending;
+                    self.record_drop(DropReason::Superseded);
+                    return;
+                }
+
+                if queue.len() < self.capacity {
+                    queue.push_back(pending);
+                    self.item_ready.notify_one();
+                    return;
+                }
+
+                if overflow == Overflow::DropWhenFull {
+                    self.record_drop(DropReason::Full);
+                    return;
+                }
+
+                item = Some(pending);
+            }
+            self.space_ready.notified().await;
+        }
+    }
+
+    /// Take the oldest item. Returns `None` once the stage is closed and
+    /// drained.
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            {
+                let mut queue = self.lock();
+                if let Some(item) = queue.pop_front() {
+                    self.space_ready.notify_one();
+                    return Some(item);
+                }
+                if self.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+            }
+            self.item_ready.notified().await;
+        }
+    }
+
+    /// Stop accepting input. Items already queued are still delivered.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.item_ready.notify_one();
+    }
+
+    /// Items currently queued.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<T>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn record_drop(&self, reason: DropReason) {
+        tracing::trace!(
+            stage = self.name,
+            reason = reason.as_str(),
+            "pipeline stage dropped an item"
+        );
+        #[cfg(feature = "metrics")]
+        crate::telemetry::Metrics::global()
+            .pipeline_dropped_total
+            .with_label_values(&[self.name, reason.as_str()])
+            .inc();
+    }
+}
+
+impl StageItem for RoutedResponse {
+    fn overflow(&self) -> Overflow {
+        match &self.response {
+            OutboundResponse::StreamChunk(_) => Overflow::Supersede,
+            OutboundResponse::Status(StatusUpdate::Thinking) => Overflow::DropWhenFull,
+            _ => Overflow::Keep,
+        }
+    }
+
+    fn same_stream(&self, other: &Self) -> bool {
+        self.target.conversation_id == other.target.conversation_id
+            && self.target.id == other.target.id
+    }
+}
+
+/// Move a channel's responses into a bounded outbound stage. The stage
+/// closes once every sender for `response_rx` is dropped.
+pub fn buffer_outbound(
+    mut response_rx: mpsc::Receiver<RoutedResponse>,
+) -> Arc<Stage<RoutedResponse>> {
+    let stage = Stage::new("outbound", OUTBOUND_STAGE_CAPACITY);
+    let input = stage.clone();
+    tokio::spawn(async move {
+        while let Some(routed) = response_rx.recv().await {
+            input.push(routed).await;
+        }
+        input.close();
+    });
+    stage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InboundMessage;
+
+    fn routed(message_id: &str, response: OutboundResponse) -> RoutedResponse {
+        let mut target = InboundMessage::empty();
+        target.id = message_id.to_string();
+        RoutedResponse { response, target }
+    }
+
+    fn text_of(routed: &RoutedResponse) -> &str {
+        match &routed.response {
+            OutboundResponse::StreamChunk(text) | OutboundResponse::Text(text) => text,
+            _ => "",
+        }
+    }
+
+    #[tokio::test]
+    async fn newer_stream_chunks_replace_queued_ones() {
+        let stage = Stage::new("test", 8);
+        stage.push(routed("a", OutboundResponse::StreamStart)).await;
+        for text in ["h", "he", "hel", "hello"] {
+            stage
+                .push(routed("a", OutboundResponse::StreamChunk(text.into())))
+                .await;
+        }
+        stage.push(routed("a", OutboundResponse::StreamEnd)).await;
+        stage
+            .push(routed("a", OutboundResponse::StreamChunk("late".into())))
+            .await;
+        stage.close();
+
+        let mut delivered = Vec::new();
+        while let Some(item) = stage.pop().await {
+            delivered.push(item);
+        }
+        assert_eq!(delivered.len(), 4);
+        assert!(matches!(
+            delivered[0].response,
+            OutboundResponse::StreamStart
+        ));
+        assert_eq!(text_of(&delivered[1]), "hello");
+        assert!(matches!(delivered[2].response, OutboundResponse::StreamEnd));
+        assert_eq!(text_of(&delivered[3]), "late");
+    }
+
+    #[tokio::test]
+    async fn full_stage_drops_typing_but_waits_for_everything_else() {
+        let stage = Stage::new("test", 1);
+        stage
+            .push(routed("a", OutboundResponse::Text("first".into())))
+            .await;
+        stage
+            .push(routed(
+                "a",
+                OutboundResponse::Status(StatusUpdate::Thinking),
+            ))
+            .await;
+        assert_eq!(stage.len(), 1);
+
+        let producer = {
+            let stage = stage.clone();
+            tokio::spawn(async move {
+                stage
+                    .push(routed("a", OutboundResponse::Text("second".into())))
+                    .await;
+            })
+        };
+        tokio::task::yield_now().await;
+        assert!(!producer.is_finished());
+
+        assert_eq!(text_of(&stage.pop().await.unwrap()), "first");
+        producer.await.unwrap();
+        assert_eq!(text_of(&stage.pop().await.unwrap()), "second");
+    }
+}
@@ -0,0 +1,363 @@
+//! Syntax-language inference for fenced code that doesn't name one.
+//!
+//! Platforms only highlight a code block whose opening fence names its
+//! language. Models often leave it off, and tool output is fenced without
+//! one. [`annotate_code_fences`] labels each closed, unlabelled fence in a
+//! reply with the language [`infer_language`] recognizes; fences it isn't
+//! sure about are left alone. Tool renderers that know the file a call
+//! touched use [`language_for_path`] instead.
+
+use crate::OutboundResponse;
+
+use std::borrow::Cow;
+
+/// Lines that must look like one language before a guess is made.
+const MIN_SIGNAL_LINES: usize = 2;
+
+/// Line prefixes that point to a language. Each matching line counts once
+/// per language.
+const LINE_SIGNALS: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            "fn ",
+            "pub fn ",
+            "async fn ",
+            "pub(crate) fn ",
+            "let mut ",
+            "use std::",
+            "use crate::",
+            "impl ",
+            "impl<",
+            "#[derive",
+            "pub struct ",
+            "pub enum ",
+            "mod ",
+        ],
+    ),
+    (
+        "python",
+        &[
+            "def ",
+            "async def ",
+            "class ",
+            "elif ",
+            "if __name__",
+            "print(",
+            "self.",
+            "@dataclass",
+        ],
+    ),
+    (
+        "go",
+        &[
+            "package ", "func ", "import (", "type ", "defer ", "go func",
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            "const ",
+            "function ",
+            "module.exports",
+            "console.log(",
+            "export default ",
+            "require(",
+            "async function ",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            "interface ",
+            "export interface ",
+            "export type ",
+            "export const ",
+            "enum ",
+        ],
+    ),
+    (
+        "java",
+        &[
+            "public class ",
+            "private ",
+            "public static ",
+            "import java.",
+            "@Override",
+        ],
+    ),
+    (
+        "c",
+        &["#include ", "#define ", "int main(", "typedef ", "struct "],
+    ),
+    (
+        "sql",
+        &[
+            "SELECT ",
+            "INSERT INTO ",
+            "CREATE TABLE ",
+            "UPDATE ",
+            "DELETE FROM ",
+            "ALTER TABLE ",
+            "WHERE ",
+            "FROM ",
+        ],
+    ),
+    (
+        "bash",
+        &[
+            "$ ", "echo ", "export ", "sudo ", "cd ", "fi", "done", "then", "set -e",
+        ],
+    ),
+];
+
+/// The highlighting language for a file, by its extension or name.
+pub fn language_for_path(path: &str) -> Option<&'static str> {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    match name {
+        "Dockerfile" => return Some("dockerfile"),
+        "Makefile" | "makefile" | "justfile" => return Some("makefile"),
+        _ => {}
+    }
+    let (_, extension) = name.rsplit_once('.')?;
+    Some(match extension.to_ascii_lowercase().as_str() {
+        "rs" => "rust",
+        "py" | "pyi" => "python",
+        "go" => "go",
+        "js" | "mjs" | "cjs" | "jsx" => "javascript",
+        "ts" | "mts" | "cts" | "tsx" => "typescript",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "rb" => "ruby",
+        "php" => "php",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "sh" | "bash" | "zsh" => "bash",
+        "sql" => "sql",
+        "json" => "json",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "xml" => "xml",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "md" | "mdx" => "markdown",
+        "lua" => "lua",
+        "diff" | "patch" => "diff",
+        _ => return None,
+    })
+}
+
+/// Guess the language of `code`, or `None` when nothing points clearly to
+/// one.
+pub fn infer_language(code: &str) -> Option<&'static str> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    if let Some(interpreter) = code.lines().next().and_then(|line| line.strip_prefix("#!")) {
+        return shebang_language(interpreter);
+    }
+    if (code.starts_with('{') || code.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(code).is_ok()
+    {
+        return Some("json");
+    }
+    if code.starts_with("<?xml") {
+        return Some("xml");
+    }
+    if code.starts_with("<!DOCTYPE html") || code.starts_with("<html") {
+        return Some("html");
+    }
+    if code.starts_with("diff --git ")
+        || (code.starts_with("--- ")
+            && code
+                .lines()
+                .nth(1)
+                .is_some_and(|line| line.starts_with("+++ ")))
+    {
+        return Some("diff");
+    }
+
+    let lines: Vec<&str> = code.lines().map(without_line_number).collect();
+    let mut scores: Vec<(&'static str, usize)> = LINE_SIGNALS
+        .iter()
+        .map(|(language, prefixes)| {
+            let matching = lines
+                .iter()
+                .filter(|line| {
+                    let line = line.trim_start();
+                    prefixes
+                        .iter()
+                        .any(|prefix| line.starts_with(prefix) || line == prefix.trim_end())
+                })
+                .count();
+            (*language, matching)
+        })
+        .collect();
+    // TypeScript is JavaScript with types: once anything points to it, the
+    // JavaScript lines count for it too.
+    let javascript = scores
+        .iter()
+        .find(|(language, _)| *language == "javascript")
+        .map_or(0, |(_, matching)| *matching);
+    if let Some((_, typescript)) = scores
+        .iter_mut()
+        .find(|(language, _)| *language == "typescript")
+        && *typescript > 0
+    {
+        *typescript += javascript;
+    }
+    scores.sort_by(|left, right| right.1.cmp(&left.1));
+    match scores[..] {
+        [(language, best), (_, runner_up), ..] if best >= MIN_SIGNAL_LINES && best > runner_up => {
+            Some(language)
+        }
+        _ => None,
+    }
+}
+
+fn shebang_language(interpreter: &str) -> Option<&'static str> {
+    let program = interpreter
+        .split_whitespace()
+        .find(|word| !word.ends_with("/env") && !word.starts_with('-'))?;
+    let program = program.rsplit('/').next().unwrap_or(program);
+    match program {
+        "bash" | "sh" | "zsh" => Some("bash"),
+        program if program.starts_with("python") => Some("python"),
+        "node" => Some("javascript"),
+        "ruby" => Some("ruby"),
+        _ => None,
+    }
+}
+
+/// A line with the `00042| ` line number file viewers put in front of it
+/// removed.
+fn without_line_number(line: &str) -> &str {
+    match line.split_once("| ") {
+        Some((number, rest))
+            if !number.trim().is_empty()
+                && number.trim().bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            rest
+        }
+        _ => line,
+    }
+}
+
+/// Label each closed code fence in `text` that names no language with the
+/// one its contents point to. A fence still open at the end, as in a
+/// half-streamed reply, is left for later.
+pub fn annotate_code_fences(text: &str) -> Cow<'_, str> {
+    if !text.contains("```") {
+        return Cow::Borrowed(text);
+    }
+    let mut lines: Vec<Cow<'_, str>> = text.split('\n').map(Cow::Borrowed).collect();
+    let mut labels = Vec::new();
+    // Opening line index, its backtick count, and whether it is unlabelled.
+    let mut open: Option<(usize, usize, bool)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.trim();
+        let backticks = line.bytes().take_while(|byte| *byte == b'`').count();
+        if backticks < 3 {
+            continue;
+        }
+        match open {
+            None => open = Some((index, backticks, line[backticks..].trim().is_empty())),
+            Some((start, opening, unlabelled))
+                if backticks >= opening && backticks == line.len() =>
+            {
+                if unlabelled {
+                    let contents = lines[start + 1..index].join("\n");
+                    if let Some(language) = infer_language(&contents) {
+                        labels.push((start, language));
+                    }
+                }
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+    if labels.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    for (index, language) in labels {
+        lines[index] = Cow::Owned(format!("{}{language}", lines[index].trim_end()));
+    }
+    Cow::Owned(lines.join("\n"))
+}
+
+/// Label unlabelled code fences in a response's text.
+pub fn annotate_response(response: OutboundResponse) -> OutboundResponse {
+    let annotate = |text: String| match annotate_code_fences(&text) {
+        Cow::Borrowed(_) => text,
+        Cow::Owned(annotated) => annotated,
+    };
+    match response {
+        OutboundResponse::Text(text) => OutboundResponse::Text(annotate(text)),
+        OutboundResponse::StreamChunk(text) => OutboundResponse::StreamChunk(annotate(text)),
+        OutboundResponse::ThreadReply { thread_name, text } => OutboundResponse::ThreadReply {
+            thread_name,
+            text: annotate(text),
+        },
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_map_to_languages() {
+        assert_eq!(language_for_path("src/messaging/manager.rs"), Some("rust"));
+        assert_eq!(language_for_path("web/App.TSX"), Some("typescript"));
+        assert_eq!(language_for_path("deploy/Dockerfile"), Some("dockerfile"));
+        assert_eq!(language_for_path("README"), None);
+        assert_eq!(language_for_path("notes.unknown"), None);
+    }
+
+    #[test]
+    fn clear_code_is_recognized_and_prose_is_not() {
+        assert_eq!(
+            infer_language("use std::fmt;\n\npub fn main() {\n    let mut x = 1;\n}"),
+            Some("rust")
+        );
+        assert_eq!(
+            infer_language("def greet(name):\n    print(name)\n\nclass Greeter:\n    pass"),
+            Some("python")
+        );
+        assert_eq!(
+            infer_language(
+                "export interface User {\n  name: string;\n}\nconst user: User = load();"
+            ),
+            Some("typescript")
+        );
+        assert_eq!(
+            infer_language("#!/usr/bin/env python3\nx = 1"),
+            Some("python")
+        );
+        assert_eq!(infer_language("{\"ok\": true}"), Some("json"));
+        assert_eq!(infer_language("--- a/x\n+++ b/x\n-old\n+new"), Some("diff"));
+        assert_eq!(
+            infer_language("00001| use std::io;\n00002| fn read() {}"),
+            Some("rust")
+        );
+        assert_eq!(infer_language("running 3 tests\n1 failed"), None);
+        assert_eq!(infer_language("fn main() {}"), None);
+    }
+
+    #[test]
+    fn only_closed_unlabelled_fences_are_labelled() {
+        let text = "Try this:\n```\nfn main() {\n    let mut x = 1;\n}\n```\n\n```toml\n[a]\n```\n```\nplain words\n```";
+        assert_eq!(
+            annotate_code_fences(text),
+            "Try this:\n```rust\nfn main() {\n    let mut x = 1;\n}\n```\n\n```toml\n[a]\n```\n```\nplain words\n```"
+        );
+
+        let streaming = "```\nfn main() {\n    let mut x = 1;";
+        assert!(matches!(annotate_code_fences(streaming), Cow::Borrowed(_)));
+    }
+}
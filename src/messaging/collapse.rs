@@ -0,0 +1,86 @@
+//! Detail a reader opens on demand, in each platform's own collapsed form.
+//!
+//! Channels with `collapse` on post worker reasoning and long tool output
+//! this way: a one-line summary that is always shown, followed by the detail
+//! tucked away. Discord hides the detail behind a spoiler, and Slack puts it
+//! in a context block, in small muted text under the summary. Platforms
+//! without either show the detail as is.
+
+use crate::OutboundResponse;
+use crate::tools::truncate_chars_ellipsis;
+
+/// Maximum characters of collapsed detail. Keeps a collapsed message within
+/// one Discord message, so a continuation split never cuts a spoiler open.
+pub const MAX_DETAIL_CHARS: usize = 1_500;
+
+/// Slack's limit on the text of one context block element.
+const SLACK_CONTEXT_CHARS: usize = 3_000;
+
+/// `summary`, then `detail` collapsed the way `platform` supports. Detail
+/// longer than [`MAX_DETAIL_CHARS`] is cut and marked.
+pub fn collapsed(platform: &str, summary: &str, detail: &str) -> OutboundResponse {
+    let detail = truncate_chars_ellipsis(detail.trim(), MAX_DETAIL_CHARS);
+    match platform {
+        "discord" => {
+            // A `||` inside the detail would close the spoiler early.
+            let detail = detail.replace("||", "|\u{200B}|");
+            OutboundResponse::Text(format!("{summary}\n||{detail}||"))
+        }
+        "slack" => OutboundResponse::RichMessage {
+            text: summary.to_string(),
+            blocks: vec![
+                serde_json::json!({
+                    "type": "section",
+                    "text": {"type": "mrkdwn", "text": summary},
+                }),
+                serde_json::json!({
+                    "type": "context",
+                    "elements": [{
+                        "type": "mrkdwn",
+                        "text": truncate_chars_ellipsis(&detail, SLACK_CONTEXT_CHARS),
+                    }],
+                }),
+            ],
+            cards: Vec::new(),
+            interactive_elements: Vec::new(),
+            poll: None,
+        },
+        _ => OutboundResponse::Text(format!("{summary}\n{detail}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_platform_collapses_its_own_way() {
+        let OutboundResponse::Text(discord) = collapsed("discord", "🔧 `bash`", "a || b") else {
+            panic!("discord collapses into text");
+        };
+        assert_eq!(discord, "🔧 `bash`\n||a |\u{200B}| b||");
+
+        let OutboundResponse::RichMessage { text, blocks, .. } =
+            collapsed("slack", "🔧 `bash`", "output")
+        else {
+            panic!("slack collapses into blocks");
+        };
+        assert_eq!(text, "🔧 `bash`");
+        assert_eq!(blocks[1]["type"], "context");
+        assert_eq!(blocks[1]["elements"][0]["text"], "output");
+
+        let OutboundResponse::Text(telegram) = collapsed("telegram", "summary", "detail") else {
+            panic!("other platforms get plain text");
+        };
+        assert_eq!(telegram, "summary\ndetail");
+    }
+
+    #[test]
+    fn long_detail_is_cut() {
+        let OutboundResponse::Text(text) = collapsed("discord", "s", &"x".repeat(5_000)) else {
+            panic!("discord collapses into text");
+        };
+        assert!(text.chars().count() < 2_000);
+        assert!(text.ends_with("…||"));
+    }
+}
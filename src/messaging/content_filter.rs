@@ -0,0 +1,511 @@
+//! Per-tenant content filters on outgoing messages.
+//!
+//! A tenant can hold its agent to a workspace content policy with
+//! `[[tenants.content_filters]]` rules. Each rule matches by regex or by
+//! whole word, and rules are checked in order against every message the
+//! agent posts: `mask` replaces the matches with `***` and keeps going,
+//! `block` withholds the message, and `flag` lets it through. Blocked and
+//! flagged messages are written to the agent's `quarantined_messages` table
+//! for an admin to review at `/api/messaging/quarantine`.
+//!
+//! Filters run in the outbound router after script hooks and before
+//! `MessagingManager` redacts secrets, so they see the final assistant text.
+
+use crate::config::{ContentFilterAction, ContentFilterRule, TenantConfig};
+use crate::{InboundMessage, OutboundResponse};
+
+use regex::Regex;
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+/// Replacement for masked matches.
+const MASK: &str = "***";
+
+/// Posted in place of a blocked message.
+const WITHHELD_NOTICE: &str = "_this message was withheld by a content filter._";
+
+struct CompiledRule {
+    name: String,
+    action: ContentFilterAction,
+    matchers: Vec<Regex>,
+}
+
+impl CompiledRule {
+    fn is_match(&self, text: &str) -> bool {
+        self.matchers.iter().any(|matcher| matcher.is_match(text))
+    }
+
+    fn mask(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for matcher in &self.matchers {
+            if matcher.is_match(&result) {
+                result = matcher.replace_all(&result, MASK).into_owned();
+            }
+        }
+        result
+    }
+}
+
+/// A message that hit a `block` or `flag` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterHit {
+    pub rule: String,
+    pub action: ContentFilterAction,
+    /// The message text before any masking.
+    pub content: String,
+}
+
+/// A tenant's compiled filter chain.
+#[derive(Default)]
+pub struct ContentFilter {
+    rules: Vec<CompiledRule>,
+}
+
+/// The single regex a rule's `words` compile to: any listed word, whole
+/// and case-insensitive. `None` for an empty list.
+pub(crate) fn words_regex(words: &[String]) -> Option<Result<Regex, regex::Error>> {
+    if words.is_empty() {
+        return None;
+    }
+    let words: Vec<String> = words.iter().map(|word| regex::escape(word)).collect();
+    Some(Regex::new(&format!(r"(?i)\b(?:{})\b", words.join("|"))))
+}
+
+impl ContentFilter {
+    /// Compile `rules`. Patterns and word lists are validated during config
+    /// load, so any that fail here are skipped with a warning.
+    pub fn new(rules: &[ContentFilterRule]) -> Self {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let mut matchers: Vec<Regex> = rule
+                    .patterns
+                    .iter()
+                    .filter_map(|pattern| match Regex::new(pattern) {
+                        Ok(regex) => Some(regex),
+                        Err(error) => {
+                            tracing::warn!(
+                                %error,
+                                %pattern,
+                                rule = %rule.name,
+                                "skipping invalid content filter pattern"
+                            );
+                            None
+                        }
+                    })
+                    .collect();
+                match words_regex(&rule.words) {
+                    Some(Ok(regex)) => matchers.push(regex),
+                    Some(Err(error)) => {
+                        tracing::warn!(
+                            %error,
+                            rule = %rule.name,
+                            "skipping content filter word list"
+                        );
+                    }
+                    None => {}
+                }
+                CompiledRule {
+                    name: rule.name.clone(),
+                    action: rule.action,
+                    matchers,
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Run the chain over every user-visible text field of `response`,
+    /// masking in place. Returns the first `block` rule that matched, or
+    /// else the first `flag` rule. A blocked response is left as it was
+    /// after masking; use [`withhold`] to replace it.
+    pub fn apply(&self, response: &mut OutboundResponse) -> Option<FilterHit> {
+        let mut fields = text_fields(response);
+        if fields.is_empty() {
+            return None;
+        }
+        let content = fields
+            .iter()
+            .map(|field| field.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut masked = false;
+        let mut hit = None;
+        for rule in &self.rules {
+            if !fields.iter().any(|field| rule.is_match(field)) {
+                continue;
+            }
+            match rule.action {
+                ContentFilterAction::Mask => {
+                    for field in &mut fields {
+                        **field = rule.mask(field);
+                    }
+                    masked = true;
+                }
+                ContentFilterAction::Block => {
+                    hit = Some(FilterHit {
+                        rule: rule.name.clone(),
+                        action: rule.action,
+                        content,
+                    });
+                    break;
+                }
+                ContentFilterAction::Flag if hit.is_none() => {
+                    hit = Some(FilterHit {
+                        rule: rule.name.clone(),
+                        action: rule.action,
+                        content: content.clone(),
+                    });
+                }
+                ContentFilterAction::Flag => {}
+            }
+        }
+
+        // Slack blocks aren't filtered field by field. Drop them so the
+        // masked text fallback is what gets posted.
+        if masked && let OutboundResponse::RichMessage { blocks, .. } = response {
+            blocks.clear();
+        }
+        hit
+    }
+}
+
+/// The text fields of `response` a filter checks.
+fn text_fields(response: &mut OutboundResponse) -> Vec<&mut String> {
+    match response {
+        OutboundResponse::Text(text)
+        | OutboundResponse::StreamChunk(text)
+        | OutboundResponse::ThreadReply { text, .. }
+        | OutboundResponse::Ephemeral { text, .. }
+        | OutboundResponse::ScheduledMessage { text, .. } => vec![text],
+        OutboundResponse::File {
+            caption: Some(caption),
+            ..
+        } => vec![caption],
+        OutboundResponse::RichMessage { text, cards, .. } => {
+            let mut fields = vec![text];
+            for card in cards {
+                if let Some(title) = &mut card.title {
+                    fields.push(title);
+                }
+                if let Some(description) = &mut card.description {
+                    fields.push(description);
+                }
+                for field in &mut card.fields {
+                    fields.push(&mut field.value);
+                }
+            }
+            fields
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Replace a blocked response with a short notice, keeping where it would
+/// have been posted.
+pub fn withhold(response: OutboundResponse) -> OutboundResponse {
+    let notice = WITHHELD_NOTICE.to_string();
+    match response {
+        OutboundResponse::StreamChunk(_) => OutboundResponse::StreamChunk(notice),
+        OutboundResponse::ThreadReply { thread_name, .. } => OutboundResponse::ThreadReply {
+            thread_name,
+            text: notice,
+        },
+        OutboundResponse::Ephemeral { user_id, .. } => OutboundResponse::Ephemeral {
+            text: notice,
+            user_id,
+        },
+        _ => OutboundResponse::Text(notice),
+    }
+}
+
+/// A message held in the quarantine table.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct QuarantinedMessage {
+    pub id: i64,
+    pub tenant_id: String,
+    pub conversation_id: String,
+    pub rule: String,
+    /// `block` or `flag`.
+    pub action: String,
+    pub content: String,
+    pub reviewed: bool,
+    pub created_at: String,
+}
+
+/// Reads and writes an agent's `quarantined_messages` table.
+#[derive(Debug, Clone)]
+pub struct Quarantine {
+    pool: SqlitePool,
+}
+
+impl Quarantine {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        tenant_id: &str,
+        conversation_id: &str,
+        hit: &FilterHit,
+    ) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO quarantined_messages (tenant_id, conversation_id, rule, action, content) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .bind(&hit.rule)
+        .bind(hit.action.as_str())
+        .bind(&hit.content)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(())
+    }
+
+    /// Newest messages first. Reviewed messages are left out unless
+    /// `include_reviewed` is set.
+    pub async fn list(
+        &self,
+        include_reviewed: bool,
+        limit: i64,
+    ) -> crate::error::Result<Vec<QuarantinedMessage>> {
+        let rows = sqlx::query(
+            "SELECT id, tenant_id, conversation_id, rule, action, content, reviewed, created_at \
+             FROM quarantined_messages \
+             WHERE ? OR reviewed = 0 \
+             ORDER BY id DESC \
+             LIMIT ?",
+        )
+        .bind(include_reviewed)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| QuarantinedMessage {
+                id: row.try_get("id").unwrap_or_default(),
+                tenant_id: row.try_get("tenant_id").unwrap_or_default(),
+                conversation_id: row.try_get("conversation_id").unwrap_or_default(),
+                rule: row.try_get("rule").unwrap_or_default(),
+                action: row.try_get("action").unwrap_or_default(),
+                content: row.try_get("content").unwrap_or_default(),
+                reviewed: row.try_get("reviewed").unwrap_or_default(),
+                created_at: row.try_get("created_at").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Mark a message reviewed. Returns false if no message has `id`.
+    pub async fn mark_reviewed(&self, id: i64) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE quarantined_messages SET reviewed = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// The content filter for one agent's outbound messages.
+pub struct OutboundFilter {
+    tenant_id: String,
+    filter: ContentFilter,
+    quarantine: Quarantine,
+}
+
+impl OutboundFilter {
+    /// The filter for the tenant served by `agent_id`, or `None` when the
+    /// agent serves no tenant or the tenant has no filters.
+    pub fn for_agent(tenants: &[TenantConfig], agent_id: &str, pool: SqlitePool) -> Option<Self> {
+        let tenant = tenants.iter().find(|tenant| tenant.agent_id == agent_id)?;
+        let filter = ContentFilter::new(&tenant.content_filters);
+        if filter.is_empty() {
+            return None;
+        }
+        Some(Self {
+            tenant_id: tenant.id.clone(),
+            filter,
+            quarantine: Quarantine::new(pool),
+        })
+    }
+
+    /// Filter a response bound for `target`, quarantining it on a hit.
+    pub async fn apply(
+        &self,
+        target: &InboundMessage,
+        mut response: OutboundResponse,
+    ) -> OutboundResponse {
+        let Some(hit) = self.filter.apply(&mut response) else {
+            return response;
+        };
+        tracing::info!(
+            tenant_id = %self.tenant_id,
+            conversation_id = %target.conversation_id,
+            rule = %hit.rule,
+            action = hit.action.as_str(),
+            "outbound message caught by content filter"
+        );
+        if let Err(error) = self
+            .quarantine
+            .record(&self.tenant_id, &target.conversation_id, &hit)
+            .await
+        {
+            tracing::warn!(%error, rule = %hit.rule, "failed to quarantine filtered message");
+        }
+        match hit.action {
+            ContentFilterAction::Block => withhold(response),
+            _ => response,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        name: &str,
+        patterns: &[&str],
+        words: &[&str],
+        action: ContentFilterAction,
+    ) -> ContentFilterRule {
+        ContentFilterRule {
+            name: name.to_string(),
+            patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+            words: words.iter().map(|word| word.to_string()).collect(),
+            action,
+        }
+    }
+
+    fn text(response: &OutboundResponse) -> &str {
+        match response {
+            OutboundResponse::Text(text) => text,
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn masks_whole_words_case_insensitively() {
+        let filter = ContentFilter::new(&[rule(
+            "codename",
+            &[],
+            &["falcon"],
+            ContentFilterAction::Mask,
+        )]);
+        let mut response = OutboundResponse::Text("Falcon ships friday, not falconry".into());
+
+        assert_eq!(filter.apply(&mut response), None);
+        assert_eq!(text(&response), "*** ships friday, not falconry");
+    }
+
+    #[test]
+    fn oversized_word_lists_are_an_error_not_a_panic() {
+        let words: Vec<String> = (0..40_000u32)
+            .map(|index| {
+                (0..24)
+                    .map(|position| char::from(b'a' + ((index >> position) % 26) as u8))
+                    .collect()
+            })
+            .collect();
+        assert!(matches!(words_regex(&words), Some(Err(_))));
+        assert!(words_regex(&[]).is_none());
+
+        let rule = ContentFilterRule {
+            name: "huge".into(),
+            patterns: Vec::new(),
+            words,
+            action: ContentFilterAction::Mask,
+        };
+        let filter = ContentFilter::new(&[rule]);
+        let mut response = OutboundResponse::Text("nothing to mask".into());
+        assert_eq!(filter.apply(&mut response), None);
+    }
+
+    #[test]
+    fn block_wins_over_an_earlier_flag() {
+        let filter = ContentFilter::new(&[
+            rule("pricing", &[r"\$\d+"], &[], ContentFilterAction::Flag),
+            rule("legal", &[], &["lawsuit"], ContentFilterAction::Block),
+        ]);
+        let mut response = OutboundResponse::Text("the $500 lawsuit".into());
+
+        let hit = filter.apply(&mut response).expect("blocked");
+        assert_eq!(hit.rule, "legal");
+        assert_eq!(hit.action, ContentFilterAction::Block);
+        assert_eq!(hit.content, "the $500 lawsuit");
+        assert_eq!(text(&withhold(response)), WITHHELD_NOTICE);
+    }
+
+    #[test]
+    fn flags_keep_the_masked_text() {
+        let filter = ContentFilter::new(&[
+            rule("codename", &[], &["falcon"], ContentFilterAction::Mask),
+            rule("pricing", &[r"\$\d+"], &[], ContentFilterAction::Flag),
+        ]);
+        let mut response = OutboundResponse::ThreadReply {
+            thread_name: "launch".into(),
+            text: "falcon costs $500".into(),
+        };
+
+        let hit = filter.apply(&mut response).expect("flagged");
+        assert_eq!(hit.action, ContentFilterAction::Flag);
+        assert_eq!(hit.content, "falcon costs $500");
+        assert!(matches!(
+            response,
+            OutboundResponse::ThreadReply { ref text, .. } if text == "*** costs $500"
+        ));
+
+        let mut reaction = OutboundResponse::Reaction("falcon".into());
+        assert_eq!(filter.apply(&mut reaction), None);
+    }
+
+    #[tokio::test]
+    async fn quarantined_messages_can_be_reviewed() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        let quarantine = Quarantine::new(pool);
+        let hit = FilterHit {
+            rule: "legal".into(),
+            action: ContentFilterAction::Block,
+            content: "the lawsuit".into(),
+        };
+        quarantine
+            .record("acme", "discord:1", &hit)
+            .await
+            .expect("record");
+
+        let pending = quarantine.list(false, 10).await.expect("list");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].action, "block");
+        assert_eq!(pending[0].content, "the lawsuit");
+
+        assert!(
+            quarantine
+                .mark_reviewed(pending[0].id)
+                .await
+                .expect("review")
+        );
+        assert!(!quarantine.mark_reviewed(999).await.expect("review"));
+        assert!(quarantine.list(false, 10).await.expect("list").is_empty());
+        assert_eq!(quarantine.list(true, 10).await.expect("list").len(), 1);
+    }
+}
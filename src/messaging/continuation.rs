@@ -0,0 +1,257 @@
+//! Continuation of replies still longer than one platform message after the
+//! overflow policy ran.
+//!
+//! [`split_into_parts`] cuts such a reply at paragraph, line, or word breaks
+//! and labels each part `(part 2/3)`. A code block cut between parts is
+//! closed at the end of one part and reopened, with its language, at the
+//! start of the next. A trailing tool timeline (the `🔧` lines the channel
+//! writes for worker tool calls) is kept on the first part only, next to the
+//! start of the reply it belongs to.
+//!
+//! Streamed replies grow past the limit while they are being edited.
+//! [`StreamRegions`] tracks which platform message shows which part, so an
+//! adapter edits only the parts that changed and posts a follow-up message
+//! for each new one.
+
+/// Lines starting with this are tool timeline lines.
+pub const TIMELINE_PREFIX: &str = "🔧";
+
+/// Room kept in every part for its label and a closing code fence.
+const PART_RESERVE: usize = 24;
+
+/// Longest code fence language carried over into the next part.
+const MAX_FENCE_LANGUAGE_CHARS: usize = 20;
+
+/// Split `text` into labelled parts of at most `limit` characters. Text that
+/// fits is returned as is, unlabelled.
+pub fn split_into_parts(text: &str, limit: usize) -> Vec<String> {
+    if text.chars().count() <= limit {
+        return vec![text.to_string()];
+    }
+    let budget = limit.saturating_sub(PART_RESERVE).max(1);
+    let (body, timeline) = match split_timeline(text) {
+        Some((body, timeline)) if timeline.chars().count() + 2 <= budget / 2 => {
+            (body, Some(timeline))
+        }
+        _ => (text, None),
+    };
+
+    let mut parts = Vec::new();
+    let mut part_budget = match timeline {
+        Some(timeline) => budget - timeline.chars().count() - 2,
+        None => budget,
+    };
+    let mut rest = body.to_string();
+    while !rest.is_empty() {
+        let (mut part, remainder) = cut(&rest, part_budget);
+        let mut next = remainder.to_string();
+        if let Some(language) = open_fence_language(&part) {
+            part.push_str("\n```");
+            if !next.trim().is_empty() {
+                next = format!("```{language}\n{next}");
+            }
+        }
+        parts.push(part);
+        rest = next;
+        part_budget = budget;
+    }
+
+    if let (Some(timeline), Some(first)) = (timeline, parts.first_mut()) {
+        first.push_str("\n\n");
+        first.push_str(timeline);
+    }
+    let total = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| format!("{part}\n\n(part {}/{total})", index + 1))
+        .collect()
+}
+
+/// Cut up to `budget` characters off the front of `text`, preferring a
+/// paragraph break, then a line break, then a space in the second half.
+/// Returns the cut part and the rest, without the break between them.
+fn cut(text: &str, budget: usize) -> (String, &str) {
+    let end = match text.char_indices().nth(budget) {
+        Some((index, _)) => index,
+        None => return (text.trim_end().to_string(), ""),
+    };
+    let head = &text[..end];
+    let usable = |index: &usize| *index > 0 && *index >= end / 2;
+    let at = head
+        .rfind("\n\n")
+        .filter(usable)
+        .or_else(|| head.rfind('\n').filter(usable))
+        .or_else(|| head.rfind(' ').filter(usable))
+        .unwrap_or(end);
+    let rest = &text[at..];
+    let rest = rest
+        .strip_prefix("\n\n")
+        .or_else(|| rest.strip_prefix('\n'))
+        .or_else(|| rest.strip_prefix(' '))
+        .unwrap_or(rest);
+    (text[..at].trim_end().to_string(), rest)
+}
+
+/// The language of a code fence left open at the end of `text`, or `None`
+/// when every fence is closed. An unlabelled fence has an empty language.
+fn open_fence_language(text: &str) -> Option<String> {
+    let mut open = None;
+    for line in text.lines() {
+        let Some(info) = line.trim_start().strip_prefix("```") else {
+            continue;
+        };
+        open = match open {
+            Some(_) => None,
+            None => Some(info.trim().chars().take(MAX_FENCE_LANGUAGE_CHARS).collect()),
+        };
+    }
+    open
+}
+
+/// Split a trailing tool timeline paragraph off `text`.
+fn split_timeline(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_end();
+    let start = text.rfind("\n\n")?;
+    let (body, timeline) = (text[..start].trim_end(), text[start..].trim_start());
+    let is_timeline = timeline
+        .lines()
+        .all(|line| line.starts_with(TIMELINE_PREFIX));
+    (is_timeline && !body.is_empty()).then_some((body, timeline))
+}
+
+/// The platform messages one streamed reply spans, first to last, and the
+/// text each shows.
+#[derive(Debug, Clone)]
+pub struct StreamRegions<Id> {
+    regions: Vec<(Id, String)>,
+}
+
+/// One step toward showing a streamed reply's latest text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionUpdate {
+    /// Edit the message of region `index` to show `text`.
+    Edit { index: usize, text: String },
+    /// Post `text` as a new message after the last region.
+    Post { text: String },
+}
+
+impl<Id: PartialEq> StreamRegions<Id> {
+    /// A reply shown so far in one message, `first`.
+    pub fn new(first: Id) -> Self {
+        Self {
+            regions: vec![(first, String::new())],
+        }
+    }
+
+    /// The edits and posts that show `text` within `limit` characters per
+    /// message, in order. Regions past the text's last part are left as
+    /// they are.
+    pub fn updates(&self, text: &str, limit: usize) -> Vec<RegionUpdate> {
+        split_into_parts(text, limit)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, part)| match self.regions.get(index) {
+                Some((_, shown)) if *shown == part => None,
+                Some(_) => Some(RegionUpdate::Edit { index, text: part }),
+                None => Some(RegionUpdate::Post { text: part }),
+            })
+            .collect()
+    }
+
+    /// The message showing region `index`.
+    pub fn id(&self, index: usize) -> Option<&Id> {
+        self.regions.get(index).map(|(id, _)| id)
+    }
+
+    /// Whether `id` is one of this reply's messages.
+    pub fn contains(&self, id: &Id) -> bool {
+        self.regions.iter().any(|(region_id, _)| region_id == id)
+    }
+
+    /// Record that region `index` now shows `text`.
+    pub fn edited(&mut self, index: usize, text: String) {
+        if let Some((_, shown)) = self.regions.get_mut(index) {
+            *shown = text;
+        }
+    }
+
+    /// Record a new region posted as message `id`.
+    pub fn posted(&mut self, id: Id, text: String) {
+        self.regions.push((id, text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_replies_split_into_labelled_parts() {
+        let paragraph = "word ".repeat(60);
+        let text = vec![paragraph.trim_end(); 6].join("\n\n");
+        let parts = split_into_parts(&text, 500);
+
+        assert_eq!(parts.len(), 3);
+        for (index, part) in parts.iter().enumerate() {
+            assert!(part.chars().count() <= 500, "part {index} too long");
+            assert!(part.ends_with(&format!("(part {}/3)", index + 1)));
+        }
+        assert_eq!(split_into_parts("short", 500), vec!["short".to_string()]);
+    }
+
+    #[test]
+    fn code_blocks_reopen_in_the_next_part() {
+        let text = format!("Here:\n```rust\n{}```", "let x = 1;\n".repeat(80));
+        let parts = split_into_parts(&text, 500);
+
+        assert!(parts.len() > 1);
+        for part in &parts {
+            assert!(part.chars().count() <= 500);
+            assert_eq!(open_fence_language(part), None, "unclosed fence in {part}");
+        }
+        assert!(parts[1].starts_with("```rust\n"));
+    }
+
+    #[test]
+    fn tool_timeline_stays_on_the_first_part() {
+        let text = format!(
+            "{}\n\n🔧 `bash` ×3 · `edit`",
+            "A sentence that goes on. ".repeat(60).trim_end()
+        );
+        let parts = split_into_parts(&text, 500);
+
+        assert!(parts.len() > 1);
+        assert!(parts[0].contains("🔧 `bash` ×3 · `edit`\n\n(part 1/"));
+        assert!(parts[1..].iter().all(|part| !part.contains('🔧')));
+    }
+
+    #[test]
+    fn stream_regions_edit_changed_parts_and_post_new_ones() {
+        let mut regions = StreamRegions::new(1);
+        let short = "hello";
+        assert_eq!(
+            regions.updates(short, 500),
+            vec![RegionUpdate::Edit {
+                index: 0,
+                text: short.into()
+            }]
+        );
+        regions.edited(0, short.into());
+        assert!(regions.updates(short, 500).is_empty());
+
+        let long = "word ".repeat(150);
+        let updates = regions.updates(&long, 500);
+        assert!(matches!(updates[0], RegionUpdate::Edit { index: 0, .. }));
+        assert!(matches!(updates[1], RegionUpdate::Post { .. }));
+        for update in updates {
+            match update {
+                RegionUpdate::Edit { index, text } => regions.edited(index, text),
+                RegionUpdate::Post { text } => regions.posted(2, text),
+            }
+        }
+        assert!(regions.updates(&long, 500).is_empty());
+        assert!(regions.contains(&2));
+        assert_eq!(regions.id(1), Some(&2));
+    }
+}
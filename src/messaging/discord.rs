@@ -2,6 +2,9 @@
 
 use crate::config::DiscordPermissions;
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
+use crate::messaging::continuation::{RegionUpdate, StreamRegions};
+use crate::messaging::edit_pacing::{EditFeedback, EditPacer};
+use crate::messaging::journal;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
@@ -11,15 +14,22 @@ use async_trait::async_trait;
 use serenity::all::{
     ButtonStyle, ChannelId, ChannelType, Context, CreateActionRow, CreateAttachment, CreateButton,
     CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateInteractionResponse,
-    CreateInteractionResponseMessage, CreateMessage, CreatePoll, CreatePollAnswer,
-    CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, CreateThread, EditMessage,
-    EventHandler, GatewayIntents, GetMessages, Http, Interaction, Message, MessageId, ReactionType,
-    Ready, ShardManager, Timestamp, User, UserId,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage, CreatePoll,
+    CreatePollAnswer, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, CreateThread,
+    EditMessage, EditThread, EventHandler, GatewayIntents, GetMessages, GuildId, Http, Interaction,
+    Message, MessageId, ReactionType, Ready, ShardManager, Timestamp, User, UserId,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
 
+/// Discord's limit on thread names.
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+/// Starting gap between edits of messages in one channel. Discord allows
+/// five edits per five seconds per channel.
+const BASE_EDIT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Discord adapter state.
 pub struct DiscordAdapter {
     runtime_key: String,
@@ -27,11 +37,14 @@ pub struct DiscordAdapter {
     permissions: Arc<ArcSwap<DiscordPermissions>>,
     http: Arc<RwLock<Option<Arc<Http>>>>,
     bot_user_id: Arc<RwLock<Option<UserId>>>,
-    /// Maps InboundMessage.id to the Discord MessageId being edited during streaming.
-    active_messages: Arc<RwLock<HashMap<String, serenity::all::MessageId>>>,
+    /// Maps InboundMessage.id to the Discord messages being edited during
+    /// streaming: the placeholder, then any continuation parts.
+    active_messages: Arc<RwLock<HashMap<String, StreamRegions<MessageId>>>>,
     /// Typing handles per message. Typing stops when the handle is dropped.
     typing_tasks: Arc<RwLock<HashMap<String, serenity::http::Typing>>>,
     shard_manager: Arc<RwLock<Option<Arc<ShardManager>>>>,
+    /// Per-channel gap between streaming edits, widened while rate limited.
+    edit_pacer: Arc<EditPacer>,
 }
 
 impl DiscordAdapter {
@@ -49,6 +62,7 @@ impl DiscordAdapter {
             active_messages: Arc::new(RwLock::new(HashMap::new())),
             typing_tasks: Arc::new(RwLock::new(HashMap::new())),
             shard_manager: Arc::new(RwLock::new(None)),
+            edit_pacer: Arc::new(EditPacer::new(BASE_EDIT_INTERVAL)),
         }
     }
 
@@ -86,6 +100,83 @@ impl DiscordAdapter {
             .remove(&Self::channel_key(message));
     }
 
+    /// Show a streamed reply's latest text: edit the messages whose part
+    /// changed and post a follow-up message for each new part. A failed
+    /// edit or post is retried with the next chunk.
+    async fn show_stream_text(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message: &InboundMessage,
+        text: &str,
+    ) {
+        let Some(updates) = self
+            .active_messages
+            .read()
+            .await
+            .get(&message.id)
+            .map(|regions| regions.updates(text, 2000))
+        else {
+            return;
+        };
+
+        let pace_key = channel_id.to_string();
+        for update in updates {
+            match update {
+                RegionUpdate::Edit { index, text } => {
+                    let Some(message_id) = self
+                        .active_messages
+                        .read()
+                        .await
+                        .get(&message.id)
+                        .and_then(|regions| regions.id(index).copied())
+                    else {
+                        return;
+                    };
+                    self.edit_pacer.wait(&pace_key).await;
+                    let started = std::time::Instant::now();
+                    let builder = EditMessage::new().content(&text);
+                    let edited = channel_id.edit_message(http, message_id, builder).await;
+                    if let Some(feedback) = edit_feedback(&edited, started.elapsed()) {
+                        self.edit_pacer.record(&pace_key, feedback);
+                    }
+                    match edited {
+                        Ok(_) => {
+                            if let Some(regions) =
+                                self.active_messages.write().await.get_mut(&message.id)
+                            {
+                                regions.edited(index, text);
+                            }
+                        }
+                        Err(error) if is_unknown_message_error(&error) => {
+                            // Deleted out from under us; stop editing it.
+                            self.active_messages.write().await.remove(&message.id);
+                            journal::finish_stream(&self.runtime_key, message).await;
+                            return;
+                        }
+                        Err(error) => {
+                            tracing::warn!(%error, "failed to edit streaming message");
+                            return;
+                        }
+                    }
+                }
+                RegionUpdate::Post { text } => match channel_id.say(http, &text).await {
+                    Ok(posted) => {
+                        if let Some(regions) =
+                            self.active_messages.write().await.get_mut(&message.id)
+                        {
+                            regions.posted(posted.id, text);
+                        }
+                    }
+                    Err(error) => {
+                        tracing::warn!(%error, "failed to send streaming continuation");
+                        return;
+                    }
+                },
+            }
+        }
+    }
+
     fn extract_reply_message_id(message: &InboundMessage) -> Option<MessageId> {
         message
             .metadata
@@ -113,6 +204,7 @@ impl Messaging for DiscordAdapter {
             permissions: self.permissions.clone(),
             http_slot: self.http.clone(),
             bot_user_id_slot: self.bot_user_id.clone(),
+            active_messages: self.active_messages.clone(),
         };
 
         let intents = GatewayIntents::GUILD_MESSAGES
@@ -138,6 +230,12 @@ impl Messaging for DiscordAdapter {
         Ok(Box::pin(stream))
     }
 
+    /// Only replies to a component interaction can be ephemeral on Discord;
+    /// they go out as a follow-up on the interaction's token.
+    fn supports_ephemeral(&self, message: &InboundMessage) -> bool {
+        message.metadata.contains_key("discord_interaction_token")
+    }
+
     async fn respond(
         &self,
         message: &InboundMessage,
@@ -317,33 +415,41 @@ impl Messaging for DiscordAdapter {
             OutboundResponse::StreamStart => {
                 self.stop_typing(message).await;
 
-                let placeholder = channel_id
-                    .say(&*http, "\u{200B}")
+                let resumed = journal::resume_stream(&self.runtime_key, message)
                     .await
-                    .context("failed to send stream placeholder")?;
+                    .and_then(|id| id.parse::<u64>().ok())
+                    .filter(|id| *id != 0)
+                    .map(MessageId::new);
+                let message_id = match resumed {
+                    Some(message_id) => message_id,
+                    None => {
+                        let placeholder = channel_id
+                            .say(&*http, "\u{200B}")
+                            .await
+                            .context("failed to send stream placeholder")?;
+                        journal::begin_stream(
+                            &self.runtime_key,
+                            message,
+                            &placeholder.id.get().to_string(),
+                        )
+                        .await;
+                        placeholder.id
+                    }
+                };
 
                 self.active_messages
                     .write()
                     .await
-                    .insert(message.id.clone(), placeholder.id);
+                    .insert(message.id.clone(), StreamRegions::new(message_id));
             }
             OutboundResponse::StreamChunk(text) => {
-                let active = self.active_messages.read().await;
-                if let Some(&message_id) = active.get(&message.id) {
-                    let display_text = if text.len() > 2000 {
-                        let end = text.floor_char_boundary(1997);
-                        format!("{}...", &text[..end])
-                    } else {
-                        text
-                    };
-                    let builder = EditMessage::new().content(display_text);
-                    if let Err(error) = channel_id.edit_message(&*http, message_id, builder).await {
-                        tracing::warn!(%error, "failed to edit streaming message");
-                    }
-                }
+                let text = journal::update_stream(&self.runtime_key, message, text).await;
+                self.show_stream_text(&http, channel_id, message, &text)
+                    .await;
             }
             OutboundResponse::StreamEnd => {
                 self.active_messages.write().await.remove(&message.id);
+                journal::finish_stream(&self.runtime_key, message).await;
             }
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
@@ -351,9 +457,21 @@ impl Messaging for DiscordAdapter {
             // Slack-specific variants — graceful fallbacks for Discord
             OutboundResponse::RemoveReaction(_) => {} // no-op
             OutboundResponse::Ephemeral { text, .. } => {
-                // Discord has no ephemeral equivalent here; send as regular text
-                if let Ok(channel_id) = self.extract_channel_id(message) {
-                    let http = self.get_http().await?;
+                let http = self.get_http().await?;
+                if let Some(token) = message
+                    .metadata
+                    .get("discord_interaction_token")
+                    .and_then(|value| value.as_str())
+                {
+                    let followup = CreateInteractionResponseFollowup::new()
+                        .content(text)
+                        .ephemeral(true);
+                    http.create_followup_message(token, &followup, Vec::new())
+                        .await
+                        .context("failed to send ephemeral follow-up on discord")?;
+                } else if let Ok(channel_id) = self.extract_channel_id(message) {
+                    // Outside an interaction there is no ephemeral message;
+                    // send as regular text.
                     channel_id
                         .say(&*http, &text)
                         .await
@@ -561,6 +679,25 @@ impl Messaging for DiscordAdapter {
         Ok(history)
     }
 
+    async fn rename_thread(&self, message: &InboundMessage, name: &str) -> crate::Result<()> {
+        let is_thread = message
+            .metadata
+            .get("discord_is_thread")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+        if !is_thread {
+            return Ok(());
+        }
+        let http = self.get_http().await?;
+        let channel_id = self.extract_channel_id(message)?;
+        let name: String = name.chars().take(MAX_THREAD_NAME_CHARS).collect();
+        channel_id
+            .edit_thread(&*http, EditThread::new().name(name))
+            .await
+            .context("failed to rename discord thread")?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> crate::Result<()> {
         let http = self.get_http().await?;
         http.get_current_user()
@@ -589,6 +726,66 @@ struct Handler {
     permissions: Arc<ArcSwap<DiscordPermissions>>,
     http_slot: Arc<RwLock<Option<Arc<Http>>>>,
     bot_user_id_slot: Arc<RwLock<Option<UserId>>>,
+    active_messages: Arc<RwLock<HashMap<String, StreamRegions<MessageId>>>>,
+}
+
+impl Handler {
+    /// Stop streaming edits to a deleted bot message and forward the deletion
+    /// to the channel. Discord does not say who deleted a message, so the
+    /// sender is recorded as `unknown`. DM deletions carry no user ID and are
+    /// not forwarded.
+    async fn forward_deletion(
+        &self,
+        channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        guild_id: Option<GuildId>,
+    ) {
+        let bot_message = {
+            let mut active = self.active_messages.write().await;
+            let before = active.len();
+            active.retain(|_, regions| !regions.contains(&deleted_message_id));
+            active.len() != before
+        };
+
+        let Some(guild_id) = guild_id else {
+            return;
+        };
+
+        let conversation_id = apply_runtime_adapter_to_conversation_id(
+            &self.runtime_key,
+            format!("discord:{}:{}", guild_id, channel_id),
+        );
+
+        let mut metadata = HashMap::new();
+        metadata.insert("discord_channel_id".into(), channel_id.get().into());
+        metadata.insert("discord_guild_id".into(), guild_id.get().into());
+        // Deletions never reach the LLM; let them past require_mention so the
+        // owning channel always hears about them.
+        metadata.insert("discord_mentions_or_replies_to_bot".into(), true.into());
+
+        let inbound = InboundMessage {
+            id: format!("deleted:{deleted_message_id}"),
+            source: "discord".into(),
+            adapter: Some(self.runtime_key.clone()),
+            conversation_id,
+            sender_id: "unknown".into(),
+            agent_id: None,
+            content: MessageContent::Deleted {
+                message_id: deleted_message_id.to_string(),
+                bot_message,
+            },
+            timestamp: chrono::Utc::now(),
+            metadata,
+            formatted_author: None,
+        };
+
+        if let Err(error) = self.inbound_tx.send(inbound).await {
+            tracing::warn!(
+                %error,
+                "failed to send message deletion from Discord (receiver dropped)"
+            );
+        }
+    }
 }
 
 #[async_trait]
@@ -675,6 +872,30 @@ impl EventHandler for Handler {
         }
     }
 
+    async fn message_delete(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        guild_id: Option<GuildId>,
+    ) {
+        self.forward_deletion(channel_id, deleted_message_id, guild_id)
+            .await;
+    }
+
+    async fn message_delete_bulk(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        guild_id: Option<GuildId>,
+    ) {
+        for deleted_message_id in multiple_deleted_messages_ids {
+            self.forward_deletion(channel_id, deleted_message_id, guild_id)
+                .await;
+        }
+    }
+
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         let component = match interaction {
             Interaction::Component(c) => c,
@@ -739,6 +960,11 @@ impl EventHandler for Handler {
             "discord_message_id".into(),
             serde_json::Value::Number(component.message.id.get().into()),
         );
+        // Lets replies to the interaction be ephemeral follow-ups.
+        metadata.insert(
+            "discord_interaction_token".into(),
+            serde_json::Value::String(component.token.clone()),
+        );
         let discord_mentioned_bot = false;
         let discord_reply_to_bot = true;
         metadata.insert("discord_mentioned_bot".into(), discord_mentioned_bot.into());
@@ -811,6 +1037,38 @@ fn is_reply_to_bot(message: &Message, bot_user_id: Option<UserId>) -> bool {
 
 // -- Helper functions --
 
+/// Discord's "Unknown Message" API error, returned when editing a deleted message.
+fn is_unknown_message_error(error: &serenity::Error) -> bool {
+    const UNKNOWN_MESSAGE: isize = 10008;
+    matches!(
+        error,
+        serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(response))
+            if response.error.code == UNKNOWN_MESSAGE
+    )
+}
+
+/// How an edit went, for the edit pacer. Serenity waits out rate limits
+/// before sending, so most throttling shows up as latency; a 429 surfaces
+/// only when Discord rejects a request anyway. Other errors say nothing
+/// about the rate.
+fn edit_feedback<T>(
+    result: &Result<T, serenity::Error>,
+    latency: std::time::Duration,
+) -> Option<EditFeedback> {
+    match result {
+        Ok(_) => Some(EditFeedback::Sent {
+            latency,
+            remaining_ratio: None,
+        }),
+        Err(serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(response)))
+            if response.status_code.as_u16() == 429 =>
+        {
+            Some(EditFeedback::RateLimited { retry_after: None })
+        }
+        Err(_) => None,
+    }
+}
+
 fn build_conversation_id(runtime_key: &str, message: &Message) -> String {
     let base_conversation_id = match message.guild_id {
         Some(guild_id) => format!("discord:{}:{}", guild_id, message.channel_id),
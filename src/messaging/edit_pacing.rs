@@ -0,0 +1,221 @@
+//! Per-channel pacing for message edits.
+//!
+//! Streaming replies, status lines, and live checklists are all edits of one
+//! message. A newer edit supersedes one still queued in the outbound stage
+//! (see [`super::backpressure`]), so the gap an adapter leaves between edits
+//! decides how many reach the platform. Each channel starts at its
+//! platform's base interval:
+//!
+//! - A rate-limited edit doubles the channel's interval, to at least the
+//!   platform's retry-after, up to [`MAX_EDIT_INTERVAL`].
+//! - An edit that went through with quota nearly gone widens it by half.
+//! - An edit that went through with quota to spare narrows it by a quarter,
+//!   back down to the base.
+//!
+//! SDK-backed adapters queue requests internally while rate limited, so an
+//! edit slower than [`THROTTLED_EDIT_LATENCY`] counts as rate limited too.
+
+use super::quota::QUOTA_WARNING_RATIO;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The widest a channel's edit interval gets.
+pub const MAX_EDIT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// An edit that took this long was held back by a rate limit.
+pub const THROTTLED_EDIT_LATENCY: Duration = Duration::from_secs(2);
+
+/// Remaining-quota ratio above which the interval narrows again. Between
+/// [`QUOTA_WARNING_RATIO`] and this it holds.
+const HEADROOM_RATIO: f64 = 0.5;
+
+/// How an edit went.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditFeedback {
+    /// The platform took the edit. `remaining_ratio` is the fraction of
+    /// quota left, when the platform reports it.
+    Sent {
+        latency: Duration,
+        remaining_ratio: Option<f64>,
+    },
+    /// The platform rejected the edit for rate limiting.
+    RateLimited { retry_after: Option<Duration> },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChannelPace {
+    interval: Duration,
+    last_edit: tokio::time::Instant,
+}
+
+/// The interval after an edit with `feedback`, from `current`.
+pub fn next_interval(current: Duration, feedback: EditFeedback, base: Duration) -> Duration {
+    let next = match feedback {
+        EditFeedback::RateLimited { retry_after } => {
+            (current * 2).max(retry_after.unwrap_or_default())
+        }
+        EditFeedback::Sent { latency, .. } if latency >= THROTTLED_EDIT_LATENCY => {
+            (current * 2).max(latency)
+        }
+        EditFeedback::Sent {
+            remaining_ratio: Some(ratio),
+            ..
+        } if ratio <= QUOTA_WARNING_RATIO => current.mul_f64(1.5),
+        EditFeedback::Sent {
+            remaining_ratio: Some(ratio),
+            ..
+        } if ratio <= HEADROOM_RATIO => current,
+        EditFeedback::Sent { .. } => current.mul_f64(0.75),
+    };
+    next.clamp(base, MAX_EDIT_INTERVAL.max(base))
+}
+
+/// Edit intervals for every channel one adapter edits in.
+#[derive(Debug)]
+pub struct EditPacer {
+    base: Duration,
+    channels: Mutex<HashMap<String, ChannelPace>>,
+}
+
+impl EditPacer {
+    /// A pacer whose channels start at, and never go below, `base`.
+    pub fn new(base: Duration) -> Self {
+        Self {
+            base,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until `channel` may be edited again, and count the edit that
+    /// follows from now.
+    pub async fn wait(&self, channel: &str) {
+        let ready_at = self
+            .lock()
+            .get(channel)
+            .map(|pace| pace.last_edit + pace.interval);
+        if let Some(ready_at) = ready_at {
+            tokio::time::sleep_until(ready_at).await;
+        }
+        let now = tokio::time::Instant::now();
+        let mut channels = self.lock();
+        match channels.get_mut(channel) {
+            Some(pace) => pace.last_edit = now,
+            None => {
+                // A channel unedited this long has had time to recover its
+                // quota, so it starts over at the base.
+                channels.retain(|_, pace| now - pace.last_edit < MAX_EDIT_INTERVAL * 2);
+                channels.insert(
+                    channel.to_string(),
+                    ChannelPace {
+                        interval: self.base,
+                        last_edit: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Widen or narrow `channel`'s interval after an edit.
+    pub fn record(&self, channel: &str, feedback: EditFeedback) {
+        let mut channels = self.lock();
+        let Some(pace) = channels.get_mut(channel) else {
+            return;
+        };
+        let before = pace.interval;
+        pace.interval = next_interval(before, feedback, self.base);
+        if pace.interval > before {
+            tracing::debug!(
+                channel,
+                interval_ms = pace.interval.as_millis() as u64,
+                "platform is rate limiting edits, slowing down"
+            );
+        }
+    }
+
+    /// The gap `channel`'s edits are kept to now.
+    pub fn interval(&self, channel: &str) -> Duration {
+        self.lock()
+            .get(channel)
+            .map_or(self.base, |pace| pace.interval)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ChannelPace>> {
+        self.channels
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(1);
+
+    fn sent(remaining_ratio: Option<f64>) -> EditFeedback {
+        EditFeedback::Sent {
+            latency: Duration::from_millis(80),
+            remaining_ratio,
+        }
+    }
+
+    #[test]
+    fn rate_limits_widen_and_headroom_narrows() {
+        let limited = EditFeedback::RateLimited {
+            retry_after: Some(Duration::from_secs(5)),
+        };
+        let interval = next_interval(BASE, limited, BASE);
+        assert_eq!(interval, Duration::from_secs(5));
+        let interval = next_interval(interval, limited, BASE);
+        assert_eq!(interval, Duration::from_secs(10));
+        assert_eq!(
+            next_interval(Duration::from_secs(20), limited, BASE),
+            MAX_EDIT_INTERVAL
+        );
+
+        let slow = EditFeedback::Sent {
+            latency: Duration::from_secs(3),
+            remaining_ratio: None,
+        };
+        assert_eq!(next_interval(BASE, slow, BASE), Duration::from_secs(3));
+
+        assert_eq!(
+            next_interval(Duration::from_secs(4), sent(Some(0.05)), BASE),
+            Duration::from_secs(6)
+        );
+        assert_eq!(
+            next_interval(Duration::from_secs(4), sent(Some(0.3)), BASE),
+            Duration::from_secs(4)
+        );
+        assert_eq!(
+            next_interval(Duration::from_secs(4), sent(Some(0.9)), BASE),
+            Duration::from_secs(3)
+        );
+        assert_eq!(next_interval(BASE, sent(None), BASE), BASE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn edits_wait_out_the_channel_interval() {
+        let pacer = EditPacer::new(BASE);
+        let started = tokio::time::Instant::now();
+        pacer.wait("c1").await;
+        assert_eq!(started.elapsed(), Duration::ZERO);
+
+        pacer.record(
+            "c1",
+            EditFeedback::RateLimited {
+                retry_after: Some(Duration::from_secs(4)),
+            },
+        );
+        pacer.wait("c2").await;
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        pacer.wait("c1").await;
+        assert_eq!(started.elapsed(), Duration::from_secs(4));
+
+        pacer.record("c1", sent(None));
+        assert_eq!(pacer.interval("c1"), Duration::from_secs(3));
+        assert_eq!(pacer.interval("c2"), BASE);
+    }
+}
@@ -1,6 +1,12 @@
 //! Email messaging adapter using IMAP polling and SMTP delivery.
+//!
+//! Inbound mail can also be pushed by a provider's inbound-parse webhook (see
+//! [`webhook`]). Mail addressed to one of `prompt_addresses` is marked as a
+//! prompt: the channel answers it by email instead of treating it as intake.
 
-use crate::config::EmailConfig;
+mod webhook;
+
+use crate::config::{EmailConfig, EmailWebhookConfig};
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse};
 
@@ -90,6 +96,7 @@ struct EmailPollConfig {
     poll_interval: Duration,
     allowed_senders: Vec<String>,
     max_body_bytes: usize,
+    prompt_addresses: Vec<String>,
     runtime_key: String,
 }
 
@@ -142,9 +149,12 @@ pub struct EmailAdapter {
     allowed_senders: Vec<String>,
     max_body_bytes: usize,
     max_attachment_bytes: usize,
+    prompt_addresses: Vec<String>,
+    webhook: Option<EmailWebhookConfig>,
     smtp_transport: AsyncSmtpTransport<Tokio1Executor>,
     shutdown_tx: Arc<RwLock<Option<watch::Sender<bool>>>>,
     poll_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+    webhook_task: Arc<RwLock<Option<JoinHandle<()>>>>,
 }
 
 impl std::fmt::Debug for EmailAdapter {
@@ -166,6 +176,8 @@ impl std::fmt::Debug for EmailAdapter {
             .field("allowed_senders", &"[REDACTED]")
             .field("max_body_bytes", &self.max_body_bytes)
             .field("max_attachment_bytes", &self.max_attachment_bytes)
+            .field("prompt_addresses", &"[REDACTED]")
+            .field("webhook", &self.webhook)
             .finish()
     }
 }
@@ -199,6 +211,8 @@ impl EmailAdapter {
             allowed_senders: config.allowed_senders.clone(),
             max_body_bytes: config.max_body_bytes,
             max_attachment_bytes: config.max_attachment_bytes,
+            prompt_addresses: config.prompt_addresses.clone(),
+            webhook: config.webhook.clone(),
             instances: Vec::new(),
         };
         Self::build(runtime_key.into(), &email_config)
@@ -238,9 +252,12 @@ impl EmailAdapter {
             allowed_senders: config.allowed_senders.clone(),
             max_body_bytes: config.max_body_bytes.max(1024),
             max_attachment_bytes: config.max_attachment_bytes.max(1024),
+            prompt_addresses: config.prompt_addresses.clone(),
+            webhook: config.webhook.clone(),
             smtp_transport,
             shutdown_tx: Arc::new(RwLock::new(None)),
             poll_task: Arc::new(RwLock::new(None)),
+            webhook_task: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -257,6 +274,7 @@ impl EmailAdapter {
             poll_interval: self.poll_interval,
             allowed_senders: self.allowed_senders.clone(),
             max_body_bytes: self.max_body_bytes,
+            prompt_addresses: self.prompt_addresses.clone(),
             runtime_key: self.runtime_key.clone(),
         }
     }
@@ -337,15 +355,33 @@ impl Messaging for EmailAdapter {
     }
 
     async fn start(&self) -> crate::Result<InboundStream> {
-        if self.poll_task.read().await.is_some() {
+        if self.shutdown_tx.read().await.is_some() {
             return Err(anyhow::anyhow!("email adapter already started").into());
         }
 
         let (inbound_tx, inbound_rx) = mpsc::channel(256);
         let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
 
+        if let Some(webhook_config) = &self.webhook {
+            let webhook_task = webhook::serve(
+                webhook_config,
+                self.poll_config(),
+                inbound_tx.clone(),
+                shutdown_rx.clone(),
+            )
+            .await?;
+            *self.webhook_task.write().await = Some(webhook_task);
+        }
+
         *self.shutdown_tx.write().await = Some(shutdown_tx);
 
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+
+        // Webhook-only setups have no mailbox to poll.
+        if self.imap_host.is_empty() {
+            return Ok(Box::pin(stream));
+        }
+
         let poll_config = self.poll_config();
 
         let poll_task = tokio::spawn(async move {
@@ -408,7 +444,6 @@ impl Messaging for EmailAdapter {
 
         *self.poll_task.write().await = Some(poll_task);
 
-        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
         Ok(Box::pin(stream))
     }
 
@@ -576,7 +611,7 @@ impl Messaging for EmailAdapter {
         message: &InboundMessage,
         limit: usize,
     ) -> crate::Result<Vec<HistoryMessage>> {
-        if limit == 0 {
+        if limit == 0 || self.imap_host.is_empty() {
             return Ok(Vec::new());
         }
 
@@ -629,21 +664,23 @@ impl Messaging for EmailAdapter {
 
     async fn health_check(&self) -> crate::Result<()> {
         let poll_config = self.poll_config();
-        tokio::task::spawn_blocking(move || {
-            let mut session = open_imap_session(&poll_config)?;
-            let folder = poll_config
-                .folders
-                .first()
-                .cloned()
-                .unwrap_or_else(|| "INBOX".to_string());
-            session
-                .select(&folder)
-                .with_context(|| format!("failed to select IMAP folder '{folder}'"))?;
-            session.logout().ok();
-            anyhow::Ok(())
-        })
-        .await
-        .context("email IMAP health check task failed")??;
+        if !poll_config.imap_host.is_empty() {
+            tokio::task::spawn_blocking(move || {
+                let mut session = open_imap_session(&poll_config)?;
+                let folder = poll_config
+                    .folders
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "INBOX".to_string());
+                session
+                    .select(&folder)
+                    .with_context(|| format!("failed to select IMAP folder '{folder}'"))?;
+                session.logout().ok();
+                anyhow::Ok(())
+            })
+            .await
+            .context("email IMAP health check task failed")??;
+        }
 
         let smtp_ok = self
             .smtp_transport
@@ -668,6 +705,12 @@ impl Messaging for EmailAdapter {
             tracing::warn!(%error, "email poll task join failed during shutdown");
         }
 
+        if let Some(webhook_task) = self.webhook_task.write().await.take()
+            && let Err(error) = webhook_task.await
+        {
+            tracing::warn!(%error, "email webhook task join failed during shutdown");
+        }
+
         self.smtp_transport.shutdown().await;
 
         tracing::info!("email adapter shut down");
@@ -900,6 +943,8 @@ fn parse_inbound_email(
         return Ok(None);
     }
 
+    let is_prompt = is_prompt_email(headers, &config.prompt_addresses);
+
     let reply_to_email = headers
         .get_first_value("Reply-To")
         .and_then(|value| parse_primary_mailbox(&value).map(|(address, _)| address))
@@ -1001,6 +1046,9 @@ fn parse_inbound_email(
         "email_thread_key".into(),
         serde_json::Value::String(thread_key),
     );
+    if is_prompt {
+        metadata.insert("email_prompt".into(), serde_json::Value::Bool(true));
+    }
     metadata.insert(
         "sender_display_name".into(),
         serde_json::Value::String(sender_name.clone().unwrap_or_else(|| sender_email.clone())),
@@ -1210,6 +1258,7 @@ pub fn search_mailbox(
         poll_interval: Duration::from_secs(config.poll_interval_secs.max(5)),
         allowed_senders: config.allowed_senders.clone(),
         max_body_bytes: config.max_body_bytes.max(1024),
+        prompt_addresses: config.prompt_addresses.clone(),
         runtime_key: "email".to_string(),
     })?;
 
@@ -1440,6 +1489,29 @@ fn is_own_sender(sender_email: &str, config: &EmailPollConfig) -> bool {
         || sender_email.eq_ignore_ascii_case(&config.smtp_username)
 }
 
+/// Whether the mail was sent to one of `prompt_addresses`. Checks the
+/// delivery headers as well as `To`/`Cc`, so Bcc'd and forwarded mail counts.
+fn is_prompt_email(headers: &[mailparse::MailHeader<'_>], prompt_addresses: &[String]) -> bool {
+    if prompt_addresses.is_empty() {
+        return false;
+    }
+
+    ["To", "Cc", "Delivered-To", "X-Original-To", "Envelope-To"]
+        .into_iter()
+        .flat_map(|name| headers.get_all_values(name))
+        .filter_map(|value| mailparse::addrparse(&value).ok())
+        .flat_map(|addresses| addresses.into_inner())
+        .flat_map(|address| match address {
+            MailAddr::Single(single) => vec![single.addr],
+            MailAddr::Group(group) => group.addrs.into_iter().map(|single| single.addr).collect(),
+        })
+        .any(|address| {
+            prompt_addresses
+                .iter()
+                .any(|prompt_address| prompt_address.trim().eq_ignore_ascii_case(&address))
+        })
+}
+
 fn is_allowed_sender(sender_email: &str, allowed_senders: &[String]) -> bool {
     if allowed_senders.is_empty() {
         return true;
@@ -1798,6 +1870,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn is_prompt_email_matches_any_recipient_header() {
+        let prompt_addresses = vec!["Tasks@Example.com".to_string()];
+        let (headers, _) = mailparse::parse_headers(
+            b"To: Team <team@example.com>\r\nCc: \"Tasks\" <tasks@example.com>\r\n\r\n",
+        )
+        .unwrap();
+        assert!(is_prompt_email(&headers, &prompt_addresses));
+        assert!(!is_prompt_email(&headers, &[]));
+
+        let (headers, _) = mailparse::parse_headers(
+            b"To: team@example.com\r\nDelivered-To: tasks@example.com\r\n\r\n",
+        )
+        .unwrap();
+        assert!(is_prompt_email(&headers, &prompt_addresses));
+
+        let (headers, _) = mailparse::parse_headers(b"To: team@example.com\r\n\r\n").unwrap();
+        assert!(!is_prompt_email(&headers, &prompt_addresses));
+    }
+
     #[test]
     fn is_local_mail_host_accepts_loopback_hosts() {
         assert!(is_local_mail_host("localhost"));
@@ -0,0 +1,272 @@
+//! HTTP intake for inbound-parse webhooks.
+//!
+//! Mail providers that receive mail for a domain (Mailgun routes, SendGrid
+//! Inbound Parse, Postmark, Cloudflare Email Workers) can forward each
+//! message here instead of Spacebot polling IMAP. Every request carries one
+//! raw RFC 822 message, which goes through the same parsing and sender
+//! filters as polled mail. Replies still go out over SMTP.
+
+use super::{EmailPollConfig, parse_inbound_email};
+use crate::InboundMessage;
+use crate::config::EmailWebhookConfig;
+
+use anyhow::Context as _;
+use axum::Router;
+use axum::extract::{DefaultBodyLimit, FromRequest as _, Multipart, Query, Request, State};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+/// Form fields that hold the raw message: Mailgun's `body-mime` (routes
+/// forwarding to a `/messages.mime` URL) and SendGrid's `email` (with "POST
+/// the raw, full MIME message" enabled).
+const RAW_MESSAGE_FIELDS: &[&str] = &["body-mime", "email"];
+
+/// Largest request accepted, leaving room for form or JSON encoding around
+/// a message with attachments.
+const MAX_REQUEST_BYTES: usize = 50 * 1024 * 1024;
+
+#[derive(Clone)]
+struct WebhookState {
+    poll_config: Arc<EmailPollConfig>,
+    auth_token: Option<String>,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+}
+
+/// Bind the webhook listener and serve it until `shutdown_rx` flips to true.
+pub(super) async fn serve(
+    config: &EmailWebhookConfig,
+    poll_config: EmailPollConfig,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+    mut shutdown_rx: watch::Receiver<bool>,
+) -> anyhow::Result<JoinHandle<()>> {
+    if config.auth_token.is_none() {
+        tracing::warn!(
+            "email webhook authentication is disabled because no auth token is configured"
+        );
+    }
+
+    let state = WebhookState {
+        poll_config: Arc::new(poll_config),
+        auth_token: config.auth_token.clone(),
+        inbound_tx,
+    };
+    let app = Router::new()
+        .route("/email", post(handle_inbound))
+        .route("/health", get(|| async { StatusCode::OK }))
+        .layer(DefaultBodyLimit::max(MAX_REQUEST_BYTES))
+        .with_state(state);
+
+    let bind = if config.bind.contains(':') {
+        format!("[{}]:{}", config.bind, config.port)
+    } else {
+        format!("{}:{}", config.bind, config.port)
+    };
+    let listener = tokio::net::TcpListener::bind(&bind)
+        .await
+        .with_context(|| format!("failed to bind email webhook server to {bind}"))?;
+    tracing::info!(%bind, "email webhook server listening");
+
+    Ok(tokio::spawn(async move {
+        let shutdown = async move {
+            while !*shutdown_rx.borrow() {
+                if shutdown_rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        };
+        if let Err(error) = axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
+            .await
+        {
+            tracing::error!(%error, "email webhook server exited with error");
+        }
+    }))
+}
+
+async fn handle_inbound(
+    State(state): State<WebhookState>,
+    Query(query): Query<HashMap<String, String>>,
+    request: Request,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let query_token = query.get("token").map(String::as_str);
+    if !is_authorized(request.headers(), query_token, state.auth_token.as_deref()) {
+        return Err((StatusCode::UNAUTHORIZED, "unauthorized".into()));
+    }
+
+    let raw_email = raw_message(request).await?;
+    let poll_config = state.poll_config.clone();
+    let parsed = tokio::task::spawn_blocking(move || {
+        parse_inbound_email(&raw_email, "webhook", 0, &poll_config)
+    })
+    .await
+    .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))?;
+
+    match parsed {
+        Ok(Some(message)) => {
+            state.inbound_tx.send(message).await.map_err(|_| {
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "email adapter is shutting down".to_string(),
+                )
+            })?;
+            Ok(StatusCode::ACCEPTED)
+        }
+        // Filtered (own mail, auto-reply, sender not allowed). Report success
+        // so the provider doesn't retry it.
+        Ok(None) => Ok(StatusCode::OK),
+        Err(error) => {
+            tracing::warn!(%error, "failed to parse email from webhook");
+            Err((StatusCode::UNPROCESSABLE_ENTITY, error.to_string()))
+        }
+    }
+}
+
+/// Pull the raw RFC 822 message out of a webhook request: a form field from
+/// Mailgun or SendGrid, Postmark's `RawEmail` JSON field, or the whole body.
+async fn raw_message(request: Request) -> Result<Vec<u8>, (StatusCode, String)> {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, message);
+    let content_type = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if content_type.starts_with("multipart/form-data") {
+        let mut multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|error| bad_request(error.to_string()))?;
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|error| bad_request(error.to_string()))?
+        {
+            if field
+                .name()
+                .is_some_and(|name| RAW_MESSAGE_FIELDS.contains(&name))
+            {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|error| bad_request(error.to_string()))?;
+                return Ok(bytes.to_vec());
+            }
+        }
+        return Err(bad_request(format!(
+            "form has no raw message field (expected one of: {})",
+            RAW_MESSAGE_FIELDS.join(", ")
+        )));
+    }
+
+    let body = axum::body::to_bytes(request.into_body(), MAX_REQUEST_BYTES)
+        .await
+        .map_err(|error| bad_request(error.to_string()))?;
+    if content_type.starts_with("application/json") {
+        let payload: serde_json::Value =
+            serde_json::from_slice(&body).map_err(|error| bad_request(error.to_string()))?;
+        return payload
+            .get("RawEmail")
+            .and_then(|value| value.as_str())
+            .map(|raw| raw.as_bytes().to_vec())
+            .ok_or_else(|| {
+                bad_request("JSON payload has no RawEmail field (enable raw email content)".into())
+            });
+    }
+    Ok(body.to_vec())
+}
+
+fn is_authorized(
+    headers: &HeaderMap,
+    query_token: Option<&str>,
+    expected_token: Option<&str>,
+) -> bool {
+    let Some(expected_token) = expected_token else {
+        return true;
+    };
+
+    // Most providers can't set headers on the forwarding request, so the
+    // token may also come as `?token=` in the webhook URL.
+    if query_token.is_some_and(|token| tokens_match(token, expected_token)) {
+        return true;
+    }
+
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens_match(token, expected_token))
+}
+
+/// Compare tokens without short-circuiting on the first differing byte, so
+/// response timing doesn't reveal how much of a guess was right.
+fn tokens_match(candidate: &str, expected: &str) -> bool {
+    let candidate = candidate.as_bytes();
+    let expected = expected.as_bytes();
+    let difference = expected.iter().enumerate().fold(
+        candidate.len() ^ expected.len(),
+        |difference, (index, byte)| {
+            let other = candidate.get(index).copied().unwrap_or(0);
+            difference | usize::from(byte ^ other)
+        },
+    );
+    difference == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[tokio::test]
+    async fn extracts_raw_message_from_provider_payloads() {
+        let raw = "From: alice@example.com\r\nSubject: hi\r\n\r\nbody";
+
+        let plain = Request::builder()
+            .header(CONTENT_TYPE, "message/rfc822")
+            .body(Body::from(raw))
+            .unwrap();
+        assert_eq!(raw_message(plain).await.unwrap(), raw.as_bytes());
+
+        let json = Request::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "RawEmail": raw, "Subject": "hi" }).to_string(),
+            ))
+            .unwrap();
+        assert_eq!(raw_message(json).await.unwrap(), raw.as_bytes());
+
+        let form_body = format!(
+            "--b\r\nContent-Disposition: form-data; name=\"to\"\r\n\r\nbot@example.com\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"email\"\r\n\r\n{raw}\r\n--b--\r\n"
+        );
+        let form = Request::builder()
+            .header(CONTENT_TYPE, "multipart/form-data; boundary=b")
+            .body(Body::from(form_body))
+            .unwrap();
+        assert_eq!(raw_message(form).await.unwrap(), raw.as_bytes());
+    }
+
+    #[test]
+    fn accepts_header_or_query_token() {
+        let mut headers = HeaderMap::new();
+        assert!(is_authorized(&headers, None, None));
+        assert!(!is_authorized(&headers, None, Some("secret")));
+        assert!(is_authorized(&headers, Some("secret"), Some("secret")));
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(is_authorized(&headers, None, Some("secret")));
+    }
+
+    #[test]
+    fn token_comparison_requires_an_exact_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secre", "secret"));
+        assert!(!tokens_match("secrets", "secret"));
+        assert!(!tokens_match("Secret", "secret"));
+        assert!(!tokens_match("", "secret"));
+    }
+}
@@ -0,0 +1,873 @@
+//! IRC messaging adapter for minimal environments.
+//!
+//! Speaks the plain IRC client protocol over TCP (optionally TLS) with no
+//! extra services, so Spacebot can be reached from a terminal IRC client on
+//! a box where nothing else is available. IRC has no message edits, so
+//! replies are rendered line by line: streamed updates are buffered until the
+//! stream ends and the final text is sent as whole lines. IRC clients treat
+//! `/` as their own command prefix, so chat commands are typed with
+//! `command_prefix` (`!` by default) and rewritten to `/` on the way in.
+
+use crate::config::IrcConfig;
+use crate::messaging::traits::{InboundStream, Messaging};
+use crate::{InboundMessage, MessageContent, OutboundResponse};
+
+use anyhow::Context as _;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, AsyncWrite, AsyncWriteExt as _, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, RwLock, mpsc};
+
+/// Bytes of text per `PRIVMSG`. Lines are capped at 512 bytes including the
+/// sender prefix the server adds, so this leaves room for long hostmasks.
+const MAX_LINE_BYTES: usize = 400;
+
+/// Lines sent for one response before the rest is summarized.
+const MAX_LINES_PER_RESPONSE: usize = 20;
+
+/// Lines that can be sent back to back before pacing kicks in.
+const SEND_BURST: f64 = 4.0;
+
+/// Sustained send rate once the burst is used up.
+const SEND_INTERVAL: Duration = Duration::from_millis(1_000);
+
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(5);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// `!`-commands the channel handles itself, which are not rewritten to `/`.
+const NATIVE_BANG_COMMANDS: &[&str] = &["context"];
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// IRC adapter state.
+pub struct IrcAdapter {
+    config: IrcConfig,
+    /// Raw protocol lines queued for the active connection.
+    outbound_tx: Arc<RwLock<Option<mpsc::Sender<String>>>>,
+    /// Each open stream and how much of it has been sent, keyed by the
+    /// target message id.
+    streams: Arc<Mutex<HashMap<String, StreamProgress>>>,
+    connected: Arc<AtomicBool>,
+    shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
+}
+
+impl IrcAdapter {
+    pub fn new(config: IrcConfig) -> Self {
+        Self {
+            config,
+            outbound_tx: Arc::new(RwLock::new(None)),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+            connected: Arc::new(AtomicBool::new(false)),
+            shutdown_tx: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    async fn say(&self, target: &str, text: &str) -> anyhow::Result<()> {
+        self.send_lines(target, render_lines(text)).await
+    }
+
+    async fn send_lines(&self, target: &str, lines: Vec<String>) -> anyhow::Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let outbound_tx = self
+            .outbound_tx
+            .read()
+            .await
+            .clone()
+            .context("irc adapter not started")?;
+        for line in lines {
+            outbound_tx
+                .send(format!("PRIVMSG {target} :{line}"))
+                .await
+                .context("irc connection task stopped")?;
+        }
+        Ok(())
+    }
+}
+
+impl Messaging for IrcAdapter {
+    fn name(&self) -> &str {
+        "irc"
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        let (inbound_tx, inbound_rx) = mpsc::channel(256);
+        let (outbound_tx, outbound_rx) = mpsc::channel(256);
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
+        *self.outbound_tx.write().await = Some(outbound_tx);
+        *self.shutdown_tx.write().await = Some(shutdown_tx);
+
+        tokio::spawn(run_connection_loop(
+            self.config.clone(),
+            inbound_tx,
+            outbound_rx,
+            shutdown_rx,
+            self.connected.clone(),
+        ));
+
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+        Ok(Box::pin(stream))
+    }
+
+    async fn respond(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        let target = message
+            .metadata
+            .get("irc_target")
+            .and_then(|value| value.as_str())
+            .context("missing irc_target in metadata")?;
+
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
+                self.say(target, &text).await?;
+            }
+            OutboundResponse::File {
+                filename, caption, ..
+            } => {
+                // IRC is text-only — send a note about the file
+                let text = match caption {
+                    Some(caption) => format!("[file: {filename}] {caption}"),
+                    None => format!("[file: {filename}]"),
+                };
+                self.say(target, &text).await?;
+            }
+            // No edits on IRC: send each line of the stream once it is
+            // complete, and the unfinished tail when the stream ends.
+            OutboundResponse::StreamStart => {
+                self.streams
+                    .lock()
+                    .await
+                    .insert(message.id.clone(), StreamProgress::default());
+            }
+            OutboundResponse::StreamChunk(text) => {
+                let lines = self
+                    .streams
+                    .lock()
+                    .await
+                    .entry(message.id.clone())
+                    .or_default()
+                    .update(text);
+                self.send_lines(target, lines).await?;
+            }
+            OutboundResponse::StreamEnd => {
+                let stream = self.streams.lock().await.remove(&message.id);
+                if let Some(stream) = stream {
+                    self.send_lines(target, stream.finish()).await?;
+                }
+            }
+            OutboundResponse::Reaction(_)
+            | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Status(_) => {}
+        }
+
+        Ok(())
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
+        if let OutboundResponse::Text(text) | OutboundResponse::RichMessage { text, .. } = response
+        {
+            self.say(target, &text).await?;
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("irc client not connected").into());
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> crate::Result<()> {
+        if let Some(tx) = self.shutdown_tx.read().await.as_ref() {
+            tx.send(()).await.ok();
+        }
+        *self.outbound_tx.write().await = None;
+        tracing::info!("irc adapter shut down");
+        Ok(())
+    }
+}
+
+/// Why a connection ended.
+enum SessionEnd {
+    Shutdown,
+    Disconnected(anyhow::Error),
+}
+
+/// Connect, and reconnect with backoff, until shutdown.
+async fn run_connection_loop(
+    config: IrcConfig,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+    mut outbound_rx: mpsc::Receiver<String>,
+    mut shutdown_rx: mpsc::Receiver<()>,
+    connected: Arc<AtomicBool>,
+) {
+    let mut delay = RECONNECT_MIN_DELAY;
+    loop {
+        let end = match connect(&config).await {
+            Ok(connection) => {
+                delay = RECONNECT_MIN_DELAY;
+                run_session(
+                    &config,
+                    connection,
+                    &inbound_tx,
+                    &mut outbound_rx,
+                    &mut shutdown_rx,
+                    &connected,
+                )
+                .await
+            }
+            Err(error) => SessionEnd::Disconnected(error),
+        };
+        connected.store(false, Ordering::Relaxed);
+
+        match end {
+            SessionEnd::Shutdown => break,
+            SessionEnd::Disconnected(error) => {
+                tracing::warn!(
+                    %error,
+                    server = %config.server,
+                    retry_in_secs = delay.as_secs(),
+                    "irc connection lost"
+                );
+                crate::notifications::ops::OpsAlerts::global()
+                    .reconnect(&format!("irc server {}", config.server));
+            }
+        }
+        tokio::select! {
+            _ = shutdown_rx.recv() => break,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+    }
+    tracing::info!("irc connection loop shutting down");
+}
+
+async fn connect(config: &IrcConfig) -> anyhow::Result<Box<dyn Connection>> {
+    let tcp = TcpStream::connect((config.server.as_str(), config.port))
+        .await
+        .with_context(|| format!("failed to connect to {}:{}", config.server, config.port))?;
+    if !config.tls {
+        return Ok(Box::new(tcp));
+    }
+
+    let roots = rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls_config = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = rustls::pki_types::ServerName::try_from(config.server.clone())
+        .context("invalid irc server name")?;
+    let stream = tokio_rustls::TlsConnector::from(Arc::new(tls_config))
+        .connect(server_name, tcp)
+        .await
+        .context("TLS handshake with irc server failed")?;
+    Ok(Box::new(stream))
+}
+
+async fn run_session(
+    config: &IrcConfig,
+    connection: Box<dyn Connection>,
+    inbound_tx: &mpsc::Sender<InboundMessage>,
+    outbound_rx: &mut mpsc::Receiver<String>,
+    shutdown_rx: &mut mpsc::Receiver<()>,
+    connected: &AtomicBool,
+) -> SessionEnd {
+    let (reader, mut writer) = tokio::io::split(connection);
+
+    // Read on a separate task so a half-read line is never dropped by select!.
+    let (line_tx, mut line_rx) = mpsc::channel::<String>(256);
+    let reader_task = tokio::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buffer)
+                        .trim_end_matches(['\r', '\n'])
+                        .to_string();
+                    if line_tx.send(line).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let mut nickname = config.nickname.clone();
+    let mut registration = Vec::new();
+    if let Some(password) = &config.password {
+        registration.push(format!("PASS {password}"));
+    }
+    registration.push(format!("NICK {nickname}"));
+    registration.push(format!("USER {nickname} 0 * :spacebot"));
+    for line in registration {
+        if let Err(error) = write_line(&mut writer, &line).await {
+            reader_task.abort();
+            return SessionEnd::Disconnected(error);
+        }
+    }
+
+    let mut throttle = Throttle::new();
+    let end = 'session: loop {
+        tokio::select! {
+            _ = shutdown_rx.recv() => {
+                write_line(&mut writer, "QUIT :shutting down").await.ok();
+                break SessionEnd::Shutdown;
+            }
+            line = line_rx.recv() => {
+                let Some(line) = line else {
+                    let error = anyhow::anyhow!("server closed the connection");
+                    break SessionEnd::Disconnected(error);
+                };
+                let Some(message) = IrcLine::parse(&line) else {
+                    continue;
+                };
+                if message.command == "PRIVMSG" {
+                    if let Some(inbound) = inbound_message(config, &nickname, &message)
+                        && inbound_tx.send(inbound).await.is_err()
+                    {
+                        tracing::warn!("irc inbound receiver dropped");
+                        break SessionEnd::Shutdown;
+                    }
+                    continue;
+                }
+                let replies = match server_replies(config, &mut nickname, connected, &message) {
+                    Ok(replies) => replies,
+                    Err(error) => break SessionEnd::Disconnected(error),
+                };
+                for reply in replies {
+                    if let Err(error) = write_line(&mut writer, &reply).await {
+                        break 'session SessionEnd::Disconnected(error);
+                    }
+                }
+            }
+            line = outbound_rx.recv(), if connected.load(Ordering::Relaxed) => {
+                let Some(line) = line else {
+                    break SessionEnd::Shutdown;
+                };
+                throttle.wait().await;
+                if let Err(error) = write_line(&mut writer, &line).await {
+                    break SessionEnd::Disconnected(error);
+                }
+            }
+        }
+    };
+
+    reader_task.abort();
+    end
+}
+
+/// Lines to send back for a server message other than `PRIVMSG`, keeping the
+/// current nickname and connection state up to date.
+fn server_replies(
+    config: &IrcConfig,
+    nickname: &mut String,
+    connected: &AtomicBool,
+    message: &IrcLine<'_>,
+) -> anyhow::Result<Vec<String>> {
+    let last_param = message.params.last().copied().unwrap_or_default();
+    let mut replies = Vec::new();
+    match message.command {
+        "PING" => replies.push(format!("PONG :{last_param}")),
+        // RPL_WELCOME: registration is done.
+        "001" => {
+            if let Some(accepted) = message.params.first() {
+                *nickname = accepted.to_string();
+            }
+            connected.store(true, Ordering::Relaxed);
+            tracing::info!(
+                server = %config.server,
+                nick = %nickname,
+                channels = ?config.channels,
+                "irc connected"
+            );
+            if let Some(password) = &config.nickserv_password {
+                replies.push(format!("PRIVMSG NickServ :IDENTIFY {password}"));
+            }
+            if !config.channels.is_empty() {
+                replies.push(format!("JOIN {}", config.channels.join(",")));
+            }
+        }
+        // ERR_NICKNAMEINUSE before registration completes.
+        "433" if !connected.load(Ordering::Relaxed) => {
+            nickname.push('_');
+            replies.push(format!("NICK {nickname}"));
+        }
+        "NICK"
+            if message
+                .nick()
+                .is_some_and(|nick| nick.eq_ignore_ascii_case(nickname)) =>
+        {
+            *nickname = last_param.to_string();
+        }
+        "ERROR" => anyhow::bail!("server error: {last_param}"),
+        _ => {}
+    }
+    Ok(replies)
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, line: &str) -> anyhow::Result<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.write_all(b"\r\n").await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Token bucket pacing outbound lines so long replies don't trip the
+/// server's flood protection.
+struct Throttle {
+    tokens: f64,
+    last_refill: tokio::time::Instant,
+}
+
+impl Throttle {
+    fn new() -> Self {
+        Self {
+            tokens: SEND_BURST,
+            last_refill: tokio::time::Instant::now(),
+        }
+    }
+
+    async fn wait(&mut self) {
+        let now = tokio::time::Instant::now();
+        let refilled =
+            now.duration_since(self.last_refill).as_secs_f64() / SEND_INTERVAL.as_secs_f64();
+        self.tokens = (self.tokens + refilled).min(SEND_BURST);
+        self.last_refill = now;
+        if self.tokens < 1.0 {
+            let wait = SEND_INTERVAL.mul_f64(1.0 - self.tokens);
+            tokio::time::sleep(wait).await;
+            self.tokens = 1.0;
+            self.last_refill = tokio::time::Instant::now();
+        }
+        self.tokens -= 1.0;
+    }
+}
+
+/// One parsed protocol line.
+#[derive(Debug, PartialEq, Eq)]
+struct IrcLine<'a> {
+    prefix: Option<&'a str>,
+    command: &'a str,
+    params: Vec<&'a str>,
+}
+
+impl<'a> IrcLine<'a> {
+    /// Parse `[@tags] [:prefix] COMMAND [params] [:trailing]`.
+    fn parse(line: &'a str) -> Option<Self> {
+        let mut rest = line.trim_end();
+        if rest.starts_with('@') {
+            rest = rest.split_once(' ')?.1.trim_start();
+        }
+        let prefix = match rest.strip_prefix(':') {
+            Some(stripped) => {
+                let (prefix, remainder) = stripped.split_once(' ')?;
+                rest = remainder.trim_start();
+                Some(prefix)
+            }
+            None => None,
+        };
+        let (command, mut rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        if command.is_empty() {
+            return None;
+        }
+
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(' ');
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(trailing) = rest.strip_prefix(':') {
+                params.push(trailing);
+                break;
+            }
+            let (param, remainder) = rest.split_once(' ').unwrap_or((rest, ""));
+            params.push(param);
+            rest = remainder;
+        }
+
+        Some(Self {
+            prefix,
+            command,
+            params,
+        })
+    }
+
+    /// Nick of the sender, from a `nick!user@host` prefix.
+    fn nick(&self) -> Option<&'a str> {
+        let prefix = self.prefix?;
+        Some(prefix.split_once('!').map_or(prefix, |(nick, _)| nick))
+    }
+}
+
+/// Build the inbound message for a `PRIVMSG`, or `None` when it should be
+/// ignored (CTCP, filtered nick, empty text).
+fn inbound_message(
+    config: &IrcConfig,
+    nickname: &str,
+    message: &IrcLine<'_>,
+) -> Option<InboundMessage> {
+    let sender = message.nick()?;
+    let [target, text] = message.params.as_slice() else {
+        return None;
+    };
+    // CTCP (ACTION, VERSION, ...) is not chat.
+    if text.starts_with('\u{1}') {
+        return None;
+    }
+    if !config.allowed_nicks.is_empty()
+        && !config
+            .allowed_nicks
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(sender))
+    {
+        return None;
+    }
+
+    let is_direct = target.eq_ignore_ascii_case(nickname);
+    let (addressed_text, addressed) = strip_address(text, nickname);
+    let mentions_bot = addressed || mentions_nick(text, nickname);
+    let text = rewrite_command(addressed_text, &config.command_prefix);
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    let (reply_target, conversation_id) = if is_direct {
+        (
+            sender.to_string(),
+            format!("irc:dm:{}", sender.to_lowercase()),
+        )
+    } else {
+        (target.to_string(), format!("irc:{}", target.to_lowercase()))
+    };
+    let message_id = uuid::Uuid::new_v4().to_string();
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "irc_target".into(),
+        serde_json::Value::String(reply_target.clone()),
+    );
+    if !is_direct {
+        metadata.insert(
+            "irc_channel".into(),
+            serde_json::Value::String(target.to_string()),
+        );
+    }
+    metadata.insert(
+        "irc_nick".into(),
+        serde_json::Value::String(sender.to_string()),
+    );
+    metadata.insert(
+        "irc_mentions_bot".into(),
+        serde_json::Value::Bool(mentions_bot || is_direct),
+    );
+    metadata.insert(
+        crate::metadata_keys::CHANNEL_NAME.into(),
+        serde_json::Value::String(reply_target),
+    );
+    metadata.insert(
+        crate::metadata_keys::MESSAGE_ID.into(),
+        serde_json::Value::String(message_id.clone()),
+    );
+    metadata.insert(
+        "sender_display_name".into(),
+        serde_json::Value::String(sender.to_string()),
+    );
+
+    Some(InboundMessage {
+        id: message_id,
+        source: "irc".into(),
+        adapter: Some("irc".into()),
+        conversation_id,
+        sender_id: sender.to_string(),
+        agent_id: None,
+        content: MessageContent::Text(text),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(sender.to_string()),
+    })
+}
+
+/// Strip a leading `nick: ` / `nick, ` address. Returns whether one was found.
+fn strip_address<'a>(text: &'a str, nickname: &str) -> (&'a str, bool) {
+    let Some(head) = text.get(..nickname.len()) else {
+        return (text, false);
+    };
+    if !head.eq_ignore_ascii_case(nickname) {
+        return (text, false);
+    }
+    match text[nickname.len()..].strip_prefix([':', ',']) {
+        Some(rest) => (rest.trim_start(), true),
+        None => (text, false),
+    }
+}
+
+/// Whether `nickname` appears in `text` as a whole word.
+fn mentions_nick(text: &str, nickname: &str) -> bool {
+    let is_nick_char =
+        |character: char| character.is_ascii_alphanumeric() || "-_[]\\`^{}|".contains(character);
+    let text = text.to_lowercase();
+    let nickname = nickname.to_lowercase();
+    text.match_indices(&nickname).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + nickname.len()..].chars().next();
+        !before.is_some_and(is_nick_char) && !after.is_some_and(is_nick_char)
+    })
+}
+
+/// Rewrite `!status` to `/status` so the channel's chat commands work from
+/// IRC clients, which swallow `/` themselves.
+fn rewrite_command(text: &str, command_prefix: &str) -> String {
+    let Some(command) = text.strip_prefix(command_prefix) else {
+        return text.to_string();
+    };
+    let name = command.split_whitespace().next().unwrap_or_default();
+    let is_command = name
+        .chars()
+        .next()
+        .is_some_and(|character| character.is_ascii_alphabetic());
+    if command_prefix.is_empty()
+        || !is_command
+        || (command_prefix == "!" && NATIVE_BANG_COMMANDS.contains(&name))
+    {
+        return text.to_string();
+    }
+    format!("/{command}")
+}
+
+/// Split a reply into `PRIVMSG`-sized lines. Blank lines and code fences are
+/// dropped, long lines wrap at spaces, and replies past
+/// [`MAX_LINES_PER_RESPONSE`] end with a count of what was left out.
+fn render_lines(text: &str) -> Vec<String> {
+    StreamProgress {
+        snapshot: text.to_string(),
+        ..StreamProgress::default()
+    }
+    .finish()
+}
+
+/// A streamed reply, rendered like [`render_lines`] but sent line by line as
+/// the snapshots grow.
+#[derive(Debug, Default)]
+struct StreamProgress {
+    /// Latest snapshot of the whole reply.
+    snapshot: String,
+    /// Bytes of `snapshot` already rendered.
+    sent_bytes: usize,
+    lines_sent: usize,
+    /// Lines past the response limit, and the last of them in case it is
+    /// the only one.
+    omitted: usize,
+    held: Option<String>,
+}
+
+impl StreamProgress {
+    /// Take a new snapshot and return the lines it completed.
+    fn update(&mut self, snapshot: String) -> Vec<String> {
+        self.snapshot = snapshot;
+        self.drain(false)
+    }
+
+    /// Return the rest of the reply, ending with the omitted-lines count.
+    fn finish(mut self) -> Vec<String> {
+        let mut lines = self.drain(true);
+        match self.omitted {
+            0 => {}
+            1 => lines.extend(self.held.take()),
+            omitted => lines.push(format!("… ({omitted} more lines)")),
+        }
+        lines
+    }
+
+    fn drain(&mut self, finished: bool) -> Vec<String> {
+        // A snapshot that no longer extends what was sent can't be unsent.
+        let Some(unsent) = self.snapshot.get(self.sent_bytes..) else {
+            return Vec::new();
+        };
+        let ready = if finished {
+            unsent.len()
+        } else {
+            unsent.rfind('\n').map_or(0, |index| index + 1)
+        };
+        let mut lines = Vec::new();
+        for line in unsent[..ready].lines().flat_map(wrap_line) {
+            if self.lines_sent < MAX_LINES_PER_RESPONSE - 1 {
+                self.lines_sent += 1;
+                lines.push(line);
+            } else {
+                self.omitted += 1;
+                self.held = Some(line);
+            }
+        }
+        self.sent_bytes += ready;
+        lines
+    }
+}
+
+/// One line of a reply as `PRIVMSG`-sized pieces, or none for blank lines and
+/// code fences.
+fn wrap_line(line: &str) -> Vec<String> {
+    let line = line.trim_end();
+    if line.trim().is_empty() || line.trim_start().starts_with("```") {
+        return Vec::new();
+    }
+    let mut pieces = Vec::new();
+    let mut remaining = line;
+    while remaining.len() > MAX_LINE_BYTES {
+        let mut split_at = MAX_LINE_BYTES;
+        while !remaining.is_char_boundary(split_at) {
+            split_at -= 1;
+        }
+        let split_at = remaining[..split_at]
+            .rfind(' ')
+            .filter(|index| *index > 0)
+            .unwrap_or(split_at);
+        pieces.push(remaining[..split_at].to_string());
+        remaining = remaining[split_at..].trim_start();
+    }
+    if !remaining.is_empty() {
+        pieces.push(remaining.to_string());
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> IrcConfig {
+        IrcConfig {
+            enabled: true,
+            server: "irc.example.org".into(),
+            port: 6697,
+            tls: true,
+            nickname: "spacebot".into(),
+            password: None,
+            nickserv_password: None,
+            channels: vec!["#ops".into()],
+            command_prefix: "!".into(),
+            allowed_nicks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_protocol_lines() {
+        let line = IrcLine::parse(
+            "@time=2026-01-01T00:00:00Z :alice!a@host PRIVMSG #ops :spacebot: deploy it",
+        )
+        .unwrap();
+        assert_eq!(line.command, "PRIVMSG");
+        assert_eq!(line.nick(), Some("alice"));
+        assert_eq!(line.params, vec!["#ops", "spacebot: deploy it"]);
+
+        let ping = IrcLine::parse("PING :irc.example.org").unwrap();
+        assert_eq!(ping.prefix, None);
+        assert_eq!(ping.params, vec!["irc.example.org"]);
+        assert!(IrcLine::parse("").is_none());
+    }
+
+    #[test]
+    fn builds_inbound_messages() {
+        let config = config();
+        let line = IrcLine::parse(":alice!a@host PRIVMSG #Ops :spacebot, !status").unwrap();
+        let inbound = inbound_message(&config, "spacebot", &line).unwrap();
+        assert_eq!(inbound.conversation_id, "irc:#ops");
+        assert!(matches!(&inbound.content, MessageContent::Text(text) if text == "/status"));
+        assert_eq!(inbound.metadata["irc_target"], "#Ops");
+        assert_eq!(inbound.metadata["irc_mentions_bot"], true);
+
+        let direct =
+            IrcLine::parse(":Bob!b@host PRIVMSG spacebot :!context the db is down").unwrap();
+        let inbound = inbound_message(&config, "spacebot", &direct).unwrap();
+        assert_eq!(inbound.conversation_id, "irc:dm:bob");
+        assert_eq!(inbound.metadata["irc_target"], "Bob");
+        assert!(
+            matches!(&inbound.content, MessageContent::Text(text) if text == "!context the db is down")
+        );
+
+        let action = IrcLine::parse(":alice!a@host PRIVMSG #ops :\u{1}ACTION waves\u{1}").unwrap();
+        assert!(inbound_message(&config, "spacebot", &action).is_none());
+
+        let mut restricted = config.clone();
+        restricted.allowed_nicks = vec!["bob".into()];
+        assert!(inbound_message(&restricted, "spacebot", &line).is_none());
+    }
+
+    #[test]
+    fn detects_mentions() {
+        assert!(mentions_nick("hey Spacebot, you there?", "spacebot"));
+        assert!(!mentions_nick("spacebots are neat", "spacebot"));
+        assert_eq!(strip_address("spacebot: hi", "spacebot"), ("hi", true));
+        assert_eq!(
+            strip_address("spacebothi", "spacebot"),
+            ("spacebothi", false)
+        );
+    }
+
+    #[test]
+    fn renders_replies_as_lines() {
+        let text = "first\n\n```rust\nlet x = 1;\n```\nlast";
+        assert_eq!(render_lines(text), vec!["first", "let x = 1;", "last"]);
+
+        let long = "word ".repeat(200);
+        let lines = render_lines(&long);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_BYTES));
+
+        let many = (0..30).map(|index| index.to_string()).collect::<Vec<_>>();
+        let lines = render_lines(&many.join("\n"));
+        assert_eq!(lines.len(), MAX_LINES_PER_RESPONSE);
+        assert_eq!(lines.last().unwrap(), "… (11 more lines)");
+
+        let exact = (0..MAX_LINES_PER_RESPONSE)
+            .map(|index| index.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(render_lines(&exact.join("\n")), exact);
+    }
+
+    #[test]
+    fn streams_completed_lines_as_they_arrive() {
+        let mut stream = StreamProgress::default();
+        assert!(stream.update("Looking".to_string()).is_empty());
+        assert_eq!(
+            stream.update("Looking at the repo\nRunning te".to_string()),
+            vec!["Looking at the repo"]
+        );
+        assert!(
+            stream
+                .update("Looking at the repo\nRunning tests".to_string())
+                .is_empty()
+        );
+
+        let long = "word ".repeat(200);
+        let lines = stream.update(format!("Looking at the repo\nRunning tests\n{long}\n"));
+        assert_eq!(lines[0], "Running tests");
+        assert!(lines[1..].iter().all(|line| line.len() <= MAX_LINE_BYTES));
+
+        let snapshot = format!("Looking at the repo\nRunning tests\n{long}\nDone");
+        assert!(stream.update(snapshot).is_empty());
+        assert_eq!(stream.finish(), vec!["Done"]);
+
+        let mut stream = StreamProgress::default();
+        let many = (0..30)
+            .map(|index| format!("{index}\n"))
+            .collect::<String>();
+        assert_eq!(stream.update(many).len(), MAX_LINES_PER_RESPONSE - 1);
+        assert_eq!(stream.finish(), vec!["… (11 more lines)"]);
+    }
+}
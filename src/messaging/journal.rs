@@ -0,0 +1,331 @@
+//! Crash-safe journal of in-flight streamed messages.
+//!
+//! A stream (`StreamStart`, `StreamChunk`s, `StreamEnd`) is rendered by
+//! posting a placeholder and editing it as chunks arrive. The adapter keeps
+//! the platform message ID in memory, so a crash mid-stream used to leave a
+//! half-rendered message behind, and the restarted stream posted a second
+//! one. Adapters that edit messages record each stream here: the platform
+//! message when the stream starts, its text every [`CHECKPOINT_INTERVAL`],
+//! and nothing once it ends.
+//!
+//! After a restart, a stream started again under the same key (a resumed
+//! worker's status line or todo list, say) picks the journaled message back
+//! up instead of posting a placeholder. Its first chunks carry the state
+//! resynced from the server, which is compared with the journaled render so
+//! the message doesn't jump backwards while the resync catches up.
+
+use crate::InboundMessage;
+
+use arc_swap::ArcSwapOption;
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+static JOURNAL: LazyLock<ArcSwapOption<StreamJournal>> = LazyLock::new(ArcSwapOption::empty);
+
+/// How often a stream's text is written to the journal.
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Journal entries untouched for this long are dropped at startup. Their
+/// streams are not coming back.
+const STALE_AFTER_HOURS: i64 = 24;
+
+/// Metadata key naming a stream independently of the inbound message that
+/// started it. Streams that outlive a restart set it so they resume.
+pub const STREAM_KEY_METADATA: &str = "stream_key";
+
+/// The journal key for the stream targeting `message`.
+pub fn stream_key(message: &InboundMessage) -> String {
+    let key = message
+        .metadata
+        .get(STREAM_KEY_METADATA)
+        .and_then(|value| value.as_str())
+        .unwrap_or(&message.id);
+    format!("{}:{key}", message.conversation_id)
+}
+
+/// What to show for a resumed stream, given its journaled render and the
+/// text resynced from the server. Returns the text and whether the resync
+/// has caught up with the journal.
+pub fn reconcile<'a>(journaled: &'a str, resynced: &'a str) -> (&'a str, bool) {
+    if resynced.len() < journaled.len() && journaled.starts_with(resynced) {
+        (journaled, false)
+    } else {
+        (resynced, true)
+    }
+}
+
+/// On `StreamStart`: the platform message a crashed stream for `message`
+/// left behind, to edit instead of posting a placeholder.
+pub async fn resume_stream(adapter: &str, message: &InboundMessage) -> Option<String> {
+    let journal = StreamJournal::global()?;
+    journal.resume(adapter, &stream_key(message)).await
+}
+
+/// After posting a stream's placeholder.
+pub async fn begin_stream(adapter: &str, message: &InboundMessage, platform_message_id: &str) {
+    if let Some(journal) = StreamJournal::global() {
+        journal
+            .begin(adapter, &stream_key(message), platform_message_id)
+            .await;
+    }
+}
+
+/// On `StreamChunk`: the text to render.
+pub async fn update_stream(adapter: &str, message: &InboundMessage, text: String) -> String {
+    match StreamJournal::global() {
+        Some(journal) => journal.update(adapter, &stream_key(message), text).await,
+        None => text,
+    }
+}
+
+/// On `StreamEnd`.
+pub async fn finish_stream(adapter: &str, message: &InboundMessage) {
+    if let Some(journal) = StreamJournal::global() {
+        journal.finish(adapter, &stream_key(message)).await;
+    }
+}
+
+#[derive(Debug)]
+struct ActiveStream {
+    platform_message_id: String,
+    last_checkpoint: Option<Instant>,
+    /// The journaled render of a resumed stream, until the resync catches up.
+    restored: Option<String>,
+}
+
+/// The process-wide stream journal, backed by the instance database.
+#[derive(Debug)]
+pub struct StreamJournal {
+    pool: SqlitePool,
+    streams: Mutex<HashMap<(String, String), ActiveStream>>,
+}
+
+impl StreamJournal {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The process-wide journal, once [`StreamJournal::install`] has run.
+    pub fn global() -> Option<Arc<Self>> {
+        JOURNAL.load_full()
+    }
+
+    /// Drop stale entries and make `pool` the process-wide journal.
+    pub async fn install(pool: SqlitePool) {
+        let journal = Self::new(pool);
+        match journal.prune().await {
+            Ok(0) => {}
+            Ok(pruned) => tracing::info!(pruned, "dropped stale stream journal entries"),
+            Err(error) => tracing::warn!(%error, "failed to prune stream journal"),
+        }
+        JOURNAL.store(Some(Arc::new(journal)));
+    }
+
+    async fn prune(&self) -> crate::error::Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM stream_journal \
+             WHERE updated_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)",
+        )
+        .bind(format!("-{STALE_AFTER_HOURS} hours"))
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(result.rows_affected())
+    }
+
+    /// The platform message a crashed stream was editing, if `key` has one.
+    /// The stream is tracked again from here, so call it on `StreamStart`.
+    pub async fn resume(&self, adapter: &str, key: &str) -> Option<String> {
+        let row = sqlx::query(
+            "SELECT platform_message_id, text FROM stream_journal \
+             WHERE adapter = ? AND stream_key = ?",
+        )
+        .bind(adapter)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await;
+        let row = match row {
+            Ok(row) => row?,
+            Err(error) => {
+                tracing::warn!(%error, adapter, key, "failed to read stream journal");
+                return None;
+            }
+        };
+        let platform_message_id: String = row.try_get("platform_message_id").ok()?;
+        let text: String = row.try_get("text").unwrap_or_default();
+        tracing::info!(adapter, key, %platform_message_id, "resuming journaled stream");
+        self.lock().insert(
+            (adapter.to_string(), key.to_string()),
+            ActiveStream {
+                platform_message_id: platform_message_id.clone(),
+                last_checkpoint: None,
+                restored: (!text.is_empty()).then_some(text),
+            },
+        );
+        Some(platform_message_id)
+    }
+
+    /// Record a new stream editing `platform_message_id`.
+    pub async fn begin(&self, adapter: &str, key: &str, platform_message_id: &str) {
+        self.lock().insert(
+            (adapter.to_string(), key.to_string()),
+            ActiveStream {
+                platform_message_id: platform_message_id.to_string(),
+                last_checkpoint: Some(Instant::now()),
+                restored: None,
+            },
+        );
+        self.write(adapter, key, platform_message_id, "").await;
+    }
+
+    /// Take a stream's latest full text and return the text to render,
+    /// writing a checkpoint when one is due.
+    pub async fn update(&self, adapter: &str, key: &str, text: String) -> String {
+        let checkpoint = {
+            let mut streams = self.lock();
+            let Some(stream) = streams.get_mut(&(adapter.to_string(), key.to_string())) else {
+                return text;
+            };
+            let text = match stream.restored.take() {
+                Some(journaled) => match reconcile(&journaled, &text) {
+                    (_, true) => text,
+                    (shown, false) => {
+                        let shown = shown.to_string();
+                        stream.restored = Some(journaled);
+                        shown
+                    }
+                },
+                None => text,
+            };
+            let due = stream
+                .last_checkpoint
+                .is_none_or(|last| last.elapsed() >= CHECKPOINT_INTERVAL);
+            if !due {
+                return text;
+            }
+            stream.last_checkpoint = Some(Instant::now());
+            (stream.platform_message_id.clone(), text)
+        };
+        let (platform_message_id, text) = checkpoint;
+        self.write(adapter, key, &platform_message_id, &text).await;
+        text
+    }
+
+    /// Forget a stream that ended.
+    pub async fn finish(&self, adapter: &str, key: &str) {
+        self.lock().remove(&(adapter.to_string(), key.to_string()));
+        if let Err(error) =
+            sqlx::query("DELETE FROM stream_journal WHERE adapter = ? AND stream_key = ?")
+                .bind(adapter)
+                .bind(key)
+                .execute(&self.pool)
+                .await
+        {
+            tracing::warn!(%error, adapter, key, "failed to clear stream journal entry");
+        }
+    }
+
+    async fn write(&self, adapter: &str, key: &str, platform_message_id: &str, text: &str) {
+        if let Err(error) = sqlx::query(
+            "INSERT INTO stream_journal (adapter, stream_key, platform_message_id, text) \
+             VALUES (?, ?, ?, ?) \
+             ON CONFLICT (adapter, stream_key) DO UPDATE SET \
+             platform_message_id = excluded.platform_message_id, text = excluded.text, \
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        )
+        .bind(adapter)
+        .bind(key)
+        .bind(platform_message_id)
+        .bind(text)
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(%error, adapter, key, "failed to checkpoint streamed message");
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, String), ActiveStream>> {
+        self.streams
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn journal() -> StreamJournal {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations/global")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        StreamJournal::new(pool)
+    }
+
+    #[test]
+    fn resyncs_behind_the_journal_keep_the_journaled_render() {
+        assert_eq!(
+            reconcile("- [x] a\n- [ ] b", "- [x] a"),
+            ("- [x] a\n- [ ] b", false)
+        );
+        assert_eq!(
+            reconcile("- [x] a\n- [ ] b", "- [x] a\n- [x] b"),
+            ("- [x] a\n- [x] b", true)
+        );
+        assert_eq!(
+            reconcile("◐ Working… 40s", "◓ Working… 2s"),
+            ("◓ Working… 2s", true)
+        );
+    }
+
+    #[tokio::test]
+    async fn a_crashed_stream_resumes_its_message() {
+        let journal = journal().await;
+        journal
+            .begin("slack", "slack:C1:todos:w1", "1700000000.000100")
+            .await;
+        // The first checkpoint waits for the interval; force one.
+        journal
+            .lock()
+            .values_mut()
+            .for_each(|stream| stream.last_checkpoint = None);
+        let shown = journal
+            .update("slack", "slack:C1:todos:w1", "- [x] a\n- [ ] b".into())
+            .await;
+        assert_eq!(shown, "- [x] a\n- [ ] b");
+
+        // A restart loses the in-memory state but not the journal.
+        let restarted = StreamJournal::new(journal.pool.clone());
+        assert_eq!(restarted.resume("slack", "slack:C1:other").await, None);
+        assert_eq!(
+            restarted
+                .resume("slack", "slack:C1:todos:w1")
+                .await
+                .as_deref(),
+            Some("1700000000.000100")
+        );
+        let shown = restarted
+            .update("slack", "slack:C1:todos:w1", "- [x] a".into())
+            .await;
+        assert_eq!(shown, "- [x] a\n- [ ] b");
+        let shown = restarted
+            .update("slack", "slack:C1:todos:w1", "- [x] a\n- [x] b".into())
+            .await;
+        assert_eq!(shown, "- [x] a\n- [x] b");
+
+        restarted.finish("slack", "slack:C1:todos:w1").await;
+        assert_eq!(restarted.resume("slack", "slack:C1:todos:w1").await, None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Outbound link rewriting.
+//!
+//! `MessagingManager` runs every outgoing message through [`Links::apply`],
+//! which strips tracking parameters from URLs and, where the platform
+//! supports it, wraps them so no link preview is rendered. Code blocks and
+//! inline code are left untouched.
+//!
+//! [`link_file_paths`] is applied separately by the channel to tool output,
+//! turning repository-relative paths into links on the configured repo host.
+
+use crate::OutboundResponse;
+use crate::config::LinksConfig;
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+/// Query parameters that only carry attribution and never change the page.
+const TRACKING_PARAMETERS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "mc_cid", "mc_eid", "igshid",
+    "yclid", "_hsenc", "_hsmi", "mkt_tok", "ref_src",
+];
+
+static URL_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^\s<>`"|]+"#).expect("hardcoded regex"));
+
+static PATH_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(^|[\s(])((?:/|\./)?(?:[\w.-]+/)+[\w.-]+\.[A-Za-z0-9]+)(?::(\d+))?")
+        .expect("hardcoded regex")
+});
+
+static WHOLE_PATH_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:/|\./)?(?:[\w.-]+/)+[\w.-]+\.[A-Za-z0-9]+)(?::(\d+))?$")
+        .expect("hardcoded regex")
+});
+
+/// Link rewriting rules, built from `[messaging.links]`.
+pub struct Links {
+    suppress_unfurls: bool,
+    strip_tracking: bool,
+}
+
+impl Default for Links {
+    fn default() -> Self {
+        Self::new(&LinksConfig::default())
+    }
+}
+
+impl Links {
+    pub fn new(config: &LinksConfig) -> Self {
+        Self {
+            suppress_unfurls: config.suppress_unfurls,
+            strip_tracking: config.strip_tracking,
+        }
+    }
+
+    /// Rewrite the URLs in a response's text for the given platform.
+    pub fn apply(&self, platform: &str, response: OutboundResponse) -> OutboundResponse {
+        // Only Discord honours `<url>` as "no preview"; elsewhere the angle
+        // brackets would show up literally.
+        let suppress = self.suppress_unfurls && platform == "discord";
+        if !suppress && !self.strip_tracking {
+            return response;
+        }
+        let rewrite = |text: String| match self.rewrite(&text, suppress) {
+            Cow::Borrowed(_) => text,
+            Cow::Owned(rewritten) => rewritten,
+        };
+        match response {
+            OutboundResponse::Text(text) => OutboundResponse::Text(rewrite(text)),
+            OutboundResponse::StreamChunk(text) => OutboundResponse::StreamChunk(rewrite(text)),
+            OutboundResponse::ThreadReply { thread_name, text } => OutboundResponse::ThreadReply {
+                thread_name,
+                text: rewrite(text),
+            },
+            other => other,
+        }
+    }
+
+    fn rewrite<'a>(&self, text: &'a str, suppress: bool) -> Cow<'a, str> {
+        if !text.contains("http") {
+            return Cow::Borrowed(text);
+        }
+        let rewritten = rewrite_outside_code(
+            text,
+            |prose| {
+                let mut output = String::with_capacity(prose.len());
+                let mut last = 0;
+                for found in URL_PATTERN.find_iter(prose) {
+                    let url = trim_url(found.as_str());
+                    let end = found.start() + url.len();
+                    output.push_str(&prose[last..found.start()]);
+                    let url = if self.strip_tracking {
+                        strip_tracking(url)
+                    } else {
+                        Cow::Borrowed(url)
+                    };
+                    let wrapped = prose[..found.start()].ends_with('<')
+                        || prose[..found.start()].ends_with("](");
+                    if suppress && !wrapped {
+                        output.push('<');
+                        output.push_str(&url);
+                        output.push('>');
+                    } else {
+                        output.push_str(&url);
+                    }
+                    last = end;
+                }
+                output.push_str(&prose[last..]);
+                output
+            },
+            |_| None,
+        );
+        if rewritten == text {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(rewritten)
+        }
+    }
+}
+
+/// Drop sentence punctuation the URL pattern swallowed, keeping a closing
+/// parenthesis only when the URL itself opened one.
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '*']);
+        let trimmed = if trimmed.ends_with(')')
+            && trimmed.matches(')').count() > trimmed.matches('(').count()
+        {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Remove `utm_*` and known click-id parameters. The URL is returned
+/// unchanged when it has none, so its original formatting survives.
+pub fn strip_tracking(url: &str) -> Cow<'_, str> {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return Cow::Borrowed(url);
+    };
+    if parsed.query().is_none() {
+        return Cow::Borrowed(url);
+    }
+    let is_tracking = |name: &str| {
+        let name = name.to_ascii_lowercase();
+        name.starts_with("utm_") || TRACKING_PARAMETERS.contains(&name.as_str())
+    };
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    let kept: Vec<&(String, String)> = pairs
+        .iter()
+        .filter(|(name, _)| !is_tracking(name))
+        .collect();
+    if kept.len() == pairs.len() {
+        return Cow::Borrowed(url);
+    }
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed
+            .query_pairs_mut()
+            .clear()
+            .extend_pairs(kept.iter().map(|(name, value)| (name, value)));
+    }
+    Cow::Owned(parsed.into())
+}
+
+/// Turn file paths in tool output into links under `repository_url`, e.g.
+/// `src/main.rs:42` becomes `[src/main.rs:42](<base>/src/main.rs#L42)`.
+///
+/// Absolute paths are linked only when they sit inside `project_directory`.
+/// Fenced code blocks are left alone; inline code that is exactly one path
+/// becomes a link around the code span.
+pub fn link_file_paths(
+    text: &str,
+    repository_url: &str,
+    project_directory: Option<&str>,
+) -> String {
+    let base = repository_url.trim_end_matches('/');
+    let link_for = |path: &str, line: Option<&str>| -> Option<String> {
+        let relative = match path.strip_prefix('/') {
+            Some(_) => {
+                let root = project_directory?.trim_end_matches('/');
+                path.strip_prefix(root)?.strip_prefix('/')?
+            }
+            None => path.strip_prefix("./").unwrap_or(path),
+        };
+        if relative.split('/').any(|segment| segment == "..") {
+            return None;
+        }
+        Some(match line {
+            Some(line) => format!("{base}/{relative}#L{line}"),
+            None => format!("{base}/{relative}"),
+        })
+    };
+
+    rewrite_outside_code(
+        text,
+        |prose| {
+            PATH_PATTERN
+                .replace_all(prose, |captures: &regex::Captures| {
+                    let lead = &captures[1];
+                    let path = &captures[2];
+                    let line = captures.get(3).map(|line| line.as_str());
+                    let whole = &captures[0][lead.len()..];
+                    match link_for(path, line) {
+                        Some(url) => format!("{lead}[{whole}]({url})"),
+                        None => captures[0].to_string(),
+                    }
+                })
+                .into_owned()
+        },
+        |code| {
+            let captures = WHOLE_PATH_PATTERN.captures(code)?;
+            let url = link_for(&captures[1], captures.get(2).map(|line| line.as_str()))?;
+            Some(format!("[`{code}`]({url})"))
+        },
+    )
+}
+
+/// Apply `prose` to text outside code and `inline` to the contents of each
+/// inline code span (returning the replacement for the whole span, backticks
+/// included). Fenced blocks are copied through verbatim.
+fn rewrite_outside_code(
+    text: &str,
+    prose: impl Fn(&str) -> String,
+    inline: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            output.push_str(line);
+            continue;
+        }
+        if in_fence {
+            output.push_str(line);
+            continue;
+        }
+        let segments: Vec<&str> = line.split('`').collect();
+        // An odd segment count means every backtick is paired; otherwise the
+        // last backtick is unmatched and what follows it is still prose.
+        let paired = segments.len() % 2 == 1;
+        for (index, segment) in segments.iter().enumerate() {
+            let is_code = index % 2 == 1 && (paired || index < segments.len() - 1);
+            if is_code {
+                match inline(segment) {
+                    Some(replacement) => {
+                        // The opening backtick was already pushed; replace it.
+                        output.pop();
+                        output.push_str(&replacement);
+                        continue;
+                    }
+                    None => {
+                        output.push_str(segment);
+                        output.push('`');
+                        continue;
+                    }
+                }
+            }
+            output.push_str(&prose(segment));
+            if index < segments.len() - 1 {
+                output.push('`');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracking_parameters_are_stripped_and_clean_urls_untouched() {
+        assert_eq!(
+            strip_tracking("https://example.com/post?id=7&utm_source=x&fbclid=abc"),
+            "https://example.com/post?id=7"
+        );
+        assert_eq!(
+            strip_tracking("https://example.com/?utm_medium=email"),
+            "https://example.com/"
+        );
+        assert!(matches!(
+            strip_tracking("https://example.com/search?q=a+b"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn discord_urls_are_wrapped_outside_code() {
+        let links = Links::new(&LinksConfig {
+            suppress_unfurls: true,
+            strip_tracking: true,
+        });
+        let text = "See https://example.com/a?utm_source=x. Also <https://example.com/b> \
+                    and `https://example.com/c`\n```\nhttps://example.com/d\n```\n";
+        let OutboundResponse::Text(discord) =
+            links.apply("discord", OutboundResponse::Text(text.into()))
+        else {
+            panic!("expected text");
+        };
+        assert_eq!(
+            discord,
+            "See <https://example.com/a>. Also <https://example.com/b> \
+             and `https://example.com/c`\n```\nhttps://example.com/d\n```\n"
+        );
+
+        let OutboundResponse::Text(slack) = links.apply(
+            "slack",
+            OutboundResponse::Text("(https://example.com/a?gclid=1)".into()),
+        ) else {
+            panic!("expected text");
+        };
+        assert_eq!(slack, "(https://example.com/a)");
+    }
+
+    #[test]
+    fn file_paths_link_to_the_repository() {
+        let linked = link_file_paths(
+            "Edited src/main.rs:42 and `/work/repo/docs/guide.md`; skipped /etc/hosts.conf\n\
+             ```\nsrc/lib.rs\n```\n",
+            "https://github.com/acme/repo/blob/main/",
+            Some("/work/repo"),
+        );
+        assert_eq!(
+            linked,
+            "Edited [src/main.rs:42](https://github.com/acme/repo/blob/main/src/main.rs#L42) \
+             and [`/work/repo/docs/guide.md`](https://github.com/acme/repo/blob/main/docs/guide.md); \
+             skipped /etc/hosts.conf\n```\nsrc/lib.rs\n```\n"
+        );
+    }
+}
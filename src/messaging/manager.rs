@@ -1,15 +1,23 @@
 //! MessagingManager: Fan-in and routing for all adapters.
 
+use crate::messaging::code_language::annotate_response;
+use crate::messaging::continuation::split_into_parts;
+use crate::messaging::links::Links;
+use crate::messaging::overflow::{Overflow, platform_message_limit};
+use crate::messaging::quota::{OutboundOperation, OutboundTracker};
 use crate::messaging::traits::{
     BroadcastFailureKind, HistoryMessage, InboundStream, Messaging, MessagingDyn,
     broadcast_failure_kind,
 };
+use crate::secrets::redact::Redactor;
 use crate::{InboundMessage, OutboundResponse, StatusUpdate};
 
 use anyhow::Context as _;
+use arc_swap::ArcSwap;
 use futures::StreamExt as _;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::{RwLock, mpsc};
 
 /// Manages all messaging adapters with support for runtime addition.
@@ -22,6 +30,12 @@ pub struct MessagingManager {
     fan_in_tx: mpsc::Sender<InboundMessage>,
     /// Receiver side, taken once by `start()`.
     fan_in_rx: RwLock<Option<mpsc::Receiver<InboundMessage>>>,
+    /// Secret masking applied to every outbound message. Swapped on config reload.
+    redactor: ArcSwap<Redactor>,
+    /// Handling for text replies longer than the platform limit.
+    overflow: ArcSwap<Overflow>,
+    /// Tracking-parameter stripping and unfurl suppression for URLs.
+    links: ArcSwap<Links>,
 }
 
 impl MessagingManager {
@@ -31,6 +45,53 @@ impl MessagingManager {
             adapters: RwLock::new(HashMap::new()),
             fan_in_tx,
             fan_in_rx: RwLock::new(Some(fan_in_rx)),
+            redactor: ArcSwap::from_pointee(Redactor::new(
+                &crate::config::RedactionConfig::default(),
+            )),
+            overflow: ArcSwap::from_pointee(Overflow::default()),
+            links: ArcSwap::from_pointee(Links::default()),
+        }
+    }
+
+    /// Replace the outbound redaction rules.
+    pub fn set_redaction(&self, config: &crate::config::RedactionConfig) {
+        self.redactor.store(Arc::new(Redactor::new(config)));
+    }
+
+    /// Replace the overflow policy for oversized replies.
+    pub fn set_overflow(&self, config: &crate::config::OverflowConfig) {
+        self.overflow.store(Arc::new(Overflow::new(config)));
+    }
+
+    /// Replace the outbound link rewriting rules.
+    pub fn set_links(&self, config: &crate::config::LinksConfig) {
+        self.links.store(Arc::new(Links::new(config)));
+    }
+
+    /// Label unlabelled code fences, rewrite links, redact, then apply the
+    /// overflow policy for the adapter's platform. Text still over the platform's limit is
+    /// continued into labelled parts, sent in order.
+    async fn prepare_response(
+        &self,
+        adapter_key: &str,
+        response: OutboundResponse,
+    ) -> Vec<OutboundResponse> {
+        let platform = adapter_key.split(':').next().unwrap_or(adapter_key);
+        let response = self
+            .links
+            .load()
+            .apply(platform, annotate_response(response));
+        let response = self.redactor.load().redact_response(response);
+        let overflow = self.overflow.load_full();
+        match overflow.apply(platform, response).await {
+            OutboundResponse::Text(text) => match platform_message_limit(platform) {
+                Some(limit) => split_into_parts(&text, limit)
+                    .into_iter()
+                    .map(OutboundResponse::Text)
+                    .collect(),
+                None => vec![OutboundResponse::Text(text)],
+            },
+            response => vec![response],
         }
     }
 
@@ -48,6 +109,14 @@ impl MessagingManager {
         self.adapters.write().await.insert(name, adapter);
     }
 
+    /// Register an adapter that is already type-erased, e.g. one supplied by
+    /// an embedding application.
+    pub async fn register_dyn(&self, adapter: Arc<dyn MessagingDyn>) {
+        let name = adapter.name().to_string();
+        tracing::info!(adapter = %name, "registered messaging adapter (dyn)");
+        self.adapters.write().await.insert(name, adapter);
+    }
+
     /// Maximum number of retry attempts for failed adapters before giving up.
     const MAX_RETRY_ATTEMPTS: u32 = 12;
     /// Maximum number of proactive-send retry attempts for transient broadcast failures.
@@ -241,7 +310,19 @@ impl MessagingManager {
                 .with_context(|| format!("no messaging adapter named '{}'", adapter_key))?,
         );
         drop(adapters);
-        adapter.respond(message, response).await
+        let response = match response {
+            OutboundResponse::Ephemeral { text, .. } if !adapter.supports_ephemeral(message) => {
+                OutboundResponse::Text(text)
+            }
+            response => response,
+        };
+        for response in self.prepare_response(adapter_key, response).await {
+            let started = Instant::now();
+            let result = adapter.respond(message, response).await;
+            Self::record_outbound(adapter_key, OutboundOperation::Respond, started, &result);
+            result?;
+        }
+        Ok(())
     }
 
     /// Route a status update to the correct adapter.
@@ -258,7 +339,11 @@ impl MessagingManager {
                 .with_context(|| format!("no messaging adapter named '{}'", adapter_key))?,
         );
         drop(adapters);
-        adapter.send_status(message, status).await
+        let status = self.redactor.load().redact_status(status);
+        let started = Instant::now();
+        let result = adapter.send_status(message, status).await;
+        Self::record_outbound(adapter_key, OutboundOperation::Status, started, &result);
+        result
     }
 
     /// Send a message through a specific adapter without retry.
@@ -275,7 +360,13 @@ impl MessagingManager {
                 .cloned()
                 .with_context(|| format!("no messaging adapter named '{adapter_name}'"))?
         };
-        adapter.broadcast(target, response).await
+        for response in self.prepare_response(adapter_name, response).await {
+            let started = Instant::now();
+            let result = adapter.broadcast(target, response).await;
+            Self::record_outbound(adapter_name, OutboundOperation::Broadcast, started, &result);
+            result?;
+        }
+        Ok(())
     }
 
     /// Send a proactive message through a specific adapter with bounded retry/backoff.
@@ -292,10 +383,26 @@ impl MessagingManager {
                 .cloned()
                 .with_context(|| format!("no messaging adapter named '{adapter_name}'"))?
         };
+        for response in self.prepare_response(adapter_name, response).await {
+            Self::broadcast_with_retry(adapter.as_ref(), adapter_name, target, response).await?;
+        }
+        Ok(())
+    }
+
+    /// Broadcast one message, retrying transient failures with backoff.
+    async fn broadcast_with_retry(
+        adapter: &dyn MessagingDyn,
+        adapter_name: &str,
+        target: &str,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
         let mut delay = Self::BROADCAST_INITIAL_RETRY_DELAY;
 
         for attempt in 1..=Self::MAX_BROADCAST_RETRY_ATTEMPTS {
-            match adapter.broadcast(target, response.clone()).await {
+            let started = Instant::now();
+            let result = adapter.broadcast(target, response.clone()).await;
+            Self::record_outbound(adapter_name, OutboundOperation::Broadcast, started, &result);
+            match result {
                 Ok(()) => {
                     if attempt > 1 {
                         tracing::info!(
@@ -358,12 +465,61 @@ impl MessagingManager {
                 .with_context(|| format!("no messaging adapter named '{}'", adapter_key))?,
         );
         drop(adapters);
-        adapter.fetch_history(message, limit).await
+        let started = Instant::now();
+        let result = adapter.fetch_history(message, limit).await;
+        Self::record_outbound(
+            adapter_key,
+            OutboundOperation::FetchHistory,
+            started,
+            &result,
+        );
+        result
+    }
+
+    /// Rename the thread a message was posted in, on platforms with named
+    /// threads.
+    pub async fn rename_thread(&self, message: &InboundMessage, name: &str) -> crate::Result<()> {
+        let adapters = self.adapters.read().await;
+        let adapter_key = message.adapter_key();
+        let adapter = Arc::clone(
+            adapters
+                .get(adapter_key)
+                .with_context(|| format!("no messaging adapter named '{}'", adapter_key))?,
+        );
+        drop(adapters);
+        let started = Instant::now();
+        let result = adapter.rename_thread(message, name).await;
+        Self::record_outbound(
+            adapter_key,
+            OutboundOperation::RenameThread,
+            started,
+            &result,
+        );
+        result
+    }
+
+    /// Record latency and outcome of an outbound platform call for quota tracking.
+    fn record_outbound<T>(
+        adapter: &str,
+        operation: OutboundOperation,
+        started: Instant,
+        result: &crate::Result<T>,
+    ) {
+        let latency = started.elapsed();
+        tracing::trace!(
+            adapter,
+            operation = operation.as_str(),
+            latency_ms = latency.as_millis() as u64,
+            success = result.is_ok(),
+            "outbound platform request"
+        );
+        OutboundTracker::global().record_call(adapter, operation, latency, result.as_ref().err());
     }
 
     /// Remove and shut down a single adapter by name.
     pub async fn remove_adapter(&self, name: &str) -> crate::Result<()> {
         let adapter = self.adapters.write().await.remove(name);
+        OutboundTracker::global().forget(name);
         if let Some(adapter) = adapter {
             adapter.shutdown().await?;
             tracing::info!(adapter = %name, "adapter removed and shut down");
@@ -586,4 +742,22 @@ mod tests {
         assert!(error.to_string().contains("invalid broadcast target"));
         assert_eq!(adapter.attempts(), 1);
     }
+
+    #[tokio::test]
+    async fn replies_over_the_platform_limit_continue_in_parts() {
+        let manager = MessagingManager::new();
+        let adapter = TestMessagingAdapter::new("twitch", Vec::new());
+        manager.register(adapter.clone()).await;
+
+        manager
+            .broadcast(
+                "twitch",
+                "target",
+                OutboundResponse::Text("word ".repeat(250)),
+            )
+            .await
+            .expect("every part is sent");
+
+        assert_eq!(adapter.attempts(), 3);
+    }
 }
@@ -2,6 +2,8 @@
 
 use crate::config::MattermostPermissions;
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
+use crate::messaging::journal;
+use crate::messaging::quota::OutboundTracker;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
@@ -127,6 +129,15 @@ impl MattermostAdapter {
         })
     }
 
+    /// Feed Mattermost's `X-Ratelimit-*` headers into the outbound quota tracker.
+    fn record_rate_limit(&self, response: &reqwest::Response) {
+        OutboundTracker::global().record_headers(
+            &self.runtime_key,
+            response.status(),
+            response.headers(),
+        );
+    }
+
     fn api_url(&self, path: &str) -> Url {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
@@ -262,6 +273,7 @@ impl MattermostAdapter {
             .send()
             .await
             .context("failed to create post")?;
+        self.record_rate_limit(&response);
 
         let status = response.status();
         if !status.is_success() {
@@ -296,6 +308,7 @@ impl MattermostAdapter {
             .send()
             .await
             .context("failed to edit post")?;
+        self.record_rate_limit(&response);
 
         let status = response.status();
         if !status.is_success() {
@@ -310,6 +323,87 @@ impl MattermostAdapter {
         Ok(())
     }
 
+    /// Upload a file to `channel_id` and post it with `caption`, inside the
+    /// thread at `root_id` if given.
+    async fn upload_file(
+        &self,
+        channel_id: &str,
+        root_id: Option<&str>,
+        filename: String,
+        data: Vec<u8>,
+        mime_type: &str,
+        caption: Option<String>,
+    ) -> crate::Result<()> {
+        Self::validate_id(channel_id)?;
+        if let Some(rid) = root_id {
+            Self::validate_id(rid)?;
+        }
+        if data.len() > self.max_attachment_bytes {
+            return Err(anyhow::anyhow!(
+                "file too large: {} bytes (max: {})",
+                data.len(),
+                self.max_attachment_bytes
+            )
+            .into());
+        }
+
+        let part = reqwest::multipart::Part::bytes(data)
+            .file_name(filename)
+            .mime_str(mime_type)
+            .context("invalid mime type")?;
+        let form = reqwest::multipart::Form::new()
+            .part("files", part)
+            .text("channel_id", channel_id.to_string());
+        let upload_response = self
+            .client
+            .post(self.api_url("/files"))
+            .bearer_auth(self.token.as_ref())
+            .multipart(form)
+            .send()
+            .await
+            .context("failed to upload file")?;
+        self.record_rate_limit(&upload_response);
+        let upload_status = upload_response.status();
+        if !upload_status.is_success() {
+            let body = upload_response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "mattermost file upload failed with status {}: {body}",
+                upload_status.as_u16()
+            )
+            .into());
+        }
+        let upload: MattermostFileUpload = upload_response
+            .json()
+            .await
+            .context("failed to parse file upload response")?;
+
+        let file_ids: Vec<_> = upload.file_infos.iter().map(|f| f.id.as_str()).collect();
+        let post_response = self
+            .client
+            .post(self.api_url("/posts"))
+            .bearer_auth(self.token.as_ref())
+            .json(&serde_json::json!({
+                "channel_id": channel_id,
+                "message": caption.unwrap_or_default(),
+                "file_ids": file_ids,
+                "root_id": root_id.unwrap_or(""),
+            }))
+            .send()
+            .await
+            .context("failed to create post with file")?;
+        self.record_rate_limit(&post_response);
+        let post_status = post_response.status();
+        if !post_status.is_success() {
+            let body = post_response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "mattermost POST /posts (file) failed with status {}: {body}",
+                post_status.as_u16()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     /// Fetch up to `limit` posts from `channel_id`, sorted by creation time.
     ///
     /// Pass `before_post_id` to retrieve posts that appeared before a specific
@@ -342,6 +436,7 @@ impl MattermostAdapter {
             .send()
             .await
             .context("failed to fetch channel posts")?;
+        self.record_rate_limit(&response);
 
         let status = response.status();
         if !status.is_success() {
@@ -384,6 +479,7 @@ impl MattermostAdapter {
             .send()
             .await
             .context("failed to create DM channel")?;
+        self.record_rate_limit(&response);
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
@@ -632,6 +728,8 @@ impl Messaging for MattermostAdapter {
                         );
                     }
                 }
+                crate::notifications::ops::OpsAlerts::global()
+                    .reconnect(&format!("{runtime_key} adapter"));
 
                 tokio::select! {
                     _ = tokio::time::sleep(retry_delay) => {
@@ -659,51 +757,55 @@ impl Messaging for MattermostAdapter {
         let channel_id = self.extract_channel_id(message)?;
 
         match response {
-            OutboundResponse::Text(text) => {
+            // Mattermost renders markdown and has no ephemeral or scheduled
+            // posts for bots, so the rich and ephemeral variants post their text.
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
                 self.stop_typing(channel_id).await;
-                // Use root_id for threading: prefer mattermost_root_id (when triggered from a
-                // threaded message) or REPLY_TO_MESSAGE_ID (set by channel.rs for branch/worker
-                // replies).
-                let root_id = message
-                    .metadata
-                    .get("mattermost_root_id")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| {
-                        message
-                            .metadata
-                            .get(crate::metadata_keys::REPLY_TO_MESSAGE_ID)
-                            .and_then(|v| v.as_str())
-                    });
+                let root_id = reply_root_id(message);
+                for chunk in split_message(&text, MAX_MESSAGE_LENGTH) {
+                    self.create_post(channel_id, &chunk, root_id).await?;
+                }
+            }
 
+            OutboundResponse::ThreadReply { text, .. } => {
+                self.stop_typing(channel_id).await;
+                // Continue the thread the message is in, or start one under it.
+                let root_id = reply_root_id(message).or_else(|| {
+                    message
+                        .metadata
+                        .get("mattermost_post_id")
+                        .and_then(|v| v.as_str())
+                });
                 for chunk in split_message(&text, MAX_MESSAGE_LENGTH) {
                     self.create_post(channel_id, &chunk, root_id).await?;
                 }
             }
 
             OutboundResponse::StreamStart => {
-                let root_id = message
-                    .metadata
-                    .get("mattermost_root_id")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| {
-                        message
-                            .metadata
-                            .get(crate::metadata_keys::REPLY_TO_MESSAGE_ID)
-                            .and_then(|v| v.as_str())
-                    });
+                let root_id = reply_root_id(message);
                 self.start_typing(channel_id).await;
-                // Create a placeholder post with a zero-width space.
-                let post = match self.create_post(channel_id, "\u{200B}", root_id).await {
-                    Ok(p) => p,
-                    Err(error) => {
-                        self.stop_typing(channel_id).await;
-                        return Err(error);
+                let post_id = match journal::resume_stream(&self.runtime_key, message).await {
+                    Some(post_id) => post_id,
+                    None => {
+                        // Create a placeholder post with a zero-width space.
+                        let post = match self.create_post(channel_id, "\u{200B}", root_id).await {
+                            Ok(p) => p,
+                            Err(error) => {
+                                self.stop_typing(channel_id).await;
+                                return Err(error);
+                            }
+                        };
+                        journal::begin_stream(&self.runtime_key, message, &post.id).await;
+                        post.id
                     }
                 };
                 self.active_messages.write().await.insert(
                     message.id.clone(),
                     ActiveStream {
-                        post_id: post.id.into(),
+                        post_id: post_id.into(),
                         channel_id: channel_id.to_string().into(),
                         last_edit: Instant::now(),
                         accumulated_text: String::new(),
@@ -712,10 +814,12 @@ impl Messaging for MattermostAdapter {
             }
 
             OutboundResponse::StreamChunk(chunk) => {
+                let chunk = journal::update_stream(&self.runtime_key, message, chunk).await;
                 let pending_edit = {
                     let mut active_messages = self.active_messages.write().await;
                     if let Some(active) = active_messages.get_mut(&message.id) {
-                        active.accumulated_text.push_str(&chunk);
+                        // Chunks carry the full text so far, not a delta.
+                        active.accumulated_text = chunk;
 
                         if active.last_edit.elapsed() > STREAM_EDIT_THROTTLE {
                             let display_text = if active.accumulated_text.len() > MAX_MESSAGE_LENGTH
@@ -745,16 +849,8 @@ impl Messaging for MattermostAdapter {
 
             OutboundResponse::StreamEnd => {
                 self.stop_typing(channel_id).await;
-                let root_id = message
-                    .metadata
-                    .get("mattermost_root_id")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| {
-                        message
-                            .metadata
-                            .get(crate::metadata_keys::REPLY_TO_MESSAGE_ID)
-                            .and_then(|v| v.as_str())
-                    });
+                let root_id = reply_root_id(message);
+                journal::finish_stream(&self.runtime_key, message).await;
                 if let Some(active) = self.active_messages.write().await.remove(&message.id) {
                     let chunks = split_message(&active.accumulated_text, MAX_MESSAGE_LENGTH);
                     let mut first = true;
@@ -804,6 +900,7 @@ impl Messaging for MattermostAdapter {
                     .send()
                     .await
                     .context("failed to add reaction")?;
+                self.record_rate_limit(&response);
 
                 if !response.status().is_success() {
                     tracing::warn!(
@@ -814,89 +911,53 @@ impl Messaging for MattermostAdapter {
                 }
             }
 
-            OutboundResponse::File {
-                filename,
-                data,
-                mime_type,
-                caption,
-            } => {
-                if data.len() > self.max_attachment_bytes {
-                    return Err(anyhow::anyhow!(
-                        "file too large: {} bytes (max: {})",
-                        data.len(),
-                        self.max_attachment_bytes
-                    )
-                    .into());
-                }
-
-                let part = reqwest::multipart::Part::bytes(data)
-                    .file_name(filename.clone())
-                    .mime_str(&mime_type)
-                    .context("invalid mime type")?;
+            OutboundResponse::RemoveReaction(emoji) => {
+                let post_id = message
+                    .metadata
+                    .get("mattermost_post_id")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("missing mattermost_post_id metadata"))?;
+                Self::validate_id(post_id)?;
+                let emoji_name = sanitize_reaction_name(&emoji);
 
-                let form = reqwest::multipart::Form::new()
-                    .part("files", part)
-                    .text("channel_id", channel_id.to_string());
+                let bot_user_id = self
+                    .bot_user_id
+                    .get()
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("bot_user_id not initialized; call start() first")
+                    })?
+                    .as_ref()
+                    .to_string();
 
                 let response = self
                     .client
-                    .post(self.api_url("/files"))
+                    .delete(self.api_url(&format!(
+                        "/users/{bot_user_id}/posts/{post_id}/reactions/{emoji_name}"
+                    )))
                     .bearer_auth(self.token.as_ref())
-                    .multipart(form)
                     .send()
                     .await
-                    .context("failed to upload file")?;
+                    .context("failed to remove reaction")?;
+                self.record_rate_limit(&response);
 
                 if !response.status().is_success() {
-                    let body = response.text().await.unwrap_or_default();
-                    return Err(anyhow::anyhow!("mattermost file upload failed: {body}").into());
-                }
-
-                let upload: MattermostFileUpload = response
-                    .json()
-                    .await
-                    .context("failed to parse file upload response")?;
-
-                let file_ids: Vec<_> = upload.file_infos.iter().map(|f| f.id.as_str()).collect();
-                let root_id = message
-                    .metadata
-                    .get("mattermost_root_id")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| {
-                        message
-                            .metadata
-                            .get(crate::metadata_keys::REPLY_TO_MESSAGE_ID)
-                            .and_then(|v| v.as_str())
-                    });
-                let post_response = self
-                    .client
-                    .post(self.api_url("/posts"))
-                    .bearer_auth(self.token.as_ref())
-                    .json(&serde_json::json!({
-                        "channel_id": channel_id,
-                        "message": caption.unwrap_or_default(),
-                        "file_ids": file_ids,
-                        "root_id": root_id.unwrap_or(""),
-                    }))
-                    .send()
-                    .await
-                    .context("failed to create post with file")?;
-                let post_status = post_response.status();
-                if !post_status.is_success() {
-                    let body = post_response.text().await.unwrap_or_default();
-                    return Err(anyhow::anyhow!(
-                        "mattermost POST /posts (file) failed with status {}: {body}",
-                        post_status.as_u16()
-                    )
-                    .into());
+                    tracing::warn!(
+                        status = %response.status(),
+                        emoji = %emoji_name,
+                        "failed to remove reaction"
+                    );
                 }
             }
 
-            _ => {
-                tracing::debug!(
-                    ?response,
-                    "mattermost adapter does not support this response type"
-                );
+            OutboundResponse::File {
+                filename,
+                data,
+                mime_type,
+                caption,
+            } => {
+                let root_id = reply_root_id(message);
+                self.upload_file(channel_id, root_id, filename, data, &mime_type, caption)
+                    .await?;
             }
         }
 
@@ -1012,7 +1073,7 @@ impl Messaging for MattermostAdapter {
         };
 
         match response {
-            OutboundResponse::Text(text) => {
+            OutboundResponse::Text(text) | OutboundResponse::RichMessage { text, .. } => {
                 for chunk in split_message(&text, MAX_MESSAGE_LENGTH) {
                     self.create_post(target, &chunk, None).await?;
                 }
@@ -1023,64 +1084,8 @@ impl Messaging for MattermostAdapter {
                 mime_type,
                 caption,
             } => {
-                if data.len() > self.max_attachment_bytes {
-                    return Err(anyhow::anyhow!(
-                        "file too large: {} bytes (max: {})",
-                        data.len(),
-                        self.max_attachment_bytes
-                    )
-                    .into());
-                }
-                let part = reqwest::multipart::Part::bytes(data)
-                    .file_name(filename)
-                    .mime_str(&mime_type)
-                    .context("invalid mime type")?;
-                let form = reqwest::multipart::Form::new()
-                    .part("files", part)
-                    .text("channel_id", target.to_string());
-                let upload_response = self
-                    .client
-                    .post(self.api_url("/files"))
-                    .bearer_auth(self.token.as_ref())
-                    .multipart(form)
-                    .send()
-                    .await
-                    .context("failed to upload file")?;
-                let upload_status = upload_response.status();
-                if !upload_status.is_success() {
-                    let body = upload_response.text().await.unwrap_or_default();
-                    return Err(anyhow::anyhow!(
-                        "mattermost file upload failed with status {}: {body}",
-                        upload_status.as_u16()
-                    )
-                    .into());
-                }
-                let upload: MattermostFileUpload = upload_response
-                    .json()
-                    .await
-                    .context("failed to parse file upload response")?;
-                let file_ids: Vec<_> = upload.file_infos.iter().map(|f| f.id.as_str()).collect();
-                let post_response = self
-                    .client
-                    .post(self.api_url("/posts"))
-                    .bearer_auth(self.token.as_ref())
-                    .json(&serde_json::json!({
-                        "channel_id": target,
-                        "message": caption.unwrap_or_default(),
-                        "file_ids": file_ids,
-                    }))
-                    .send()
-                    .await
-                    .context("failed to create post with file")?;
-                let post_status = post_response.status();
-                if !post_status.is_success() {
-                    let body = post_response.text().await.unwrap_or_default();
-                    return Err(anyhow::anyhow!(
-                        "mattermost create post with file failed with status {}: {body}",
-                        post_status.as_u16()
-                    )
-                    .into());
-                }
+                self.upload_file(target, None, filename, data, &mime_type, caption)
+                    .await?;
             }
             other => {
                 tracing::debug!(
@@ -1467,6 +1472,21 @@ async fn resolve_root_post_author(
     Some(post.user_id)
 }
 
+/// Thread a reply belongs in: the thread the inbound post was in, or the
+/// post channel.rs asked to reply to (branch and worker results).
+fn reply_root_id(message: &InboundMessage) -> Option<&str> {
+    message
+        .metadata
+        .get("mattermost_root_id")
+        .and_then(|v| v.as_str())
+        .or_else(|| {
+            message
+                .metadata
+                .get(crate::metadata_keys::REPLY_TO_MESSAGE_ID)
+                .and_then(|v| v.as_str())
+        })
+}
+
 /// Convert an emoji input to a Mattermost reaction short-code name.
 ///
 /// Handles three input forms:
@@ -1782,6 +1802,30 @@ mod tests {
         assert!(msg.metadata.contains_key(crate::metadata_keys::MESSAGE_ID));
     }
 
+    // --- threading ---
+
+    #[test]
+    fn replies_stay_in_the_inbound_thread() {
+        let mut p = post("user1", "chan1", None);
+        p.root_id = "root1".into();
+        let msg =
+            build_message_from_mattermost_post(&p, "bot", Some("team1"), &no_filters()).unwrap();
+        assert_eq!(reply_root_id(&msg), Some("root1"));
+    }
+
+    #[test]
+    fn top_level_replies_use_reply_to_message_id() {
+        let p = post("user1", "chan1", None);
+        let mut msg =
+            build_message_from_mattermost_post(&p, "bot", Some("team1"), &no_filters()).unwrap();
+        assert_eq!(reply_root_id(&msg), None);
+        msg.metadata.insert(
+            crate::metadata_keys::REPLY_TO_MESSAGE_ID.into(),
+            serde_json::json!("post1"),
+        );
+        assert_eq!(reply_root_id(&msg), Some("post1"));
+    }
+
     // --- FN4: bot mention detection ---
 
     #[test]
@@ -0,0 +1,275 @@
+//! Overflow handling for replies longer than a platform's message limit.
+//!
+//! By default adapters split long text into several messages. With an
+//! overflow backend configured, `MessagingManager` instead sends a short
+//! preview and moves the full text into a file attachment or a paste
+//! service link.
+
+use crate::OutboundResponse;
+use crate::config::{OverflowConfig, OverflowMode};
+
+use anyhow::Context as _;
+
+/// Per-message character limits for platforms that enforce one. Mirrors the
+/// split lengths each adapter uses.
+pub fn platform_message_limit(platform: &str) -> Option<usize> {
+    match platform {
+        "discord" => Some(2_000),
+        "telegram" => Some(4_096),
+        "slack" => Some(12_000),
+        "mattermost" => Some(16_383),
+        "twitch" => Some(500),
+        _ => None,
+    }
+}
+
+/// Whether the platform can deliver `OutboundResponse::File` as a real file.
+fn platform_supports_files(platform: &str) -> bool {
+    !matches!(platform, "twitch")
+}
+
+/// Where the full text of an oversized reply goes.
+pub enum OverflowBackend {
+    /// Attach the full text to the message as `response.md`.
+    FileAttachment,
+    /// POST the full text to a paste service and link the returned URL.
+    HttpUpload(HttpUploader),
+}
+
+/// Client for a paste service that accepts a `text/plain` POST body.
+pub struct HttpUploader {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    url_field: Option<String>,
+}
+
+impl HttpUploader {
+    pub fn new(url: String, token: Option<String>, url_field: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            url,
+            token,
+            url_field,
+        }
+    }
+
+    /// Upload `content` and return the public URL.
+    pub async fn upload(&self, content: &str) -> anyhow::Result<String> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(content.to_string());
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("paste upload request failed")?
+            .error_for_status()
+            .context("paste service rejected upload")?;
+        let body = response
+            .text()
+            .await
+            .context("failed to read paste response")?;
+
+        parse_paste_response(&body, self.url_field.as_deref())
+    }
+}
+
+/// Extract the paste URL from a response body, either a JSON field or the
+/// trimmed body itself.
+fn parse_paste_response(body: &str, url_field: Option<&str>) -> anyhow::Result<String> {
+    let url = match url_field {
+        Some(field) => {
+            let json: serde_json::Value =
+                serde_json::from_str(body).context("paste response is not JSON")?;
+            json.get(field)
+                .and_then(|value| value.as_str())
+                .with_context(|| format!("paste response has no string field '{field}'"))?
+                .trim()
+                .to_string()
+        }
+        None => body.trim().to_string(),
+    };
+
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        anyhow::bail!("paste response is not a URL: {url}");
+    }
+    Ok(url)
+}
+
+/// Overflow policy applied to outbound replies.
+#[derive(Default)]
+pub struct Overflow {
+    backend: Option<OverflowBackend>,
+    preview_chars: usize,
+}
+
+impl Overflow {
+    pub fn new(config: &OverflowConfig) -> Self {
+        let backend = match config.mode {
+            OverflowMode::Split => None,
+            OverflowMode::File => Some(OverflowBackend::FileAttachment),
+            OverflowMode::Paste => match &config.paste_url {
+                Some(url) => Some(OverflowBackend::HttpUpload(HttpUploader::new(
+                    url.clone(),
+                    config.paste_token.clone(),
+                    config.paste_url_field.clone(),
+                ))),
+                None => {
+                    tracing::warn!("overflow mode is paste but no paste_url is set, splitting");
+                    None
+                }
+            },
+        };
+        Self {
+            backend,
+            preview_chars: config.preview_chars,
+        }
+    }
+
+    /// Rewrite an oversized text reply for `platform`. Anything that fits,
+    /// or that isn't plain text, is returned unchanged.
+    pub async fn apply(&self, platform: &str, response: OutboundResponse) -> OutboundResponse {
+        let Some(backend) = &self.backend else {
+            return response;
+        };
+        let Some(limit) = platform_message_limit(platform) else {
+            return response;
+        };
+        let OutboundResponse::Text(text) = response else {
+            return response;
+        };
+        if text.chars().count() <= limit {
+            return OutboundResponse::Text(text);
+        }
+
+        // Leave room for the trailer line under the platform limit.
+        let preview = preview(&text, self.preview_chars.min(limit.saturating_sub(120)));
+        let total_chars = text.chars().count();
+
+        if let OverflowBackend::HttpUpload(uploader) = backend {
+            match uploader.upload(&text).await {
+                Ok(url) => {
+                    return OutboundResponse::Text(format!(
+                        "{preview}\n\n… full response ({total_chars} chars): {url}"
+                    ));
+                }
+                Err(error) => {
+                    tracing::warn!(%error, platform, "paste upload failed, attaching as file");
+                }
+            }
+        }
+
+        if !platform_supports_files(platform) {
+            return OutboundResponse::Text(text);
+        }
+
+        OutboundResponse::File {
+            filename: "response.md".into(),
+            data: text.into_bytes(),
+            mime_type: "text/markdown".into(),
+            caption: Some(format!(
+                "{preview}\n\n… full response ({total_chars} chars) attached"
+            )),
+        }
+    }
+}
+
+/// First `max_chars` of `text`, cut back to the last line break when one is
+/// reasonably close so code blocks and lists aren't sliced mid-line.
+fn preview(text: &str, max_chars: usize) -> String {
+    let end = match text.char_indices().nth(max_chars) {
+        Some((index, _)) => index,
+        None => return text.to_string(),
+    };
+    let head = &text[..end];
+    let cut = match head.rfind('\n') {
+        Some(newline) if newline >= end / 2 => newline,
+        _ => end,
+    };
+    let mut preview = head[..cut].trim_end().to_string();
+    // An unterminated code fence would swallow the trailer line.
+    if preview.matches("```").count() % 2 == 1 {
+        preview.push_str("\n```");
+    }
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_overflow() -> Overflow {
+        Overflow::new(&OverflowConfig {
+            mode: OverflowMode::File,
+            ..OverflowConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn short_replies_pass_through() {
+        let response = file_overflow()
+            .apply("discord", OutboundResponse::Text("hello".into()))
+            .await;
+        assert!(matches!(response, OutboundResponse::Text(text) if text == "hello"));
+    }
+
+    #[tokio::test]
+    async fn long_replies_become_file_attachments() {
+        let text = "line\n".repeat(1_000);
+        let response = file_overflow()
+            .apply("discord", OutboundResponse::Text(text.clone()))
+            .await;
+        let OutboundResponse::File { data, caption, .. } = response else {
+            panic!("expected a file attachment");
+        };
+        assert_eq!(data, text.into_bytes());
+        let caption = caption.unwrap_or_default();
+        assert!(caption.len() < 2_000);
+        assert!(caption.contains("5000 chars"));
+    }
+
+    #[tokio::test]
+    async fn split_mode_and_unlimited_platforms_are_untouched() {
+        let text = "x".repeat(5_000);
+        let split = Overflow::new(&OverflowConfig::default())
+            .apply("discord", OutboundResponse::Text(text.clone()))
+            .await;
+        assert!(matches!(split, OutboundResponse::Text(_)));
+
+        let email = file_overflow()
+            .apply("email", OutboundResponse::Text(text))
+            .await;
+        assert!(matches!(email, OutboundResponse::Text(_)));
+    }
+
+    #[test]
+    fn preview_closes_open_code_fences() {
+        let text = format!("intro\n```rust\n{}", "let x = 1;\n".repeat(100));
+        let preview = preview(&text, 60);
+        assert!(preview.ends_with("```"));
+        assert_eq!(preview.matches("```").count(), 2);
+    }
+
+    #[test]
+    fn paste_responses_parse_as_text_or_json() {
+        assert_eq!(
+            parse_paste_response("https://paste.example/abc\n", None).unwrap(),
+            "https://paste.example/abc"
+        );
+        assert_eq!(
+            parse_paste_response(r#"{"link":"https://paste.example/xyz"}"#, Some("link")).unwrap(),
+            "https://paste.example/xyz"
+        );
+        assert!(parse_paste_response("<html>error</html>", None).is_err());
+    }
+}
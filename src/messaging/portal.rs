@@ -5,7 +5,7 @@
 //! and outbound responses are delivered through the global SSE event bus — the same
 //! path used by all other channels. No per-session SSE streams or dedup needed.
 
-use crate::api::ApiEvent;
+use crate::api::{ApiEvent, EventBus};
 use crate::conversation::ConversationLogger;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, OutboundResponse};
@@ -14,7 +14,6 @@ use anyhow::Context as _;
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::broadcast;
 
 /// Portal adapter. Inbound arrives via `inject_message`, outbound is handled
 /// by the global SSE event bus in main.rs.
@@ -22,7 +21,7 @@ pub struct PortalAdapter {
     conversation_loggers: HashMap<String, ConversationLogger>,
     /// SSE event bus for delivering broadcast messages (cron, etc.) to the
     /// portal frontend. Set after construction via `set_event_tx`.
-    event_tx: std::sync::RwLock<Option<broadcast::Sender<ApiEvent>>>,
+    event_tx: std::sync::RwLock<Option<EventBus>>,
 }
 
 impl Default for PortalAdapter {
@@ -45,7 +44,7 @@ impl PortalAdapter {
 
     /// Provide the SSE event bus sender so `broadcast` can push messages to
     /// connected portal clients.
-    pub fn set_event_tx(&self, tx: broadcast::Sender<ApiEvent>) {
+    pub fn set_event_tx(&self, tx: EventBus) {
         *self.event_tx.write().unwrap() = Some(tx);
     }
 }
@@ -0,0 +1,465 @@
+//! Outbound platform request tracing and rate-limit quota tracking.
+
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Global tracker instance. Adapters that talk to platform APIs directly
+/// report rate-limit headers here; the messaging manager records every
+/// outbound call it routes.
+static TRACKER: LazyLock<OutboundTracker> = LazyLock::new(OutboundTracker::new);
+
+/// Remaining-quota ratio at or below which an adapter is reported as near
+/// its platform limit.
+pub const QUOTA_WARNING_RATIO: f64 = 0.1;
+
+/// Outbound calls slower than this are logged as likely platform throttling.
+/// SDK-backed adapters (serenity, slack-morphism) queue requests internally
+/// when rate limited, so latency is the only signal we get from them.
+pub const SLOW_OUTBOUND_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// Outbound operation routed through the messaging manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutboundOperation {
+    Respond,
+    Status,
+    Broadcast,
+    FetchHistory,
+    RenameThread,
+}
+
+impl OutboundOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Respond => "respond",
+            Self::Status => "status",
+            Self::Broadcast => "broadcast",
+            Self::FetchHistory => "fetch_history",
+            Self::RenameThread => "rename_thread",
+        }
+    }
+}
+
+/// Rate-limit state parsed from platform response headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitHeaders {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset_after: Option<Duration>,
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimitHeaders {
+    /// Parse the common rate-limit header families.
+    ///
+    /// Covers `X-RateLimit-*` (Discord, Mattermost, GitHub-style) plus the
+    /// standard `Retry-After`. Header names are matched case-insensitively
+    /// by `HeaderMap`. Returns `None` when no rate-limit headers are present.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<f64>().ok())
+        };
+
+        let limit = number("x-ratelimit-limit").map(|value| value as u64);
+        let remaining = number("x-ratelimit-remaining").map(|value| value as u64);
+        let reset_after = number("x-ratelimit-reset-after")
+            .or_else(|| {
+                // Mattermost sends seconds-until-reset here; GitHub sends an
+                // epoch timestamp. Treat large values as epoch seconds.
+                number("x-ratelimit-reset").map(|value| {
+                    if value > 1_000_000_000.0 {
+                        (value - chrono::Utc::now().timestamp() as f64).max(0.0)
+                    } else {
+                        value
+                    }
+                })
+            })
+            .map(Duration::from_secs_f64);
+        let retry_after = number("retry-after").map(Duration::from_secs_f64);
+
+        if limit.is_none() && remaining.is_none() && reset_after.is_none() && retry_after.is_none()
+        {
+            return None;
+        }
+
+        Some(Self {
+            limit,
+            remaining,
+            reset_after,
+            retry_after,
+        })
+    }
+
+    /// Fraction of the quota still available, when the platform reports both
+    /// the limit and the remaining count.
+    pub fn remaining_ratio(&self) -> Option<f64> {
+        match (self.limit, self.remaining) {
+            (Some(limit), Some(remaining)) if limit > 0 => Some(remaining as f64 / limit as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Point-in-time view of one adapter's outbound traffic and quota.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct AdapterQuotaSnapshot {
+    pub adapter: String,
+    pub requests_total: u64,
+    pub errors_total: u64,
+    pub rate_limited_total: u64,
+    pub slow_requests_total: u64,
+    pub last_latency_ms: Option<u64>,
+    pub average_latency_ms: Option<u64>,
+    pub max_latency_ms: Option<u64>,
+    pub requests_by_operation: HashMap<String, u64>,
+    pub rate_limit_limit: Option<u64>,
+    pub rate_limit_remaining: Option<u64>,
+    pub rate_limit_reset_after_secs: Option<f64>,
+    /// Seconds since the last rate-limit headers were observed.
+    pub rate_limit_observed_secs_ago: Option<u64>,
+    /// True when remaining quota is at or below [`QUOTA_WARNING_RATIO`].
+    pub near_limit: bool,
+}
+
+#[derive(Debug, Default)]
+struct AdapterQuota {
+    requests_total: u64,
+    errors_total: u64,
+    rate_limited_total: u64,
+    slow_requests_total: u64,
+    latency_total: Duration,
+    last_latency: Option<Duration>,
+    max_latency: Option<Duration>,
+    requests_by_operation: HashMap<OutboundOperation, u64>,
+    rate_limit: Option<RateLimitHeaders>,
+    rate_limit_observed_at: Option<Instant>,
+    /// Set once the near-limit warning has fired, cleared when quota recovers
+    /// so each exhaustion window alerts exactly once.
+    near_limit_alerted: bool,
+}
+
+impl AdapterQuota {
+    fn is_near_limit(&self) -> bool {
+        self.rate_limit
+            .as_ref()
+            .and_then(RateLimitHeaders::remaining_ratio)
+            .is_some_and(|ratio| ratio <= QUOTA_WARNING_RATIO)
+    }
+
+    fn snapshot(&self, adapter: &str) -> AdapterQuotaSnapshot {
+        let average_latency_ms = (self.requests_total > 0).then(|| {
+            let average_nanos = self.latency_total.as_nanos() / u128::from(self.requests_total);
+            u64::try_from(average_nanos / 1_000_000).unwrap_or(u64::MAX)
+        });
+        let rate_limit = self.rate_limit.as_ref();
+
+        AdapterQuotaSnapshot {
+            adapter: adapter.to_string(),
+            requests_total: self.requests_total,
+            errors_total: self.errors_total,
+            rate_limited_total: self.rate_limited_total,
+            slow_requests_total: self.slow_requests_total,
+            last_latency_ms: self.last_latency.map(|latency| latency.as_millis() as u64),
+            average_latency_ms,
+            max_latency_ms: self.max_latency.map(|latency| latency.as_millis() as u64),
+            requests_by_operation: self
+                .requests_by_operation
+                .iter()
+                .map(|(operation, count)| (operation.as_str().to_string(), *count))
+                .collect(),
+            rate_limit_limit: rate_limit.and_then(|headers| headers.limit),
+            rate_limit_remaining: rate_limit.and_then(|headers| headers.remaining),
+            rate_limit_reset_after_secs: rate_limit
+                .and_then(|headers| headers.reset_after)
+                .map(|reset| reset.as_secs_f64()),
+            rate_limit_observed_secs_ago: self
+                .rate_limit_observed_at
+                .map(|observed| observed.elapsed().as_secs()),
+            near_limit: self.is_near_limit(),
+        }
+    }
+}
+
+/// Per-adapter outbound request accounting.
+///
+/// Access via `OutboundTracker::global()`. Counters are in-memory and reset
+/// on restart; long-term history belongs in Prometheus (`metrics` feature).
+#[derive(Debug)]
+pub struct OutboundTracker {
+    adapters: Mutex<HashMap<String, AdapterQuota>>,
+}
+
+impl OutboundTracker {
+    fn new() -> Self {
+        Self {
+            adapters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Access the global tracker instance.
+    pub fn global() -> &'static Self {
+        &TRACKER
+    }
+
+    /// Record a completed outbound call routed through the messaging manager.
+    pub fn record_call(
+        &self,
+        adapter: &str,
+        operation: OutboundOperation,
+        latency: Duration,
+        error: Option<&crate::Error>,
+    ) {
+        let rate_limited = error.is_some_and(is_rate_limit_error);
+        let slow = latency >= SLOW_OUTBOUND_THRESHOLD;
+
+        {
+            let mut adapters = self
+                .adapters
+                .lock()
+                .expect("outbound tracker lock poisoned");
+            let quota = adapters.entry(adapter.to_string()).or_default();
+            quota.requests_total += 1;
+            quota.latency_total += latency;
+            quota.last_latency = Some(latency);
+            quota.max_latency = Some(quota.max_latency.map_or(latency, |max| max.max(latency)));
+            *quota.requests_by_operation.entry(operation).or_default() += 1;
+            if error.is_some() {
+                quota.errors_total += 1;
+            }
+            if rate_limited {
+                quota.rate_limited_total += 1;
+            }
+            if slow {
+                quota.slow_requests_total += 1;
+            }
+        }
+
+        if slow {
+            tracing::warn!(
+                adapter,
+                operation = operation.as_str(),
+                latency_ms = latency.as_millis() as u64,
+                "slow outbound platform request, adapter may be throttled"
+            );
+        }
+        if rate_limited {
+            tracing::warn!(
+                adapter,
+                operation = operation.as_str(),
+                "outbound platform request was rate limited"
+            );
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let metrics = crate::telemetry::Metrics::global();
+            let result = if rate_limited {
+                "rate_limited"
+            } else if error.is_some() {
+                "error"
+            } else {
+                "success"
+            };
+            metrics
+                .messaging_outbound_requests_total
+                .with_label_values(&[adapter, operation.as_str(), result])
+                .inc();
+            metrics
+                .messaging_outbound_duration_seconds
+                .with_label_values(&[adapter, operation.as_str()])
+                .observe(latency.as_secs_f64());
+        }
+    }
+
+    /// Record rate-limit headers from a raw platform HTTP response.
+    ///
+    /// Adapters that own their HTTP client call this after every API call so
+    /// quota consumption is visible before the hard limit is reached.
+    pub fn record_headers(&self, adapter: &str, status: reqwest::StatusCode, headers: &HeaderMap) {
+        let Some(rate_limit) = RateLimitHeaders::from_headers(headers) else {
+            return;
+        };
+        self.record_rate_limit(adapter, status.as_u16() == 429, rate_limit);
+    }
+
+    fn record_rate_limit(&self, adapter: &str, throttled: bool, rate_limit: RateLimitHeaders) {
+        #[cfg(feature = "metrics")]
+        let ratio = rate_limit.remaining_ratio();
+        let remaining = rate_limit.remaining;
+        let limit = rate_limit.limit;
+        let reset_after = rate_limit.reset_after.or(rate_limit.retry_after);
+
+        let should_alert = {
+            let mut adapters = self
+                .adapters
+                .lock()
+                .expect("outbound tracker lock poisoned");
+            let quota = adapters.entry(adapter.to_string()).or_default();
+            quota.rate_limit = Some(rate_limit);
+            quota.rate_limit_observed_at = Some(Instant::now());
+            if throttled {
+                quota.rate_limited_total += 1;
+            }
+
+            if quota.is_near_limit() || throttled {
+                !std::mem::replace(&mut quota.near_limit_alerted, true)
+            } else {
+                quota.near_limit_alerted = false;
+                false
+            }
+        };
+
+        if should_alert {
+            tracing::warn!(
+                adapter,
+                ?remaining,
+                ?limit,
+                reset_after_secs = reset_after.map(|reset| reset.as_secs_f64()),
+                throttled,
+                "platform rate-limit quota nearly exhausted"
+            );
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(ratio) = ratio {
+            crate::telemetry::Metrics::global()
+                .messaging_rate_limit_remaining_ratio
+                .with_label_values(&[adapter])
+                .set(ratio);
+        }
+    }
+
+    /// Snapshot every adapter that has made at least one outbound call or
+    /// reported rate-limit headers, sorted by adapter name.
+    pub fn snapshot(&self) -> Vec<AdapterQuotaSnapshot> {
+        let adapters = self
+            .adapters
+            .lock()
+            .expect("outbound tracker lock poisoned");
+        let mut snapshots: Vec<_> = adapters
+            .iter()
+            .map(|(adapter, quota)| quota.snapshot(adapter))
+            .collect();
+        snapshots.sort_by(|left, right| left.adapter.cmp(&right.adapter));
+        snapshots
+    }
+
+    /// Drop tracked state for an adapter that was removed at runtime.
+    pub fn forget(&self, adapter: &str) {
+        self.adapters
+            .lock()
+            .expect("outbound tracker lock poisoned")
+            .remove(adapter);
+    }
+}
+
+fn is_rate_limit_error(error: &crate::Error) -> bool {
+    let lower = error.to_string().to_lowercase();
+    lower.contains("429")
+        || lower.contains("rate limit")
+        || lower.contains("ratelimit")
+        || lower.contains("ratelimited")
+        || lower.contains("too many requests")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutboundOperation, OutboundTracker, RateLimitHeaders};
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use std::time::Duration;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).expect("valid header"));
+        }
+        map
+    }
+
+    #[test]
+    fn parses_mattermost_style_rate_limit_headers() {
+        let parsed = RateLimitHeaders::from_headers(&headers(&[
+            ("X-Ratelimit-Limit", "10"),
+            ("X-Ratelimit-Remaining", "1"),
+            ("X-Ratelimit-Reset", "3"),
+        ]))
+        .expect("headers present");
+
+        assert_eq!(parsed.limit, Some(10));
+        assert_eq!(parsed.remaining, Some(1));
+        assert_eq!(parsed.reset_after, Some(Duration::from_secs(3)));
+        assert_eq!(parsed.remaining_ratio(), Some(0.1));
+    }
+
+    #[test]
+    fn missing_rate_limit_headers_yield_none() {
+        assert!(
+            RateLimitHeaders::from_headers(&headers(&[("content-type", "text/plain")])).is_none()
+        );
+    }
+
+    #[test]
+    fn average_latency_survives_request_counts_past_u32() {
+        let quota = AdapterQuota {
+            requests_total: u64::from(u32::MAX) + 1,
+            latency_total: Duration::from_millis(3) * u32::MAX,
+            ..AdapterQuota::default()
+        };
+        assert_eq!(quota.snapshot("slack").average_latency_ms, Some(2));
+
+        let quota = AdapterQuota {
+            requests_total: 2,
+            latency_total: Duration::from_millis(5),
+            ..AdapterQuota::default()
+        };
+        assert_eq!(quota.snapshot("slack").average_latency_ms, Some(2));
+    }
+
+    #[test]
+    fn near_limit_is_reported_in_snapshot() {
+        let tracker = OutboundTracker::new();
+        tracker.record_headers(
+            "mattermost",
+            reqwest::StatusCode::OK,
+            &headers(&[("x-ratelimit-limit", "100"), ("x-ratelimit-remaining", "5")]),
+        );
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot[0].near_limit);
+        assert_eq!(snapshot[0].rate_limit_remaining, Some(5));
+    }
+
+    #[test]
+    fn records_latency_errors_and_rate_limits_per_adapter() {
+        let tracker = OutboundTracker::new();
+        tracker.record_call(
+            "discord",
+            OutboundOperation::Respond,
+            Duration::from_millis(100),
+            None,
+        );
+        let throttled = crate::Error::Other(anyhow::anyhow!("HTTP 429 Too Many Requests"));
+        tracker.record_call(
+            "discord",
+            OutboundOperation::Broadcast,
+            Duration::from_millis(300),
+            Some(&throttled),
+        );
+
+        let snapshot = tracker.snapshot();
+        let discord = &snapshot[0];
+        assert_eq!(discord.requests_total, 2);
+        assert_eq!(discord.errors_total, 1);
+        assert_eq!(discord.rate_limited_total, 1);
+        assert_eq!(discord.average_latency_ms, Some(200));
+        assert_eq!(discord.max_latency_ms, Some(300));
+        assert_eq!(discord.requests_by_operation.get("respond"), Some(&1));
+    }
+}
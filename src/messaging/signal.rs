@@ -1241,6 +1241,7 @@ async fn sse_listener(
         }
 
         tracing::debug!("signal SSE stream ended, reconnecting with backoff...");
+        crate::notifications::ops::OpsAlerts::global().reconnect("signal adapter");
         tokio::select! {
             _ = tokio::time::sleep(retry_delay) => {}
             _ = shutdown_rx.recv() => {
@@ -1261,7 +1262,7 @@ async fn process_sse_event(
     let sse: SseEnvelope = match serde_json::from_str(data) {
         Ok(sse) => sse,
         Err(error) => {
-            tracing::debug!(%error, "signal SSE: failed to parse event, skipping");
+            crate::dead_letters::DeadLetterQueue::global().record("signal", &error, data);
             return;
         }
     };
@@ -23,6 +23,9 @@
 
 use crate::config::{SlackCommandConfig, SlackPermissions};
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
+use crate::messaging::continuation::{RegionUpdate, StreamRegions};
+use crate::messaging::edit_pacing::{EditFeedback, EditPacer};
+use crate::messaging::journal;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
@@ -34,6 +37,10 @@ use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
 use tokio::time::{Duration, timeout};
 
+/// Starting gap between `chat.update` calls in one channel. The method is
+/// Tier 3, about 50 calls a minute.
+const BASE_EDIT_INTERVAL: Duration = Duration::from_millis(1200);
+
 /// State shared with socket mode callbacks via `SlackClientEventsUserState`.
 struct SlackAdapterState {
     inbound_tx: mpsc::Sender<InboundMessage>,
@@ -68,11 +75,14 @@ pub struct SlackAdapter {
     client: Arc<SlackHyperClient>,
     /// Pre-built API token wrapping `bot_token`. Created once alongside `client`.
     token: SlackApiToken,
-    /// Maps InboundMessage.id → Slack ts for streaming edits.
-    active_messages: Arc<RwLock<HashMap<String, String>>>,
+    /// Maps InboundMessage.id → the Slack ts of each message a streamed
+    /// reply spans: the placeholder, then any continuation parts.
+    active_messages: Arc<RwLock<HashMap<String, StreamRegions<String>>>>,
     shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
     /// Slash command routing: command string → agent_id.
     commands: Arc<HashMap<String, String>>,
+    /// Per-channel gap between streaming edits, widened while rate limited.
+    edit_pacer: Arc<EditPacer>,
 }
 
 impl SlackAdapter {
@@ -103,6 +113,7 @@ impl SlackAdapter {
             active_messages: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
             commands: Arc::new(commands_map),
+            edit_pacer: Arc::new(EditPacer::new(BASE_EDIT_INTERVAL)),
         })
     }
 
@@ -110,6 +121,93 @@ impl SlackAdapter {
     fn session(&self) -> SlackClientSession<'_, SlackClientHyperHttpsConnector> {
         self.client.open_session(&self.token)
     }
+
+    /// Show a streamed reply's latest text: edit the messages whose part
+    /// changed and post a follow-up message for each new part. A failed
+    /// edit or post is retried with the next chunk.
+    async fn show_stream_text(
+        &self,
+        channel_id: &SlackChannelId,
+        message: &InboundMessage,
+        text: &str,
+    ) {
+        let Some(updates) = self
+            .active_messages
+            .read()
+            .await
+            .get(&message.id)
+            .map(|regions| regions.updates(text, 12_000))
+        else {
+            return;
+        };
+
+        let session = self.session();
+        for update in updates {
+            match update {
+                RegionUpdate::Edit { index, text } => {
+                    // Not held across the pacing wait, which can take seconds.
+                    let Some(ts) = self
+                        .active_messages
+                        .read()
+                        .await
+                        .get(&message.id)
+                        .and_then(|regions| regions.id(index).cloned())
+                    else {
+                        return;
+                    };
+                    let req = SlackApiChatUpdateRequest::new(
+                        channel_id.clone(),
+                        markdown_content(text.clone()),
+                        SlackTs(ts),
+                    );
+                    self.edit_pacer.wait(&channel_id.0).await;
+                    let started = std::time::Instant::now();
+                    let edited = session.chat_update(&req).await;
+                    let feedback = match &edited {
+                        Ok(_) => Some(EditFeedback::Sent {
+                            latency: started.elapsed(),
+                            remaining_ratio: None,
+                        }),
+                        Err(SlackClientError::RateLimitError(rate_limit)) => {
+                            Some(EditFeedback::RateLimited {
+                                retry_after: rate_limit.retry_after,
+                            })
+                        }
+                        Err(_) => None,
+                    };
+                    if let Some(feedback) = feedback {
+                        self.edit_pacer.record(&channel_id.0, feedback);
+                    }
+                    if let Err(error) = edited {
+                        tracing::warn!(%error, "failed to edit streaming message");
+                        return;
+                    }
+                    if let Some(regions) = self.active_messages.write().await.get_mut(&message.id) {
+                        regions.edited(index, text);
+                    }
+                }
+                RegionUpdate::Post { text } => {
+                    let req = SlackApiChatPostMessageRequest::new(
+                        channel_id.clone(),
+                        markdown_content(text.clone()),
+                    );
+                    match session.chat_post_message(&req).await {
+                        Ok(posted) => {
+                            if let Some(regions) =
+                                self.active_messages.write().await.get_mut(&message.id)
+                            {
+                                regions.posted(posted.ts.0, text);
+                            }
+                        }
+                        Err(error) => {
+                            tracing::warn!(%error, "failed to send streaming continuation");
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -899,6 +997,10 @@ impl Messaging for SlackAdapter {
         Ok(())
     }
 
+    fn supports_ephemeral(&self, message: &InboundMessage) -> bool {
+        !message.sender_id.is_empty()
+    }
+
     async fn respond(
         &self,
         message: &InboundMessage,
@@ -1075,42 +1177,35 @@ impl Messaging for SlackAdapter {
             }
 
             OutboundResponse::StreamStart => {
-                let req = SlackApiChatPostMessageRequest::new(
-                    channel_id.clone(),
-                    SlackMessageContent::new().with_text("\u{200B}".into()),
-                );
-                let resp = session
-                    .chat_post_message(&req)
-                    .await
-                    .context("failed to send stream placeholder")?;
+                let ts = match journal::resume_stream(&self.runtime_key, message).await {
+                    Some(ts) => ts,
+                    None => {
+                        let req = SlackApiChatPostMessageRequest::new(
+                            channel_id.clone(),
+                            SlackMessageContent::new().with_text("\u{200B}".into()),
+                        );
+                        let resp = session
+                            .chat_post_message(&req)
+                            .await
+                            .context("failed to send stream placeholder")?;
+                        journal::begin_stream(&self.runtime_key, message, &resp.ts.0).await;
+                        resp.ts.0
+                    }
+                };
                 self.active_messages
                     .write()
                     .await
-                    .insert(message.id.clone(), resp.ts.0);
+                    .insert(message.id.clone(), StreamRegions::new(ts));
             }
 
             OutboundResponse::StreamChunk(text) => {
-                let active = self.active_messages.read().await;
-                if let Some(ts) = active.get(&message.id) {
-                    let display_text = if text.len() > 12_000 {
-                        let end = text.floor_char_boundary(11_997);
-                        format!("{}...", &text[..end])
-                    } else {
-                        text
-                    };
-                    let req = SlackApiChatUpdateRequest::new(
-                        channel_id.clone(),
-                        markdown_content(display_text),
-                        SlackTs(ts.clone()),
-                    );
-                    if let Err(error) = session.chat_update(&req).await {
-                        tracing::warn!(%error, "failed to edit streaming message");
-                    }
-                }
+                let text = journal::update_stream(&self.runtime_key, message, text).await;
+                self.show_stream_text(&channel_id, message, &text).await;
             }
 
             OutboundResponse::StreamEnd => {
                 self.active_messages.write().await.remove(&message.id);
+                journal::finish_stream(&self.runtime_key, message).await;
             }
 
             OutboundResponse::Status(_) => {
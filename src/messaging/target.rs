@@ -120,6 +120,23 @@ pub fn resolve_broadcast_target(channel: &ChannelInfo) -> Option<BroadcastTarget
                 }
             }
         }
+        "irc" => {
+            if let Some(irc_target) = channel
+                .platform_meta
+                .as_ref()
+                .and_then(|meta| meta.get("irc_target"))
+                .and_then(json_value_to_string)
+            {
+                irc_target
+            } else {
+                let parts: Vec<&str> = channel.id.split(':').collect();
+                match parts.as_slice() {
+                    ["irc", "dm", nick] => (*nick).to_string(),
+                    ["irc", irc_channel] => (*irc_channel).to_string(),
+                    _ => return None,
+                }
+            }
+        }
         "signal" => {
             // Signal channels store target in signal_target metadata
             if let Some(signal_target) = channel
@@ -207,6 +224,7 @@ pub fn normalize_target(adapter: &str, raw_target: &str) -> Option<String> {
         "slack" => normalize_slack_target(trimmed),
         "telegram" => normalize_telegram_target(trimmed),
         "twitch" => normalize_twitch_target(trimmed),
+        "irc" => normalize_irc_target(trimmed),
         "email" => normalize_email_target(trimmed),
         "mattermost" => normalize_mattermost_target(trimmed),
         // Portal targets are full conversation IDs (e.g. "portal:chat:main")
@@ -284,6 +302,17 @@ fn normalize_twitch_target(raw_target: &str) -> Option<String> {
     }
 }
 
+/// IRC targets are a `#channel` (or `&channel`) or a bare nick for DMs.
+fn normalize_irc_target(raw_target: &str) -> Option<String> {
+    let target = strip_repeated_prefix(raw_target, "irc");
+    let target = target.strip_prefix("dm:").unwrap_or(target);
+    if target.is_empty() || target.contains(char::is_whitespace) {
+        None
+    } else {
+        Some(target.to_string())
+    }
+}
+
 /// Extract the runtime adapter key from a Mattermost conversation ID.
 ///
 /// Mattermost conversation IDs encode whether a named instance was used:
@@ -32,7 +32,8 @@ pub struct TelegramAdapter {
     bot: Bot,
     bot_user_id: Arc<RwLock<Option<UserId>>>,
     bot_username: Arc<RwLock<Option<String>>>,
-    /// Maps conversation_id to the message_id being edited during streaming.
+    /// Maps the inbound message id to the message being edited during
+    /// streaming, so several streams can run in one chat.
     active_messages: Arc<RwLock<HashMap<String, ActiveStream>>>,
     /// Repeating typing indicator tasks per conversation_id.
     typing_tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
@@ -436,7 +437,7 @@ impl Messaging for TelegramAdapter {
                     .context("failed to send stream placeholder")?;
 
                 self.active_messages.write().await.insert(
-                    message.conversation_id.clone(),
+                    message.id.clone(),
                     ActiveStream {
                         chat_id,
                         message_id: placeholder.id,
@@ -446,7 +447,7 @@ impl Messaging for TelegramAdapter {
             }
             OutboundResponse::StreamChunk(text) => {
                 let mut active = self.active_messages.write().await;
-                if let Some(stream) = active.get_mut(&message.conversation_id) {
+                if let Some(stream) = active.get_mut(&message.id) {
                     if stream.last_edit.elapsed() < STREAM_EDIT_INTERVAL {
                         return Ok(());
                     }
@@ -480,10 +481,7 @@ impl Messaging for TelegramAdapter {
                 }
             }
             OutboundResponse::StreamEnd => {
-                self.active_messages
-                    .write()
-                    .await
-                    .remove(&message.conversation_id);
+                self.active_messages.write().await.remove(&message.id);
             }
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
@@ -514,6 +512,9 @@ impl Messaging for TelegramAdapter {
                 let bot = self.bot.clone();
                 let conversation_id = message.conversation_id.clone();
 
+                // Abort any existing typing task before starting a new one.
+                self.stop_typing(&conversation_id).await;
+
                 // Telegram typing indicators expire after 5 seconds.
                 // Send one immediately, then repeat every 4 seconds.
                 let handle = tokio::spawn(async move {
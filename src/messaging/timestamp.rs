@@ -0,0 +1,110 @@
+//! Timestamps in chat messages.
+//!
+//! Discord and Slack have markup that shows a time in each reader's own
+//! timezone and locale: `<t:…>` on Discord, `<!date^…>` on Slack. Other
+//! platforms get the time written out in the channel's timezone, set with
+//! `/timezone`, or UTC.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+/// How much of a timestamp to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampStyle {
+    /// Date and time of day.
+    DateTime,
+    /// Time of day only.
+    Time,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Markup {
+    Discord,
+    Slack,
+    Plain,
+}
+
+/// Formats timestamps for one channel.
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamps {
+    markup: Markup,
+    timezone: Tz,
+}
+
+impl Timestamps {
+    /// Timestamps for `platform`, written out in `timezone` (UTC when unset)
+    /// where the platform has no markup of its own.
+    pub fn new(platform: &str, timezone: Option<Tz>) -> Self {
+        let markup = match platform {
+            "discord" => Markup::Discord,
+            "slack" => Markup::Slack,
+            _ => Markup::Plain,
+        };
+        Self {
+            markup,
+            timezone: timezone.unwrap_or(Tz::UTC),
+        }
+    }
+
+    pub fn format(&self, at: DateTime<Utc>, style: TimestampStyle) -> String {
+        let seconds = at.timestamp();
+        match self.markup {
+            Markup::Discord => {
+                let format = match style {
+                    TimestampStyle::DateTime => "f",
+                    TimestampStyle::Time => "t",
+                };
+                format!("<t:{seconds}:{format}>")
+            }
+            // Slack shows the fallback text in notifications and clients
+            // that can't render the token.
+            Markup::Slack => {
+                let format = match style {
+                    TimestampStyle::DateTime => "{date_short_pretty} {time}",
+                    TimestampStyle::Time => "{time}",
+                };
+                format!("<!date^{seconds}^{format}|{}>", self.plain(at, style))
+            }
+            Markup::Plain => self.plain(at, style),
+        }
+    }
+
+    fn plain(&self, at: DateTime<Utc>, style: TimestampStyle) -> String {
+        let local = at.with_timezone(&self.timezone);
+        match style {
+            TimestampStyle::DateTime => local.format("%Y-%m-%d %H:%M %Z"),
+            TimestampStyle::Time => local.format("%H:%M %Z"),
+        }
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platforms_with_markup_use_it_and_others_use_the_channel_timezone() {
+        let at = DateTime::parse_from_rfc3339("2026-10-15T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let berlin = Some(chrono_tz::Europe::Berlin);
+
+        assert_eq!(
+            Timestamps::new("discord", berlin).format(at, TimestampStyle::DateTime),
+            "<t:1792067400:f>"
+        );
+        assert_eq!(
+            Timestamps::new("slack", berlin).format(at, TimestampStyle::Time),
+            "<!date^1792067400^{time}|14:30 CEST>"
+        );
+        assert_eq!(
+            Timestamps::new("telegram", berlin).format(at, TimestampStyle::DateTime),
+            "2026-10-15 14:30 CEST"
+        );
+        assert_eq!(
+            Timestamps::new("telegram", None).format(at, TimestampStyle::Time),
+            "12:30 UTC"
+        );
+    }
+}
@@ -212,6 +212,13 @@ pub trait Messaging: Send + Sync + 'static {
         response: OutboundResponse,
     ) -> impl std::future::Future<Output = Result<()>> + Send;
 
+    /// Whether an [`OutboundResponse::Ephemeral`] reply to `message` reaches
+    /// only its sender. When false, the manager sends it as plain text.
+    fn supports_ephemeral(&self, message: &InboundMessage) -> bool {
+        let _ = message;
+        false
+    }
+
     /// Send a status update.
     fn send_status(
         &self,
@@ -242,6 +249,17 @@ pub trait Messaging: Send + Sync + 'static {
         async { Ok(Vec::new()) }
     }
 
+    /// Rename the thread `message` was posted in. A no-op when the message
+    /// isn't in a thread or the platform's threads have no names.
+    fn rename_thread(
+        &self,
+        message: &InboundMessage,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<()>> + Send {
+        let _ = (message, name);
+        async { Ok(()) }
+    }
+
     /// Health check.
     fn health_check(&self) -> impl std::future::Future<Output = Result<()>> + Send;
 
@@ -266,6 +284,8 @@ pub trait MessagingDyn: Send + Sync + 'static {
         response: OutboundResponse,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
 
+    fn supports_ephemeral(&self, message: &InboundMessage) -> bool;
+
     fn send_status<'a>(
         &'a self,
         message: &'a InboundMessage,
@@ -284,6 +304,12 @@ pub trait MessagingDyn: Send + Sync + 'static {
         limit: usize,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<Vec<HistoryMessage>>> + Send + 'a>>;
 
+    fn rename_thread<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        name: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
+
     fn health_check<'a>(
         &'a self,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
@@ -312,6 +338,10 @@ impl<T: Messaging> MessagingDyn for T {
         Box::pin(Messaging::respond(self, message, response))
     }
 
+    fn supports_ephemeral(&self, message: &InboundMessage) -> bool {
+        Messaging::supports_ephemeral(self, message)
+    }
+
     fn send_status<'a>(
         &'a self,
         message: &'a InboundMessage,
@@ -336,6 +366,14 @@ impl<T: Messaging> MessagingDyn for T {
         Box::pin(Messaging::fetch_history(self, message, limit))
     }
 
+    fn rename_thread<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        name: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(Messaging::rename_thread(self, message, name))
+    }
+
     fn health_check<'a>(
         &'a self,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
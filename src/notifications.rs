@@ -4,11 +4,22 @@
 //! observations) to the global spacebot.db so the dashboard Inbox card always
 //! has up-to-date data, even after a page reload or reconnect.
 
+pub mod ops;
+pub mod postgres;
+pub mod sqlite;
+pub mod webhook;
+
+pub use postgres::PostgresNotifications;
+pub use sqlite::SqliteNotifications;
+
 use crate::error::Result;
 
 use anyhow::Context as _;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use sqlx::{Row as _, SqlitePool};
+use sqlx::SqlitePool;
+
+use std::sync::Arc;
 
 // ---------------------------------------------------------------------------
 // Types
@@ -33,8 +44,10 @@ impl NotificationKind {
     }
 }
 
-/// Urgency level of the notification.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+/// Urgency level of the notification. Ordered from least to most urgent.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum NotificationSeverity {
     Info,
@@ -50,6 +63,15 @@ impl NotificationSeverity {
             NotificationSeverity::Error => "error",
         }
     }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "info" => Some(NotificationSeverity::Info),
+            "warn" => Some(NotificationSeverity::Warn),
+            "error" => Some(NotificationSeverity::Error),
+            _ => None,
+        }
+    }
 }
 
 /// A persisted notification row.
@@ -101,9 +123,40 @@ pub struct NotificationFilter {
 // Store
 // ---------------------------------------------------------------------------
 
+/// Storage for notifications. [`SqliteNotifications`] keeps them in the
+/// instance's own `spacebot.db`; [`PostgresNotifications`] in a database
+/// every replica shares, selected by `[database]`.
+#[async_trait]
+pub trait NotificationBackend: Send + Sync + std::fmt::Debug {
+    /// Insert a notification with the given ID. Returns false if a
+    /// duplicate undismissed notification for the same entity exists.
+    async fn insert(&self, id: &str, notification: &NewNotification) -> Result<bool>;
+
+    async fn get_by_id(&self, id: &str) -> Result<Notification>;
+
+    async fn list(&self, filter: &NotificationFilter) -> Result<Vec<Notification>>;
+
+    async fn unread_count(&self) -> Result<i64>;
+
+    async fn mark_read(&self, id: &str) -> Result<bool>;
+
+    async fn mark_all_read(&self) -> Result<u64>;
+
+    async fn dismiss(&self, id: &str) -> Result<bool>;
+
+    async fn dismiss_by_entity(
+        &self,
+        kind: &str,
+        entity_type: &str,
+        entity_id: &str,
+    ) -> Result<u64>;
+
+    async fn dismiss_read(&self) -> Result<u64>;
+}
+
 #[derive(Debug, Clone)]
 pub struct NotificationStore {
-    pool: SqlitePool,
+    backend: Arc<dyn NotificationBackend>,
 }
 
 const SELECT_COLUMNS: &str = "SELECT id, kind, severity, title, body, agent_id, \
@@ -111,142 +164,50 @@ const SELECT_COLUMNS: &str = "SELECT id, kind, severity, title, body, agent_id,
     created_at, read_at, dismissed_at";
 
 impl NotificationStore {
+    /// A store in the instance's SQLite database.
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self::with_backend(Arc::new(SqliteNotifications::new(pool)))
+    }
+
+    pub fn with_backend(backend: Arc<dyn NotificationBackend>) -> Self {
+        Self { backend }
     }
 
     /// Insert a notification. Returns the new row, or `None` if a duplicate
     /// undismissed notification for the same entity already exists.
     pub async fn insert(&self, n: NewNotification) -> Result<Option<Notification>> {
         let id = uuid::Uuid::new_v4().to_string();
-        let metadata_json = n.metadata.as_ref().map(|m| m.to_string());
-
-        let affected = sqlx::query(
-            r#"
-            INSERT OR IGNORE INTO notifications
-                (id, kind, severity, title, body, agent_id,
-                 related_entity_type, related_entity_id, action_url, metadata)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(&id)
-        .bind(n.kind.as_str())
-        .bind(n.severity.as_str())
-        .bind(&n.title)
-        .bind(&n.body)
-        .bind(&n.agent_id)
-        .bind(&n.related_entity_type)
-        .bind(&n.related_entity_id)
-        .bind(&n.action_url)
-        .bind(&metadata_json)
-        .execute(&self.pool)
-        .await
-        .context("failed to insert notification")?
-        .rows_affected();
-
-        if affected == 0 {
+        if !self.backend.insert(&id, &n).await? {
             return Ok(None);
         }
-
         self.get_by_id(&id).await.map(Some)
     }
 
     pub async fn get_by_id(&self, id: &str) -> Result<Notification> {
-        let row = sqlx::query(&format!("{SELECT_COLUMNS} FROM notifications WHERE id = ?"))
-            .bind(id)
-            .fetch_one(&self.pool)
-            .await
-            .context("failed to fetch notification by id")?;
-
-        notification_from_row(row)
+        self.backend.get_by_id(id).await
     }
 
     pub async fn list(&self, filter: NotificationFilter) -> Result<Vec<Notification>> {
-        let mut query = format!("{SELECT_COLUMNS} FROM notifications WHERE 1=1");
-
-        if filter.unread_only {
-            query.push_str(" AND read_at IS NULL");
-        }
-        if !filter.include_dismissed {
-            query.push_str(" AND dismissed_at IS NULL");
-        }
-        if filter.agent_id.is_some() {
-            query.push_str(" AND agent_id = ?");
-        }
-        if filter.kind.is_some() {
-            query.push_str(" AND kind = ?");
-        }
-        query.push_str(" ORDER BY created_at DESC LIMIT ? OFFSET ?");
-
-        let mut sql = sqlx::query(&query);
-        if let Some(ref agent_id) = filter.agent_id {
-            sql = sql.bind(agent_id);
-        }
-        if let Some(kind) = filter.kind {
-            sql = sql.bind(kind.as_str());
-        }
-        sql = sql.bind(filter.limit.unwrap_or(50).clamp(1, 500));
-        sql = sql.bind(filter.offset.unwrap_or(0));
-
-        let rows = sql
-            .fetch_all(&self.pool)
-            .await
-            .context("failed to list notifications")?;
-
-        rows.into_iter().map(notification_from_row).collect()
+        self.backend.list(&filter).await
     }
 
     pub async fn unread_count(&self) -> Result<i64> {
-        let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM notifications WHERE read_at IS NULL AND dismissed_at IS NULL",
-        )
-        .fetch_one(&self.pool)
-        .await
-        .context("failed to count unread notifications")?;
-        Ok(count)
+        self.backend.unread_count().await
     }
 
     /// Mark a single notification as read. Returns true if it was updated.
     pub async fn mark_read(&self, id: &str) -> Result<bool> {
-        let affected = sqlx::query(
-            "UPDATE notifications SET read_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
-             WHERE id = ? AND read_at IS NULL",
-        )
-        .bind(id)
-        .execute(&self.pool)
-        .await
-        .context("failed to mark notification read")?
-        .rows_affected();
-        Ok(affected > 0)
+        self.backend.mark_read(id).await
     }
 
     /// Mark all undismissed notifications as read. Returns the count updated.
     pub async fn mark_all_read(&self) -> Result<u64> {
-        let affected = sqlx::query(
-            "UPDATE notifications SET read_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
-             WHERE read_at IS NULL AND dismissed_at IS NULL",
-        )
-        .execute(&self.pool)
-        .await
-        .context("failed to mark all notifications read")?
-        .rows_affected();
-        Ok(affected)
+        self.backend.mark_all_read().await
     }
 
     /// Dismiss a single notification. Returns true if it was updated.
     pub async fn dismiss(&self, id: &str) -> Result<bool> {
-        let affected = sqlx::query(
-            "UPDATE notifications SET \
-             dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), \
-             read_at = COALESCE(read_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')) \
-             WHERE id = ? AND dismissed_at IS NULL",
-        )
-        .bind(id)
-        .execute(&self.pool)
-        .await
-        .context("failed to dismiss notification")?
-        .rows_affected();
-        Ok(affected > 0)
+        self.backend.dismiss(id).await
     }
 
     /// Dismiss all active (undismissed) notifications for a given entity.
@@ -257,34 +218,14 @@ impl NotificationStore {
         entity_type: &str,
         entity_id: &str,
     ) -> Result<u64> {
-        let affected = sqlx::query(
-            "UPDATE notifications SET \
-             dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), \
-             read_at = COALESCE(read_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')) \
-             WHERE kind = ? AND related_entity_type = ? AND related_entity_id = ? \
-             AND dismissed_at IS NULL",
-        )
-        .bind(kind)
-        .bind(entity_type)
-        .bind(entity_id)
-        .execute(&self.pool)
-        .await
-        .context("failed to dismiss notifications by entity")?
-        .rows_affected();
-        Ok(affected)
+        self.backend
+            .dismiss_by_entity(kind, entity_type, entity_id)
+            .await
     }
 
     /// Dismiss all already-read notifications. Returns the count updated.
     pub async fn dismiss_read(&self) -> Result<u64> {
-        let affected = sqlx::query(
-            "UPDATE notifications SET dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
-             WHERE read_at IS NOT NULL AND dismissed_at IS NULL",
-        )
-        .execute(&self.pool)
-        .await
-        .context("failed to dismiss read notifications")?
-        .rows_affected();
-        Ok(affected)
+        self.backend.dismiss_read().await
     }
 }
 
@@ -292,7 +233,12 @@ impl NotificationStore {
 // Row mapping
 // ---------------------------------------------------------------------------
 
-fn notification_from_row(row: sqlx::sqlite::SqliteRow) -> Result<Notification> {
+fn notification_from_row<R>(row: R) -> Result<Notification>
+where
+    R: sqlx::Row,
+    for<'r> &'r str: sqlx::ColumnIndex<R>,
+    for<'r> String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
     Ok(Notification {
         id: row
             .try_get("id")
@@ -0,0 +1,472 @@
+//! Ops alerts: operational problems posted to an ops channel.
+//!
+//! Failed worker sessions, reconnect storms, spent budgets, and
+//! dead-lettered events are classified into an [`Alert`] with a severity
+//! and handed to [`OpsAlerts::global`]. Alerts below `notify.min_severity`
+//! are dropped, and an alert identical to one sent within
+//! `notify.dedupe_window_secs` is suppressed and counted instead; the count
+//! is appended to the next copy that goes out. The runtime's main loop
+//! delivers admitted alerts to `notify.ops_channel` through the messaging
+//! manager, so nothing is posted until an ops channel is configured.
+
+use crate::config::NotifyConfig;
+use crate::messaging::target::BroadcastTarget;
+use crate::notifications::NotificationSeverity;
+use crate::notifications::webhook::classify_completion;
+use crate::{AgentId, BroadcastRecvResult, ProcessEvent, classify_broadcast_recv_result};
+
+use tokio::sync::broadcast;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Alerts buffered for a slow main loop before the oldest are dropped.
+const ALERT_CAPACITY: usize = 64;
+
+/// Characters of an alert summary posted.
+const SUMMARY_MAX_CHARS: usize = 300;
+
+/// How long a suppressed count is kept waiting for the alert to recur.
+const SUPPRESSED_COUNT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+static OPS_ALERTS: LazyLock<OpsAlerts> = LazyLock::new(OpsAlerts::new);
+
+/// The class of problem an alert reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// A worker session failed.
+    SessionError,
+    /// A connection kept dropping: an OpenCode event stream, a messaging
+    /// adapter, or an MCP server.
+    ReconnectStorm,
+    /// A worker's wall-clock budget or a tenant's daily message budget ran
+    /// out.
+    BudgetBreach,
+    /// An event stream sent an envelope that failed to parse.
+    DeadLetter,
+}
+
+impl AlertKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertKind::SessionError => "session error",
+            AlertKind::ReconnectStorm => "reconnect storm",
+            AlertKind::BudgetBreach => "budget breach",
+            AlertKind::DeadLetter => "dead letter",
+        }
+    }
+
+    fn severity(self) -> NotificationSeverity {
+        match self {
+            AlertKind::SessionError | AlertKind::ReconnectStorm => NotificationSeverity::Error,
+            AlertKind::BudgetBreach => NotificationSeverity::Warn,
+            AlertKind::DeadLetter => NotificationSeverity::Info,
+        }
+    }
+}
+
+/// One classified problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub severity: NotificationSeverity,
+    /// What the problem is about, e.g. `agent main` or `opencode backend
+    /// build-box`.
+    pub subject: String,
+    pub summary: String,
+}
+
+impl Alert {
+    /// An alert at the kind's usual severity.
+    pub fn new(kind: AlertKind, subject: impl Into<String>, summary: impl Into<String>) -> Self {
+        Self {
+            kind,
+            severity: kind.severity(),
+            subject: subject.into(),
+            summary: summary.into(),
+        }
+    }
+
+    fn dedupe_key(&self) -> String {
+        format!("{}|{}|{}", self.kind.as_str(), self.subject, self.summary)
+    }
+}
+
+/// An admitted alert, ready to post.
+#[derive(Debug, Clone)]
+pub struct OpsMessage {
+    pub target: BroadcastTarget,
+    pub text: String,
+}
+
+#[derive(Debug)]
+struct SentAlert {
+    sent_at: Instant,
+    /// Copies suppressed since `sent_at`.
+    suppressed: usize,
+}
+
+#[derive(Debug, Default)]
+struct AlertState {
+    sent: HashMap<String, SentAlert>,
+    /// Recent reconnect times per connection.
+    reconnects: HashMap<String, VecDeque<Instant>>,
+}
+
+/// Ops alert routing and de-duplication. Access via [`OpsAlerts::global`].
+pub struct OpsAlerts {
+    config: Mutex<NotifyConfig>,
+    state: Mutex<AlertState>,
+    message_tx: broadcast::Sender<OpsMessage>,
+}
+
+impl OpsAlerts {
+    fn new() -> Self {
+        let (message_tx, _) = broadcast::channel(ALERT_CAPACITY);
+        Self {
+            config: Mutex::new(NotifyConfig::default()),
+            state: Mutex::new(AlertState::default()),
+            message_tx,
+        }
+    }
+
+    /// The process-wide router.
+    pub fn global() -> &'static Self {
+        &OPS_ALERTS
+    }
+
+    /// Apply `[notify]` config.
+    pub fn configure(&self, config: NotifyConfig) {
+        *self
+            .config
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = config;
+    }
+
+    /// Route `alert` to the ops channel unless it is filtered or a repeat.
+    pub fn notify(&self, alert: Alert) {
+        let config = self
+            .config
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        let Some(target) = config.ops_channel.clone() else {
+            return;
+        };
+        let text = self.lock_state().admit(&alert, &config, Instant::now());
+        if let Some(text) = text {
+            tracing::debug!(kind = alert.kind.as_str(), subject = %alert.subject, "routing ops alert");
+            self.message_tx.send(OpsMessage { target, text }).ok();
+        }
+    }
+
+    /// Record a reconnect of `connection`, alerting once it reconnects
+    /// `notify.reconnect_storm_threshold` times within
+    /// `notify.reconnect_storm_window_secs`.
+    pub fn reconnect(&self, connection: &str) {
+        let config = self
+            .config
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        if config.ops_channel.is_none() {
+            return;
+        }
+        let storm = self
+            .lock_state()
+            .record_reconnect(connection, &config, Instant::now());
+        if let Some(alert) = storm {
+            self.notify(alert);
+        }
+    }
+
+    /// Receive alerts admitted after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<OpsMessage> {
+        self.message_tx.subscribe()
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, AlertState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl AlertState {
+    /// Decide whether to post `alert`, returning the chat text if so.
+    fn admit(&mut self, alert: &Alert, config: &NotifyConfig, now: Instant) -> Option<String> {
+        if alert.severity < config.min_severity {
+            return None;
+        }
+        let window = Duration::from_secs(config.dedupe_window_secs);
+        self.sent.retain(|_, sent| {
+            let age = now.duration_since(sent.sent_at);
+            age < window || (sent.suppressed > 0 && age < SUPPRESSED_COUNT_TTL)
+        });
+        let key = alert.dedupe_key();
+        let suppressed = match self.sent.get_mut(&key) {
+            Some(sent) if now.duration_since(sent.sent_at) < window => {
+                sent.suppressed += 1;
+                return None;
+            }
+            Some(sent) => sent.suppressed,
+            None => 0,
+        };
+        self.sent.insert(
+            key,
+            SentAlert {
+                sent_at: now,
+                suppressed: 0,
+            },
+        );
+        Some(format_alert(alert, suppressed))
+    }
+
+    fn record_reconnect(
+        &mut self,
+        connection: &str,
+        config: &NotifyConfig,
+        now: Instant,
+    ) -> Option<Alert> {
+        let window = Duration::from_secs(config.reconnect_storm_window_secs);
+        let times = self.reconnects.entry(connection.to_string()).or_default();
+        while times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) >= window)
+        {
+            times.pop_front();
+        }
+        times.push_back(now);
+        (times.len() >= config.reconnect_storm_threshold as usize).then(|| {
+            Alert::new(
+                AlertKind::ReconnectStorm,
+                connection,
+                format!(
+                    "reconnected {}+ times in {}",
+                    config.reconnect_storm_threshold,
+                    format_window(window)
+                ),
+            )
+        })
+    }
+}
+
+fn format_alert(alert: &Alert, suppressed: usize) -> String {
+    let summary = match alert.summary.char_indices().nth(SUMMARY_MAX_CHARS) {
+        Some((index, _)) => format!("{}…", &alert.summary[..index]),
+        None => alert.summary.clone(),
+    };
+    let summary = summary.trim();
+    let mut text = match alert.severity {
+        NotificationSeverity::Error => {
+            format!(
+                "🛑 **{}** ({}): {summary}",
+                alert.kind.as_str(),
+                alert.subject
+            )
+        }
+        NotificationSeverity::Warn => {
+            format!("⚠️ {} ({}): {summary}", alert.kind.as_str(), alert.subject)
+        }
+        NotificationSeverity::Info => {
+            format!("ℹ️ {} ({}): {summary}", alert.kind.as_str(), alert.subject)
+        }
+    };
+    if suppressed > 0 {
+        text.push_str(&format!(
+            "\n(repeated {suppressed} more times since the last alert)"
+        ));
+    }
+    text
+}
+
+fn format_window(window: Duration) -> String {
+    let seconds = window.as_secs();
+    if seconds >= 60 && seconds % 60 == 0 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// The alert for a process event, if it reports a problem.
+fn alert_for_event(agent_id: &AgentId, event: &ProcessEvent) -> Option<Alert> {
+    let ProcessEvent::WorkerComplete {
+        agent_id: event_agent_id,
+        result,
+        success,
+        ..
+    } = event
+    else {
+        return None;
+    };
+    if event_agent_id != agent_id {
+        return None;
+    }
+    let kind = match classify_completion(*success, result)? {
+        crate::config::NotifyEvent::SessionCompleted => return None,
+        crate::config::NotifyEvent::SessionFailed => AlertKind::SessionError,
+        crate::config::NotifyEvent::BudgetExhausted => AlertKind::BudgetBreach,
+    };
+    let summary = result.lines().next().unwrap_or_default();
+    Some(Alert::new(kind, format!("agent {agent_id}"), summary))
+}
+
+/// Raise alerts for failed and over-budget worker sessions on an agent's
+/// event bus.
+pub fn spawn_ops_alert_recorder(
+    agent_id: AgentId,
+    mut event_rx: broadcast::Receiver<ProcessEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match classify_broadcast_recv_result(event_rx.recv().await) {
+                BroadcastRecvResult::Event(event) => {
+                    if let Some(alert) = alert_for_event(&agent_id, &event) {
+                        OpsAlerts::global().notify(alert);
+                    }
+                }
+                BroadcastRecvResult::Lagged(count) => {
+                    tracing::warn!(
+                        %agent_id,
+                        skipped = count,
+                        "ops alert recorder lagged, events were not checked"
+                    );
+                }
+                BroadcastRecvResult::Closed => break,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> NotifyConfig {
+        NotifyConfig {
+            ops_channel: Some(BroadcastTarget {
+                adapter: "discord".into(),
+                target: "1".into(),
+            }),
+            min_severity: NotificationSeverity::Warn,
+            dedupe_window_secs: 600,
+            reconnect_storm_threshold: 3,
+            reconnect_storm_window_secs: 300,
+        }
+    }
+
+    #[test]
+    fn suppresses_repeats_within_the_window() {
+        let config = config();
+        let mut state = AlertState::default();
+        let alert = Alert::new(AlertKind::SessionError, "agent main", "Worker failed: boom");
+        let now = Instant::now();
+
+        assert_eq!(
+            state.admit(&alert, &config, now).as_deref(),
+            Some("🛑 **session error** (agent main): Worker failed: boom")
+        );
+        assert!(
+            state
+                .admit(&alert, &config, now + Duration::from_secs(10))
+                .is_none()
+        );
+        assert!(
+            state
+                .admit(&alert, &config, now + Duration::from_secs(20))
+                .is_none()
+        );
+
+        let other = Alert::new(
+            AlertKind::SessionError,
+            "agent main",
+            "Worker failed: other",
+        );
+        assert!(
+            state
+                .admit(&other, &config, now + Duration::from_secs(30))
+                .is_some()
+        );
+
+        assert_eq!(
+            state
+                .admit(&alert, &config, now + Duration::from_secs(601))
+                .as_deref(),
+            Some(
+                "🛑 **session error** (agent main): Worker failed: boom\n\
+                 (repeated 2 more times since the last alert)"
+            )
+        );
+    }
+
+    #[test]
+    fn drops_alerts_below_the_minimum_severity() {
+        let config = config();
+        let mut state = AlertState::default();
+        let now = Instant::now();
+        let dead_letter = Alert::new(AlertKind::DeadLetter, "opencode", "missing field `type`");
+        assert!(state.admit(&dead_letter, &config, now).is_none());
+
+        let budget = Alert::new(AlertKind::BudgetBreach, "tenant acme", "daily budget spent");
+        assert_eq!(
+            state.admit(&budget, &config, now).as_deref(),
+            Some("⚠️ budget breach (tenant acme): daily budget spent")
+        );
+    }
+
+    #[test]
+    fn detects_reconnect_storms() {
+        let config = config();
+        let mut state = AlertState::default();
+        let now = Instant::now();
+        let connection = "opencode backend build-box";
+
+        assert!(state.record_reconnect(connection, &config, now).is_none());
+        assert!(
+            state
+                .record_reconnect(connection, &config, now + Duration::from_secs(400))
+                .is_none()
+        );
+        assert!(
+            state
+                .record_reconnect(connection, &config, now + Duration::from_secs(450))
+                .is_none()
+        );
+        let storm = state
+            .record_reconnect(connection, &config, now + Duration::from_secs(500))
+            .expect("storm expected");
+        assert_eq!(storm.kind, AlertKind::ReconnectStorm);
+        assert_eq!(storm.summary, "reconnected 3+ times in 5m");
+    }
+
+    #[test]
+    fn classifies_worker_failures() {
+        let agent_id: AgentId = "main".into();
+        let completion = |result: &str, success| ProcessEvent::WorkerComplete {
+            agent_id: "main".into(),
+            worker_id: uuid::Uuid::nil(),
+            channel_id: None,
+            result: result.into(),
+            notify: true,
+            success,
+        };
+
+        assert!(alert_for_event(&agent_id, &completion("done", true)).is_none());
+        assert!(alert_for_event(&agent_id, &completion("Worker cancelled: user", false)).is_none());
+        let failed = alert_for_event(&agent_id, &completion("Worker failed: boom\ntrace", false))
+            .expect("alert expected");
+        assert_eq!(failed.kind, AlertKind::SessionError);
+        assert_eq!(failed.summary, "Worker failed: boom");
+        let timeout = alert_for_event(
+            &agent_id,
+            &completion(
+                "Worker exceeded 1800s wall-clock timeout after 3 segments.",
+                false,
+            ),
+        )
+        .expect("alert expected");
+        assert_eq!(timeout.kind, AlertKind::BudgetBreach);
+    }
+}
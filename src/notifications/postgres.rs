@@ -0,0 +1,198 @@
+//! Notifications in a Postgres database shared by every replica.
+//!
+//! Timestamps are stored as the same ISO-8601 text the SQLite store uses,
+//! so rows look the same to the API whichever backend holds them.
+
+use super::{
+    NewNotification, Notification, NotificationBackend, NotificationFilter, SELECT_COLUMNS,
+    notification_from_row,
+};
+use crate::error::Result;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// The current time, formatted like SQLite's
+/// `strftime('%Y-%m-%dT%H:%M:%SZ', 'now')`.
+const NOW: &str = r#"to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')"#;
+
+#[derive(Debug, Clone)]
+pub struct PostgresNotifications {
+    pool: PgPool,
+}
+
+impl PostgresNotifications {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for PostgresNotifications {
+    async fn insert(&self, id: &str, n: &NewNotification) -> Result<bool> {
+        let metadata_json = n.metadata.as_ref().map(|m| m.to_string());
+
+        let affected = sqlx::query(
+            r#"
+            INSERT INTO notifications
+                (id, kind, severity, title, body, agent_id,
+                 related_entity_type, related_entity_id, action_url, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(n.kind.as_str())
+        .bind(n.severity.as_str())
+        .bind(&n.title)
+        .bind(&n.body)
+        .bind(&n.agent_id)
+        .bind(&n.related_entity_type)
+        .bind(&n.related_entity_id)
+        .bind(&n.action_url)
+        .bind(&metadata_json)
+        .execute(&self.pool)
+        .await
+        .context("failed to insert notification")?
+        .rows_affected();
+
+        Ok(affected > 0)
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Notification> {
+        let row = sqlx::query(&format!(
+            "{SELECT_COLUMNS} FROM notifications WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to fetch notification by id")?;
+
+        notification_from_row(row)
+    }
+
+    async fn list(&self, filter: &NotificationFilter) -> Result<Vec<Notification>> {
+        let mut query = format!("{SELECT_COLUMNS} FROM notifications WHERE 1=1");
+        let mut parameter = 0;
+        let mut next = || {
+            parameter += 1;
+            format!("${parameter}")
+        };
+
+        if filter.unread_only {
+            query.push_str(" AND read_at IS NULL");
+        }
+        if !filter.include_dismissed {
+            query.push_str(" AND dismissed_at IS NULL");
+        }
+        if filter.agent_id.is_some() {
+            query.push_str(&format!(" AND agent_id = {}", next()));
+        }
+        if filter.kind.is_some() {
+            query.push_str(&format!(" AND kind = {}", next()));
+        }
+        query.push_str(&format!(
+            " ORDER BY created_at DESC LIMIT {} OFFSET {}",
+            next(),
+            next()
+        ));
+
+        let mut sql = sqlx::query(&query);
+        if let Some(ref agent_id) = filter.agent_id {
+            sql = sql.bind(agent_id);
+        }
+        if let Some(kind) = filter.kind {
+            sql = sql.bind(kind.as_str());
+        }
+        sql = sql.bind(filter.limit.unwrap_or(50).clamp(1, 500));
+        sql = sql.bind(filter.offset.unwrap_or(0));
+
+        let rows = sql
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to list notifications")?;
+
+        rows.into_iter().map(notification_from_row).collect()
+    }
+
+    async fn unread_count(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE read_at IS NULL AND dismissed_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to count unread notifications")?;
+        Ok(count)
+    }
+
+    async fn mark_read(&self, id: &str) -> Result<bool> {
+        let affected = sqlx::query(&format!(
+            "UPDATE notifications SET read_at = {NOW} WHERE id = $1 AND read_at IS NULL"
+        ))
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("failed to mark notification read")?
+        .rows_affected();
+        Ok(affected > 0)
+    }
+
+    async fn mark_all_read(&self) -> Result<u64> {
+        let affected = sqlx::query(&format!(
+            "UPDATE notifications SET read_at = {NOW} \
+             WHERE read_at IS NULL AND dismissed_at IS NULL"
+        ))
+        .execute(&self.pool)
+        .await
+        .context("failed to mark all notifications read")?
+        .rows_affected();
+        Ok(affected)
+    }
+
+    async fn dismiss(&self, id: &str) -> Result<bool> {
+        let affected = sqlx::query(&format!(
+            "UPDATE notifications SET dismissed_at = {NOW}, read_at = COALESCE(read_at, {NOW}) \
+             WHERE id = $1 AND dismissed_at IS NULL"
+        ))
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("failed to dismiss notification")?
+        .rows_affected();
+        Ok(affected > 0)
+    }
+
+    async fn dismiss_by_entity(
+        &self,
+        kind: &str,
+        entity_type: &str,
+        entity_id: &str,
+    ) -> Result<u64> {
+        let affected = sqlx::query(&format!(
+            "UPDATE notifications SET dismissed_at = {NOW}, read_at = COALESCE(read_at, {NOW}) \
+             WHERE kind = $1 AND related_entity_type = $2 AND related_entity_id = $3 \
+             AND dismissed_at IS NULL"
+        ))
+        .bind(kind)
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&self.pool)
+        .await
+        .context("failed to dismiss notifications by entity")?
+        .rows_affected();
+        Ok(affected)
+    }
+
+    async fn dismiss_read(&self) -> Result<u64> {
+        let affected = sqlx::query(&format!(
+            "UPDATE notifications SET dismissed_at = {NOW} \
+             WHERE read_at IS NOT NULL AND dismissed_at IS NULL"
+        ))
+        .execute(&self.pool)
+        .await
+        .context("failed to dismiss read notifications")?
+        .rows_affected();
+        Ok(affected)
+    }
+}
@@ -0,0 +1,240 @@
+//! Notifications in the instance's own `spacebot.db`.
+
+use super::{
+    NewNotification, Notification, NotificationBackend, NotificationFilter, SELECT_COLUMNS,
+    notification_from_row,
+};
+use crate::error::Result;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+#[derive(Debug, Clone)]
+pub struct SqliteNotifications {
+    pool: SqlitePool,
+}
+
+impl SqliteNotifications {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for SqliteNotifications {
+    async fn insert(&self, id: &str, n: &NewNotification) -> Result<bool> {
+        let metadata_json = n.metadata.as_ref().map(|m| m.to_string());
+
+        let affected = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO notifications
+                (id, kind, severity, title, body, agent_id,
+                 related_entity_type, related_entity_id, action_url, metadata)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(n.kind.as_str())
+        .bind(n.severity.as_str())
+        .bind(&n.title)
+        .bind(&n.body)
+        .bind(&n.agent_id)
+        .bind(&n.related_entity_type)
+        .bind(&n.related_entity_id)
+        .bind(&n.action_url)
+        .bind(&metadata_json)
+        .execute(&self.pool)
+        .await
+        .context("failed to insert notification")?
+        .rows_affected();
+
+        Ok(affected > 0)
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Notification> {
+        let row = sqlx::query(&format!("{SELECT_COLUMNS} FROM notifications WHERE id = ?"))
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .context("failed to fetch notification by id")?;
+
+        notification_from_row(row)
+    }
+
+    async fn list(&self, filter: &NotificationFilter) -> Result<Vec<Notification>> {
+        let mut query = format!("{SELECT_COLUMNS} FROM notifications WHERE 1=1");
+
+        if filter.unread_only {
+            query.push_str(" AND read_at IS NULL");
+        }
+        if !filter.include_dismissed {
+            query.push_str(" AND dismissed_at IS NULL");
+        }
+        if filter.agent_id.is_some() {
+            query.push_str(" AND agent_id = ?");
+        }
+        if filter.kind.is_some() {
+            query.push_str(" AND kind = ?");
+        }
+        query.push_str(" ORDER BY created_at DESC LIMIT ? OFFSET ?");
+
+        let mut sql = sqlx::query(&query);
+        if let Some(ref agent_id) = filter.agent_id {
+            sql = sql.bind(agent_id);
+        }
+        if let Some(kind) = filter.kind {
+            sql = sql.bind(kind.as_str());
+        }
+        sql = sql.bind(filter.limit.unwrap_or(50).clamp(1, 500));
+        sql = sql.bind(filter.offset.unwrap_or(0));
+
+        let rows = sql
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to list notifications")?;
+
+        rows.into_iter().map(notification_from_row).collect()
+    }
+
+    async fn unread_count(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE read_at IS NULL AND dismissed_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to count unread notifications")?;
+        Ok(count)
+    }
+
+    async fn mark_read(&self, id: &str) -> Result<bool> {
+        let affected = sqlx::query(
+            "UPDATE notifications SET read_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
+             WHERE id = ? AND read_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("failed to mark notification read")?
+        .rows_affected();
+        Ok(affected > 0)
+    }
+
+    async fn mark_all_read(&self) -> Result<u64> {
+        let affected = sqlx::query(
+            "UPDATE notifications SET read_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
+             WHERE read_at IS NULL AND dismissed_at IS NULL",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to mark all notifications read")?
+        .rows_affected();
+        Ok(affected)
+    }
+
+    async fn dismiss(&self, id: &str) -> Result<bool> {
+        let affected = sqlx::query(
+            "UPDATE notifications SET \
+             dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), \
+             read_at = COALESCE(read_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')) \
+             WHERE id = ? AND dismissed_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("failed to dismiss notification")?
+        .rows_affected();
+        Ok(affected > 0)
+    }
+
+    async fn dismiss_by_entity(
+        &self,
+        kind: &str,
+        entity_type: &str,
+        entity_id: &str,
+    ) -> Result<u64> {
+        let affected = sqlx::query(
+            "UPDATE notifications SET \
+             dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), \
+             read_at = COALESCE(read_at, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')) \
+             WHERE kind = ? AND related_entity_type = ? AND related_entity_id = ? \
+             AND dismissed_at IS NULL",
+        )
+        .bind(kind)
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&self.pool)
+        .await
+        .context("failed to dismiss notifications by entity")?
+        .rows_affected();
+        Ok(affected)
+    }
+
+    async fn dismiss_read(&self) -> Result<u64> {
+        let affected = sqlx::query(
+            "UPDATE notifications SET dismissed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
+             WHERE read_at IS NOT NULL AND dismissed_at IS NULL",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to dismiss read notifications")?
+        .rows_affected();
+        Ok(affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::{NotificationKind, NotificationSeverity, NotificationStore};
+
+    async fn store() -> NotificationStore {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        crate::db::INSTANCE_MIGRATOR
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        NotificationStore::new(pool)
+    }
+
+    fn approval(task: &str) -> NewNotification {
+        NewNotification {
+            kind: NotificationKind::TaskApproval,
+            severity: NotificationSeverity::Warn,
+            title: format!("Task {task} needs approval"),
+            body: None,
+            agent_id: Some("main".into()),
+            related_entity_type: Some("task".into()),
+            related_entity_id: Some(task.into()),
+            action_url: None,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn active_duplicates_are_ignored_until_dismissed() {
+        let store = store().await;
+        let first = store.insert(approval("7")).await.unwrap().unwrap();
+        assert!(store.insert(approval("7")).await.unwrap().is_none());
+        assert_eq!(store.unread_count().await.unwrap(), 1);
+
+        assert!(store.mark_read(&first.id).await.unwrap());
+        assert_eq!(store.unread_count().await.unwrap(), 0);
+        assert_eq!(
+            store
+                .dismiss_by_entity("task_approval", "task", "7")
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(store.insert(approval("7")).await.unwrap().is_some());
+
+        let listed = store.list(NotificationFilter::default()).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_ne!(listed[0].id, first.id);
+    }
+}
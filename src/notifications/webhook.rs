@@ -0,0 +1,243 @@
+//! Outgoing webhooks for session lifecycle events.
+//!
+//! Each agent's `[[agents.notify_webhooks]]` entries receive a JSON POST when
+//! a worker session completes, fails, or runs out of its wall-clock budget,
+//! so ops tooling (PagerDuty, Opsgenie, a Slack workflow) can react without
+//! polling the API. Bodies are signed with HMAC-SHA256 in
+//! `X-Spacebot-Signature-256: sha256=<hex>` when the webhook has a secret,
+//! the same scheme GitHub uses for its deliveries.
+
+use crate::config::{NotifyEvent, NotifyWebhookConfig, RuntimeConfig};
+use crate::{AgentId, BroadcastRecvResult, ProcessEvent, classify_broadcast_recv_result};
+
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Characters of the session result included in the payload.
+const RESULT_MAX_CHARS: usize = 4_000;
+
+/// Per-request timeout. Deliveries are fire-and-forget and never retried.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying the body signature.
+pub const SIGNATURE_HEADER: &str = "X-Spacebot-Signature-256";
+
+/// JSON body POSTed to each subscribed webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotifyPayload {
+    pub event: &'static str,
+    pub agent_id: String,
+    pub worker_id: String,
+    pub channel_id: Option<String>,
+    pub result: String,
+    pub timestamp: String,
+}
+
+/// POST session lifecycle events from an agent's event bus to its webhooks.
+pub fn spawn_webhook_notifier(
+    agent_id: AgentId,
+    mut event_rx: broadcast::Receiver<ProcessEvent>,
+    runtime_config: Arc<RuntimeConfig>,
+) -> tokio::task::JoinHandle<()> {
+    let client = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    tokio::spawn(async move {
+        loop {
+            match classify_broadcast_recv_result(event_rx.recv().await) {
+                BroadcastRecvResult::Event(event) => {
+                    let Some((notify_event, payload)) = payload_for_event(&agent_id, &event) else {
+                        continue;
+                    };
+                    let webhooks = runtime_config.notify_webhooks.load();
+                    for webhook in webhooks
+                        .iter()
+                        .filter(|webhook| webhook.events.contains(&notify_event))
+                    {
+                        tokio::spawn(deliver(client.clone(), webhook.clone(), payload.clone()));
+                    }
+                }
+                BroadcastRecvResult::Lagged(count) => {
+                    tracing::warn!(
+                        %agent_id,
+                        skipped = count,
+                        "webhook notifier lagged, events were not sent"
+                    );
+                }
+                BroadcastRecvResult::Closed => break,
+            }
+        }
+    })
+}
+
+async fn deliver(client: reqwest::Client, webhook: NotifyWebhookConfig, payload: NotifyPayload) {
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::warn!(%error, "failed to serialize webhook notification");
+            return;
+        }
+    };
+    let mut request = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    if let Some(secret) = &webhook.secret {
+        request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+    }
+    match request.body(body).send().await {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => {
+            tracing::warn!(
+                url = %webhook.url,
+                status = %response.status(),
+                event = payload.event,
+                "webhook notification was rejected"
+            );
+        }
+        Err(error) => {
+            tracing::warn!(
+                %error,
+                url = %webhook.url,
+                event = payload.event,
+                "failed to send webhook notification"
+            );
+        }
+    }
+}
+
+fn payload_for_event(
+    agent_id: &AgentId,
+    event: &ProcessEvent,
+) -> Option<(NotifyEvent, NotifyPayload)> {
+    let ProcessEvent::WorkerComplete {
+        agent_id: event_agent_id,
+        worker_id,
+        channel_id,
+        result,
+        success,
+        ..
+    } = event
+    else {
+        return None;
+    };
+    if event_agent_id != agent_id {
+        return None;
+    }
+    let notify_event = classify_completion(*success, result)?;
+    let result = match result.char_indices().nth(RESULT_MAX_CHARS) {
+        Some((index, _)) => format!("{}…", &result[..index]),
+        None => result.clone(),
+    };
+    Some((
+        notify_event,
+        NotifyPayload {
+            event: notify_event.as_str(),
+            agent_id: agent_id.to_string(),
+            worker_id: worker_id.to_string(),
+            channel_id: channel_id.as_ref().map(ToString::to_string),
+            result,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+    ))
+}
+
+/// Map a worker completion to a lifecycle event. The completion only carries
+/// the rendered result text, so timeouts and cancellations are recognized by
+/// the prefixes `classify_worker_completion` gives them. Cancelled sessions
+/// were stopped on purpose and are not reported.
+pub(super) fn classify_completion(success: bool, result: &str) -> Option<NotifyEvent> {
+    if success {
+        Some(NotifyEvent::SessionCompleted)
+    } else if result.starts_with("Worker cancelled:") {
+        None
+    } else if result.starts_with("Worker exceeded ") && result.contains("wall-clock timeout") {
+        Some(NotifyEvent::BudgetExhausted)
+    } else {
+        Some(NotifyEvent::SessionFailed)
+    }
+}
+
+/// `sha256=<hex>` signature of `body`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    format!(
+        "sha256={}",
+        hex::encode(hmac_sha256(secret.as_bytes(), body))
+    )
+}
+
+/// HMAC-SHA256 (RFC 2104).
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(result: &str, success: bool) -> ProcessEvent {
+        ProcessEvent::WorkerComplete {
+            agent_id: "main".into(),
+            worker_id: uuid::Uuid::nil(),
+            channel_id: Some("discord:1".into()),
+            result: result.into(),
+            notify: true,
+            success,
+        }
+    }
+
+    #[test]
+    fn classifies_worker_completions() {
+        let agent_id: AgentId = "main".into();
+        let cases = [
+            ("done", true, Some(NotifyEvent::SessionCompleted)),
+            (
+                "Worker failed: boom",
+                false,
+                Some(NotifyEvent::SessionFailed),
+            ),
+            (
+                "Worker exceeded 1800s wall-clock timeout after 3 segments.",
+                false,
+                Some(NotifyEvent::BudgetExhausted),
+            ),
+            ("Worker cancelled: user request", false, None),
+        ];
+        for (result, success, expected) in cases {
+            let event =
+                payload_for_event(&agent_id, &completion(result, success)).map(|(event, _)| event);
+            assert_eq!(event, expected, "{result}");
+        }
+
+        let (_, payload) = payload_for_event(&agent_id, &completion("done", true)).unwrap();
+        assert_eq!(payload.event, "session_completed");
+        assert_eq!(payload.channel_id.as_deref(), Some("discord:1"));
+        assert!(payload_for_event(&"other".into(), &completion("done", true)).is_none());
+    }
+
+    #[test]
+    fn signs_bodies_with_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -5,10 +5,25 @@
 //! alternative worker backend that delegates to OpenCode's full agent
 //! capabilities instead of running a Rig agent loop with basic tools.
 
+pub mod attach;
+pub mod capabilities;
+pub mod command;
+pub mod compact;
+pub mod fan_in;
+pub mod fixtures;
+pub mod mirror;
+pub mod render;
 pub mod server;
+pub mod server_log;
 pub mod types;
 pub mod worker;
 
+pub use capabilities::ServerCapabilities;
+pub use fan_in::{FanIn, TaggedEvent};
+pub use mirror::OpenCodeMirrorStore;
+pub use render::{ToolOutput, ToolOutputRenderer, ToolOutputRenderers};
 pub use server::{OpenCodeServer, OpenCodeServerPool};
-pub use types::{OpenCodePermissions, QuestionAnswer, QuestionInfo, QuestionOption};
+pub use types::{
+    OpenCodePermissions, PermissionReply, QuestionAnswer, QuestionInfo, QuestionOption,
+};
 pub use worker::{OpenCodeWorker, OpenCodeWorkerResult};
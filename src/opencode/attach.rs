@@ -0,0 +1,122 @@
+//! Attaching an existing OpenCode session to a channel (`/attach`).
+//!
+//! A session started from the OpenCode TUI or another client can be handed
+//! to a channel. The channel runs it as an idle interactive worker, so the
+//! next messages go to the session as follow-ups, the same way they would
+//! for a worker the channel spawned. So that people in the channel know what
+//! the session was doing, the last few messages are fetched over HTTP and
+//! posted as a short summary.
+
+use serde_json::Value;
+
+/// Messages included in the summary posted on attach.
+pub const SUMMARY_MESSAGES: usize = 8;
+
+/// Longest excerpt quoted from one message, in characters.
+const MAX_EXCERPT_CHARS: usize = 240;
+
+/// Summarize the last `limit` user and assistant messages of a session, one
+/// quoted line each, oldest first. Tool calls are counted, not quoted.
+/// Returns `None` when the session has no text yet.
+pub fn summarize_recent(messages: &[Value], limit: usize) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut tool_calls = 0;
+    for message in messages {
+        let role = message
+            .pointer("/info/role")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        let Some(parts) = message.get("parts").and_then(Value::as_array) else {
+            continue;
+        };
+        tool_calls += parts
+            .iter()
+            .filter(|part| part.get("type").and_then(Value::as_str) == Some("tool"))
+            .count();
+        let text = parts
+            .iter()
+            .filter(|part| part.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() || !matches!(role, "user" | "assistant") {
+            continue;
+        }
+        lines.push(format!("> **{role}:** {}", excerpt(&text)));
+    }
+    if lines.is_empty() {
+        return None;
+    }
+
+    let skipped = lines.len().saturating_sub(limit);
+    let mut summary = Vec::with_capacity(limit + 2);
+    if skipped > 0 {
+        summary.push(format!("> _({skipped} earlier not shown)_"));
+    }
+    summary.extend(lines.into_iter().skip(skipped));
+    if tool_calls > 0 {
+        summary.push(format!("_tool calls so far: {tool_calls}_"));
+    }
+    Some(summary.join("\n"))
+}
+
+fn excerpt(text: &str) -> String {
+    if text.chars().count() <= MAX_EXCERPT_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_EXCERPT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(role: &str, parts: Value) -> Value {
+        json!({ "info": { "role": role }, "parts": parts })
+    }
+
+    #[test]
+    fn summarizes_the_latest_text_messages() {
+        let messages = vec![
+            message(
+                "user",
+                json!([{ "type": "text", "text": "add a\nhealth check" }]),
+            ),
+            message(
+                "assistant",
+                json!([
+                    { "type": "tool", "tool": "read" },
+                    { "type": "text", "text": "Added `/healthz`." }
+                ]),
+            ),
+            message("user", json!([{ "type": "text", "text": "and tests" }])),
+            message("assistant", json!([{ "type": "step-start" }])),
+        ];
+
+        assert_eq!(
+            summarize_recent(&messages, 2).as_deref(),
+            Some(
+                "> _(1 earlier not shown)_\n\
+                 > **assistant:** Added `/healthz`.\n\
+                 > **user:** and tests\n\
+                 _tool calls so far: 1_"
+            )
+        );
+        assert_eq!(summarize_recent(&messages[3..], 2), None);
+    }
+
+    #[test]
+    fn long_messages_are_cut() {
+        let long = "word ".repeat(100);
+        let messages = vec![message(
+            "assistant",
+            json!([{ "type": "text", "text": long }]),
+        )];
+        let summary = summarize_recent(&messages, SUMMARY_MESSAGES).unwrap();
+        assert!(summary.ends_with('…'));
+        assert!(summary.chars().count() < MAX_EXCERPT_CHARS + 20);
+    }
+}
@@ -0,0 +1,105 @@
+//! What the connected OpenCode server supports, derived from its version.
+//!
+//! The version comes from `/global/health` when a server is spawned,
+//! reattached, or connected. Routes that changed between releases are picked
+//! from the resulting `ServerCapabilities`. A version outside the range
+//! Spacebot is tested against still works on a best-effort basis, but logs a
+//! warning on every connect.
+
+use semver::{Version, VersionReq};
+
+/// OpenCode releases Spacebot is tested against.
+pub const TESTED_VERSIONS: &str = ">=1.1.0, <2.0.0";
+
+/// First release with `POST /permission/{id}/reply` and `POST
+/// /question/{id}/reply`. Earlier releases reply to permissions at `POST
+/// /session/{session_id}/permissions/{id}` and have no question tool.
+const REQUEST_REPLY_ROUTES_SINCE: Version = Version::new(1, 1, 0);
+
+/// Features of one OpenCode server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    /// Version reported by the server. `None` if it didn't report one or it
+    /// wasn't valid semver, in which case the newest behavior is assumed.
+    pub version: Option<Version>,
+    /// Permission replies use `/permission/{id}/reply` rather than the
+    /// session-scoped route.
+    pub permission_reply_route: bool,
+    /// The server has the question tool and `/question/{id}/reply`.
+    pub questions: bool,
+}
+
+impl Default for ServerCapabilities {
+    /// Newest known behavior, for servers whose version is unknown.
+    fn default() -> Self {
+        Self {
+            version: None,
+            permission_reply_route: true,
+            questions: true,
+        }
+    }
+}
+
+impl ServerCapabilities {
+    /// Capabilities for a server reporting `version`.
+    pub fn for_version(version: Option<&str>) -> Self {
+        let Some(version) = version.and_then(parse_version) else {
+            return Self::default();
+        };
+        let request_reply_routes = version >= REQUEST_REPLY_ROUTES_SINCE;
+        Self {
+            version: Some(version),
+            permission_reply_route: request_reply_routes,
+            questions: request_reply_routes,
+        }
+    }
+
+    /// Whether the version is in [`TESTED_VERSIONS`]. Unknown versions are
+    /// not.
+    pub fn is_tested(&self) -> bool {
+        let tested = VersionReq::parse(TESTED_VERSIONS).expect("hardcoded version requirement");
+        self.version
+            .as_ref()
+            .is_some_and(|version| tested.matches(version))
+    }
+
+    /// Version for logs and errors.
+    pub fn version_label(&self) -> String {
+        self.version
+            .as_ref()
+            .map_or_else(|| "unknown".into(), ToString::to_string)
+    }
+}
+
+/// Parse a reported version, tolerating a leading `v`.
+fn parse_version(version: &str) -> Option<Version> {
+    Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_servers_use_session_scoped_permission_replies() {
+        let legacy = ServerCapabilities::for_version(Some("0.15.8"));
+        assert!(!legacy.permission_reply_route);
+        assert!(!legacy.questions);
+        assert!(!legacy.is_tested());
+
+        let current = ServerCapabilities::for_version(Some("v1.1.4"));
+        assert!(current.permission_reply_route);
+        assert!(current.questions);
+        assert!(current.is_tested());
+    }
+
+    #[test]
+    fn unknown_versions_assume_newest_behavior_but_are_untested() {
+        for version in [None, Some("dev")] {
+            let capabilities = ServerCapabilities::for_version(version);
+            assert!(capabilities.permission_reply_route);
+            assert!(!capabilities.is_tested());
+        }
+        assert!(!ServerCapabilities::for_version(Some("2.0.0")).is_tested());
+    }
+}
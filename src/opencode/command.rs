@@ -0,0 +1,139 @@
+//! Named OpenCode commands run from chat (`/oc`).
+//!
+//! OpenCode commands are prompt templates kept on the server: built-ins such
+//! as `init`, plus markdown files a repository defines under
+//! `.opencode/command/`. `/oc` lists them, and `/oc <name> [args]` runs one in
+//! the channel's idle session on the model the session last ran with. The
+//! command's reply is posted to chat directly.
+
+use crate::opencode::compact::session_model;
+use crate::opencode::server::OpenCodeServer;
+use crate::opencode::types::{CommandInfo, SendCommandRequest};
+
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+/// Parsed arguments of `/oc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcCommand {
+    List,
+    Run { name: String, arguments: String },
+}
+
+/// Parse the text after `/oc`. The command name may keep its leading `/`.
+pub fn parse_oc_command(arguments: &str) -> OcCommand {
+    let arguments = arguments.trim();
+    let (name, rest) = arguments
+        .split_once(char::is_whitespace)
+        .unwrap_or((arguments, ""));
+    let name = name.trim_start_matches('/');
+    if name.is_empty() || (name == "list" && rest.trim().is_empty()) {
+        return OcCommand::List;
+    }
+    OcCommand::Run {
+        name: name.to_string(),
+        arguments: rest.trim().to_string(),
+    }
+}
+
+/// One line per command: `- /name: description`.
+pub fn render_commands(commands: &[CommandInfo]) -> String {
+    commands
+        .iter()
+        .map(|command| match command.description.as_deref() {
+            Some(description) if !description.trim().is_empty() => {
+                format!("- /{}: {}", command.name, description.trim())
+            }
+            _ => format!("- /{}", command.name),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Run `name` in `session_id` and return the text of its reply, if any.
+pub async fn run_command(
+    server: &Mutex<OpenCodeServer>,
+    session_id: &str,
+    name: &str,
+    arguments: &str,
+) -> anyhow::Result<Option<String>> {
+    // Lock per request: a command can run for minutes, and `/status` locks
+    // every pooled server to label backends.
+    let messages = server.lock().await.get_messages(session_id).await?;
+    let request = SendCommandRequest {
+        command: name.to_string(),
+        arguments: arguments.to_string(),
+        model: session_model(&messages)
+            .map(|model| format!("{}/{}", model.provider_id, model.model_id)),
+    };
+    let response = server
+        .lock()
+        .await
+        .run_command(session_id, &request)
+        .await?;
+    Ok(reply_text(&response))
+}
+
+/// Text parts of a `{info, parts}` message, joined.
+fn reply_text(message: &Value) -> Option<String> {
+    let text = message
+        .get("parts")?
+        .as_array()?
+        .iter()
+        .filter(|part| part.get("type").and_then(Value::as_str) == Some("text"))
+        .filter(|part| part.get("synthetic").and_then(Value::as_bool) != Some(true))
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!text.trim().is_empty()).then(|| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_list_and_run() {
+        assert_eq!(parse_oc_command(""), OcCommand::List);
+        assert_eq!(parse_oc_command(" list "), OcCommand::List);
+        assert_eq!(
+            parse_oc_command("/release  v1.2.0 --dry"),
+            OcCommand::Run {
+                name: "release".into(),
+                arguments: "v1.2.0 --dry".into(),
+            }
+        );
+        // A command that happens to be called `list` still runs with arguments.
+        assert_eq!(
+            parse_oc_command("list open issues"),
+            OcCommand::Run {
+                name: "list".into(),
+                arguments: "open issues".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn renders_commands_and_replies() {
+        let commands: Vec<CommandInfo> = serde_json::from_value(serde_json::json!([
+            { "name": "init", "description": "create/update AGENTS.md", "template": "..." },
+            { "name": "release", "template": "..." },
+        ]))
+        .unwrap();
+        assert_eq!(
+            render_commands(&commands),
+            "- /init: create/update AGENTS.md\n- /release"
+        );
+
+        let response = serde_json::json!({
+            "info": { "role": "assistant" },
+            "parts": [
+                { "type": "text", "text": "command prompt", "synthetic": true },
+                { "type": "tool", "tool": "bash" },
+                { "type": "text", "text": "Tagged v1.2.0." },
+            ],
+        });
+        assert_eq!(reply_text(&response).as_deref(), Some("Tagged v1.2.0."));
+        assert_eq!(reply_text(&serde_json::json!({ "parts": [] })), None);
+    }
+}
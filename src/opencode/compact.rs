@@ -0,0 +1,122 @@
+//! On-demand compaction of an idle OpenCode session (`/compact`).
+//!
+//! OpenCode compacts a session by asking a model to summarize the
+//! conversation so far; later turns start from that summary instead of the
+//! full history. The summary is written as an assistant message flagged
+//! `summary: true`, which is what gets posted back to chat. Compaction uses
+//! the model the session last ran with, read from its message history.
+
+use crate::conversation::worker_transcript::{TranscriptStep, convert_opencode_messages};
+use crate::opencode::server::OpenCodeServer;
+use crate::opencode::types::SummarizeRequest;
+
+use anyhow::Context as _;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+/// Result of compacting a session.
+pub struct Compaction {
+    pub summary: String,
+    /// The session's full transcript after compaction, including the summary.
+    pub transcript: Vec<TranscriptStep>,
+}
+
+/// Summarize `session_id` and return the summary with the refreshed transcript.
+pub async fn compact_session(
+    server: &Mutex<OpenCodeServer>,
+    session_id: &str,
+) -> anyhow::Result<Compaction> {
+    // Lock per request: summarizing can take minutes, and `/status` locks
+    // every pooled server to label backends.
+    let messages = server.lock().await.get_messages(session_id).await?;
+    let request =
+        session_model(&messages).context("the session has no model to summarize with yet")?;
+    server
+        .lock()
+        .await
+        .summarize_session(session_id, &request)
+        .await?;
+
+    let messages = server.lock().await.get_messages(session_id).await?;
+    let summary =
+        latest_summary(&messages).context("OpenCode did not produce a session summary")?;
+    let (transcript, _) = convert_opencode_messages(&messages);
+    Ok(Compaction {
+        summary,
+        transcript,
+    })
+}
+
+/// Provider and model of the most recent message that names one. Assistant
+/// messages carry `providerID`/`modelID`; user messages carry
+/// `model: {providerID, modelID}`.
+pub(crate) fn session_model(messages: &[Value]) -> Option<SummarizeRequest> {
+    messages.iter().rev().find_map(|message| {
+        let info = message.get("info")?;
+        let model = info.get("model").unwrap_or(info);
+        Some(SummarizeRequest {
+            provider_id: model.get("providerID")?.as_str()?.to_string(),
+            model_id: model.get("modelID")?.as_str()?.to_string(),
+        })
+    })
+}
+
+/// Text of the newest assistant message flagged as a summary.
+fn latest_summary(messages: &[Value]) -> Option<String> {
+    let message = messages.iter().rev().find(|message| {
+        message.pointer("/info/role").and_then(Value::as_str) == Some("assistant")
+            && message.pointer("/info/summary").and_then(Value::as_bool) == Some(true)
+    })?;
+    let text = message
+        .get("parts")?
+        .as_array()?
+        .iter()
+        .filter(|part| part.get("type").and_then(Value::as_str) == Some("text"))
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!text.trim().is_empty()).then(|| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_model_and_summary_from_history() {
+        let messages = vec![
+            serde_json::json!({
+                "info": {
+                    "role": "user",
+                    "model": { "providerID": "anthropic", "modelID": "claude-sonnet-4" },
+                },
+                "parts": [{ "type": "text", "text": "fix the tests" }],
+            }),
+            serde_json::json!({
+                "info": { "role": "assistant", "providerID": "openai", "modelID": "gpt-5" },
+                "parts": [{ "type": "text", "text": "fixed" }],
+            }),
+            serde_json::json!({
+                "info": { "role": "assistant", "summary": true },
+                "parts": [
+                    { "type": "text", "text": "Fixed the failing tests." },
+                    { "type": "step-finish" },
+                    { "type": "text", "text": "Next: update docs." },
+                ],
+            }),
+        ];
+
+        let model = session_model(&messages).unwrap();
+        assert_eq!(model.provider_id, "openai");
+        assert_eq!(model.model_id, "gpt-5");
+        assert_eq!(
+            latest_summary(&messages).as_deref(),
+            Some("Fixed the failing tests.\n\nNext: update docs.")
+        );
+        assert_eq!(latest_summary(&messages[..2]), None);
+        assert_eq!(
+            session_model(&messages[..1]).unwrap().model_id,
+            "claude-sonnet-4"
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! Merged event stream across several remote OpenCode backends.
+//!
+//! Each backend gets its own task, its own reconnect backoff, and its own
+//! bounded event buffer. A backend that is down, reconnecting, or streaming
+//! slowly only stalls its own task. Downstream, [`FanIn`] polls every buffer
+//! fairly and yields events tagged with the backend they came from.
+//!
+//! A reconnect resumes where the previous stream left off. When the server
+//! tags events with SSE `id:` fields, the last one is sent back as
+//! `Last-Event-ID`. Otherwise the sessions that were busy at the disconnect
+//! are resynced from the HTTP API, replaying their messages and parts.
+
+use crate::config::OpenCodeBackendConfig;
+use crate::opencode::server::OpenCodeServer;
+use crate::opencode::types::{MessageInfo, OpenCodePermissions, SessionStatusPayload, SseEvent};
+use crate::opencode::worker::{extract_sse_event_with_id, message_events};
+
+use anyhow::Context as _;
+use futures::StreamExt as _;
+use tokio::sync::mpsc;
+use tokio_stream::StreamMap;
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+const RECONNECT_BACKOFF_INITIAL: Duration = Duration::from_secs(2);
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Events buffered per backend before its task waits for the consumer.
+pub const BACKEND_BUFFER: usize = 256;
+
+/// An event from one backend.
+#[derive(Debug, Clone)]
+pub struct TaggedEvent {
+    /// Name of the backend that sent the event.
+    pub backend: Arc<str>,
+    pub event: SseEvent,
+}
+
+/// Merged, tagged event stream over any number of backends.
+#[derive(Default)]
+pub struct FanIn {
+    streams: StreamMap<Arc<str>, ReceiverStream<SseEvent>>,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl FanIn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to `backend`'s event stream in a task of its own, which
+    /// reconnects with backoff until the `FanIn` is dropped.
+    pub fn attach(&mut self, backend: OpenCodeBackendConfig, permissions: OpenCodePermissions) {
+        let name: Arc<str> = Arc::from(backend.name.as_str());
+        self.attach_with(name, move |event_tx| {
+            stream_backend(backend, permissions, event_tx)
+        });
+    }
+
+    /// Attach a producer that sends one backend's events into `event_tx`.
+    fn attach_with<F, Fut>(&mut self, backend: Arc<str>, producer: F)
+    where
+        F: FnOnce(mpsc::Sender<SseEvent>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (event_tx, event_rx) = mpsc::channel(BACKEND_BUFFER);
+        self.tasks.push(tokio::spawn(producer(event_tx)));
+        self.streams.insert(backend, ReceiverStream::new(event_rx));
+    }
+
+    /// Number of attached backends.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Next event from any backend. Returns `None` once every backend task
+    /// has stopped.
+    pub async fn next(&mut self) -> Option<TaggedEvent> {
+        let (backend, event) = self.streams.next().await?;
+        Some(TaggedEvent { backend, event })
+    }
+}
+
+impl Drop for FanIn {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Where a reconnect picks up from.
+#[derive(Debug, Default)]
+struct StreamCursor {
+    /// ID of the last event received, when the server sends `id:` fields.
+    last_event_id: Option<String>,
+    /// Sessions busy when last heard from, resynced after a reconnect that
+    /// can't resume from an event ID.
+    active_sessions: HashSet<String>,
+    /// Whether a stream was attached before, making the next one a reconnect.
+    attached: bool,
+}
+
+impl StreamCursor {
+    fn observe(&mut self, event: &SseEvent) {
+        match event {
+            SseEvent::MessageUpdated {
+                info:
+                    Some(MessageInfo {
+                        session_id: Some(session_id),
+                        ..
+                    }),
+            }
+            | SseEvent::SessionStatus {
+                session_id,
+                status: SessionStatusPayload::Busy | SessionStatusPayload::Retry { .. },
+            } => {
+                self.active_sessions.insert(session_id.clone());
+            }
+            SseEvent::SessionIdle { session_id }
+            | SseEvent::SessionStatus {
+                session_id,
+                status: SessionStatusPayload::Idle,
+            } => {
+                self.active_sessions.remove(session_id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Forward one backend's events until `event_tx` closes, reconnecting after
+/// every disconnect.
+async fn stream_backend(
+    backend: OpenCodeBackendConfig,
+    permissions: OpenCodePermissions,
+    event_tx: mpsc::Sender<SseEvent>,
+) {
+    let mut backoff = RECONNECT_BACKOFF_INITIAL;
+    let mut cursor = StreamCursor::default();
+    loop {
+        let result = stream_once(&backend, &permissions, &event_tx, &mut cursor).await;
+        if event_tx.is_closed() {
+            return;
+        }
+        match result {
+            Ok(()) => {
+                tracing::info!(
+                    backend = %backend.name,
+                    "opencode event stream ended, reconnecting"
+                );
+                backoff = RECONNECT_BACKOFF_INITIAL;
+            }
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    backend = %backend.name,
+                    retry_in_secs = backoff.as_secs(),
+                    "opencode event stream disconnected"
+                );
+            }
+        }
+        crate::notifications::ops::OpsAlerts::global()
+            .reconnect(&format!("opencode backend {}", backend.name));
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RECONNECT_BACKOFF_MAX);
+    }
+}
+
+async fn stream_once(
+    backend: &OpenCodeBackendConfig,
+    permissions: &OpenCodePermissions,
+    event_tx: &mpsc::Sender<SseEvent>,
+    cursor: &mut StreamCursor,
+) -> anyhow::Result<()> {
+    let server =
+        OpenCodeServer::connect_remote(backend.directory.clone(), backend, permissions).await?;
+    let response = server
+        .subscribe_events_after(cursor.last_event_id.as_deref())
+        .await?;
+    tracing::info!(
+        backend = %backend.name,
+        resumed_from = cursor.last_event_id.as_deref(),
+        "opencode event stream attached"
+    );
+
+    // Subscribed first, so nothing that happens during the resync is lost.
+    if cursor.attached && cursor.last_event_id.is_none() {
+        for session_id in cursor.active_sessions.clone() {
+            let events = match resync_session(&server, &session_id).await {
+                Ok(events) => events,
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        backend = %backend.name,
+                        %session_id,
+                        "failed to resync opencode session after reconnect"
+                    );
+                    continue;
+                }
+            };
+            for event in events {
+                if event_tx.send(event).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    cursor.attached = true;
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    while let Some(chunk) = stream.next().await {
+        let bytes = chunk.context("failed to read SSE chunk")?;
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+        while let Some(event) = extract_sse_event_with_id(&mut buffer, &mut cursor.last_event_id) {
+            cursor.observe(&event);
+            if event_tx.send(event).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A session's messages and parts from the HTTP API, as the events that
+/// would have delivered them. Consumers upsert parts by ID, so parts they
+/// already have are overwritten with their latest version.
+async fn resync_session(
+    server: &OpenCodeServer,
+    session_id: &str,
+) -> anyhow::Result<Vec<SseEvent>> {
+    let messages = server.get_messages(session_id).await?;
+    Ok(message_events(messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opencode::types::Part;
+
+    fn idle(session_id: &str) -> SseEvent {
+        SseEvent::SessionIdle {
+            session_id: session_id.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn stalled_backend_does_not_block_the_others() {
+        let mut fan_in = FanIn::new();
+        // Floods its buffer as fast as the consumer drains it.
+        fan_in.attach_with("stuck".into(), |event_tx| async move {
+            loop {
+                if event_tx.send(idle("stuck")).await.is_err() {
+                    return;
+                }
+            }
+        });
+        // Connects, sends nothing, and never disconnects.
+        fan_in.attach_with("silent".into(), |event_tx| async move {
+            std::future::pending::<()>().await;
+            drop(event_tx);
+        });
+        fan_in.attach_with("healthy".into(), |event_tx| async move {
+            for index in 0..3 {
+                event_tx.send(idle(&format!("ses-{index}"))).await.ok();
+            }
+        });
+
+        let mut healthy = 0;
+        while healthy < 3 {
+            let tagged = tokio::time::timeout(Duration::from_secs(5), fan_in.next())
+                .await
+                .expect("healthy backend events arrive")
+                .expect("stream still open");
+            if &*tagged.backend == "healthy" {
+                healthy += 1;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn ends_once_every_backend_stops() {
+        let mut fan_in = FanIn::new();
+        for name in ["a", "b"] {
+            fan_in.attach_with(name.into(), move |event_tx| async move {
+                event_tx.send(idle(name)).await.ok();
+            });
+        }
+
+        let mut backends = Vec::new();
+        while let Some(tagged) = fan_in.next().await {
+            backends.push(tagged.backend.to_string());
+        }
+        backends.sort();
+        assert_eq!(backends, ["a", "b"]);
+    }
+
+    #[test]
+    fn cursor_tracks_busy_sessions_and_event_ids() {
+        let mut cursor = StreamCursor::default();
+        let mut buffer = concat!(
+            "id: 41\n",
+            "data: {\"type\":\"session.status\",\"properties\":",
+            "{\"sessionID\":\"ses-1\",\"status\":{\"type\":\"busy\"}}}\n\n",
+            "data: {\"type\":\"session.status\",\"properties\":",
+            "{\"sessionID\":\"ses-2\",\"status\":{\"type\":\"busy\"}}}\n\n",
+        )
+        .to_string();
+        while let Some(event) = extract_sse_event_with_id(&mut buffer, &mut cursor.last_event_id) {
+            cursor.observe(&event);
+        }
+        // The second event carries no ID, so the first one's still applies.
+        assert_eq!(cursor.last_event_id.as_deref(), Some("41"));
+        assert_eq!(cursor.active_sessions.len(), 2);
+
+        cursor.observe(&idle("ses-1"));
+        assert_eq!(cursor.active_sessions.iter().collect::<Vec<_>>(), ["ses-2"]);
+    }
+
+    #[test]
+    fn resync_replays_messages_and_parts() {
+        let messages = vec![serde_json::json!({
+            "info": { "id": "msg-1", "role": "assistant", "sessionID": "ses-1" },
+            "parts": [
+                { "type": "text", "id": "part-1", "sessionID": "ses-1",
+                  "messageID": "msg-1", "text": "done" },
+                { "type": "tool", "id": "part-2", "sessionID": "ses-1",
+                  "messageID": "msg-1", "tool": "bash",
+                  "state": { "status": "completed", "input": {}, "output": "ok" } },
+            ],
+        })];
+
+        let events = message_events(messages);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[0],
+            SseEvent::MessageUpdated { info: Some(info) } if info.role == "assistant"
+        ));
+        assert!(matches!(
+            &events[2],
+            SseEvent::MessagePartUpdated { part: Part::Tool { id, .. }, delta: None }
+                if id == "part-2"
+        ));
+    }
+}
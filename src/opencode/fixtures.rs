@@ -0,0 +1,481 @@
+//! Seeded generator for synthetic OpenCode SSE event streams.
+//!
+//! Produces realistic `/event` traffic — user prompts, streamed assistant
+//! text, tool lifecycles, permission round-trips, and session errors — from
+//! a seed, so load and property tests don't depend on hand-captured corpora.
+//! The same seed and config always produce the same stream.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{Value, json};
+
+/// Fixed epoch (milliseconds) that fixture timestamps count up from.
+const FIXTURE_EPOCH_MS: u64 = 1_770_000_000_000;
+
+/// Words used to build assistant and user text.
+const VOCABULARY: &[&str] = &[
+    "refactor", "the", "parser", "to", "handle", "nested", "blocks", "and", "update", "tests",
+    "build", "passes", "now", "checking", "config", "loader", "error", "path", "fixed", "module",
+];
+
+/// Shape of the generated stream.
+#[derive(Debug, Clone)]
+pub struct FixtureConfig {
+    /// Number of sessions, emitted one after another.
+    pub sessions: usize,
+    /// Prompt/response rounds per session.
+    pub messages_per_session: usize,
+    /// Tool names and relative weights to draw tool calls from.
+    pub tool_mix: Vec<(String, u32)>,
+    /// Upper bound on tool calls per assistant message (inclusive).
+    pub max_tools_per_message: usize,
+    /// Number of `delta` events a streamed text part is split into.
+    pub text_deltas: usize,
+    /// Probability a tool call ends in the `error` state.
+    pub tool_error_rate: f64,
+    /// Probability a tool call triggers a permission ask/reply round-trip.
+    pub permission_rate: f64,
+    /// Probability a session ends with `session.error` instead of idling.
+    pub session_error_rate: f64,
+}
+
+impl Default for FixtureConfig {
+    fn default() -> Self {
+        Self {
+            sessions: 1,
+            messages_per_session: 3,
+            tool_mix: vec![
+                ("bash".into(), 4),
+                ("read".into(), 3),
+                ("edit".into(), 2),
+                ("grep".into(), 1),
+            ],
+            max_tools_per_message: 3,
+            text_deltas: 4,
+            tool_error_rate: 0.1,
+            permission_rate: 0.05,
+            session_error_rate: 0.0,
+        }
+    }
+}
+
+/// A generated stream of SSE envelopes (`{ type, properties }`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseFixture {
+    pub events: Vec<Value>,
+}
+
+impl SseFixture {
+    /// Render as an SSE body: one `data:` line per event, blank-line separated.
+    pub fn to_sse_string(&self) -> String {
+        let mut body = String::new();
+        for event in &self.events {
+            body.push_str("data: ");
+            body.push_str(&event.to_string());
+            body.push_str("\n\n");
+        }
+        body
+    }
+
+    /// Split the SSE body into chunks at seeded random byte offsets, the way a
+    /// network stream arrives. Exercises buffering across event boundaries.
+    pub fn to_chunks(&self, seed: u64, max_chunk_bytes: usize) -> Vec<Vec<u8>> {
+        let body = self.to_sse_string().into_bytes();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let max_chunk_bytes = max_chunk_bytes.max(1);
+        let mut chunks = Vec::new();
+        let mut offset = 0;
+        while offset < body.len() {
+            let size = rng
+                .random_range(1..=max_chunk_bytes)
+                .min(body.len() - offset);
+            chunks.push(body[offset..offset + size].to_vec());
+            offset += size;
+        }
+        chunks
+    }
+
+    /// Count events of a given `type`.
+    pub fn count(&self, event_type: &str) -> usize {
+        self.events
+            .iter()
+            .filter(|event| event["type"] == event_type)
+            .count()
+    }
+}
+
+/// Generate a deterministic fixture stream from `seed`.
+pub fn generate(seed: u64, config: &FixtureConfig) -> SseFixture {
+    let mut generator = Generator {
+        rng: StdRng::seed_from_u64(seed),
+        seed,
+        clock_ms: FIXTURE_EPOCH_MS,
+        counter: 0,
+        events: Vec::new(),
+    };
+    for _ in 0..config.sessions {
+        generator.session(config);
+    }
+    SseFixture {
+        events: generator.events,
+    }
+}
+
+struct Generator {
+    rng: StdRng,
+    seed: u64,
+    clock_ms: u64,
+    counter: u64,
+    events: Vec<Value>,
+}
+
+impl Generator {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.counter += 1;
+        format!("{prefix}_{:08x}{:06}", self.seed as u32, self.counter)
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock_ms += self.rng.random_range(5..250);
+        self.clock_ms
+    }
+
+    fn push(&mut self, event_type: &str, properties: Value) {
+        self.events
+            .push(json!({ "type": event_type, "properties": properties }));
+    }
+
+    fn sentence(&mut self, words: usize) -> String {
+        (0..words.max(1))
+            .map(|_| VOCABULARY[self.rng.random_range(0..VOCABULARY.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn pick_tool(&mut self, tool_mix: &[(String, u32)]) -> String {
+        let total: u32 = tool_mix.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return "bash".to_string();
+        }
+        let mut roll = self.rng.random_range(0..total);
+        for (tool, weight) in tool_mix {
+            if roll < *weight {
+                return tool.clone();
+            }
+            roll -= weight;
+        }
+        "bash".to_string()
+    }
+
+    fn session(&mut self, config: &FixtureConfig) {
+        let session_id = self.next_id("ses");
+        self.push(
+            "session.status",
+            json!({ "sessionID": session_id, "status": { "type": "busy" } }),
+        );
+
+        for _ in 0..config.messages_per_session {
+            self.user_message(&session_id);
+            self.assistant_message(&session_id, config);
+        }
+
+        if self
+            .rng
+            .random_bool(config.session_error_rate.clamp(0.0, 1.0))
+        {
+            self.push(
+                "session.error",
+                json!({
+                    "sessionID": session_id,
+                    "error": { "name": "ProviderError", "data": { "message": "synthetic failure" } }
+                }),
+            );
+        } else {
+            self.push(
+                "session.status",
+                json!({ "sessionID": session_id, "status": { "type": "idle" } }),
+            );
+            self.push("session.idle", json!({ "sessionID": session_id }));
+        }
+    }
+
+    fn user_message(&mut self, session_id: &str) {
+        let message_id = self.next_id("msg");
+        let created = self.tick();
+        self.push(
+            "message.updated",
+            json!({ "info": {
+                "id": message_id, "sessionID": session_id, "role": "user",
+                "time": { "created": created }
+            }}),
+        );
+        let part_id = self.next_id("prt");
+        let words = self.rng.random_range(4..16);
+        let text = self.sentence(words);
+        self.push(
+            "message.part.updated",
+            json!({ "part": {
+                "id": part_id, "sessionID": session_id, "messageID": message_id,
+                "type": "text", "text": text
+            }}),
+        );
+    }
+
+    fn assistant_message(&mut self, session_id: &str, config: &FixtureConfig) {
+        let message_id = self.next_id("msg");
+        let created = self.tick();
+        self.push(
+            "message.updated",
+            json!({ "info": {
+                "id": message_id, "sessionID": session_id, "role": "assistant",
+                "time": { "created": created }
+            }}),
+        );
+        let step_id = self.next_id("prt");
+        self.push(
+            "message.part.updated",
+            json!({ "part": { "id": step_id, "sessionID": session_id, "type": "step-start" }}),
+        );
+
+        self.streamed_text(session_id, &message_id, config.text_deltas);
+
+        let tool_count = self.rng.random_range(0..=config.max_tools_per_message);
+        for _ in 0..tool_count {
+            self.tool_call(session_id, &message_id, config);
+        }
+
+        let finish_id = self.next_id("prt");
+        self.push(
+            "message.part.updated",
+            json!({ "part": {
+                "id": finish_id, "sessionID": session_id, "type": "step-finish", "reason": "stop"
+            }}),
+        );
+    }
+
+    fn streamed_text(&mut self, session_id: &str, message_id: &str, deltas: usize) {
+        let part_id = self.next_id("prt");
+        let start = self.tick();
+        let mut text = String::new();
+        for _ in 0..deltas.max(1) {
+            let words = self.rng.random_range(1..6);
+            let delta = format!("{} ", self.sentence(words));
+            text.push_str(&delta);
+            self.push(
+                "message.part.updated",
+                json!({ "part": {
+                    "id": part_id, "sessionID": session_id, "messageID": message_id,
+                    "type": "text", "text": text, "time": { "start": start }
+                }, "delta": delta }),
+            );
+        }
+        let end = self.tick();
+        self.push(
+            "message.part.updated",
+            json!({ "part": {
+                "id": part_id, "sessionID": session_id, "messageID": message_id,
+                "type": "text", "text": text.trim_end(), "time": { "start": start, "end": end }
+            }}),
+        );
+    }
+
+    fn tool_call(&mut self, session_id: &str, message_id: &str, config: &FixtureConfig) {
+        let part_id = self.next_id("prt");
+        let call_id = self.next_id("call");
+        let tool = self.pick_tool(&config.tool_mix);
+        let input = match tool.as_str() {
+            "bash" => json!({ "command": format!("cargo {}", self.sentence(1)) }),
+            "read" | "edit" => json!({ "filePath": format!("src/{}.rs", self.sentence(1)) }),
+            "grep" => json!({ "pattern": self.sentence(1) }),
+            _ => json!({ "query": self.sentence(2) }),
+        };
+        let part = |state: Value| {
+            json!({ "part": {
+                "id": part_id, "sessionID": session_id, "messageID": message_id,
+                "type": "tool", "callID": call_id, "tool": tool, "state": state
+            }})
+        };
+
+        self.push(
+            "message.part.updated",
+            part(json!({ "status": "pending", "input": {}, "raw": "" })),
+        );
+
+        if self.rng.random_bool(config.permission_rate.clamp(0.0, 1.0)) {
+            let request_id = self.next_id("per");
+            self.push(
+                "permission.asked",
+                json!({
+                    "id": request_id, "sessionID": session_id, "permission": tool,
+                    "patterns": ["*"], "metadata": {}
+                }),
+            );
+            self.push(
+                "permission.replied",
+                json!({ "sessionID": session_id, "requestID": request_id, "reply": "once" }),
+            );
+        }
+
+        let start = self.tick();
+        self.push(
+            "message.part.updated",
+            part(json!({
+                "status": "running", "input": input, "title": tool, "time": { "start": start }
+            })),
+        );
+
+        let end = self.tick();
+        let final_state = if self.rng.random_bool(config.tool_error_rate.clamp(0.0, 1.0)) {
+            json!({
+                "status": "error", "input": input, "error": "synthetic tool failure",
+                "time": { "start": start, "end": end }
+            })
+        } else {
+            let lines = self.rng.random_range(1..4);
+            let output = (0..lines)
+                .map(|_| self.sentence(5))
+                .collect::<Vec<_>>()
+                .join("\n");
+            json!({
+                "status": "completed", "input": input, "output": output, "title": tool,
+                "time": { "start": start, "end": end }
+            })
+        };
+        self.push("message.part.updated", part(final_state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opencode::types::{
+        Part, SseEvent, SseEventEnvelope, SseEventEnvelopeRef, ToolState,
+    };
+    use crate::opencode::worker::extract_sse_event;
+
+    #[test]
+    fn same_seed_produces_same_stream() {
+        let config = FixtureConfig::default();
+        assert_eq!(generate(42, &config), generate(42, &config));
+        assert_ne!(generate(42, &config), generate(43, &config));
+    }
+
+    #[test]
+    fn every_event_parses_into_a_known_variant() {
+        let config = FixtureConfig {
+            sessions: 3,
+            permission_rate: 0.5,
+            session_error_rate: 0.5,
+            ..FixtureConfig::default()
+        };
+        let fixture = generate(7, &config);
+        for event in &fixture.events {
+            let envelope: SseEventEnvelope =
+                serde_json::from_value(event.clone()).expect("valid envelope");
+            let parsed = SseEvent::from_envelope(envelope);
+            assert!(
+                !matches!(parsed, SseEvent::Unknown(_)),
+                "unparsed fixture event: {event}"
+            );
+
+            let raw = event.to_string();
+            let borrowed: SseEventEnvelopeRef =
+                serde_json::from_str(&raw).expect("valid borrowed envelope");
+            assert_eq!(
+                std::mem::discriminant(&SseEvent::from_envelope_ref(borrowed)),
+                std::mem::discriminant(&parsed),
+                "borrowed parse differs for: {event}"
+            );
+        }
+        assert_eq!(
+            fixture.count("session.idle") + fixture.count("session.error"),
+            3
+        );
+    }
+
+    #[test]
+    fn chunked_stream_round_trips_through_sse_parser() {
+        let config = FixtureConfig {
+            tool_error_rate: 0.3,
+            ..FixtureConfig::default()
+        };
+        let fixture = generate(99, &config);
+
+        let mut buffer = String::new();
+        let mut parsed = Vec::new();
+        for chunk in fixture.to_chunks(5, 64) {
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+            while let Some(event) = extract_sse_event(&mut buffer) {
+                parsed.push(event);
+            }
+        }
+        assert_eq!(parsed.len(), fixture.events.len());
+
+        let finished_tools = parsed
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    SseEvent::MessagePartUpdated {
+                        part: Part::Tool {
+                            state: Some(ToolState::Completed { .. } | ToolState::Error { .. }),
+                            ..
+                        },
+                        ..
+                    }
+                )
+            })
+            .count();
+        let running_tools = parsed
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    SseEvent::MessagePartUpdated {
+                        part: Part::Tool {
+                            state: Some(ToolState::Running { .. }),
+                            ..
+                        },
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(finished_tools, running_tools);
+    }
+
+    #[test]
+    fn unmodeled_fields_are_kept_instead_of_failing() {
+        let envelope: SseEventEnvelope = serde_json::from_value(json!({
+            "type": "message.part.updated",
+            "properties": {
+                "part": {
+                    "type": "tool",
+                    "id": "prt_1",
+                    "tool": "bash",
+                    "futureField": 7,
+                    "state": {
+                        "status": "completed",
+                        "output": "ok",
+                        "exitCode": 0,
+                    },
+                },
+            },
+        }))
+        .unwrap();
+
+        let SseEvent::MessagePartUpdated { part, .. } = SseEvent::from_envelope(envelope) else {
+            panic!("expected a part update");
+        };
+        assert_eq!(part.extra().unwrap()["futureField"], json!(7));
+        let Part::Tool {
+            state: Some(state), ..
+        } = &part
+        else {
+            panic!("expected a tool part");
+        };
+        assert!(state.is_completed());
+        assert_eq!(state.extra()["exitCode"], json!(0));
+        assert!(!state.extra().contains_key("status"));
+    }
+}
@@ -0,0 +1,554 @@
+//! Read-only mirroring of OpenCode sessions for compliance recording.
+//!
+//! A mirror attaches to a remote OpenCode backend's event stream and records
+//! every session it sees, including ones started from the OpenCode TUI or by
+//! other clients. It never creates sessions, sends prompts, or answers
+//! permissions — it only listens.
+
+use crate::AgentId;
+use crate::config::OpenCodeBackendConfig;
+use crate::opencode::fan_in::FanIn;
+use crate::opencode::types::{OpenCodePermissions, Part, SseEvent, ToolState};
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// A single recorded entry from a mirrored session.
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct MirrorRecord {
+    pub id: String,
+    pub backend: String,
+    pub session_id: String,
+    pub message_id: Option<String>,
+    pub role: Option<String>,
+    /// `text`, `tool`, `permission`, or `error`.
+    pub kind: String,
+    pub tool: Option<String>,
+    /// Tool input as JSON, when `kind` is `tool`.
+    pub input: Option<String>,
+    pub content: String,
+    pub recorded_at: String,
+}
+
+/// Summary of a mirrored session for listing and search results.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct MirrorSessionSummary {
+    pub session_id: String,
+    pub backend: String,
+    pub entry_count: i64,
+    pub first_recorded_at: String,
+    pub last_recorded_at: String,
+}
+
+/// SQLite persistence for mirrored sessions.
+#[derive(Debug, Clone)]
+pub struct OpenCodeMirrorStore {
+    pool: SqlitePool,
+}
+
+impl OpenCodeMirrorStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert or update a record. Parts are re-emitted as they change, so the
+    /// latest content wins while the original `recorded_at` is kept.
+    pub async fn record(&self, agent_id: &str, record: &MirrorRecord) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO opencode_mirror_parts \
+             (id, agent_id, backend, session_id, message_id, role, kind, tool, input, content) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (agent_id, backend, id) DO UPDATE SET \
+             role = COALESCE(excluded.role, opencode_mirror_parts.role), \
+             input = excluded.input, content = excluded.content",
+        )
+        .bind(&record.id)
+        .bind(agent_id)
+        .bind(&record.backend)
+        .bind(&record.session_id)
+        .bind(&record.message_id)
+        .bind(&record.role)
+        .bind(&record.kind)
+        .bind(&record.tool)
+        .bind(&record.input)
+        .bind(&record.content)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(())
+    }
+
+    /// List mirrored sessions, most recent first. When `query` is set, only
+    /// sessions with an entry whose content or tool input contains it match.
+    pub async fn search_sessions(
+        &self,
+        agent_id: &str,
+        query: Option<&str>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<MirrorSessionSummary>> {
+        let pattern = query
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .map(|query| format!("%{}%", escape_like(query)));
+
+        let rows = sqlx::query(
+            "SELECT session_id, backend, COUNT(*) AS entry_count, \
+             MIN(recorded_at) AS first_recorded_at, MAX(recorded_at) AS last_recorded_at \
+             FROM opencode_mirror_parts \
+             WHERE agent_id = ? AND (? IS NULL OR session_id IN ( \
+                 SELECT session_id FROM opencode_mirror_parts \
+                 WHERE agent_id = ? AND (content LIKE ? ESCAPE '\\' OR input LIKE ? ESCAPE '\\'))) \
+             GROUP BY session_id, backend \
+             ORDER BY last_recorded_at DESC \
+             LIMIT ?",
+        )
+        .bind(agent_id)
+        .bind(&pattern)
+        .bind(agent_id)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MirrorSessionSummary {
+                session_id: row.try_get("session_id").unwrap_or_default(),
+                backend: row.try_get("backend").unwrap_or_default(),
+                entry_count: row.try_get("entry_count").unwrap_or(0),
+                first_recorded_at: row.try_get("first_recorded_at").unwrap_or_default(),
+                last_recorded_at: row.try_get("last_recorded_at").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// All recorded entries for a session in the order they were first seen.
+    pub async fn session_records(
+        &self,
+        agent_id: &str,
+        session_id: &str,
+    ) -> crate::error::Result<Vec<MirrorRecord>> {
+        let rows = sqlx::query(
+            "SELECT id, backend, session_id, message_id, role, kind, tool, input, content, \
+             recorded_at FROM opencode_mirror_parts \
+             WHERE agent_id = ? AND session_id = ? \
+             ORDER BY recorded_at ASC, rowid ASC",
+        )
+        .bind(agent_id)
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MirrorRecord {
+                id: row.try_get("id").unwrap_or_default(),
+                backend: row.try_get("backend").unwrap_or_default(),
+                session_id: row.try_get("session_id").unwrap_or_default(),
+                message_id: row.try_get("message_id").ok().flatten(),
+                role: row.try_get("role").ok().flatten(),
+                kind: row.try_get("kind").unwrap_or_default(),
+                tool: row.try_get("tool").ok().flatten(),
+                input: row.try_get("input").ok().flatten(),
+                content: row.try_get("content").unwrap_or_default(),
+                recorded_at: row.try_get("recorded_at").unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// A backend to mirror and the agent database its sessions are recorded in.
+#[derive(Debug, Clone)]
+pub struct MirrorTarget {
+    pub agent_id: AgentId,
+    pub backend: OpenCodeBackendConfig,
+    pub permissions: OpenCodePermissions,
+    pub pool: SqlitePool,
+}
+
+/// Spawn a background task that mirrors every target. Each backend streams
+/// in its own [`FanIn`] task, so one that is down or slow doesn't hold up
+/// the others. Targets are keyed by backend name; a repeated name is skipped.
+pub fn spawn_mirrors(targets: Vec<MirrorTarget>) -> tokio::task::JoinHandle<()> {
+    let mut fan_in = FanIn::new();
+    let mut mirrors = HashMap::new();
+    for target in targets {
+        let name: Arc<str> = Arc::from(target.backend.name.as_str());
+        if mirrors.contains_key(&name) {
+            tracing::warn!(
+                backend = %name,
+                "another mirrored opencode backend has the same name, skipping"
+            );
+            continue;
+        }
+        fan_in.attach(target.backend, target.permissions);
+        mirrors.insert(
+            name.clone(),
+            Mirror {
+                agent_id: target.agent_id,
+                store: OpenCodeMirrorStore::new(target.pool),
+                recorder: MirrorRecorder::new(name.to_string()),
+            },
+        );
+    }
+
+    tokio::spawn(async move {
+        while let Some(tagged) = fan_in.next().await {
+            let Some(mirror) = mirrors.get_mut(&tagged.backend) else {
+                continue;
+            };
+            let Some(record) = mirror.recorder.observe(&tagged.event) else {
+                continue;
+            };
+            if let Err(error) = mirror.store.record(&mirror.agent_id, &record).await {
+                tracing::warn!(
+                    %error,
+                    agent_id = %mirror.agent_id,
+                    backend = %tagged.backend,
+                    session_id = %record.session_id,
+                    "failed to persist opencode mirror record"
+                );
+            }
+        }
+    })
+}
+
+/// Where one backend's mirrored events go.
+struct Mirror {
+    agent_id: AgentId,
+    store: OpenCodeMirrorStore,
+    recorder: MirrorRecorder,
+}
+
+/// Most message roles a recorder remembers. Roles are dropped when their
+/// session goes idle; this bounds sessions that never do.
+const MAX_MESSAGE_ROLES: usize = 512;
+
+/// The role of a message seen on the stream, and the session it belongs to.
+struct MessageRole {
+    session_id: Option<String>,
+    role: String,
+}
+
+/// Turns SSE events into records. Tracks message roles so parts can be
+/// attributed to the user or the assistant.
+struct MirrorRecorder {
+    backend: String,
+    message_roles: HashMap<String, MessageRole>,
+    /// Message IDs in `message_roles`, oldest first.
+    role_order: VecDeque<String>,
+}
+
+impl MirrorRecorder {
+    fn new(backend: String) -> Self {
+        Self {
+            backend,
+            message_roles: HashMap::new(),
+            role_order: VecDeque::new(),
+        }
+    }
+
+    fn remember_role(&mut self, message_id: &str, session_id: Option<&str>, role: &str) {
+        let message_role = MessageRole {
+            session_id: session_id.map(String::from),
+            role: role.to_string(),
+        };
+        if self
+            .message_roles
+            .insert(message_id.to_string(), message_role)
+            .is_some()
+        {
+            return;
+        }
+        self.role_order.push_back(message_id.to_string());
+        while self.role_order.len() > MAX_MESSAGE_ROLES {
+            if let Some(oldest) = self.role_order.pop_front() {
+                self.message_roles.remove(&oldest);
+            }
+        }
+    }
+
+    fn forget_session(&mut self, session_id: &str) {
+        self.message_roles
+            .retain(|_, message_role| message_role.session_id.as_deref() != Some(session_id));
+        let message_roles = &self.message_roles;
+        self.role_order
+            .retain(|message_id| message_roles.contains_key(message_id));
+    }
+
+    fn observe(&mut self, event: &SseEvent) -> Option<MirrorRecord> {
+        match event {
+            SseEvent::MessageUpdated { info: Some(info) } => {
+                self.remember_role(&info.id, info.session_id.as_deref(), &info.role);
+                None
+            }
+            SseEvent::SessionIdle { session_id } => {
+                self.forget_session(session_id);
+                None
+            }
+            SseEvent::MessagePartUpdated { part, .. } => self.observe_part(part),
+            SseEvent::PermissionReplied {
+                session_id,
+                request_id,
+                reply,
+            } => Some(self.record(
+                format!("permission:{request_id}"),
+                session_id.clone(),
+                None,
+                "permission",
+                None,
+                None,
+                reply.clone(),
+            )),
+            SseEvent::SessionError {
+                session_id: Some(session_id),
+                error,
+            } => Some(self.record(
+                format!("error:{}", uuid::Uuid::new_v4()),
+                session_id.clone(),
+                None,
+                "error",
+                None,
+                None,
+                error.as_ref().map(ToString::to_string).unwrap_or_default(),
+            )),
+            _ => None,
+        }
+    }
+
+    fn observe_part(&self, part: &Part) -> Option<MirrorRecord> {
+        match part {
+            // Streaming assistant text has a start time but no end time until
+            // it finishes; user text is delivered complete with no timing.
+            Part::Text {
+                id,
+                session_id: Some(session_id),
+                message_id,
+                text,
+                time,
+                ..
+            } if time.as_ref().is_none_or(|span| span.end.is_some()) => Some(self.record(
+                id.clone(),
+                session_id.clone(),
+                message_id.clone(),
+                "text",
+                None,
+                None,
+                text.clone(),
+            )),
+            Part::Tool {
+                id,
+                session_id: Some(session_id),
+                message_id,
+                tool,
+                state: Some(state),
+                ..
+            } => {
+                let (input, content) = match state {
+                    ToolState::Completed { input, output, .. } => {
+                        (input, output.clone().unwrap_or_default())
+                    }
+                    ToolState::Error { input, error, .. } => {
+                        (input, error.clone().unwrap_or_default())
+                    }
+                    _ => return None,
+                };
+                Some(self.record(
+                    id.clone(),
+                    session_id.clone(),
+                    message_id.clone(),
+                    "tool",
+                    tool.clone(),
+                    input.as_ref().map(ToString::to_string),
+                    content,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn record(
+        &self,
+        id: String,
+        session_id: String,
+        message_id: Option<String>,
+        kind: &str,
+        tool: Option<String>,
+        input: Option<String>,
+        content: String,
+    ) -> MirrorRecord {
+        let role = message_id
+            .as_ref()
+            .and_then(|message_id| self.message_roles.get(message_id))
+            .map(|message_role| message_role.role.clone());
+        MirrorRecord {
+            id,
+            backend: self.backend.clone(),
+            session_id,
+            message_id,
+            role,
+            kind: kind.to_string(),
+            tool,
+            input,
+            content,
+            recorded_at: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opencode::types::SseEventEnvelope;
+
+    fn event(json: serde_json::Value) -> SseEvent {
+        let envelope: SseEventEnvelope = serde_json::from_value(json).expect("valid envelope");
+        SseEvent::from_envelope(envelope)
+    }
+
+    #[test]
+    fn records_finished_text_with_role() {
+        let mut recorder = MirrorRecorder::new("shared".into());
+        assert!(
+            recorder
+                .observe(&event(serde_json::json!({
+                    "type": "message.updated",
+                    "properties": {"info": {"id": "msg-1", "role": "user", "sessionID": "ses-1"}}
+                })))
+                .is_none()
+        );
+
+        let record = recorder
+            .observe(&event(serde_json::json!({
+                "type": "message.part.updated",
+                "properties": {"part": {
+                    "type": "text", "id": "part-1", "sessionID": "ses-1",
+                    "messageID": "msg-1", "text": "fix the build"
+                }}
+            })))
+            .expect("user text should be recorded");
+        assert_eq!(record.role.as_deref(), Some("user"));
+        assert_eq!(record.content, "fix the build");
+        assert_eq!(record.backend, "shared");
+    }
+
+    #[test]
+    fn message_roles_are_dropped_on_idle_and_bounded() {
+        let mut recorder = MirrorRecorder::new("shared".into());
+        let updated = |message_id: &str, session_id: &str| {
+            event(serde_json::json!({
+                "type": "message.updated",
+                "properties": {"info": {"id": message_id, "role": "user", "sessionID": session_id}}
+            }))
+        };
+        recorder.observe(&updated("msg-1", "ses-1"));
+        recorder.observe(&updated("msg-2", "ses-2"));
+        recorder.observe(&event(serde_json::json!({
+            "type": "session.idle",
+            "properties": {"sessionID": "ses-1"}
+        })));
+        assert!(!recorder.message_roles.contains_key("msg-1"));
+        assert!(recorder.message_roles.contains_key("msg-2"));
+        assert_eq!(recorder.role_order, ["msg-2"]);
+
+        for index in 0..MAX_MESSAGE_ROLES + 10 {
+            recorder.observe(&updated(&format!("msg-{index}-x"), "ses-3"));
+        }
+        assert_eq!(recorder.message_roles.len(), MAX_MESSAGE_ROLES);
+        assert_eq!(recorder.role_order.len(), MAX_MESSAGE_ROLES);
+        assert!(!recorder.message_roles.contains_key("msg-2"));
+    }
+
+    #[test]
+    fn skips_streaming_text_and_running_tools() {
+        let mut recorder = MirrorRecorder::new("shared".into());
+        let streaming = recorder.observe(&event(serde_json::json!({
+            "type": "message.part.updated",
+            "properties": {"part": {
+                "type": "text", "id": "part-2", "sessionID": "ses-1",
+                "text": "partial", "time": {"start": 1.0}
+            }, "delta": "partial"}
+        })));
+        assert!(streaming.is_none());
+
+        let running = recorder.observe(&event(serde_json::json!({
+            "type": "message.part.updated",
+            "properties": {"part": {
+                "type": "tool", "id": "part-3", "sessionID": "ses-1", "tool": "bash",
+                "state": {"status": "running", "input": {"command": "ls"}}
+            }}
+        })));
+        assert!(running.is_none());
+
+        let completed = recorder
+            .observe(&event(serde_json::json!({
+                "type": "message.part.updated",
+                "properties": {"part": {
+                    "type": "tool", "id": "part-3", "sessionID": "ses-1", "tool": "bash",
+                    "state": {"status": "completed", "input": {"command": "ls"}, "output": "src"}
+                }}
+            })))
+            .expect("completed tool should be recorded");
+        assert_eq!(completed.kind, "tool");
+        assert_eq!(completed.tool.as_deref(), Some("bash"));
+        assert_eq!(completed.content, "src");
+        assert!(completed.input.unwrap_or_default().contains("ls"));
+    }
+
+    #[tokio::test]
+    async fn same_part_id_on_two_backends_keeps_both() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        let store = OpenCodeMirrorStore::new(pool.clone());
+
+        let record = |backend: &str| MirrorRecord {
+            id: "part-1".into(),
+            backend: backend.into(),
+            session_id: "ses-1".into(),
+            message_id: None,
+            role: Some("user".into()),
+            kind: "text".into(),
+            tool: None,
+            input: None,
+            content: format!("from {backend}"),
+            recorded_at: String::new(),
+        };
+        store.record("main", &record("alpha")).await.unwrap();
+        store.record("main", &record("beta")).await.unwrap();
+
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT content FROM opencode_mirror_parts WHERE id = 'part-1' ORDER BY backend",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows, ["from alpha", "from beta"]);
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(escape_like("100%_done\\"), "100\\%\\_done\\\\");
+    }
+}
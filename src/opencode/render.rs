@@ -0,0 +1,602 @@
+//! Per-tool rendering of OpenCode tool output for transcripts.
+//!
+//! Structured tools read much better formatted than as raw text. The
+//! [`ToolOutputRenderers`] registry maps tool names to renderers: a diff for
+//! `edit`, a table for `grep` matches, an exit-code badge for `bash`,
+//! numbered lines for `read`, and a file tree for `list` and `glob`.
+//! Tools without a renderer, and renderers that return `None`, fall back to
+//! the raw output. Downstream crates add or replace renderers with
+//! [`ToolOutputRenderers::register`].
+
+use crate::messaging::code_language::language_for_path;
+
+use serde_json::Value;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, RwLock};
+
+static RENDERERS: LazyLock<ToolOutputRenderers> = LazyLock::new(ToolOutputRenderers::with_builtins);
+
+/// Lines of a `read` result shown before the rest is elided.
+const MAX_READ_LINES: usize = 50;
+
+/// Entries of a `list` or `glob` tree shown before the rest is elided.
+const MAX_TREE_ENTRIES: usize = 60;
+
+/// A completed tool call, as seen by a renderer.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolOutput<'a> {
+    pub tool: &'a str,
+    /// Tool input as sent by the model.
+    pub input: Option<&'a Value>,
+    /// Raw output text.
+    pub output: &'a str,
+    /// Tool-specific metadata object, e.g. `exit` for `bash` or `diff` for
+    /// `edit`.
+    pub metadata: Option<&'a Value>,
+}
+
+impl<'a> ToolOutput<'a> {
+    fn input_str(&self, key: &str) -> Option<&'a str> {
+        self.input?.get(key)?.as_str()
+    }
+
+    fn metadata_field(&self, key: &str) -> Option<&'a Value> {
+        self.metadata?.get(key)
+    }
+}
+
+/// Formats one tool's output.
+pub trait ToolOutputRenderer: Send + Sync {
+    /// Rendered text, or `None` to fall back to the raw output.
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String>;
+}
+
+impl<F> ToolOutputRenderer for F
+where
+    F: Fn(&ToolOutput<'_>) -> Option<String> + Send + Sync,
+{
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        self(output)
+    }
+}
+
+/// Registry of renderers by tool name. Access via
+/// [`ToolOutputRenderers::global`].
+pub struct ToolOutputRenderers {
+    renderers: RwLock<HashMap<String, Arc<dyn ToolOutputRenderer>>>,
+}
+
+impl ToolOutputRenderers {
+    /// An empty registry: every tool renders as raw output.
+    pub fn new() -> Self {
+        Self {
+            renderers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// A registry with the built-in `edit`, `grep`, `bash`, `read`, `list`,
+    /// and `glob` renderers.
+    pub fn with_builtins() -> Self {
+        let registry = Self::new();
+        registry.register("edit", DiffRenderer);
+        registry.register("grep", MatchTableRenderer);
+        registry.register("bash", ExitCodeRenderer);
+        registry.register("read", NumberedLinesRenderer);
+        // OpenCode names its directory listing tool `list`; older releases
+        // called it `ls`.
+        registry.register("list", DirectoryTreeRenderer);
+        registry.register("ls", DirectoryTreeRenderer);
+        registry.register("glob", GlobTreeRenderer);
+        registry
+    }
+
+    /// The process-wide registry used for transcripts.
+    pub fn global() -> &'static Self {
+        &RENDERERS
+    }
+
+    /// Render `tool`'s output with `renderer`, replacing any existing one.
+    pub fn register(&self, tool: impl Into<String>, renderer: impl ToolOutputRenderer + 'static) {
+        self.register_shared(tool, Arc::new(renderer));
+    }
+
+    /// Like [`register`](Self::register), for a renderer the caller already
+    /// holds behind an `Arc`.
+    pub fn register_shared(&self, tool: impl Into<String>, renderer: Arc<dyn ToolOutputRenderer>) {
+        self.renderers
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(tool.into(), renderer);
+    }
+
+    /// Drop `tool`'s renderer so its output renders raw.
+    pub fn unregister(&self, tool: &str) {
+        self.renderers
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(tool);
+    }
+
+    /// Render `output` with its tool's renderer, or return it raw.
+    pub fn render(&self, output: &ToolOutput<'_>) -> String {
+        let renderer = self
+            .renderers
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(output.tool)
+            .cloned();
+        renderer
+            .and_then(|renderer| renderer.render(output))
+            .unwrap_or_else(|| output.output.to_string())
+    }
+}
+
+impl Default for ToolOutputRenderers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `edit`: the tool's message followed by a diff. Uses the unified diff from
+/// metadata when present, otherwise diffs `oldString` against `newString`.
+pub struct DiffRenderer;
+
+impl ToolOutputRenderer for DiffRenderer {
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        let diff = match output.metadata_field("diff").and_then(Value::as_str) {
+            Some(diff) if !diff.trim().is_empty() => diff.trim_end().to_string(),
+            _ => {
+                let old = output.input_str("oldString")?;
+                let new = output.input_str("newString")?;
+                let path = output.input_str("filePath").unwrap_or("file");
+                let mut diff = format!("--- {path}\n+++ {path}");
+                for line in old.lines() {
+                    diff.push_str("\n-");
+                    diff.push_str(line);
+                }
+                for line in new.lines() {
+                    diff.push_str("\n+");
+                    diff.push_str(line);
+                }
+                diff
+            }
+        };
+        let message = output.output.trim();
+        let fence = fence_for(&diff);
+        Some(if message.is_empty() {
+            format!("{fence}diff\n{diff}\n{fence}")
+        } else {
+            format!("{message}\n\n{fence}diff\n{diff}\n{fence}")
+        })
+    }
+}
+
+/// `grep`: matches as a file/line/text table. Understands OpenCode's grouped
+/// output (`path:` then `  Line N: text`) and ripgrep's `path:line:text`.
+pub struct MatchTableRenderer;
+
+impl ToolOutputRenderer for MatchTableRenderer {
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        let mut rows = Vec::new();
+        let mut current_file: Option<&str> = None;
+        for line in output.output.lines() {
+            if let Some(rest) = line.trim_start().strip_prefix("Line ")
+                && let Some((number, text)) = rest.split_once(": ")
+                && let Some(file) = current_file
+                && number.parse::<u64>().is_ok()
+            {
+                rows.push((file, number, text));
+            } else if !line.starts_with(char::is_whitespace)
+                && let Some(file) = line.strip_suffix(':')
+            {
+                current_file = Some(file);
+            } else if let Some((file, rest)) = line.split_once(':')
+                && let Some((number, text)) = rest.split_once(':')
+                && number.parse::<u64>().is_ok()
+            {
+                rows.push((file, number, text));
+            }
+        }
+        if rows.is_empty() {
+            return None;
+        }
+
+        let summary = output
+            .output
+            .lines()
+            .next()
+            .filter(|line| line.starts_with("Found "));
+        let mut table = String::new();
+        if let Some(summary) = summary {
+            table.push_str(summary);
+            table.push_str("\n\n");
+        }
+        table.push_str("| File | Line | Match |\n| --- | ---: | --- |");
+        for (file, number, text) in rows {
+            table.push_str(&format!(
+                "\n| {} | {number} | {} |",
+                escape_cell(file),
+                escape_cell(text.trim())
+            ));
+        }
+        Some(table)
+    }
+}
+
+/// `bash`: an exit-code badge above the output.
+pub struct ExitCodeRenderer;
+
+impl ToolOutputRenderer for ExitCodeRenderer {
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        let exit_code = output.metadata_field("exit")?.as_i64()?;
+        let badge = if exit_code == 0 {
+            "`exit 0` ✓".to_string()
+        } else {
+            format!("`exit {exit_code}` ✗")
+        };
+        let text = output.output.trim_end();
+        if text.is_empty() {
+            return Some(badge);
+        }
+        let fence = fence_for(text);
+        Some(format!("{badge}\n\n{fence}\n{text}\n{fence}"))
+    }
+}
+
+/// `read`: the file's lines, numbered, in a code block labelled with the
+/// file's language. Understands both the `00001| text` and `1: text` line
+/// formats OpenCode has used, and keeps its trailing note such as
+/// `(End of file - total 80 lines)`.
+pub struct NumberedLinesRenderer;
+
+impl ToolOutputRenderer for NumberedLinesRenderer {
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        let mut lines = Vec::new();
+        let mut notes = Vec::new();
+        for line in output.output.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || is_markup_line(trimmed) {
+                continue;
+            }
+            if let Some((number, text)) = numbered_line(line) {
+                lines.push((number, text));
+            } else if trimmed.starts_with('(') {
+                notes.push(trimmed);
+            } else {
+                // Not file content (an image, a directory, an error): show
+                // it as the tool wrote it.
+                return None;
+            }
+        }
+        let (first, _) = *lines.first()?;
+
+        let shown = &lines[..lines.len().min(MAX_READ_LINES)];
+        let (last, _) = *shown.last()?;
+        let width = last.to_string().len();
+        let body = shown
+            .iter()
+            .map(|(number, text)| format!("{number:>width$} │ {text}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let path = output.input_str("filePath");
+        let language = path.and_then(language_for_path).unwrap_or_default();
+        let fence = fence_for(&body);
+        let mut rendered = String::new();
+        if let Some(path) = path {
+            rendered.push_str(&format!("`{path}` · lines {first}–{last}\n"));
+        }
+        rendered.push_str(&format!("{fence}{language}\n{body}\n{fence}"));
+        if lines.len() > shown.len() {
+            rendered.push_str(&format!("\n… {} more lines", lines.len() - shown.len()));
+        }
+        for note in notes {
+            rendered.push('\n');
+            rendered.push_str(note);
+        }
+        Some(rendered)
+    }
+}
+
+/// Wrapper tags around `read` output, e.g. `<file>` or `<path>…</path>`.
+fn is_markup_line(line: &str) -> bool {
+    line.starts_with('<') && line.ends_with('>')
+}
+
+/// Split `00042| text` or `42: text` into its line number and text.
+fn numbered_line(line: &str) -> Option<(u64, &str)> {
+    let line = line.trim_start();
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let number = line[..digits].parse().ok()?;
+    let rest = &line[digits..];
+    let text = rest.strip_prefix('|').or_else(|| rest.strip_prefix(':'))?;
+    Some((number, text.strip_prefix(' ').unwrap_or(text)))
+}
+
+/// `list`: OpenCode's indented listing (two spaces per level, directories
+/// ending in `/`) redrawn as a tree.
+pub struct DirectoryTreeRenderer;
+
+impl ToolOutputRenderer for DirectoryTreeRenderer {
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        let mut lines = output.output.lines().filter(|line| !line.trim().is_empty());
+        let root = lines.next()?;
+        if root.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        let mut tree = FileTree::default();
+        let mut directories: Vec<&str> = Vec::new();
+        let mut notes = Vec::new();
+        for line in lines {
+            let name = line.trim_start();
+            let indent = line.len() - name.len();
+            if indent == 0 && name.starts_with('(') {
+                notes.push(name.trim_end());
+                continue;
+            }
+            let depth = indent / 2;
+            if indent % 2 != 0 || depth == 0 || depth > directories.len() + 1 {
+                return None;
+            }
+            let name = name.trim_end();
+            directories.truncate(depth - 1);
+            tree.insert(directories.iter().copied().chain([name]));
+            if name.ends_with('/') {
+                directories.push(name);
+            }
+        }
+        Some(fenced_tree(&tree, root.trim_end(), &notes))
+    }
+}
+
+/// `glob`: the matched paths as a tree under their common directory.
+pub struct GlobTreeRenderer;
+
+impl ToolOutputRenderer for GlobTreeRenderer {
+    fn render(&self, output: &ToolOutput<'_>) -> Option<String> {
+        let mut paths = Vec::new();
+        let mut notes = Vec::new();
+        for line in output.output.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('(') {
+                notes.push(line);
+            } else if line.contains('/') {
+                paths.push(line.split('/').collect::<Vec<_>>());
+            } else {
+                // "No files found" and other messages stay as written.
+                return None;
+            }
+        }
+        let (first, rest) = paths.split_first()?;
+
+        let mut common = first.len() - 1;
+        for components in rest {
+            common = first[..common.min(components.len() - 1)]
+                .iter()
+                .zip(components)
+                .take_while(|(left, right)| left == right)
+                .count();
+        }
+        let root = if common == 0 {
+            ".".to_string()
+        } else {
+            format!("{}/", first[..common].join("/"))
+        };
+
+        let mut tree = FileTree::default();
+        for components in &paths {
+            let (file, directories) = components[common..].split_last()?;
+            let directories: Vec<String> = directories
+                .iter()
+                .map(|directory| format!("{directory}/"))
+                .collect();
+            tree.insert(directories.iter().map(String::as_str).chain([*file]));
+        }
+        Some(fenced_tree(&tree, &root, &notes))
+    }
+}
+
+/// Paths grouped by directory, for drawing as a tree. Directory names end
+/// in `/`.
+#[derive(Debug, Default)]
+struct FileTree {
+    children: BTreeMap<String, FileTree>,
+}
+
+impl FileTree {
+    fn insert<'a>(&mut self, path: impl IntoIterator<Item = &'a str>) {
+        let mut node = self;
+        for name in path {
+            node = node.children.entry(name.to_string()).or_default();
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.children.values().map(|child| 1 + child.len()).sum()
+    }
+
+    /// Draw the tree under `root`, directories first, stopping after
+    /// [`MAX_TREE_ENTRIES`] entries.
+    fn render(&self, root: &str) -> String {
+        let mut lines = vec![root.to_string()];
+        self.render_children("", &mut lines);
+        let hidden = self.len() - (lines.len() - 1);
+        if hidden > 0 {
+            lines.push(format!("… {hidden} more"));
+        }
+        lines.join("\n")
+    }
+
+    fn render_children(&self, prefix: &str, lines: &mut Vec<String>) {
+        let mut children: Vec<_> = self.children.iter().collect();
+        children.sort_by_key(|(name, _)| !name.ends_with('/'));
+        let count = children.len();
+        for (index, (name, child)) in children.into_iter().enumerate() {
+            if lines.len() > MAX_TREE_ENTRIES {
+                return;
+            }
+            let (branch, indent) = if index + 1 == count {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            lines.push(format!("{prefix}{branch}{name}"));
+            child.render_children(&format!("{prefix}{indent}"), lines);
+        }
+    }
+}
+
+fn fenced_tree(tree: &FileTree, root: &str, notes: &[&str]) -> String {
+    let body = tree.render(root);
+    let fence = fence_for(&body);
+    let mut rendered = format!("{fence}\n{body}\n{fence}");
+    for note in notes {
+        rendered.push('\n');
+        rendered.push_str(note);
+    }
+    rendered
+}
+
+/// A code fence longer than any backtick run in `text`.
+pub(crate) fn fence_for(text: &str) -> String {
+    let longest_run = text
+        .split(|character| character != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(tool: &str, input: Value, output: &str, metadata: Value) -> String {
+        ToolOutputRenderers::with_builtins().render(&ToolOutput {
+            tool,
+            input: Some(&input),
+            output,
+            metadata: Some(&metadata),
+        })
+    }
+
+    #[test]
+    fn edit_renders_a_diff_from_its_input() {
+        let rendered = render(
+            "edit",
+            json!({"filePath": "src/lib.rs", "oldString": "let a = 1;", "newString": "let a = 2;"}),
+            "Edit applied successfully.",
+            json!({}),
+        );
+        assert_eq!(
+            rendered,
+            "Edit applied successfully.\n\n```diff\n--- src/lib.rs\n+++ src/lib.rs\n-let a = 1;\n+let a = 2;\n```"
+        );
+    }
+
+    #[test]
+    fn grep_renders_grouped_matches_as_a_table() {
+        let rendered = render(
+            "grep",
+            json!({"pattern": "todo"}),
+            "Found 2 matches\n/repo/a.rs:\n  Line 3: // todo | later\n\n/repo/b.rs:\n  Line 10: todo!()",
+            json!({}),
+        );
+        assert_eq!(
+            rendered,
+            "Found 2 matches\n\n| File | Line | Match |\n| --- | ---: | --- |\n\
+             | /repo/a.rs | 3 | // todo \\| later |\n| /repo/b.rs | 10 | todo!() |"
+        );
+    }
+
+    #[test]
+    fn bash_gets_an_exit_code_badge_and_unknown_tools_render_raw() {
+        let rendered = render("bash", json!({}), "boom\n", json!({"exit": 2}));
+        assert_eq!(rendered, "`exit 2` ✗\n\n```\nboom\n```");
+
+        let raw = render("bash", json!({}), "no metadata", json!({}));
+        assert_eq!(raw, "no metadata");
+        assert_eq!(render("webfetch", json!({}), "<html>", json!({})), "<html>");
+    }
+
+    #[test]
+    fn read_numbers_lines_in_a_labelled_block() {
+        let rendered = render(
+            "read",
+            json!({"filePath": "src/lib.rs"}),
+            "<file>\n00009| pub mod a;\n00010| pub mod b;\n\n(File has more lines. Use 'offset' parameter to read beyond line 10)\n</file>",
+            json!({}),
+        );
+        assert_eq!(
+            rendered,
+            "`src/lib.rs` · lines 9–10\n```rust\n 9 │ pub mod a;\n10 │ pub mod b;\n```\n\
+             (File has more lines. Use 'offset' parameter to read beyond line 10)"
+        );
+
+        let long = (1..=60)
+            .map(|number| format!("{number}: line"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let rendered = render("read", json!({}), &long, json!({}));
+        assert!(rendered.ends_with("50 │ line\n```\n… 10 more lines"));
+        assert_eq!(
+            render("read", json!({}), "Image read successfully", json!({})),
+            "Image read successfully"
+        );
+    }
+
+    #[test]
+    fn list_and_glob_render_as_trees() {
+        let rendered = render(
+            "list",
+            json!({}),
+            "/repo/\n  src/\n    main.rs\n    agent/\n      channel.rs\n  Cargo.toml\n",
+            json!({}),
+        );
+        assert_eq!(
+            rendered,
+            "```\n/repo/\n├── src/\n│   ├── agent/\n│   │   └── channel.rs\n│   └── main.rs\n\
+             └── Cargo.toml\n```"
+        );
+
+        let rendered = render(
+            "glob",
+            json!({"pattern": "**/*.rs"}),
+            "/repo/src/main.rs\n/repo/src/agent/channel.rs\n/repo/build.rs",
+            json!({}),
+        );
+        assert_eq!(
+            rendered,
+            "```\n/repo/\n├── src/\n│   ├── agent/\n│   │   └── channel.rs\n│   └── main.rs\n\
+             └── build.rs\n```"
+        );
+        assert_eq!(
+            render("glob", json!({}), "No files found", json!({})),
+            "No files found"
+        );
+    }
+
+    #[test]
+    fn registered_renderers_replace_builtins() {
+        let registry = ToolOutputRenderers::with_builtins();
+        registry.register("bash", |output: &ToolOutput<'_>| {
+            Some(output.output.to_uppercase())
+        });
+        let rendered = registry.render(&ToolOutput {
+            tool: "bash",
+            input: None,
+            output: "ok",
+            metadata: None,
+        });
+        assert_eq!(rendered, "OK");
+    }
+}
@@ -7,13 +7,21 @@
 //!
 //! Port mappings are persisted to disk so that after a spacebot restart, we can
 //! reattach to OpenCode servers that are still running from the previous session.
+//!
+//! Directories covered by a configured remote backend skip the subprocess
+//! entirely and talk to that server with the backend's auth headers and TLS
+//! client identity.
 
+use crate::config::{OpenCodeBackendAuth, OpenCodeBackendConfig};
+use crate::opencode::capabilities::{ServerCapabilities, TESTED_VERSIONS};
 use crate::opencode::types::*;
 
 use anyhow::{Context as _, bail};
+use base64::Engine as _;
 use reqwest::Client;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
@@ -39,6 +47,11 @@ pub struct OpenCodeServer {
     restart_count: u32,
     opencode_path: String,
     permissions: OpenCodePermissions,
+    /// Name of the remote backend serving this directory. Remote servers are
+    /// never spawned or restarted by us.
+    remote_backend: Option<String>,
+    /// What the server supports, from the version it reported on connect.
+    capabilities: ServerCapabilities,
 }
 
 impl OpenCodeServer {
@@ -81,12 +94,9 @@ impl OpenCodeServer {
                 )
             })?;
 
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(300))
-            .build()
-            .context("failed to create HTTP client")?;
+        let client = build_client(None)?;
 
-        let server = Self {
+        let mut server = Self {
             directory,
             port,
             process: Some(process),
@@ -95,9 +105,12 @@ impl OpenCodeServer {
             restart_count: 0,
             opencode_path: opencode_path.to_string(),
             permissions: permissions.clone(),
+            remote_backend: None,
+            capabilities: ServerCapabilities::default(),
         };
 
         server.wait_for_health().await?;
+        server.negotiate_capabilities().await;
 
         tracing::info!(
             directory = %server.directory.display(),
@@ -117,12 +130,9 @@ impl OpenCodeServer {
     ) -> Option<Self> {
         let port = port_for_directory(&directory);
         let base_url = format!("http://127.0.0.1:{port}");
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(300))
-            .build()
-            .ok()?;
+        let client = build_client(None).ok()?;
 
-        let server = Self {
+        let mut server = Self {
             directory,
             port,
             process: None, // we didn't spawn it
@@ -131,11 +141,14 @@ impl OpenCodeServer {
             restart_count: 0,
             opencode_path: opencode_path.to_string(),
             permissions: permissions.clone(),
+            remote_backend: None,
+            capabilities: ServerCapabilities::default(),
         };
 
         // Quick health check -- if it fails, server is gone
         match server.health_check().await {
             Ok(true) => {
+                server.negotiate_capabilities().await;
                 tracing::info!(
                     directory = %server.directory.display(),
                     port,
@@ -154,6 +167,63 @@ impl OpenCodeServer {
         }
     }
 
+    /// Connect to a remote OpenCode server described by a configured backend.
+    ///
+    /// Fails if the server does not pass a health check, so misconfigured
+    /// auth or TLS surfaces at worker start rather than mid-session.
+    pub async fn connect_remote(
+        directory: PathBuf,
+        backend: &OpenCodeBackendConfig,
+        permissions: &OpenCodePermissions,
+    ) -> anyhow::Result<Self> {
+        let client = build_client(Some(backend))?;
+        let port = url::Url::parse(&backend.url)
+            .ok()
+            .and_then(|url| url.port_or_known_default())
+            .unwrap_or(0);
+
+        let mut server = Self {
+            directory,
+            port,
+            process: None,
+            base_url: backend.url.clone(),
+            client,
+            restart_count: 0,
+            opencode_path: String::new(),
+            permissions: permissions.clone(),
+            remote_backend: Some(backend.name.clone()),
+            capabilities: ServerCapabilities::default(),
+        };
+
+        match server.health_check().await {
+            Ok(true) => {}
+            Ok(false) => bail!(
+                "remote OpenCode backend '{}' at {} is not healthy",
+                backend.name,
+                backend.url
+            ),
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!(
+                        "failed to reach remote OpenCode backend '{}' at {}",
+                        backend.name, backend.url
+                    )
+                });
+            }
+        }
+
+        server.negotiate_capabilities().await;
+
+        tracing::info!(
+            backend = %backend.name,
+            url = %backend.url,
+            directory = %server.directory.display(),
+            "connected to remote OpenCode backend"
+        );
+
+        Ok(server)
+    }
+
     /// Poll the health endpoint until the server is ready.
     async fn wait_for_health(&self) -> anyhow::Result<()> {
         for attempt in 1..=HEALTH_CHECK_MAX_ATTEMPTS {
@@ -211,6 +281,59 @@ impl OpenCodeServer {
         Ok(response.status().is_success())
     }
 
+    /// Read the server's version and derive its capabilities. A version
+    /// outside the tested range is logged as a warning but not refused.
+    async fn negotiate_capabilities(&mut self) {
+        let version = match self.server_version().await {
+            Ok(version) => version,
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    directory = %self.directory.display(),
+                    "failed to read OpenCode server version"
+                );
+                None
+            }
+        };
+        self.capabilities = ServerCapabilities::for_version(version.as_deref());
+
+        if self.capabilities.is_tested() {
+            tracing::debug!(
+                version = %self.capabilities.version_label(),
+                directory = %self.directory.display(),
+                "OpenCode server version is supported"
+            );
+        } else {
+            tracing::warn!(
+                version = %self.capabilities.version_label(),
+                tested = TESTED_VERSIONS,
+                directory = %self.directory.display(),
+                "OpenCode server version is outside the tested range, some features may misbehave"
+            );
+        }
+    }
+
+    /// Version reported by `/global/health`, if any.
+    async fn server_version(&self) -> anyhow::Result<Option<String>> {
+        let url = format!("{}/global/health", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        let health = response
+            .json::<HealthResponse>()
+            .await
+            .context("failed to parse health response")?;
+        Ok(health.version)
+    }
+
     /// Check if the server is still alive. For spawned servers, checks the
     /// process handle. For reattached servers, does a health check.
     pub async fn is_alive(&mut self) -> bool {
@@ -226,8 +349,22 @@ impl OpenCodeServer {
         self.port
     }
 
+    /// What the server supports.
+    pub fn capabilities(&self) -> &ServerCapabilities {
+        &self.capabilities
+    }
+
+    /// Name of the remote backend, if this server is not a local subprocess.
+    pub fn remote_backend(&self) -> Option<&str> {
+        self.remote_backend.as_deref()
+    }
+
     /// Restart the server process. Reuses the same directory and config.
     pub async fn restart(&mut self) -> anyhow::Result<()> {
+        if let Some(backend) = &self.remote_backend {
+            bail!("remote OpenCode backend '{backend}' is not responding");
+        }
+
         self.restart_count += 1;
         if self.restart_count > MAX_RESTART_RETRIES {
             bail!(
@@ -277,6 +414,7 @@ impl OpenCodeServer {
         self.process = Some(process);
 
         self.wait_for_health().await?;
+        self.negotiate_capabilities().await;
 
         tracing::info!(
             directory = %self.directory.display(),
@@ -313,6 +451,54 @@ impl OpenCodeServer {
         &self.directory
     }
 
+    /// Get the project the server resolves for its directory.
+    pub async fn current_project(&self) -> anyhow::Result<Project> {
+        let url = format!("{}/project/current", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("directory", self.directory.to_str().unwrap_or("."))])
+            .send()
+            .await
+            .context("failed to get current OpenCode project")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("get current project failed ({status}): {text}");
+        }
+
+        response
+            .json::<Project>()
+            .await
+            .context("failed to parse project response")
+    }
+
+    /// Get the directory, worktree, and config paths the server uses.
+    pub async fn path_info(&self) -> anyhow::Result<PathInfo> {
+        let url = format!("{}/path", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("directory", self.directory.to_str().unwrap_or("."))])
+            .send()
+            .await
+            .context("failed to get OpenCode paths")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("get paths failed ({status}): {text}");
+        }
+
+        response
+            .json::<PathInfo>()
+            .await
+            .context("failed to parse path response")
+    }
+
     /// Create a new session.
     pub async fn create_session(&self, title: Option<String>) -> anyhow::Result<Session> {
         let url = format!("{}/session", self.base_url);
@@ -415,23 +601,140 @@ impl OpenCodeServer {
         Ok(())
     }
 
-    /// Reply to a permission request.
+    /// Compact a session: OpenCode summarizes the conversation so far with
+    /// the given model and continues later turns from the summary. Returns
+    /// once the summary has been written.
+    pub async fn summarize_session(
+        &self,
+        session_id: &str,
+        request: &SummarizeRequest,
+    ) -> anyhow::Result<()> {
+        let url = format!("{}/session/{}/summarize", self.base_url, session_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("directory", self.directory.to_str().unwrap_or("."))])
+            .json(request)
+            .timeout(std::time::Duration::from_secs(600))
+            .send()
+            .await
+            .context("failed to summarize OpenCode session")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("summarize session failed ({status}): {text}");
+        }
+
+        Ok(())
+    }
+
+    /// Connection state of every MCP server configured for this directory,
+    /// keyed by server name.
+    pub async fn mcp_status(&self) -> anyhow::Result<BTreeMap<String, McpStatus>> {
+        let url = format!("{}/mcp", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("directory", self.directory.to_str().unwrap_or("."))])
+            .send()
+            .await
+            .context("failed to get OpenCode MCP status")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("get mcp status failed ({status}): {text}");
+        }
+
+        response
+            .json::<BTreeMap<String, McpStatus>>()
+            .await
+            .context("failed to parse MCP status response")
+    }
+
+    /// List the commands the server can run in this directory.
+    pub async fn list_commands(&self) -> anyhow::Result<Vec<CommandInfo>> {
+        let url = format!("{}/command", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("directory", self.directory.to_str().unwrap_or("."))])
+            .send()
+            .await
+            .context("failed to list OpenCode commands")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("list commands failed ({status}): {text}");
+        }
+
+        response
+            .json::<Vec<CommandInfo>>()
+            .await
+            .context("failed to parse command list")
+    }
+
+    /// Run a named command in a session (blocking until complete). Returns
+    /// the assistant message it produced.
+    pub async fn run_command(
+        &self,
+        session_id: &str,
+        request: &SendCommandRequest,
+    ) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/session/{}/command", self.base_url, session_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("directory", self.directory.to_str().unwrap_or("."))])
+            .json(request)
+            .timeout(std::time::Duration::from_secs(600))
+            .send()
+            .await
+            .context("failed to run OpenCode command")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            bail!("run command failed ({status}): {text}");
+        }
+
+        response
+            .json::<serde_json::Value>()
+            .await
+            .context("failed to parse command response")
+    }
+
+    /// Reply to a permission request raised in `session_id`.
     pub async fn reply_permission(
         &self,
+        session_id: &str,
         request_id: &str,
         reply: PermissionReply,
     ) -> anyhow::Result<()> {
-        let url = format!("{}/permission/{}/reply", self.base_url, request_id);
-        let body = PermissionReplyRequest {
-            reply,
-            message: None,
+        let request = if self.capabilities.permission_reply_route {
+            let url = format!("{}/permission/{}/reply", self.base_url, request_id);
+            self.client.post(&url).json(&PermissionReplyRequest {
+                reply,
+                message: None,
+            })
+        } else {
+            let url = format!(
+                "{}/session/{}/permissions/{}",
+                self.base_url, session_id, request_id
+            );
+            self.client
+                .post(&url)
+                .json(&LegacyPermissionReplyRequest { response: reply })
         };
 
-        let response = self
-            .client
-            .post(&url)
+        let response = request
             .query(&[("directory", self.directory.to_str().unwrap_or("."))])
-            .json(&body)
             .send()
             .await
             .context("failed to reply to permission")?;
@@ -451,6 +754,13 @@ impl OpenCodeServer {
         request_id: &str,
         answers: Vec<QuestionAnswer>,
     ) -> anyhow::Result<()> {
+        if !self.capabilities.questions {
+            bail!(
+                "OpenCode {} does not support question replies",
+                self.capabilities.version_label()
+            );
+        }
+
         let url = format!("{}/question/{}/reply", self.base_url, request_id);
         let body = QuestionReplyRequest { answers };
 
@@ -475,14 +785,27 @@ impl OpenCodeServer {
     /// Subscribe to the SSE event stream. Returns a response whose body can
     /// be read as a byte stream and parsed line-by-line for SSE events.
     pub async fn subscribe_events(&self) -> anyhow::Result<reqwest::Response> {
+        self.subscribe_events_after(None).await
+    }
+
+    /// Subscribe to the SSE event stream, asking the server to resume after
+    /// `last_event_id` with a `Last-Event-ID` header.
+    pub async fn subscribe_events_after(
+        &self,
+        last_event_id: Option<&str>,
+    ) -> anyhow::Result<reqwest::Response> {
         let url = format!("{}/event", self.base_url);
 
-        let response = self
+        let mut request = self
             .client
             .get(&url)
             .query(&[("directory", self.directory.to_str().unwrap_or("."))])
             .header("Accept", "text/event-stream")
-            .timeout(std::time::Duration::from_secs(86400)) // long-lived
+            .timeout(std::time::Duration::from_secs(86400)); // long-lived
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("Last-Event-ID", last_event_id);
+        }
+        let response = request
             .send()
             .await
             .context("failed to subscribe to OpenCode event stream")?;
@@ -547,6 +870,7 @@ pub struct OpenCodeServerPool {
     opencode_path: String,
     permissions: OpenCodePermissions,
     max_servers: usize,
+    backends: Vec<OpenCodeBackendConfig>,
 }
 
 impl OpenCodeServerPool {
@@ -555,6 +879,7 @@ impl OpenCodeServerPool {
         opencode_path: impl Into<String>,
         permissions: OpenCodePermissions,
         max_servers: usize,
+        backends: Vec<OpenCodeBackendConfig>,
     ) -> Self {
         Self {
             servers: Mutex::new(HashMap::new()),
@@ -562,9 +887,31 @@ impl OpenCodeServerPool {
             opencode_path: opencode_path.into(),
             permissions,
             max_servers,
+            backends,
         }
     }
 
+    /// Find the remote backend serving a directory, if any. The most
+    /// specific (longest) matching backend directory wins. Mirrored backends
+    /// are only ever listened to, so they never run workers.
+    fn backend_for(&self, directory: &Path) -> Option<&OpenCodeBackendConfig> {
+        self.backends
+            .iter()
+            .filter(|backend| !backend.mirror && directory.starts_with(&backend.directory))
+            .max_by_key(|backend| backend.directory.components().count())
+    }
+
+    /// Resolve the pool key for a directory. Remote directories may not exist
+    /// on this host, so they're used verbatim instead of canonicalized.
+    fn resolve_directory(&self, directory: &Path) -> anyhow::Result<PathBuf> {
+        if self.backend_for(directory).is_some() {
+            return Ok(directory.to_path_buf());
+        }
+        directory
+            .canonicalize()
+            .with_context(|| format!("directory '{}' does not exist", directory.display()))
+    }
+
     /// Get or create a server for the given directory.
     ///
     /// On first access for a directory, checks the deterministic port for
@@ -574,9 +921,7 @@ impl OpenCodeServerPool {
         &self,
         directory: &Path,
     ) -> anyhow::Result<Arc<Mutex<OpenCodeServer>>> {
-        let canonical = directory
-            .canonicalize()
-            .with_context(|| format!("directory '{}' does not exist", directory.display()))?;
+        let canonical = self.resolve_directory(directory)?;
 
         let mut servers = self.servers.lock().await;
 
@@ -595,6 +940,15 @@ impl OpenCodeServerPool {
             return Ok(Arc::clone(server));
         }
 
+        if let Some(backend) = self.backend_for(&canonical) {
+            let server =
+                OpenCodeServer::connect_remote(canonical.clone(), backend, &self.permissions)
+                    .await?;
+            let server = Arc::new(Mutex::new(server));
+            servers.insert(canonical, Arc::clone(&server));
+            return Ok(server);
+        }
+
         // Not in pool yet. Try reattaching to an existing server on the
         // deterministic port (left over from a previous spacebot run).
         if let Some(reattached) =
@@ -637,6 +991,36 @@ impl OpenCodeServerPool {
         }
     }
 
+    /// Describe the pooled server listening on `port`: the remote backend's
+    /// name, or `local` for a spawned or reattached process.
+    pub async fn backend_label(&self, port: u16) -> Option<String> {
+        let servers = self.servers.lock().await;
+        for server in servers.values() {
+            let guard = server.lock().await;
+            if guard.port() == port {
+                return Some(guard.remote_backend().unwrap_or("local").to_string());
+            }
+        }
+        None
+    }
+
+    /// Whether `name` is a label [`backend_label`](Self::backend_label) can
+    /// return: a configured remote backend, or `local`.
+    pub fn has_backend(&self, name: &str) -> bool {
+        name == "local" || self.backends.iter().any(|backend| backend.name == name)
+    }
+
+    /// The pooled server listening on `port`.
+    pub async fn server_for_port(&self, port: u16) -> Option<Arc<Mutex<OpenCodeServer>>> {
+        let servers = self.servers.lock().await;
+        for server in servers.values() {
+            if server.lock().await.port() == port {
+                return Some(server.clone());
+            }
+        }
+        None
+    }
+
     /// Number of active servers.
     pub async fn server_count(&self) -> usize {
         self.servers.lock().await.len()
@@ -647,9 +1031,7 @@ impl OpenCodeServerPool {
     /// Returns an error if another worker is already running in this directory.
     /// The caller must call [`release_directory`] when the worker finishes.
     pub async fn claim_directory(&self, directory: &Path) -> anyhow::Result<()> {
-        let canonical = directory
-            .canonicalize()
-            .with_context(|| format!("directory '{}' does not exist", directory.display()))?;
+        let canonical = self.resolve_directory(directory)?;
 
         let mut active = self.active_directories.lock().await;
         if !active.insert(canonical.clone()) {
@@ -667,7 +1049,7 @@ impl OpenCodeServerPool {
 
     /// Release a directory previously claimed by [`claim_directory`].
     pub async fn release_directory(&self, directory: &Path) {
-        let canonical = match directory.canonicalize() {
+        let canonical = match self.resolve_directory(directory) {
             Ok(path) => path,
             Err(error) => {
                 tracing::warn!(
@@ -694,6 +1076,79 @@ impl OpenCodeServerPool {
     }
 }
 
+/// Build the HTTP client for one OpenCode server.
+///
+/// Local subprocesses get a plain client. Remote backends get their auth and
+/// extra headers installed as defaults so the SSE subscription carries them
+/// too, plus an optional TLS client identity and CA bundle for mTLS.
+fn build_client(backend: Option<&OpenCodeBackendConfig>) -> anyhow::Result<Client> {
+    let mut builder = Client::builder().timeout(std::time::Duration::from_secs(300));
+
+    if let Some(backend) = backend {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &backend.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).with_context(|| {
+                format!(
+                    "invalid header name '{name}' for backend '{}'",
+                    backend.name
+                )
+            })?;
+            let value = HeaderValue::from_str(value).with_context(|| {
+                format!(
+                    "invalid header value for '{name}' on backend '{}'",
+                    backend.name
+                )
+            })?;
+            headers.insert(name, value);
+        }
+
+        let authorization = match &backend.auth {
+            OpenCodeBackendAuth::None => None,
+            OpenCodeBackendAuth::Bearer { token } => Some(format!("Bearer {token}")),
+            OpenCodeBackendAuth::Basic { username, password } => {
+                let credentials = format!("{username}:{}", password.as_deref().unwrap_or(""));
+                Some(format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD.encode(credentials)
+                ))
+            }
+        };
+        if let Some(authorization) = authorization {
+            let mut value = HeaderValue::from_str(&authorization)
+                .with_context(|| format!("invalid credentials for backend '{}'", backend.name))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        builder = builder.default_headers(headers);
+
+        if let Some(cert_path) = &backend.client_cert {
+            let mut pem = std::fs::read(cert_path)
+                .with_context(|| format!("failed to read client_cert '{}'", cert_path.display()))?;
+            if let Some(key_path) = &backend.client_key {
+                let key = std::fs::read(key_path).with_context(|| {
+                    format!("failed to read client_key '{}'", key_path.display())
+                })?;
+                pem.push(b'\n');
+                pem.extend_from_slice(&key);
+            }
+            let identity = reqwest::Identity::from_pem(&pem).with_context(|| {
+                format!("invalid TLS client identity for backend '{}'", backend.name)
+            })?;
+            builder = builder.identity(identity);
+        }
+
+        if let Some(ca_path) = &backend.ca_cert {
+            let pem = std::fs::read(ca_path)
+                .with_context(|| format!("failed to read ca_cert '{}'", ca_path.display()))?;
+            let certificate = reqwest::Certificate::from_pem(&pem)
+                .with_context(|| format!("invalid ca_cert for backend '{}'", backend.name))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder.build().context("failed to create HTTP client")
+}
+
 /// Derive a deterministic port from a directory path.
 ///
 /// Uses a hash of the canonical path mapped into the range 10000-60000.
@@ -0,0 +1,203 @@
+//! Forwarding of OpenCode server warnings to an ops channel.
+//!
+//! OpenCode reports server-level problems (bad provider credentials, MCP
+//! servers failing to start, LSP crashes) as log and toast events that are
+//! not tied to any session. Workers publish them on the event bus and the
+//! cortex forwards entries at or above `server_logs.min_level` to
+//! `server_logs.forward_to`, deduplicated and rate limited so a flapping
+//! server can't flood the channel.
+
+use crate::config::OpenCodeServerLogConfig;
+use crate::opencode::types::ServerLogEntry;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Window for `max_per_minute`.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// How long an identical entry is suppressed after being forwarded. Every
+/// worker on a server sees the same server events, so duplicates are normal.
+const DEDUP_WINDOW: Duration = Duration::from_secs(600);
+
+/// Characters of the log message included in the chat post.
+const MESSAGE_CHARS: usize = 500;
+
+/// Rate limiter and deduplicator for forwarded server log entries.
+#[derive(Debug, Default)]
+pub struct ServerLogForwarder {
+    /// Send times within the current rate window.
+    sent: VecDeque<Instant>,
+    /// Last forward time per entry key.
+    seen: HashMap<String, Instant>,
+    /// Entries dropped by the rate limit since the last forward.
+    suppressed: usize,
+}
+
+impl ServerLogForwarder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide whether to forward `entry`, returning the chat text if so.
+    pub fn admit(
+        &mut self,
+        agent_id: &str,
+        entry: &ServerLogEntry,
+        config: &OpenCodeServerLogConfig,
+        now: Instant,
+    ) -> Option<String> {
+        if entry.level < config.min_level {
+            return None;
+        }
+
+        let key = format!(
+            "{}|{}|{}",
+            entry.level,
+            entry.service.as_deref().unwrap_or_default(),
+            entry.message
+        );
+        self.seen
+            .retain(|_, forwarded_at| now.duration_since(*forwarded_at) < DEDUP_WINDOW);
+        if self.seen.contains_key(&key) {
+            return None;
+        }
+
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent_at| now.duration_since(*sent_at) >= RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= config.max_per_minute as usize {
+            self.suppressed += 1;
+            return None;
+        }
+
+        self.sent.push_back(now);
+        self.seen.insert(key, now);
+        let suppressed = std::mem::take(&mut self.suppressed);
+        Some(format_entry(agent_id, entry, suppressed))
+    }
+}
+
+fn format_entry(agent_id: &str, entry: &ServerLogEntry, suppressed: usize) -> String {
+    let icon = if entry.level >= crate::opencode::types::ServerLogLevel::Error {
+        "🛑"
+    } else {
+        "⚠️"
+    };
+    let service = entry
+        .service
+        .as_deref()
+        .map(|service| format!(" [{service}]"))
+        .unwrap_or_default();
+    let message = match entry.message.char_indices().nth(MESSAGE_CHARS) {
+        Some((index, _)) => format!("{}…", &entry.message[..index]),
+        None => entry.message.clone(),
+    };
+    let mut text = format!(
+        "{icon} OpenCode {}{service} (agent {agent_id}): {}",
+        entry.level,
+        message.trim()
+    );
+    if suppressed > 0 {
+        text.push_str(&format!("\n({suppressed} more entries were rate limited)"));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opencode::types::ServerLogLevel;
+
+    fn entry(level: ServerLogLevel, message: &str) -> ServerLogEntry {
+        ServerLogEntry {
+            level,
+            service: Some("provider".into()),
+            message: message.into(),
+        }
+    }
+
+    fn config(max_per_minute: u32) -> OpenCodeServerLogConfig {
+        OpenCodeServerLogConfig {
+            max_per_minute,
+            ..OpenCodeServerLogConfig::default()
+        }
+    }
+
+    #[test]
+    fn filters_by_level_and_deduplicates() {
+        let mut forwarder = ServerLogForwarder::new();
+        let now = Instant::now();
+        let config = config(5);
+
+        assert!(
+            forwarder
+                .admit(
+                    "main",
+                    &entry(ServerLogLevel::Info, "started"),
+                    &config,
+                    now
+                )
+                .is_none()
+        );
+        let text = forwarder
+            .admit(
+                "main",
+                &entry(ServerLogLevel::Error, "invalid API key"),
+                &config,
+                now,
+            )
+            .unwrap();
+        assert_eq!(
+            text,
+            "🛑 OpenCode error [provider] (agent main): invalid API key"
+        );
+        assert!(
+            forwarder
+                .admit(
+                    "main",
+                    &entry(ServerLogLevel::Error, "invalid API key"),
+                    &config,
+                    now
+                )
+                .is_none()
+        );
+        assert!(
+            forwarder
+                .admit(
+                    "main",
+                    &entry(ServerLogLevel::Error, "invalid API key"),
+                    &config,
+                    now + DEDUP_WINDOW
+                )
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn rate_limits_and_reports_suppressed_count() {
+        let mut forwarder = ServerLogForwarder::new();
+        let now = Instant::now();
+        let config = config(2);
+
+        for index in 0..4 {
+            let message = format!("warning {index}");
+            let admitted =
+                forwarder.admit("main", &entry(ServerLogLevel::Warn, &message), &config, now);
+            assert_eq!(admitted.is_some(), index < 2);
+        }
+        let text = forwarder
+            .admit(
+                "main",
+                &entry(ServerLogLevel::Warn, "later"),
+                &config,
+                now + RATE_WINDOW,
+            )
+            .unwrap();
+        assert!(text.ends_with("(2 more entries were rate limited)"));
+    }
+}
@@ -5,9 +5,20 @@
 //!
 //! Every SSE event from OpenCode follows the envelope: `{ type: "...", properties: { ... } }`.
 //! The `properties` content varies per event type.
+//!
+//! OpenCode's payloads grow between releases. `MessageInfo`, `Part`, and
+//! `ToolState` keep fields they don't model in an `extra` map instead of
+//! failing, and each unmodeled field is logged the first time it appears so
+//! upgrades show what is worth modeling next.
+
+use crate::opencode::render::{ToolOutput, ToolOutputRenderers};
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
 
 // -- Request types --
 
@@ -44,7 +55,7 @@ pub struct ModelParam {
 }
 
 /// Body for `POST /session/{id}/message` (send prompt).
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SendPromptRequest {
     pub parts: Vec<PartInput>,
@@ -64,8 +75,15 @@ pub struct PermissionReplyRequest {
     pub message: Option<String>,
 }
 
+/// Body for `POST /session/{id}/permissions/{permission_id}`, the permission
+/// reply route before `/permission/{id}/reply`.
+#[derive(Debug, Serialize)]
+pub struct LegacyPermissionReplyRequest {
+    pub response: PermissionReply,
+}
+
 /// Permission reply options.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionReply {
     Once,
@@ -81,6 +99,59 @@ pub struct QuestionAnswer {
     pub description: Option<String>,
 }
 
+/// Body for `POST /session/{id}/summarize`.
+#[derive(Debug, Serialize)]
+pub struct SummarizeRequest {
+    #[serde(rename = "providerID")]
+    pub provider_id: String,
+    #[serde(rename = "modelID")]
+    pub model_id: String,
+}
+
+/// A command the server can run (`GET /command`): OpenCode's built-in ones
+/// plus any the project defines under `.opencode/command/`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Agent the command runs under, if it names one.
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+/// Body for `POST /session/{id}/command`.
+#[derive(Debug, Serialize)]
+pub struct SendCommandRequest {
+    pub command: String,
+    pub arguments: String,
+    /// `provider/model`; the command's own model or the session default
+    /// when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Connection state of one MCP server configured in OpenCode, from
+/// `GET /mcp`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum McpStatus {
+    Connected,
+    Disabled,
+    Failed {
+        #[serde(default)]
+        error: String,
+    },
+    NeedsAuth,
+    NeedsClientRegistration {
+        #[serde(default)]
+        error: String,
+    },
+    /// A state this version of Spacebot doesn't know.
+    #[serde(other)]
+    Unknown,
+}
+
 /// Body for `POST /question/{id}/reply`.
 #[derive(Debug, Serialize)]
 pub struct QuestionReplyRequest {
@@ -96,10 +167,34 @@ pub struct Session {
     pub id: String,
     #[serde(default)]
     pub title: Option<String>,
-    #[serde(default)]
+    #[serde(default, alias = "parentID")]
     pub parent_id: Option<String>,
 }
 
+/// Project resolved for a directory, from `GET /project/current`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Project {
+    pub id: String,
+    /// Root of the repository or worktree containing the directory.
+    pub worktree: String,
+    /// Version control system, e.g. `git`. Absent outside a repository.
+    #[serde(default)]
+    pub vcs: Option<String>,
+}
+
+/// Paths the server uses for a directory, from `GET /path`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathInfo {
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default)]
+    pub worktree: Option<String>,
+    #[serde(default)]
+    pub config: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+}
+
 /// Health check response from `GET /global/health` or `GET /api/health`.
 #[derive(Debug, Deserialize)]
 pub struct HealthResponse {
@@ -128,6 +223,37 @@ pub struct MessageInfo {
     pub session_id: Option<String>,
     #[serde(default)]
     pub time: Option<TimeSpan>,
+    /// Fields not modeled above.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Unmodeled fields already logged, per type.
+static SEEN_UNMODELED_FIELDS: LazyLock<Mutex<HashMap<&'static str, HashSet<String>>>> =
+    LazyLock::new(Default::default);
+
+/// Log each field in `extra` the first time it's seen on `type_name`.
+fn note_unmodeled_fields(
+    type_name: &'static str,
+    extra: &serde_json::Map<String, serde_json::Value>,
+) {
+    if extra.is_empty() {
+        return;
+    }
+    let mut seen = SEEN_UNMODELED_FIELDS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let seen_fields = seen.entry(type_name).or_default();
+    for field in extra.keys() {
+        if !seen_fields.contains(field) {
+            seen_fields.insert(field.clone());
+            tracing::info!(
+                type_name,
+                field,
+                "OpenCode sent a field that isn't modeled yet"
+            );
+        }
+    }
 }
 
 // -- SSE Event types --
@@ -145,6 +271,37 @@ pub struct SseEventEnvelope {
     pub properties: serde_json::Value,
 }
 
+/// Borrowed SSE event envelope, used on the event stream's hot path.
+///
+/// `properties` is kept as unparsed JSON borrowed from the input, so large
+/// payloads such as tool outputs are only copied into the typed fields of
+/// the event that retains them.
+#[derive(Debug, Deserialize)]
+pub struct SseEventEnvelopeRef<'a> {
+    #[serde(rename = "type", borrow)]
+    pub event_type: Cow<'a, str>,
+    #[serde(borrow, default)]
+    pub properties: Option<&'a RawValue>,
+}
+
+/// An event's `properties`, deserialized on demand into the type its event
+/// type needs.
+trait EventProperties {
+    fn parse<T: DeserializeOwned>(&self) -> serde_json::Result<T>;
+}
+
+impl EventProperties for serde_json::Value {
+    fn parse<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        T::deserialize(self)
+    }
+}
+
+impl EventProperties for Option<&RawValue> {
+    fn parse<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(self.map_or("null", RawValue::get))
+    }
+}
+
 /// Parsed SSE event. Constructed from `SseEventEnvelope` after matching on type.
 #[derive(Debug, Clone)]
 pub enum SseEvent {
@@ -177,58 +334,102 @@ pub enum SseEvent {
         session_id: String,
         request_id: String,
     },
+    TodoUpdated {
+        session_id: String,
+        todos: Vec<TodoItem>,
+    },
+    /// `session.created` / `session.updated`. Child sessions spawned by the
+    /// `task` tool carry their parent's id.
+    SessionUpdated(Session),
+    /// Server-level log line or notification (`log`, `server.log`,
+    /// `tui.toast.show`). Not scoped to a session.
+    ServerLog(ServerLogEntry),
+    /// `file.edited`: an edit or write tool changed a file. Not scoped to a
+    /// session; the path is absolute.
+    FileEdited {
+        file: String,
+    },
+    /// `file.watcher.updated`: the server's file watcher saw a change in
+    /// the project directory. Not scoped to a session.
+    FileWatcherUpdated {
+        file: String,
+        event: FileWatcherEvent,
+    },
     Unknown(String),
 }
 
+/// Kind of change reported by `file.watcher.updated`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileWatcherEvent {
+    Add,
+    Change,
+    Unlink,
+}
+
 impl SseEvent {
     /// Parse from an envelope. Returns `Unknown` for unrecognized event types.
     pub fn from_envelope(envelope: SseEventEnvelope) -> Self {
-        let props = envelope.properties;
+        Self::from_properties(&envelope.event_type, &envelope.properties)
+    }
+
+    /// Parse from a borrowed envelope. Properties are deserialized straight
+    /// from the raw JSON into the event's own types, with no intermediate
+    /// `Value`, and never for unrecognized event types.
+    pub fn from_envelope_ref(envelope: SseEventEnvelopeRef<'_>) -> Self {
+        Self::from_properties(&envelope.event_type, &envelope.properties)
+    }
 
-        match envelope.event_type.as_str() {
+    fn from_properties(event_type: &str, props: &impl EventProperties) -> Self {
+        match event_type {
             "message.updated" => {
-                let info = serde_json::from_value::<MessageUpdatedProps>(props)
+                let info = props
+                    .parse::<MessageUpdatedProps>()
                     .ok()
                     .and_then(|p| p.info);
+                if let Some(info) = &info {
+                    note_unmodeled_fields("MessageInfo", &info.extra);
+                }
                 SseEvent::MessageUpdated { info }
             }
-            "message.part.updated" => {
-                match serde_json::from_value::<MessagePartUpdatedProps>(props) {
-                    Ok(p) => SseEvent::MessagePartUpdated {
+            "message.part.updated" => match props.parse::<MessagePartUpdatedProps>() {
+                Ok(p) => {
+                    p.part.note_unmodeled_fields();
+                    SseEvent::MessagePartUpdated {
                         part: p.part,
                         delta: p.delta,
-                    },
-                    Err(error) => {
-                        tracing::trace!(%error, "failed to parse message.part.updated properties");
-                        SseEvent::Unknown("message.part.updated (parse error)".into())
                     }
                 }
-            }
-            "session.idle" => match serde_json::from_value::<SessionIdProps>(props) {
+                Err(error) => {
+                    tracing::trace!(%error, "failed to parse message.part.updated properties");
+                    SseEvent::Unknown("message.part.updated (parse error)".into())
+                }
+            },
+            "session.idle" => match props.parse::<SessionIdProps>() {
                 Ok(p) => SseEvent::SessionIdle {
                     session_id: p.session_id,
                 },
                 Err(_) => SseEvent::Unknown("session.idle (parse error)".into()),
             },
             "session.error" => {
-                let p = serde_json::from_value::<SessionErrorProps>(props).unwrap_or_default();
+                let p = props.parse::<SessionErrorProps>().unwrap_or_default();
                 SseEvent::SessionError {
                     session_id: p.session_id,
                     error: p.error,
                 }
             }
-            "session.status" => match serde_json::from_value::<SessionStatusProps>(props) {
+            "session.status" => match props.parse::<SessionStatusProps>() {
                 Ok(p) => SseEvent::SessionStatus {
                     session_id: p.session_id,
                     status: p.status,
                 },
                 Err(_) => SseEvent::Unknown("session.status (parse error)".into()),
             },
-            "permission.asked" => match serde_json::from_value::<PermissionRequest>(props) {
+            "permission.asked" => match props.parse::<PermissionRequest>() {
                 Ok(p) => SseEvent::PermissionAsked(p),
                 Err(_) => SseEvent::Unknown("permission.asked (parse error)".into()),
             },
-            "permission.replied" => match serde_json::from_value::<PermissionRepliedProps>(props) {
+            "permission.replied" => match props.parse::<PermissionRepliedProps>() {
                 Ok(p) => SseEvent::PermissionReplied {
                     session_id: p.session_id,
                     request_id: p.request_id,
@@ -236,22 +437,113 @@ impl SseEvent {
                 },
                 Err(_) => SseEvent::Unknown("permission.replied (parse error)".into()),
             },
-            "question.asked" => match serde_json::from_value::<QuestionRequest>(props) {
+            "question.asked" => match props.parse::<QuestionRequest>() {
                 Ok(p) => SseEvent::QuestionAsked(p),
                 Err(_) => SseEvent::Unknown("question.asked (parse error)".into()),
             },
-            "question.replied" => match serde_json::from_value::<QuestionRepliedProps>(props) {
+            "question.replied" => match props.parse::<QuestionRepliedProps>() {
                 Ok(p) => SseEvent::QuestionReplied {
                     session_id: p.session_id,
                     request_id: p.request_id,
                 },
                 Err(_) => SseEvent::Unknown("question.replied (parse error)".into()),
             },
+            "session.created" | "session.updated" => match props.parse::<SessionUpdatedProps>() {
+                Ok(p) => SseEvent::SessionUpdated(p.info),
+                Err(_) => SseEvent::Unknown(format!("{} (parse error)", event_type)),
+            },
+            "todo.updated" => match props.parse::<TodoUpdatedProps>() {
+                Ok(p) => SseEvent::TodoUpdated {
+                    session_id: p.session_id,
+                    todos: p.todos,
+                },
+                Err(_) => SseEvent::Unknown("todo.updated (parse error)".into()),
+            },
+            "log" | "server.log" => match props.parse::<ServerLogProps>() {
+                Ok(p) => SseEvent::ServerLog(ServerLogEntry {
+                    level: ServerLogLevel::parse(&p.level).unwrap_or(ServerLogLevel::Info),
+                    service: p.service,
+                    message: p.message,
+                }),
+                Err(_) => SseEvent::Unknown(format!("{} (parse error)", event_type)),
+            },
+            "tui.toast.show" => match props.parse::<ToastProps>() {
+                Ok(p) => SseEvent::ServerLog(ServerLogEntry {
+                    level: ServerLogLevel::parse(&p.variant).unwrap_or(ServerLogLevel::Info),
+                    service: Some("toast".into()),
+                    message: match p.title {
+                        Some(title) if !title.is_empty() => format!("{title}: {}", p.message),
+                        _ => p.message,
+                    },
+                }),
+                Err(_) => SseEvent::Unknown("tui.toast.show (parse error)".into()),
+            },
+            "file.edited" => match props.parse::<FileEditedProps>() {
+                Ok(p) => SseEvent::FileEdited { file: p.file },
+                Err(_) => SseEvent::Unknown("file.edited (parse error)".into()),
+            },
+            "file.watcher.updated" => match props.parse::<FileWatcherProps>() {
+                Ok(p) => SseEvent::FileWatcherUpdated {
+                    file: p.file,
+                    event: p.event,
+                },
+                Err(_) => SseEvent::Unknown("file.watcher.updated (parse error)".into()),
+            },
             other => SseEvent::Unknown(other.to_string()),
         }
     }
 }
 
+/// Severity of an OpenCode server log entry, ordered from least severe.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerLogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ServerLogLevel {
+    /// Parse a log level or toast variant (`warning`, `success`, ...).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "debug" | "trace" => Some(Self::Debug),
+            "info" | "success" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "fatal" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for ServerLogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A server-level log line or notification from OpenCode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ServerLogEntry {
+    pub level: ServerLogLevel,
+    /// Emitting subsystem, e.g. `provider` or `lsp`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    pub message: String,
+}
+
 // -- Properties structs for each event type --
 
 #[derive(Debug, Deserialize)]
@@ -305,6 +597,244 @@ struct QuestionRepliedProps {
     request_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ServerLogProps {
+    #[serde(default)]
+    level: String,
+    #[serde(default)]
+    service: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToastProps {
+    #[serde(default)]
+    title: Option<String>,
+    message: String,
+    #[serde(default)]
+    variant: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionUpdatedProps {
+    info: Session,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileEditedProps {
+    file: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileWatcherProps {
+    file: String,
+    event: FileWatcherEvent,
+}
+
+#[derive(Debug, Deserialize)]
+struct TodoUpdatedProps {
+    #[serde(rename = "sessionID")]
+    session_id: String,
+    #[serde(default)]
+    todos: Vec<TodoItem>,
+}
+
+// -- Todo types --
+
+/// One entry of an OpenCode session's todo list, as written by the
+/// `todowrite` tool and broadcast in `todo.updated` events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TodoItem {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub content: String,
+    /// "pending", "in_progress", "completed", or "cancelled". Kept as a
+    /// string so new statuses don't fail the whole list.
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub priority: Option<String>,
+}
+
+/// Extract the todo list from a `todowrite` tool call. OpenCode puts the
+/// list in the call's `input` and echoes it in `metadata` once completed.
+pub fn todos_from_tool_state(tool: &str, state: &ToolState) -> Option<Vec<TodoItem>> {
+    if tool != "todowrite" {
+        return None;
+    }
+    let (input, metadata) = match state {
+        ToolState::Running {
+            input, metadata, ..
+        }
+        | ToolState::Completed {
+            input, metadata, ..
+        } => (input.as_ref(), metadata.as_ref()),
+        ToolState::Pending { .. } | ToolState::Error { .. } => return None,
+    };
+    metadata
+        .and_then(|metadata| metadata.get("todos"))
+        .or_else(|| input.and_then(|input| input.get("todos")))
+        .and_then(|todos| serde_json::from_value(todos.clone()).ok())
+}
+
+// -- File change types --
+
+/// How a file changed during an assistant turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl FileChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileChangeKind::Created => "created",
+            FileChangeKind::Modified => "modified",
+            FileChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+/// A file changed during an assistant turn. Line counts are known when an
+/// edit or write tool reported them; file events and patch parts only name
+/// the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    #[serde(default)]
+    pub additions: Option<u32>,
+    #[serde(default)]
+    pub deletions: Option<u32>,
+}
+
+impl FileChange {
+    pub fn new(path: impl Into<String>, kind: FileChangeKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+            additions: None,
+            deletions: None,
+        }
+    }
+
+    /// Fold a later change to the same file into this one. Line counts add
+    /// up; a file created and then edited stays created, and a file deleted
+    /// and then written again counts as modified.
+    pub fn merge(&mut self, later: FileChange) {
+        self.kind = match (self.kind, later.kind) {
+            (_, FileChangeKind::Deleted) => FileChangeKind::Deleted,
+            (FileChangeKind::Deleted, _) => FileChangeKind::Modified,
+            (FileChangeKind::Created, _) => FileChangeKind::Created,
+            (FileChangeKind::Modified, kind) => kind,
+        };
+        let add = |current: Option<u32>, later: Option<u32>| match (current, later) {
+            (Some(current), Some(later)) => Some(current + later),
+            (current, later) => current.or(later),
+        };
+        self.additions = add(self.additions, later.additions);
+        self.deletions = add(self.deletions, later.deletions);
+    }
+}
+
+/// File change reported by a completed `edit` or `write` call. `edit`
+/// records line counts in `metadata.filediff`; `write` records whether the
+/// file already existed, and a new file's lines all count as additions.
+pub fn file_change_from_tool_state(tool: &str, state: &ToolState) -> Option<FileChange> {
+    let ToolState::Completed {
+        input, metadata, ..
+    } = state
+    else {
+        return None;
+    };
+    let input = input.as_ref()?;
+    let path = input.get("filePath").and_then(|path| path.as_str())?;
+    let file_diff = metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("filediff"));
+    let count = |field: &str| {
+        file_diff
+            .and_then(|file_diff| file_diff.get(field))
+            .and_then(|count| count.as_u64())
+            .map(|count| count as u32)
+    };
+
+    let mut change = match tool {
+        "edit" => {
+            let created = file_diff
+                .and_then(|file_diff| file_diff.get("before"))
+                .and_then(|before| before.as_str())
+                .is_some_and(str::is_empty);
+            let kind = if created {
+                FileChangeKind::Created
+            } else {
+                FileChangeKind::Modified
+            };
+            FileChange::new(path, kind)
+        }
+        "write" => {
+            let existed = metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("exists"))
+                .and_then(|exists| exists.as_bool())
+                .unwrap_or(true);
+            if !existed {
+                let lines = input
+                    .get("content")
+                    .and_then(|content| content.as_str())
+                    .map(|content| content.lines().count() as u32);
+                let mut change = FileChange::new(path, FileChangeKind::Created);
+                change.additions = lines;
+                change.deletions = lines.map(|_| 0);
+                return Some(change);
+            }
+            FileChange::new(path, FileChangeKind::Modified)
+        }
+        _ => return None,
+    };
+    change.additions = count("additions");
+    change.deletions = count("deletions");
+    Some(change)
+}
+
+// -- Sub-agent types --
+
+/// A child session spawned by the `task` tool, attached to every part that
+/// session emits so the UI and chat can nest it under the parent's call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct OpenCodeSubagent {
+    /// The child session's id.
+    pub session_id: String,
+    /// Id of the parent session's `task` tool part, once known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_part_id: Option<String>,
+    /// Task description or session title.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Child session id from a `task` tool call. OpenCode records it in the
+/// call's metadata as soon as the sub-agent starts.
+pub fn subagent_session_id(tool: &str, state: &ToolState) -> Option<String> {
+    if tool != "task" {
+        return None;
+    }
+    let metadata = match state {
+        ToolState::Running { metadata, .. } | ToolState::Completed { metadata, .. } => {
+            metadata.as_ref()?
+        }
+        ToolState::Pending { .. } | ToolState::Error { .. } => return None,
+    };
+    metadata
+        .get("sessionId")
+        .or_else(|| metadata.get("sessionID"))
+        .and_then(|value| value.as_str())
+        .map(String::from)
+}
+
 // -- Part types --
 
 /// A content part within a message. Discriminated by `type` field.
@@ -322,6 +852,8 @@ pub enum Part {
         text: String,
         #[serde(default)]
         time: Option<TimeSpan>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     #[serde(rename = "tool")]
     Tool {
@@ -339,12 +871,16 @@ pub enum Part {
         /// not a simple enum. Contains `input`, `output`, `title`, `time`, etc.
         #[serde(default)]
         state: Option<ToolState>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     #[serde(rename = "step-start")]
     StepStart {
         id: String,
         #[serde(rename = "sessionID", default)]
         session_id: Option<String>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     #[serde(rename = "step-finish")]
     StepFinish {
@@ -353,12 +889,105 @@ pub enum Part {
         session_id: Option<String>,
         #[serde(default)]
         reason: Option<String>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
+    /// Files a step changed, from OpenCode's snapshot diff. Absolute paths.
+    #[serde(rename = "patch")]
+    Patch {
+        id: String,
+        #[serde(rename = "sessionID", default)]
+        session_id: Option<String>,
+        #[serde(default)]
+        files: Vec<String>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
+    },
+    /// The model's reasoning ahead of its answer. `time.end` is set once the
+    /// reasoning is complete.
+    #[serde(rename = "reasoning")]
+    Reasoning {
+        id: String,
+        #[serde(rename = "sessionID", default)]
+        session_id: Option<String>,
+        #[serde(rename = "messageID", default)]
+        message_id: Option<String>,
+        #[serde(default)]
+        text: String,
+        #[serde(default)]
+        time: Option<TimeSpan>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
-    /// Catch-all for part types we don't process (reasoning, file, subtask, snapshot, etc.)
+    /// Catch-all for part types we don't process (file, subtask, snapshot, etc.)
     #[serde(other)]
     Other,
 }
 
+impl Part {
+    /// ID of the session this part belongs to. `None` for `Other`.
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Part::Text { session_id, .. }
+            | Part::Tool { session_id, .. }
+            | Part::StepStart { session_id, .. }
+            | Part::StepFinish { session_id, .. }
+            | Part::Patch { session_id, .. }
+            | Part::Reasoning { session_id, .. } => session_id.as_deref(),
+            Part::Other => None,
+        }
+    }
+
+    /// ID of the message this part belongs to. Step and patch parts carry it
+    /// among their unmodeled fields.
+    pub fn message_id(&self) -> Option<&str> {
+        match self {
+            Part::Text { message_id, .. }
+            | Part::Tool { message_id, .. }
+            | Part::Reasoning { message_id, .. } => message_id.as_deref(),
+            _ => self
+                .extra()?
+                .get("messageID")
+                .and_then(|value| value.as_str()),
+        }
+    }
+
+    /// Fields of this part that aren't modeled. `None` for `Other`, which
+    /// keeps nothing.
+    pub fn extra(&self) -> Option<&serde_json::Map<String, serde_json::Value>> {
+        match self {
+            Part::Text { extra, .. }
+            | Part::Tool { extra, .. }
+            | Part::StepStart { extra, .. }
+            | Part::StepFinish { extra, .. }
+            | Part::Patch { extra, .. }
+            | Part::Reasoning { extra, .. } => Some(extra),
+            Part::Other => None,
+        }
+    }
+
+    fn note_unmodeled_fields(&self) {
+        let type_name = match self {
+            Part::Text { .. } => "Part::Text",
+            Part::Tool { .. } => "Part::Tool",
+            Part::StepStart { .. } => "Part::StepStart",
+            Part::StepFinish { .. } => "Part::StepFinish",
+            Part::Patch { .. } => "Part::Patch",
+            Part::Reasoning { .. } => "Part::Reasoning",
+            Part::Other => return,
+        };
+        if let Some(extra) = self.extra() {
+            note_unmodeled_fields(type_name, extra);
+        }
+        if let Part::Tool {
+            state: Some(state), ..
+        } = self
+        {
+            note_unmodeled_fields(state.type_name(), state.extra());
+        }
+    }
+}
+
 /// Tool execution state. Tagged by `status` field.
 ///
 /// OpenCode sends this as e.g.:
@@ -372,6 +1001,8 @@ pub enum ToolState {
     Pending {
         #[serde(default)]
         input: Option<serde_json::Value>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     #[serde(rename = "running")]
     Running {
@@ -381,6 +1012,8 @@ pub enum ToolState {
         title: Option<String>,
         #[serde(default)]
         metadata: Option<HashMap<String, serde_json::Value>>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     #[serde(rename = "completed")]
     Completed {
@@ -392,6 +1025,8 @@ pub enum ToolState {
         title: Option<String>,
         #[serde(default)]
         metadata: Option<HashMap<String, serde_json::Value>>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     #[serde(rename = "error")]
     Error {
@@ -399,10 +1034,31 @@ pub enum ToolState {
         input: Option<serde_json::Value>,
         #[serde(default)]
         error: Option<String>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
 }
 
 impl ToolState {
+    /// Fields of this state that aren't modeled.
+    pub fn extra(&self) -> &serde_json::Map<String, serde_json::Value> {
+        match self {
+            ToolState::Pending { extra, .. }
+            | ToolState::Running { extra, .. }
+            | ToolState::Completed { extra, .. }
+            | ToolState::Error { extra, .. } => extra,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            ToolState::Pending { .. } => "ToolState::Pending",
+            ToolState::Running { .. } => "ToolState::Running",
+            ToolState::Completed { .. } => "ToolState::Completed",
+            ToolState::Error { .. } => "ToolState::Error",
+        }
+    }
+
     /// Check if this is a running state.
     pub fn is_running(&self) -> bool {
         matches!(self, ToolState::Running { .. })
@@ -634,6 +1290,7 @@ pub fn part_to_opencode_part(part: &Part) -> Option<OpenCodePart> {
                     title,
                     input,
                     output,
+                    metadata,
                     ..
                 }) => OpenCodeToolState::Completed {
                     title: title.clone(),
@@ -646,7 +1303,24 @@ pub fn part_to_opencode_part(part: &Part) -> Option<OpenCodePart> {
                         }
                     }),
                     output: output.as_ref().map(|o| {
-                        crate::tools::truncate_output(o, crate::tools::MAX_TOOL_OUTPUT_BYTES)
+                        let metadata = metadata.as_ref().map(|metadata| {
+                            serde_json::Value::Object(
+                                metadata
+                                    .iter()
+                                    .map(|(key, value)| (key.clone(), value.clone()))
+                                    .collect(),
+                            )
+                        });
+                        let rendered = ToolOutputRenderers::global().render(&ToolOutput {
+                            tool: &tool_name,
+                            input: input.as_ref(),
+                            output: o,
+                            metadata: metadata.as_ref(),
+                        });
+                        crate::tools::truncate_output(
+                            &rendered,
+                            crate::tools::MAX_TOOL_OUTPUT_BYTES,
+                        )
                     }),
                 },
                 Some(ToolState::Error { error, .. }) => OpenCodeToolState::Error {
@@ -665,6 +1339,6 @@ pub fn part_to_opencode_part(part: &Part) -> Option<OpenCodePart> {
             id: id.clone(),
             reason: reason.clone(),
         }),
-        Part::Other => None,
+        Part::Patch { .. } | Part::Reasoning { .. } | Part::Other => None,
     }
 }
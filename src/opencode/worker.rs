@@ -4,6 +4,8 @@
 //! delegates to an OpenCode subprocess that has its own codebase exploration,
 //! context management, and tool suite. Communication happens over HTTP + SSE.
 
+use crate::conversation::WriteBehind;
+use crate::conversation::worker_transcript::OpenCodeTranscriptAssembler;
 use crate::opencode::server::OpenCodeServerPool;
 use crate::opencode::types::*;
 use crate::secrets::store::SecretsStore;
@@ -11,11 +13,49 @@ use crate::{AgentId, ChannelId, ProcessEvent, WorkerId};
 
 use anyhow::{Context as _, bail};
 use futures::StreamExt as _;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::{Mutex, broadcast, mpsc};
 use uuid::Uuid;
 
+/// OpenCode's read-only planning agent, used for the first stage of a
+/// plan-approval session.
+pub const PLAN_AGENT: &str = "plan";
+
+/// OpenCode's default agent with edit and shell access.
+pub const BUILD_AGENT: &str = "build";
+
+/// Stream gaps resynced per prompt before an interrupted stream is treated
+/// as the end of the session.
+const MAX_STREAM_RESYNCS: u32 = 3;
+
+/// Largest SSE block kept while waiting for its terminating blank line.
+/// OpenCode truncates tool output long before this, so only a broken or
+/// hostile stream reaches it.
+pub const MAX_SSE_BLOCK_BYTES: usize = 8 * 1024 * 1024;
+
+/// Follow-up that approves a pending plan. The worker sends it to the
+/// session under [`BUILD_AGENT`]; any other follow-up while a plan is
+/// pending is sent to [`PLAN_AGENT`] as a revision request.
+pub const PLAN_APPROVAL_PROMPT: &str = "The plan is approved. Implement it now.";
+
+/// The user whose message a prompt carries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contributor {
+    /// `source:sender_id`, which the prompt's token usage is attributed to.
+    pub user_id: String,
+    /// Name the prompt is prefixed with, so the session can tell
+    /// collaborators apart.
+    pub display_name: String,
+}
+
+/// Who the next queued prompt comes from. The channel fills it just before
+/// queueing a user's follow-up; the worker takes it when the prompt is sent.
+/// Prompts queued without it (plan approval, `/git`) aren't attributed.
+pub type ContributorSlot = Arc<std::sync::Mutex<Option<Contributor>>>;
+
 /// State for resuming an idle OpenCode session after restart.
 pub struct ResumeSession {
     pub session_id: String,
@@ -23,6 +63,27 @@ pub struct ResumeSession {
     pub tool_calls: i64,
 }
 
+impl ResumeSession {
+    /// Rebuild the session state from its messages API response.
+    pub fn from_messages(session_id: String, messages: &[serde_json::Value]) -> Self {
+        let accumulated_parts: Vec<OpenCodePart> = messages
+            .iter()
+            .filter_map(|message| message.get("parts").and_then(|parts| parts.as_array()))
+            .flatten()
+            .filter_map(|part| serde_json::from_value::<OpenCodePart>(part.clone()).ok())
+            .collect();
+        let tool_calls = accumulated_parts
+            .iter()
+            .filter(|part| matches!(part, OpenCodePart::Tool { .. }))
+            .count() as i64;
+        Self {
+            session_id,
+            accumulated_parts,
+            tool_calls,
+        }
+    }
+}
+
 /// An OpenCode-backed worker that drives a coding session via subprocess.
 pub struct OpenCodeWorker {
     pub id: WorkerId,
@@ -38,12 +99,42 @@ pub struct OpenCodeWorker {
     pub system_prompt: Option<String>,
     /// Model override (provider/model format like "anthropic/claude-sonnet-4").
     pub model: Option<String>,
+    /// Models to retry a prompt on, in order, when the provider is rate
+    /// limited or unavailable.
+    pub fallback_models: Vec<String>,
     /// Secrets store for exact-match scrubbing of tool secret values in SSE output.
     pub secrets_store: Option<Arc<SecretsStore>>,
-    /// SQLite pool for incremental transcript persistence (set by channel_dispatch).
-    pub sqlite_pool: Option<sqlx::SqlitePool>,
+    /// Writer for incremental transcript persistence (set by channel_dispatch).
+    pub write_behind: Option<WriteBehind>,
     /// Pre-populated session state for resumed workers (set by `resume_interactive`).
     pub resuming_session: Option<ResumeSession>,
+    /// Start under [`PLAN_AGENT`] and wait for [`PLAN_APPROVAL_PROMPT`]
+    /// before building. Not restored for resumed sessions.
+    pub plan_approval: bool,
+    /// Post each prompt as [`ProcessEvent::WorkerDryRun`] instead of sending
+    /// it. No server or session is created.
+    pub dry_run: bool,
+    /// How long a question waits for an answer from chat before its first
+    /// option is picked, and a permission request for an admin's approval
+    /// before it is rejected. Zero answers both immediately.
+    pub question_timeout: std::time::Duration,
+    /// Author of the next prompt, filled by the channel.
+    pub contributor: ContributorSlot,
+    /// Shared with the channel and set while `/debug` is on: every raw SSE
+    /// event for this session is also sent as [`ProcessEvent::WorkerRawEvent`].
+    pub mirror_events: Arc<AtomicBool>,
+}
+
+/// A question from OpenCode waiting for an answer from chat.
+struct PendingQuestion {
+    request: QuestionRequest,
+    deadline: tokio::time::Instant,
+}
+
+/// A permission request from OpenCode waiting for an admin in chat.
+struct PendingPermission {
+    request: PermissionRequest,
+    deadline: tokio::time::Instant,
 }
 
 /// Accumulated state from SSE event processing.
@@ -57,9 +148,75 @@ struct EventState {
     /// Guards: don't treat session.idle as completion until we've seen real work.
     has_received_event: bool,
     has_assistant_message: bool,
-    /// Accumulated OpenCode parts from SSE events, used as a fallback transcript
-    /// source when the post-completion `get_messages()` API call fails.
-    accumulated_parts: Vec<OpenCodePart>,
+    /// Latest version of each OpenCode part from SSE events, used for
+    /// transcript snapshots and as the fallback transcript source when the
+    /// post-completion `get_messages()` API call fails.
+    accumulated_parts: OpenCodeTranscriptAssembler,
+    /// Last todo list sent to the channel, to skip re-sending identical lists
+    /// (`todowrite` parts and `todo.updated` events carry the same data).
+    last_todos: Option<Vec<TodoItem>>,
+    /// Sub-agent sessions spawned by `task` tool calls, keyed by session id.
+    /// Their parts are forwarded as nested activity instead of dropped.
+    child_sessions: HashMap<String, OpenCodeSubagent>,
+    /// Files changed during the current assistant turn, in first-seen order.
+    turn_changes: Vec<FileChange>,
+    /// Every file changed during the session, in first-seen order.
+    session_files: Vec<String>,
+    /// Tool parts already counted in `turn_changes`. A completed part can be
+    /// re-sent, and its line counts must only be added once.
+    counted_tool_parts: HashSet<String>,
+    /// Reasoning parts already sent as `ProcessEvent::WorkerText`.
+    reported_reasoning: HashSet<String>,
+    /// Fallback model that answered the latest prompt, when the requested
+    /// model was unavailable.
+    answered_by: Option<String>,
+    /// Last session title sent to the channel.
+    session_title: Option<String>,
+    /// Question the channel has been asked to answer.
+    pending_question: Option<PendingQuestion>,
+    /// Permission requests the channel has been asked to approve.
+    pending_permissions: Vec<PendingPermission>,
+    /// Latest token usage of each assistant message in the current prompt,
+    /// keyed by message id. `message.updated` repeats as a message grows.
+    prompt_usage: HashMap<String, MessageUsage>,
+}
+
+/// Token usage OpenCode reports on one assistant message.
+#[derive(Debug, Clone)]
+struct MessageUsage {
+    tokens: crate::llm::usage::ExtendedUsage,
+    model: String,
+    provider: String,
+    cost: f64,
+}
+
+impl MessageUsage {
+    /// Read `tokens`, `cost`, `modelID`, and `providerID` off an assistant
+    /// message. `None` until OpenCode has counted any tokens.
+    fn from_info(info: &MessageInfo) -> Option<Self> {
+        let tokens =
+            crate::llm::usage::ExtendedUsage::from_opencode_tokens(info.extra.get("tokens")?);
+        if tokens.input_tokens + tokens.output_tokens == 0 {
+            return None;
+        }
+        let field = |name: &str| {
+            info.extra
+                .get(name)
+                .and_then(|value| value.as_str())
+                .unwrap_or("unknown")
+                .to_string()
+        };
+        Some(Self {
+            tokens,
+            model: field("modelID"),
+            provider: field("providerID"),
+            cost: info
+                .extra
+                .get("cost")
+                .and_then(|value| value.as_f64())
+                .unwrap_or(0.0),
+        })
+    }
 }
 
 impl EventState {
@@ -70,7 +227,46 @@ impl EventState {
             tool_calls: 0,
             has_received_event: false,
             has_assistant_message: false,
-            accumulated_parts: Vec::new(),
+            accumulated_parts: OpenCodeTranscriptAssembler::new(),
+            last_todos: None,
+            child_sessions: HashMap::new(),
+            turn_changes: Vec::new(),
+            session_files: Vec::new(),
+            counted_tool_parts: HashSet::new(),
+            reported_reasoning: HashSet::new(),
+            answered_by: None,
+            session_title: None,
+            pending_question: None,
+            pending_permissions: Vec::new(),
+            prompt_usage: HashMap::new(),
+        }
+    }
+
+    /// Record a change whose kind is known (a tool result or a watcher
+    /// add/unlink), merging it into any earlier change to the same file.
+    fn record_change(&mut self, change: FileChange) {
+        if !self.session_files.contains(&change.path) {
+            self.session_files.push(change.path.clone());
+        }
+        match self
+            .turn_changes
+            .iter_mut()
+            .find(|existing| existing.path == change.path)
+        {
+            Some(existing) => existing.merge(change),
+            None => self.turn_changes.push(change),
+        }
+    }
+
+    /// Record that a file changed without knowing how (`file.edited`, a
+    /// watcher change, a patch part). Never overrides a known change.
+    fn touch_file(&mut self, path: String) {
+        if !self
+            .turn_changes
+            .iter()
+            .any(|existing| existing.path == path)
+        {
+            self.record_change(FileChange::new(path, FileChangeKind::Modified));
         }
     }
 }
@@ -106,9 +302,15 @@ impl OpenCodeWorker {
             input_rx: None,
             system_prompt: None,
             model: None,
+            fallback_models: Vec::new(),
             secrets_store: None,
-            sqlite_pool: None,
+            write_behind: None,
             resuming_session: None,
+            plan_approval: false,
+            dry_run: false,
+            question_timeout: std::time::Duration::ZERO,
+            contributor: ContributorSlot::default(),
+            mirror_events: Arc::default(),
         }
     }
 
@@ -127,6 +329,24 @@ impl OpenCodeWorker {
         (worker, input_tx)
     }
 
+    /// Plan first and build only once the plan is approved.
+    pub fn with_plan_approval(mut self) -> Self {
+        self.plan_approval = true;
+        self
+    }
+
+    /// Post prompts to the channel instead of sending them to OpenCode.
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Wait up to `timeout` for questions to be answered from chat.
+    pub fn with_question_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.question_timeout = timeout;
+        self
+    }
+
     /// Set the system prompt injected into OpenCode prompts.
     pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
         self.system_prompt = Some(prompt.into());
@@ -139,15 +359,21 @@ impl OpenCodeWorker {
         self
     }
 
+    /// Set the models to fall back to when the provider is unavailable.
+    pub fn with_fallback_models(mut self, models: Vec<String>) -> Self {
+        self.fallback_models = models;
+        self
+    }
+
     /// Set the secrets store for exact-match scrubbing of tool secret values.
     pub fn with_secrets_store(mut self, store: Arc<SecretsStore>) -> Self {
         self.secrets_store = Some(store);
         self
     }
 
-    /// Set the SQLite pool for incremental transcript persistence.
-    pub fn with_sqlite_pool(mut self, pool: sqlx::SqlitePool) -> Self {
-        self.sqlite_pool = Some(pool);
+    /// Set the writer for incremental transcript persistence.
+    pub fn with_write_behind(mut self, write_behind: WriteBehind) -> Self {
+        self.write_behind = Some(write_behind);
         self
     }
 
@@ -200,46 +426,38 @@ impl OpenCodeWorker {
             return None;
         }
 
-        // Reconstruct accumulated_parts from the session messages (preferred)
-        // or from the persisted transcript blob (fallback).
-        let accumulated_parts = if let Ok(messages) = &messages {
-            // Re-parse the parts from the session messages API.
-            // This gives us the authoritative state.
-            let mut parts = Vec::new();
-            for message in messages {
-                if let Some(msg_parts) = message.get("parts").and_then(|p| p.as_array()) {
-                    for part_value in msg_parts {
-                        if let Ok(part) = serde_json::from_value::<OpenCodePart>(part_value.clone())
-                        {
-                            parts.push(part);
-                        }
-                    }
-                }
-            }
-            parts
-        } else {
-            Vec::new()
-        };
-
-        // Count tool calls from accumulated parts
-        let tool_calls = accumulated_parts
-            .iter()
-            .filter(|p| matches!(p, OpenCodePart::Tool { .. }))
-            .count() as i64;
+        // Reconstruct accumulated_parts from the session messages, which
+        // are the authoritative state.
+        let resume = ResumeSession::from_messages(session_id, &messages.unwrap_or_default());
 
         let (input_tx, input_rx) = mpsc::channel(32);
         let mut worker = Self::new(channel_id, agent_id, task, directory, server_pool, event_tx);
         worker.id = existing_id;
         worker.input_rx = Some(input_rx);
-        worker.resuming_session = Some(ResumeSession {
-            session_id,
-            accumulated_parts,
-            tool_calls,
-        });
+        worker.resuming_session = Some(resume);
 
         Some((worker, input_tx))
     }
 
+    /// Create an interactive worker that takes over `session_id`, a session
+    /// started outside spacebot whose `messages` were already fetched.
+    #[allow(clippy::too_many_arguments)]
+    pub fn attach_interactive(
+        channel_id: Option<ChannelId>,
+        agent_id: AgentId,
+        task: impl Into<String>,
+        directory: PathBuf,
+        server_pool: Arc<OpenCodeServerPool>,
+        event_tx: broadcast::Sender<ProcessEvent>,
+        session_id: String,
+        messages: &[serde_json::Value],
+    ) -> (Self, mpsc::Sender<String>) {
+        let (mut worker, input_tx) =
+            Self::new_interactive(channel_id, agent_id, task, directory, server_pool, event_tx);
+        worker.resuming_session = Some(ResumeSession::from_messages(session_id, messages));
+        (worker, input_tx)
+    }
+
     /// Scrub tool secret values from text, replacing each with `[REDACTED:<name>]`.
     /// Returns the scrubbed text. If no secrets store is set, returns the input unchanged.
     fn scrub_text(&self, text: &str) -> String {
@@ -253,6 +471,9 @@ impl OpenCodeWorker {
     /// send the task, monitor via SSE, and return the result.
     pub async fn run(mut self) -> anyhow::Result<OpenCodeWorkerResult> {
         let resuming = self.resuming_session.is_some();
+        if self.dry_run && !resuming {
+            return Ok(self.run_dry().await);
+        }
 
         // --- Session setup: either resume an existing session or create a new one ---
         let (server, session_id, mut event_state, result_text) =
@@ -296,7 +517,7 @@ impl OpenCodeWorker {
                 );
 
                 let mut event_state = EventState::new();
-                event_state.accumulated_parts = resume.accumulated_parts;
+                event_state.accumulated_parts = resume.accumulated_parts.into_iter().collect();
                 event_state.tool_calls = resume.tool_calls;
                 event_state.has_received_event = true;
                 event_state.has_assistant_message = true;
@@ -349,109 +570,74 @@ impl OpenCodeWorker {
                     "OpenCode session created"
                 );
 
-                // Subscribe to SSE events before sending the prompt
-                let event_response = {
-                    let guard = server.lock().await;
-                    guard.subscribe_events().await?
-                };
-
-                let model_param = self.model.as_ref().and_then(|m| parse_model_param(m));
-                let prompt_request = SendPromptRequest {
-                    parts: vec![PartInput::Text {
-                        text: self.task.clone(),
-                        synthetic: None,
-                    }],
-                    system: self.system_prompt.clone(),
-                    model: model_param,
-                    agent: None,
-                };
+                let (task, contributor) = self.attribute_prompt(self.task.clone());
+                let prompt_request =
+                    self.prompt_request(task, self.plan_approval.then(|| PLAN_AGENT.to_string()));
 
                 self.send_status("sending task to OpenCode");
-                {
-                    let guard = server.lock().await;
-                    guard
-                        .send_prompt_async(&session_id, &prompt_request)
-                        .await?;
-                }
-
                 let mut event_state = EventState::new();
-                self.process_events(event_response, &session_id, &server, &mut event_state)
-                    .await?;
+                let outcome = self
+                    .run_prompt(&server, &session_id, &prompt_request, &mut event_state)
+                    .await;
+                self.record_prompt_usage(&mut event_state, contributor.as_ref())
+                    .await;
+                outcome?;
 
                 let result_text = event_state.last_text.clone();
                 (server, session_id, event_state, result_text)
             };
 
         // Interactive follow-up loop
+        let mut awaiting_approval = self.plan_approval && !resuming;
         if let Some(mut input_rx) = self.input_rx.take() {
             if resuming {
                 // Resumed worker: go straight to idle without emitting initial result
                 // (it was already relayed before the restart). Persist the recovered
                 // transcript so a second crash doesn't lose it.
-                self.persist_transcript_snapshot(&event_state).await;
+                self.persist_transcript_snapshot(&mut event_state).await;
                 self.send_status("resumed — waiting for follow-up");
                 self.send_idle();
             } else {
-                // Fresh worker: emit the initial result so the channel can retrigger.
-                let scrubbed_result = self.scrub_text(&result_text);
-                let scrubbed_result = crate::secrets::scrub::scrub_leaks(&scrubbed_result);
-                let _ = self.event_tx.send(ProcessEvent::WorkerInitialResult {
-                    agent_id: self.agent_id.clone(),
-                    worker_id: self.id,
-                    channel_id: self.channel_id.clone(),
-                    result: scrubbed_result,
-                });
+                // Fresh worker: emit the initial result (or the plan awaiting
+                // approval) so the channel can deliver it.
+                self.send_result(
+                    &with_model_footer(result_text.clone(), event_state.answered_by.as_deref()),
+                    awaiting_approval,
+                );
+                self.send_files_changed(&mut event_state);
 
-                self.persist_transcript_snapshot(&event_state).await;
+                self.persist_transcript_snapshot(&mut event_state).await;
                 self.send_status("waiting for follow-up");
                 self.send_idle();
             }
 
             while let Some(follow_up) = input_rx.recv().await {
                 self.send_status("processing follow-up");
-
-                // Subscribe to fresh events for the follow-up
-                let event_response = {
-                    let guard = server.lock().await;
-                    guard.subscribe_events().await?
-                };
-
-                let follow_up_request = SendPromptRequest {
-                    parts: vec![PartInput::Text {
-                        text: follow_up,
-                        synthetic: None,
-                    }],
-                    system: self.system_prompt.clone(),
-                    model: self.model.as_ref().and_then(|m| parse_model_param(m)),
-                    agent: None,
-                };
-
-                {
-                    let guard = server.lock().await;
-                    guard
-                        .send_prompt_async(&session_id, &follow_up_request)
-                        .await?;
-                }
-
-                match self
-                    .process_events(event_response, &session_id, &server, &mut event_state)
-                    .await
-                {
+                let agent = self.follow_up_agent(&mut awaiting_approval, &follow_up);
+                let (follow_up, contributor) = self.attribute_prompt(follow_up);
+                let follow_up_request = self.prompt_request(follow_up, agent);
+
+                let outcome = self
+                    .run_prompt(&server, &session_id, &follow_up_request, &mut event_state)
+                    .await;
+                self.record_prompt_usage(&mut event_state, contributor.as_ref())
+                    .await;
+                match outcome {
                     Ok(_) => {
                         // Emit follow-up result so the channel can retrigger
                         // and relay this to the user — same as initial result.
                         let follow_up_text = event_state.last_text.clone();
                         if !follow_up_text.is_empty() {
-                            let scrubbed = self.scrub_text(&follow_up_text);
-                            let scrubbed = crate::secrets::scrub::scrub_leaks(&scrubbed);
-                            let _ = self.event_tx.send(ProcessEvent::WorkerInitialResult {
-                                agent_id: self.agent_id.clone(),
-                                worker_id: self.id,
-                                channel_id: self.channel_id.clone(),
-                                result: scrubbed,
-                            });
+                            self.send_result(
+                                &with_model_footer(
+                                    follow_up_text,
+                                    event_state.answered_by.as_deref(),
+                                ),
+                                awaiting_approval,
+                            );
                         }
-                        self.persist_transcript_snapshot(&event_state).await;
+                        self.send_files_changed(&mut event_state);
+                        self.persist_transcript_snapshot(&mut event_state).await;
                         self.send_status("waiting for follow-up");
                         self.send_idle();
                     }
@@ -466,6 +652,8 @@ impl OpenCodeWorker {
                     }
                 }
             }
+        } else {
+            self.send_files_changed(&mut event_state);
         }
 
         self.send_status("completed");
@@ -493,10 +681,7 @@ impl OpenCodeWorker {
                     // API call failed (server recycled, process exited, etc.).
                     // Fall back to the OpenCodeParts accumulated from SSE events
                     // during the session — better than losing the transcript entirely.
-                    let fallback_steps =
-                        crate::conversation::worker_transcript::convert_opencode_parts(
-                            &event_state.accumulated_parts,
-                        );
+                    let fallback_steps = event_state.accumulated_parts.steps();
                     tracing::warn!(
                         worker_id = %self.id,
                         %error,
@@ -508,7 +693,10 @@ impl OpenCodeWorker {
             };
 
         // Prefer API-fetched result text, fall back to SSE last_text
-        let final_result_text = api_result_text.unwrap_or(result_text);
+        let final_result_text = with_model_footer(
+            api_result_text.unwrap_or(result_text),
+            event_state.answered_by.as_deref(),
+        );
 
         tracing::info!(
             worker_id = %self.id,
@@ -525,6 +713,82 @@ impl OpenCodeWorker {
         })
     }
 
+    /// Send a prompt and process its events until the session goes idle.
+    ///
+    /// If the session fails because the conversation no longer fits the
+    /// model's context window, the session is compacted and the prompt is
+    /// sent once more. A second overflow is returned as an error. If the
+    /// provider is rate limited or unavailable, the prompt is sent again on
+    /// each of [`Self::fallback_models`] in turn.
+    async fn run_prompt(
+        &self,
+        server: &Arc<Mutex<crate::opencode::server::OpenCodeServer>>,
+        session_id: &str,
+        request: &SendPromptRequest,
+        event_state: &mut EventState,
+    ) -> anyhow::Result<()> {
+        let mut request = request.clone();
+        let mut fallbacks = self.fallback_models.iter();
+        let mut compacted = false;
+        event_state.answered_by = None;
+        loop {
+            // Subscribe to SSE events before sending the prompt
+            let event_response = {
+                let guard = server.lock().await;
+                guard.subscribe_events().await?
+            };
+            {
+                let guard = server.lock().await;
+                guard.send_prompt_async(session_id, &request).await?;
+            }
+
+            match self
+                .process_events(event_response, session_id, server, event_state)
+                .await
+            {
+                Err(error) if !compacted && error.is::<ContextOverflow>() => {
+                    tracing::warn!(
+                        worker_id = %self.id,
+                        %session_id,
+                        %error,
+                        "OpenCode session overflowed its context, compacting"
+                    );
+                    self.send_status("context full, compacting");
+                    crate::opencode::compact::compact_session(server, session_id)
+                        .await
+                        .context("failed to compact session after a context overflow")?;
+                    compacted = true;
+                    self.event_tx
+                        .send(ProcessEvent::WorkerContextCompacted {
+                            agent_id: self.agent_id.clone(),
+                            worker_id: self.id,
+                            channel_id: self.channel_id.clone(),
+                        })
+                        .ok();
+                    self.send_status("context compacted, retrying");
+                }
+                Err(error) if error.is::<ProviderUnavailable>() => {
+                    let Some((fallback, model)) = fallbacks.find_map(|fallback| {
+                        parse_model_param(fallback).map(|model| (fallback, model))
+                    }) else {
+                        return Err(error);
+                    };
+                    tracing::warn!(
+                        worker_id = %self.id,
+                        %session_id,
+                        %error,
+                        %fallback,
+                        "OpenCode provider unavailable, retrying on fallback model"
+                    );
+                    self.send_status(&format!("model unavailable, retrying on {fallback}"));
+                    request.model = Some(model);
+                    event_state.answered_by = Some(fallback.clone());
+                }
+                result => return result.map(|_| ()),
+            }
+        }
+    }
+
     /// Process SSE events from the OpenCode event stream until the session
     /// goes idle or encounters an error.
     async fn process_events(
@@ -536,40 +800,136 @@ impl OpenCodeWorker {
     ) -> anyhow::Result<String> {
         let mut stream = response.bytes_stream();
         let mut buffer = String::new();
+        let mut last_event_id = None;
+        let mut resyncs = 0;
 
         loop {
+            // The session is quiet while a question or permission request
+            // waits on chat, so the earliest deadline replaces the inactivity
+            // timeout.
+            let chat_deadline = event_state
+                .pending_question
+                .iter()
+                .map(|pending| pending.deadline)
+                .chain(
+                    event_state
+                        .pending_permissions
+                        .iter()
+                        .map(|pending| pending.deadline),
+                )
+                .min();
             let chunk = tokio::select! {
                 chunk = stream.next() => chunk,
-                _ = tokio::time::sleep(std::time::Duration::from_secs(600)) => {
+                _ = tokio::time::sleep_until(chat_deadline.unwrap_or_else(tokio::time::Instant::now)),
+                    if chat_deadline.is_some() =>
+                {
+                    self.expire_chat_requests(server, event_state).await;
+                    continue;
+                }
+                _ = tokio::time::sleep(std::time::Duration::from_secs(600)),
+                    if chat_deadline.is_none() =>
+                {
                     bail!("OpenCode session timed out after 10 minutes of inactivity");
                 }
             };
 
-            let Some(chunk) = chunk else {
-                // Stream ended -- if we have results, return them
-                if event_state.has_assistant_message && !event_state.last_text.is_empty() {
-                    return Ok(event_state.last_text.clone());
+            let bytes = match chunk {
+                Some(Ok(bytes)) => bytes,
+                gap => {
+                    let error = match gap {
+                        Some(Err(error)) => {
+                            anyhow::Error::from(error).context("failed to read SSE chunk")
+                        }
+                        _ => {
+                            anyhow::anyhow!("OpenCode event stream ended before session completed")
+                        }
+                    };
+                    if resyncs == MAX_STREAM_RESYNCS {
+                        // Out of resyncs -- if we have results, return them
+                        if event_state.has_assistant_message && !event_state.last_text.is_empty() {
+                            return Ok(event_state.last_text.clone());
+                        }
+                        return Err(error);
+                    }
+                    resyncs += 1;
+                    tracing::warn!(
+                        worker_id = %self.id,
+                        %session_id,
+                        %error,
+                        resumed_from = last_event_id.as_deref(),
+                        "OpenCode event stream interrupted, resyncing session"
+                    );
+
+                    // Resubscribe before fetching, so nothing falls between
+                    // the fetch and the new stream.
+                    let response = {
+                        let guard = server.lock().await;
+                        guard
+                            .subscribe_events_after(last_event_id.as_deref())
+                            .await?
+                    };
+                    stream = response.bytes_stream();
+                    buffer.clear();
+                    let action = self.resync(session_id, server, event_state).await?;
+                    if let Some(result) = event_result(action, event_state) {
+                        return result;
+                    }
+                    continue;
                 }
-                bail!("OpenCode event stream ended before session completed");
             };
-
-            let bytes = chunk.context("failed to read SSE chunk")?;
             buffer.push_str(&String::from_utf8_lossy(&bytes));
 
             // Parse SSE lines from buffer
-            while let Some(event) = extract_sse_event(&mut buffer) {
-                match self
+            let mirror = self.mirror_events.load(Ordering::Relaxed);
+            while let Some((event, data)) =
+                extract_sse_event_with_data(&mut buffer, &mut last_event_id, mirror)
+            {
+                if let Some(data) = data {
+                    self.mirror_raw_event(&data, session_id, event_state);
+                }
+                let action = self
                     .handle_sse_event(&event, session_id, server, event_state)
-                    .await
-                {
-                    EventAction::Continue => {}
-                    EventAction::Complete => return Ok(event_state.last_text.clone()),
-                    EventAction::Error(message) => bail!("OpenCode session error: {message}"),
+                    .await;
+                if let Some(result) = event_result(action, event_state) {
+                    return result;
                 }
             }
         }
     }
 
+    /// Reconcile with the session's stored messages after a stream gap.
+    ///
+    /// The current turn, from the latest user message on, is fetched over
+    /// HTTP and replayed through [`Self::handle_sse_event`]. Parts the stream
+    /// already delivered are dropped as repeats. Parts that were missed or
+    /// that diverged are upserted and re-sent to the channel, repairing its
+    /// rendering. A turn that finished or failed during the gap ends the
+    /// prompt as the missed `session.idle` or `session.error` would have.
+    async fn resync(
+        &self,
+        session_id: &str,
+        server: &Arc<Mutex<crate::opencode::server::OpenCodeServer>>,
+        event_state: &mut EventState,
+    ) -> anyhow::Result<EventAction> {
+        let messages = {
+            let guard = server.lock().await;
+            guard
+                .get_messages(session_id)
+                .await
+                .context("failed to resync session after a stream gap")?
+        };
+        let (messages, ending) = current_turn(session_id, messages);
+        for event in message_events(messages).into_iter().chain(ending) {
+            let action = self
+                .handle_sse_event(&event, session_id, server, event_state)
+                .await;
+            if !matches!(action, EventAction::Continue) {
+                return Ok(action);
+            }
+        }
+        Ok(EventAction::Continue)
+    }
+
     /// Handle a single SSE event. Returns whether to continue, complete, or error.
     async fn handle_sse_event(
         &self,
@@ -589,6 +949,15 @@ impl OpenCodeWorker {
                 {
                     state.has_assistant_message = true;
                 }
+                // Sub-agents spend tokens on the prompt's behalf too.
+                if let Some(msg) = info
+                    && msg.role == "assistant"
+                    && let Some(sid) = &msg.session_id
+                    && (sid == session_id || state.child_sessions.contains_key(sid))
+                    && let Some(usage) = MessageUsage::from_info(msg)
+                {
+                    state.prompt_usage.insert(msg.id.clone(), usage);
+                }
                 EventAction::Continue
             }
 
@@ -596,28 +965,48 @@ impl OpenCodeWorker {
                 state.has_received_event = true;
 
                 // Filter out parts from other sessions
-                let part_session_id = match part {
-                    Part::Text { session_id: s, .. } => s.as_deref(),
-                    Part::Tool { session_id: s, .. } => s.as_deref(),
-                    Part::StepStart { session_id: s, .. } => s.as_deref(),
-                    Part::StepFinish { session_id: s, .. } => s.as_deref(),
-                    Part::Other => None,
-                };
-                if let Some(sid) = part_session_id
+                if let Some(sid) = part.session_id()
                     && sid != session_id
                 {
+                    // Sub-agent activity is forwarded for nested rendering but
+                    // kept out of this worker's own status and transcript.
+                    if let Some(subagent) = state.child_sessions.get(sid)
+                        && let Some(opencode_part) = part_to_opencode_part(part)
+                        && !state
+                            .accumulated_parts
+                            .is_repeat(part.message_id(), &opencode_part)
+                    {
+                        self.event_tx
+                            .send(ProcessEvent::OpenCodePartUpdated {
+                                agent_id: self.agent_id.clone(),
+                                worker_id: self.id,
+                                channel_id: self.channel_id.clone(),
+                                part: opencode_part,
+                                subagent: Some(subagent.clone()),
+                            })
+                            .ok();
+                    }
                     return EventAction::Continue;
                 }
 
                 // Emit OpenCodePartUpdated for the frontend live transcript
-                // and accumulate for fallback transcript persistence.
+                // and accumulate for fallback transcript persistence. Exact
+                // repeats, re-sent after a reconnect, are dropped entirely.
                 if let Some(opencode_part) = part_to_opencode_part(part) {
+                    if state
+                        .accumulated_parts
+                        .is_repeat(part.message_id(), &opencode_part)
+                    {
+                        return EventAction::Continue;
+                    }
                     let _ = self.event_tx.send(ProcessEvent::OpenCodePartUpdated {
                         agent_id: self.agent_id.clone(),
                         worker_id: self.id,
+                        channel_id: self.channel_id.clone(),
                         part: opencode_part.clone(),
+                        subagent: None,
                     });
-                    state.accumulated_parts.push(opencode_part);
+                    state.accumulated_parts.upsert(opencode_part);
                 }
 
                 // Continue processing for status updates and state tracking
@@ -638,6 +1027,7 @@ impl OpenCodeWorker {
                         state.last_text = scrubbed;
                     }
                     Part::Tool {
+                        id: part_id,
                         tool,
                         state: tool_state,
                         ..
@@ -646,6 +1036,37 @@ impl OpenCodeWorker {
                         if let Some(tool_name) = tool
                             && let Some(tool_state) = tool_state
                         {
+                            if let Some(todos) = todos_from_tool_state(tool_name, tool_state) {
+                                self.send_todos(state, todos);
+                            }
+                            if let Some(mut change) =
+                                file_change_from_tool_state(tool_name, tool_state)
+                                && state.counted_tool_parts.insert(part_id.clone())
+                            {
+                                change.path = self.relative_path(&change.path);
+                                state.record_change(change);
+                            }
+                            if let Some(child_session_id) =
+                                subagent_session_id(tool_name, tool_state)
+                            {
+                                let title = match tool_state {
+                                    ToolState::Running { title, .. }
+                                    | ToolState::Completed { title, .. } => title.clone(),
+                                    _ => None,
+                                };
+                                let subagent = state
+                                    .child_sessions
+                                    .entry(child_session_id.clone())
+                                    .or_insert_with(|| OpenCodeSubagent {
+                                        session_id: child_session_id,
+                                        parent_part_id: None,
+                                        title: None,
+                                    });
+                                subagent.parent_part_id = Some(part_id.clone());
+                                if title.is_some() {
+                                    subagent.title = title;
+                                }
+                            }
                             match tool_state {
                                 ToolState::Running { title, input, .. } => {
                                     state.current_tool = Some(tool_name.clone());
@@ -694,6 +1115,31 @@ impl OpenCodeWorker {
                             }
                         }
                     }
+                    Part::Patch { files, .. } => {
+                        for file in files {
+                            state.touch_file(self.relative_path(file));
+                        }
+                    }
+                    // Sent once complete, for channels that show reasoning
+                    // collapsed.
+                    Part::Reasoning {
+                        id: part_id,
+                        text,
+                        time,
+                        ..
+                    } if time.as_ref().is_some_and(|span| span.end.is_some())
+                        && !text.trim().is_empty()
+                        && state.reported_reasoning.insert(part_id.clone()) =>
+                    {
+                        self.event_tx
+                            .send(ProcessEvent::WorkerText {
+                                agent_id: self.agent_id.clone(),
+                                worker_id: self.id,
+                                channel_id: self.channel_id.clone(),
+                                text: self.scrub_text(text),
+                            })
+                            .ok();
+                    }
                     _ => {}
                 }
                 EventAction::Continue
@@ -728,16 +1174,14 @@ impl OpenCodeWorker {
                 if event_session_id.as_deref() != Some(session_id) {
                     return EventAction::Continue;
                 }
-                let message = error
-                    .as_ref()
-                    .and_then(|e| e.get("message").and_then(|v| v.as_str()))
-                    .unwrap_or("unknown error")
-                    .to_string();
-                EventAction::Error(message)
+                self.send_busy(false);
+                EventAction::Error(session_error_message(error.as_ref()))
             }
 
             SseEvent::PermissionAsked(permission) => {
-                if permission.session_id != session_id {
+                if permission.session_id != session_id
+                    && !state.child_sessions.contains_key(&permission.session_id)
+                {
                     return EventAction::Continue;
                 }
 
@@ -749,10 +1193,16 @@ impl OpenCodeWorker {
                     "OpenCode requesting permission"
                 );
 
+                // Without a channel to ask, or with no time to wait, allow it
+                // once. Otherwise the channel posts it for an admin to allow
+                // or deny, and one nobody answers is rejected.
+                let awaiting_approval =
+                    self.channel_id.is_some() && !self.question_timeout.is_zero();
                 let _ = self.event_tx.send(ProcessEvent::WorkerPermission {
                     agent_id: self.agent_id.clone(),
                     worker_id: self.id,
                     channel_id: self.channel_id.clone(),
+                    session_id: permission.session_id.clone(),
                     permission_id: permission.id.clone(),
                     description: format!(
                         "{}: {}",
@@ -760,27 +1210,41 @@ impl OpenCodeWorker {
                         permission.patterns.join(", ")
                     ),
                     patterns: permission.patterns.clone(),
+                    awaiting_approval,
                 });
 
-                // Auto-allow (OPENCODE_CONFIG_CONTENT should prevent most prompts)
-                let guard = server.lock().await;
-                if let Err(error) = guard
-                    .reply_permission(&permission.id, PermissionReply::Once)
-                    .await
-                {
-                    tracing::warn!(
-                        worker_id = %self.id,
-                        permission_id = %permission.id,
-                        %error,
-                        "failed to auto-reply permission"
-                    );
+                if awaiting_approval {
+                    self.send_status("waiting for approval of a permission request");
+                    state.pending_permissions.push(PendingPermission {
+                        request: permission.clone(),
+                        deadline: tokio::time::Instant::now() + self.question_timeout,
+                    });
+                } else {
+                    self.reply_permission(server, permission, PermissionReply::Once)
+                        .await;
                 }
 
                 EventAction::Continue
             }
 
+            SseEvent::PermissionReplied { request_id, .. } => {
+                let waiting = state.pending_permissions.len();
+                state
+                    .pending_permissions
+                    .retain(|pending| pending.request.id != *request_id);
+                if state.pending_permissions.len() < waiting
+                    && state.pending_permissions.is_empty()
+                    && state.pending_question.is_none()
+                {
+                    self.send_status("working");
+                }
+                EventAction::Continue
+            }
+
             SseEvent::QuestionAsked(question) => {
-                if question.session_id != session_id {
+                if question.session_id != session_id
+                    && !state.child_sessions.contains_key(&question.session_id)
+                {
                     return EventAction::Continue;
                 }
 
@@ -807,35 +1271,101 @@ impl OpenCodeWorker {
                         .collect(),
                 });
 
-                // Auto-select first option
-                let answers: Vec<QuestionAnswer> = question
-                    .questions
-                    .iter()
-                    .map(|q| {
-                        if let Some(first_option) = q.options.first() {
-                            QuestionAnswer {
-                                label: first_option.label.clone(),
-                                description: first_option.description.clone(),
-                            }
-                        } else {
-                            QuestionAnswer {
-                                label: "continue".to_string(),
-                                description: None,
-                            }
-                        }
-                    })
-                    .collect();
+                // Without a channel to ask, or with no time to wait, pick the
+                // first option. Otherwise the channel posts the question and
+                // replies with the user's answer.
+                if self.channel_id.is_none() || self.question_timeout.is_zero() {
+                    self.answer_by_default(server, question).await;
+                } else {
+                    // A newer question supersedes one still waiting.
+                    if let Some(pending) = state.pending_question.take() {
+                        self.answer_by_default(server, &pending.request).await;
+                    }
+                    self.send_status("waiting for an answer to its question");
+                    state.pending_question = Some(PendingQuestion {
+                        request: question.clone(),
+                        deadline: tokio::time::Instant::now() + self.question_timeout,
+                    });
+                }
 
-                let guard = server.lock().await;
-                if let Err(error) = guard.reply_question(&question.id, answers).await {
-                    tracing::warn!(
-                        worker_id = %self.id,
-                        question_id = %question.id,
-                        %error,
-                        "failed to auto-reply question"
-                    );
+                EventAction::Continue
+            }
+
+            SseEvent::QuestionReplied { request_id, .. } => {
+                if state
+                    .pending_question
+                    .as_ref()
+                    .is_some_and(|pending| pending.request.id == *request_id)
+                {
+                    state.pending_question = None;
+                    self.send_status("working");
+                }
+                EventAction::Continue
+            }
+
+            SseEvent::SessionUpdated(session) => {
+                if session.id == session_id
+                    && let Some(title) = &session.title
+                    && !is_placeholder_title(title)
+                    && state.session_title.as_ref() != Some(title)
+                {
+                    state.session_title = Some(title.clone());
+                    self.event_tx
+                        .send(ProcessEvent::WorkerSessionTitle {
+                            agent_id: self.agent_id.clone(),
+                            worker_id: self.id,
+                            channel_id: self.channel_id.clone(),
+                            title: title.clone(),
+                        })
+                        .ok();
                 }
 
+                // Track sub-agents of this session, and their own sub-agents.
+                if let Some(parent_id) = &session.parent_id
+                    && (parent_id == session_id || state.child_sessions.contains_key(parent_id))
+                {
+                    let subagent = state
+                        .child_sessions
+                        .entry(session.id.clone())
+                        .or_insert_with(|| OpenCodeSubagent {
+                            session_id: session.id.clone(),
+                            parent_part_id: None,
+                            title: None,
+                        });
+                    if subagent.title.is_none() {
+                        subagent.title = session.title.clone();
+                    }
+                }
+                EventAction::Continue
+            }
+
+            SseEvent::TodoUpdated {
+                session_id: event_session_id,
+                todos,
+            } => {
+                if event_session_id == session_id {
+                    self.send_todos(state, todos.clone());
+                }
+                EventAction::Continue
+            }
+
+            // File events aren't scoped to a session. They're only read while
+            // this worker's prompt is running, which is as close as the
+            // server lets us attribute them.
+            SseEvent::FileWatcherUpdated {
+                file,
+                event: event @ (FileWatcherEvent::Add | FileWatcherEvent::Unlink),
+            } => {
+                let kind = if *event == FileWatcherEvent::Add {
+                    FileChangeKind::Created
+                } else {
+                    FileChangeKind::Deleted
+                };
+                state.record_change(FileChange::new(self.relative_path(file), kind));
+                EventAction::Continue
+            }
+            SseEvent::FileEdited { file } | SseEvent::FileWatcherUpdated { file, .. } => {
+                state.touch_file(self.relative_path(file));
                 EventAction::Continue
             }
 
@@ -852,11 +1382,37 @@ impl OpenCodeWorker {
                     } => {
                         let description = message.as_deref().unwrap_or("rate limited");
                         self.send_status(&format!("retry attempt {attempt}: {description}"));
+                        self.send_busy(true);
                     }
                     SessionStatusPayload::Busy => {
                         self.send_status("working");
+                        self.send_busy(true);
                     }
-                    SessionStatusPayload::Idle => {}
+                    SessionStatusPayload::Idle => {
+                        self.send_busy(false);
+                    }
+                }
+                EventAction::Continue
+            }
+
+            SseEvent::ServerLog(entry) => {
+                if entry.level >= ServerLogLevel::Warn {
+                    tracing::warn!(
+                        worker_id = %self.id,
+                        level = %entry.level,
+                        service = entry.service.as_deref().unwrap_or("unknown"),
+                        message = %entry.message,
+                        "OpenCode server reported a problem"
+                    );
+                }
+                if entry.level >= ServerLogLevel::Info {
+                    self.event_tx
+                        .send(ProcessEvent::OpenCodeServerLog {
+                            agent_id: self.agent_id.clone(),
+                            worker_id: self.id,
+                            entry: entry.clone(),
+                        })
+                        .ok();
                 }
                 EventAction::Continue
             }
@@ -865,7 +1421,222 @@ impl OpenCodeWorker {
         }
     }
 
+    /// Take the author of the prompt about to be sent, and prefix `text`
+    /// with their name.
+    fn attribute_prompt(&self, text: String) -> (String, Option<Contributor>) {
+        let contributor = self
+            .contributor
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        match contributor {
+            Some(contributor) => (
+                format!("[{}] {text}", contributor.display_name),
+                Some(contributor),
+            ),
+            None => (text, None),
+        }
+    }
+
+    /// Queue the token usage of the prompt that just ended, attributed to
+    /// the user who sent it.
+    async fn record_prompt_usage(
+        &self,
+        event_state: &mut EventState,
+        contributor: Option<&Contributor>,
+    ) {
+        let prompt_usage = std::mem::take(&mut event_state.prompt_usage);
+        let Some(write_behind) = &self.write_behind else {
+            return;
+        };
+        let mut accumulator = crate::llm::usage::UsageAccumulator::new();
+        for usage in prompt_usage.values() {
+            accumulator.add(usage.tokens, &usage.model, &usage.provider, usage.cost);
+        }
+        let record = accumulator
+            .to_record(&self.agent_id, "worker", self.channel_id.as_deref())
+            .map(|record| {
+                record.with_user(contributor.map(|contributor| contributor.user_id.clone()))
+            });
+        if let Some(record) = record {
+            write_behind.write_usage(record).await;
+        }
+    }
+
+    /// Build the request body for one prompt to the session.
+    fn prompt_request(&self, text: String, agent: Option<String>) -> SendPromptRequest {
+        SendPromptRequest {
+            parts: vec![PartInput::Text {
+                text,
+                synthetic: None,
+            }],
+            system: self.system_prompt.clone(),
+            model: self.model.as_ref().and_then(|m| parse_model_param(m)),
+            agent,
+        }
+    }
+
+    /// Pick the OpenCode agent for a follow-up. Approving the pending plan
+    /// switches the session from [`PLAN_AGENT`] to [`BUILD_AGENT`].
+    fn follow_up_agent(&self, awaiting_approval: &mut bool, follow_up: &str) -> Option<String> {
+        if *awaiting_approval && follow_up == PLAN_APPROVAL_PROMPT {
+            *awaiting_approval = false;
+        }
+        if *awaiting_approval {
+            Some(PLAN_AGENT.to_string())
+        } else if self.plan_approval {
+            Some(BUILD_AGENT.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Dry run: build every prompt exactly as [`run`](Self::run) would and
+    /// post it to the channel instead of sending it. Interactive workers stay
+    /// idle and echo each follow-up the same way.
+    async fn run_dry(mut self) -> OpenCodeWorkerResult {
+        let mut awaiting_approval = self.plan_approval;
+        let (task, _) = self.attribute_prompt(self.task.clone());
+        let request = self.prompt_request(task, self.plan_approval.then(|| PLAN_AGENT.to_string()));
+        self.send_dry_run(&request);
+        let mut prompts = 1;
+
+        if let Some(mut input_rx) = self.input_rx.take() {
+            self.send_status("dry run — waiting for follow-up");
+            self.send_idle();
+            while let Some(follow_up) = input_rx.recv().await {
+                let agent = self.follow_up_agent(&mut awaiting_approval, &follow_up);
+                let (follow_up, _) = self.attribute_prompt(follow_up);
+                let request = self.prompt_request(follow_up, agent);
+                self.send_dry_run(&request);
+                prompts += 1;
+                self.send_status("dry run — waiting for follow-up");
+                self.send_idle();
+            }
+        }
+
+        self.send_status("completed");
+        OpenCodeWorkerResult {
+            session_id: String::new(),
+            result_text: format!(
+                "Dry run: {prompts} prompt(s) were posted to the channel and none were sent to OpenCode."
+            ),
+            transcript: Vec::new(),
+            tool_calls: 0,
+        }
+    }
+
+    /// Post the request body a dry run would have sent.
+    fn send_dry_run(&self, request: &SendPromptRequest) {
+        let payload = match serde_json::to_string_pretty(request) {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::warn!(%error, worker_id = %self.id, "failed to serialize dry-run payload");
+                return;
+            }
+        };
+        let scrubbed = crate::secrets::scrub::scrub_leaks(&self.scrub_text(&payload));
+        self.event_tx
+            .send(ProcessEvent::WorkerDryRun {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                payload: scrubbed,
+            })
+            .ok();
+    }
+
+    /// Send a raw event to the channel's debug mirror if it concerns this
+    /// session or one of its sub-agents. Events from other sessions on the
+    /// same server, and unscoped ones, are left out.
+    fn mirror_raw_event(&self, data: &str, session_id: &str, state: &EventState) {
+        let concerns_session = data.contains(session_id)
+            || state
+                .child_sessions
+                .keys()
+                .any(|child| data.contains(child.as_str()));
+        if !concerns_session {
+            return;
+        }
+        let scrubbed = crate::secrets::scrub::scrub_leaks(&self.scrub_text(data));
+        self.event_tx
+            .send(ProcessEvent::WorkerRawEvent {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                data: scrubbed,
+            })
+            .ok();
+    }
+
     /// Send a status update via the process event bus.
+    /// Answer every question in `question` with its first option.
+    async fn answer_by_default(
+        &self,
+        server: &Arc<Mutex<crate::opencode::server::OpenCodeServer>>,
+        question: &QuestionRequest,
+    ) {
+        let guard = server.lock().await;
+        if let Err(error) = guard
+            .reply_question(&question.id, default_answers(question))
+            .await
+        {
+            tracing::warn!(
+                worker_id = %self.id,
+                question_id = %question.id,
+                %error,
+                "failed to auto-reply question"
+            );
+        }
+    }
+
+    /// Answer whatever has waited on chat past its deadline: questions get
+    /// their first option and permission requests are rejected.
+    async fn expire_chat_requests(
+        &self,
+        server: &Arc<Mutex<crate::opencode::server::OpenCodeServer>>,
+        state: &mut EventState,
+    ) {
+        let now = tokio::time::Instant::now();
+        if let Some(pending) = state
+            .pending_question
+            .take_if(|pending| pending.deadline <= now)
+        {
+            self.send_status("no answer to the question, picking the first option");
+            self.answer_by_default(server, &pending.request).await;
+        }
+        let (expired, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut state.pending_permissions)
+            .into_iter()
+            .partition(|pending| pending.deadline <= now);
+        state.pending_permissions = waiting;
+        for pending in expired {
+            self.send_status("no approval for the permission request, rejecting it");
+            self.reply_permission(server, &pending.request, PermissionReply::Reject)
+                .await;
+        }
+    }
+
+    async fn reply_permission(
+        &self,
+        server: &Arc<Mutex<crate::opencode::server::OpenCodeServer>>,
+        permission: &PermissionRequest,
+        reply: PermissionReply,
+    ) {
+        let guard = server.lock().await;
+        if let Err(error) = guard
+            .reply_permission(&permission.session_id, &permission.id, reply)
+            .await
+        {
+            tracing::warn!(
+                worker_id = %self.id,
+                permission_id = %permission.id,
+                ?reply,
+                %error,
+                "failed to reply to permission"
+            );
+        }
+    }
+
     fn send_status(&self, status: &str) {
         let _ = self.event_tx.send(ProcessEvent::WorkerStatus {
             agent_id: self.agent_id.clone(),
@@ -875,6 +1646,82 @@ impl OpenCodeWorker {
         });
     }
 
+    /// Send the session's todo list to the channel if it changed.
+    fn send_todos(&self, state: &mut EventState, todos: Vec<TodoItem>) {
+        if state.last_todos.as_ref() == Some(&todos) {
+            return;
+        }
+        state.last_todos = Some(todos.clone());
+        self.event_tx
+            .send(ProcessEvent::WorkerTodos {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                todos,
+            })
+            .ok();
+    }
+
+    /// Emit a result for the channel to relay: a plan awaiting approval, or
+    /// an ordinary initial/follow-up result.
+    fn send_result(&self, text: &str, is_plan: bool) {
+        let scrubbed = self.scrub_text(text);
+        let scrubbed = crate::secrets::scrub::scrub_leaks(&scrubbed);
+        let event = if is_plan {
+            ProcessEvent::WorkerPlanReady {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                plan: scrubbed,
+            }
+        } else {
+            ProcessEvent::WorkerInitialResult {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                result: scrubbed,
+            }
+        };
+        self.event_tx.send(event).ok();
+    }
+
+    /// `file` relative to the worker's directory, for display.
+    fn relative_path(&self, file: &str) -> String {
+        let path = std::path::Path::new(file);
+        path.strip_prefix(&self.directory)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    /// Report the files changed during the turn that just finished, if any.
+    fn send_files_changed(&self, state: &mut EventState) {
+        if state.turn_changes.is_empty() {
+            return;
+        }
+        self.event_tx
+            .send(ProcessEvent::WorkerFilesChanged {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                files: std::mem::take(&mut state.turn_changes),
+                session_total: state.session_files.len(),
+            })
+            .ok();
+    }
+
+    /// Report whether the session is working, for the channel's heartbeat.
+    fn send_busy(&self, busy: bool) {
+        self.event_tx
+            .send(ProcessEvent::WorkerBusy {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.id,
+                channel_id: self.channel_id.clone(),
+                busy,
+            })
+            .ok();
+    }
+
     /// Send an idle event to mark this worker as waiting for follow-up input.
     fn send_idle(&self) {
         let _ = self.event_tx.send(ProcessEvent::WorkerIdle {
@@ -888,36 +1735,25 @@ impl OpenCodeWorker {
     ///
     /// Called each time the worker goes idle so that if spacebot restarts
     /// while the worker is waiting for follow-up, the transcript survives.
-    /// Awaited directly so "idle implies persisted" — no out-of-order writes.
-    async fn persist_transcript_snapshot(&self, event_state: &EventState) {
-        let Some(pool) = &self.sqlite_pool else {
+    /// Flushed before returning so "idle implies persisted".
+    async fn persist_transcript_snapshot(&self, event_state: &mut EventState) {
+        let Some(write_behind) = &self.write_behind else {
             return;
         };
         if event_state.accumulated_parts.is_empty() {
             return;
         }
 
-        let steps = crate::conversation::worker_transcript::convert_opencode_parts(
-            &event_state.accumulated_parts,
-        );
+        let steps = event_state.accumulated_parts.steps();
         if steps.is_empty() {
             return;
         }
 
         let blob = crate::conversation::worker_transcript::serialize_steps(&steps);
-        let tool_calls = event_state.tool_calls;
-        let worker_id = self.id.to_string();
-
-        if let Err(error) =
-            sqlx::query("UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?")
-                .bind(&blob)
-                .bind(tool_calls)
-                .bind(&worker_id)
-                .execute(pool)
-                .await
-        {
-            tracing::warn!(%error, worker_id, "failed to persist transcript snapshot");
-        }
+        write_behind
+            .write_transcript(self.id.to_string(), blob, event_state.tool_calls)
+            .await;
+        write_behind.flush().await;
     }
 }
 
@@ -997,48 +1833,233 @@ enum EventAction {
     Error(String),
 }
 
+/// What a prompt's event loop returns for `action`, or `None` to keep going.
+fn event_result(action: EventAction, event_state: &EventState) -> Option<anyhow::Result<String>> {
+    match action {
+        EventAction::Continue => None,
+        EventAction::Complete => Some(Ok(event_state.last_text.clone())),
+        EventAction::Error(message) if crate::llm::routing::is_context_overflow_error(&message) => {
+            Some(Err(ContextOverflow(message).into()))
+        }
+        EventAction::Error(message) if crate::llm::routing::is_retriable_error(&message) => {
+            Some(Err(ProviderUnavailable(message).into()))
+        }
+        EventAction::Error(message) => {
+            Some(Err(anyhow::anyhow!("OpenCode session error: {message}")))
+        }
+    }
+}
+
+/// A `session.error` reporting that the conversation exceeded the model's
+/// context window. Recovered from by compacting the session.
+#[derive(Debug, thiserror::Error)]
+#[error("OpenCode session error: {0}")]
+struct ContextOverflow(String);
+
+/// A `session.error` from a rate-limited or unavailable provider. Recovered
+/// from by retrying on the next fallback model.
+#[derive(Debug, thiserror::Error)]
+#[error("OpenCode session error: {0}")]
+struct ProviderUnavailable(String);
+
+/// Note which fallback model answered, if the requested one didn't.
+fn with_model_footer(text: String, answered_by: Option<&str>) -> String {
+    match answered_by {
+        Some(model) if !text.is_empty() => format!("{text}\n\n_Answered by `{model}`_"),
+        _ => text,
+    }
+}
+
+/// Message of a `session.error` payload. Provider errors are nested as
+/// `{ name, data: { message } }`; other errors carry `message` directly.
+fn session_error_message(error: Option<&serde_json::Value>) -> String {
+    error
+        .and_then(|error| {
+            error
+                .get("message")
+                .or_else(|| error.pointer("/data/message"))
+        })
+        .and_then(|message| message.as_str())
+        .unwrap_or("unknown error")
+        .to_string()
+}
+
 /// Parse an SSE event from a buffer. Parses the `{ type, properties }` envelope
 /// and converts to our `SseEvent` enum. Returns None if no complete event is available.
-fn extract_sse_event(buffer: &mut String) -> Option<SseEvent> {
+pub(crate) fn extract_sse_event(buffer: &mut String) -> Option<SseEvent> {
+    extract_sse_event_with_id(buffer, &mut None)
+}
+
+/// Like [`extract_sse_event`], also recording the latest `id:` field into
+/// `last_event_id`. As in the SSE spec, the ID carries over to later blocks
+/// that have none, and blocks without data can still set it.
+pub(super) fn extract_sse_event_with_id(
+    buffer: &mut String,
+    last_event_id: &mut Option<String>,
+) -> Option<SseEvent> {
+    extract_sse_event_with_data(buffer, last_event_id, false).map(|(event, _)| event)
+}
+
+/// Like [`extract_sse_event_with_id`], also returning the event's raw
+/// `data:` JSON when `keep_data` is set.
+pub(super) fn extract_sse_event_with_data(
+    buffer: &mut String,
+    last_event_id: &mut Option<String>,
+    keep_data: bool,
+) -> Option<(SseEvent, Option<String>)> {
     // SSE format: lines starting with "data: " followed by JSON, terminated by
-    // a blank line. We may also see "event:" and "id:" lines which we ignore.
+    // a blank line. We may also see "event:" lines, which we ignore.
     loop {
-        let double_newline = buffer.find("\n\n")?;
-        let block = buffer[..double_newline].to_string();
-        *buffer = buffer[double_newline + 2..].to_string();
-
-        // Extract all data lines from the block
-        let mut data_parts = Vec::new();
-        for line in block.lines() {
-            if let Some(data) = line.strip_prefix("data: ") {
-                data_parts.push(data);
-            } else if let Some(data) = line.strip_prefix("data:") {
-                data_parts.push(data);
-            }
+        let Some(double_newline) = buffer.find("\n\n") else {
+            discard_oversized_block(buffer);
+            return None;
+        };
+        let block = &buffer[..double_newline];
+        if let Some(id) = sse_block_id(block) {
+            *last_event_id = Some(id.to_string());
         }
-
-        if data_parts.is_empty() {
-            continue;
+        let event = parse_sse_block(block).map(|event| {
+            let data = keep_data
+                .then(|| sse_block_data(block).map(std::borrow::Cow::into_owned))
+                .flatten();
+            (event, data)
+        });
+        buffer.drain(..double_newline + 2);
+        if event.is_some() {
+            return event;
         }
+    }
+}
+
+/// Drop an unterminated block once it passes [`MAX_SSE_BLOCK_BYTES`], so a
+/// stream that never ends its block can't grow the buffer without bound.
+/// The rest of that block is skipped when its blank line arrives, because it
+/// doesn't start with a `data:` line.
+fn discard_oversized_block(buffer: &mut String) {
+    if buffer.len() > MAX_SSE_BLOCK_BYTES {
+        tracing::warn!(
+            bytes = buffer.len(),
+            "dropping unterminated OpenCode SSE block over the size limit"
+        );
+        buffer.clear();
+    }
+}
+
+/// The `id:` field of one SSE block, if it has one. IDs containing NUL are
+/// ignored, per the spec.
+fn sse_block_id(block: &str) -> Option<&str> {
+    block
+        .lines()
+        .filter_map(|line| line.strip_prefix("id:"))
+        .map(|id| id.strip_prefix(' ').unwrap_or(id))
+        .filter(|id| !id.contains('\0'))
+        .last()
+}
+
+/// The `data:` payload of one SSE block. A block with a single `data:`
+/// line, which is how OpenCode sends every event, is borrowed without
+/// copying.
+fn sse_block_data(block: &str) -> Option<std::borrow::Cow<'_, str>> {
+    let mut data_lines = block.lines().filter_map(|line| {
+        line.strip_prefix("data: ")
+            .or_else(|| line.strip_prefix("data:"))
+    });
+    let first_line = data_lines.next()?;
+    Some(match data_lines.next() {
+        None => first_line.into(),
+        Some(second_line) => [first_line, second_line]
+            .into_iter()
+            .chain(data_lines)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .into(),
+    })
+}
+
+/// Parse one SSE block.
+fn parse_sse_block(block: &str) -> Option<SseEvent> {
+    let json_str = sse_block_data(block)?;
+    let json_str = json_str.as_ref();
+    if json_str.is_empty() {
+        return None;
+    }
 
-        let json_str = data_parts.join("\n");
-        if json_str.is_empty() {
-            continue;
+    // Parse the envelope first, then convert to our event type
+    match serde_json::from_str::<SseEventEnvelopeRef>(json_str) {
+        Ok(envelope) => Some(SseEvent::from_envelope_ref(envelope)),
+        Err(error) => {
+            crate::dead_letters::DeadLetterQueue::global().record("opencode", &error, json_str);
+            None
         }
+    }
+}
 
-        // Parse the envelope first, then convert to our event type
-        match serde_json::from_str::<SseEventEnvelope>(&json_str) {
-            Ok(envelope) => return Some(SseEvent::from_envelope(envelope)),
-            Err(error) => {
-                tracing::trace!(
-                    %error,
-                    json = %json_str,
-                    "failed to parse SSE event envelope, skipping"
-                );
-                continue;
+/// The current turn of a session's messages, from the latest user message
+/// on, and the event that ended the turn if it finished or failed.
+fn current_turn(
+    session_id: &str,
+    mut messages: Vec<serde_json::Value>,
+) -> (Vec<serde_json::Value>, Option<SseEvent>) {
+    let role = |message: &serde_json::Value| {
+        message
+            .pointer("/info/role")
+            .and_then(|role| role.as_str())
+            .map(String::from)
+    };
+    let start = messages
+        .iter()
+        .rposition(|message| role(message).as_deref() == Some("user"))
+        .unwrap_or(0);
+    let mut messages = messages.split_off(start);
+
+    let ending = match messages.last_mut() {
+        Some(last) if role(last).as_deref() == Some("assistant") => {
+            match last.pointer_mut("/info/error").map(serde_json::Value::take) {
+                Some(error) if !error.is_null() => Some(SseEvent::SessionError {
+                    session_id: Some(session_id.to_string()),
+                    error: Some(error),
+                }),
+                _ if last
+                    .pointer("/info/time/completed")
+                    .is_some_and(|completed| !completed.is_null()) =>
+                {
+                    Some(SseEvent::SessionIdle {
+                        session_id: session_id.to_string(),
+                    })
+                }
+                _ => None,
             }
         }
+        _ => None,
+    };
+    (messages, ending)
+}
+
+/// The events that would have delivered `messages`, as returned by the
+/// messages API: a `message.updated` for each message, then a
+/// `message.part.updated` for each of its parts.
+pub(super) fn message_events(messages: Vec<serde_json::Value>) -> Vec<SseEvent> {
+    let mut events = Vec::new();
+    for mut message in messages {
+        if let Some(info) = message
+            .get_mut("info")
+            .map(serde_json::Value::take)
+            .and_then(|info| serde_json::from_value::<MessageInfo>(info).ok())
+        {
+            events.push(SseEvent::MessageUpdated { info: Some(info) });
+        }
+        let parts = match message.get_mut("parts").map(serde_json::Value::take) {
+            Some(serde_json::Value::Array(parts)) => parts,
+            _ => continue,
+        };
+        events.extend(
+            parts
+                .into_iter()
+                .filter_map(|part| serde_json::from_value::<Part>(part).ok())
+                .map(|part| SseEvent::MessagePartUpdated { part, delta: None }),
+        );
     }
+    events
 }
 
 /// Parse a model string like "anthropic/claude-sonnet-4" into a ModelParam.
@@ -1049,3 +2070,263 @@ fn parse_model_param(model: &str) -> Option<ModelParam> {
         model_id: model_id.to_string(),
     })
 }
+
+/// Whether `title` is the timestamp OpenCode gives a session before its
+/// summarizer names it.
+fn is_placeholder_title(title: &str) -> bool {
+    let title = title.trim();
+    title.is_empty() || title.starts_with("New session - ") || title.starts_with("Child session - ")
+}
+
+/// The first option of each question, or `continue` for a question without
+/// options.
+fn default_answers(question: &QuestionRequest) -> Vec<QuestionAnswer> {
+    question
+        .questions
+        .iter()
+        .map(|info| match info.options.first() {
+            Some(first_option) => QuestionAnswer {
+                label: first_option.label.clone(),
+                description: first_option.description.clone(),
+            },
+            None => QuestionAnswer {
+                label: "continue".to_string(),
+                description: None,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn dry_run_posts_the_prompt_without_starting_a_server() {
+        let (event_tx, mut event_rx) = broadcast::channel(16);
+        let server_pool = Arc::new(OpenCodeServerPool::new(
+            "/nonexistent/opencode",
+            crate::opencode::OpenCodePermissions::default(),
+            1,
+            Vec::new(),
+        ));
+        let worker = OpenCodeWorker::new(
+            None,
+            Arc::from("agent"),
+            "fix the flaky test",
+            PathBuf::from("/nonexistent/project"),
+            server_pool,
+            event_tx,
+        )
+        .with_system_prompt("## Channel Instructions\n\nBe brief.")
+        .with_model("anthropic/claude-sonnet-4")
+        .with_plan_approval()
+        .with_dry_run();
+
+        let result = worker.run().await.expect("dry run never fails");
+        assert!(result.session_id.is_empty());
+        assert!(result.transcript.is_empty());
+
+        let mut payload = None;
+        while let Ok(event) = event_rx.try_recv() {
+            if let ProcessEvent::WorkerDryRun { payload: body, .. } = event {
+                payload = Some(body);
+            }
+        }
+        let payload: serde_json::Value =
+            serde_json::from_str(&payload.expect("dry-run payload posted")).unwrap();
+        assert_eq!(payload["parts"][0]["text"], "fix the flaky test");
+        assert_eq!(payload["system"], "## Channel Instructions\n\nBe brief.");
+        assert_eq!(payload["agent"], PLAN_AGENT);
+        assert!(payload.get("model").is_some());
+    }
+
+    #[test]
+    fn prompts_carry_their_author_and_usage() {
+        let (event_tx, _event_rx) = broadcast::channel(16);
+        let server_pool = Arc::new(OpenCodeServerPool::new(
+            "/nonexistent/opencode",
+            crate::opencode::OpenCodePermissions::default(),
+            1,
+            Vec::new(),
+        ));
+        let worker = OpenCodeWorker::new(
+            None,
+            Arc::from("agent"),
+            "fix the flaky test",
+            PathBuf::from("/nonexistent/project"),
+            server_pool,
+            event_tx,
+        );
+        *worker.contributor.lock().unwrap() = Some(Contributor {
+            user_id: "discord:42".into(),
+            display_name: "alice".into(),
+        });
+
+        let (text, contributor) = worker.attribute_prompt("add a test".into());
+        assert_eq!(text, "[alice] add a test");
+        assert_eq!(contributor.unwrap().user_id, "discord:42");
+        // The slot is emptied, so a later bot-queued prompt isn't attributed.
+        assert_eq!(
+            worker.attribute_prompt("/git status".into()),
+            ("/git status".to_string(), None)
+        );
+
+        let info: MessageInfo = serde_json::from_value(serde_json::json!({
+            "id": "msg_1",
+            "role": "assistant",
+            "sessionID": "ses_1",
+            "modelID": "claude-sonnet-4",
+            "providerID": "anthropic",
+            "cost": 0.012,
+            "tokens": { "input": 1200, "output": 300, "reasoning": 0, "cache": { "read": 800, "write": 0 } },
+        }))
+        .unwrap();
+        let usage = MessageUsage::from_info(&info).unwrap();
+        assert_eq!(usage.tokens.input_tokens, 1200);
+        assert_eq!(usage.tokens.cache_read_tokens, 800);
+        assert_eq!(usage.model, "claude-sonnet-4");
+        assert_eq!(usage.provider, "anthropic");
+    }
+
+    #[test]
+    fn placeholder_session_titles_are_skipped() {
+        assert!(is_placeholder_title(
+            "New session - 2026-10-15T12:00:00.000Z"
+        ));
+        assert!(is_placeholder_title(
+            "Child session - 2026-10-15T12:00:00.000Z"
+        ));
+        assert!(is_placeholder_title("  "));
+        assert!(!is_placeholder_title("Fix flaky retry test"));
+    }
+
+    #[test]
+    fn reads_nested_session_error_messages() {
+        let overflow = serde_json::json!({
+            "name": "APIError",
+            "data": {
+                "message": "prompt is too long: 212044 tokens > 200000 maximum",
+                "statusCode": 400,
+            },
+        });
+        let message = session_error_message(Some(&overflow));
+        assert_eq!(
+            message,
+            "prompt is too long: 212044 tokens > 200000 maximum"
+        );
+        assert!(crate::llm::routing::is_context_overflow_error(&message));
+
+        let aborted = serde_json::json!({ "message": "aborted" });
+        assert_eq!(session_error_message(Some(&aborted)), "aborted");
+        assert_eq!(session_error_message(None), "unknown error");
+    }
+
+    #[test]
+    fn resync_replays_only_the_current_turn() {
+        let message = |id: &str, role: &str, info: serde_json::Value| {
+            let mut message = serde_json::json!({
+                "info": { "id": id, "role": role, "sessionID": "ses-1" },
+                "parts": [{ "type": "text", "id": format!("{id}-text"),
+                            "sessionID": "ses-1", "text": "hello" }],
+            });
+            if let (Some(target), serde_json::Value::Object(extra)) =
+                (message["info"].as_object_mut(), info)
+            {
+                target.extend(extra);
+            }
+            message
+        };
+        let history = vec![
+            message("msg-1", "user", serde_json::json!({})),
+            message(
+                "msg-2",
+                "assistant",
+                serde_json::json!({ "time": { "completed": 1 } }),
+            ),
+            message("msg-3", "user", serde_json::json!({})),
+            message(
+                "msg-4",
+                "assistant",
+                serde_json::json!({ "time": { "created": 2 } }),
+            ),
+        ];
+
+        let (turn, ending) = current_turn("ses-1", history.clone());
+        assert_eq!(turn.len(), 2);
+        assert!(ending.is_none());
+        assert_eq!(message_events(turn).len(), 4);
+
+        let mut finished = history.clone();
+        finished[3] = message(
+            "msg-4",
+            "assistant",
+            serde_json::json!({ "time": { "completed": 3 } }),
+        );
+        assert!(matches!(
+            current_turn("ses-1", finished).1,
+            Some(SseEvent::SessionIdle { .. })
+        ));
+
+        let mut failed = history;
+        failed[3] = message(
+            "msg-4",
+            "assistant",
+            serde_json::json!({ "error": { "name": "APIError", "data": { "message": "overloaded" } } }),
+        );
+        assert!(matches!(
+            current_turn("ses-1", failed).1,
+            Some(SseEvent::SessionError { error: Some(_), .. })
+        ));
+    }
+
+    #[test]
+    fn only_fallback_answers_are_annotated() {
+        assert!(crate::llm::routing::is_retriable_error(
+            "429 Too Many Requests: rate limit exceeded"
+        ));
+        assert_eq!(with_model_footer("done".into(), None), "done");
+        assert_eq!(
+            with_model_footer("done".into(), Some("openai/gpt-4.1")),
+            "done\n\n_Answered by `openai/gpt-4.1`_"
+        );
+        assert_eq!(with_model_footer(String::new(), Some("openai/gpt-4.1")), "");
+    }
+
+    #[test]
+    fn raw_event_data_is_kept_only_when_mirroring() {
+        let block = "id: 7\ndata: {\"type\":\"session.idle\",\ndata: \"properties\":{\"sessionID\":\"ses-1\"}}\n\n";
+        let mut last_event_id = None;
+
+        let mut buffer = block.to_string();
+        let (event, data) =
+            extract_sse_event_with_data(&mut buffer, &mut last_event_id, false).unwrap();
+        assert!(matches!(event, SseEvent::SessionIdle { .. }));
+        assert_eq!(data, None);
+
+        let mut buffer = block.to_string();
+        let (_, data) = extract_sse_event_with_data(&mut buffer, &mut last_event_id, true).unwrap();
+        assert_eq!(
+            data.as_deref(),
+            Some("{\"type\":\"session.idle\",\n\"properties\":{\"sessionID\":\"ses-1\"}}")
+        );
+        assert!(buffer.is_empty());
+        assert_eq!(last_event_id.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn unterminated_blocks_stop_growing_at_the_limit() {
+        let mut buffer = format!("data: {}", "x".repeat(MAX_SSE_BLOCK_BYTES));
+        assert!(extract_sse_event(&mut buffer).is_none());
+        assert!(buffer.is_empty());
+
+        buffer.push_str(
+            "xxx\n\ndata: {\"type\":\"session.idle\",\"properties\":{\"sessionID\":\"ses-1\"}}\n\n",
+        );
+        assert!(matches!(
+            extract_sse_event(&mut buffer),
+            Some(SseEvent::SessionIdle { .. })
+        ));
+        assert!(buffer.is_empty());
+    }
+}
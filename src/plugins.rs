@@ -0,0 +1,694 @@
+//! Plugins: chat commands and event hooks supplied by other crates.
+//!
+//! A [`Plugin`] adds slash commands to every channel and observes worker
+//! sessions finishing and tool calls failing. Register plugins on
+//! [`PluginRegistry::global`], or through
+//! [`SpacebotBuilder::plugin`](crate::SpacebotBuilder::plugin), before the
+//! runtime starts.
+//!
+//! Every plugin call runs in a task of its own under a timeout, so a plugin
+//! that panics or hangs fails only that call. A plugin that panics
+//! [`MAX_PANICS`] times is disabled for the rest of the process.
+//!
+//! The API is versioned by [`PLUGIN_API_VERSION`]. Plugins built against
+//! another version are rejected at registration.
+
+use crate::access::AccessTier;
+use crate::{
+    AgentId, BroadcastRecvResult, ChannelId, ProcessEvent, ProcessId, WorkerId,
+    classify_broadcast_recv_result,
+};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Version of the plugin API this build implements. Bumped on any breaking
+/// change to [`Plugin`] or the event types it receives.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Longest a command handler may run before the user gets an error reply.
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest an event hook may run before it is cancelled.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Panics after which a plugin is disabled.
+pub const MAX_PANICS: u32 = 3;
+
+/// Commands handled by the channel itself, which plugins cannot claim.
+/// `builtin_commands_match_the_channel` keeps this in step with the
+/// channel's dispatch.
+pub(crate) const BUILTIN_COMMANDS: &[&str] = &[
+    "active",
+    "admin",
+    "agent-id",
+    "approve",
+    "attach",
+    "audit",
+    "collapse",
+    "compact",
+    "debug",
+    "delivery",
+    "detach",
+    "digest",
+    "driver",
+    "dryrun",
+    "git",
+    "help",
+    "instructions",
+    "locale",
+    "mention-only",
+    "model",
+    "observe",
+    "oc",
+    "pipeline",
+    "plan",
+    "project",
+    "quiet",
+    "rebind",
+    "reject",
+    "run",
+    "schedule",
+    "search",
+    "stats",
+    "status",
+    "tasks",
+    "template",
+    "templates",
+    "timezone",
+    "today",
+];
+
+/// Tool results with this prefix are failures, as `SpacebotHook` classifies
+/// them.
+const TOOL_ERROR_PREFIX: &str = "Toolset error:";
+
+static PLUGINS: LazyLock<PluginRegistry> = LazyLock::new(PluginRegistry::new);
+
+/// A chat command a plugin handles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginCommand {
+    /// Command name without the leading slash.
+    pub name: String,
+    /// One-line description shown by `/help`.
+    pub description: String,
+}
+
+impl PluginCommand {
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// A chat command sent to a plugin.
+#[derive(Debug, Clone)]
+pub struct CommandInvocation {
+    pub agent_id: AgentId,
+    pub conversation_id: String,
+    /// Platform the message came from, e.g. `discord`.
+    pub source: String,
+    pub sender_id: String,
+    /// The sender's access tier. Plugins enforce their own requirements.
+    pub access_tier: AccessTier,
+    /// Command name without the leading slash.
+    pub command: String,
+    /// Everything after the command name, trimmed.
+    pub arguments: String,
+}
+
+/// A worker session finished, successfully or not.
+#[derive(Debug, Clone)]
+pub struct SessionComplete {
+    pub agent_id: AgentId,
+    pub worker_id: WorkerId,
+    pub channel_id: Option<ChannelId>,
+    pub result: String,
+    pub success: bool,
+}
+
+/// A tool call returned an error.
+#[derive(Debug, Clone)]
+pub struct ToolError {
+    pub agent_id: AgentId,
+    pub process_id: ProcessId,
+    pub channel_id: Option<ChannelId>,
+    pub tool_name: String,
+    pub error: String,
+}
+
+/// Static trait for plugins. Every method but [`name`](Plugin::name) has a
+/// default, so a plugin implements only what it uses.
+pub trait Plugin: Send + Sync + 'static {
+    /// Unique name, used in logs and registration errors.
+    fn name(&self) -> &str;
+
+    /// Plugin API version the plugin was built against.
+    fn api_version(&self) -> u32 {
+        PLUGIN_API_VERSION
+    }
+
+    /// Chat commands routed to [`handle_command`](Plugin::handle_command).
+    fn commands(&self) -> Vec<PluginCommand> {
+        Vec::new()
+    }
+
+    /// Handle one of this plugin's commands and return the reply text.
+    /// Errors are shown to the user.
+    fn handle_command(
+        &self,
+        invocation: CommandInvocation,
+    ) -> impl Future<Output = anyhow::Result<String>> + Send {
+        async move { anyhow::bail!("/{} is not implemented", invocation.command) }
+    }
+
+    /// Called when a worker session completes.
+    fn on_session_complete(&self, _event: SessionComplete) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
+    /// Called when a tool call in any process fails.
+    fn on_tool_error(&self, _event: ToolError) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+/// Dynamic trait for runtime polymorphism.
+/// Use this when you need `Arc<dyn PluginDyn>` for storing different plugins.
+pub trait PluginDyn: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    fn api_version(&self) -> u32;
+
+    fn commands(&self) -> Vec<PluginCommand>;
+
+    fn handle_command<'a>(
+        &'a self,
+        invocation: CommandInvocation,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>>;
+
+    fn on_session_complete<'a>(
+        &'a self,
+        event: SessionComplete,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+    fn on_tool_error<'a>(
+        &'a self,
+        event: ToolError,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+}
+
+/// Blanket implementation: any type implementing Plugin automatically implements PluginDyn.
+impl<T: Plugin> PluginDyn for T {
+    fn name(&self) -> &str {
+        Plugin::name(self)
+    }
+
+    fn api_version(&self) -> u32 {
+        Plugin::api_version(self)
+    }
+
+    fn commands(&self) -> Vec<PluginCommand> {
+        Plugin::commands(self)
+    }
+
+    fn handle_command<'a>(
+        &'a self,
+        invocation: CommandInvocation,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+        Box::pin(Plugin::handle_command(self, invocation))
+    }
+
+    fn on_session_complete<'a>(
+        &'a self,
+        event: SessionComplete,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(Plugin::on_session_complete(self, event))
+    }
+
+    fn on_tool_error<'a>(
+        &'a self,
+        event: ToolError,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(Plugin::on_tool_error(self, event))
+    }
+}
+
+/// Why a plugin was rejected at registration.
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    #[error(
+        "plugin {plugin} targets plugin API v{version}, this build supports v{PLUGIN_API_VERSION}"
+    )]
+    IncompatibleApi { plugin: String, version: u32 },
+
+    #[error("a plugin named {0} is already registered")]
+    DuplicatePlugin(String),
+
+    #[error("plugin {plugin} cannot register /{command}: already handled by {owner}")]
+    CommandTaken {
+        plugin: String,
+        command: String,
+        owner: String,
+    },
+}
+
+/// A registered plugin and its health.
+struct Entry {
+    name: String,
+    commands: Vec<PluginCommand>,
+    plugin: Arc<dyn PluginDyn>,
+    panics: AtomicU32,
+}
+
+impl Entry {
+    fn is_disabled(&self) -> bool {
+        self.panics.load(Ordering::Relaxed) >= MAX_PANICS
+    }
+
+    fn handles(&self, command: &str) -> bool {
+        self.commands.iter().any(|spec| spec.name == command)
+    }
+}
+
+/// Registered plugins. Access via [`PluginRegistry::global`].
+pub struct PluginRegistry {
+    entries: RwLock<Vec<Arc<Entry>>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// The process-wide registry the runtime dispatches to.
+    pub fn global() -> &'static Self {
+        &PLUGINS
+    }
+
+    /// Register `plugin`, checking its API version and that its commands
+    /// don't collide with built-in or other plugins' commands.
+    pub fn register(&self, plugin: impl Plugin) -> Result<(), PluginError> {
+        self.register_shared(Arc::new(plugin))
+    }
+
+    /// Like [`register`](Self::register), for a plugin the caller already
+    /// holds behind an `Arc`.
+    pub fn register_shared(&self, plugin: Arc<dyn PluginDyn>) -> Result<(), PluginError> {
+        let name = plugin.name().to_string();
+        let version = plugin.api_version();
+        if version != PLUGIN_API_VERSION {
+            return Err(PluginError::IncompatibleApi {
+                plugin: name,
+                version,
+            });
+        }
+
+        let commands = plugin.commands();
+        let mut entries = self
+            .entries
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if entries.iter().any(|entry| entry.name == name) {
+            return Err(PluginError::DuplicatePlugin(name));
+        }
+        for command in &commands {
+            let owner = if BUILTIN_COMMANDS.contains(&command.name.as_str()) {
+                Some("a built-in command".to_string())
+            } else {
+                entries
+                    .iter()
+                    .find(|entry| entry.handles(&command.name))
+                    .map(|entry| format!("plugin {}", entry.name))
+            };
+            if let Some(owner) = owner {
+                return Err(PluginError::CommandTaken {
+                    plugin: name,
+                    command: command.name.clone(),
+                    owner,
+                });
+            }
+        }
+
+        tracing::info!(plugin = %name, commands = commands.len(), "registered plugin");
+        entries.push(Arc::new(Entry {
+            name,
+            commands,
+            plugin,
+            panics: AtomicU32::new(0),
+        }));
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    /// Commands of every enabled plugin, for `/help`.
+    pub fn commands(&self) -> Vec<PluginCommand> {
+        self.entries()
+            .iter()
+            .filter(|entry| !entry.is_disabled())
+            .flat_map(|entry| entry.commands.iter().cloned())
+            .collect()
+    }
+
+    /// Whether an enabled plugin handles `/name`.
+    pub fn handles_command(&self, name: &str) -> bool {
+        self.entries()
+            .iter()
+            .any(|entry| !entry.is_disabled() && entry.handles(name))
+    }
+
+    /// Run the plugin command in `invocation` and return the reply. Returns
+    /// `None` when no enabled plugin handles the command.
+    pub async fn handle_command(&self, invocation: CommandInvocation) -> Option<String> {
+        let entry = self
+            .entries()
+            .into_iter()
+            .find(|entry| !entry.is_disabled() && entry.handles(&invocation.command))?;
+
+        let command = invocation.command.clone();
+        let plugin = entry.plugin.clone();
+        let outcome = run_isolated(&entry, "handle_command", COMMAND_TIMEOUT, async move {
+            plugin.handle_command(invocation).await
+        })
+        .await;
+        Some(match outcome {
+            Some(Ok(reply)) => reply,
+            Some(Err(error)) => format!("/{command} failed: {error}"),
+            None => format!("/{command} is unavailable right now."),
+        })
+    }
+
+    /// Hand `event` to the matching hook of every enabled plugin. Hooks run
+    /// in the background.
+    pub fn dispatch(&self, event: &ProcessEvent) {
+        let Some(hook) = HookEvent::from_process_event(event) else {
+            return;
+        };
+        for entry in self.entries() {
+            if entry.is_disabled() {
+                continue;
+            }
+            let hook = hook.clone();
+            tokio::spawn(async move {
+                let plugin = entry.plugin.clone();
+                let name = hook.name();
+                run_isolated(&entry, name, HOOK_TIMEOUT, async move {
+                    match hook {
+                        HookEvent::SessionComplete(event) => {
+                            plugin.on_session_complete(event).await;
+                        }
+                        HookEvent::ToolError(event) => plugin.on_tool_error(event).await,
+                    }
+                })
+                .await;
+            });
+        }
+    }
+
+    fn entries(&self) -> Vec<Arc<Entry>> {
+        self.entries
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Default for PluginRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum HookEvent {
+    SessionComplete(SessionComplete),
+    ToolError(ToolError),
+}
+
+impl HookEvent {
+    fn from_process_event(event: &ProcessEvent) -> Option<Self> {
+        match event {
+            ProcessEvent::WorkerComplete {
+                agent_id,
+                worker_id,
+                channel_id,
+                result,
+                success,
+                ..
+            } => Some(Self::SessionComplete(SessionComplete {
+                agent_id: agent_id.clone(),
+                worker_id: *worker_id,
+                channel_id: channel_id.clone(),
+                result: result.clone(),
+                success: *success,
+            })),
+            ProcessEvent::ToolCompleted {
+                agent_id,
+                process_id,
+                channel_id,
+                tool_name,
+                result,
+                ..
+            } if result.starts_with(TOOL_ERROR_PREFIX) => Some(Self::ToolError(ToolError {
+                agent_id: agent_id.clone(),
+                process_id: process_id.clone(),
+                channel_id: channel_id.clone(),
+                tool_name: tool_name.clone(),
+                error: result[TOOL_ERROR_PREFIX.len()..].trim().to_string(),
+            })),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::SessionComplete(_) => "on_session_complete",
+            Self::ToolError(_) => "on_tool_error",
+        }
+    }
+}
+
+/// Run one plugin call in its own task. Returns `None` if it panicked or
+/// timed out, counting panics against the plugin.
+async fn run_isolated<T: Send + 'static>(
+    entry: &Entry,
+    hook: &'static str,
+    timeout: Duration,
+    call: impl Future<Output = T> + Send + 'static,
+) -> Option<T> {
+    let task = tokio::spawn(call);
+    let abort_handle = task.abort_handle();
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(error)) => {
+            if error.is_panic() {
+                let panics = entry.panics.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::error!(plugin = %entry.name, hook, panics, "plugin panicked");
+                if panics == MAX_PANICS {
+                    tracing::error!(plugin = %entry.name, "plugin disabled after repeated panics");
+                }
+            }
+            None
+        }
+        Err(_) => {
+            abort_handle.abort();
+            tracing::warn!(
+                plugin = %entry.name,
+                hook,
+                timeout_secs = timeout.as_secs(),
+                "plugin call timed out"
+            );
+            None
+        }
+    }
+}
+
+/// Split `/name arguments` into the command name and its arguments.
+pub fn split_command(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim().strip_prefix('/')?;
+    let (name, arguments) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    (!name.is_empty()).then_some((name, arguments.trim()))
+}
+
+/// Forward an agent's session and tool-error events to the global registry.
+pub fn spawn_event_dispatch(
+    agent_id: AgentId,
+    mut event_rx: broadcast::Receiver<ProcessEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match classify_broadcast_recv_result(event_rx.recv().await) {
+                BroadcastRecvResult::Event(event) => PluginRegistry::global().dispatch(&event),
+                BroadcastRecvResult::Lagged(count) => {
+                    tracing::warn!(
+                        %agent_id,
+                        skipped = count,
+                        "plugin dispatch lagged, events were not delivered"
+                    );
+                }
+                BroadcastRecvResult::Closed => break,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn builtin_commands_match_the_channel() {
+        // Every `"/name"` literal outside tests in the modules that parse
+        // builtin commands.
+        let sources = [
+            include_str!("agent/channel.rs"),
+            include_str!("agent/channel_plan.rs"),
+            include_str!("templates.rs"),
+        ];
+        let literal = regex::Regex::new(r#""/([a-z][a-z-]*)""#).expect("hardcoded regex");
+        let mut dispatched: Vec<&str> = sources
+            .iter()
+            .flat_map(|source| {
+                let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+                literal
+                    .captures_iter(code)
+                    .map(|captures| captures.get(1).map_or("", |name| name.as_str()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        dispatched.sort_unstable();
+        dispatched.dedup();
+        assert_eq!(dispatched, BUILTIN_COMMANDS);
+    }
+
+    struct Echo;
+
+    impl Plugin for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn commands(&self) -> Vec<PluginCommand> {
+            vec![
+                PluginCommand::new("echo", "repeat the arguments"),
+                PluginCommand::new("boom", "panic"),
+            ]
+        }
+
+        async fn handle_command(&self, invocation: CommandInvocation) -> anyhow::Result<String> {
+            if invocation.command == "boom" {
+                panic!("boom");
+            }
+            Ok(invocation.arguments)
+        }
+    }
+
+    struct ToolErrors(mpsc::UnboundedSender<ToolError>);
+
+    impl Plugin for ToolErrors {
+        fn name(&self) -> &str {
+            "tool-errors"
+        }
+
+        async fn on_tool_error(&self, event: ToolError) {
+            self.0.send(event).ok();
+        }
+    }
+
+    fn invocation(text: &str) -> CommandInvocation {
+        let (command, arguments) = split_command(text).unwrap();
+        CommandInvocation {
+            agent_id: "main".into(),
+            conversation_id: "discord:1".into(),
+            source: "discord".into(),
+            sender_id: "42".into(),
+            access_tier: AccessTier::ReadOnly,
+            command: command.into(),
+            arguments: arguments.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_command_is_isolated_and_eventually_disabled() {
+        let registry = PluginRegistry::new();
+        registry.register(Echo).unwrap();
+
+        assert_eq!(
+            registry.handle_command(invocation("/echo  hi there")).await,
+            Some("hi there".to_string())
+        );
+        for _ in 0..MAX_PANICS {
+            assert_eq!(
+                registry.handle_command(invocation("/boom")).await,
+                Some("/boom is unavailable right now.".to_string())
+            );
+        }
+        assert_eq!(registry.handle_command(invocation("/echo hi")).await, None);
+        assert!(registry.commands().is_empty());
+    }
+
+    #[test]
+    fn registration_rejects_taken_commands() {
+        struct Shadow(&'static str);
+
+        impl Plugin for Shadow {
+            fn name(&self) -> &str {
+                self.0
+            }
+
+            fn commands(&self) -> Vec<PluginCommand> {
+                vec![PluginCommand::new(self.0, "")]
+            }
+        }
+
+        let registry = PluginRegistry::new();
+        registry.register(Echo).unwrap();
+        assert!(matches!(
+            registry.register(Shadow("status")),
+            Err(PluginError::CommandTaken { .. })
+        ));
+        assert!(matches!(
+            registry.register(Shadow("echo")),
+            Err(PluginError::DuplicatePlugin(_))
+        ));
+        registry.register(Shadow("weather")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_tool_calls_reach_on_tool_error() {
+        let registry = PluginRegistry::new();
+        let (error_tx, mut error_rx) = mpsc::unbounded_channel();
+        registry.register(ToolErrors(error_tx)).unwrap();
+
+        for result in ["ok", "Toolset error: file not found"] {
+            registry.dispatch(&ProcessEvent::ToolCompleted {
+                agent_id: "main".into(),
+                process_id: ProcessId::Worker(uuid::Uuid::new_v4()),
+                channel_id: None,
+                call_id: "call-1".into(),
+                tool_name: "file_read".into(),
+                result: result.into(),
+            });
+        }
+
+        let event = tokio::time::timeout(Duration::from_secs(5), error_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.tool_name, "file_read");
+        assert_eq!(event.error, "file not found");
+        assert!(error_rx.try_recv().is_err());
+    }
+}
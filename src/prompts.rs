@@ -1,4 +1,5 @@
 pub mod engine;
+pub mod messages;
 pub mod text;
 
 pub use engine::{PromptEngine, SkillInfo};
@@ -70,6 +70,10 @@ impl PromptEngine {
             crate::prompts::text::get("cortex_daily_summary"),
         )?;
         env.add_template("compactor", crate::prompts::text::get("compactor"))?;
+        env.add_template(
+            "tool_output_summary",
+            crate::prompts::text::get("tool_output_summary"),
+        )?;
         env.add_template(
             "memory_persistence",
             crate::prompts::text::get("memory_persistence"),
@@ -87,6 +91,10 @@ impl PromptEngine {
             "adapters/email",
             crate::prompts::text::get("adapters/email"),
         )?;
+        env.add_template(
+            "adapters/email_prompt",
+            crate::prompts::text::get("adapters/email_prompt"),
+        )?;
         env.add_template("adapters/cron", crate::prompts::text::get("adapters/cron"))?;
         env.add_template(
             "adapters/signal",
@@ -577,6 +585,7 @@ impl PromptEngine {
     pub fn render_channel_adapter_prompt(&self, adapter: &str) -> Option<String> {
         let template_name = match adapter {
             "email" => "adapters/email",
+            "email_prompt" => "adapters/email_prompt",
             "cron" => "adapters/cron",
             "signal" => "adapters/signal",
             _ => return None,
@@ -0,0 +1,139 @@
+//! Localized strings for bot-authored chat messages: built-in command
+//! replies and help, access errors, busy status lines, and rate-limit
+//! notices.
+//!
+//! Each locale has a TOML catalog at `prompts/{locale}/messages.toml`,
+//! embedded at compile time. Keys are dotted paths into the catalog
+//! (`"help.status"`), and `{name}` placeholders are filled in from the
+//! arguments. A key missing from a locale falls back to English.
+//!
+//! ```rust
+//! use spacebot::prompts::messages;
+//!
+//! let reply = messages::get_with("es", "model.set", &[("model", "openai/gpt-4.1")]);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Locale used when a channel has none or asks for an unsupported one.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales with a catalog.
+pub const LOCALES: &[&str] = &["en", "es"];
+
+type Catalog = HashMap<String, String>;
+
+static CATALOGS: LazyLock<HashMap<&'static str, Catalog>> = LazyLock::new(|| {
+    LOCALES
+        .iter()
+        .map(|locale| (*locale, parse_catalog(locale, catalog_source(locale))))
+        .collect()
+});
+
+fn catalog_source(locale: &str) -> &'static str {
+    match locale {
+        "es" => include_str!("../../prompts/es/messages.toml"),
+        _ => include_str!("../../prompts/en/messages.toml"),
+    }
+}
+
+fn parse_catalog(locale: &str, source: &str) -> Catalog {
+    let mut catalog = Catalog::new();
+    match source.parse::<toml::Table>() {
+        Ok(table) => flatten("", &table, &mut catalog),
+        Err(error) => tracing::error!(%error, locale, "invalid message catalog"),
+    }
+    catalog
+}
+
+fn flatten(prefix: &str, table: &toml::Table, catalog: &mut Catalog) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::Table(table) => flatten(&path, table, catalog),
+            toml::Value::String(text) => {
+                catalog.insert(path, text.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether `locale` has a catalog.
+pub fn is_supported(locale: &str) -> bool {
+    LOCALES.contains(&locale)
+}
+
+/// The string for `key` in `locale`, falling back to English, then to the
+/// key itself.
+pub fn get(locale: &str, key: &str) -> String {
+    get_with(locale, key, &[])
+}
+
+/// Like [`get`], with each `{name}` placeholder replaced by its value.
+pub fn get_with(locale: &str, key: &str, arguments: &[(&str, &str)]) -> String {
+    let template = [locale, DEFAULT_LOCALE]
+        .into_iter()
+        .find_map(|locale| CATALOGS.get(locale)?.get(key));
+    let Some(template) = template else {
+        tracing::warn!(locale, key, "missing message catalog entry");
+        return key.to_string();
+    };
+    arguments
+        .iter()
+        .fold(template.clone(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn every_locale_matches_the_english_catalog() {
+        let english = &CATALOGS[DEFAULT_LOCALE];
+        assert!(!english.is_empty());
+        for locale in LOCALES {
+            let catalog = &CATALOGS[locale];
+            for (key, text) in english {
+                let translated = catalog
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{locale} is missing {key}"));
+                assert_eq!(
+                    placeholders(translated),
+                    placeholders(text),
+                    "{locale} {key} has different placeholders"
+                );
+            }
+            assert_eq!(catalog.len(), english.len(), "{locale} has extra keys");
+        }
+    }
+
+    #[test]
+    fn fills_placeholders_and_falls_back() {
+        assert_eq!(
+            get_with("es", "rate_limit.slow_down", &[("seconds", "5")]),
+            "Estás enviando prompts demasiado rápido. Espera y vuelve a intentarlo en 5s."
+        );
+        assert_eq!(get("fr", "help.header"), "commands:");
+        assert_eq!(get("es", "help.nope"), "help.nope");
+        assert!(is_supported("es"));
+        assert!(!is_supported("fr"));
+    }
+}
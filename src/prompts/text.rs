@@ -70,6 +70,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         }
         ("en", "cortex_profile") => include_str!("../../prompts/en/cortex_profile.md.j2"),
         ("en", "compactor") => include_str!("../../prompts/en/compactor.md.j2"),
+        ("en", "tool_output_summary") => {
+            include_str!("../../prompts/en/tool_output_summary.md.j2")
+        }
         ("en", "memory_persistence") => include_str!("../../prompts/en/memory_persistence.md.j2"),
         ("en", "ingestion") => include_str!("../../prompts/en/ingestion.md.j2"),
         ("en", "cortex_chat") => include_str!("../../prompts/en/cortex_chat.md.j2"),
@@ -77,6 +80,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
 
         // Adapter-specific prompt fragments
         ("en", "adapters/email") => include_str!("../../prompts/en/adapters/email.md.j2"),
+        ("en", "adapters/email_prompt") => {
+            include_str!("../../prompts/en/adapters/email_prompt.md.j2")
+        }
         ("en", "adapters/cron") => include_str!("../../prompts/en/adapters/cron.md.j2"),
         ("en", "adapters/signal") => include_str!("../../prompts/en/adapters/signal.md.j2"),
 
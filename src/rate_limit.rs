@@ -0,0 +1,343 @@
+//! Token-bucket limits on inbound prompts.
+//!
+//! Every user message spends one token from its sender's bucket and one from
+//! its conversation's bucket before it is routed to an agent. A message that
+//! finds either bucket empty is dropped, and the sender gets a single "slow
+//! down" reply per empty spell rather than one per dropped message. Admins
+//! bypass the limits once access tiers are configured.
+
+use crate::InboundMessage;
+use crate::access::{self, Action};
+use crate::config::{HumanDef, RateLimitConfig, TokenBucketConfig};
+
+use arc_swap::ArcSwap;
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Buckets kept before idle ones are pruned. Past this, the least recently
+/// seen buckets are dropped too.
+const MAX_BUCKETS: usize = 4096;
+
+static LIMITER: LazyLock<PromptRateLimiter> = LazyLock::new(PromptRateLimiter::new);
+
+/// What to do with an inbound message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    /// Drop the message. `notify` is set for the first message of an empty
+    /// spell, which should get a [`slow_down_message`] reply.
+    Limited {
+        retry_after: Duration,
+        notify: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BucketKey {
+    User { source: String, sender_id: String },
+    Channel { conversation_id: String },
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// When a message last spent, or tried to spend, from this bucket.
+    last_seen: Instant,
+    /// When the sender was last told to slow down, until this instant.
+    notified_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn full(config: TokenBucketConfig, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(config.burst),
+            updated_at: now,
+            last_seen: now,
+            notified_until: None,
+        }
+    }
+
+    fn refill(&mut self, config: TokenBucketConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        let rate = f64::from(config.per_minute) / 60.0;
+        self.tokens = (self.tokens + elapsed * rate).min(f64::from(config.burst));
+        self.updated_at = now;
+    }
+
+    /// Time until one token is available.
+    fn wait(&self, config: TokenBucketConfig) -> Duration {
+        let missing = (1.0 - self.tokens).max(0.0);
+        Duration::from_secs_f64(missing * 60.0 / f64::from(config.per_minute))
+    }
+
+    /// Whether the bucket has gone unused long enough to refill from empty,
+    /// so dropping it loses nothing.
+    fn is_idle(&self, config: TokenBucketConfig, now: Instant) -> bool {
+        let window =
+            Duration::from_secs_f64(f64::from(config.burst) * 60.0 / f64::from(config.per_minute));
+        now.saturating_duration_since(self.last_seen) >= window
+    }
+}
+
+/// Drop idle buckets, then the least recently seen ones until there is room
+/// for the two a message may insert.
+fn prune(buckets: &mut HashMap<BucketKey, Bucket>, config: &RateLimitConfig, now: Instant) {
+    buckets.retain(|key, bucket| {
+        let bucket_config = match key {
+            BucketKey::User { .. } => config.user,
+            BucketKey::Channel { .. } => config.channel,
+        };
+        !bucket.is_idle(bucket_config, now)
+    });
+
+    let keep = MAX_BUCKETS - 2;
+    if buckets.len() > keep {
+        let mut by_age: Vec<(Instant, BucketKey)> = buckets
+            .iter()
+            .map(|(key, bucket)| (bucket.last_seen, key.clone()))
+            .collect();
+        by_age.sort_by_key(|(last_seen, _)| *last_seen);
+        let excess = buckets.len() - keep;
+        for (_, key) in by_age.into_iter().take(excess) {
+            buckets.remove(&key);
+        }
+    }
+}
+
+/// Per-sender and per-conversation prompt limits. Access via
+/// [`PromptRateLimiter::global`].
+pub struct PromptRateLimiter {
+    config: ArcSwap<RateLimitConfig>,
+    buckets: Mutex<HashMap<BucketKey, Bucket>>,
+}
+
+impl PromptRateLimiter {
+    /// A limiter with the default, disabled config.
+    pub fn new() -> Self {
+        Self {
+            config: ArcSwap::from_pointee(RateLimitConfig::default()),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The process-wide limiter the router checks.
+    pub fn global() -> &'static Self {
+        &LIMITER
+    }
+
+    /// Apply new limits. Existing buckets keep their tokens, capped at the
+    /// new burst on their next refill.
+    pub fn reconfigure(&self, config: RateLimitConfig) {
+        self.config.store(std::sync::Arc::new(config));
+    }
+
+    /// Spend a token for `message` if both its buckets have one.
+    ///
+    /// System messages and deletions are never limited, nor are senders
+    /// with the admin tier while access enforcement is on.
+    pub fn check(&self, message: &InboundMessage, humans: &[HumanDef], now: Instant) -> Decision {
+        let config = self.config.load();
+        if !config.enabled
+            || message.source == "system"
+            || matches!(message.content, crate::MessageContent::Deleted { .. })
+            || (access::enforcement_enabled(humans)
+                && access::authorize(humans, message, Action::BypassRateLimit))
+        {
+            return Decision::Allow;
+        }
+
+        let user_key = BucketKey::User {
+            source: message.source.clone(),
+            sender_id: message.sender_id.clone(),
+        };
+        let channel_key = BucketKey::Channel {
+            conversation_id: message.conversation_id.clone(),
+        };
+
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if buckets.len() >= MAX_BUCKETS - 1 {
+            prune(&mut buckets, &config, now);
+        }
+
+        let mut retry_after = Duration::ZERO;
+        for (key, bucket_config) in [(&user_key, config.user), (&channel_key, config.channel)] {
+            let bucket = buckets
+                .entry(key.clone())
+                .or_insert_with(|| Bucket::full(bucket_config, now));
+            bucket.refill(bucket_config, now);
+            bucket.last_seen = now;
+            retry_after = retry_after.max(bucket.wait(bucket_config));
+        }
+
+        if retry_after.is_zero() {
+            for key in [&user_key, &channel_key] {
+                if let Some(bucket) = buckets.get_mut(key) {
+                    bucket.tokens -= 1.0;
+                }
+            }
+            return Decision::Allow;
+        }
+
+        let user_bucket = buckets
+            .get_mut(&user_key)
+            .expect("user bucket was inserted above");
+        let notify = user_bucket
+            .notified_until
+            .is_none_or(|notified_until| now >= notified_until);
+        if notify {
+            user_bucket.notified_until = Some(now + retry_after);
+        }
+        Decision::Limited {
+            retry_after,
+            notify,
+        }
+    }
+}
+
+impl Default for PromptRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The reply sent to a rate-limited sender, in `locale`.
+pub fn slow_down_message(retry_after: Duration, locale: &str) -> String {
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    crate::prompts::messages::get_with(
+        locale,
+        "rate_limit.slow_down",
+        &[("seconds", &seconds.to_string())],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(user: (u32, u32), channel: (u32, u32)) -> PromptRateLimiter {
+        let limiter = PromptRateLimiter::new();
+        limiter.reconfigure(RateLimitConfig {
+            enabled: true,
+            user: TokenBucketConfig {
+                burst: user.0,
+                per_minute: user.1,
+            },
+            channel: TokenBucketConfig {
+                burst: channel.0,
+                per_minute: channel.1,
+            },
+        });
+        limiter
+    }
+
+    fn message(sender_id: &str, conversation_id: &str) -> InboundMessage {
+        InboundMessage {
+            source: "discord".to_string(),
+            adapter: Some("discord".to_string()),
+            conversation_id: conversation_id.to_string(),
+            sender_id: sender_id.to_string(),
+            content: crate::MessageContent::Text("run the tests".to_string()),
+            ..InboundMessage::empty()
+        }
+    }
+
+    #[test]
+    fn user_bucket_empties_and_refills() {
+        let limiter = limiter((2, 6), (100, 100));
+        let start = Instant::now();
+        let flood = message("alice", "discord:1:2");
+
+        assert_eq!(limiter.check(&flood, &[], start), Decision::Allow);
+        assert_eq!(limiter.check(&flood, &[], start), Decision::Allow);
+        assert_eq!(
+            limiter.check(&flood, &[], start),
+            Decision::Limited {
+                retry_after: Duration::from_secs(10),
+                notify: true,
+            }
+        );
+        assert!(matches!(
+            limiter.check(&flood, &[], start + Duration::from_secs(5)),
+            Decision::Limited { notify: false, .. }
+        ));
+        // Other senders in the same conversation are unaffected.
+        assert_eq!(
+            limiter.check(&message("bob", "discord:1:2"), &[], start),
+            Decision::Allow
+        );
+        assert_eq!(
+            limiter.check(&flood, &[], start + Duration::from_secs(10)),
+            Decision::Allow
+        );
+    }
+
+    #[test]
+    fn buckets_stay_under_the_cap() {
+        let limiter = limiter((5, 10), (20, 40));
+        let start = Instant::now();
+
+        // Steady traffic from many senders, none of them idle yet.
+        for index in 0..MAX_BUCKETS {
+            let now = start + Duration::from_millis(index as u64);
+            let sender = format!("user-{index}");
+            limiter.check(&message(&sender, "discord:1:2"), &[], now);
+            assert!(limiter.buckets.lock().unwrap().len() <= MAX_BUCKETS);
+        }
+        let buckets = limiter.buckets.lock().unwrap();
+        assert!(buckets.contains_key(&BucketKey::Channel {
+            conversation_id: "discord:1:2".to_string(),
+        }));
+        assert!(!buckets.contains_key(&BucketKey::User {
+            source: "discord".to_string(),
+            sender_id: "user-0".to_string(),
+        }));
+        drop(buckets);
+
+        // Once the refill window passes, idle buckets go first.
+        let later = start + Duration::from_secs(60);
+        limiter.check(&message("latecomer", "discord:9:9"), &[], later);
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn channel_bucket_is_shared_and_admins_bypass() {
+        let limiter = limiter((10, 10), (1, 1));
+        let start = Instant::now();
+
+        assert_eq!(
+            limiter.check(&message("alice", "discord:1:2"), &[], start),
+            Decision::Allow
+        );
+        assert!(matches!(
+            limiter.check(&message("bob", "discord:1:2"), &[], start),
+            Decision::Limited { .. }
+        ));
+
+        let humans = vec![HumanDef {
+            id: "ops".to_string(),
+            display_name: None,
+            role: None,
+            bio: None,
+            description: None,
+            discord_id: Some("carol".to_string()),
+            telegram_id: None,
+            slack_id: None,
+            email: None,
+            access: Some(crate::access::AccessTier::Admin),
+        }];
+        assert_eq!(
+            limiter.check(&message("carol", "discord:1:2"), &humans, start),
+            Decision::Allow
+        );
+        assert!(matches!(
+            limiter.check(&message("dave", "discord:1:2"), &humans, start),
+            Decision::Limited { .. }
+        ));
+    }
+}
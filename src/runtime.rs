@@ -0,0 +1,3210 @@
+//! The spacebot runtime: agents, messaging, the API server, and the main
+//! event loop that routes inbound messages to agent channels.
+//!
+//! The `spacebot` binary calls [`run`] after daemonizing and loading config.
+//! Embedding applications usually go through
+//! [`SpacebotBuilder`](crate::builder::SpacebotBuilder) instead.
+
+use anyhow::Context as _;
+use arc_swap::ArcSwap;
+use futures::StreamExt as _;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Everything [`run`] needs besides the config.
+#[derive(Default)]
+pub struct RunOptions {
+    /// Print setup hints to stderr instead of only logging them.
+    pub foreground: bool,
+    /// Tracer provider flushed on shutdown.
+    pub otel_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+    /// Instance secrets store, already opened and unlocked.
+    pub secrets_store: Option<Arc<crate::secrets::store::SecretsStore>>,
+    /// Messaging adapters registered alongside the configured ones, and
+    /// re-registered whenever agents are reinitialized.
+    pub bridges: Vec<Arc<dyn crate::messaging::traits::MessagingDyn>>,
+    /// Whether databases are migrated on open. Also applies to agents
+    /// reinitialized after a config change.
+    pub migrations: crate::db::Migrations,
+    /// Resolves when the runtime should shut down.
+    pub shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+struct ActiveChannel {
+    message_tx: mpsc::Sender<crate::InboundMessage>,
+    /// Retained so the outbound routing task stays alive.
+    _outbound_handle: tokio::task::JoinHandle<()>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ActiveChannelKey {
+    agent_id: String,
+    conversation_id: String,
+}
+
+impl ActiveChannelKey {
+    fn new(agent_id: impl Into<String>, conversation_id: impl Into<String>) -> Self {
+        Self {
+            agent_id: agent_id.into(),
+            conversation_id: conversation_id.into(),
+        }
+    }
+}
+
+/// Inbound messages accepted per tenant on the current UTC day.
+#[derive(Default)]
+struct TenantUsage {
+    day: Option<chrono::NaiveDate>,
+    accepted: HashMap<String, u64>,
+    /// `(tenant, user)` pairs already told the budget is spent today.
+    notified: std::collections::HashSet<(String, String)>,
+}
+
+/// Outcome of counting a message against a tenant's budget.
+#[derive(Debug, PartialEq, Eq)]
+enum Admission {
+    Accepted,
+    /// The budget is spent. `notify` is true the first time each user hits
+    /// it on a given day.
+    Dropped {
+        notify: bool,
+    },
+}
+
+impl TenantUsage {
+    /// Count a message from `user_id` against `tenant`'s daily budget.
+    fn admit(
+        &mut self,
+        tenant: &crate::config::TenantConfig,
+        user_id: &str,
+        today: chrono::NaiveDate,
+    ) -> Admission {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.accepted.clear();
+            self.notified.clear();
+        }
+        let accepted = self.accepted.entry(tenant.id.clone()).or_default();
+        if let Some(budget) = tenant.daily_message_budget
+            && *accepted >= budget
+        {
+            if *accepted == budget {
+                tracing::warn!(
+                    tenant_id = %tenant.id,
+                    budget,
+                    "tenant daily message budget spent, dropping messages until tomorrow (UTC)"
+                );
+                crate::notifications::ops::OpsAlerts::global().notify(
+                    crate::notifications::ops::Alert::new(
+                        crate::notifications::ops::AlertKind::BudgetBreach,
+                        format!("tenant {}", tenant.id),
+                        format!(
+                            "daily message budget of {budget} spent, dropping messages until tomorrow (UTC)"
+                        ),
+                    ),
+                );
+                *accepted += 1;
+            }
+            let notify = self
+                .notified
+                .insert((tenant.id.clone(), user_id.to_string()));
+            return Admission::Dropped { notify };
+        }
+        *accepted += 1;
+        Admission::Accepted
+    }
+}
+
+/// Maximum number of deferred messages per channel before oldest are dropped.
+const DEFERRED_INJECTION_CAP: usize = 64;
+
+fn queue_deferred_injection(
+    deferred_injections: &mut HashMap<ActiveChannelKey, Vec<crate::InboundMessage>>,
+    injection: crate::ChannelInjection,
+) {
+    let key = ActiveChannelKey::new(injection.agent_id, injection.conversation_id);
+    let queue = deferred_injections.entry(key).or_default();
+    if queue.len() >= DEFERRED_INJECTION_CAP {
+        tracing::warn!(
+            "deferred injection queue at capacity ({DEFERRED_INJECTION_CAP}), dropping oldest message"
+        );
+        queue.remove(0);
+    }
+    queue.push(injection.message);
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BackfillTranscriptEntry {
+    role: String,
+    author: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_utc: Option<String>,
+    content: String,
+}
+
+fn serialize_backfill_transcript(entries: Vec<BackfillTranscriptEntry>) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+
+    match serde_json::to_string_pretty(&entries) {
+        Ok(serialized) => Some(serialized),
+        Err(error) => {
+            tracing::warn!(%error, "failed to serialize backfill transcript");
+            None
+        }
+    }
+}
+
+fn render_platform_history_backfill(
+    history_messages: &[crate::messaging::traits::HistoryMessage],
+) -> Option<String> {
+    let entries = history_messages
+        .iter()
+        .map(|entry| BackfillTranscriptEntry {
+            role: if entry.is_bot {
+                "assistant".to_string()
+            } else {
+                "user".to_string()
+            },
+            author: if entry.is_bot {
+                "(you)".to_string()
+            } else {
+                entry.author.clone()
+            },
+            timestamp_utc: entry
+                .timestamp
+                .as_ref()
+                .map(|timestamp| timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            content: entry.content.clone(),
+        })
+        .collect();
+
+    serialize_backfill_transcript(entries)
+}
+
+fn render_conversation_history_backfill(
+    history_messages: &[crate::conversation::history::ConversationMessage],
+) -> Option<String> {
+    let entries = history_messages
+        .iter()
+        .filter(|entry| entry.role == "user" || entry.role == "assistant")
+        .map(|entry| {
+            let author = if entry.role == "assistant" {
+                "(you)".to_string()
+            } else {
+                entry
+                    .sender_name
+                    .clone()
+                    .or_else(|| entry.sender_id.clone())
+                    .unwrap_or_else(|| "user".to_string())
+            };
+
+            BackfillTranscriptEntry {
+                role: entry.role.clone(),
+                author,
+                timestamp_utc: Some(
+                    entry
+                        .created_at
+                        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                ),
+                content: entry.content.clone(),
+            }
+        })
+        .collect();
+
+    serialize_backfill_transcript(entries)
+}
+
+/// Forward outbound response events to SSE clients for the dashboard.
+fn forward_sse_event(
+    api_event_tx: &crate::api::EventBus,
+    agent_id: &str,
+    channel_id: &str,
+    response: &crate::OutboundResponse,
+) {
+    match response {
+        crate::OutboundResponse::Text(text)
+        | crate::OutboundResponse::RichMessage { text, .. }
+        | crate::OutboundResponse::ThreadReply { text, .. } => {
+            api_event_tx
+                .send(crate::api::ApiEvent::OutboundMessage {
+                    agent_id: agent_id.to_string(),
+                    channel_id: channel_id.to_string(),
+                    text: text.clone(),
+                })
+                .ok();
+        }
+        crate::OutboundResponse::Status(crate::StatusUpdate::Thinking) => {
+            api_event_tx
+                .send(crate::api::ApiEvent::TypingState {
+                    agent_id: agent_id.to_string(),
+                    channel_id: channel_id.to_string(),
+                    is_typing: true,
+                })
+                .ok();
+        }
+        crate::OutboundResponse::Status(crate::StatusUpdate::StopTyping) => {
+            api_event_tx
+                .send(crate::api::ApiEvent::TypingState {
+                    agent_id: agent_id.to_string(),
+                    channel_id: channel_id.to_string(),
+                    is_typing: false,
+                })
+                .ok();
+        }
+        _ => {}
+    }
+}
+
+/// Route an outbound response to the messaging adapter using the pinned target
+/// message for platform routing metadata (thread_ts, channel_id, etc.).
+async fn route_outbound(
+    messaging: &std::sync::Arc<crate::messaging::MessagingManager>,
+    target: &crate::InboundMessage,
+    content_filter: Option<&crate::messaging::content_filter::OutboundFilter>,
+    response: crate::OutboundResponse,
+) {
+    match response {
+        crate::OutboundResponse::Status(status) => {
+            if let Err(error) = messaging.send_status(target, status).await {
+                tracing::warn!(%error, "failed to send status update");
+            }
+        }
+        mut response => {
+            crate::scripting::ScriptHooks::global().post_process(
+                &mut response,
+                &crate::scripting::ScriptContext::from_message(target),
+            );
+            if let Some(content_filter) = content_filter {
+                response = content_filter.apply(target, response).await;
+            }
+            if let Err(error) = messaging.respond(target, response).await {
+                tracing::error!(%error, "failed to send outbound response");
+            }
+        }
+    }
+}
+
+fn has_provider_credentials(
+    llm_config: &crate::config::LlmConfig,
+    instance_dir: &std::path::Path,
+) -> bool {
+    llm_config.has_any_key()
+        || crate::auth::credentials_path(instance_dir).exists()
+        || crate::openai_auth::credentials_path(instance_dir).exists()
+}
+
+fn configured_agent_infos(config: &crate::config::Config) -> Vec<crate::api::AgentInfo> {
+    config
+        .resolve_agents()
+        .into_iter()
+        .map(|agent| crate::api::AgentInfo {
+            id: agent.id,
+            display_name: agent.display_name,
+            role: agent.role,
+            gradient_start: agent.gradient_start,
+            gradient_end: agent.gradient_end,
+            workspace: agent.workspace.to_string_lossy().to_string(),
+            context_window: agent.context_window,
+            max_turns: agent.max_turns,
+            max_concurrent_branches: agent.max_concurrent_branches,
+            max_concurrent_workers: agent.max_concurrent_workers,
+        })
+        .collect()
+}
+
+/// Run the instance described by `config` until shutdown is requested over
+/// IPC, by Ctrl-C, or by [`RunOptions::shutdown`].
+pub async fn run(config: crate::config::Config, options: RunOptions) -> anyhow::Result<()> {
+    let RunOptions {
+        foreground,
+        otel_provider,
+        secrets_store: bootstrapped_store,
+        bridges,
+        migrations,
+        shutdown,
+    } = options;
+    let mut shutdown = shutdown.unwrap_or_else(|| Box::pin(std::future::pending()));
+    let paths = crate::daemon::DaemonPaths::new(&config.instance_dir);
+
+    tracing::info!("starting spacebot");
+    tracing::info!(instance_dir = %config.instance_dir.display(), "configuration loaded");
+
+    // Operator scripts; the config file watcher reloads them on change.
+    crate::scripting::ScriptHooks::global().load_dir(&config.instance_dir.join("scripts"));
+    crate::rate_limit::PromptRateLimiter::global().reconfigure(config.rate_limit.clone());
+    crate::db::blob::BlobStore::configure(&config.blob_store);
+    crate::notifications::ops::OpsAlerts::global().configure(config.notify.clone());
+
+    // Start the IPC server for stop/status commands
+    let (mut shutdown_rx, _ipc_handle) = crate::daemon::start_ipc_server(&paths)
+        .await
+        .context("failed to start IPC server")?;
+
+    // Create the provider setup channel so API handlers can signal the main loop
+    let (provider_tx, mut provider_rx) = mpsc::channel::<crate::ProviderSetupEvent>(1);
+    // Channel for newly created agents to be registered in the main event loop
+    let (agent_tx, mut agent_rx) = mpsc::channel::<crate::Agent>(8);
+    // Channel for removing agents from the main event loop
+    let (agent_remove_tx, mut agent_remove_rx) = mpsc::channel::<String>(8);
+
+    // Channel for cross-agent message injection (e.g. delegated task completion notifications).
+    // The sender is shared with all agents via AgentDeps; the receiver is polled in the main loop.
+    let (injection_tx, mut injection_rx) =
+        tokio::sync::mpsc::channel::<crate::ChannelInjection>(64);
+
+    // Instance-level global task database. Shared across all agents with globally
+    // unique task numbers. Lives alongside secrets.redb in the instance data dir.
+    let instance_pool =
+        crate::db::connect_instance_db(&config.instance_dir.join("data"), migrations)
+            .await
+            .context("failed to initialize instance database")?;
+
+    // Migrate legacy per-agent tasks to the global database on first run.
+    crate::tasks::migration::migrate_legacy_tasks(&config.instance_dir, &instance_pool)
+        .await
+        .context("failed to migrate legacy tasks to global database")?;
+
+    let global_task_store = Arc::new(crate::tasks::TaskStore::new(instance_pool.clone()));
+
+    // Streams left mid-edit by a crash resume their platform messages.
+    crate::messaging::journal::StreamJournal::install(instance_pool.clone()).await;
+
+    // Instance-wide wiki knowledge base.
+    let global_wiki_store = Arc::new(crate::wiki::WikiStore::new(instance_pool.clone()));
+
+    // Database shared by every replica, when `[database]` points at Postgres.
+    let shared_pool = match &config.database {
+        crate::config::DatabaseConfig::Sqlite => None,
+        crate::config::DatabaseConfig::Postgres(postgres) => Some(
+            crate::db::connect_postgres(postgres, migrations)
+                .await
+                .context("failed to initialize shared database")?,
+        ),
+    };
+
+    // Instance-level notification store for the dashboard inbox.
+    let global_notification_store = Arc::new(match &shared_pool {
+        None => crate::notifications::NotificationStore::new(instance_pool.clone()),
+        Some(pool) => crate::notifications::NotificationStore::with_backend(Arc::new(
+            crate::notifications::PostgresNotifications::new(pool.clone()),
+        )),
+    });
+
+    // With several replicas behind the same bot tokens, each session is
+    // handled by the replica holding its lease.
+    let session_leases = match &shared_pool {
+        Some(pool) if config.cluster.enabled => {
+            let leases = Arc::new(crate::cluster::SessionLeases::new(
+                pool.clone(),
+                &config.cluster,
+            ));
+            tracing::info!(replica_id = %leases.replica_id(), "cluster mode enabled");
+            tracing::warn!(
+                "conversations, transcripts, usage, and channel settings are kept in each \
+                 replica's own agent databases; a session that moves replicas starts without them"
+            );
+            Some(leases)
+        }
+        _ => None,
+    };
+    let lease_renewal = session_leases
+        .as_ref()
+        .map(crate::cluster::SessionLeases::spawn_renewal);
+
+    // Instance-level shared project store. Replaces per-agent project stores.
+    let global_project_store = Arc::new(crate::projects::ProjectStore::new(instance_pool.clone()));
+
+    // Migrate per-agent projects into the instance database on first run.
+    crate::projects::migration::migrate_legacy_projects(&config.instance_dir, &instance_pool)
+        .await
+        .context("failed to migrate legacy projects to instance database")?;
+
+    // Start HTTP API server if enabled
+    let mut api_state = crate::api::ApiState::new_with_provider_sender(
+        provider_tx,
+        agent_tx,
+        agent_remove_tx,
+        injection_tx.clone(),
+    );
+    api_state.auth_token = config.api.auth_token.clone();
+    api_state.oncall_token = config.api.oncall_token.clone();
+    api_state.public_url = config.api.public_url.clone();
+    api_state.trust_forwarded_headers = config.api.trust_forwarded_headers;
+    if let crate::config::EventBusConfig::Redis(redis) = &config.event_bus {
+        api_state
+            .event_tx
+            .connect_redis(redis, &config.cluster.replica_id);
+    }
+    api_state.set_task_store(global_task_store.clone());
+    api_state.set_wiki_store(global_wiki_store.clone());
+    api_state.set_notification_store(global_notification_store.clone());
+    let api_state = Arc::new(api_state);
+
+    // Keep the secrets API available in setup mode so encrypted stores can be
+    // unlocked before providers/agents are initialized.
+    if let Some(store) = &bootstrapped_store {
+        api_state.set_secrets_store(store.clone());
+    }
+
+    // Start background update checker
+    crate::update::spawn_update_checker(api_state.update_status.clone());
+
+    crate::dead_letters::DeadLetterQueue::global().configure(
+        config.dead_letters.capacity,
+        config.dead_letters.file.clone(),
+    );
+
+    // Start metrics server if enabled (requires `metrics` cargo feature)
+    #[cfg(feature = "metrics")]
+    let _metrics_handle = if config.metrics.enabled {
+        Some(
+            crate::telemetry::start_metrics_server(&config.metrics, shutdown_rx.clone())
+                .await
+                .context("failed to start metrics server")?,
+        )
+    } else {
+        None
+    };
+
+    let _http_handle = if config.api.enabled {
+        // IPv6 addresses need brackets when combined with port: [::]:19898
+        let raw_bind = config
+            .api
+            .bind
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+        let bind_str = if raw_bind.contains(':') {
+            format!("[{}]:{}", raw_bind, config.api.port)
+        } else {
+            format!("{}:{}", raw_bind, config.api.port)
+        };
+        let bind: std::net::SocketAddr = bind_str.parse().context("invalid API bind address")?;
+        let http_shutdown = shutdown_rx.clone();
+        Some(
+            crate::api::start_http_server(bind, api_state.clone(), http_shutdown)
+                .await
+                .context("failed to start HTTP server")?,
+        )
+    } else {
+        None
+    };
+
+    // Check if we have provider configuration (API keys or OAuth credentials)
+    let has_providers = has_provider_credentials(&config.llm, &config.instance_dir);
+
+    if !has_providers {
+        tracing::info!("No LLM providers configured. Starting in setup mode.");
+        if foreground {
+            eprintln!("No LLM provider keys configured.");
+            match &config.api.public_url {
+                Some(public_url) => {
+                    eprintln!("Please add a provider key via the web UI at {public_url}")
+                }
+                None => eprintln!(
+                    "Please add a provider key via the web UI at http://{}:{}",
+                    config.api.bind, config.api.port
+                ),
+            }
+        }
+    }
+
+    // Shared LLM manager (same API keys for all agents)
+    // This works even without keys; it will fail later at call time if no keys exist.
+    // Loads OAuth credentials from auth.json if available.
+    let llm_manager = Arc::new(
+        crate::llm::LlmManager::with_instance_dir(config.llm.clone(), config.instance_dir.clone())
+            .await
+            .with_context(|| "failed to initialize LLM manager")?,
+    );
+
+    // Shared embedding model (stateless, agent-agnostic)
+    let embedding_cache_dir = config.instance_dir.join("embedding_cache");
+    let embedding_model = Arc::new(
+        crate::memory::EmbeddingModel::new(&embedding_cache_dir)
+            .context("failed to initialize embedding model")?,
+    );
+
+    tracing::info!("shared resources initialized");
+
+    // Initialize the language for all text lookups (must happen before PromptEngine/tools)
+    crate::prompts::text::init("en").with_context(|| "failed to initialize language")?;
+
+    // Create the PromptEngine with bundled templates (no file watching, no user overrides)
+    let prompt_engine = crate::prompts::PromptEngine::new("en")
+        .with_context(|| "failed to initialize prompt engine")?;
+
+    // Parse config links into shared agent links (hot-reloadable via ArcSwap)
+    let agent_links = Arc::new(ArcSwap::from_pointee(crate::links::AgentLink::from_config(
+        &config.links,
+    )?));
+    if !config.links.is_empty() {
+        tracing::info!(count = config.links.len(), "loaded agent links from config");
+    }
+
+    // Shared humans list (hot-reloadable via ArcSwap, same pattern as agent_links)
+    let agent_humans = Arc::new(ArcSwap::from_pointee(config.humans.clone()));
+
+    // These hold the initialized subsystems. Empty until agents are initialized.
+    let mut agents: HashMap<crate::AgentId, crate::Agent> = HashMap::new();
+    let mut messaging_manager: Arc<crate::messaging::MessagingManager> =
+        Arc::new(crate::messaging::MessagingManager::new());
+    // Use an Option to represent "no inbound stream yet" (setup mode)
+    let mut inbound_stream: Option<
+        std::pin::Pin<Box<dyn futures::Stream<Item = crate::InboundMessage> + Send>>,
+    > = None;
+    let mut cron_schedulers_for_shutdown: Vec<Arc<crate::cron::Scheduler>> = Vec::new();
+    let mut _ingestion_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+    let mut _cortex_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+    let bindings: Arc<ArcSwap<Vec<crate::config::Binding>>> =
+        Arc::new(ArcSwap::from_pointee(config.bindings.clone()));
+    api_state.set_bindings(bindings.clone()).await;
+    let tenants: Arc<ArcSwap<Vec<crate::config::TenantConfig>>> =
+        Arc::new(ArcSwap::from_pointee(config.tenants.clone()));
+    let mut tenant_usage = TenantUsage::default();
+    let default_agent_id = config.default_agent_id().to_string();
+
+    // Set the config path on the API state for config.toml writes
+    let config_path = config.instance_dir.join("config.toml");
+    api_state.set_config_path(config_path.clone()).await;
+    api_state.set_instance_dir(config.instance_dir.clone());
+    api_state.set_llm_manager(llm_manager.clone()).await;
+    api_state.set_embedding_model(embedding_model.clone()).await;
+    api_state.set_prompt_engine(prompt_engine.clone()).await;
+    api_state.set_defaults_config(config.defaults.clone()).await;
+    api_state.set_agent_links((**agent_links.load()).clone());
+    api_state.set_agent_groups(config.groups.clone());
+    api_state.set_agent_humans(config.humans.clone());
+    api_state.set_agent_configs(configured_agent_infos(&config));
+
+    // Track whether agents have been initialized
+    let mut agents_initialized = false;
+
+    // File watcher handle — started after agent init (or in setup mode with empty data)
+    let mut _file_watcher;
+
+    // If providers are available, initialize agents immediately
+    if has_providers {
+        let mut watcher_agents = Vec::new();
+        let mut discord_permissions = None;
+        let mut slack_permissions = None;
+        let mut telegram_permissions = None;
+        let mut twitch_permissions = None;
+        let mut mattermost_permissions = None;
+        let mut signal_permissions = None;
+        initialize_agents(
+            &config,
+            &llm_manager,
+            &embedding_model,
+            &prompt_engine,
+            &api_state,
+            &mut agents,
+            &mut messaging_manager,
+            &mut inbound_stream,
+            &mut cron_schedulers_for_shutdown,
+            &mut _ingestion_handles,
+            &mut _cortex_handles,
+            &mut watcher_agents,
+            &mut discord_permissions,
+            &mut slack_permissions,
+            &mut telegram_permissions,
+            &mut twitch_permissions,
+            &mut mattermost_permissions,
+            &mut signal_permissions,
+            agent_links.clone(),
+            agent_humans.clone(),
+            injection_tx.clone(),
+            global_task_store.clone(),
+            global_wiki_store.clone(),
+            global_project_store.clone(),
+            global_notification_store.clone(),
+            &bootstrapped_store,
+            &bridges,
+            migrations,
+        )
+        .await?;
+        agents_initialized = true;
+
+        // Start file watcher with populated agent data
+        _file_watcher = crate::config::spawn_file_watcher(
+            config_path.clone(),
+            config.instance_dir.clone(),
+            watcher_agents,
+            discord_permissions,
+            slack_permissions,
+            telegram_permissions,
+            twitch_permissions,
+            mattermost_permissions,
+            signal_permissions,
+            bindings.clone(),
+            tenants.clone(),
+            Some(messaging_manager.clone()),
+            llm_manager.clone(),
+            agent_links.clone(),
+            agent_humans.clone(),
+        );
+    } else {
+        // Start file watcher in setup mode (no agents to watch yet)
+        _file_watcher = crate::config::spawn_file_watcher(
+            config_path.clone(),
+            config.instance_dir.clone(),
+            Vec::new(),
+            None, // discord_permissions
+            None, // slack_permissions
+            None, // telegram_permissions
+            None, // twitch_permissions
+            None, // mattermost_permissions
+            None, // signal_permissions
+            bindings.clone(),
+            tenants.clone(),
+            None,
+            llm_manager.clone(),
+            agent_links.clone(),
+            agent_humans.clone(),
+        );
+    }
+
+    if foreground {
+        eprintln!(
+            "spacebot running in foreground (pid {})",
+            std::process::id()
+        );
+    } else {
+        tracing::info!(pid = std::process::id(), "spacebot daemon started");
+    }
+
+    // Active conversation channels keyed by their owning agent and conversation.
+    let mut active_channels: HashMap<ActiveChannelKey, ActiveChannel> = HashMap::new();
+    let mut deferred_injections: HashMap<ActiveChannelKey, Vec<crate::InboundMessage>> =
+        HashMap::new();
+    // DM conversations routed to a private session, mapped to that
+    // session's key so injections addressed to the conversation find it.
+    let mut private_session_keys: HashMap<ActiveChannelKey, ActiveChannelKey> = HashMap::new();
+
+    // Resume idle interactive workers that survived the restart.
+    // For each idle worker, pre-create the channel if needed and spawn
+    // the resumed worker into its state so follow-ups route correctly.
+    if agents_initialized {
+        for (agent_id, agent) in agents.iter() {
+            let run_logger = crate::conversation::ProcessRunLogger::new(agent.db.sqlite.clone());
+            let idle_workers = match run_logger
+                .get_idle_interactive_workers(&agent.config.id)
+                .await
+            {
+                Ok(workers) => workers,
+                Err(error) => {
+                    tracing::warn!(agent_id = %agent_id, %error, "failed to query idle workers");
+                    continue;
+                }
+            };
+            if idle_workers.is_empty() {
+                continue;
+            }
+            tracing::info!(
+                agent_id = %agent_id,
+                idle_count = idle_workers.len(),
+                "found idle interactive workers to resume"
+            );
+
+            // Group idle workers by channel_id
+            let mut by_channel: HashMap<String, Vec<&crate::conversation::history::IdleWorkerRow>> =
+                HashMap::new();
+            for worker in &idle_workers {
+                if let Some(channel_id) = &worker.channel_id {
+                    by_channel
+                        .entry(channel_id.clone())
+                        .or_default()
+                        .push(worker);
+                } else {
+                    // Workers without a channel_id can't be resumed (no follow-up
+                    // routing). Leave them as idle — the transcript is preserved
+                    // for inspection in the UI.
+                    tracing::warn!(
+                        worker_id = %worker.id,
+                        "idle worker has no channel_id, cannot resume (leaving as idle)"
+                    );
+                }
+            }
+
+            for (conversation_id, workers) in by_channel {
+                // Another replica may have taken the session while this one
+                // was down; its workers stay idle here.
+                if let Some(leases) = &session_leases
+                    && !leases
+                        .claim(&crate::cluster::session_key(agent_id, &conversation_id))
+                        .await
+                {
+                    continue;
+                }
+                // Ensure the channel exists. If it's already in active_channels
+                // (unlikely at startup), use its state. Otherwise, pre-create it.
+                let channel_key =
+                    ActiveChannelKey::new(agent_id.to_string(), conversation_id.clone());
+                #[allow(clippy::map_entry)] // 250-line block is clearer with contains_key+insert
+                if !active_channels.contains_key(&channel_key) {
+                    // First pass: retire any workers whose sessions can't be
+                    // reconnected. Only create the channel if at least one
+                    // worker has a chance of resuming.
+                    let mut resumable: Vec<&crate::conversation::history::IdleWorkerRow> =
+                        Vec::new();
+                    for idle_worker in &workers {
+                        if idle_worker.worker_type == "opencode"
+                            && idle_worker.opencode_session_id.is_none()
+                        {
+                            // OpenCode workers without session metadata can never
+                            // resume — the server died with kill_on_drop.
+                            if let Err(error) = run_logger.retire_idle_worker(&idle_worker.id).await
+                            {
+                                tracing::warn!(
+                                    worker_id = %idle_worker.id,
+                                    %error,
+                                    "failed to retire idle worker"
+                                );
+                            }
+                            tracing::info!(
+                                worker_id = %idle_worker.id,
+                                channel_id = %conversation_id,
+                                "retired idle opencode worker (no session metadata)"
+                            );
+                        } else {
+                            resumable.push(idle_worker);
+                        }
+                    }
+                    if resumable.is_empty() {
+                        continue;
+                    }
+
+                    let (response_tx, response_rx) = mpsc::channel::<crate::RoutedResponse>(32);
+                    let event_rx = agent.deps.event_tx.subscribe();
+                    let channel_id: crate::ChannelId = Arc::from(conversation_id.as_str());
+
+                    let snapshot_store = agent
+                        .deps
+                        .runtime_config
+                        .prompt_snapshots
+                        .load()
+                        .as_ref()
+                        .clone();
+
+                    // Load per-conversation settings (idle worker resume).
+                    // Try portal store first, then channel_settings for platform channels.
+                    let resolved_settings = {
+                        let agent_id_str = agent_id.to_string();
+                        let portal_store = crate::conversation::PortalConversationStore::new(
+                            agent.deps.sqlite_pool.clone(),
+                        );
+                        let channel_store = crate::conversation::ChannelSettingsStore::new(
+                            agent.deps.sqlite_pool.clone(),
+                        );
+                        match portal_store.get(&agent_id_str, &conversation_id).await {
+                            Ok(Some(conv)) => {
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    conv.settings.as_ref(),
+                                    None,
+                                    None,
+                                )
+                            }
+                            Ok(None) => {
+                                match channel_store.get(&agent_id_str, &conversation_id).await {
+                                    Ok(Some(settings)) => {
+                                        crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                            Some(&settings),
+                                            None,
+                                            None,
+                                        )
+                                    }
+                                    Ok(None) => {
+                                        crate::conversation::settings::ResolvedConversationSettings::default()
+                                    }
+                                    Err(error) => {
+                                        tracing::warn!(
+                                            %error,
+                                            %conversation_id,
+                                            "idle worker resume: failed to load channel settings, using defaults"
+                                        );
+                                        crate::conversation::settings::ResolvedConversationSettings::default()
+                                    }
+                                }
+                            }
+                            Err(error) => {
+                                tracing::warn!(
+                                    %error,
+                                    %conversation_id,
+                                    "idle worker resume: failed to load portal settings, using defaults"
+                                );
+                                crate::conversation::settings::ResolvedConversationSettings::default(
+                                )
+                            }
+                        }
+                    };
+
+                    let (mut channel, channel_tx) = crate::agent::channel::Channel::new(
+                        channel_id,
+                        agent.deps.clone(),
+                        response_tx,
+                        event_rx,
+                        agent.config.screenshot_dir(),
+                        agent.config.logs_dir(),
+                        snapshot_store,
+                        Some(api_state.live_worker_transcripts.clone()),
+                        resolved_settings,
+                        None, // no cron outcome for normal channels
+                    );
+                    let channel_registration_id = agent
+                        .deps
+                        .process_control_registry
+                        .register_channel(channel.id.clone(), channel.control_handle().downgrade())
+                        .await;
+                    api_state
+                        .register_channel_status(
+                            conversation_id.clone(),
+                            channel.state.status_block.clone(),
+                        )
+                        .await;
+                    api_state
+                        .register_channel_state(conversation_id.clone(), channel.state.clone())
+                        .await;
+
+                    let backfill_count = agent.config.history_backfill_count();
+                    if backfill_count > 0 {
+                        let backfill_limit =
+                            std::cmp::min(backfill_count, i64::MAX as usize) as i64;
+                        match channel
+                            .state
+                            .conversation_logger
+                            .load_recent(&channel.id, backfill_limit)
+                            .await
+                        {
+                            Ok(history_messages) => {
+                                if let Some(transcript) =
+                                    render_conversation_history_backfill(&history_messages)
+                                {
+                                    channel.set_backfill_transcript(transcript);
+                                    tracing::info!(
+                                        conversation_id = %conversation_id,
+                                        message_count = history_messages.len(),
+                                        "backfilled resumed channel history from conversation log"
+                                    );
+                                }
+                            }
+                            Err(error) => {
+                                tracing::warn!(
+                                    conversation_id = %conversation_id,
+                                    %error,
+                                    "failed to backfill resumed channel history from conversation log"
+                                );
+                            }
+                        }
+                    }
+
+                    // Resume workers into the channel state before spawning the event loop.
+                    let mut any_resumed = false;
+                    for idle_worker in &resumable {
+                        match crate::agent::channel_dispatch::resume_idle_worker_into_state(
+                            &channel.state,
+                            idle_worker,
+                        )
+                        .await
+                        {
+                            Ok(worker_id) => {
+                                any_resumed = true;
+                                tracing::info!(
+                                    worker_id = %worker_id,
+                                    channel_id = %conversation_id,
+                                    "resumed idle worker"
+                                );
+                            }
+                            Err(reason) => {
+                                // Resume failed at runtime (e.g. OpenCode disabled,
+                                // transcript corrupt). Retire the worker.
+                                if let Err(error) =
+                                    run_logger.retire_idle_worker(&idle_worker.id).await
+                                {
+                                    tracing::warn!(
+                                        worker_id = %idle_worker.id,
+                                        %error,
+                                        "failed to retire idle worker"
+                                    );
+                                }
+                                tracing::info!(
+                                    worker_id = %idle_worker.id,
+                                    channel_id = %conversation_id,
+                                    %reason,
+                                    "retired idle worker (session expired)"
+                                );
+                            }
+                        }
+                    }
+
+                    // Spawn the channel event loop.
+                    let cleanup_channel_id = conversation_id.clone();
+                    let process_control_registry = agent.deps.process_control_registry.clone();
+                    let api_state_for_cleanup = api_state.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = channel.run().await {
+                            tracing::error!(%error, "channel event loop failed");
+                        }
+                        let scoped_channel_id: crate::ChannelId =
+                            Arc::from(cleanup_channel_id.as_str());
+                        process_control_registry
+                            .unregister_channel(&scoped_channel_id, channel_registration_id)
+                            .await;
+                        api_state_for_cleanup
+                            .unregister_channel_status(&cleanup_channel_id)
+                            .await;
+                        api_state_for_cleanup
+                            .unregister_channel_state(&cleanup_channel_id)
+                            .await;
+                    });
+
+                    let messaging_for_outbound = messaging_manager.clone();
+                    let api_event_tx = api_state.event_tx.clone();
+                    let sse_agent_id = agent_id.to_string();
+                    let sse_channel_id = conversation_id.clone();
+                    let content_filter =
+                        crate::messaging::content_filter::OutboundFilter::for_agent(
+                            &tenants.load(),
+                            agent_id,
+                            agent.deps.sqlite_pool.clone(),
+                        );
+                    let outbound = crate::messaging::backpressure::buffer_outbound(response_rx);
+                    let outbound_handle = tokio::spawn(async move {
+                        while let Some(routed) = outbound.pop().await {
+                            let crate::RoutedResponse { response, target } = routed;
+                            forward_sse_event(
+                                &api_event_tx,
+                                &sse_agent_id,
+                                &sse_channel_id,
+                                &response,
+                            );
+                            route_outbound(
+                                &messaging_for_outbound,
+                                &target,
+                                content_filter.as_ref(),
+                                response,
+                            )
+                            .await;
+                        }
+                    });
+
+                    active_channels.insert(
+                        channel_key,
+                        ActiveChannel {
+                            message_tx: channel_tx,
+                            _outbound_handle: outbound_handle,
+                        },
+                    );
+
+                    tracing::info!(
+                        conversation_id = %conversation_id,
+                        agent_id = %agent_id,
+                        any_resumed,
+                        "pre-created channel for idle worker resumption"
+                    );
+                }
+            }
+        }
+    }
+
+    let mut admin_broadcast_rx = crate::maintenance::AdminControls::global().subscribe();
+    let mut admin_abort_rx = crate::maintenance::AdminControls::global().subscribe_aborts();
+    let mut ops_alert_rx = crate::notifications::ops::OpsAlerts::global().subscribe();
+
+    // Main event loop: route inbound messages to agent channels
+    loop {
+        // Poll the inbound stream if it exists, otherwise yield a never-resolving future
+        let inbound_next = async {
+            match inbound_stream.as_mut() {
+                Some(stream) => stream.next().await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            Some(mut message) = inbound_next, if agents_initialized => {
+                let mut binding_settings: Option<crate::conversation::ConversationSettings> = None;
+                let current_tenants = tenants.load();
+                let tenant = crate::config::resolve_tenant(&current_tenants, &message);
+                let agent_id = if let Some(existing) = message.agent_id.as_ref() {
+                    existing.clone()
+                } else {
+                    let current_bindings = bindings.load();
+                    let resolved = match tenant {
+                        Some(tenant) => crate::config::resolve_agent_for_tenant(
+                            tenant,
+                            &current_bindings,
+                            &message,
+                        ),
+                        None => crate::config::resolve_agent_for_message(
+                            &current_bindings,
+                            &message,
+                            &default_agent_id,
+                        ),
+                    };
+                    let Some((resolved, matched_settings)) = resolved else {
+                        // Message suppressed by require_mention — drop it.
+                        continue;
+                    };
+                    binding_settings = matched_settings;
+                    message.agent_id = Some(resolved.clone());
+                    resolved
+                };
+
+                // Maintenance, rate limits and tenant budgets apply only once
+                // a binding has claimed the message, so traffic the agent
+                // would ignore (require_mention, unbound channels) never
+                // draws a notice or uses up the sender's allowance.
+                if let crate::maintenance::Decision::Paused { notify, notice } =
+                    crate::maintenance::AdminControls::global().check(&message)
+                {
+                    tracing::debug!(
+                        source = %message.source,
+                        conversation_id = %message.conversation_id,
+                        "maintenance mode on, dropping prompt"
+                    );
+                    if notify {
+                        let messaging = messaging_manager.clone();
+                        let locale = crate::config::binding_locale(&bindings.load(), &message);
+                        tokio::spawn(async move {
+                            let reply = crate::OutboundResponse::Ephemeral {
+                                text: crate::maintenance::paused_message(notice, &locale),
+                                user_id: message.sender_id.clone(),
+                            };
+                            if let Err(error) = messaging.respond(&message, reply).await {
+                                tracing::warn!(%error, "failed to send maintenance notice");
+                            }
+                        });
+                    }
+                    continue;
+                }
+                let decision = crate::rate_limit::PromptRateLimiter::global().check(
+                    &message,
+                    &agent_humans.load(),
+                    std::time::Instant::now(),
+                );
+                if let crate::rate_limit::Decision::Limited { retry_after, notify } = decision {
+                    tracing::debug!(
+                        source = %message.source,
+                        sender_id = %message.sender_id,
+                        conversation_id = %message.conversation_id,
+                        "prompt rate limited, dropping"
+                    );
+                    if notify {
+                        let messaging = messaging_manager.clone();
+                        let locale = crate::config::binding_locale(&bindings.load(), &message);
+                        tokio::spawn(async move {
+                            let reply = crate::OutboundResponse::Ephemeral {
+                                text: crate::rate_limit::slow_down_message(retry_after, &locale),
+                                user_id: message.sender_id.clone(),
+                            };
+                            if let Err(error) = messaging.respond(&message, reply).await {
+                                tracing::warn!(%error, "failed to send rate limit reply");
+                            }
+                        });
+                    }
+                    continue;
+                }
+                if let Some(tenant) = tenant
+                    && !matches!(message.content, crate::MessageContent::Deleted { .. })
+                {
+                    let user_id = format!("{}:{}", message.source, message.sender_id);
+                    let admission =
+                        tenant_usage.admit(tenant, &user_id, chrono::Utc::now().date_naive());
+                    if let Admission::Dropped { notify } = admission {
+                        if notify {
+                            let messaging = messaging_manager.clone();
+                            let locale = crate::config::binding_locale(&bindings.load(), &message);
+                            tokio::spawn(async move {
+                                let reply = crate::OutboundResponse::Ephemeral {
+                                    text: crate::prompts::messages::get(
+                                        &locale,
+                                        "tenant.budget_spent",
+                                    ),
+                                    user_id: message.sender_id.clone(),
+                                };
+                                if let Err(error) = messaging.respond(&message, reply).await {
+                                    tracing::warn!(%error, "failed to send budget notice");
+                                }
+                            });
+                        }
+                        continue;
+                    }
+                }
+
+                let conversation_id = message.conversation_id.clone();
+                let conversation_key =
+                    ActiveChannelKey::new(agent_id.to_string(), conversation_id.clone());
+                // DMs are private sessions keyed by the sender, unless the
+                // conversation is already running under its own key (a
+                // resumed worker pre-created it at startup).
+                let private_session = agents.get(&agent_id).and_then(|agent| {
+                    crate::conversation::private::session_key(
+                        &agent.deps.runtime_config.private_sessions.load(),
+                        &message,
+                    )
+                });
+                let channel_key = match &private_session {
+                    Some(session_key) if !active_channels.contains_key(&conversation_key) => {
+                        let channel_key =
+                            ActiveChannelKey::new(agent_id.to_string(), session_key.clone());
+                        private_session_keys.insert(conversation_key.clone(), channel_key.clone());
+                        channel_key
+                    }
+                    _ => conversation_key.clone(),
+                };
+
+                // Deletions only matter to a channel that is already running.
+                if matches!(message.content, crate::MessageContent::Deleted { .. })
+                    && !active_channels.contains_key(&channel_key)
+                {
+                    continue;
+                }
+
+                // In cluster mode, only the replica holding the session's
+                // lease handles it.
+                if let Some(leases) = &session_leases
+                    && !leases
+                        .claim(&crate::cluster::session_key(
+                            &channel_key.agent_id,
+                            &channel_key.conversation_id,
+                        ))
+                        .await
+                {
+                    continue;
+                }
+
+                // Find or create a channel for this conversation
+                if !active_channels.contains_key(&channel_key) {
+                    let Some(agent) = agents.get(&agent_id) else {
+                        tracing::warn!(
+                            agent_id = %agent_id,
+                            conversation_id = %conversation_id,
+                            "message routed to unknown agent, dropping"
+                        );
+                        continue;
+                    };
+
+                    // Create outbound response channel
+                    let (response_tx, response_rx) = mpsc::channel::<crate::RoutedResponse>(32);
+
+                    // Subscribe to the agent's event bus
+                    let event_rx = agent.deps.event_tx.subscribe();
+
+                    let channel_id: crate::ChannelId = Arc::from(conversation_id.as_str());
+
+                    let snapshot_store = agent
+                        .deps
+                        .runtime_config
+                        .prompt_snapshots
+                        .load()
+                        .as_ref()
+                        .clone();
+
+                    // Load per-conversation settings.
+                    // Resolution: per-channel DB override > binding defaults > agent defaults > system defaults
+                    let resolved_settings = if message.adapter.as_deref() == Some("portal") {
+                        // Portal: load from portal_conversations table.
+                        let store = crate::conversation::PortalConversationStore::new(
+                            agent.deps.sqlite_pool.clone(),
+                        );
+                        match store.get(agent_id.as_ref(), &conversation_id).await {
+                            Ok(Some(conv)) => {
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    conv.settings.as_ref(),
+                                    binding_settings.as_ref(),
+                                    None,
+                                )
+                            }
+                            Ok(None) => {
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    None,
+                                    binding_settings.as_ref(),
+                                    None,
+                                )
+                            }
+                            Err(error) => {
+                                tracing::warn!(
+                                    %error,
+                                    %conversation_id,
+                                    "failed to load portal conversation settings, falling back to binding defaults"
+                                );
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    None,
+                                    binding_settings.as_ref(),
+                                    None,
+                                )
+                            }
+                        }
+                    } else {
+                        // Platform channels: load from channel_settings table.
+                        let store = crate::conversation::ChannelSettingsStore::new(
+                            agent.deps.sqlite_pool.clone(),
+                        );
+                        let private_defaults = private_session.as_ref().map(|_| {
+                            crate::conversation::private::default_settings(
+                                &agent.deps.runtime_config.private_sessions.load(),
+                            )
+                        });
+                        let mut resolved = match store.get(agent_id.as_ref(), &conversation_id).await {
+                            Ok(Some(settings)) => {
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    Some(&settings),
+                                    binding_settings.as_ref(),
+                                    private_defaults.as_ref(),
+                                )
+                            }
+                            Ok(None) => {
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    None,
+                                    binding_settings.as_ref(),
+                                    private_defaults.as_ref(),
+                                )
+                            }
+                            Err(error) => {
+                                tracing::warn!(
+                                    %error,
+                                    %conversation_id,
+                                    "failed to load channel settings, falling back to binding defaults"
+                                );
+                                crate::conversation::settings::ResolvedConversationSettings::resolve(
+                                    None,
+                                    binding_settings.as_ref(),
+                                    private_defaults.as_ref(),
+                                )
+                            }
+                        };
+                        if private_session.is_some() {
+                            crate::conversation::private::restrict(&mut resolved);
+                        }
+                        resolved
+                    };
+
+                    let (mut channel, channel_tx) = crate::agent::channel::Channel::new(
+                        channel_id,
+                        agent.deps.clone(),
+                        response_tx,
+                        event_rx,
+                        agent.config.screenshot_dir(),
+                        agent.config.logs_dir(),
+                        snapshot_store,
+                        Some(api_state.live_worker_transcripts.clone()),
+                        resolved_settings,
+                        None, // no cron outcome for normal channels
+                    );
+                    let channel_registration_id = agent
+                        .deps
+                        .process_control_registry
+                        .register_channel(channel.id.clone(), channel.control_handle().downgrade())
+                        .await;
+
+                    // Register the channel's status block with the API for snapshot queries
+                    api_state.register_channel_status(
+                        conversation_id.clone(),
+                        channel.state.status_block.clone(),
+                    ).await;
+
+                    // Register the channel state for API-driven cancellation
+                    api_state.register_channel_state(
+                        conversation_id.clone(),
+                        channel.state.clone(),
+                    ).await;
+
+                    // Backfill recent message history from the platform.
+                    // The transcript is injected into the system prompt (not chat
+                    // history) so the LLM treats it as read-only system context
+                    // rather than actionable user messages.
+                    let backfill_count = agent.config.history_backfill_count();
+                    if backfill_count > 0 {
+                        match messaging_manager.fetch_history(&message, backfill_count).await {
+                            Ok(history_messages) => {
+                                if let Some(transcript) =
+                                    render_platform_history_backfill(&history_messages)
+                                {
+                                    channel.set_backfill_transcript(transcript);
+
+                                    tracing::info!(
+                                        conversation_id = %conversation_id,
+                                        message_count = history_messages.len(),
+                                        "backfilled channel history into system prompt"
+                                    );
+                                }
+                            }
+                            Err(error) => {
+                                tracing::warn!(%error, "failed to backfill channel history");
+                            }
+                        }
+                    }
+
+                    // Spawn the channel's event loop
+                    let cleanup_channel_id = conversation_id.clone();
+                    let process_control_registry = agent.deps.process_control_registry.clone();
+                    let api_state_for_cleanup = api_state.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = channel.run().await {
+                            tracing::error!(%error, "channel event loop failed");
+                        }
+
+                        let scoped_channel_id: crate::ChannelId =
+                            Arc::from(cleanup_channel_id.as_str());
+                        process_control_registry
+                            .unregister_channel(&scoped_channel_id, channel_registration_id)
+                            .await;
+                        api_state_for_cleanup
+                            .unregister_channel_status(&cleanup_channel_id)
+                            .await;
+                        api_state_for_cleanup
+                            .unregister_channel_state(&cleanup_channel_id)
+                            .await;
+                    });
+
+                    // Spawn outbound response routing: buffers response_rx in a
+                    // bounded stage, sends to the messaging adapter and forwards to SSE
+                    let messaging_for_outbound = messaging_manager.clone();
+                    let outbound_conversation_id = conversation_id.clone();
+                    let api_event_tx = api_state.event_tx.clone();
+                    let sse_agent_id = agent_id.to_string();
+                    let sse_channel_id = conversation_id.clone();
+                    let content_filter =
+                        crate::messaging::content_filter::OutboundFilter::for_agent(
+                            &tenants.load(),
+                            &agent_id,
+                            agent.deps.sqlite_pool.clone(),
+                        );
+                    let outbound = crate::messaging::backpressure::buffer_outbound(response_rx);
+                    let outbound_handle = tokio::spawn(async move {
+                        while let Some(routed) = outbound.pop().await {
+                            let crate::RoutedResponse { response, target } = routed;
+                            forward_sse_event(&api_event_tx, &sse_agent_id, &sse_channel_id, &response);
+                            route_outbound(
+                                &messaging_for_outbound,
+                                &target,
+                                content_filter.as_ref(),
+                                response,
+                            )
+                            .await;
+                        }
+                        tracing::debug!(
+                            conversation_id = %outbound_conversation_id,
+                            "outbound response channel closed"
+                        );
+                    });
+
+                    active_channels.insert(channel_key.clone(), ActiveChannel {
+                        message_tx: channel_tx,
+                        _outbound_handle: outbound_handle,
+                    });
+
+                    tracing::info!(
+                        conversation_id = %conversation_id,
+                        agent_id = %agent_id,
+                        private = private_session.is_some(),
+                        "new channel created"
+                    );
+                }
+
+                // Forward the message to the channel
+                if let Some(message_tx) = active_channels
+                    .get(&channel_key)
+                    .map(|active| active.message_tx.clone())
+                {
+                    let mut pending_delivery_failed = false;
+                    if let Some(pending_injections) = deferred_injections.remove(&conversation_key) {
+                        let mut remaining_injections = Vec::new();
+                        let mut pending_injections = pending_injections.into_iter();
+
+                        while let Some(injection_message) = pending_injections.next() {
+                            if let Err(error) = message_tx.send(injection_message).await {
+                                tracing::warn!(
+                                    conversation_id = %conversation_id,
+                                    agent_id = %agent_id,
+                                    "failed to deliver deferred injected message to channel"
+                                );
+                                remaining_injections.push(error.0);
+                                remaining_injections.extend(pending_injections);
+                                // Also re-queue the current inbound message so it isn't lost
+                                remaining_injections.push(message.clone());
+                                deferred_injections
+                                    .entry(conversation_key.clone())
+                                    .or_default()
+                                    .extend(remaining_injections);
+                                active_channels.remove(&channel_key);
+                                pending_delivery_failed = true;
+                                break;
+                            }
+                        }
+                    }
+
+                    if pending_delivery_failed {
+                        continue;
+                    }
+
+                    // Emit inbound message to SSE clients
+                    let sender_name = message.formatted_author.clone().or_else(|| {
+                        message
+                            .metadata
+                            .get("sender_display_name")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string())
+                    });
+                    let inbound_attachments: Vec<crate::agent::channel_attachments::SavedAttachmentMeta> =
+                        message.metadata
+                            .get("portal_attachment_metas")
+                            .and_then(|v| serde_json::from_value(v.clone()).ok())
+                            .unwrap_or_default();
+                    api_state.event_tx.send(crate::api::ApiEvent::InboundMessage {
+                        agent_id: agent_id.to_string(),
+                        channel_id: conversation_id.clone(),
+                        sender_name,
+                        sender_id: message.sender_id.clone(),
+                        text: message.content.to_string(),
+                        attachments: inbound_attachments,
+                    }).ok();
+
+                    if let Err(error) = message_tx.send(message).await {
+                        tracing::error!(
+                            conversation_id = %conversation_id,
+                            %error,
+                            "failed to forward message to channel"
+                        );
+                        active_channels.remove(&channel_key);
+                    }
+                }
+            }
+            // Admin broadcasts go to every agent's channels over its event bus.
+            received = admin_broadcast_rx.recv() => {
+                let text = match received {
+                    Ok(text) => text,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "admin broadcast receiver lagged");
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => continue,
+                };
+                for (agent_id, agent) in &agents {
+                    agent
+                        .deps
+                        .event_tx
+                        .send(crate::ProcessEvent::Broadcast {
+                            agent_id: agent_id.clone(),
+                            text: text.clone(),
+                        })
+                        .ok();
+                }
+                tracing::info!(agents = agents.len(), "delivered admin broadcast");
+            }
+            // Abort-all requests go to every agent's channels the same way.
+            received = admin_abort_rx.recv() => {
+                let backend = match received {
+                    Ok(backend) => backend,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "admin abort-all receiver lagged");
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => continue,
+                };
+                for (agent_id, agent) in &agents {
+                    agent
+                        .deps
+                        .event_tx
+                        .send(crate::ProcessEvent::AbortAll {
+                            agent_id: agent_id.clone(),
+                            backend: backend.clone(),
+                        })
+                        .ok();
+                }
+                tracing::info!(agents = agents.len(), ?backend, "delivered admin abort-all");
+            }
+            // Ops alerts go straight to the configured ops channel.
+            received = ops_alert_rx.recv() => {
+                let message = match received {
+                    Ok(message) => message,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "ops alert receiver lagged");
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => continue,
+                };
+                let messaging = messaging_manager.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = messaging
+                        .broadcast_proactive(
+                            &message.target.adapter,
+                            &message.target.target,
+                            crate::OutboundResponse::Text(message.text),
+                        )
+                        .await
+                    {
+                        tracing::warn!(
+                            %error,
+                            target = %message.target,
+                            "failed to post ops alert"
+                        );
+                    }
+                });
+            }
+            Some(agent) = agent_rx.recv() => {
+                tracing::info!(agent_id = %agent.id, "registering new agent in main loop");
+                agents.insert(agent.id.clone(), agent);
+            }
+            Some(agent_id) = agent_remove_rx.recv() => {
+                let key: crate::AgentId = Arc::from(agent_id.as_str());
+                if let Some(agent) = agents.remove(&key) {
+                    agent.deps.mcp_manager.disconnect_all().await;
+                    tracing::info!(agent_id = %agent_id, "removed agent from main loop");
+                } else {
+                    tracing::warn!(agent_id = %agent_id, "agent not found in main loop for removal");
+                }
+            }
+            // Cross-agent message injection (e.g. delegated task completion retrigger).
+            // Forwards the injected message to the target channel if it exists.
+            Some(injection) = injection_rx.recv() => {
+                let conversation_key = ActiveChannelKey::new(
+                    injection.agent_id.clone(),
+                    injection.conversation_id.clone(),
+                );
+                let channel_key = private_session_keys
+                    .get(&conversation_key)
+                    .cloned()
+                    .unwrap_or(conversation_key);
+
+                if let Some(message_tx) = active_channels
+                    .get(&channel_key)
+                    .map(|active| active.message_tx.clone())
+                {
+                    if let Err(error) = message_tx.send(injection.message.clone()).await {
+                        tracing::warn!(
+                            %error,
+                            conversation_id = %injection.conversation_id,
+                            agent_id = %injection.agent_id,
+                            "failed to forward injected message to channel"
+                        );
+                        active_channels.remove(&channel_key);
+                        queue_deferred_injection(&mut deferred_injections, injection);
+                    } else {
+                        tracing::info!(
+                            conversation_id = %injection.conversation_id,
+                            agent_id = %injection.agent_id,
+                            "forwarded cross-agent injection to active channel"
+                        );
+                    }
+                } else {
+                    queue_deferred_injection(&mut deferred_injections, injection);
+                    tracing::info!(
+                        conversation_id = %channel_key.conversation_id,
+                        agent_id = %channel_key.agent_id,
+                        "injection target channel not active, notification deferred until that exact channel resumes"
+                    );
+                }
+            }
+            Some(_event) = provider_rx.recv(), if !agents_initialized => {
+                tracing::info!("providers configured, initializing agents");
+
+                // Reload config from disk to pick up new keys
+                let new_config = if config_path.exists() {
+                    crate::config::Config::load_from_path(&config_path)
+                } else {
+                    let instance_dir = config_path.parent()
+                        .map(|p| p.to_path_buf())
+                        .unwrap_or_else(|| std::path::PathBuf::from("."));
+                    crate::config::Config::load_from_env(&instance_dir)
+                };
+
+                match new_config {
+                    Ok(new_config) => {
+                        api_state.set_agent_configs(configured_agent_infos(&new_config));
+
+                        if has_provider_credentials(&new_config.llm, &new_config.instance_dir) {
+                        // Refresh in-memory defaults so newly created agents
+                        // inherit the latest routing from the updated config.
+                        api_state.set_defaults_config(new_config.defaults.clone()).await;
+
+                        // Rebuild LlmManager with the new keys
+                        match crate::llm::LlmManager::with_instance_dir(
+                            new_config.llm.clone(),
+                            new_config.instance_dir.clone(),
+                        )
+                        .await
+                        {
+                            Ok(new_llm) => {
+                                let new_llm_manager = Arc::new(new_llm);
+                                // Update agent_humans from the reloaded config
+                                // before initialize_agents so agents see the
+                                // latest [[humans]] entries.
+                                agent_humans.store(Arc::new(new_config.humans.clone()));
+                                let mut new_watcher_agents = Vec::new();
+                                let mut new_discord_permissions = None;
+                                let mut new_slack_permissions = None;
+                                let mut new_telegram_permissions = None;
+                                let mut new_twitch_permissions = None;
+                                let mut new_mattermost_permissions = None;
+                                let mut new_signal_permissions = None;
+                                match initialize_agents(
+                                    &new_config,
+                                    &new_llm_manager,
+                                    &embedding_model,
+                                    &prompt_engine,
+                                    &api_state,
+                                    &mut agents,
+                                    &mut messaging_manager,
+                                    &mut inbound_stream,
+                                    &mut cron_schedulers_for_shutdown,
+                                    &mut _ingestion_handles,
+                                    &mut _cortex_handles,
+                                    &mut new_watcher_agents,
+                                    &mut new_discord_permissions,
+                                    &mut new_slack_permissions,
+                                    &mut new_telegram_permissions,
+                                    &mut new_twitch_permissions,
+                                    &mut new_mattermost_permissions,
+                                    &mut new_signal_permissions,
+                                    agent_links.clone(),
+                                    agent_humans.clone(),
+                                    injection_tx.clone(),
+                                    global_task_store.clone(),
+                                    global_wiki_store.clone(),
+                                    global_project_store.clone(),
+                                    global_notification_store.clone(),
+                                    &bootstrapped_store,
+                                    &bridges,
+                                    migrations,
+                                ).await {
+                                    Ok(()) => {
+                                        agents_initialized = true;
+                                        // Restart file watcher with the new agent data
+                                        _file_watcher = crate::config::spawn_file_watcher(
+                                            config_path.clone(),
+                                            new_config.instance_dir.clone(),
+                                            new_watcher_agents,
+                                            new_discord_permissions,
+                                            new_slack_permissions,
+                                            new_telegram_permissions,
+                                            new_twitch_permissions,
+                                            new_mattermost_permissions,
+                                            new_signal_permissions,
+                                            bindings.clone(),
+                                            tenants.clone(),
+                                            Some(messaging_manager.clone()),
+                                            new_llm_manager.clone(),
+                                            agent_links.clone(),
+                                            agent_humans.clone(),
+                                        );
+                                        tracing::info!("agents initialized after provider setup");
+                                    }
+                                    Err(error) => {
+                                        tracing::error!(%error, "failed to initialize agents after provider setup");
+                                    }
+                                }
+                            }
+                            Err(error) => {
+                                tracing::error!(%error, "failed to create LLM manager with new keys");
+                            }
+                        }
+                        } else {
+                            tracing::warn!("config reloaded but still no providers configured");
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "failed to reload config after provider setup");
+                    }
+                }
+            }
+            _ = shutdown_rx.wait_for(|shutdown| *shutdown) => {
+                tracing::info!("shutdown signal received via IPC");
+                break;
+            }
+            _ = &mut shutdown => {
+                tracing::info!("shutdown requested by embedding application");
+                break;
+            }
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("shutdown signal received");
+                break;
+            }
+        }
+    }
+
+    // Graceful shutdown
+    drop(active_channels);
+
+    // Hand sessions to the other replicas without waiting for expiry.
+    if let Some(leases) = &session_leases {
+        if let Some(renewal) = &lease_renewal {
+            renewal.abort();
+        }
+        leases.release_all().await;
+    }
+
+    for scheduler in &cron_schedulers_for_shutdown {
+        scheduler.shutdown().await;
+    }
+    drop(cron_schedulers_for_shutdown);
+
+    messaging_manager.shutdown().await;
+
+    for (agent_id, agent) in agents {
+        tracing::info!(%agent_id, "shutting down agent");
+        agent.deps.mcp_manager.disconnect_all().await;
+        agent.deps.write_behind.flush().await;
+        agent.db.close().await;
+    }
+
+    tracing::info!("spacebot stopped");
+
+    // Flush buffered OTLP spans before the process exits. Without this the
+    // batch exporter drops any spans recorded in the last export interval.
+    if let Some(provider) = otel_provider
+        && let Err(error) = provider.shutdown()
+    {
+        tracing::warn!(%error, "failed to flush OTel spans on shutdown");
+    }
+
+    crate::daemon::cleanup(&paths);
+
+    Ok(())
+}
+
+/// Initialize agents, messaging adapters, cron, cortex, and ingestion.
+/// Extracted so it can be called either at startup or after providers are configured.
+async fn wait_for_startup_warmup_tasks(
+    startup_warmup: &mut tokio::task::JoinSet<()>,
+    timeout: std::time::Duration,
+) -> bool {
+    let wait_all = async {
+        while let Some(result) = startup_warmup.join_next().await {
+            if let Err(error) = result {
+                if error.is_cancelled() {
+                    tracing::warn!(%error, "startup warmup task cancelled");
+                } else {
+                    tracing::error!(%error, "startup warmup task panicked");
+                }
+            }
+        }
+    };
+
+    if tokio::time::timeout(timeout, wait_all).await.is_err() {
+        startup_warmup.abort_all();
+        true
+    } else {
+        false
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
+async fn initialize_agents(
+    config: &crate::config::Config,
+    llm_manager: &Arc<crate::llm::LlmManager>,
+    embedding_model: &Arc<crate::memory::EmbeddingModel>,
+    prompt_engine: &crate::prompts::PromptEngine,
+    api_state: &Arc<crate::api::ApiState>,
+    agents: &mut HashMap<crate::AgentId, crate::Agent>,
+    messaging_manager: &mut Arc<crate::messaging::MessagingManager>,
+    inbound_stream: &mut Option<
+        std::pin::Pin<Box<dyn futures::Stream<Item = crate::InboundMessage> + Send>>,
+    >,
+    cron_schedulers_for_shutdown: &mut Vec<Arc<crate::cron::Scheduler>>,
+    ingestion_handles: &mut Vec<tokio::task::JoinHandle<()>>,
+    cortex_handles: &mut Vec<tokio::task::JoinHandle<()>>,
+    watcher_agents: &mut Vec<(
+        String,
+        std::path::PathBuf,
+        std::path::PathBuf,
+        Arc<crate::config::RuntimeConfig>,
+        Arc<crate::mcp::McpManager>,
+    )>,
+    discord_permissions: &mut Option<Arc<ArcSwap<crate::config::DiscordPermissions>>>,
+    slack_permissions: &mut Option<Arc<ArcSwap<crate::config::SlackPermissions>>>,
+    telegram_permissions: &mut Option<Arc<ArcSwap<crate::config::TelegramPermissions>>>,
+    twitch_permissions: &mut Option<Arc<ArcSwap<crate::config::TwitchPermissions>>>,
+    mattermost_permissions: &mut Option<Arc<ArcSwap<crate::config::MattermostPermissions>>>,
+    signal_permissions: &mut Option<Arc<ArcSwap<crate::config::SignalPermissions>>>,
+    agent_links: Arc<ArcSwap<Vec<crate::links::AgentLink>>>,
+    agent_humans: Arc<ArcSwap<Vec<crate::config::HumanDef>>>,
+    injection_tx: tokio::sync::mpsc::Sender<crate::ChannelInjection>,
+    global_task_store: Arc<crate::tasks::TaskStore>,
+    global_wiki_store: Arc<crate::wiki::WikiStore>,
+    global_project_store: Arc<crate::projects::ProjectStore>,
+    global_notification_store: Arc<crate::notifications::NotificationStore>,
+    bootstrapped_store: &Option<Arc<crate::secrets::store::SecretsStore>>,
+    bridges: &[Arc<dyn crate::messaging::traits::MessagingDyn>],
+    migrations: crate::db::Migrations,
+) -> anyhow::Result<()> {
+    let resolved_agents = config.resolve_agents();
+
+    // Build agent name map for inter-agent message routing
+    let agent_name_map: Arc<std::collections::HashMap<String, String>> = Arc::new(
+        resolved_agents
+            .iter()
+            .map(|a| {
+                let name = a.display_name.clone().unwrap_or_else(|| a.id.clone());
+                (a.id.clone(), name)
+            })
+            .collect(),
+    );
+
+    // Wake-dispatch infrastructure for dormant-mode agents. Always spawned
+    // so active-mode agents can also participate (cron / message wake hooks
+    // are mode-agnostic — `cortex::wake_one` is a no-op contention with the
+    // active loop's pickup, harmless either way). The registry lives on
+    // `api_state` so runtime agent-create / agent-delete paths can keep it
+    // in sync without going through main.
+    let wake_registry = api_state.wake_registry.clone();
+    let wake_tx = crate::agent::wake::spawn_wake_manager(wake_registry.clone());
+    api_state.wake_tx.store(Arc::new(Some(wake_tx.clone())));
+
+    for agent_config in &resolved_agents {
+        tracing::info!(agent_id = %agent_config.id, "initializing agent");
+
+        // Ensure agent directories exist
+        std::fs::create_dir_all(&agent_config.workspace).with_context(|| {
+            format!(
+                "failed to create workspace: {}",
+                agent_config.workspace.display()
+            )
+        })?;
+        std::fs::create_dir_all(&agent_config.data_dir).with_context(|| {
+            format!(
+                "failed to create data dir: {}",
+                agent_config.data_dir.display()
+            )
+        })?;
+        std::fs::create_dir_all(&agent_config.archives_dir).with_context(|| {
+            format!(
+                "failed to create archives dir: {}",
+                agent_config.archives_dir.display()
+            )
+        })?;
+        std::fs::create_dir_all(agent_config.ingest_dir()).with_context(|| {
+            format!(
+                "failed to create ingest dir: {}",
+                agent_config.ingest_dir().display()
+            )
+        })?;
+        std::fs::create_dir_all(agent_config.logs_dir()).with_context(|| {
+            format!(
+                "failed to create logs dir: {}",
+                agent_config.logs_dir().display()
+            )
+        })?;
+        std::fs::create_dir_all(agent_config.saved_dir()).with_context(|| {
+            format!(
+                "failed to create saved dir: {}",
+                agent_config.saved_dir().display()
+            )
+        })?;
+
+        // Per-agent database connections
+        let db = crate::db::Db::connect(&agent_config.data_dir, migrations)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to connect databases for agent '{}'",
+                    agent_config.id
+                )
+            })?;
+
+        let run_logger = crate::conversation::ProcessRunLogger::new(db.sqlite.clone());
+        let orphaned_workers = run_logger
+            .reconcile_running_workers_for_agent(
+                &agent_config.id,
+                "Worker interrupted: Spacebot restarted before completion.",
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to reconcile stale running workers for agent '{}'",
+                    agent_config.id
+                )
+            })?;
+        if orphaned_workers > 0 {
+            tracing::warn!(
+                agent_id = %agent_config.id,
+                orphaned_workers,
+                "marked stale running workers as failed during startup"
+            );
+        }
+        match global_task_store
+            .requeue_interrupted(&agent_config.id)
+            .await
+        {
+            Ok(task_numbers) if !task_numbers.is_empty() => {
+                tracing::info!(
+                    agent_id = %agent_config.id,
+                    ?task_numbers,
+                    "requeued interrupted tasks to resume from their checkpoints"
+                );
+            }
+            Ok(_) => {}
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    agent_id = %agent_config.id,
+                    "failed to requeue interrupted tasks"
+                );
+            }
+        }
+
+        // Per-agent settings store (redb-backed)
+        let settings_path = agent_config.data_dir.join("settings.redb");
+        let settings_store = Arc::new(
+            crate::settings::SettingsStore::new(&settings_path).with_context(|| {
+                format!(
+                    "failed to initialize settings store for agent '{}'",
+                    agent_config.id
+                )
+            })?,
+        );
+
+        // Per-agent prompt snapshot store (separate redb, easy to delete).
+        // Non-fatal: a corrupt/unwritable DB disables snapshotting for this agent.
+        let snapshot_path = agent_config.data_dir.join("prompt_snapshots.redb");
+        let prompt_snapshot_store =
+            match crate::agent::prompt_snapshot::PromptSnapshotStore::new(&snapshot_path) {
+                Ok(store) => Some(Arc::new(store)),
+                Err(error) => {
+                    tracing::warn!(
+                        agent_id = %agent_config.id,
+                        path = %snapshot_path.display(),
+                        %error,
+                        "failed to initialize prompt snapshot store; prompt snapshots disabled"
+                    );
+                    None
+                }
+            };
+
+        // Per-agent memory system
+        let memory_store =
+            crate::memory::MemoryStore::with_agent_id(db.sqlite.clone(), &agent_config.id);
+        let project_store = global_project_store.clone();
+        let embedding_table = crate::memory::EmbeddingTable::open_or_create(&db.lance)
+            .await
+            .with_context(|| {
+                format!("failed to init embeddings for agent '{}'", agent_config.id)
+            })?;
+
+        // Ensure FTS index exists for full-text search queries
+        if let Err(error) = embedding_table.ensure_fts_index().await {
+            tracing::warn!(%error, agent = %agent_config.id, "failed to create FTS index");
+        }
+
+        let memory_search = Arc::new(crate::memory::MemorySearch::new(
+            memory_store,
+            embedding_table,
+            embedding_model.clone(),
+        ));
+
+        // Working memory event log (temporal situational awareness).
+        let working_memory_timezone = {
+            let user_tz = agent_config.user_timezone.as_deref();
+            let cron_tz = agent_config.cron_timezone.as_deref();
+            user_tz
+                .or(cron_tz)
+                .and_then(|tz_name| tz_name.parse::<chrono_tz::Tz>().ok())
+                .unwrap_or(chrono_tz::Tz::UTC)
+        };
+        let working_memory =
+            crate::memory::WorkingMemoryStore::new(db.sqlite.clone(), working_memory_timezone);
+
+        // Per-agent control and memory event buses (broadcast fan-out).
+        let process_event_buses = crate::create_process_event_buses();
+        let event_tx = process_event_buses.control;
+        let memory_event_tx = process_event_buses.memory;
+        let tool_output_tx = process_event_buses.tool_output;
+
+        let agent_id: crate::AgentId = Arc::from(agent_config.id.as_str());
+        let mcp_manager = Arc::new(crate::mcp::McpManager::new(agent_config.mcp.clone()));
+        mcp_manager.connect_all().await;
+
+        // Scaffold identity templates if missing, then load.
+        // Identity files live in the agent root (identity_dir), outside the
+        // workspace sandbox boundary.
+        crate::identity::scaffold_identity_files(&agent_config.identity_dir)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to scaffold identity files for agent '{}'",
+                    agent_config.id
+                )
+            })?;
+        let identity = crate::identity::Identity::load(&agent_config.identity_dir).await;
+
+        // Load skills (instance-level, then workspace overrides)
+        let skills =
+            crate::skills::SkillSet::load(&config.skills_dir(), &agent_config.skills_dir()).await;
+
+        // Build the RuntimeConfig with all hot-reloadable values
+        let runtime_config = Arc::new(crate::config::RuntimeConfig::new(
+            &config.instance_dir,
+            agent_config,
+            &config.defaults,
+            prompt_engine.clone(),
+            identity,
+            skills,
+        ));
+
+        runtime_config.set_settings(settings_store.clone());
+        runtime_config
+            .public_url
+            .store(Arc::new(config.api.public_url.clone()));
+        runtime_config
+            .prompt_snapshots
+            .store(Arc::new(prompt_snapshot_store.clone()));
+        if let Err(error) = settings_store.set_worker_log_mode(config.defaults.worker_log_mode) {
+            tracing::warn!(%error, agent = %agent_config.id, "failed to set worker_log_mode from config");
+        }
+
+        // Share the instance-level secrets store with this agent.
+        if let Some(secrets_store) = bootstrapped_store {
+            runtime_config.set_secrets(secrets_store.clone());
+            crate::config::set_resolve_secrets_store(secrets_store.clone());
+        }
+
+        watcher_agents.push((
+            agent_config.id.clone(),
+            agent_config.workspace.clone(),
+            agent_config.identity_dir.clone(),
+            runtime_config.clone(),
+            mcp_manager.clone(),
+        ));
+
+        let sandbox = std::sync::Arc::new(
+            crate::sandbox::Sandbox::new(
+                runtime_config.sandbox.clone(),
+                agent_config.workspace.clone(),
+                &config.instance_dir,
+                agent_config.data_dir.clone(),
+                agent_id.clone(),
+            )
+            .await,
+        );
+
+        // Wire the instance-level secrets store into the sandbox for tool secret injection.
+        if let Some(secrets_store) = &bootstrapped_store {
+            sandbox.set_secrets_store(secrets_store.clone());
+        }
+
+        // Inject active project root paths into the sandbox allowlist so
+        // workers can access project directories even outside the workspace.
+        crate::projects::refresh_sandbox_project_paths(&project_store, &sandbox).await;
+
+        let deps = crate::AgentDeps {
+            agent_id: agent_id.clone(),
+            memory_search,
+            llm_manager: llm_manager.clone(),
+            mcp_manager,
+            task_store: global_task_store.clone(),
+            project_store: project_store.clone(),
+            cron_tool: None,
+            runtime_config,
+            event_tx,
+            memory_event_tx,
+            tool_output_tx,
+            sqlite_pool: db.sqlite.clone(),
+            write_behind: crate::conversation::WriteBehind::spawn(db.sqlite.clone()),
+            messaging_manager: None,
+            sandbox,
+            links: agent_links.clone(),
+            agent_names: agent_name_map.clone(),
+            humans: agent_humans.clone(),
+            process_control_registry: Arc::new(
+                crate::agent::process_control::ProcessControlRegistry::new(),
+            ),
+            injection_tx: injection_tx.clone(),
+            working_memory,
+            api_state: Some(api_state.clone()),
+            wiki_store: Some(global_wiki_store.clone()),
+            wake_tx: Some(wake_tx.clone()),
+        };
+
+        let agent = crate::Agent {
+            id: agent_id.clone(),
+            config: agent_config.clone(),
+            db,
+            deps,
+        };
+
+        // Register with the wake manager so external triggers can reach this agent.
+        wake_registry
+            .write()
+            .await
+            .insert(agent_id.clone(), agent.deps.clone());
+
+        tracing::info!(agent_id = %agent_config.id, "agent initialized");
+        agents.insert(agent_id, agent);
+    }
+
+    // Pre-register both sides of every link channel so they appear in each
+    // agent's channel list from boot. The actual Channel instances are spawned
+    // on-demand when the first message arrives; this just creates the DB records
+    // so the UI can display them.
+    {
+        let all_links = agent_links.load();
+        let empty_meta = std::collections::HashMap::new();
+        for link in all_links.iter() {
+            let from_channel = link.channel_id_for(&link.from_agent_id);
+            let to_channel = link.channel_id_for(&link.to_agent_id);
+
+            if let Some(agent) = agents.get(&Arc::from(link.from_agent_id.as_str())) {
+                let store = crate::conversation::ChannelStore::new(agent.db.sqlite.clone());
+                store.upsert(&from_channel, &empty_meta);
+            }
+            if let Some(agent) = agents.get(&Arc::from(link.to_agent_id.as_str())) {
+                let store = crate::conversation::ChannelStore::new(agent.db.sqlite.clone());
+                store.upsert(&to_channel, &empty_meta);
+            }
+        }
+        if !all_links.is_empty() {
+            tracing::info!(link_count = all_links.len(), "pre-registered link channels");
+        }
+    }
+
+    tracing::info!(agent_count = agents.len(), "all agents initialized");
+
+    // Record startup in each agent's working memory.
+    for agent in agents.values() {
+        agent
+            .deps
+            .working_memory
+            .emit(
+                crate::memory::WorkingMemoryEventType::System,
+                format!("Agent started ({})", agent.config.id),
+            )
+            .importance(0.3)
+            .record();
+    }
+
+    // Wire agent event streams, DB pools, and config summaries into the API server
+    {
+        let mut agent_pools = std::collections::HashMap::new();
+        let mut agent_configs = Vec::new();
+        let mut memory_searches = std::collections::HashMap::new();
+        let mut mcp_managers = std::collections::HashMap::new();
+        let mut agent_workspaces = std::collections::HashMap::new();
+        let mut agent_identity_dirs = std::collections::HashMap::new();
+        let mut agent_data_dirs = std::collections::HashMap::new();
+        let mut runtime_configs = std::collections::HashMap::new();
+        let mut sandboxes = std::collections::HashMap::new();
+        for (agent_id, agent) in agents.iter() {
+            let event_rx = agent.deps.event_tx.subscribe();
+            api_state.register_agent_events(agent_id.to_string(), event_rx);
+            let tool_output_rx = agent.deps.tool_output_tx.subscribe();
+            api_state.register_tool_output_stream(agent_id.to_string(), tool_output_rx);
+            agent_pools.insert(agent_id.to_string(), agent.db.sqlite.clone());
+            memory_searches.insert(agent_id.to_string(), agent.deps.memory_search.clone());
+            mcp_managers.insert(agent_id.to_string(), agent.deps.mcp_manager.clone());
+            agent_workspaces.insert(agent_id.to_string(), agent.config.workspace.clone());
+            agent_identity_dirs.insert(agent_id.to_string(), agent.config.identity_dir.clone());
+            agent_data_dirs.insert(agent_id.to_string(), agent.config.data_dir.clone());
+            runtime_configs.insert(agent_id.to_string(), agent.deps.runtime_config.clone());
+            sandboxes.insert(agent_id.to_string(), agent.deps.sandbox.clone());
+            agent_configs.push(crate::api::AgentInfo {
+                id: agent.config.id.clone(),
+                display_name: agent.config.display_name.clone(),
+                role: agent.config.role.clone(),
+                gradient_start: agent.config.gradient_start.clone(),
+                gradient_end: agent.config.gradient_end.clone(),
+                workspace: agent.config.workspace.to_string_lossy().to_string(),
+                context_window: agent.config.context_window,
+                max_turns: agent.config.max_turns,
+                max_concurrent_branches: agent.config.max_concurrent_branches,
+                max_concurrent_workers: agent.config.max_concurrent_workers,
+            });
+        }
+        api_state.set_agent_pools(agent_pools);
+        api_state.set_agent_configs(agent_configs);
+        api_state.set_memory_searches(memory_searches);
+        api_state.set_mcp_managers(mcp_managers);
+        api_state.set_project_store(global_project_store.clone());
+        api_state.set_runtime_configs(runtime_configs);
+        api_state.set_agent_workspaces(agent_workspaces);
+        api_state.set_agent_identity_dirs(agent_identity_dirs);
+        api_state.set_agent_data_dirs(agent_data_dirs);
+        api_state.set_sandboxes(sandboxes);
+        // Wire the instance-level secrets store into the API state.
+        if let Some(store) = &bootstrapped_store {
+            api_state.set_secrets_store(store.clone());
+        }
+        api_state.set_instance_dir(config.instance_dir.clone());
+    }
+
+    // Run a startup warmup pass for every agent before adapters begin receiving
+    // inbound traffic. This reduces first-message cold-start latency.
+    {
+        const STARTUP_WARMUP_WAIT_SECS: u64 = 30;
+        let mut startup_warmup = tokio::task::JoinSet::new();
+
+        for (agent_id, agent) in agents.iter() {
+            // Dormant agents stay cold at startup — running warmup here would
+            // touch model/embedding load paths the dormant-mode contract
+            // promises to skip until an explicit wake.
+            if agent.deps.runtime_config.cortex.load().mode.is_dormant() {
+                tracing::info!(agent_id = %agent_id, "startup warmup skipped: dormant");
+                continue;
+            }
+            let deps = agent.deps.clone();
+            let sqlite_pool = agent.db.sqlite.clone();
+            let agent_id = agent_id.clone();
+            startup_warmup.spawn(async move {
+                let logger = crate::agent::cortex::CortexLogger::new(sqlite_pool);
+                crate::agent::cortex::run_warmup_once(&deps, &logger, "startup_pre_adapter", false)
+                    .await;
+                let status = deps.runtime_config.warmup_status.load().as_ref().clone();
+                tracing::info!(
+                    agent_id = %agent_id,
+                    state = ?status.state,
+                    embedding_ready = status.embedding_ready,
+                    bulletin_age_secs = ?status.bulletin_age_secs,
+                    last_error = ?status.last_error,
+                    "startup warmup pass finished"
+                );
+            });
+        }
+
+        if wait_for_startup_warmup_tasks(
+            &mut startup_warmup,
+            std::time::Duration::from_secs(STARTUP_WARMUP_WAIT_SECS),
+        )
+        .await
+        {
+            tracing::warn!(
+                timeout_secs = STARTUP_WARMUP_WAIT_SECS,
+                "startup warmup wait timed out; cancelled unfinished startup warmup tasks and continuing startup"
+            );
+        }
+    }
+
+    // Initialize messaging adapters
+    let new_messaging_manager = crate::messaging::MessagingManager::new();
+    new_messaging_manager.set_redaction(&config.messaging.redaction);
+    new_messaging_manager.set_overflow(&config.messaging.overflow);
+    new_messaging_manager.set_links(&config.messaging.links);
+
+    // Shared Discord permissions (hot-reloadable via file watcher)
+    *discord_permissions = config.messaging.discord.as_ref().map(|discord_config| {
+        let perms =
+            crate::config::DiscordPermissions::from_config(discord_config, &config.bindings);
+        Arc::new(ArcSwap::from_pointee(perms))
+    });
+    if let Some(perms) = &*discord_permissions {
+        api_state.set_discord_permissions(perms.clone()).await;
+    }
+
+    if let Some(discord_config) = &config.messaging.discord
+        && discord_config.enabled
+    {
+        if !discord_config.token.is_empty() {
+            let adapter = crate::messaging::discord::DiscordAdapter::new(
+                "discord",
+                &discord_config.token,
+                discord_permissions.clone().ok_or_else(|| {
+                    anyhow::anyhow!("discord permissions not initialized when discord is enabled")
+                })?,
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+
+        for instance in discord_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.token.is_empty() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled discord instance with empty token");
+                continue;
+            }
+            let runtime_key =
+                crate::config::binding_runtime_adapter_key("discord", Some(instance.name.as_str()));
+            let perms = Arc::new(ArcSwap::from_pointee(
+                crate::config::DiscordPermissions::from_instance_config(instance, &config.bindings),
+            ));
+            let adapter =
+                crate::messaging::discord::DiscordAdapter::new(runtime_key, &instance.token, perms);
+            new_messaging_manager.register(adapter).await;
+        }
+    }
+
+    // Shared Slack permissions (hot-reloadable via file watcher)
+    *slack_permissions = config.messaging.slack.as_ref().map(|slack_config| {
+        let perms = crate::config::SlackPermissions::from_config(slack_config, &config.bindings);
+        Arc::new(ArcSwap::from_pointee(perms))
+    });
+    if let Some(perms) = &*slack_permissions {
+        api_state.set_slack_permissions(perms.clone()).await;
+    }
+
+    if let Some(slack_config) = &config.messaging.slack
+        && slack_config.enabled
+    {
+        if !slack_config.bot_token.is_empty() && !slack_config.app_token.is_empty() {
+            match crate::messaging::slack::SlackAdapter::new(
+                "slack",
+                &slack_config.bot_token,
+                &slack_config.app_token,
+                slack_permissions.clone().ok_or_else(|| {
+                    anyhow::anyhow!("slack permissions not initialized when slack is enabled")
+                })?,
+                slack_config.commands.clone(),
+            ) {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, "failed to build slack adapter");
+                }
+            }
+        }
+
+        for instance in slack_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.bot_token.is_empty() || instance.app_token.is_empty() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled slack instance with missing tokens");
+                continue;
+            }
+            let runtime_key =
+                crate::config::binding_runtime_adapter_key("slack", Some(instance.name.as_str()));
+            let perms = Arc::new(ArcSwap::from_pointee(
+                crate::config::SlackPermissions::from_instance_config(instance, &config.bindings),
+            ));
+            match crate::messaging::slack::SlackAdapter::new(
+                runtime_key,
+                &instance.bot_token,
+                &instance.app_token,
+                perms,
+                instance.commands.clone(),
+            ) {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, adapter = %instance.name, "failed to build named slack adapter");
+                }
+            }
+        }
+    }
+
+    // Shared Telegram permissions (hot-reloadable via file watcher)
+    *telegram_permissions = config.messaging.telegram.as_ref().map(|telegram_config| {
+        let perms =
+            crate::config::TelegramPermissions::from_config(telegram_config, &config.bindings);
+        Arc::new(ArcSwap::from_pointee(perms))
+    });
+
+    if let Some(telegram_config) = &config.messaging.telegram
+        && telegram_config.enabled
+    {
+        if !telegram_config.token.is_empty() {
+            let adapter = crate::messaging::telegram::TelegramAdapter::new(
+                "telegram",
+                &telegram_config.token,
+                telegram_permissions.clone().ok_or_else(|| {
+                    anyhow::anyhow!("telegram permissions not initialized when telegram is enabled")
+                })?,
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+
+        for instance in telegram_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.token.is_empty() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled telegram instance with empty token");
+                continue;
+            }
+            let runtime_key = crate::config::binding_runtime_adapter_key(
+                "telegram",
+                Some(instance.name.as_str()),
+            );
+            let perms = Arc::new(ArcSwap::from_pointee(
+                crate::config::TelegramPermissions::from_instance_config(
+                    instance,
+                    &config.bindings,
+                ),
+            ));
+            let adapter = crate::messaging::telegram::TelegramAdapter::new(
+                runtime_key,
+                &instance.token,
+                perms,
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+    }
+
+    if let Some(email_config) = &config.messaging.email
+        && email_config.enabled
+    {
+        if !email_config.imap_host.is_empty() || email_config.webhook.is_some() {
+            match crate::messaging::email::EmailAdapter::from_config(email_config) {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, "failed to build email adapter");
+                }
+            }
+        }
+
+        for instance in email_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.imap_host.is_empty() && instance.webhook.is_none() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled email instance with empty credentials");
+                continue;
+            }
+            let runtime_key =
+                crate::config::binding_runtime_adapter_key("email", Some(instance.name.as_str()));
+            match crate::messaging::email::EmailAdapter::from_instance_config(runtime_key, instance)
+            {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, adapter = %instance.name, "failed to build named email adapter");
+                }
+            }
+        }
+    }
+
+    if let Some(webhook_config) = &config.messaging.webhook
+        && webhook_config.enabled
+    {
+        let adapter = crate::messaging::webhook::WebhookAdapter::new(
+            webhook_config.port,
+            &webhook_config.bind,
+            webhook_config.auth_token.clone(),
+        );
+        new_messaging_manager.register(adapter).await;
+    }
+
+    if let Some(irc_config) = &config.messaging.irc
+        && irc_config.enabled
+    {
+        if irc_config.server.is_empty() || irc_config.nickname.is_empty() {
+            tracing::warn!("skipping enabled irc adapter with empty server or nickname");
+        } else {
+            let adapter = crate::messaging::irc::IrcAdapter::new(irc_config.clone());
+            new_messaging_manager.register(adapter).await;
+        }
+    }
+
+    // Shared Twitch permissions (hot-reloadable via file watcher)
+    *twitch_permissions = config.messaging.twitch.as_ref().map(|twitch_config| {
+        let perms = crate::config::TwitchPermissions::from_config(twitch_config, &config.bindings);
+        Arc::new(ArcSwap::from_pointee(perms))
+    });
+
+    if let Some(twitch_config) = &config.messaging.twitch
+        && twitch_config.enabled
+    {
+        let twitch_token_path = config.instance_dir.join("twitch_token.json");
+        if !twitch_config.username.is_empty() && !twitch_config.oauth_token.is_empty() {
+            let adapter = crate::messaging::twitch::TwitchAdapter::new(
+                "twitch",
+                &twitch_config.username,
+                &twitch_config.oauth_token,
+                twitch_config.client_id.clone(),
+                twitch_config.client_secret.clone(),
+                twitch_config.refresh_token.clone(),
+                Some(twitch_token_path),
+                twitch_config.channels.clone(),
+                twitch_config.trigger_prefix.clone(),
+                twitch_permissions.clone().ok_or_else(|| {
+                    anyhow::anyhow!("twitch permissions not initialized when twitch is enabled")
+                })?,
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+
+        for instance in twitch_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.username.is_empty() || instance.oauth_token.is_empty() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled twitch instance with missing credentials");
+                continue;
+            }
+            let runtime_key =
+                crate::config::binding_runtime_adapter_key("twitch", Some(instance.name.as_str()));
+            let token_file_name = {
+                use std::hash::{Hash, Hasher};
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                instance.name.hash(&mut hasher);
+                let name_hash = hasher.finish();
+                format!(
+                    "twitch_token_{}_{name_hash:016x}.json",
+                    instance
+                        .name
+                        .chars()
+                        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+                        .collect::<String>()
+                )
+            };
+            let token_path = config.instance_dir.join(token_file_name);
+            let perms = Arc::new(ArcSwap::from_pointee(
+                crate::config::TwitchPermissions::from_instance_config(instance, &config.bindings),
+            ));
+            let adapter = crate::messaging::twitch::TwitchAdapter::new(
+                runtime_key,
+                &instance.username,
+                &instance.oauth_token,
+                instance.client_id.clone(),
+                instance.client_secret.clone(),
+                instance.refresh_token.clone(),
+                Some(token_path),
+                instance.channels.clone(),
+                instance.trigger_prefix.clone(),
+                perms,
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+    }
+
+    // Shared Mattermost permissions (hot-reloadable via file watcher)
+    *mattermost_permissions = config
+        .messaging
+        .mattermost
+        .as_ref()
+        .map(|mattermost_config| {
+            let perms = crate::config::MattermostPermissions::from_config(
+                mattermost_config,
+                &config.bindings,
+            );
+            Arc::new(ArcSwap::from_pointee(perms))
+        });
+
+    if let Some(mattermost_config) = &config.messaging.mattermost
+        && mattermost_config.enabled
+    {
+        if !mattermost_config.base_url.is_empty() && !mattermost_config.token.is_empty() {
+            match crate::messaging::mattermost::MattermostAdapter::new(
+                "mattermost",
+                &mattermost_config.base_url,
+                mattermost_config.token.as_str(),
+                mattermost_config.team_id.as_deref().map(Arc::from),
+                mattermost_config.max_attachment_bytes,
+                mattermost_permissions.clone().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "mattermost permissions not initialized when mattermost is enabled"
+                    )
+                })?,
+            ) {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, "failed to create mattermost adapter");
+                }
+            }
+        }
+
+        for instance in mattermost_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.base_url.is_empty() || instance.token.is_empty() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled mattermost instance with missing credentials");
+                continue;
+            }
+            let runtime_key = crate::config::binding_runtime_adapter_key(
+                "mattermost",
+                Some(instance.name.as_str()),
+            );
+            let perms = Arc::new(ArcSwap::from_pointee(
+                crate::config::MattermostPermissions::from_instance_config(
+                    instance,
+                    &config.bindings,
+                ),
+            ));
+            match crate::messaging::mattermost::MattermostAdapter::new(
+                runtime_key,
+                &instance.base_url,
+                instance.token.as_str(),
+                instance.team_id.as_deref().map(Arc::from),
+                instance.max_attachment_bytes,
+                perms,
+            ) {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, adapter = %instance.name, "failed to create named mattermost adapter");
+                }
+            }
+        }
+    }
+
+    // Shared Signal permissions (hot-reloadable via file watcher)
+    *signal_permissions = config.messaging.signal.as_ref().map(|signal_config| {
+        let perms = crate::config::SignalPermissions::from_config(signal_config);
+        Arc::new(ArcSwap::from_pointee(perms))
+    });
+    if let Some(perms) = &*signal_permissions {
+        api_state.set_signal_permissions(perms.clone()).await;
+    }
+
+    // Signal: start default adapter (requires root enabled) and named instances (independent).
+    // Unlike Discord/Telegram where named instances inherit the root enabled gate,
+    // Signal named instances start independently when they have valid credentials
+    // and their own enabled flag is set. This allows running multiple Signal accounts
+    // without needing a "default" account enabled.
+    let tmp_dir = config.instance_dir.join("tmp");
+    if let Some(signal_config) = &config.messaging.signal {
+        // Start default adapter only if root is enabled AND has credentials
+        if signal_config.enabled
+            && !signal_config.http_url.is_empty()
+            && !signal_config.account.is_empty()
+        {
+            let adapter = crate::messaging::signal::SignalAdapter::new(
+                "signal",
+                &signal_config.http_url,
+                &signal_config.account,
+                signal_config.ignore_stories,
+                signal_permissions.clone().ok_or_else(|| {
+                    anyhow::anyhow!("signal permissions not initialized when signal is enabled")
+                })?,
+                tmp_dir.clone(),
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+
+        // Start named instances regardless of root enabled flag (as long as config exists)
+        for instance in signal_config
+            .instances
+            .iter()
+            .filter(|instance| instance.enabled)
+        {
+            if instance.http_url.is_empty() || instance.account.is_empty() {
+                tracing::warn!(adapter = %instance.name, "skipping enabled signal instance with missing credentials");
+                continue;
+            }
+            let runtime_key =
+                crate::config::binding_runtime_adapter_key("signal", Some(instance.name.as_str()));
+            let perms = Arc::new(ArcSwap::from_pointee(
+                crate::config::SignalPermissions::from_instance_config(instance),
+            ));
+            let adapter = crate::messaging::signal::SignalAdapter::new(
+                runtime_key,
+                &instance.http_url,
+                &instance.account,
+                instance.ignore_stories,
+                perms,
+                tmp_dir.clone(),
+            );
+            new_messaging_manager.register(adapter).await;
+        }
+    }
+
+    let portal_agent_pools = agents
+        .iter()
+        .map(|(agent_id, agent)| (agent_id.to_string(), agent.db.sqlite.clone()))
+        .collect();
+    let portal_adapter = Arc::new(crate::messaging::portal::PortalAdapter::new(
+        portal_agent_pools,
+    ));
+    portal_adapter.set_event_tx(api_state.event_tx.clone());
+    new_messaging_manager
+        .register_shared(portal_adapter.clone())
+        .await;
+    api_state.set_portal_adapter(portal_adapter);
+
+    for bridge in bridges {
+        new_messaging_manager.register_dyn(bridge.clone()).await;
+    }
+
+    *messaging_manager = Arc::new(new_messaging_manager);
+    api_state
+        .set_messaging_manager(messaging_manager.clone())
+        .await;
+
+    // Start all messaging adapters and get the merged inbound stream
+    let new_inbound = messaging_manager
+        .start()
+        .await
+        .context("failed to start messaging adapters")?;
+    *inbound_stream = Some(new_inbound);
+
+    tracing::info!("messaging adapters started");
+
+    // Initialize cron schedulers for each agent
+    let mut cron_stores_map = std::collections::HashMap::new();
+    let mut cron_schedulers_map = std::collections::HashMap::new();
+
+    for (agent_id, agent) in agents.iter_mut() {
+        let store = Arc::new(crate::cron::CronStore::new(agent.db.sqlite.clone()));
+        agent.deps.messaging_manager = Some(messaging_manager.clone());
+
+        // Seed cron jobs from config into the database
+        for cron_def in &agent.config.cron {
+            let cron_config = crate::cron::CronConfig {
+                id: cron_def.id.clone(),
+                prompt: cron_def.prompt.clone(),
+                cron_expr: cron_def.cron_expr.clone(),
+                interval_secs: cron_def.interval_secs,
+                delivery_target: cron_def.delivery_target.clone(),
+                active_hours: cron_def.active_hours,
+                enabled: cron_def.enabled,
+                run_once: cron_def.run_once,
+                next_run_at: None,
+                timeout_secs: cron_def.timeout_secs,
+            };
+            if let Err(error) = store.save(&cron_config).await {
+                tracing::warn!(
+                    agent_id = %agent_id,
+                    cron_id = %cron_def.id,
+                    %error,
+                    "failed to seed cron config"
+                );
+            }
+        }
+
+        // Load all enabled cron jobs and start the scheduler
+        let cron_context = crate::cron::CronContext {
+            deps: agent.deps.clone(),
+            screenshot_dir: agent.config.screenshot_dir(),
+            logs_dir: agent.config.logs_dir(),
+            messaging_manager: messaging_manager.clone(),
+            store: store.clone(),
+        };
+
+        let scheduler = Arc::new(crate::cron::Scheduler::new(cron_context));
+
+        // Make cron store and scheduler available via RuntimeConfig
+        agent
+            .deps
+            .runtime_config
+            .set_cron(store.clone(), scheduler.clone());
+
+        match store.load_all().await {
+            Ok(configs) => {
+                // Load last execution times so interval-based jobs can anchor
+                // their first tick to the previous run, surviving restarts.
+                let last_times = match store.last_execution_times().await {
+                    Ok(times) => times,
+                    Err(error) => {
+                        tracing::warn!(agent_id = %agent_id, %error, "failed to load cron last execution times");
+                        std::collections::HashMap::new()
+                    }
+                };
+                for cron_config in configs {
+                    let anchor = last_times.get(&cron_config.id).map(String::as_str);
+                    if let Err(error) = scheduler.register_with_anchor(cron_config, anchor).await {
+                        tracing::warn!(agent_id = %agent_id, %error, "failed to register cron job");
+                    }
+                }
+            }
+            Err(error) => {
+                tracing::warn!(agent_id = %agent_id, %error, "failed to load cron jobs from database");
+            }
+        }
+
+        // Store cron tool on deps so each channel can register it on its own tool server
+        let cron_tool = crate::tools::CronTool::new(
+            store.clone(),
+            scheduler.clone(),
+            messaging_manager.clone(),
+        );
+        agent.deps.cron_tool = Some(cron_tool);
+
+        cron_stores_map.insert(agent_id.to_string(), store);
+        cron_schedulers_map.insert(agent_id.to_string(), scheduler.clone());
+        cron_schedulers_for_shutdown.push(scheduler);
+        tracing::info!(agent_id = %agent_id, "cron scheduler started");
+    }
+
+    // Set cron stores and schedulers on the API state
+    api_state.set_cron_stores(cron_stores_map);
+    api_state.set_cron_schedulers(cron_schedulers_map);
+    tracing::info!("cron stores and schedulers registered with API state");
+
+    // Start memory ingestion loops for each agent
+    for (agent_id, agent) in agents.iter() {
+        let ingestion_config = **agent.deps.runtime_config.ingestion.load();
+        if ingestion_config.enabled {
+            let handle = crate::agent::ingestion::spawn_ingestion_loop(
+                agent.config.ingest_dir(),
+                agent.deps.clone(),
+            );
+            ingestion_handles.push(handle);
+            tracing::info!(agent_id = %agent_id, "memory ingestion loop started");
+        }
+    }
+
+    // Start cortex warmup, runtime, and association loops for each agent.
+    // Skip every loop when the agent is in Dormant mode — those agents wake
+    // only on external triggers (cross-agent message, cron fire, admin API).
+    for (agent_id, agent) in agents.iter() {
+        let cortex_mode = agent.deps.runtime_config.cortex.load().mode;
+        if cortex_mode.is_dormant() {
+            tracing::info!(
+                agent_id = %agent_id,
+                "cortex loops skipped: agent is in dormant mode"
+            );
+            continue;
+        }
+
+        let cortex_logger = crate::agent::cortex::CortexLogger::new(agent.db.sqlite.clone())
+            .with_notifications(global_notification_store.clone(), agent_id.to_string());
+        let warmup_handle =
+            crate::agent::cortex::spawn_warmup_loop(agent.deps.clone(), cortex_logger.clone());
+        cortex_handles.push(warmup_handle);
+        tracing::info!(agent_id = %agent_id, "warmup loop started");
+
+        let cortex_handle =
+            crate::agent::cortex::spawn_cortex_loop(agent.deps.clone(), cortex_logger.clone());
+        cortex_handles.push(cortex_handle);
+        tracing::info!(agent_id = %agent_id, "cortex loop started");
+
+        let association_handle =
+            crate::agent::cortex::spawn_association_loop(agent.deps.clone(), cortex_logger);
+        cortex_handles.push(association_handle);
+        tracing::info!(agent_id = %agent_id, "cortex association loop started");
+
+        let ready_task_handle = crate::agent::cortex::spawn_ready_task_loop(
+            agent.deps.clone(),
+            crate::agent::cortex::CortexLogger::new(agent.db.sqlite.clone())
+                .with_notifications(global_notification_store.clone(), agent_id.to_string()),
+        );
+        cortex_handles.push(ready_task_handle);
+        tracing::info!(agent_id = %agent_id, "cortex ready-task loop started");
+    }
+
+    // Record tool calls and permission decisions into each agent's audit
+    // log, and tool durations and outcomes for `/stats tools`.
+    for (agent_id, agent) in agents.iter() {
+        let handle = crate::audit::spawn_audit_recorder(
+            agent_id.clone(),
+            agent.deps.event_tx.subscribe(),
+            agent.db.sqlite.clone(),
+        );
+        cortex_handles.push(handle);
+        let handle = crate::tool_stats::spawn_tool_stats_recorder(
+            agent_id.clone(),
+            agent.deps.event_tx.subscribe(),
+            agent.db.sqlite.clone(),
+        );
+        cortex_handles.push(handle);
+    }
+
+    // POST session lifecycle events to each agent's outgoing webhooks, and
+    // raise ops alerts for failed sessions.
+    for (agent_id, agent) in agents.iter() {
+        let handle = crate::notifications::webhook::spawn_webhook_notifier(
+            agent_id.clone(),
+            agent.deps.event_tx.subscribe(),
+            agent.deps.runtime_config.clone(),
+        );
+        cortex_handles.push(handle);
+        let handle = crate::notifications::ops::spawn_ops_alert_recorder(
+            agent_id.clone(),
+            agent.deps.event_tx.subscribe(),
+        );
+        cortex_handles.push(handle);
+    }
+
+    // Hand session and tool-error events to registered plugins.
+    for (agent_id, agent) in agents.iter() {
+        let handle =
+            crate::plugins::spawn_event_dispatch(agent_id.clone(), agent.deps.event_tx.subscribe());
+        cortex_handles.push(handle);
+    }
+
+    // Attach read-only mirrors to OpenCode backends flagged with `mirror`.
+    // A backend shared by several agents is mirrored once, into the first
+    // agent that has it configured, so sessions aren't recorded twice.
+    let mut mirrored_backends = std::collections::HashSet::new();
+    let mut mirror_targets = Vec::new();
+    for (agent_id, agent) in agents.iter() {
+        let opencode_config = agent.deps.runtime_config.opencode.load();
+        for backend in opencode_config
+            .backends
+            .iter()
+            .filter(|backend| backend.mirror)
+        {
+            if !mirrored_backends.insert(backend.url.clone()) {
+                continue;
+            }
+            tracing::info!(
+                agent_id = %agent_id,
+                backend = %backend.name,
+                "opencode mirror started"
+            );
+            mirror_targets.push(crate::opencode::mirror::MirrorTarget {
+                agent_id: agent_id.clone(),
+                backend: backend.clone(),
+                permissions: opencode_config.permissions.clone(),
+                pool: agent.db.sqlite.clone(),
+            });
+        }
+    }
+    if !mirror_targets.is_empty() {
+        cortex_handles.push(crate::opencode::mirror::spawn_mirrors(mirror_targets));
+    }
+
+    // Spawn the instance-wide memory janitor when configured. Required for
+    // dormant-mode agents (their cortex loop never runs maintenance);
+    // additive on active-mode agents (idempotent).
+    if config.memory_janitor.enabled {
+        let janitor_handle = crate::agent::maintenance::spawn_memory_janitor(
+            wake_registry.clone(),
+            config.memory_janitor.interval_secs,
+        );
+        cortex_handles.push(janitor_handle);
+        tracing::info!(
+            interval_secs = config.memory_janitor.interval_secs,
+            "memory janitor started"
+        );
+    }
+
+    // Post the weekly spend report when a target is configured.
+    if let Some(target) = &config.cost_report.post_to {
+        cortex_handles.push(crate::cost_report::spawn_weekly_cost_report(
+            wake_registry.clone(),
+            config.cost_report.clone(),
+        ));
+        tracing::info!(
+            %target,
+            weekday = %config.cost_report.weekday,
+            hour = config.cost_report.hour,
+            "weekly cost report scheduled"
+        );
+    }
+
+    // Post the weekly activity digest when a target is configured.
+    if let Some(target) = &config.digest.post_to {
+        cortex_handles.push(crate::digest::spawn_weekly_digest(
+            wake_registry.clone(),
+            config.digest.clone(),
+        ));
+        tracing::info!(
+            %target,
+            weekday = %config.digest.weekday,
+            hour = config.digest.hour,
+            "weekly digest scheduled"
+        );
+    }
+
+    // Create cortex chat sessions for each agent
+    {
+        let mut sessions = std::collections::HashMap::new();
+        for (agent_id, agent) in agents.iter() {
+            let browser_config = (**agent.deps.runtime_config.browser_config.load()).clone();
+            let brave_search_key = (**agent.deps.runtime_config.brave_search_key.load()).clone();
+            let conversation_logger =
+                crate::conversation::history::ConversationLogger::new(agent.db.sqlite.clone());
+            let channel_store = crate::conversation::ChannelStore::new(agent.db.sqlite.clone());
+            let run_logger = crate::conversation::ProcessRunLogger::new(agent.db.sqlite.clone());
+            let cortex_ctx = crate::agent::cortex_chat::CortexChatSession::create_context();
+            #[allow(deprecated)] // Cortex chat is legacy — being replaced by Channel Settings
+            let tool_server = crate::tools::create_cortex_chat_tool_server(
+                agent.deps.agent_id.clone(),
+                agent.deps.clone(),
+                agent.deps.task_store.clone(),
+                agent.deps.memory_search.clone(),
+                agent.deps.memory_event_tx.clone(),
+                conversation_logger,
+                channel_store,
+                run_logger,
+                browser_config,
+                agent.config.screenshot_dir(),
+                brave_search_key,
+                agent.deps.runtime_config.workspace_dir.clone(),
+                agent.deps.sandbox.clone(),
+                agent.deps.runtime_config.clone(),
+                api_state.clone(),
+                Some(cortex_ctx.clone()),
+            );
+            // Add factory tools to the cortex chat tool server
+            let factory_enabled = match crate::tools::add_factory_tools(
+                &tool_server,
+                api_state.clone(),
+                agent.deps.memory_search.clone(),
+            )
+            .await
+            {
+                Ok(()) => true,
+                Err(error) => {
+                    tracing::warn!(%error, agent_id = %agent_id, "failed to add factory tools to cortex chat");
+                    false
+                }
+            };
+
+            let store = crate::agent::cortex_chat::CortexChatStore::new(agent.db.sqlite.clone());
+            let session = crate::agent::cortex_chat::CortexChatSession::new(
+                agent.deps.clone(),
+                tool_server,
+                store,
+                cortex_ctx,
+            )
+            .with_factory(factory_enabled);
+            let session = std::sync::Arc::new(session);
+            session.start_event_loop();
+            sessions.insert(agent_id.to_string(), session);
+        }
+        api_state.set_cortex_chat_sessions(sessions);
+        tracing::info!("cortex chat sessions initialized");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ActiveChannelKey, Admission, TenantUsage, queue_deferred_injection,
+        wait_for_startup_warmup_tasks,
+    };
+    use crate::{ChannelInjection, InboundMessage, MessageContent};
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use std::future::pending;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn tenant_budget_resets_each_day() {
+        let tenant = crate::config::TenantConfig {
+            id: "acme".into(),
+            agent_id: "acme".into(),
+            discord_guild_ids: vec!["1".into()],
+            slack_workspace_ids: Vec::new(),
+            daily_message_budget: Some(2),
+            content_filters: Vec::new(),
+        };
+        let monday = chrono::NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let tuesday = monday.succ_opt().unwrap();
+        let mut usage = TenantUsage::default();
+
+        assert_eq!(
+            usage.admit(&tenant, "discord:1", monday),
+            Admission::Accepted
+        );
+        assert_eq!(
+            usage.admit(&tenant, "discord:2", monday),
+            Admission::Accepted
+        );
+        assert_eq!(
+            usage.admit(&tenant, "discord:1", monday),
+            Admission::Dropped { notify: true }
+        );
+        assert_eq!(
+            usage.admit(&tenant, "discord:1", monday),
+            Admission::Dropped { notify: false }
+        );
+        assert_eq!(
+            usage.admit(&tenant, "discord:2", monday),
+            Admission::Dropped { notify: true }
+        );
+        assert_eq!(
+            usage.admit(&tenant, "discord:1", tuesday),
+            Admission::Accepted
+        );
+    }
+
+    #[tokio::test]
+    async fn startup_warmup_wait_returns_false_when_tasks_finish_in_time() {
+        let mut tasks = tokio::task::JoinSet::new();
+        tasks.spawn(async {});
+        let timed_out = wait_for_startup_warmup_tasks(&mut tasks, Duration::from_millis(50)).await;
+        assert!(!timed_out);
+    }
+
+    #[tokio::test]
+    async fn startup_warmup_wait_returns_true_when_timeout_expires() {
+        let mut tasks = tokio::task::JoinSet::new();
+        tasks.spawn(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        });
+        let timed_out = wait_for_startup_warmup_tasks(&mut tasks, Duration::from_millis(5)).await;
+        assert!(timed_out);
+    }
+
+    #[tokio::test]
+    async fn startup_warmup_wait_aborts_timed_out_task_and_releases_lock() {
+        let warmup_lock = Arc::new(tokio::sync::Mutex::new(()));
+        let mut tasks = tokio::task::JoinSet::new();
+        let warmup_lock_for_task = Arc::clone(&warmup_lock);
+        let (locked_tx, locked_rx) = tokio::sync::oneshot::channel();
+        tasks.spawn(async move {
+            let _guard = warmup_lock_for_task.lock().await;
+            locked_tx.send(()).ok();
+            pending::<()>().await;
+        });
+
+        tokio::time::timeout(Duration::from_millis(50), locked_rx)
+            .await
+            .expect("task should acquire lock")
+            .expect("lock signal should send");
+
+        let timed_out = wait_for_startup_warmup_tasks(&mut tasks, Duration::from_millis(5)).await;
+        assert!(timed_out);
+
+        let _guard = tokio::time::timeout(Duration::from_millis(50), warmup_lock.lock())
+            .await
+            .expect("startup warmup timeout should cancel blocked task and release lock");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn startup_warmup_wait_timeout_stays_bounded_for_non_cooperative_task() {
+        let mut tasks = tokio::task::JoinSet::new();
+        tasks.spawn(async {
+            std::thread::sleep(Duration::from_millis(100));
+        });
+
+        let started = std::time::Instant::now();
+        let timed_out = wait_for_startup_warmup_tasks(&mut tasks, Duration::from_millis(5)).await;
+        assert!(timed_out);
+        assert!(
+            started.elapsed() < Duration::from_millis(80),
+            "startup warmup timeout should return without waiting for non-cooperative task"
+        );
+    }
+
+    #[test]
+    fn deferred_injections_are_scoped_to_exact_agent_and_channel() {
+        let mut deferred_injections: HashMap<ActiveChannelKey, Vec<InboundMessage>> =
+            HashMap::new();
+        let injection = ChannelInjection {
+            conversation_id: "discord:dm:42".to_string(),
+            agent_id: "agent-a".to_string(),
+            message: InboundMessage {
+                id: "inj-1".to_string(),
+                source: "system".to_string(),
+                adapter: None,
+                conversation_id: "discord:dm:42".to_string(),
+                sender_id: "system".to_string(),
+                agent_id: Some(Arc::from("agent-a")),
+                content: MessageContent::Text("secret cron output".to_string()),
+                timestamp: Utc::now(),
+                metadata: HashMap::new(),
+                formatted_author: None,
+            },
+        };
+
+        queue_deferred_injection(&mut deferred_injections, injection);
+
+        assert_eq!(
+            deferred_injections
+                .get(&ActiveChannelKey::new("agent-a", "discord:dm:42"))
+                .map(Vec::len),
+            Some(1)
+        );
+        assert!(
+            !deferred_injections.contains_key(&ActiveChannelKey::new("agent-a", "discord:123:456"))
+        );
+        assert!(
+            !deferred_injections.contains_key(&ActiveChannelKey::new("agent-b", "discord:dm:42"))
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Operator scripts that rewrite prompts and replies.
+//!
+//! Every `*.rhai` file in `<instance_dir>/scripts/` may define either hook:
+//!
+//! - `pre_prompt(text, ctx)` runs on each user message before the channel
+//!   sees it, and on each worker task before the worker starts.
+//! - `post_response(text, ctx)` runs on each text reply before delivery,
+//!   including every stream update. An update carries the whole text so
+//!   far, so a rewrite never misses a match split across updates.
+//!
+//! A hook returns the new text, or `()` to leave it unchanged. Scripts run in
+//! filename order, each seeing the previous one's output. `ctx` is a map
+//! with `kind` (see [`HookKind`]), `agent_id`, `conversation_id`, `source`,
+//! and `sender_id`; fields that don't apply are empty strings.
+//!
+//! Rhai scripts have no file or network access, and each call is capped at
+//! [`MAX_OPERATIONS`]. A script that fails to compile is skipped; one that
+//! errors at run time leaves the text unchanged for that call. The config
+//! file watcher reloads the directory when its files change.
+
+use crate::{InboundMessage, OutboundResponse};
+
+use arc_swap::ArcSwap;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, Scope};
+
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+/// Rhai operations a single hook call may run before it is aborted.
+pub const MAX_OPERATIONS: u64 = 200_000;
+
+/// Longest string a script may build.
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+
+const PRE_PROMPT: &str = "pre_prompt";
+const POST_RESPONSE: &str = "post_response";
+
+static SCRIPTS: LazyLock<ScriptHooks> = LazyLock::new(ScriptHooks::new);
+
+/// What a hook is being run on, exposed to scripts as `ctx.kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// A user message, before the channel LLM sees it.
+    Message,
+    /// A worker task, before the worker starts.
+    WorkerTask,
+    /// A text reply, before it is delivered.
+    Response,
+}
+
+impl HookKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Message => "message",
+            Self::WorkerTask => "worker_task",
+            Self::Response => "response",
+        }
+    }
+}
+
+/// Where the text came from or is going, exposed to scripts as `ctx`.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptContext {
+    pub agent_id: String,
+    pub conversation_id: String,
+    pub source: String,
+    pub sender_id: String,
+}
+
+impl ScriptContext {
+    pub fn from_message(message: &InboundMessage) -> Self {
+        Self {
+            agent_id: message.agent_id.as_deref().unwrap_or_default().to_string(),
+            conversation_id: message.conversation_id.clone(),
+            source: message.source.clone(),
+            sender_id: message.sender_id.clone(),
+        }
+    }
+
+    fn to_map(&self, kind: HookKind) -> rhai::Map {
+        let mut map = rhai::Map::new();
+        map.insert("kind".into(), kind.as_str().into());
+        map.insert("agent_id".into(), self.agent_id.clone().into());
+        map.insert(
+            "conversation_id".into(),
+            self.conversation_id.clone().into(),
+        );
+        map.insert("source".into(), self.source.clone().into());
+        map.insert("sender_id".into(), self.sender_id.clone().into());
+        map
+    }
+}
+
+/// A compiled script and the hooks it defines.
+struct Script {
+    name: String,
+    ast: AST,
+    pre_prompt: bool,
+    post_response: bool,
+}
+
+impl Script {
+    fn defines(&self, hook: &str) -> bool {
+        match hook {
+            PRE_PROMPT => self.pre_prompt,
+            POST_RESPONSE => self.post_response,
+            _ => false,
+        }
+    }
+}
+
+/// Loaded scripts. Access via [`ScriptHooks::global`].
+pub struct ScriptHooks {
+    engine: Engine,
+    scripts: ArcSwap<Vec<Script>>,
+}
+
+impl ScriptHooks {
+    /// A sandboxed engine with no scripts loaded.
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_string_size(MAX_STRING_SIZE);
+        engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+        engine.on_print(|text| tracing::info!(script_output = %text, "script print"));
+        engine.on_debug(|text, source, _position| {
+            tracing::debug!(script_output = %text, source = source.unwrap_or(""), "script debug");
+        });
+        Self {
+            engine,
+            scripts: ArcSwap::from_pointee(Vec::new()),
+        }
+    }
+
+    /// The process-wide scripts the runtime applies.
+    pub fn global() -> &'static Self {
+        &SCRIPTS
+    }
+
+    /// Replace the loaded scripts with the `*.rhai` files in `directory`.
+    /// A missing directory unloads everything. Returns the number loaded.
+    pub fn load_dir(&self, directory: &Path) -> usize {
+        let mut paths: Vec<_> = match std::fs::read_dir(directory) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "rhai")
+                })
+                .collect(),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                tracing::warn!(%error, path = %directory.display(), "failed to read scripts directory");
+                return self.scripts.load().len();
+            }
+        };
+        paths.sort();
+
+        let mut scripts = Vec::with_capacity(paths.len());
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match self.compile(&path) {
+                Ok(ast) => {
+                    let defines = |hook: &str| {
+                        ast.iter_functions()
+                            .any(|function| function.name == hook && function.params.len() == 2)
+                    };
+                    let pre_prompt = defines(PRE_PROMPT);
+                    let post_response = defines(POST_RESPONSE);
+                    if !pre_prompt && !post_response {
+                        tracing::warn!(script = %name, "script defines no hooks, skipping");
+                        continue;
+                    }
+                    scripts.push(Script {
+                        name,
+                        ast,
+                        pre_prompt,
+                        post_response,
+                    });
+                }
+                Err(error) => {
+                    tracing::warn!(%error, script = %name, "failed to load script, skipping");
+                }
+            }
+        }
+
+        let loaded = scripts.len();
+        tracing::info!(loaded, path = %directory.display(), "scripts loaded");
+        self.scripts.store(Arc::new(scripts));
+        loaded
+    }
+
+    fn compile(&self, path: &Path) -> anyhow::Result<AST> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(self.engine.compile(&source)?)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.load().is_empty()
+    }
+
+    /// Run `pre_prompt` hooks over a message or worker task. Returns `None`
+    /// when no script changed it.
+    pub fn pre_prompt(
+        &self,
+        kind: HookKind,
+        text: &str,
+        context: &ScriptContext,
+    ) -> Option<String> {
+        self.run(PRE_PROMPT, kind, text, context)
+    }
+
+    /// Run `post_response` hooks over reply text. Returns `None` when no
+    /// script changed it.
+    pub fn post_response(&self, text: &str, context: &ScriptContext) -> Option<String> {
+        self.run(POST_RESPONSE, HookKind::Response, text, context)
+    }
+
+    /// Run `post_response` hooks over the text of an outbound reply or
+    /// stream update, if it has any.
+    pub fn post_process(&self, response: &mut OutboundResponse, context: &ScriptContext) {
+        let text = match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::StreamChunk(text)
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::RichMessage { text, .. } => text,
+            _ => return,
+        };
+        if let Some(processed) = self.post_response(text, context) {
+            *text = processed;
+        }
+    }
+
+    fn run(
+        &self,
+        hook: &str,
+        kind: HookKind,
+        text: &str,
+        context: &ScriptContext,
+    ) -> Option<String> {
+        let scripts = self.scripts.load();
+        if !scripts.iter().any(|script| script.defines(hook)) {
+            return None;
+        }
+
+        let map = context.to_map(kind);
+        let mut current: Option<String> = None;
+        for script in scripts.iter().filter(|script| script.defines(hook)) {
+            let input = current.as_deref().unwrap_or(text).to_string();
+            let result = self.engine.call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut Scope::new(),
+                &script.ast,
+                hook,
+                (input, map.clone()),
+            );
+            match result {
+                Ok(value) if value.is_unit() => {}
+                Ok(value) => match value.into_string() {
+                    Ok(output) => current = Some(output),
+                    Err(type_name) => {
+                        tracing::warn!(
+                            script = %script.name,
+                            hook,
+                            returned = type_name,
+                            "script hook must return a string or ()"
+                        );
+                    }
+                },
+                Err(error) => {
+                    tracing::warn!(%error, script = %script.name, hook, "script hook failed");
+                }
+            }
+        }
+        current.filter(|output| output != text)
+    }
+}
+
+impl Default for ScriptHooks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(scripts: &[(&str, &str)]) -> (ScriptHooks, tempfile::TempDir) {
+        let directory = tempfile::tempdir().unwrap();
+        for (name, source) in scripts {
+            std::fs::write(directory.path().join(name), source).unwrap();
+        }
+        let hooks = ScriptHooks::new();
+        hooks.load_dir(directory.path());
+        (hooks, directory)
+    }
+
+    #[test]
+    fn hooks_chain_in_filename_order() {
+        let (hooks, _directory) = load(&[
+            (
+                "20-footer.rhai",
+                r#"fn post_response(text, ctx) { text + "\n-- sent by spacebot" }"#,
+            ),
+            (
+                "10-conventions.rhai",
+                r#"
+                fn pre_prompt(text, ctx) {
+                    if ctx.kind != "worker_task" { return; }
+                    text + "\n\nFollow the repo's RUST_STYLE_GUIDE.md."
+                }
+                fn post_response(text, ctx) { text.replace("TODO", "[redacted]"); text }
+                "#,
+            ),
+        ]);
+        let context = ScriptContext::default();
+
+        assert_eq!(
+            hooks.pre_prompt(HookKind::WorkerTask, "fix the bug", &context),
+            Some("fix the bug\n\nFollow the repo's RUST_STYLE_GUIDE.md.".to_string())
+        );
+        assert_eq!(hooks.pre_prompt(HookKind::Message, "hi", &context), None);
+        assert_eq!(
+            hooks.post_response("done, TODO tests", &context),
+            Some("done, [redacted] tests\n-- sent by spacebot".to_string())
+        );
+    }
+
+    #[test]
+    fn stream_updates_are_post_processed() {
+        let (hooks, _directory) = load(&[(
+            "mask.rhai",
+            r#"fn post_response(text, ctx) { text.replace("Falcon", "the project"); text }"#,
+        )]);
+        let context = ScriptContext::default();
+
+        let mut update = OutboundResponse::StreamChunk("shipping Falcon".into());
+        hooks.post_process(&mut update, &context);
+        assert!(
+            matches!(update, OutboundResponse::StreamChunk(ref text) if text == "shipping the project")
+        );
+
+        let mut start = OutboundResponse::StreamStart;
+        hooks.post_process(&mut start, &context);
+        assert!(matches!(start, OutboundResponse::StreamStart));
+    }
+
+    #[test]
+    fn broken_scripts_leave_text_unchanged() {
+        let (hooks, directory) = load(&[
+            ("syntax.rhai", "fn pre_prompt(text, ctx) {"),
+            (
+                "runtime.rhai",
+                "fn pre_prompt(text, ctx) { text.no_such_method() }",
+            ),
+            ("spin.rhai", "fn post_response(text, ctx) { loop {} }"),
+        ]);
+
+        let context = ScriptContext::default();
+        assert_eq!(hooks.pre_prompt(HookKind::Message, "hi", &context), None);
+        assert_eq!(hooks.post_response("hi", &context), None);
+
+        std::fs::remove_dir_all(directory.path()).unwrap();
+        assert_eq!(hooks.load_dir(directory.path()), 0);
+        assert!(hooks.is_empty());
+    }
+}
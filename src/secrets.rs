@@ -1,5 +1,6 @@
 //! Credential storage, output protection, and OS keystore integration.
 
 pub mod keystore;
+pub mod redact;
 pub mod scrub;
 pub mod store;
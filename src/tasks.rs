@@ -4,6 +4,6 @@ pub mod migration;
 pub mod store;
 
 pub use store::{
-    CreateTaskInput, Task, TaskListFilter, TaskPriority, TaskStatus, TaskStore, TaskSubtask,
-    TaskUpdateResult, UpdateTaskInput, WorkerTaskUpdateResult,
+    CreateTaskInput, Task, TaskCheckpoint, TaskListFilter, TaskPriority, TaskStatus, TaskStore,
+    TaskSubtask, TaskUpdateResult, UpdateTaskInput, WorkerTaskUpdateResult,
 };
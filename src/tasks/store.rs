@@ -157,10 +157,22 @@ pub struct UpdateTaskInput {
     pub clear_worker_id: bool,
     pub approved_by: Option<String>,
     pub complete_subtask: Option<usize>,
+    /// Result to store as the checkpoint for subtasks this update completes.
+    pub checkpoint: Option<String>,
     /// Reassign the task to a different agent.
     pub assigned_agent_id: Option<String>,
 }
 
+/// Saved result of a completed subtask (pipeline stage).
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TaskCheckpoint {
+    pub stage_index: i64,
+    pub stage_title: String,
+    pub result: String,
+    pub worker_id: Option<String>,
+    pub recorded_at: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskUpdateResult {
     pub previous_status: TaskStatus,
@@ -489,6 +501,11 @@ impl TaskStore {
             )));
         }
 
+        let previously_completed: Vec<bool> = current
+            .subtasks
+            .iter()
+            .map(|subtask| subtask.completed)
+            .collect();
         let mut subtasks = input.subtasks.unwrap_or(current.subtasks);
         if let Some(index) = input.complete_subtask
             && let Some(subtask) = subtasks.get_mut(index)
@@ -568,7 +585,7 @@ impl TaskStore {
             .bind(next_metadata.to_string());
 
         if !clear_worker {
-            sql = sql.bind(next_worker_id);
+            sql = sql.bind(next_worker_id.clone());
         }
 
         sql.bind(input.approved_by)
@@ -577,6 +594,37 @@ impl TaskStore {
             .await
             .context("failed to update task")?;
 
+        // A task re-opened after completion runs its stages from scratch.
+        if current.status == TaskStatus::Done && next_status != TaskStatus::Done {
+            sqlx::query("DELETE FROM task_checkpoints WHERE task_id = ?")
+                .bind(&current.id)
+                .execute(&mut **tx)
+                .await
+                .context("failed to clear task checkpoints")?;
+        }
+
+        for (index, subtask) in subtasks.iter().enumerate() {
+            if !subtask.completed || previously_completed.get(index).copied().unwrap_or(false) {
+                continue;
+            }
+            sqlx::query(
+                "INSERT INTO task_checkpoints (task_id, stage_index, stage_title, result, worker_id) \
+                 VALUES (?, ?, ?, ?, ?) \
+                 ON CONFLICT(task_id, stage_index) DO UPDATE SET \
+                 stage_title = excluded.stage_title, result = excluded.result, \
+                 worker_id = excluded.worker_id, \
+                 recorded_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            )
+            .bind(&current.id)
+            .bind(index as i64)
+            .bind(&subtask.title)
+            .bind(input.checkpoint.as_deref().unwrap_or_default())
+            .bind(next_worker_id.as_deref())
+            .execute(&mut **tx)
+            .await
+            .context("failed to record task checkpoint")?;
+        }
+
         let updated = sqlx::query(&format!(
             "{SELECT_COLUMNS} FROM tasks WHERE task_number = ?"
         ))
@@ -589,6 +637,15 @@ impl TaskStore {
     }
 
     pub async fn delete(&self, task_number: i64) -> Result<bool> {
+        sqlx::query(
+            "DELETE FROM task_checkpoints WHERE task_id IN \
+             (SELECT id FROM tasks WHERE task_number = ?)",
+        )
+        .bind(task_number)
+        .execute(&self.pool)
+        .await
+        .context("failed to delete task checkpoints")?;
+
         let result = sqlx::query("DELETE FROM tasks WHERE task_number = ?")
             .bind(task_number)
             .execute(&self.pool)
@@ -641,6 +698,52 @@ impl TaskStore {
         self.get_by_number(task_number).await
     }
 
+    /// Stage checkpoints recorded for a task, in stage order.
+    pub async fn list_checkpoints(&self, task_id: &str) -> Result<Vec<TaskCheckpoint>> {
+        let rows = sqlx::query(
+            "SELECT stage_index, stage_title, result, worker_id, recorded_at \
+             FROM task_checkpoints WHERE task_id = ? ORDER BY stage_index ASC",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list task checkpoints")?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(TaskCheckpoint {
+                    stage_index: row
+                        .try_get("stage_index")
+                        .context("failed to read stage_index")?,
+                    stage_title: row
+                        .try_get("stage_title")
+                        .context("failed to read stage_title")?,
+                    result: row.try_get("result").context("failed to read result")?,
+                    worker_id: row.try_get("worker_id").ok().flatten(),
+                    recorded_at: read_timestamp(&row, "recorded_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Return in-progress tasks whose worker died with the previous process
+    /// to `ready`, so the pickup loop resumes them from their checkpoints.
+    /// Only call at startup, before any worker for this agent is spawned.
+    pub async fn requeue_interrupted(&self, assigned_agent_id: &str) -> Result<Vec<i64>> {
+        let task_numbers = sqlx::query_scalar::<_, i64>(
+            "UPDATE tasks SET status = 'ready', worker_id = NULL, \
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') \
+             WHERE assigned_agent_id = ? AND status = 'in_progress' AND worker_id IS NOT NULL \
+             RETURNING task_number",
+        )
+        .bind(assigned_agent_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to requeue interrupted tasks")?;
+
+        Ok(task_numbers)
+    }
+
     pub async fn get_by_worker_id(&self, worker_id: &str) -> Result<Option<Task>> {
         let row = sqlx::query(&format!(
             "{SELECT_COLUMNS} FROM tasks WHERE worker_id = ? ORDER BY updated_at DESC LIMIT 1"
@@ -849,6 +952,21 @@ pub(crate) async fn setup_test_store() -> TaskStore {
     .await
     .expect("task_number_seq should be created");
 
+    sqlx::query(
+        "CREATE TABLE task_checkpoints (
+            task_id TEXT NOT NULL,
+            stage_index INTEGER NOT NULL,
+            stage_title TEXT NOT NULL,
+            result TEXT NOT NULL DEFAULT '',
+            worker_id TEXT,
+            recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            PRIMARY KEY (task_id, stage_index)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .expect("task_checkpoints should be created");
+
     sqlx::query("INSERT INTO task_number_seq (id, next_number) VALUES (1, 1)")
         .execute(&pool)
         .await
@@ -1234,4 +1352,71 @@ mod tests {
         assert_eq!(updated.assigned_agent_id, "agent-other");
         assert_eq!(updated.owner_agent_id, "agent-test");
     }
+
+    #[tokio::test]
+    async fn completed_subtasks_checkpoint_and_interrupted_tasks_requeue() {
+        let store = setup_store().await;
+        let created = store
+            .create(CreateTaskInput {
+                subtasks: vec![
+                    TaskSubtask {
+                        title: "fetch data".to_string(),
+                        completed: false,
+                    },
+                    TaskSubtask {
+                        title: "build report".to_string(),
+                        completed: false,
+                    },
+                ],
+                ..self_assigned_input("pipeline", TaskStatus::InProgress)
+            })
+            .await
+            .expect("should create");
+
+        store
+            .update(
+                created.task_number,
+                UpdateTaskInput {
+                    worker_id: Some("worker-1".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect("worker assignment should update");
+        store
+            .update_worker_task(
+                "worker-1",
+                created.task_number,
+                UpdateTaskInput {
+                    complete_subtask: Some(0),
+                    checkpoint: Some("42 rows".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect("subtask update should succeed");
+
+        let checkpoints = store
+            .list_checkpoints(&created.id)
+            .await
+            .expect("checkpoints should list");
+        assert_eq!(checkpoints.len(), 1);
+        assert_eq!(checkpoints[0].stage_title, "fetch data");
+        assert_eq!(checkpoints[0].result, "42 rows");
+        assert_eq!(checkpoints[0].worker_id.as_deref(), Some("worker-1"));
+
+        let requeued = store
+            .requeue_interrupted("agent-test")
+            .await
+            .expect("requeue should succeed");
+        assert_eq!(requeued, vec![created.task_number]);
+        let task = store
+            .get_by_number(created.task_number)
+            .await
+            .expect("task should load")
+            .expect("task should exist");
+        assert_eq!(task.status, TaskStatus::Ready);
+        assert!(task.worker_id.is_none());
+        assert!(task.subtasks[0].completed);
+    }
 }
@@ -1,7 +1,8 @@
 //! Global metrics registry and metric handle definitions.
 
 use prometheus::{
-    CounterVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+    CounterVec, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts,
+    Registry,
 };
 
 use std::sync::LazyLock;
@@ -140,6 +141,18 @@ pub struct Metrics {
     /// Labels: agent_id, channel_type, error_type.
     pub channel_errors_total: IntCounterVec,
 
+    /// Outbound platform API calls routed through the messaging manager.
+    /// Labels: adapter, operation, result.
+    pub messaging_outbound_requests_total: IntCounterVec,
+
+    /// Outbound platform API call duration.
+    /// Labels: adapter, operation.
+    pub messaging_outbound_duration_seconds: HistogramVec,
+
+    /// Fraction of the platform rate-limit quota remaining, from response headers.
+    /// Labels: adapter.
+    pub messaging_rate_limit_remaining_ratio: GaugeVec,
+
     // -- Memory operations --
     /// Memory operation duration.
     /// Labels: agent_id, operation.
@@ -188,6 +201,15 @@ pub struct Metrics {
     /// Ingestion files processed.
     /// Labels: agent_id, result.
     pub ingestion_files_processed_total: IntCounterVec,
+
+    // -- Streaming --
+    /// SSE events whose envelope failed to parse and were dead-lettered.
+    /// Labels: source.
+    pub sse_dead_letters_total: IntCounterVec,
+
+    /// Items dropped by bounded pipeline stages under backpressure.
+    /// Labels: stage, reason (superseded/full).
+    pub pipeline_dropped_total: IntCounterVec,
 }
 
 impl Metrics {
@@ -438,6 +460,34 @@ impl Metrics {
         )
         .expect("hardcoded metric descriptor");
 
+        let messaging_outbound_requests_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_messaging_outbound_requests_total",
+                "Outbound platform API calls",
+            ),
+            &["adapter", "operation", "result"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let messaging_outbound_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "spacebot_messaging_outbound_duration_seconds",
+                "Outbound platform API call duration",
+            )
+            .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+            &["adapter", "operation"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let messaging_rate_limit_remaining_ratio = GaugeVec::new(
+            Opts::new(
+                "spacebot_messaging_rate_limit_remaining_ratio",
+                "Fraction of platform rate-limit quota remaining",
+            ),
+            &["adapter"],
+        )
+        .expect("hardcoded metric descriptor");
+
         // Memory (3)
         let memory_operation_duration_seconds = HistogramVec::new(
             HistogramOpts::new(
@@ -534,6 +584,25 @@ impl Metrics {
         )
         .expect("hardcoded metric descriptor");
 
+        // Streaming (2)
+        let sse_dead_letters_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_sse_dead_letters_total",
+                "SSE events dead-lettered after failing to parse",
+            ),
+            &["source"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let pipeline_dropped_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_pipeline_dropped_total",
+                "Items dropped by bounded pipeline stages under backpressure",
+            ),
+            &["stage", "reason"],
+        )
+        .expect("hardcoded metric descriptor");
+
         // === Register all metrics ===
 
         // Existing (upgraded)
@@ -625,6 +694,15 @@ impl Metrics {
         registry
             .register(Box::new(channel_errors_total.clone()))
             .expect("hardcoded metric");
+        registry
+            .register(Box::new(messaging_outbound_requests_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(messaging_outbound_duration_seconds.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(messaging_rate_limit_remaining_ratio.clone()))
+            .expect("hardcoded metric");
 
         // New: Memory operations
         registry
@@ -671,6 +749,14 @@ impl Metrics {
             .register(Box::new(ingestion_files_processed_total.clone()))
             .expect("hardcoded metric");
 
+        // New: Streaming
+        registry
+            .register(Box::new(sse_dead_letters_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(pipeline_dropped_total.clone()))
+            .expect("hardcoded metric");
+
         Self {
             registry,
             llm_requests_total,
@@ -702,6 +788,9 @@ impl Metrics {
             messages_sent_total,
             message_handling_duration_seconds,
             channel_errors_total,
+            messaging_outbound_requests_total,
+            messaging_outbound_duration_seconds,
+            messaging_rate_limit_remaining_ratio,
             memory_operation_duration_seconds,
             memory_search_results,
             memory_embedding_duration_seconds,
@@ -713,6 +802,8 @@ impl Metrics {
             cron_executions_total,
             cron_delivery_total,
             ingestion_files_processed_total,
+            sse_dead_letters_total,
+            pipeline_dropped_total,
         }
     }
 
@@ -0,0 +1,297 @@
+//! Named prompt templates invoked from chat with `/run`.
+//!
+//! A template is a prompt with `{{placeholders}}`, e.g. `review-pr` →
+//! `Review PR {{url}} focusing on {{area}}`. `/run review-pr url=... area=...`
+//! renders it and submits the result as if the user had typed it, so the
+//! rest of the conversation continues from there. Templates come from
+//! `[[agents.templates]]` in config or are saved per agent with
+//! `/template add`; a saved template replaces a config one with the same name.
+
+use sqlx::{Row as _, SqlitePool};
+
+/// A named prompt with `{{placeholder}}` variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub prompt: String,
+    pub description: Option<String>,
+}
+
+/// A parsed `/run` invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateInvocation {
+    pub name: String,
+    pub arguments: Vec<(String, String)>,
+}
+
+/// Template names are short identifiers: letters, digits, `-` and `_`.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || matches!(character, '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid template name '{name}'. use up to 64 letters, digits, '-' or '_'."
+        ))
+    }
+}
+
+/// Placeholders a template expects, sorted.
+pub fn placeholders(prompt: &str) -> Result<Vec<String>, String> {
+    let environment = minijinja::Environment::new();
+    let template = environment
+        .template_from_str(prompt)
+        .map_err(|error| format!("invalid template: {error}"))?;
+    let mut names: Vec<String> = template.undeclared_variables(false).into_iter().collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Render `template` with the invocation's arguments. Every placeholder must
+/// be given and every argument must be used, so typos surface instead of
+/// rendering blanks.
+pub fn render(template: &PromptTemplate, arguments: &[(String, String)]) -> Result<String, String> {
+    let expected = placeholders(&template.prompt)?;
+    let missing: Vec<&str> = expected
+        .iter()
+        .filter(|name| !arguments.iter().any(|(key, _)| key == *name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} needs {}. usage: {}",
+            template.name,
+            missing.join(", "),
+            usage(template, &expected)
+        ));
+    }
+    if let Some((unknown, _)) = arguments.iter().find(|(key, _)| !expected.contains(key)) {
+        return Err(format!(
+            "{} has no placeholder '{unknown}'. usage: {}",
+            template.name,
+            usage(template, &expected)
+        ));
+    }
+
+    let context: serde_json::Map<String, serde_json::Value> = arguments
+        .iter()
+        .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+        .collect();
+    minijinja::Environment::new()
+        .render_str(&template.prompt, context)
+        .map(|rendered| rendered.trim().to_string())
+        .map_err(|error| format!("couldn't render {}: {error}", template.name))
+}
+
+/// `/run <name> key=value ...` line for a template.
+pub fn usage(template: &PromptTemplate, placeholders: &[String]) -> String {
+    let mut usage = format!("/run {}", template.name);
+    for placeholder in placeholders {
+        usage.push_str(&format!(" {placeholder}=..."));
+    }
+    usage
+}
+
+/// Parse `/run <name> key=value key="quoted value"`. Returns `None` when the
+/// text isn't a `/run` command.
+pub fn parse_run_command(text: &str) -> Option<Result<TemplateInvocation, String>> {
+    let rest = text.trim().strip_prefix("/run")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(parse_invocation(rest.trim()))
+}
+
+fn parse_invocation(input: &str) -> Result<TemplateInvocation, String> {
+    let (name, mut rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    if name.is_empty() {
+        return Err("usage: /run <template> key=value ... (/templates lists them)".into());
+    }
+
+    let mut arguments = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let (key, after_key) = rest
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, got '{rest}'"))?;
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("expected key=value, got '{rest}'"));
+        }
+        let (value, remainder) = match after_key.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let inner = &after_key[1..];
+                let end = inner
+                    .find(quote)
+                    .ok_or_else(|| format!("missing closing quote for {key}"))?;
+                (&inner[..end], &inner[end + 1..])
+            }
+            _ => after_key
+                .split_once(char::is_whitespace)
+                .unwrap_or((after_key, "")),
+        };
+        arguments.push((key.to_string(), value.to_string()));
+        rest = remainder;
+    }
+
+    Ok(TemplateInvocation {
+        name: name.to_string(),
+        arguments,
+    })
+}
+
+/// Templates saved from chat, per agent.
+#[derive(Debug, Clone)]
+pub struct TemplateStore {
+    pool: SqlitePool,
+}
+
+impl TemplateStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, agent_id: &str) -> crate::error::Result<Vec<PromptTemplate>> {
+        let rows = sqlx::query(
+            "SELECT name, prompt, description FROM prompt_templates WHERE agent_id = ? ORDER BY name",
+        )
+        .bind(agent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| PromptTemplate {
+                name: row.try_get("name").unwrap_or_default(),
+                prompt: row.try_get("prompt").unwrap_or_default(),
+                description: row.try_get("description").ok().flatten(),
+            })
+            .collect())
+    }
+
+    pub async fn upsert(
+        &self,
+        agent_id: &str,
+        template: &PromptTemplate,
+    ) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO prompt_templates (agent_id, name, prompt, description) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(agent_id, name) DO UPDATE SET \
+             prompt = excluded.prompt, description = excluded.description, \
+             updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(agent_id)
+        .bind(&template.name)
+        .bind(&template.prompt)
+        .bind(&template.description)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(())
+    }
+
+    /// Delete a saved template. Returns whether one existed.
+    pub async fn delete(&self, agent_id: &str, name: &str) -> crate::error::Result<bool> {
+        let result = sqlx::query("DELETE FROM prompt_templates WHERE agent_id = ? AND name = ?")
+            .bind(agent_id)
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Config templates overlaid with saved ones, sorted by name.
+pub fn merge(configured: &[PromptTemplate], saved: Vec<PromptTemplate>) -> Vec<PromptTemplate> {
+    let mut templates = saved;
+    for template in configured {
+        if !templates.iter().any(|saved| saved.name == template.name) {
+            templates.push(template.clone());
+        }
+    }
+    templates.sort_by(|left, right| left.name.cmp(&right.name));
+    templates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review_pr() -> PromptTemplate {
+        PromptTemplate {
+            name: "review-pr".into(),
+            prompt: "Review PR {{url}} focusing on {{area}}".into(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn parses_run_arguments() {
+        let invocation = parse_run_command(
+            "/run review-pr url=https://github.com/acme/api/pull/7 area=\"error handling\"",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(invocation.name, "review-pr");
+        assert_eq!(
+            invocation.arguments,
+            vec![
+                (
+                    "url".to_string(),
+                    "https://github.com/acme/api/pull/7".to_string()
+                ),
+                ("area".to_string(), "error handling".to_string()),
+            ]
+        );
+        assert!(parse_run_command("/runner").is_none());
+        assert!(parse_run_command("/run").unwrap().is_err());
+        assert!(parse_run_command("/run review-pr area").unwrap().is_err());
+    }
+
+    #[test]
+    fn renders_with_every_placeholder() {
+        let template = review_pr();
+        let arguments = vec![
+            ("url".to_string(), "#7".to_string()),
+            ("area".to_string(), "tests".to_string()),
+        ];
+        assert_eq!(
+            render(&template, &arguments).as_deref(),
+            Ok("Review PR #7 focusing on tests")
+        );
+
+        let error = render(&template, &arguments[..1]).unwrap_err();
+        assert!(error.contains("needs area"), "{error}");
+        let mut extra = arguments.clone();
+        extra.push(("areas".to_string(), "docs".to_string()));
+        assert!(render(&template, &extra).unwrap_err().contains("'areas'"));
+    }
+
+    #[test]
+    fn saved_templates_replace_configured_ones() {
+        let configured = vec![
+            review_pr(),
+            PromptTemplate {
+                name: "standup".into(),
+                prompt: "Summarize yesterday".into(),
+                description: None,
+            },
+        ];
+        let saved = vec![PromptTemplate {
+            name: "review-pr".into(),
+            prompt: "Review {{url}}".into(),
+            description: None,
+        }];
+        let merged = merge(&configured, saved);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].prompt, "Review {{url}}");
+        assert_eq!(merged[1].name, "standup");
+    }
+}
@@ -0,0 +1,220 @@
+//! Helpers for tests and benchmarks that replay OpenCode event streams.
+//!
+//! Recorded streams live in `tests/fixtures/opencode/` as SSE bodies: one
+//! `data:` line per event, separated by blank lines. [`write_generated_fixture`]
+//! writes a seeded stream from [`crate::opencode::fixtures`] in that format,
+//! as a starting point for a new fixture. [`replay`] feeds a stream through
+//! the pieces a worker and its channel use to show a session — the transcript
+//! assembler, the tool output renderers, the busy status line, and the todo
+//! checklist — and collects what they produce. [`parse_sse_chunks`] runs raw
+//! bytes through the worker's SSE parser, for property tests and fuzzing.
+
+use crate::agent::channel_heartbeat::{HeartbeatStyle, Progress, status_line};
+use crate::agent::channel_tool_render::render_todo_checklist;
+use crate::conversation::worker_transcript::{
+    ActionContent, OpenCodeTranscriptAssembler, TranscriptStep,
+};
+use crate::messaging::overflow::platform_message_limit;
+use crate::opencode::fixtures::{FixtureConfig, generate};
+use crate::opencode::types::{SseEvent, SseEventEnvelope, part_to_opencode_part};
+use crate::opencode::worker::extract_sse_event;
+
+use anyhow::Context as _;
+
+use std::path::Path;
+use std::time::Duration;
+
+/// Read a recorded SSE body. Lines other than `data:` are ignored.
+pub fn load_sse_fixture(path: impl AsRef<Path>) -> anyhow::Result<Vec<SseEvent>> {
+    let path = path.as_ref();
+    let body = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read fixture {}", path.display()))?;
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .enumerate()
+        .map(|(index, data)| {
+            let envelope: SseEventEnvelope =
+                serde_json::from_str(data.trim_start()).with_context(|| {
+                    format!("event {index} of {} is not an envelope", path.display())
+                })?;
+            Ok(SseEvent::from_envelope(envelope))
+        })
+        .collect()
+}
+
+/// Write the stream [`generate`] produces for `seed` as an SSE body.
+pub fn write_generated_fixture(
+    path: impl AsRef<Path>,
+    seed: u64,
+    config: &FixtureConfig,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, generate(seed, config).to_sse_string())
+        .with_context(|| format!("failed to write fixture {}", path.display()))
+}
+
+/// Events parsed from a stream and how much of it the parser held at once.
+#[derive(Debug, Default)]
+pub struct ParsedStream {
+    pub events: Vec<SseEvent>,
+    /// Most bytes buffered after any chunk arrived.
+    pub peak_buffered_bytes: usize,
+}
+
+/// Parse a byte stream that arrives in `chunks`, the way the worker reads
+/// `/event`: each chunk is appended as lossy UTF-8 and every complete event
+/// is taken before the next one.
+pub fn parse_sse_chunks<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> ParsedStream {
+    let mut buffer = String::new();
+    let mut parsed = ParsedStream::default();
+    for chunk in chunks {
+        buffer.push_str(&String::from_utf8_lossy(chunk));
+        parsed.peak_buffered_bytes = parsed.peak_buffered_bytes.max(buffer.len());
+        while let Some(event) = extract_sse_event(&mut buffer) {
+            parsed.events.push(event);
+        }
+    }
+    parsed
+}
+
+/// What a replayed session looks like in chat and in its transcript.
+#[derive(Debug, Default)]
+pub struct Replay {
+    /// Transcript steps, as the worker persists them.
+    pub transcript: Vec<TranscriptStep>,
+    /// Each distinct busy status line, in order, with the clock held at 0s.
+    pub status_lines: Vec<String>,
+    /// Each distinct rendering of the todo checklist, in order.
+    pub checklists: Vec<String>,
+    /// The session's last assistant text, which the worker reports as its
+    /// result.
+    pub result: Option<String>,
+}
+
+/// Replay `events` for `session_id`. Parts from other sessions and exact
+/// repeats are dropped, as the worker drops them.
+pub fn replay(events: &[SseEvent], session_id: &str) -> Replay {
+    let mut assembler = OpenCodeTranscriptAssembler::new();
+    let mut progress = Progress::default();
+    let mut replay = Replay::default();
+
+    for event in events {
+        match event {
+            SseEvent::MessagePartUpdated { part, .. } => {
+                if part.session_id().is_some_and(|id| id != session_id) {
+                    continue;
+                }
+                let Some(part) = part_to_opencode_part(part)
+                    .filter(|opencode_part| !assembler.is_repeat(part.message_id(), opencode_part))
+                else {
+                    continue;
+                };
+                progress.apply(&part);
+                push_changed(
+                    &mut replay.status_lines,
+                    status_line(0, Duration::ZERO, &progress, "en"),
+                );
+                assembler.upsert(part);
+            }
+            SseEvent::TodoUpdated {
+                session_id: todo_session,
+                todos,
+            } if todo_session == session_id => {
+                push_changed(&mut replay.checklists, render_todo_checklist(todos));
+            }
+            _ => {}
+        }
+    }
+
+    replay.transcript = assembler.steps();
+    replay.result = replay.transcript.iter().rev().find_map(|step| match step {
+        TranscriptStep::Action { content } => {
+            content.iter().rev().find_map(|content| match content {
+                ActionContent::Text { text } => Some(text.clone()),
+                ActionContent::ToolCall { .. } => None,
+            })
+        }
+        _ => None,
+    });
+    replay
+}
+
+fn push_changed(renderings: &mut Vec<String>, rendering: String) {
+    if renderings.last() != Some(&rendering) {
+        renderings.push(rendering);
+    }
+}
+
+impl Replay {
+    /// The transcript as plain text, one block per step.
+    pub fn transcript_text(&self) -> String {
+        self.transcript
+            .iter()
+            .map(|step| match step {
+                TranscriptStep::Action { content } => content
+                    .iter()
+                    .map(|content| match content {
+                        ActionContent::Text { text } => format!("[assistant]\n{text}"),
+                        ActionContent::ToolCall { name, args, .. } => {
+                            format!("[tool call] {name} {args}")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                TranscriptStep::UserText { text } => format!("[user]\n{text}"),
+                TranscriptStep::SystemText { text } => format!("[system]\n{text}"),
+                TranscriptStep::ToolResult { name, text, .. } => {
+                    format!("[tool result] {name}\n{text}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// What a channel on `platform` shows for the session: how it signals
+    /// that the session is busy, the todo checklist, and the result.
+    /// Messages longer than the platform's limit are flagged.
+    pub fn chat_text(&self, platform: &str) -> String {
+        let limit = platform_message_limit(platform);
+        let fit = |message: &str| {
+            let length = message.chars().count();
+            match limit {
+                Some(limit) if length > limit => {
+                    format!("{message}\n⚠ {length} characters, over the limit")
+                }
+                _ => message.to_string(),
+            }
+        };
+
+        let mut sections = vec![match limit {
+            Some(limit) => format!("# {platform}, {limit} characters per message"),
+            None => format!("# {platform}, no message limit"),
+        }];
+        sections.push(match HeartbeatStyle::for_source(platform) {
+            Some(HeartbeatStyle::Typing) => "# busy\ntyping indicator".to_string(),
+            Some(HeartbeatStyle::StatusLine) => {
+                format!("# busy (edited in place)\n{}", self.status_lines.join("\n"))
+            }
+            None => "# busy\nnot shown".to_string(),
+        });
+        if !self.checklists.is_empty() {
+            let checklists: Vec<String> = self
+                .checklists
+                .iter()
+                .map(|checklist| fit(checklist))
+                .collect();
+            sections.push(format!(
+                "# tasks (edited in place)\n{}",
+                checklists.join("\n---\n")
+            ));
+        }
+        if let Some(result) = &self.result {
+            sections.push(format!("# result\n{}", fit(result)));
+        }
+        sections.join("\n\n")
+    }
+}
@@ -0,0 +1,453 @@
+//! Per-tool execution statistics: how often each tool runs, how long it
+//! takes, and how often it fails.
+//!
+//! A recorder on each agent's event bus pairs tool starts with their
+//! results and writes one `tool_executions` row per finished call. Builtin
+//! tools report through `ToolStarted`/`ToolCompleted`; OpenCode workers
+//! report through the tool parts of `OpenCodePartUpdated`. Durations are
+//! measured between the two events, so they include event-bus latency.
+
+use crate::opencode::types::{OpenCodePart, OpenCodeToolState};
+use crate::{AgentId, BroadcastRecvResult, ProcessEvent, classify_broadcast_recv_result};
+
+use sqlx::{Row as _, SqlitePool};
+use tokio::sync::broadcast;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Started calls tracked before stale ones are pruned.
+const MAX_PENDING_CALLS: usize = 4_096;
+
+/// A started call with no result after this long is assumed abandoned
+/// (its process was cancelled or crashed).
+const PENDING_CALL_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A finished tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolExecution {
+    pub channel_id: Option<String>,
+    pub tool_name: String,
+    pub duration: Duration,
+    pub success: bool,
+}
+
+/// Aggregated executions of one tool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolSummary {
+    pub tool_name: String,
+    pub runs: i64,
+    pub failures: i64,
+    pub average_duration_ms: f64,
+}
+
+impl ToolSummary {
+    /// Failed runs as a percentage of all runs.
+    pub fn failure_rate(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        self.failures as f64 * 100.0 / self.runs as f64
+    }
+}
+
+/// Reads and writes an agent's `tool_executions` table.
+#[derive(Debug, Clone)]
+pub struct ToolStats {
+    pool: SqlitePool,
+}
+
+impl ToolStats {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        agent_id: &str,
+        execution: &ToolExecution,
+    ) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO tool_executions (agent_id, channel_id, tool_name, duration_ms, success) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(agent_id)
+        .bind(&execution.channel_id)
+        .bind(&execution.tool_name)
+        .bind(execution.duration.as_millis() as i64)
+        .bind(execution.success)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(())
+    }
+
+    /// Most-run tools over the trailing `days`, across the agent or in one
+    /// channel.
+    pub async fn summary(
+        &self,
+        agent_id: &str,
+        channel_id: Option<&str>,
+        days: i64,
+        limit: i64,
+    ) -> crate::error::Result<Vec<ToolSummary>> {
+        let rows = sqlx::query(
+            "SELECT tool_name, COUNT(*) AS runs, SUM(success = 0) AS failures, \
+                    AVG(duration_ms) AS average_duration_ms \
+             FROM tool_executions \
+             WHERE agent_id = ? AND (? IS NULL OR channel_id = ?) \
+               AND recorded_at >= strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?) \
+             GROUP BY tool_name \
+             ORDER BY runs DESC, tool_name \
+             LIMIT ?",
+        )
+        .bind(agent_id)
+        .bind(channel_id)
+        .bind(channel_id)
+        .bind(format!("-{days} days"))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ToolSummary {
+                tool_name: row.try_get("tool_name").unwrap_or_default(),
+                runs: row.try_get("runs").unwrap_or_default(),
+                failures: row.try_get("failures").unwrap_or_default(),
+                average_duration_ms: row.try_get("average_duration_ms").unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+struct PendingCall {
+    started_at: Instant,
+    channel_id: Option<String>,
+    tool_name: String,
+}
+
+/// Pairs tool starts with their results.
+#[derive(Debug, Default)]
+struct CallTracker {
+    pending: HashMap<String, PendingCall>,
+}
+
+impl CallTracker {
+    fn start(&mut self, key: String, channel_id: Option<String>, tool_name: &str, now: Instant) {
+        if self.pending.len() >= MAX_PENDING_CALLS {
+            self.pending
+                .retain(|_, call| now.duration_since(call.started_at) < PENDING_CALL_TTL);
+        }
+        self.pending.entry(key).or_insert_with(|| PendingCall {
+            started_at: now,
+            channel_id,
+            tool_name: tool_name.to_string(),
+        });
+    }
+
+    /// The finished execution, if its start was seen. Results re-sent for a
+    /// call that already finished are ignored.
+    fn finish(&mut self, key: &str, success: bool, now: Instant) -> Option<ToolExecution> {
+        let call = self.pending.remove(key)?;
+        Some(ToolExecution {
+            channel_id: call.channel_id,
+            tool_name: call.tool_name,
+            duration: now.duration_since(call.started_at),
+            success,
+        })
+    }
+
+    fn observe(
+        &mut self,
+        agent_id: &AgentId,
+        event: &ProcessEvent,
+        now: Instant,
+    ) -> Option<ToolExecution> {
+        match event {
+            ProcessEvent::ToolStarted {
+                agent_id: event_agent_id,
+                process_id,
+                channel_id,
+                call_id,
+                tool_name,
+                ..
+            } if event_agent_id == agent_id => {
+                self.start(
+                    format!("{process_id}:{call_id}"),
+                    channel_id.as_ref().map(|channel_id| channel_id.to_string()),
+                    tool_name,
+                    now,
+                );
+                None
+            }
+            ProcessEvent::ToolCompleted {
+                agent_id: event_agent_id,
+                process_id,
+                call_id,
+                result,
+                ..
+            } if event_agent_id == agent_id => self.finish(
+                &format!("{process_id}:{call_id}"),
+                !is_failed_result(result),
+                now,
+            ),
+            ProcessEvent::OpenCodePartUpdated {
+                agent_id: event_agent_id,
+                channel_id,
+                part: OpenCodePart::Tool { id, tool, state },
+                ..
+            } if event_agent_id == agent_id => match state {
+                OpenCodeToolState::Pending | OpenCodeToolState::Running { .. } => {
+                    self.start(
+                        id.clone(),
+                        channel_id.as_ref().map(|channel_id| channel_id.to_string()),
+                        tool,
+                        now,
+                    );
+                    None
+                }
+                OpenCodeToolState::Completed { .. } => self.finish(id, true, now),
+                OpenCodeToolState::Error { .. } => self.finish(id, false, now),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Whether a builtin tool result reports a failure: a toolset error, or a
+/// structured result with `success`/`ok` false.
+fn is_failed_result(result: &str) -> bool {
+    result.starts_with("Toolset error:")
+        || result.starts_with("Error:")
+        || crate::agent::cortex::parse_structured_success_flag(result) == Some(false)
+}
+
+/// Record finished tool calls from an agent's event bus.
+pub fn spawn_tool_stats_recorder(
+    agent_id: AgentId,
+    mut event_rx: broadcast::Receiver<ProcessEvent>,
+    pool: SqlitePool,
+) -> tokio::task::JoinHandle<()> {
+    let stats = ToolStats::new(pool);
+    tokio::spawn(async move {
+        let mut tracker = CallTracker::default();
+        loop {
+            match classify_broadcast_recv_result(event_rx.recv().await) {
+                BroadcastRecvResult::Event(event) => {
+                    if let Some(execution) = tracker.observe(&agent_id, &event, Instant::now())
+                        && let Err(error) = stats.record(&agent_id, &execution).await
+                    {
+                        tracing::warn!(%error, %agent_id, "failed to record tool execution");
+                    }
+                }
+                BroadcastRecvResult::Lagged(count) => {
+                    tracing::warn!(
+                        %agent_id,
+                        skipped = count,
+                        "tool stats recorder lagged, executions were not recorded"
+                    );
+                }
+                BroadcastRecvResult::Closed => break,
+            }
+        }
+    })
+}
+
+/// A duration in milliseconds as `850ms`, `2.3s`, or `4m 05s`.
+pub fn format_duration_ms(milliseconds: f64) -> String {
+    if milliseconds < 1_000.0 {
+        format!("{milliseconds:.0}ms")
+    } else if milliseconds < 60_000.0 {
+        format!("{:.1}s", milliseconds / 1_000.0)
+    } else {
+        let seconds = (milliseconds / 1_000.0).round() as u64;
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    fn tool_started(call_id: &str, tool_name: &str) -> ProcessEvent {
+        ProcessEvent::ToolStarted {
+            agent_id: Arc::from("main"),
+            process_id: crate::ProcessId::Channel(Arc::from("discord:1")),
+            channel_id: Some(Arc::from("discord:1")),
+            call_id: call_id.into(),
+            tool_name: tool_name.into(),
+            args: String::new(),
+        }
+    }
+
+    fn tool_completed(call_id: &str, tool_name: &str, result: &str) -> ProcessEvent {
+        ProcessEvent::ToolCompleted {
+            agent_id: Arc::from("main"),
+            process_id: crate::ProcessId::Channel(Arc::from("discord:1")),
+            channel_id: Some(Arc::from("discord:1")),
+            call_id: call_id.into(),
+            tool_name: tool_name.into(),
+            result: result.into(),
+        }
+    }
+
+    fn opencode_tool(state: OpenCodeToolState) -> ProcessEvent {
+        ProcessEvent::OpenCodePartUpdated {
+            agent_id: Arc::from("main"),
+            worker_id: uuid::Uuid::nil(),
+            channel_id: Some(Arc::from("discord:1")),
+            part: OpenCodePart::Tool {
+                id: "part-1".into(),
+                tool: "bash".into(),
+                state,
+            },
+            subagent: None,
+        }
+    }
+
+    #[test]
+    fn pairs_starts_with_results() {
+        let agent_id: AgentId = Arc::from("main");
+        let mut tracker = CallTracker::default();
+        let started = Instant::now();
+        let finished = started + Duration::from_millis(250);
+
+        assert!(
+            tracker
+                .observe(&agent_id, &tool_started("call-1", "shell"), started)
+                .is_none()
+        );
+        let execution = tracker
+            .observe(
+                &agent_id,
+                &tool_completed("call-1", "shell", "Toolset error: exit 1"),
+                finished,
+            )
+            .expect("execution expected");
+        assert_eq!(execution.tool_name, "shell");
+        assert_eq!(execution.channel_id.as_deref(), Some("discord:1"));
+        assert_eq!(execution.duration, Duration::from_millis(250));
+        assert!(!execution.success);
+
+        // A result with no recorded start is ignored.
+        assert!(
+            tracker
+                .observe(
+                    &agent_id,
+                    &tool_completed("call-2", "shell", "ok"),
+                    finished
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn opencode_parts_record_once() {
+        let agent_id: AgentId = Arc::from("main");
+        let mut tracker = CallTracker::default();
+        let now = Instant::now();
+
+        tracker.observe(&agent_id, &opencode_tool(OpenCodeToolState::Pending), now);
+        tracker.observe(
+            &agent_id,
+            &opencode_tool(OpenCodeToolState::Running {
+                title: None,
+                input: None,
+            }),
+            now + Duration::from_secs(1),
+        );
+        let error = OpenCodeToolState::Error {
+            error: Some("command not found".into()),
+        };
+        let execution = tracker
+            .observe(
+                &agent_id,
+                &opencode_tool(error.clone()),
+                now + Duration::from_secs(3),
+            )
+            .expect("execution expected");
+        assert_eq!(execution.duration, Duration::from_secs(3));
+        assert!(!execution.success);
+        assert!(
+            tracker
+                .observe(
+                    &agent_id,
+                    &opencode_tool(error),
+                    now + Duration::from_secs(4)
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn detects_failed_results() {
+        assert!(is_failed_result("Toolset error: tool not found"));
+        assert!(is_failed_result(r#"{"success":false,"error":"denied"}"#));
+        assert!(!is_failed_result(r#"{"success":true}"#));
+        assert!(!is_failed_result("3 files changed"));
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration_ms(850.4), "850ms");
+        assert_eq!(format_duration_ms(2_340.0), "2.3s");
+        assert_eq!(format_duration_ms(245_000.0), "4m 05s");
+    }
+
+    #[tokio::test]
+    async fn summarizes_per_channel_and_globally() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory db");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+        let stats = ToolStats::new(pool);
+
+        let execution = |channel_id: &str, tool_name: &str, milliseconds, success| ToolExecution {
+            channel_id: Some(channel_id.to_string()),
+            tool_name: tool_name.to_string(),
+            duration: Duration::from_millis(milliseconds),
+            success,
+        };
+        for execution in [
+            execution("discord:1", "shell", 100, true),
+            execution("discord:1", "shell", 300, false),
+            execution("discord:1", "read", 10, true),
+            execution("discord:2", "read", 30, true),
+            execution("discord:2", "read", 50, true),
+        ] {
+            stats.record("main", &execution).await.expect("record");
+        }
+
+        let channel = stats
+            .summary("main", Some("discord:1"), 7, 10)
+            .await
+            .expect("summary");
+        assert_eq!(channel[0].tool_name, "shell");
+        assert_eq!(channel[0].runs, 2);
+        assert_eq!(channel[0].failures, 1);
+        assert_eq!(channel[0].average_duration_ms, 200.0);
+        assert_eq!(channel[0].failure_rate(), 50.0);
+
+        let global = stats.summary("main", None, 7, 10).await.expect("summary");
+        assert_eq!(global[0].tool_name, "read");
+        assert_eq!(global[0].runs, 3);
+        assert!(
+            stats
+                .summary("other", None, 7, 10)
+                .await
+                .expect("summary")
+                .is_empty()
+        );
+    }
+}
@@ -125,7 +125,8 @@ pub use project_manage::{
 pub use react::{ReactArgs, ReactError, ReactOutput, ReactTool};
 pub use read_skill::{ReadSkillArgs, ReadSkillError, ReadSkillOutput, ReadSkillTool};
 pub use reply::{
-    RepliedFlag, ReplyArgs, ReplyError, ReplyOutput, ReplyTarget, ReplyTool, new_replied_flag,
+    DeferredReplies, RepliedFlag, ReplyArgs, ReplyError, ReplyOutput, ReplyTarget, ReplyTool,
+    new_replied_flag,
 };
 pub use route::{RouteArgs, RouteError, RouteOutput, RouteTool};
 pub use secret_set::{SecretSetArgs, SecretSetError, SecretSetOutput, SecretSetTool};
@@ -385,6 +386,16 @@ pub fn truncate_utf8_ellipsis(value: &str, max_bytes: usize) -> String {
     }
 }
 
+/// Truncate to `max_chars` characters and mark the cut with `…`.
+///
+/// For chat text, where the limit is what a reader sees rather than bytes.
+pub fn truncate_chars_ellipsis(value: &str, max_chars: usize) -> String {
+    match value.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &value[..index]),
+        None => value.to_string(),
+    }
+}
+
 /// Returns true when text looks like structured/tool payloads that should never
 /// be sent to end users as plain chat output.
 pub fn should_block_user_visible_text(value: &str) -> bool {
@@ -717,7 +728,7 @@ pub async fn add_direct_mode_tools(
     Ok(())
 }
 
-fn default_delivery_target_for_conversation(
+pub(crate) fn default_delivery_target_for_conversation(
     conversation_id: &str,
     slack_thread_ts: Option<&str>,
 ) -> Option<String> {
@@ -123,6 +123,8 @@ pub struct CronEntry {
     pub delivery_target: String,
     pub run_once: bool,
     pub active_hours: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Tool for CronTool {
@@ -451,6 +453,10 @@ impl CronTool {
                 active_hours: config
                     .active_hours
                     .map(|(s, e)| format!("{s:02}:00-{e:02}:00")),
+                next_run_at: config
+                    .next_run_at
+                    .as_deref()
+                    .and_then(crate::cron::scheduler::parse_cron_timestamp),
             })
             .collect();
 
@@ -31,17 +31,23 @@ pub fn new_replied_flag() -> RepliedFlag {
     Arc::new(AtomicBool::new(false))
 }
 
+/// Replies held back until the end of the turn, in the order they were made.
+pub type DeferredReplies = Arc<std::sync::Mutex<Vec<OutboundResponse>>>;
+
 /// Target for reply delivery.
 #[derive(Debug, Clone)]
 pub enum ReplyTarget {
     /// Live delivery via the messaging adapter.
     Live(Box<RoutedSender>),
+    /// Held for the channel to post as one message when the turn ends
+    /// (`DeliveryMode::Summary`).
+    Deferred(DeferredReplies),
 }
 
 /// Tool for replying to users.
 ///
-/// Holds a reply target which is either a live sender channel or a buffered
-/// accumulator for cron jobs. The channel process creates a response sender per
+/// Holds a reply target which is either a live sender channel or a buffer the
+/// channel flushes at the end of the turn. The channel process creates a response sender per
 /// conversation turn and the tool routes replies through it. This is compatible
 /// with Rig's ToolServer which registers tools once and shares them across calls.
 #[derive(Clone)]
@@ -491,36 +497,42 @@ impl Tool for ReplyTool {
             OutboundResponse::Text(converted_content.clone())
         };
 
-        // Branch on reply target: live delivery or buffered staging
+        // Branch on reply target: live delivery or held for the end of the turn
         match &self.target {
             ReplyTarget::Live(sender) => {
                 sender
                     .send(response)
                     .await
                     .map_err(|e| ReplyError(format!("failed to send reply: {e}")))?;
-
-                // Drain accumulated channel tool calls and pack into message metadata.
-                let tool_calls_json = if let Some(ref api_state) = self.api_state {
-                    let calls = api_state.take_channel_tool_calls(&self.channel_id).await;
-                    if calls.is_empty() {
-                        None
-                    } else {
-                        serde_json::to_string(&calls).ok()
-                    }
-                } else {
-                    None
-                };
-                self.conversation_logger.log_bot_message_with_metadata(
-                    &self.channel_id,
-                    &converted_content,
-                    Some(&self.agent_display_name),
-                    tool_calls_json,
-                );
-
                 tracing::debug!(conversation_id = %self.conversation_id, "reply sent to outbound channel");
             }
+            ReplyTarget::Deferred(replies) => {
+                replies
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .push(response);
+                tracing::debug!(conversation_id = %self.conversation_id, "reply held for end of turn");
+            }
         }
 
+        // Drain accumulated channel tool calls and pack into message metadata.
+        let tool_calls_json = if let Some(ref api_state) = self.api_state {
+            let calls = api_state.take_channel_tool_calls(&self.channel_id).await;
+            if calls.is_empty() {
+                None
+            } else {
+                serde_json::to_string(&calls).ok()
+            }
+        } else {
+            None
+        };
+        self.conversation_logger.log_bot_message_with_metadata(
+            &self.channel_id,
+            &converted_content,
+            Some(&self.agent_display_name),
+            tool_calls_json,
+        );
+
         // Mark the turn as handled so handle_agent_result skips the fallback send.
         self.replied_flag.store(true, Ordering::Relaxed);
 
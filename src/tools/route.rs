@@ -100,6 +100,7 @@ impl Tool for RouteTool {
                 if let Some(input_tx) = inputs.get(&worker_id).cloned() {
                     drop(inputs);
 
+                    self.state.attribute_next_prompt(worker_id).await;
                     input_tx.send(args.message).await.map_err(|_| {
                         RouteError(format!(
                             "Worker {worker_id} has stopped accepting input (channel closed)"
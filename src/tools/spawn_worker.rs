@@ -160,7 +160,7 @@ impl Tool for SpawnWorkerTool {
                 "directory".to_string(),
                 serde_json::json!({
                     "type": "string",
-                    "description": "Working directory for the worker. Required when worker_type is \"opencode\" unless project_id or worktree_id is set. The OpenCode agent operates in this directory."
+                    "description": "Working directory for the worker. Required when worker_type is \"opencode\" unless project_id or worktree_id is set or the channel has a bound project. The OpenCode agent operates in this directory."
                 }),
             );
             obj.insert(
@@ -239,11 +239,15 @@ impl Tool for SpawnWorkerTool {
         .await;
 
         let worker_id = if is_opencode {
-            let directory = resolved_directory.as_deref().ok_or_else(|| {
-                SpawnWorkerError(
-                    "directory is required for opencode workers (set directory, project_id, or worktree_id)".into(),
-                )
-            })?;
+            // Fall back to the directory bound with `/project`.
+            let directory = resolved_directory
+                .as_deref()
+                .or(self.state.model_overrides.project_directory.as_deref())
+                .ok_or_else(|| {
+                    SpawnWorkerError(
+                        "directory is required for opencode workers (set directory, project_id, or worktree_id, or bind one with /project)".into(),
+                    )
+                })?;
 
             // OpenCode workers are always interactive — ignore args.interactive.
             spawn_opencode_worker_from_state(&self.state, &args.task, directory, true)
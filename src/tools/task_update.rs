@@ -65,6 +65,7 @@ pub struct TaskUpdateArgs {
     pub subtasks: Option<Vec<TaskSubtask>>,
     pub metadata: Option<serde_json::Value>,
     pub complete_subtask: Option<i32>,
+    pub checkpoint: Option<String>,
     pub worker_id: Option<String>,
     pub approved_by: Option<String>,
 }
@@ -106,7 +107,8 @@ impl Tool for TaskUpdateTool {
                         }
                     },
                     "metadata": { "type": "object", "description": "Metadata object deep-merged with current metadata" },
-                    "complete_subtask": { "type": "integer", "description": "Subtask index to mark complete" }
+                    "complete_subtask": { "type": "integer", "description": "Subtask index to mark complete" },
+                    "checkpoint": { "type": "string", "description": "Result of the subtask(s) completed by this update, saved so the task resumes after them if interrupted" }
                 },
                 "required": ["task_number"]
             })
@@ -141,6 +143,7 @@ impl Tool for TaskUpdateTool {
                     },
                     "metadata": { "type": "object", "description": "Metadata object deep-merged with current metadata" },
                     "complete_subtask": { "type": "integer", "description": "Subtask index to mark complete" },
+                    "checkpoint": { "type": "string", "description": "Result of the subtask(s) completed by this update, saved so the task resumes after them if interrupted" },
                     "worker_id": { "type": "string", "description": "Optional worker ID to bind to this task" },
                     "approved_by": { "type": "string", "description": "Optional approver identifier" }
                 },
@@ -203,6 +206,7 @@ impl Tool for TaskUpdateTool {
             clear_worker_id: false,
             approved_by: args.approved_by,
             complete_subtask,
+            checkpoint: args.checkpoint,
             ..Default::default()
         };
 
@@ -347,6 +351,7 @@ mod tests {
                 subtasks: None,
                 metadata: None,
                 complete_subtask: None,
+                checkpoint: None,
                 worker_id: None,
                 approved_by: None,
             })
@@ -397,6 +402,7 @@ mod tests {
                 subtasks: None,
                 metadata: None,
                 complete_subtask: None,
+                checkpoint: None,
                 worker_id: None,
                 approved_by: None,
             })
@@ -470,6 +476,7 @@ mod tests {
                 subtasks: None,
                 metadata: Some(serde_json::json!({"progress": "checked"})),
                 complete_subtask: None,
+                checkpoint: None,
                 worker_id: None,
                 approved_by: None,
             })
@@ -485,6 +492,7 @@ mod tests {
                 subtasks: None,
                 metadata: Some(serde_json::json!({"progress": "checked"})),
                 complete_subtask: None,
+                checkpoint: None,
                 worker_id: None,
                 approved_by: None,
             })
@@ -0,0 +1,130 @@
+//! Voice note transcription through a Whisper-compatible API.
+//!
+//! With `[agents.transcribe]` set, audio attachments on Telegram and Discord
+//! are sent to `<base_url>/audio/transcriptions` (OpenAI's Whisper endpoint,
+//! also served by Groq, LocalAI, faster-whisper-server and others). The
+//! transcription replaces the audio in the prompt and, with `echo` on, is
+//! posted back so the sender can see what was heard. Audio that fails to
+//! transcribe falls back to the `routing.voice` path.
+
+use crate::config::TranscribeConfig;
+
+use anyhow::Context as _;
+use std::time::Duration;
+
+/// Whisper rejects uploads above 25 MB.
+pub const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Sources whose audio attachments are voice notes.
+pub fn transcribes_source(source: &str) -> bool {
+    matches!(source, "telegram" | "discord")
+}
+
+/// Transcribe one audio file and return the trimmed text.
+pub async fn transcribe(
+    http: &reqwest::Client,
+    config: &TranscribeConfig,
+    audio: Vec<u8>,
+    filename: &str,
+    mime_type: &str,
+) -> anyhow::Result<String> {
+    if audio.len() > MAX_AUDIO_BYTES {
+        anyhow::bail!(
+            "{filename} is {} MB, over the 25 MB transcription limit",
+            audio.len() / (1024 * 1024)
+        );
+    }
+
+    let file = reqwest::multipart::Part::bytes(audio)
+        .file_name(filename.to_string())
+        .mime_str(mime_type)
+        .context("invalid audio MIME type")?;
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text("model", config.model.clone())
+        .text("response_format", "json");
+    if let Some(language) = &config.language {
+        form = form.text("language", language.clone());
+    }
+
+    let mut request = http
+        .post(format!("{}/audio/transcriptions", config.base_url))
+        .timeout(REQUEST_TIMEOUT)
+        .multipart(form);
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .await
+        .context("transcription request failed")?;
+
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .context("invalid transcription response")?;
+    if !status.is_success() {
+        let message = body
+            .pointer("/error/message")
+            .and_then(|message| message.as_str())
+            .unwrap_or("unknown error");
+        anyhow::bail!("transcription API returned {status}: {message}");
+    }
+    transcription_text(&body).context("transcription response has no text")
+}
+
+/// `text` of a `response_format=json` reply, when not blank.
+fn transcription_text(body: &serde_json::Value) -> Option<String> {
+    let text = body.get("text")?.as_str()?.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// The prompt a transcribed voice note is submitted as. A caption sent with
+/// the voice note follows the transcription.
+pub fn prompt_text(transcriptions: &[String], caption: &str) -> String {
+    let mut prompt = transcriptions.join("\n\n");
+    if !caption.trim().is_empty() {
+        prompt.push_str("\n\n");
+        prompt.push_str(caption.trim());
+    }
+    prompt
+}
+
+/// Confirmation posted back to the chat.
+pub fn echo_text(transcriptions: &[String]) -> String {
+    let quoted = transcriptions
+        .iter()
+        .flat_map(|transcription| transcription.lines())
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("heard:\n{quoted}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_prompt_and_echo() {
+        assert_eq!(
+            transcription_text(&serde_json::json!({ "text": "  deploy the api  " })).as_deref(),
+            Some("deploy the api")
+        );
+        assert_eq!(
+            transcription_text(&serde_json::json!({ "text": " " })),
+            None
+        );
+
+        let transcriptions = vec!["deploy the api".to_string()];
+        assert_eq!(
+            prompt_text(&transcriptions, "to staging"),
+            "deploy the api\n\nto staging"
+        );
+        assert_eq!(prompt_text(&transcriptions, ""), "deploy the api");
+        assert_eq!(echo_text(&transcriptions), "heard:\n> deploy the api");
+    }
+}
@@ -0,0 +1,141 @@
+//! Terminal UI for operating a running Spacebot instance.
+//!
+//! The TUI talks to the daemon through the same HTTP API as the dashboard.
+//! Sessions are portal conversations, prompts go through `/v1/prompts`, and
+//! the open session streams over its WebSocket. No chat platform needs to be
+//! configured.
+
+mod app;
+mod client;
+mod ui;
+
+pub use client::{ApiClient, SessionStream};
+
+use app::{Action, App};
+
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::StreamExt as _;
+use ratatui::DefaultTerminal;
+
+/// Name prompts from the TUI are sent under.
+const SENDER_NAME: &str = "tui";
+
+/// Run the TUI against `client` for `agent_id` until the user quits.
+pub async fn run(client: ApiClient, agent_id: String) -> anyhow::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &client, agent_id).await;
+    ratatui::restore();
+    result
+}
+
+async fn run_app(
+    terminal: &mut DefaultTerminal,
+    client: &ApiClient,
+    agent_id: String,
+) -> anyhow::Result<()> {
+    let mut app = App::new(agent_id);
+    let mut stream: Option<SessionStream> = None;
+    let mut keys = EventStream::new();
+
+    refresh_sessions(client, &mut app).await;
+    if let Some(session_id) = app.selected_session_id() {
+        stream = open_session(client, &mut app, session_id).await;
+    }
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        tokio::select! {
+            terminal_event = keys.next() => {
+                let key = match terminal_event {
+                    Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => key,
+                    Some(Ok(_)) => continue,
+                    Some(Err(error)) => return Err(error.into()),
+                    None => break,
+                };
+                match app.handle_key(key) {
+                    Action::None => {}
+                    Action::Quit => break,
+                    Action::NewSession => {
+                        stream = None;
+                        app.start_new_session();
+                    }
+                    Action::Open(session_id) => {
+                        stream = open_session(client, &mut app, session_id).await;
+                    }
+                    Action::Submit(prompt) => {
+                        let is_new_session = app.open_session.is_none();
+                        let submitted = client
+                            .submit(
+                                &app.agent_id,
+                                app.open_session.as_deref(),
+                                &prompt,
+                                SENDER_NAME,
+                            )
+                            .await;
+                        match submitted {
+                            Ok(submitted) if is_new_session => {
+                                // The stream can only open once the session
+                                // exists, so this prompt's early events may be
+                                // missed; the transcript is reloaded on open.
+                                let session_id = submitted.session_id;
+                                stream = open_session(client, &mut app, session_id).await;
+                                app.push_prompt(prompt);
+                                refresh_sessions(client, &mut app).await;
+                            }
+                            Ok(_) => app.push_prompt(prompt),
+                            Err(error) => app.notice = Some(format!("send failed: {error:#}")),
+                        }
+                    }
+                }
+            }
+            stream_event = next_stream_event(&mut stream) => match stream_event {
+                Some(stream_event) => app.apply(stream_event),
+                None => {
+                    stream = None;
+                    app.notice = Some("session stream closed".into());
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+async fn refresh_sessions(client: &ApiClient, app: &mut App) {
+    match client.sessions(&app.agent_id).await {
+        Ok(sessions) => app.set_sessions(sessions),
+        Err(error) => app.notice = Some(format!("failed to list sessions: {error:#}")),
+    }
+}
+
+/// Load a session's transcript and connect to its event stream.
+async fn open_session(
+    client: &ApiClient,
+    app: &mut App,
+    session_id: String,
+) -> Option<SessionStream> {
+    // Connect first so nothing between the load and the connect is lost.
+    let stream = match client.stream(&session_id).await {
+        Ok(stream) => Some(stream),
+        Err(error) => {
+            app.notice = Some(format!("failed to stream session: {error:#}"));
+            None
+        }
+    };
+    match client.session(&app.agent_id, &session_id).await {
+        Ok(session) => app.load_session(session),
+        Err(error) => {
+            app.open_session = Some(session_id);
+            app.notice = Some(format!("failed to load session: {error:#}"));
+        }
+    }
+    stream
+}
+
+async fn next_stream_event(stream: &mut Option<SessionStream>) -> Option<crate::api::StreamEvent> {
+    match stream {
+        Some(stream) => stream.next().await,
+        None => std::future::pending().await,
+    }
+}
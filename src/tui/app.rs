@@ -0,0 +1,371 @@
+//! TUI state and input handling, kept free of terminal and network I/O.
+
+use crate::api::{SessionResponse, SessionStatus, StreamEvent};
+use crate::conversation::PortalConversationSummary;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Which pane receives key presses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Focus {
+    Sessions,
+    Input,
+}
+
+/// What the event loop should do after a key press.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Action {
+    None,
+    Quit,
+    NewSession,
+    Open(String),
+    Submit(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct TranscriptEntry {
+    pub role: Role,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum ActivityState {
+    Running(String),
+    Done,
+    Failed,
+}
+
+/// A tool call, worker, or branch in the open session's timeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Activity {
+    pub id: String,
+    pub label: String,
+    pub state: ActivityState,
+}
+
+pub(super) struct App {
+    pub agent_id: String,
+    pub sessions: Vec<PortalConversationSummary>,
+    pub selected: usize,
+    pub open_session: Option<String>,
+    pub transcript: Vec<TranscriptEntry>,
+    /// Reply text streamed so far for the reply in progress.
+    pub streaming: Option<String>,
+    pub activity: Vec<Activity>,
+    pub busy: bool,
+    pub input: String,
+    pub focus: Focus,
+    pub notice: Option<String>,
+}
+
+impl App {
+    pub fn new(agent_id: String) -> Self {
+        Self {
+            agent_id,
+            sessions: Vec::new(),
+            selected: 0,
+            open_session: None,
+            transcript: Vec::new(),
+            streaming: None,
+            activity: Vec::new(),
+            busy: false,
+            input: String::new(),
+            focus: Focus::Input,
+            notice: None,
+        }
+    }
+
+    pub fn selected_session_id(&self) -> Option<String> {
+        self.sessions
+            .get(self.selected)
+            .map(|session| session.id.clone())
+    }
+
+    pub fn set_sessions(&mut self, sessions: Vec<PortalConversationSummary>) {
+        self.sessions = sessions;
+        self.selected = self
+            .open_session
+            .as_ref()
+            .and_then(|open| self.sessions.iter().position(|session| &session.id == open))
+            .unwrap_or(0);
+    }
+
+    /// Clear the transcript so the next prompt starts a new session.
+    pub fn start_new_session(&mut self) {
+        self.open_session = None;
+        self.clear_transcript();
+        self.focus = Focus::Input;
+        self.notice = Some("new session: the first prompt creates it".into());
+    }
+
+    pub fn load_session(&mut self, session: SessionResponse) {
+        self.open_session = Some(session.session_id);
+        self.clear_transcript();
+        self.transcript = session
+            .messages
+            .into_iter()
+            .filter_map(|message| {
+                let role = match message.role.as_str() {
+                    "user" => Role::User,
+                    "assistant" => Role::Assistant,
+                    _ => return None,
+                };
+                Some(TranscriptEntry {
+                    role,
+                    text: message.content,
+                })
+            })
+            .collect();
+        self.activity = session
+            .workers
+            .into_iter()
+            .map(|worker| Activity {
+                id: worker.id,
+                label: format!("worker: {}", worker.task),
+                state: ActivityState::Running(worker.status),
+            })
+            .collect();
+        self.busy = session.status != SessionStatus::Idle;
+    }
+
+    /// Show a prompt sent from this TUI. Its stream echo is skipped, and so
+    /// is a copy already loaded with the session.
+    pub fn push_prompt(&mut self, prompt: String) {
+        let entry = TranscriptEntry {
+            role: Role::User,
+            text: prompt,
+        };
+        if self.transcript.last() != Some(&entry) {
+            self.transcript.push(entry);
+        }
+        self.busy = true;
+    }
+
+    pub fn apply(&mut self, event: StreamEvent) {
+        match event {
+            StreamEvent::Prompt { sender, text } => {
+                if sender != super::SENDER_NAME {
+                    self.transcript.push(TranscriptEntry {
+                        role: Role::User,
+                        text,
+                    });
+                }
+            }
+            StreamEvent::TextDelta { text, .. } => self.streaming = Some(text),
+            StreamEvent::Message { text } => {
+                self.streaming = None;
+                self.transcript.push(TranscriptEntry {
+                    role: Role::Assistant,
+                    text,
+                });
+            }
+            StreamEvent::Typing { active } => {
+                if active {
+                    self.busy = true;
+                }
+            }
+            StreamEvent::ToolStarted {
+                call_id, tool_name, ..
+            } => self.start_activity(call_id, tool_name),
+            StreamEvent::ToolCompleted { call_id, .. } => {
+                self.finish_activity(&call_id, ActivityState::Done)
+            }
+            StreamEvent::WorkerStarted { worker_id, task } => {
+                self.start_activity(worker_id, format!("worker: {task}"))
+            }
+            StreamEvent::WorkerStatus { worker_id, status } => {
+                if let Some(activity) = self.activity_mut(&worker_id) {
+                    activity.state = ActivityState::Running(status);
+                }
+            }
+            StreamEvent::WorkerCompleted {
+                worker_id, success, ..
+            } => {
+                let state = if success {
+                    ActivityState::Done
+                } else {
+                    ActivityState::Failed
+                };
+                self.finish_activity(&worker_id, state);
+            }
+            StreamEvent::BranchStarted {
+                branch_id,
+                description,
+            } => self.start_activity(branch_id, format!("branch: {description}")),
+            StreamEvent::BranchCompleted { branch_id, .. } => {
+                self.finish_activity(&branch_id, ActivityState::Done)
+            }
+            StreamEvent::Completed => {
+                self.busy = false;
+                self.streaming = None;
+            }
+            StreamEvent::Lagged { skipped } => {
+                self.notice = Some(format!("stream fell behind, {skipped} events dropped"));
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        if key.code == KeyCode::Tab {
+            self.focus = match self.focus {
+                Focus::Sessions => Focus::Input,
+                Focus::Input => Focus::Sessions,
+            };
+            return Action::None;
+        }
+
+        match self.focus {
+            Focus::Sessions => match key.code {
+                KeyCode::Char('q') => Action::Quit,
+                KeyCode::Char('n') => Action::NewSession,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected = self.selected.saturating_sub(1);
+                    Action::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if self.selected + 1 < self.sessions.len() {
+                        self.selected += 1;
+                    }
+                    Action::None
+                }
+                KeyCode::Enter => match self.selected_session_id() {
+                    Some(session_id) => {
+                        self.focus = Focus::Input;
+                        Action::Open(session_id)
+                    }
+                    None => Action::None,
+                },
+                _ => Action::None,
+            },
+            Focus::Input => match key.code {
+                KeyCode::Esc => {
+                    self.focus = Focus::Sessions;
+                    Action::None
+                }
+                KeyCode::Enter => {
+                    let prompt = self.input.trim().to_string();
+                    if prompt.is_empty() {
+                        return Action::None;
+                    }
+                    self.input.clear();
+                    self.notice = None;
+                    Action::Submit(prompt)
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                    Action::None
+                }
+                KeyCode::Char(character) => {
+                    self.input.push(character);
+                    Action::None
+                }
+                _ => Action::None,
+            },
+        }
+    }
+
+    fn clear_transcript(&mut self) {
+        self.transcript.clear();
+        self.streaming = None;
+        self.activity.clear();
+        self.busy = false;
+    }
+
+    fn activity_mut(&mut self, id: &str) -> Option<&mut Activity> {
+        self.activity.iter_mut().find(|activity| activity.id == id)
+    }
+
+    fn start_activity(&mut self, id: String, label: String) {
+        self.activity.push(Activity {
+            id,
+            label,
+            state: ActivityState::Running(String::new()),
+        });
+    }
+
+    fn finish_activity(&mut self, id: &str, state: ActivityState) {
+        if let Some(activity) = self.activity_mut(id) {
+            activity.state = state;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn stream_events_build_transcript_and_timeline() {
+        let mut app = App::new("main".into());
+        app.push_prompt("check the build".into());
+        app.apply(StreamEvent::Prompt {
+            sender: super::super::SENDER_NAME.into(),
+            text: "check the build".into(),
+        });
+        app.apply(StreamEvent::ToolStarted {
+            process_type: "channel".into(),
+            process_id: "c1".into(),
+            call_id: "call-1".into(),
+            tool_name: "spawn_worker".into(),
+            args: "{}".into(),
+        });
+        app.apply(StreamEvent::TextDelta {
+            delta: "On".into(),
+            text: "On it".into(),
+        });
+        assert_eq!(app.streaming.as_deref(), Some("On it"));
+
+        app.apply(StreamEvent::Message {
+            text: "On it.".into(),
+        });
+        app.apply(StreamEvent::ToolCompleted {
+            process_type: "channel".into(),
+            process_id: "c1".into(),
+            call_id: "call-1".into(),
+            tool_name: "spawn_worker".into(),
+            result: "ok".into(),
+        });
+        app.apply(StreamEvent::Completed);
+
+        assert_eq!(app.transcript.len(), 2);
+        assert_eq!(app.transcript[1].role, Role::Assistant);
+        assert_eq!(app.streaming, None);
+        assert_eq!(app.activity[0].state, ActivityState::Done);
+        assert!(!app.busy);
+    }
+
+    #[test]
+    fn keys_edit_input_and_switch_panes() {
+        let mut app = App::new("main".into());
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::None);
+        for character in "hi".chars() {
+            app.handle_key(key(KeyCode::Char(character)));
+        }
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Action::Submit("hi".into())
+        );
+        assert!(app.input.is_empty());
+
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.focus, Focus::Sessions);
+        assert_eq!(app.handle_key(key(KeyCode::Char('n'))), Action::NewSession);
+        assert_eq!(
+            app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+    }
+}
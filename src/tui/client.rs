@@ -0,0 +1,172 @@
+//! HTTP and WebSocket client for the daemon's API.
+
+use crate::api::{SessionResponse, StreamEvent, SubmitPromptResponse};
+use crate::conversation::PortalConversationSummary;
+
+use anyhow::Context as _;
+use futures::StreamExt as _;
+use serde::Deserialize;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Most recent sessions shown in the session list.
+const SESSION_LIST_LIMIT: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    http: reqwest::Client,
+    /// API root including the `/api` prefix, without a trailing slash.
+    api_base: String,
+    auth_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SessionList {
+    conversations: Vec<PortalConversationSummary>,
+}
+
+impl ApiClient {
+    pub fn new(api_base: impl Into<String>, auth_token: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_base: api_base.into().trim_end_matches('/').to_string(),
+            auth_token,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .http
+            .request(method, format!("{}/{path}", self.api_base));
+        match &self.auth_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        request: reqwest::RequestBuilder,
+    ) -> anyhow::Result<T> {
+        let response = request
+            .send()
+            .await
+            .context("failed to connect to spacebot API — is the daemon running?")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("API returned {status}");
+        }
+        response
+            .json()
+            .await
+            .context("failed to decode API response")
+    }
+
+    /// Sessions for `agent_id`, most recently active first.
+    pub async fn sessions(&self, agent_id: &str) -> anyhow::Result<Vec<PortalConversationSummary>> {
+        let request = self
+            .request(reqwest::Method::GET, "portal/conversations")
+            .query(&[
+                ("agent_id", agent_id),
+                ("limit", &SESSION_LIST_LIMIT.to_string()),
+            ]);
+        let list: SessionList = Self::send(request).await?;
+        Ok(list.conversations)
+    }
+
+    pub async fn session(
+        &self,
+        agent_id: &str,
+        session_id: &str,
+    ) -> anyhow::Result<SessionResponse> {
+        let path = format!("v1/sessions/{}", urlencoding::encode(session_id));
+        let request = self
+            .request(reqwest::Method::GET, &path)
+            .query(&[("agent_id", agent_id)]);
+        Self::send(request).await
+    }
+
+    /// Submit a prompt, starting a new session when `session_id` is `None`.
+    pub async fn submit(
+        &self,
+        agent_id: &str,
+        session_id: Option<&str>,
+        prompt: &str,
+        sender_name: &str,
+    ) -> anyhow::Result<SubmitPromptResponse> {
+        let request = self
+            .request(reqwest::Method::POST, "v1/prompts")
+            .json(&serde_json::json!({
+                "agent_id": agent_id,
+                "session_id": session_id,
+                "prompt": prompt,
+                "sender_name": sender_name,
+            }));
+        Self::send(request).await
+    }
+
+    /// Connect to a session's WebSocket event stream.
+    pub async fn stream(&self, session_id: &str) -> anyhow::Result<SessionStream> {
+        let mut url = format!(
+            "{}/v1/sessions/{}/stream",
+            websocket_base(&self.api_base),
+            urlencoding::encode(session_id)
+        );
+        if let Some(token) = &self.auth_token {
+            url.push_str(&format!("?token={}", urlencoding::encode(token)));
+        }
+        let (socket, _) = tokio_tungstenite::connect_async(url.as_str())
+            .await
+            .context("failed to connect to session stream")?;
+        Ok(SessionStream { socket })
+    }
+}
+
+/// An open session event stream.
+pub struct SessionStream {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl SessionStream {
+    /// The next event, or `None` once the connection closes.
+    pub async fn next(&mut self) -> Option<StreamEvent> {
+        loop {
+            match self.socket.next().await? {
+                Ok(Message::Text(text)) => {
+                    if let Ok(event) = serde_json::from_str(text.as_str()) {
+                        return Some(event);
+                    }
+                }
+                Ok(Message::Close(_)) | Err(_) => return None,
+                Ok(_) => {}
+            }
+        }
+    }
+}
+
+fn websocket_base(api_base: &str) -> String {
+    if let Some(rest) = api_base.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = api_base.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        api_base.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn websocket_base_swaps_scheme() {
+        assert_eq!(
+            websocket_base("http://127.0.0.1:19898/api"),
+            "ws://127.0.0.1:19898/api"
+        );
+        assert_eq!(
+            websocket_base("https://bot.example.com/spacebot/api"),
+            "wss://bot.example.com/spacebot/api"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Layout and rendering.
+
+use super::app::{ActivityState, App, Focus, Role};
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize as _};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+
+/// Most timeline rows shown under the transcript.
+const ACTIVITY_ROWS: usize = 6;
+
+pub(super) fn draw(frame: &mut Frame, app: &App) {
+    let [sessions_area, main_area] =
+        Layout::horizontal([Constraint::Length(32), Constraint::Min(20)]).areas(frame.area());
+    let activity_height = if app.activity.is_empty() {
+        0
+    } else {
+        app.activity.len().min(ACTIVITY_ROWS) as u16 + 2
+    };
+    let [transcript_area, activity_area, input_area] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(activity_height),
+        Constraint::Length(3),
+    ])
+    .areas(main_area);
+
+    draw_sessions(frame, app, sessions_area);
+    draw_transcript(frame, app, transcript_area);
+    if activity_height > 0 {
+        draw_activity(frame, app, activity_area);
+    }
+    draw_input(frame, app, input_area);
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(title);
+    if focused {
+        block.border_style(Style::new().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+fn draw_sessions(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .sessions
+        .iter()
+        .map(|session| {
+            let marker = if app.open_session.as_deref() == Some(session.id.as_str()) {
+                "● "
+            } else {
+                "  "
+            };
+            ListItem::new(format!("{marker}{}", session.title))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane(
+            format!(" Sessions · {} ", app.agent_id),
+            app.focus == Focus::Sessions,
+        ))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_transcript(frame: &mut Frame, app: &App, area: Rect) {
+    let mut lines: Vec<Line> = Vec::new();
+    for entry in &app.transcript {
+        push_entry(&mut lines, entry.role, &entry.text);
+    }
+    if let Some(streaming) = &app.streaming {
+        push_entry(&mut lines, Role::Assistant, streaming);
+    }
+
+    let title = if app.busy {
+        " Transcript · working… "
+    } else {
+        " Transcript "
+    };
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let content_height: usize = lines
+        .iter()
+        .map(|line| line.width().div_ceil(inner_width).max(1))
+        .sum();
+    let scroll = content_height.saturating_sub(inner_height) as u16;
+
+    let paragraph = Paragraph::new(lines)
+        .block(pane(title.into(), false))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn push_entry(lines: &mut Vec<Line<'static>>, role: Role, text: &str) {
+    let (label, color) = match role {
+        Role::User => ("you", Color::Green),
+        Role::Assistant => ("agent", Color::Magenta),
+    };
+    lines.push(Line::from(Span::styled(
+        label,
+        Style::new().fg(color).bold(),
+    )));
+    lines.extend(text.lines().map(|line| Line::from(line.to_string())));
+    lines.push(Line::default());
+}
+
+fn draw_activity(frame: &mut Frame, app: &App, area: Rect) {
+    let skip = app.activity.len().saturating_sub(ACTIVITY_ROWS);
+    let items: Vec<ListItem> = app
+        .activity
+        .iter()
+        .skip(skip)
+        .map(|activity| {
+            let (symbol, status, color) = match &activity.state {
+                ActivityState::Running(status) => ("…", status.as_str(), Color::Yellow),
+                ActivityState::Done => ("✓", "", Color::Green),
+                ActivityState::Failed => ("✗", "failed", Color::Red),
+            };
+            let mut spans = vec![
+                Span::styled(format!("{symbol} "), Style::new().fg(color)),
+                Span::raw(activity.label.clone()),
+            ];
+            if !status.is_empty() {
+                spans.push(Span::styled(format!("  {status}"), Style::new().dim()));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(items).block(pane(" Activity ".into(), false)),
+        area,
+    );
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let hint = match app.focus {
+        Focus::Input => "Enter send · Tab/Esc sessions · Ctrl-C quit",
+        Focus::Sessions => "↑↓ select · Enter open · n new · Tab prompt · q quit",
+    };
+    let title = match &app.notice {
+        Some(notice) => format!(" {notice} "),
+        None => format!(" {hint} "),
+    };
+    let input = Paragraph::new(app.input.as_str()).block(pane(title, app.focus == Focus::Input));
+    frame.render_widget(input, area);
+
+    if app.focus == Focus::Input {
+        let cursor_x = area.x + 1 + app.input.chars().count() as u16;
+        frame.set_cursor_position((cursor_x.min(area.right().saturating_sub(2)), area.y + 1));
+    }
+}
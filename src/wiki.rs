@@ -2,6 +2,8 @@
 
 mod store;
 
+pub(crate) use store::sanitize_fts_query;
+
 pub use store::{
     CreateWikiPageInput, EditWikiPageInput, WikiPage, WikiPageSummary, WikiPageType,
     WikiPageVersion, WikiStore, extract_wiki_links, slugify, tolerant_replace,
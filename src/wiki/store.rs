@@ -837,7 +837,7 @@ fn lower_hex_id() -> String {
 /// alphanumeric tokens (plus `_` and `-`) and append `*` to the last token for
 /// prefix matching. Returns an empty string when no usable tokens remain, in
 /// which case the caller should short-circuit instead of issuing the query.
-fn sanitize_fts_query(input: &str) -> String {
+pub(crate) fn sanitize_fts_query(input: &str) -> String {
     let tokens: Vec<String> = input
         .split_whitespace()
         .map(|token| {
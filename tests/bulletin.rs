@@ -52,7 +52,7 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
     let resolved_agents = config.resolve_agents();
     let agent_config = resolved_agents.first().context("no agents configured")?;
 
-    let db = spacebot::db::Db::connect(&agent_config.data_dir)
+    let db = spacebot::db::Db::connect(&agent_config.data_dir, spacebot::db::Migrations::Run)
         .await
         .context("failed to connect databases")?;
 
@@ -123,6 +123,7 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
         memory_event_tx,
         tool_output_tx,
         sqlite_pool: db.sqlite.clone(),
+        write_behind: spacebot::conversation::WriteBehind::spawn(db.sqlite.clone()),
         messaging_manager: None,
         sandbox,
         links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
@@ -51,7 +51,7 @@ async fn bootstrap_deps() -> anyhow::Result<(spacebot::AgentDeps, spacebot::conf
     let resolved_agents = config.resolve_agents();
     let agent_config = resolved_agents.first().context("no agents configured")?;
 
-    let db = spacebot::db::Db::connect(&agent_config.data_dir)
+    let db = spacebot::db::Db::connect(&agent_config.data_dir, spacebot::db::Migrations::Run)
         .await
         .context("failed to connect databases")?;
 
@@ -122,6 +122,7 @@ async fn bootstrap_deps() -> anyhow::Result<(spacebot::AgentDeps, spacebot::conf
         memory_event_tx,
         tool_output_tx,
         sqlite_pool: db.sqlite.clone(),
+        write_behind: spacebot::conversation::WriteBehind::spawn(db.sqlite.clone()),
         messaging_manager: None,
         sandbox,
         links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
@@ -245,6 +246,7 @@ async fn dump_channel_context() {
         active_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_inputs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_injections: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+        archived_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
         reserved_tasks: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
         status_block,
         deps: deps.clone(),
@@ -254,6 +256,7 @@ async fn dump_channel_context() {
         screenshot_dir: std::path::PathBuf::from("/tmp/screenshots"),
         logs_dir: std::path::PathBuf::from("/tmp/logs"),
         reply_target_message_id: Arc::new(tokio::sync::RwLock::new(None)),
+        turn_requester: Arc::new(tokio::sync::RwLock::new(None)),
         prompt_snapshot_store: None,
         live_worker_transcripts: Arc::new(tokio::sync::RwLock::new(
             std::collections::HashMap::new(),
@@ -502,6 +505,7 @@ async fn dump_all_contexts() {
         active_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_inputs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_injections: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+        archived_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
         reserved_tasks: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
         status_block: Arc::new(tokio::sync::RwLock::new(
             spacebot::agent::status::StatusBlock::new(),
@@ -513,6 +517,7 @@ async fn dump_all_contexts() {
         screenshot_dir: std::path::PathBuf::from("/tmp/screenshots"),
         logs_dir: std::path::PathBuf::from("/tmp/logs"),
         reply_target_message_id: Arc::new(tokio::sync::RwLock::new(None)),
+        turn_requester: Arc::new(tokio::sync::RwLock::new(None)),
         prompt_snapshot_store: None,
         live_worker_transcripts: Arc::new(tokio::sync::RwLock::new(
             std::collections::HashMap::new(),
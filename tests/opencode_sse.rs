@@ -223,6 +223,72 @@ fn parse_session_error() {
     }
 }
 
+#[test]
+fn parse_file_events() {
+    let event = parse_sse_line(
+        r#"data: {"type":"file.edited","properties":{"file":"/code/app/src/main.rs"}}"#,
+    );
+    assert!(matches!(event, SseEvent::FileEdited { ref file } if file == "/code/app/src/main.rs"));
+
+    let event = parse_sse_line(
+        r#"data: {"type":"file.watcher.updated","properties":{"file":"/code/app/old.rs","event":"unlink"}}"#,
+    );
+    match event {
+        SseEvent::FileWatcherUpdated { file, event } => {
+            assert_eq!(file, "/code/app/old.rs");
+            assert_eq!(event, FileWatcherEvent::Unlink);
+        }
+        other => panic!("expected FileWatcherUpdated, got {other:?}"),
+    }
+}
+
+#[test]
+fn file_changes_from_tools_and_patches() {
+    let event = parse_sse_line(
+        r#"data: {"type":"message.part.updated","properties":{"part":{"id":"prt_edit","sessionID":"ses_456","messageID":"msg_789","type":"tool","callID":"call_1","tool":"edit","state":{"status":"completed","input":{"filePath":"/code/app/src/lib.rs","oldString":"a","newString":"b"},"output":"","title":"src/lib.rs","metadata":{"filediff":{"file":"/code/app/src/lib.rs","before":"a","after":"b","additions":42,"deletions":7}}}}}}"#,
+    );
+    let SseEvent::MessagePartUpdated {
+        part:
+            Part::Tool {
+                tool: Some(tool),
+                state: Some(state),
+                ..
+            },
+        ..
+    } = event
+    else {
+        panic!("expected a tool part, got {event:?}");
+    };
+    let mut change = file_change_from_tool_state(&tool, &state).unwrap();
+    assert_eq!(change.path, "/code/app/src/lib.rs");
+    assert_eq!(change.kind, FileChangeKind::Modified);
+    assert_eq!((change.additions, change.deletions), (Some(42), Some(7)));
+
+    change.merge(FileChange {
+        path: change.path.clone(),
+        kind: FileChangeKind::Modified,
+        additions: Some(3),
+        deletions: Some(1),
+    });
+    assert_eq!((change.additions, change.deletions), (Some(45), Some(8)));
+    change.merge(FileChange::new(
+        change.path.clone(),
+        FileChangeKind::Deleted,
+    ));
+    assert_eq!(change.kind, FileChangeKind::Deleted);
+
+    let event = parse_sse_line(
+        r#"data: {"type":"message.part.updated","properties":{"part":{"id":"prt_patch","sessionID":"ses_456","messageID":"msg_789","type":"patch","hash":"abc123","files":["/code/app/src/lib.rs","/code/app/tests/foo.rs"]}}}"#,
+    );
+    match event {
+        SseEvent::MessagePartUpdated {
+            part: Part::Patch { files, .. },
+            ..
+        } => assert_eq!(files.len(), 2),
+        other => panic!("expected a patch part, got {other:?}"),
+    }
+}
+
 #[test]
 fn parse_step_start() {
     let event = parse_sse_line(
@@ -253,16 +319,19 @@ fn parse_step_finish() {
 }
 
 #[test]
-fn parse_reasoning_part_as_other() {
-    // Reasoning parts should parse as Part::Other (we don't model them)
+fn parse_reasoning_part() {
     let event = parse_sse_line(
         r#"data: {"type":"message.part.updated","properties":{"part":{"id":"prt_reason","sessionID":"ses_456","messageID":"msg_789","type":"reasoning","text":"thinking...","metadata":{},"time":{"start":1234}}}}"#,
     );
     match event {
-        SseEvent::MessagePartUpdated { part, .. } => {
-            assert!(matches!(part, Part::Other));
+        SseEvent::MessagePartUpdated {
+            part: Part::Reasoning { text, time, .. },
+            ..
+        } => {
+            assert_eq!(text, "thinking...");
+            assert!(time.is_some_and(|time| time.end.is_none()));
         }
-        other => panic!("expected MessagePartUpdated with Other, got {other:?}"),
+        other => panic!("expected a reasoning part, got {other:?}"),
     }
 }
 
@@ -0,0 +1,258 @@
+//! Property tests for OpenCode SSE parsing.
+//!
+//! Envelopes are generated both in OpenCode's shape, with fields of the
+//! wrong type or missing, and as arbitrary JSON. Whatever arrives, parsing,
+//! assembling the transcript, and rendering must not panic, and an
+//! unterminated block must not grow the parser's buffer without bound. The
+//! cargo-fuzz targets in `fuzz/` run the same paths on raw bytes.
+
+use spacebot::opencode::fixtures::{FixtureConfig, generate};
+use spacebot::opencode::types::*;
+use spacebot::opencode::worker::MAX_SSE_BLOCK_BYTES;
+use spacebot::testkit::{parse_sse_chunks, replay};
+
+use proptest::prelude::*;
+use serde_json::{Value, json};
+
+/// Event types OpenCode sends, plus any other string.
+fn event_type() -> impl Strategy<Value = String> {
+    prop_oneof![
+        4 => prop::sample::select(vec![
+            "message.updated",
+            "message.part.updated",
+            "session.idle",
+            "session.error",
+            "session.status",
+            "permission.asked",
+            "permission.replied",
+            "question.asked",
+            "question.replied",
+            "todo.updated",
+            "tui.toast.show",
+            "file.edited",
+            "file.watcher.updated",
+        ])
+        .prop_map(String::from),
+        1 => "\\PC{0,16}",
+    ]
+}
+
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        any::<f64>()
+            .prop_filter("JSON numbers are finite", |number| number.is_finite())
+            .prop_map(Value::from),
+        "\\PC{0,24}".prop_map(Value::from),
+    ];
+    leaf.prop_recursive(3, 32, 6, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..6).prop_map(Value::from),
+            prop::collection::btree_map("[a-zA-Z]{1,10}", inner, 0..6)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+/// A field that is usually a string, sometimes any other value, and
+/// sometimes missing.
+fn loose_string() -> impl Strategy<Value = Option<Value>> {
+    prop_oneof![
+        4 => "\\PC{0,24}".prop_map(|text| Some(Value::from(text))),
+        1 => json_value().prop_map(Some),
+        1 => Just(None),
+    ]
+}
+
+fn object(fields: Vec<(&str, Option<Value>)>) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| (key.to_string(), value)))
+            .collect(),
+    )
+}
+
+/// A part in OpenCode's shape, with loosely typed fields.
+fn part() -> impl Strategy<Value = Value> {
+    let part_type = prop_oneof![
+        prop::sample::select(vec![
+            "text",
+            "tool",
+            "reasoning",
+            "step-start",
+            "step-finish",
+            "patch",
+            "file",
+        ])
+        .prop_map(Value::from),
+        json_value(),
+    ];
+    let status = prop_oneof![
+        prop::sample::select(vec!["pending", "running", "completed", "error"])
+            .prop_map(Value::from),
+        json_value(),
+    ];
+    let state = (
+        status,
+        prop::option::of(json_value()),
+        loose_string(),
+        loose_string(),
+        prop::option::of(json_value()),
+    )
+        .prop_map(|(status, input, output, error, metadata)| {
+            object(vec![
+                ("status", Some(status)),
+                ("input", input),
+                ("output", output),
+                ("error", error),
+                ("metadata", metadata),
+            ])
+        });
+    (
+        part_type,
+        loose_string(),
+        loose_string(),
+        loose_string(),
+        loose_string(),
+        loose_string(),
+        prop::option::of(state),
+    )
+        .prop_map(
+            |(part_type, id, session_id, message_id, text, tool, state)| {
+                object(vec![
+                    ("type", Some(part_type)),
+                    ("id", id),
+                    ("sessionID", session_id),
+                    ("messageID", message_id),
+                    ("text", text),
+                    ("tool", tool),
+                    ("state", state),
+                ])
+            },
+        )
+}
+
+/// Envelopes in OpenCode's shape, and ones with arbitrary properties.
+fn envelope() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        (part(), loose_string()).prop_map(|(part, delta)| json!({
+            "type": "message.part.updated",
+            "properties": object(vec![("part", Some(part)), ("delta", delta)]),
+        })),
+        (event_type(), loose_string(), json_value()).prop_map(
+            |(event_type, session_id, extra)| json!({
+                "type": event_type,
+                "properties": object(vec![
+                    ("sessionID", session_id),
+                    ("status", Some(extra.clone())),
+                    ("error", Some(extra.clone())),
+                    ("info", Some(extra.clone())),
+                    ("todos", Some(extra)),
+                ]),
+            })
+        ),
+        (event_type(), json_value()).prop_map(|(event_type, properties)| json!({
+            "type": event_type,
+            "properties": properties,
+        })),
+    ]
+}
+
+/// Byte counts to cut a stream into.
+fn chunk_sizes() -> impl Strategy<Value = Vec<usize>> {
+    prop::collection::vec(1usize..64, 1..32)
+}
+
+fn split<'a>(mut body: &'a [u8], sizes: &[usize]) -> Vec<&'a [u8]> {
+    let mut chunks = Vec::new();
+    for size in sizes.iter().cycle() {
+        if body.is_empty() {
+            break;
+        }
+        let (chunk, rest) = body.split_at((*size).min(body.len()));
+        chunks.push(chunk);
+        body = rest;
+    }
+    chunks
+}
+
+proptest! {
+    #[test]
+    fn envelopes_parse_without_panicking(envelope in envelope()) {
+        let raw = envelope.to_string();
+        if let Ok(borrowed) = serde_json::from_str::<SseEventEnvelopeRef>(&raw) {
+            SseEvent::from_envelope_ref(borrowed);
+        }
+        let Ok(owned) = serde_json::from_value::<SseEventEnvelope>(envelope) else {
+            return Ok(());
+        };
+        let event = SseEvent::from_envelope(owned);
+        if let SseEvent::MessagePartUpdated { part, .. } = &event {
+            part.session_id();
+            part_to_opencode_part(part);
+        }
+        let replayed = replay(&[event], "ses_fuzz");
+        replayed.transcript_text();
+        replayed.chat_text("discord");
+    }
+
+    /// Every complete block yields one event however the stream is cut,
+    /// including through multi-byte characters.
+    #[test]
+    fn streams_yield_every_event_in_any_chunking(
+        envelopes in prop::collection::vec(envelope(), 1..12),
+        sizes in chunk_sizes(),
+    ) {
+        let body: String = envelopes
+            .iter()
+            .map(|envelope| format!("data: {envelope}\n\n"))
+            .collect();
+        let parsed = parse_sse_chunks(split(body.as_bytes(), &sizes));
+        prop_assert_eq!(parsed.events.len(), envelopes.len());
+    }
+
+    #[test]
+    fn generated_sessions_replay_in_any_chunking(seed in any::<u64>(), sizes in chunk_sizes()) {
+        let fixture = generate(seed, &FixtureConfig::default());
+        let body = fixture.to_sse_string();
+        let parsed = parse_sse_chunks(split(body.as_bytes(), &sizes));
+        prop_assert_eq!(parsed.events.len(), fixture.events.len());
+    }
+
+    /// SSE-looking noise: field names, JSON fragments, and stray newlines.
+    #[test]
+    fn malformed_streams_never_panic(
+        pieces in prop::collection::vec(
+            prop_oneof![
+                prop::sample::select(vec![
+                    "data: ", "data:", "id: ", "event: ", ": ", "\n", "\n\n", "\r\n",
+                    "{", "}", "[", "]", "\"type\":", "\"properties\":", "\"part\":",
+                    "\"message.part.updated\"", "\"tool\"", "\"state\":", ",", "null",
+                ])
+                .prop_map(String::from),
+                "\\PC{0,8}",
+            ],
+            0..128,
+        ),
+        sizes in chunk_sizes(),
+    ) {
+        let body = pieces.concat();
+        let parsed = parse_sse_chunks(split(body.as_bytes(), &sizes));
+        replay(&parsed.events, "ses_fuzz");
+    }
+}
+
+#[test]
+fn unterminated_stream_stays_within_the_block_limit() {
+    let chunk = vec![b'x'; 64 * 1024];
+    let chunks = std::iter::once(b"data: ".as_slice()).chain(std::iter::repeat_n(
+        chunk.as_slice(),
+        2 * MAX_SSE_BLOCK_BYTES / chunk.len(),
+    ));
+    let parsed = parse_sse_chunks(chunks);
+    assert!(parsed.events.is_empty());
+    assert!(parsed.peak_buffered_bytes <= MAX_SSE_BLOCK_BYTES + chunk.len());
+}
@@ -228,6 +228,8 @@ fn format_event(event: &SseEvent) -> String {
                 }
                 Part::StepStart { .. } => "step-start",
                 Part::StepFinish { .. } => "step-finish",
+                Part::Patch { .. } => "patch",
+                Part::Reasoning { .. } => "reasoning",
                 Part::Other => "other",
             };
             format!("message.part.updated ({part_type})")
@@ -0,0 +1,139 @@
+//! Tests for assembling worker transcripts from streamed OpenCode parts.
+//!
+//! `render_cache_benchmark` is ignored by default. Run it with
+//! `cargo test --release --test opencode_transcript -- --ignored --nocapture`.
+
+use spacebot::conversation::worker_transcript::{
+    MAX_ASSEMBLED_PARTS, OpenCodeTranscriptAssembler, TranscriptStep, convert_opencode_parts,
+};
+use spacebot::opencode::fixtures::{FixtureConfig, generate};
+use spacebot::opencode::types::*;
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+/// Part updates from a generated stream, in order, up to the update that
+/// introduces the `distinct_parts`th part.
+fn part_updates(distinct_parts: usize) -> Vec<OpenCodePart> {
+    let config = FixtureConfig {
+        sessions: 20,
+        messages_per_session: 10,
+        ..FixtureConfig::default()
+    };
+    let fixture = generate(7, &config);
+
+    let mut seen = HashSet::new();
+    let mut updates = Vec::new();
+    for event in fixture.events {
+        let envelope: SseEventEnvelope =
+            serde_json::from_value(event).expect("fixture events are valid envelopes");
+        let SseEvent::MessagePartUpdated { part, .. } = SseEvent::from_envelope(envelope) else {
+            continue;
+        };
+        let Some(part) = part_to_opencode_part(&part) else {
+            continue;
+        };
+        seen.insert(part.id().to_string());
+        updates.push(part);
+        if seen.len() == distinct_parts {
+            return updates;
+        }
+    }
+    panic!("fixture produced only {} distinct parts", seen.len());
+}
+
+fn to_json(steps: &[TranscriptStep]) -> serde_json::Value {
+    serde_json::to_value(steps).expect("steps serialize")
+}
+
+#[test]
+fn assembler_matches_full_conversion_after_every_update() {
+    let updates = part_updates(60);
+    let mut assembler = OpenCodeTranscriptAssembler::new();
+    for (index, part) in updates.iter().enumerate() {
+        assembler.upsert(part.clone());
+        assert_eq!(
+            to_json(&assembler.steps()),
+            to_json(&convert_opencode_parts(&updates[..=index])),
+            "diverged after update {index}"
+        );
+    }
+    assert_eq!(assembler.len(), 60);
+}
+
+/// Replaying the stream, as after a reconnect, flags every update as a repeat
+/// and leaves the transcript unchanged. A changed state is not a repeat.
+#[test]
+fn assembler_drops_replayed_updates() {
+    // Stay inside the repeat window.
+    let updates: Vec<_> = part_updates(40).into_iter().take(200).collect();
+    let mut assembler = OpenCodeTranscriptAssembler::new();
+    for part in &updates {
+        if !assembler.is_repeat(Some("message-1"), part) {
+            assembler.upsert(part.clone());
+        }
+    }
+    let before = to_json(&assembler.steps());
+
+    for part in &updates {
+        assert!(assembler.is_repeat(Some("message-1"), part));
+    }
+    assert_eq!(to_json(&assembler.steps()), before);
+
+    let edited = OpenCodePart::Text {
+        id: updates[0].id().to_string(),
+        text: "a different version".to_string(),
+    };
+    assert!(!assembler.is_repeat(Some("message-1"), &edited));
+    assert!(!assembler.is_repeat(Some("message-2"), &updates[0]));
+}
+
+#[test]
+fn assembler_stops_taking_new_parts_at_the_limit() {
+    let text = |id: usize, text: &str| OpenCodePart::Text {
+        id: format!("prt_{id}"),
+        text: text.to_string(),
+    };
+    let mut assembler: OpenCodeTranscriptAssembler = (0..MAX_ASSEMBLED_PARTS + 10)
+        .map(|id| text(id, "x"))
+        .collect();
+    assert_eq!(assembler.len(), MAX_ASSEMBLED_PARTS);
+
+    assembler.upsert(text(0, "updated"));
+    assert_eq!(assembler.len(), MAX_ASSEMBLED_PARTS);
+    assert_eq!(
+        to_json(&assembler.steps()[..1]),
+        to_json(&convert_opencode_parts(&[text(0, "updated")]))
+    );
+}
+
+/// Snapshot the transcript after every update of a 500-part session, once
+/// by re-rendering every part and once through the assembler's cache.
+#[test]
+#[ignore = "benchmark"]
+fn render_cache_benchmark() {
+    let updates = part_updates(500);
+
+    let started = Instant::now();
+    let mut full_steps = 0;
+    for index in 0..updates.len() {
+        full_steps += convert_opencode_parts(&updates[..=index]).len();
+    }
+    let full = started.elapsed();
+
+    let started = Instant::now();
+    let mut cached_steps = 0;
+    let mut assembler = OpenCodeTranscriptAssembler::new();
+    for part in &updates {
+        assembler.upsert(part.clone());
+        cached_steps += assembler.steps().len();
+    }
+    let cached = started.elapsed();
+
+    assert_eq!(full_steps, cached_steps);
+    println!(
+        "{} updates over 500 parts: full re-render {full:?}, cached {cached:?} ({:.1}x)",
+        updates.len(),
+        full.as_secs_f64() / cached.as_secs_f64()
+    );
+}
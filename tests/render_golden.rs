@@ -0,0 +1,53 @@
+//! Golden tests for how recorded OpenCode sessions render.
+//!
+//! Each fixture in `tests/fixtures/opencode/` is replayed through the
+//! transcript assembler and the chat renderers, and the result is compared
+//! against the snapshots in `tests/snapshots/`. After an intended rendering
+//! change, review and accept the new output with `cargo insta review`.
+//!
+//! To add a fixture, record a session's `/event` stream (or start from
+//! `spacebot::testkit::write_generated_fixture`), save it next to the others,
+//! and add it to `FIXTURES`.
+
+use spacebot::opencode::fixtures::FixtureConfig;
+use spacebot::testkit::{load_sse_fixture, replay, write_generated_fixture};
+
+use std::path::Path;
+
+/// Fixture name and the session it records.
+const FIXTURES: &[(&str, &str)] = &[("test_fix", "ses_golden")];
+
+/// Platforms whose chat output is snapshotted. They differ in how a busy
+/// session shows and in their message limits.
+const PLATFORMS: &[&str] = &["discord", "slack"];
+
+fn fixture_path(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/opencode")
+        .join(format!("{name}.sse"))
+}
+
+#[test]
+fn recorded_sessions_render_like_their_snapshots() {
+    for (name, session_id) in FIXTURES {
+        let events = load_sse_fixture(fixture_path(name)).unwrap();
+        let replay = replay(&events, session_id);
+
+        insta::assert_snapshot!(format!("{name}_transcript"), replay.transcript_text());
+        for platform in PLATFORMS {
+            insta::assert_snapshot!(format!("{name}_{platform}"), replay.chat_text(platform));
+        }
+    }
+}
+
+#[test]
+fn generated_fixtures_load_back() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("generated.sse");
+    let config = FixtureConfig::default();
+    write_generated_fixture(&path, 11, &config).unwrap();
+
+    let events = load_sse_fixture(&path).unwrap();
+    let expected = spacebot::opencode::fixtures::generate(11, &config);
+    assert_eq!(events.len(), expected.events.len());
+}
@@ -0,0 +1,99 @@
+//! Throughput of transcript persistence with and without the write-behind
+//! buffer.
+//!
+//! Ignored by default. Run it with
+//! `cargo test --release --test write_behind -- --ignored --nocapture`.
+
+use spacebot::conversation::WriteBehind;
+
+use sqlx::SqlitePool;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+
+use std::time::{Duration, Instant};
+
+const WORKERS: usize = 20;
+const SNAPSHOTS_PER_WORKER: usize = 200;
+const BLOB_BYTES: usize = 4 * 1024;
+
+/// A file-backed pool configured like the agent database.
+async fn agent_like_pool(directory: &std::path::Path) -> SqlitePool {
+    let options = SqliteConnectOptions::new()
+        .filename(directory.join("agent.db"))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal);
+    let pool = SqlitePool::connect_with(options).await.unwrap();
+    sqlx::query(
+        "CREATE TABLE worker_runs (id TEXT PRIMARY KEY, transcript BLOB, tool_calls INTEGER)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    for worker in 0..WORKERS {
+        sqlx::query("INSERT INTO worker_runs (id) VALUES (?)")
+            .bind(worker.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    pool
+}
+
+/// Snapshot every worker's transcript in turn, the way concurrent workers
+/// interleave their per-segment checkpoints.
+async fn write_direct(pool: &SqlitePool) -> Duration {
+    let blob = vec![0u8; BLOB_BYTES];
+    let started = Instant::now();
+    for snapshot in 0..SNAPSHOTS_PER_WORKER {
+        for worker in 0..WORKERS {
+            sqlx::query("UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?")
+                .bind(&blob)
+                .bind(snapshot as i64)
+                .bind(worker.to_string())
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+    }
+    started.elapsed()
+}
+
+async fn write_behind(pool: &SqlitePool) -> Duration {
+    let writer = WriteBehind::spawn(pool.clone());
+    let blob = vec![0u8; BLOB_BYTES];
+    let started = Instant::now();
+    for snapshot in 0..SNAPSHOTS_PER_WORKER {
+        for worker in 0..WORKERS {
+            writer
+                .write_transcript(worker.to_string(), blob.clone(), snapshot as i64)
+                .await;
+        }
+    }
+    writer.flush().await;
+    started.elapsed()
+}
+
+#[tokio::test]
+#[ignore = "benchmark"]
+async fn write_behind_throughput() {
+    let writes = (WORKERS * SNAPSHOTS_PER_WORKER) as f64;
+
+    let direct_directory = tempfile::tempdir().unwrap();
+    let direct = write_direct(&agent_like_pool(direct_directory.path()).await).await;
+
+    let buffered_directory = tempfile::tempdir().unwrap();
+    let pool = agent_like_pool(buffered_directory.path()).await;
+    let buffered = write_behind(&pool).await;
+
+    let (last_tool_calls,): (i64,) = sqlx::query_as("SELECT MIN(tool_calls) FROM worker_runs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(last_tool_calls, SNAPSHOTS_PER_WORKER as i64 - 1);
+
+    println!(
+        "{writes} snapshots: direct {:.0}/s, write-behind {:.0}/s",
+        writes / direct.as_secs_f64(),
+        writes / buffered.as_secs_f64()
+    );
+}